@@ -7,7 +7,12 @@ use clap::{Parser, Subcommand, ValueEnum};
 mod kfx_dump;
 use serde::Serialize;
 
-use boko::{Book, Chapter, ChapterId, Format, NodeId, Role, ToCss, TocEntry, extract_section_tree};
+use boko::model::{Landmark, Metadata};
+use boko::optimize::FontEmbedding;
+use boko::{
+    Book, Chapter, ChapterId, Format, NodeId, ProgressEvent, Role, Severity, ToCss, TocEntry,
+    extract_section_tree,
+};
 
 #[derive(Parser)]
 #[command(name = "boko")]
@@ -51,6 +56,24 @@ enum Command {
         #[arg(short = 'O', long)]
         optimize: bool,
 
+        /// Rewrite simple two-column (label/value) tables as
+        /// "label: value" paragraphs for narrow screens
+        #[arg(long)]
+        linearize_tables: bool,
+
+        /// Embedded fonts: drop them (none), keep only the glyphs the text
+        /// uses (subset), or keep them unchanged (full)
+        #[arg(long, value_enum, value_name = "MODE", default_value = "full")]
+        embed_fonts: EmbedFontsArg,
+
+        /// Replace the cover with this image (ignored for text output)
+        #[arg(long, value_name = "IMAGE")]
+        cover: Option<String>,
+
+        /// Wrap Markdown/text output at this many columns (0 = no wrapping)
+        #[arg(long, value_name = "COLUMNS", default_value_t = 0)]
+        wrap: usize,
+
         /// Suppress output messages
         #[arg(short, long)]
         quiet: bool,
@@ -59,6 +82,12 @@ enum Command {
     /// Dump KFX/KDF/Ion files for debugging (KFX containers and raw Ion binary)
     KfxDump(kfx_dump::KfxDumpArgs),
 
+    /// Check a book for broken links, missing assets, and other problems
+    Validate {
+        /// Input file (EPUB, AZW3, MOBI, or KFX)
+        file: String,
+    },
+
     /// Extract hierarchical section tree (JSON)
     Sections {
         /// Input file (EPUB, AZW3, MOBI, or KFX)
@@ -100,6 +129,9 @@ enum Command {
     },
 }
 
+/// Reading speed behind `info`'s reading-time estimate.
+const READING_SPEED_WPM: u32 = 250;
+
 /// Hard recursion cap for tree walkers, mirroring the library's internal
 /// `util::MAX_TREE_DEPTH`: a hostile chapter can nest arbitrarily deep and
 /// would otherwise overflow the stack.
@@ -112,12 +144,17 @@ fn main() -> ExitCode {
         Command::Info { file, json } => show_info(&file, json),
         Command::KfxDump(args) => kfx_dump::run(&args),
         Command::Sections { file } => show_sections(&file),
+        Command::Validate { file } => validate(&file),
         Command::Convert {
             input,
             output,
             from_format,
             to_format,
             optimize,
+            linearize_tables,
+            embed_fonts,
+            cover,
+            wrap,
             quiet,
         } => convert(
             &input,
@@ -125,6 +162,10 @@ fn main() -> ExitCode {
             from_format,
             to_format,
             optimize,
+            linearize_tables,
+            embed_fonts,
+            cover.as_deref(),
+            wrap,
             quiet,
         ),
         Command::Dump {
@@ -161,13 +202,17 @@ fn main() -> ExitCode {
 
 // JSON output structures
 #[derive(Serialize)]
-struct BookInfo {
-    file: String,
-    metadata: MetadataInfo,
+struct BookInfo<'a> {
+    file: &'a str,
+    metadata: &'a Metadata,
+    word_count: usize,
+    reading_time_minutes: u32,
+    chapter_count: usize,
+    asset_count: usize,
     spine: Vec<SpineInfo>,
-    toc: Vec<TocInfo>,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
-    landmarks: Vec<LandmarkInfo>,
+    toc: &'a [TocEntry],
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    landmarks: &'a [Landmark],
     assets: Vec<AssetInfo>,
 }
 
@@ -177,54 +222,6 @@ struct AssetInfo {
     size: usize,
 }
 
-#[derive(Serialize)]
-struct MetadataInfo {
-    title: String,
-    authors: Vec<String>,
-    language: String,
-    identifier: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    publisher: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    date: Option<String>,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
-    subjects: Vec<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    rights: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    cover_image: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    description: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    modified_date: Option<String>,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
-    contributors: Vec<ContributorInfo>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    title_sort: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    author_sort: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    collection: Option<CollectionInfoJson>,
-}
-
-#[derive(Serialize)]
-struct ContributorInfo {
-    name: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    role: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    file_as: Option<String>,
-}
-
-#[derive(Serialize)]
-struct CollectionInfoJson {
-    name: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    collection_type: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    position: Option<f64>,
-}
-
 #[derive(Serialize)]
 struct SpineInfo {
     id: u32,
@@ -232,21 +229,6 @@ struct SpineInfo {
     size: usize,
 }
 
-#[derive(Serialize)]
-struct TocInfo {
-    title: String,
-    href: String,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
-    children: Vec<TocInfo>,
-}
-
-#[derive(Serialize)]
-struct LandmarkInfo {
-    landmark_type: String,
-    href: String,
-    label: String,
-}
-
 fn show_info(path: &str, json: bool) -> Result<(), String> {
     let mut book = Book::open(path).map_err(|e| format!("Failed to open '{path}': {e}"))?;
 
@@ -265,6 +247,30 @@ fn show_sections(path: &str) -> Result<(), String> {
     Ok(())
 }
 
+fn validate(path: &str) -> Result<(), String> {
+    let book = Book::open(path).map_err(|e| format!("Failed to open '{path}': {e}"))?;
+    let diagnostics = book.validate();
+    for d in &diagnostics {
+        let severity = match d.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        match &d.location {
+            Some(location) => println!("{severity}[{}] {location}: {}", d.code, d.message),
+            None => println!("{severity}[{}] {}", d.code, d.message),
+        }
+    }
+
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    if errors > 0 {
+        return Err(format!("{errors} error(s) found in '{path}'"));
+    }
+    Ok(())
+}
+
 fn print_json(book: &mut Book, path: &str) -> Result<(), String> {
     let meta = book.metadata();
     let asset_paths = book.list_assets();
@@ -280,37 +286,14 @@ fn print_json(book: &mut Book, path: &str) -> Result<(), String> {
         })
         .collect();
 
+    let word_count = book.word_count();
     let info = BookInfo {
-        file: path.to_string(),
-        metadata: MetadataInfo {
-            title: meta.title.clone(),
-            authors: meta.authors.clone(),
-            language: meta.language.clone(),
-            identifier: meta.identifier.clone(),
-            publisher: meta.publisher.clone(),
-            date: meta.date.clone(),
-            subjects: meta.subjects.clone(),
-            rights: meta.rights.clone(),
-            cover_image: meta.cover_image.clone(),
-            description: meta.description.clone(),
-            modified_date: meta.modified_date.clone(),
-            contributors: meta
-                .contributors
-                .iter()
-                .map(|c| ContributorInfo {
-                    name: c.name.clone(),
-                    role: c.role.clone(),
-                    file_as: c.file_as.clone(),
-                })
-                .collect(),
-            title_sort: meta.title_sort.clone(),
-            author_sort: meta.author_sort.clone(),
-            collection: meta.collection.as_ref().map(|c| CollectionInfoJson {
-                name: c.name.clone(),
-                collection_type: c.collection_type.clone(),
-                position: c.position,
-            }),
-        },
+        file: path,
+        metadata: meta,
+        word_count,
+        reading_time_minutes: book.reading_time_minutes(READING_SPEED_WPM),
+        chapter_count: book.chapter_count(),
+        asset_count: book.asset_count(),
         spine: book
             .spine()
             .iter()
@@ -320,16 +303,8 @@ fn print_json(book: &mut Book, path: &str) -> Result<(), String> {
                 size: e.size_estimate,
             })
             .collect(),
-        toc: book.toc().iter().map(toc_to_info).collect(),
-        landmarks: book
-            .landmarks()
-            .iter()
-            .map(|l| LandmarkInfo {
-                landmark_type: format!("{:?}", l.landmark_type),
-                href: l.href.clone(),
-                label: l.label.clone(),
-            })
-            .collect(),
+        toc: book.toc(),
+        landmarks: book.landmarks(),
         assets,
     };
 
@@ -338,14 +313,6 @@ fn print_json(book: &mut Book, path: &str) -> Result<(), String> {
     Ok(())
 }
 
-fn toc_to_info(entry: &TocEntry) -> TocInfo {
-    TocInfo {
-        title: entry.title.clone(),
-        href: entry.href.clone(),
-        children: entry.children.iter().map(toc_to_info).collect(),
-    }
-}
-
 fn print_human(book: &mut Book, path: &str) -> Result<(), String> {
     let meta = book.metadata();
     println!("File: {path}");
@@ -359,6 +326,16 @@ fn print_human(book: &mut Book, path: &str) -> Result<(), String> {
     if !meta.identifier.is_empty() {
         println!("Identifier: {}", meta.identifier);
     }
+    for other in meta
+        .identifiers
+        .iter()
+        .filter(|i| i.value != meta.identifier)
+    {
+        match &other.scheme {
+            Some(scheme) => println!("Identifier ({scheme}): {}", other.value),
+            None => println!("Identifier: {}", other.value),
+        }
+    }
     if let Some(ref publisher) = meta.publisher {
         println!("Publisher: {publisher}");
     }
@@ -386,6 +363,9 @@ fn print_human(book: &mut Book, path: &str) -> Result<(), String> {
     if let Some(ref modified) = meta.modified_date {
         println!("Modified: {modified}");
     }
+    if let Some(rating) = meta.rating {
+        println!("Rating: {rating}/10");
+    }
     if let Some(ref title_sort) = meta.title_sort {
         println!("Title Sort: {title_sort}");
     }
@@ -415,9 +395,29 @@ fn print_human(book: &mut Book, path: &str) -> Result<(), String> {
             println!("Collection: {} ({})", coll.name, coll_type);
         }
     }
+    let a11y = &meta.accessibility;
+    for (label, values) in [
+        ("Accessibility Features", &a11y.features),
+        ("Access Modes", &a11y.access_modes),
+        ("Access Modes Sufficient", &a11y.access_modes_sufficient),
+        ("Accessibility Hazards", &a11y.hazards),
+    ] {
+        if !values.is_empty() {
+            println!("{label}: {}", values.join(", "));
+        }
+    }
+    if let Some(ref summary) = a11y.summary {
+        println!("Accessibility Summary: {summary}");
+    }
+
+    println!(
+        "Reading Time: ~{} min ({} words at {READING_SPEED_WPM} wpm)",
+        book.reading_time_minutes(READING_SPEED_WPM),
+        book.word_count()
+    );
 
     // Spine (chapters)
-    println!("\nSpine ({} chapters):", book.spine().len());
+    println!("\nSpine ({} chapters):", book.chapter_count());
     for entry in book.spine() {
         let source = book.source_id(entry.id).unwrap_or("?");
         println!(
@@ -484,6 +484,9 @@ enum FormatArg {
     Md,
     #[value(alias = "text")]
     Txt,
+    Ir,
+    Fb2,
+    Cbz,
 }
 
 impl From<FormatArg> for Format {
@@ -494,16 +497,42 @@ impl From<FormatArg> for Format {
             FormatArg::Mobi => Format::Mobi,
             FormatArg::Kfx => Format::Kfx,
             FormatArg::Md | FormatArg::Txt => Format::Markdown,
+            FormatArg::Ir => Format::Ir,
+            FormatArg::Fb2 => Format::Fb2,
+            FormatArg::Cbz => Format::Cbz,
         }
     }
 }
 
+/// Modes accepted by `convert --embed-fonts`.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum EmbedFontsArg {
+    None,
+    Subset,
+    Full,
+}
+
+impl From<EmbedFontsArg> for FontEmbedding {
+    fn from(arg: EmbedFontsArg) -> Self {
+        match arg {
+            EmbedFontsArg::None => FontEmbedding::None,
+            EmbedFontsArg::Subset => FontEmbedding::Subset,
+            EmbedFontsArg::Full => FontEmbedding::Full,
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn convert(
     input: &str,
     output: Option<&str>,
     from_format: Option<FormatArg>,
     to_format: Option<FormatArg>,
     optimize: bool,
+    linearize_tables: bool,
+    embed_fonts: EmbedFontsArg,
+    cover: Option<&str>,
+    wrap: usize,
     quiet: bool,
 ) -> Result<(), String> {
     // Check if reading from stdin
@@ -514,7 +543,7 @@ fn convert(
         Some(Format::from(fmt))
     } else if from_stdin {
         return Err(
-            "Input format required when reading from stdin. Use -f (epub|azw3|mobi|kfx)"
+            "Input format required when reading from stdin. Use -f (epub|azw3|mobi|kfx|ir|fb2|cbz)"
                 .to_string(),
         );
     } else {
@@ -535,10 +564,16 @@ fn convert(
         if out == "-" {
             // Explicit stdout, default to markdown
             Format::Markdown
+        } else if out.to_ascii_lowercase().ends_with(".gz") {
+            // `Format::from_path` looks through `.gz` for reading; writing
+            // compressed output isn't supported.
+            return Err(format!(
+                "Cannot write gzip output '{out}'; write the uncompressed file instead"
+            ));
         } else {
             Format::from_path(out).ok_or_else(|| {
                 format!(
-                    "Cannot infer output format from '{out}'. Supported extensions: .epub, .azw3, .kfx, .md, .txt (or pass -t)"
+                    "Cannot infer output format from '{out}'. Supported extensions: .epub, .azw3, .kfx, .md, .txt, .ir (or pass -t)"
                 )
             })?
         }
@@ -550,6 +585,9 @@ fn convert(
     if output_format == Format::Mobi {
         return Err("MOBI output is not supported; use .azw3 instead".to_string());
     }
+    if wrap > 0 && output_format != Format::Markdown {
+        return Err("--wrap only applies to Markdown/text output".to_string());
+    }
 
     // Check if writing to stdout
     let to_stdout = output.is_none() || output == Some("-");
@@ -582,6 +620,27 @@ fn convert(
         }
     };
 
+    // Only EPUB output carries fixed layout through; say so rather than
+    // silently reflowing comics and picture books.
+    if book.metadata().rendition.is_fixed_layout()
+        && matches!(output_format, Format::Kfx | Format::Azw3)
+    {
+        eprintln!(
+            "Warning: fixed-layout (pre-paginated) books aren't fully supported in {output_format:?} output; pages will be reflowed"
+        );
+    }
+
+    if let Some(cover) = cover {
+        if matches!(output_format, Format::Markdown | Format::Ir) {
+            eprintln!("Warning: --cover is ignored for {output_format:?} output");
+        } else {
+            let data =
+                std::fs::read(cover).map_err(|e| format!("Failed to read cover '{cover}': {e}"))?;
+            book.set_cover(cover, data)
+                .map_err(|e| format!("Failed to set cover: {e}"))?;
+        }
+    }
+
     if optimize {
         let report = book.optimize();
         if !quiet {
@@ -597,11 +656,32 @@ fn convert(
         }
     }
 
+    if linearize_tables {
+        book.linearize_tables();
+    }
+
+    if embed_fonts != EmbedFontsArg::Full {
+        let report = book.embed_fonts(embed_fonts.into());
+        if !quiet && report.assets_changed > 0 {
+            let verb = if embed_fonts == EmbedFontsArg::None {
+                "dropped"
+            } else {
+                "subset"
+            };
+            eprintln!(
+                "Fonts: {} font{} {verb}, saved {:.1} KB",
+                report.assets_changed,
+                if report.assets_changed == 1 { "" } else { "s" },
+                report.bytes_saved as f64 / 1024.0,
+            );
+        }
+    }
+
     if to_stdout {
         // Write to stdout
         let mut stdout = std::io::stdout();
         let mut cursor = std::io::Cursor::new(Vec::new());
-        book.export(output_format, &mut cursor)
+        export_book(&book, output_format, wrap, &mut cursor, &mut |_| {})
             .map_err(|e| format!("Conversion failed: {e}"))?;
         use std::io::Write;
         stdout
@@ -614,8 +694,30 @@ fn convert(
         // Buffer the writer: the EPUB ZipWriter issues many small writes, each
         // of which would otherwise be a syscall.
         let mut writer = std::io::BufWriter::with_capacity(64 << 10, file);
-        book.export(output_format, &mut writer)
-            .map_err(|e| format!("Conversion failed: {e}"))?;
+        // A chapter counter, rewritten in place on one stderr line.
+        let mut counted = false;
+        let mut progress = |event| match event {
+            ProgressEvent::ChapterPrepared { index, total } if !quiet => {
+                eprint!("\r{:<24}", format!("Preparing {}/{total}", index + 1));
+                counted = true;
+            }
+            ProgressEvent::ChapterFinished { index, total } if !quiet => {
+                eprint!("\r{:<24}", format!("Chapter {}/{total}", index + 1));
+                counted = true;
+            }
+            ProgressEvent::CharactersRemoved { index, count } if !quiet => {
+                eprintln!(
+                    "\rChapter {}: removed {count} control character(s) or noncharacter(s)",
+                    index + 1
+                );
+            }
+            _ => {}
+        };
+        let result = export_book(&book, output_format, wrap, &mut writer, &mut progress);
+        if counted {
+            eprintln!();
+        }
+        result.map_err(|e| format!("Conversion failed: {e}"))?;
         std::io::Write::flush(&mut writer).map_err(|e| format!("Write failed: {e}"))?;
     }
 
@@ -626,6 +728,24 @@ fn convert(
     Ok(())
 }
 
+/// Export through the default exporter for `format`, except that Markdown
+/// gets the requested wrap width.
+fn export_book<W: std::io::Write + std::io::Seek>(
+    book: &Book,
+    format: Format,
+    wrap: usize,
+    writer: &mut W,
+    progress: &mut dyn FnMut(ProgressEvent),
+) -> boko::Result<()> {
+    if format == Format::Markdown && wrap > 0 {
+        use boko::export::Exporter;
+        boko::MarkdownExporter::with_config(boko::MarkdownConfig { line_width: wrap })
+            .export_with_progress(book, writer, progress)
+    } else {
+        book.export_with_progress(format, writer, progress)
+    }
+}
+
 // ----------------------------------------------------------------------------
 // Dump command
 // ----------------------------------------------------------------------------
@@ -5,16 +5,23 @@
 //! `crate::import` and `crate::export` in the layering.
 
 use std::collections::HashMap;
-use std::io::{self, Seek, Write};
-use std::path::Path;
+use std::io::{self, Read, Seek, Write};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, OnceLock, RwLock};
 
-use crate::export::{Azw3Exporter, EpubExporter, Exporter, KfxExporter, MarkdownExporter};
+use crate::dom::optimize::is_inline_role;
+use crate::export::{
+    Azw3Exporter, EpubExporter, Exporter, IrExporter, KfxExporter, MarkdownExporter, ProgressEvent,
+    synthesize_standalone_xhtml,
+};
 use crate::import::{
-    Azw3Importer, ChapterId, EpubImporter, Importer, KfxImporter, MobiImporter, SpineEntry,
+    Azw3Importer, CbzImporter, ChapterId, EpubImporter, Fb2Importer, Importer, IrImporter,
+    KfxImporter, MobiImporter, SpineEntry,
+};
+use crate::io::{ByteSource, FileSource, MemorySource, RangeFetcher, RangeSource};
+use crate::model::{
+    AnchorTarget, Chapter, Format, GlobalNodeId, Landmark, Metadata, ResolvedLinks, Role, TocEntry,
 };
-use crate::io::MemorySource;
-use crate::model::{AnchorTarget, Chapter, Format, Landmark, Metadata, ResolvedLinks, TocEntry};
 use crate::resolved::resolve_book_links;
 
 /// Runtime handle for an ebook.
@@ -22,6 +29,10 @@ use crate::resolved::resolve_book_links;
 /// `Book` wraps a format-specific `Importer` backend and provides
 /// unified access to metadata, table of contents, and content.
 ///
+/// With the `serde` feature, `Book` implements `Serialize`/`Deserialize` as
+/// a self-contained snapshot (metadata, navigation, raw chapters, and
+/// base64-encoded resources); a deserialized book needs no source file.
+///
 /// # Example
 ///
 /// ```no_run
@@ -51,10 +62,17 @@ pub struct Book {
     targeted_toc: OnceLock<Vec<TocEntry>>,
     /// Memoized link resolution, shared with callers as an `Arc`.
     resolved_links: OnceLock<Arc<ResolvedLinks>>,
+    /// Asset media types set by [`set_resource_media_type`](Self::set_resource_media_type),
+    /// taking precedence over the backend's.
+    media_types: HashMap<String, &'static str>,
 }
 
 impl Book {
     /// Open an ebook file, auto-detecting the format.
+    ///
+    /// Gzip-compressed files (`book.epub.gz`) are detected by their magic
+    /// bytes and decompressed into memory; the format comes from the
+    /// extension under `.gz`.
     pub fn open(path: impl AsRef<Path>) -> crate::Result<Self> {
         let path = path.as_ref();
         let format = Format::from_path(path).ok_or_else(|| crate::Error::UnsupportedFormat {
@@ -63,13 +81,43 @@ impl Book {
         Self::open_format(path, format)
     }
 
+    /// Open a password-protected EPUB: one whose ZIP entries are encrypted
+    /// (as some review copies are; this is not DRM). Traditional ZipCrypto
+    /// entries are always supported, AES ones with the `zip-aes` feature.
+    ///
+    /// Opening such a file with [`open`](Self::open) fails with
+    /// [`Error::PasswordRequired`](crate::Error::PasswordRequired); a wrong
+    /// password fails with [`Error::WrongPassword`](crate::Error::WrongPassword).
+    /// Files in other formats open as with `open`, ignoring the password.
+    pub fn open_with_password(path: impl AsRef<Path>, password: &str) -> crate::Result<Self> {
+        let path = path.as_ref();
+        if Format::from_path(path) != Some(Format::Epub) {
+            return Self::open(path);
+        }
+        let source: Arc<dyn ByteSource> = match Self::read_gunzipped(path)? {
+            Some(data) => Arc::new(MemorySource::new(data)),
+            None => Arc::new(FileSource::new(std::fs::File::open(path)?)?),
+        };
+        let backend = EpubImporter::from_source_with_password(source, Some(password))?;
+        Ok(Self::from_backend(Box::new(backend)))
+    }
+
     /// Open an ebook file with an explicit format.
+    ///
+    /// A gzip-compressed file is decompressed into memory first.
     pub fn open_format(path: impl AsRef<Path>, format: Format) -> crate::Result<Self> {
+        if let Some(data) = Self::read_gunzipped(path.as_ref())? {
+            return Self::from_source(Arc::new(MemorySource::new(data)), format);
+        }
+
         let backend: Box<dyn Importer> = match format {
             Format::Epub => Box::new(EpubImporter::open(path.as_ref())?),
             Format::Azw3 => Box::new(Azw3Importer::open(path.as_ref())?),
             Format::Mobi => Box::new(MobiImporter::open(path.as_ref())?),
             Format::Kfx => Box::new(KfxImporter::open(path.as_ref())?),
+            Format::Ir => Box::new(IrImporter::open(path.as_ref())?),
+            Format::Fb2 => Box::new(Fb2Importer::open(path.as_ref())?),
+            Format::Cbz => Box::new(CbzImporter::open(path.as_ref())?),
             Format::Markdown => {
                 return Err(crate::Error::UnsupportedFormat {
                     detail: "Markdown format is export-only".into(),
@@ -79,6 +127,91 @@ impl Book {
         Ok(Self::from_backend(backend))
     }
 
+    /// Open a remote ebook through a [`RangeFetcher`], auto-detecting the
+    /// format from the URL's path.
+    ///
+    /// Bytes are fetched on demand, so ZIP-based formats (EPUB) read only
+    /// the central directory and the entries actually loaded. A gzipped
+    /// book is fetched whole and decompressed into memory.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::io;
+    /// use std::process::Command;
+    ///
+    /// use boko::{Book, RangeFetcher};
+    ///
+    /// /// Fetches ranges by running `curl`.
+    /// struct Curl;
+    ///
+    /// fn curl(args: &[&str]) -> io::Result<Vec<u8>> {
+    ///     let output = Command::new("curl")
+    ///         .args(["--fail", "--silent", "--location"])
+    ///         .args(args)
+    ///         .output()?;
+    ///     if !output.status.success() {
+    ///         return Err(io::Error::other(format!("curl: {}", output.status)));
+    ///     }
+    ///     Ok(output.stdout)
+    /// }
+    ///
+    /// impl RangeFetcher for Curl {
+    ///     fn content_length(&self, url: &str) -> io::Result<u64> {
+    ///         // The last header block is the final response after redirects.
+    ///         let headers = String::from_utf8_lossy(&curl(&["--head", url])?).to_ascii_lowercase();
+    ///         headers
+    ///             .lines()
+    ///             .rev()
+    ///             .find_map(|line| line.strip_prefix("content-length:"))
+    ///             .and_then(|len| len.trim().parse().ok())
+    ///             .ok_or_else(|| io::Error::other("no Content-Length"))
+    ///     }
+    ///
+    ///     fn read_at(&self, url: &str, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+    ///         let range = format!("{offset}-{}", offset + len as u64 - 1);
+    ///         curl(&["--range", &range, url])
+    ///     }
+    /// }
+    ///
+    /// let book = Book::open_url("https://example.com/book.epub", Curl)?;
+    /// println!("{}", book.metadata().title);
+    /// # Ok::<(), boko::Error>(())
+    /// ```
+    pub fn open_url(url: &str, fetcher: impl RangeFetcher + 'static) -> crate::Result<Self> {
+        let format = Self::url_format(url)?;
+        Self::from_remote(RangeSource::new(url, fetcher)?, format)
+    }
+
+    /// Open a remote ebook over HTTP(S), auto-detecting the format from the
+    /// URL's path.
+    ///
+    /// Like [`open_url`](Self::open_url) with a built-in client: bytes are
+    /// fetched with `Range` requests, or downloaded whole when the server
+    /// doesn't support them. See [`crate::HttpRangeSource`].
+    #[cfg(all(feature = "http", not(target_arch = "wasm32")))]
+    pub fn open_http(url: &str) -> crate::Result<Self> {
+        let format = Self::url_format(url)?;
+        Self::from_remote(crate::io::HttpRangeSource::open(url)?, format)
+    }
+
+    /// The format named by a URL's path extension.
+    fn url_format(url: &str) -> crate::Result<Format> {
+        let path = url.split(['?', '#']).next().unwrap_or(url);
+        Format::from_path(path).ok_or_else(|| crate::Error::UnsupportedFormat {
+            detail: format!("unknown file format: {url}"),
+        })
+    }
+
+    /// Open a remote source, fetching a gzipped book whole to decompress it.
+    fn from_remote(source: impl ByteSource + 'static, format: Format) -> crate::Result<Self> {
+        if source.len() >= 2 && source.read_at(0, 2)? == crate::util::GZIP_MAGIC {
+            let compressed = source.read_at(0, source.len() as usize)?;
+            return Self::from_gzip(&compressed, format);
+        }
+        Self::from_source(Arc::new(source), format)
+    }
+
     /// Swap the importer backend, returning the old one.
     ///
     /// Cached chapters are dropped: they were produced by the old backend
@@ -92,26 +225,78 @@ impl Book {
         std::mem::replace(&mut self.backend, backend)
     }
 
-    fn from_backend(backend: Box<dyn Importer>) -> Self {
+    /// Drop the cached TOC fixups and link resolution, for edits that
+    /// replace the book's navigation or anchors.
+    pub(crate) fn clear_navigation_cache(&mut self) {
+        self.fixed_toc = OnceLock::new();
+        self.targeted_toc = OnceLock::new();
+        self.resolved_links = OnceLock::new();
+    }
+
+    pub(crate) fn from_backend(backend: Box<dyn Importer>) -> Self {
         Self {
             backend,
             ir_cache: Arc::new(RwLock::new(HashMap::new())),
             fixed_toc: OnceLock::new(),
             targeted_toc: OnceLock::new(),
             resolved_links: OnceLock::new(),
+            media_types: HashMap::new(),
         }
     }
 
     /// Create a Book from in-memory bytes with an explicit format.
     ///
     /// This is useful for reading from stdin or other non-file sources.
+    /// Gzip-compressed bytes are decompressed first.
     pub fn from_bytes(data: &[u8], format: Format) -> crate::Result<Self> {
-        let source = Arc::new(MemorySource::new(data.to_vec()));
+        if data.starts_with(&crate::util::GZIP_MAGIC) {
+            return Self::from_gzip(data, format);
+        }
+        Self::from_source(Arc::new(MemorySource::new(data.to_vec())), format)
+    }
+
+    /// Build a book from a directory of loose files.
+    ///
+    /// An unpacked EPUB (with `META-INF/container.xml`, or just an OPF) is
+    /// read as that EPUB. Otherwise the directory's XHTML files, in natural
+    /// name order (`ch2` before `ch10`), form the spine and every other file
+    /// is an asset typed by its extension; the title is the directory name.
+    /// Hidden files and symlinks are skipped. The files are read into memory.
+    pub fn from_directory(path: impl AsRef<Path>) -> crate::Result<Self> {
+        let epub = crate::import::pack_directory(path.as_ref())?;
+        Self::from_source(Arc::new(MemorySource::new(epub)), Format::Epub)
+    }
+
+    /// The decompressed contents of the file at `path` if it is gzipped,
+    /// `None` if it isn't.
+    fn read_gunzipped(path: &Path) -> crate::Result<Option<Vec<u8>>> {
+        let mut file = std::fs::File::open(path)?;
+        let mut magic = [0u8; 2];
+        if file.read_exact(&mut magic).is_err() || magic != crate::util::GZIP_MAGIC {
+            return Ok(None);
+        }
+        let mut compressed = Vec::new();
+        file.rewind()?;
+        file.read_to_end(&mut compressed)?;
+        let data = crate::util::bounded_gunzip(&compressed, crate::util::MAX_DECOMPRESSED_ENTRY)?;
+        Ok(Some(data))
+    }
+
+    /// Decompress a gzipped ebook into memory and open the inner format.
+    fn from_gzip(compressed: &[u8], format: Format) -> crate::Result<Self> {
+        let data = crate::util::bounded_gunzip(compressed, crate::util::MAX_DECOMPRESSED_ENTRY)?;
+        Self::from_source(Arc::new(MemorySource::new(data)), format)
+    }
+
+    fn from_source(source: Arc<dyn ByteSource>, format: Format) -> crate::Result<Self> {
         let backend: Box<dyn Importer> = match format {
             Format::Epub => Box::new(EpubImporter::from_source(source)?),
             Format::Azw3 => Box::new(Azw3Importer::from_source(source)?),
             Format::Mobi => Box::new(MobiImporter::from_source(source)?),
             Format::Kfx => Box::new(KfxImporter::from_source(source)?),
+            Format::Ir => Box::new(IrImporter::from_source(source)?),
+            Format::Fb2 => Box::new(Fb2Importer::from_source(source)?),
+            Format::Cbz => Box::new(CbzImporter::from_source(source)?),
             Format::Markdown => {
                 return Err(crate::Error::UnsupportedFormat {
                     detail: "Markdown format is export-only".into(),
@@ -152,6 +337,11 @@ impl Book {
         self.backend.spine()
     }
 
+    /// Number of chapters in the reading order.
+    pub fn chapter_count(&self) -> usize {
+        self.spine().len()
+    }
+
     /// Get the internal source path for a chapter.
     pub fn source_id(&self, id: ChapterId) -> Option<&str> {
         self.backend.source_id(id)
@@ -334,6 +524,12 @@ impl Book {
         self.backend.index_anchors(chapters);
     }
 
+    /// Index anchors from `(node, id)` pairs gathered without keeping every
+    /// chapter loaded (chapter-at-a-time export).
+    pub(crate) fn index_anchor_ids(&self, ids: &[(GlobalNodeId, &str)]) {
+        self.backend.index_anchor_ids(ids);
+    }
+
     /// Resolve TOC hrefs (fills in fragments for AZW3/MOBI).
     ///
     /// Computes the importer's fixed-up TOC once and caches it; formats whose
@@ -391,6 +587,104 @@ impl Book {
         self.backend.list_assets()
     }
 
+    /// Whether [`load_asset`](Self::load_asset) serves `path`, checked
+    /// without reading it. Covers names the backend serves without listing
+    /// them (KFX resource names).
+    pub(crate) fn contains_asset(&self, path: &str) -> bool {
+        self.backend.contains_asset(path)
+    }
+
+    /// Number of assets, as listed by [`list_assets`](Self::list_assets).
+    pub fn asset_count(&self) -> usize {
+        self.list_assets().len()
+    }
+
+    /// List all assets with their media types.
+    ///
+    /// Types come from the package manifest where the format has one (EPUB),
+    /// so filtering by type doesn't touch asset bytes. Otherwise the type is
+    /// guessed from the extension, and sniffed from the content only for
+    /// extensionless names (e.g. KFX resource ids). Types set with
+    /// [`set_resource_media_type`](Self::set_resource_media_type) win.
+    pub fn list_assets_with_types(&self) -> Vec<(PathBuf, String)> {
+        self.list_assets()
+            .iter()
+            .map(|path| (PathBuf::from(path), self.asset_type(path).to_string()))
+            .collect()
+    }
+
+    /// Media type of one asset, as [`list_assets_with_types`](Self::list_assets_with_types)
+    /// reports it.
+    pub(crate) fn asset_type(&self, path: &str) -> &str {
+        match self.media_types.get(path) {
+            Some(set) => set,
+            None => match self.backend.asset_media_type(path) {
+                Some(declared) => declared,
+                None => self.sniff_media_type(path),
+            },
+        }
+    }
+
+    /// [`asset_type`](Self::asset_type) as one of the media types boko knows,
+    /// for writing into a manifest. A declared type boko doesn't recognize
+    /// falls back to the extension and the bytes.
+    pub(crate) fn declared_media_type(&self, path: &str) -> &'static str {
+        crate::util::known_media_type(self.asset_type(path))
+            .unwrap_or_else(|| self.sniff_media_type(path))
+    }
+
+    /// Forget a media type set with
+    /// [`set_resource_media_type`](Self::set_resource_media_type).
+    pub(crate) fn clear_media_type_override(&mut self, path: &str) {
+        self.media_types.remove(path);
+    }
+
+    /// Record a media type for an asset, ahead of the backend's and the
+    /// extension's. The type must already be interned.
+    pub(crate) fn set_media_type_override(&mut self, path: &str, media_type: &'static str) {
+        self.media_types.insert(path.to_string(), media_type);
+    }
+
+    /// Relabel an asset's media type, e.g. to fix a JPEG the source declares
+    /// as PNG. Exported manifests use the new type.
+    ///
+    /// Fails with [`Error::NotFound`](crate::Error::NotFound) for a path that
+    /// isn't an asset, and [`Error::UnsupportedFormat`](crate::Error::UnsupportedFormat)
+    /// for a media type boko doesn't know.
+    pub fn set_resource_media_type(&mut self, path: &str, media_type: &str) -> crate::Result<()> {
+        if !self.list_assets().iter().any(|asset| asset == path) {
+            return Err(crate::Error::NotFound {
+                what: format!("asset {path}"),
+            });
+        }
+        let media_type = crate::util::require_known_media_type(media_type)?;
+        self.media_types.insert(path.to_string(), media_type);
+        Ok(())
+    }
+
+    /// Media type set for an asset with
+    /// [`set_resource_media_type`](Self::set_resource_media_type), if any.
+    pub(crate) fn media_type_override(&self, path: &str) -> Option<&'static str> {
+        self.media_types.get(path).copied()
+    }
+
+    /// Media type of an asset: a type set by the caller first, then the
+    /// extension, with magic-byte sniffing as fallback for
+    /// unknown/extensionless names (e.g. KFX short resource names like "e6")
+    /// so real images aren't typed as octet-streams.
+    pub(crate) fn sniff_media_type(&self, path: &str) -> &'static str {
+        if let Some(set) = self.media_type_override(path) {
+            return set;
+        }
+        let by_ext = crate::util::guess_media_type(path);
+        if by_ext != "application/octet-stream" {
+            return by_ext;
+        }
+        self.load_asset(path)
+            .map(|data| crate::util::detect_media_format(path, &data).mime_type())
+            .unwrap_or(by_ext)
+    }
+
     /// Collect all @font-face definitions from CSS files.
     ///
     /// Returns font-face rules that map font family names to font files.
@@ -400,6 +694,194 @@ impl Book {
         self.backend.font_faces()
     }
 
+    /// Page size and margins from the book's `@page` CSS rules, if any.
+    ///
+    /// Print-oriented and pre-paginated books declare the page box their
+    /// content was laid out for here.
+    pub fn page_rule(&self) -> Option<crate::style::PageRule> {
+        self.backend.page_rule()
+    }
+
+    /// Count the words in the book's content, across every spine chapter.
+    ///
+    /// Words are whitespace-separated runs of text in the compiled IR, so
+    /// markup never splits or joins them. Chapters that fail to load count
+    /// as empty. Loads chapters through the IR cache.
+    pub fn word_count(&self) -> usize {
+        self.spine()
+            .iter()
+            .filter_map(|entry| self.load_chapter_cached(entry.id).ok())
+            .map(|chapter| count_words(&chapter))
+            .sum()
+    }
+
+    /// The plain text of every spine chapter (see [`Chapter::plain_text`]),
+    /// separated by blank lines. Chapters that fail to load are skipped.
+    /// Loads chapters through the IR cache.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use boko::Book;
+    ///
+    /// let book = Book::open("input.epub")?;
+    /// let text = book.plain_text();
+    /// # Ok::<(), boko::Error>(())
+    /// ```
+    pub fn plain_text(&self) -> String {
+        self.spine()
+            .iter()
+            .filter_map(|entry| self.load_chapter_cached(entry.id).ok())
+            .map(|chapter| chapter.plain_text())
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Estimated reading time in whole minutes at `words_per_minute`
+    /// (250 is a typical adult reading speed), rounded up.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use boko::Book;
+    ///
+    /// let book = Book::open("input.epub")?;
+    /// println!("about {} min", book.reading_time_minutes(250));
+    /// # Ok::<(), boko::Error>(())
+    /// ```
+    pub fn reading_time_minutes(&self, words_per_minute: u32) -> u32 {
+        let words = self.word_count() as u64;
+        words.div_ceil(u64::from(words_per_minute.max(1))) as u32
+    }
+
+    /// Standalone XHTML for one chapter, as the EPUB writer would emit it.
+    ///
+    /// The chapter's IR is serialized with the EPUB synthesizer, and its
+    /// styles become an inline `<style>` element, so the result renders on
+    /// its own. Useful for inspecting or re-templating chapters of books
+    /// whose source isn't HTML (KFX, MOBI). The title is the first heading,
+    /// falling back to the chapter's source path.
+    pub fn chapter_html(&self, id: ChapterId) -> crate::Result<String> {
+        let chapter = self.load_chapter_cached(id)?;
+        let title = crate::export::extract_chapter_title(&chapter)
+            .or_else(|| self.source_id(id).map(str::to_string))
+            .unwrap_or_default();
+        Ok(synthesize_standalone_xhtml(&chapter, &title).body)
+    }
+
+    /// Unified diff of this book's plain text against `other`'s.
+    ///
+    /// Chapters are paired by source path (by spine position when the two
+    /// books share no paths, as after a conversion that renames files) and
+    /// rendered one line per block, so the diff shows which paragraphs a
+    /// conversion changed regardless of markup. Each differing chapter gets
+    /// `---`/`+++` headers naming its source path in either book; a chapter
+    /// only one book has is diffed against `/dev/null`. Returns an empty
+    /// string when the text is identical.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use boko::Book;
+    ///
+    /// let original = Book::open("input.epub")?;
+    /// let converted = Book::open("output.azw3")?;
+    /// print!("{}", original.diff_text(&converted));
+    /// # Ok::<(), boko::Error>(())
+    /// ```
+    pub fn diff_text(&self, other: &Book) -> String {
+        let mut out = String::new();
+        for (old_id, new_id) in self.chapter_pairs(other) {
+            let (old_name, old_text) = self.chapter_text(old_id, "a");
+            let (new_name, new_text) = other.chapter_text(new_id, "b");
+            let old: Vec<&str> = old_text.lines().collect();
+            let new: Vec<&str> = new_text.lines().collect();
+            crate::diff::unified_diff(
+                &mut out,
+                &old_name,
+                &new_name,
+                &old,
+                &new,
+                crate::diff::CONTEXT,
+            );
+        }
+        out
+    }
+
+    /// Spine chapters of this book and `other` to diff against each other,
+    /// in spine order. Chapters pair by source path, and one the other book
+    /// lacks pairs with `None`; books with no path in common pair by spine
+    /// position instead.
+    fn chapter_pairs(&self, other: &Book) -> Vec<(Option<ChapterId>, Option<ChapterId>)> {
+        let theirs: HashMap<&str, usize> = other
+            .spine()
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| Some((other.source_id(entry.id)?, index)))
+            .collect();
+        let matches: Vec<Option<usize>> = self
+            .spine()
+            .iter()
+            .map(|entry| {
+                self.source_id(entry.id)
+                    .and_then(|path| theirs.get(path).copied())
+            })
+            .collect();
+        if matches.iter().all(Option::is_none) {
+            let chapters = self.spine().len().max(other.spine().len());
+            return (0..chapters)
+                .map(|index| {
+                    (
+                        self.spine().get(index).map(|e| e.id),
+                        other.spine().get(index).map(|e| e.id),
+                    )
+                })
+                .collect();
+        }
+
+        // Walk this spine, slotting each of `other`'s unmatched chapters in
+        // before the next matched chapter that follows it.
+        let mut matched = vec![false; other.spine().len()];
+        for &index in matches.iter().flatten() {
+            matched[index] = true;
+        }
+        let unmatched = |range: std::ops::Range<usize>| {
+            range
+                .filter(|&index| !matched[index])
+                .map(|index| (None, Some(other.spine()[index].id)))
+        };
+        let mut pairs = Vec::new();
+        let mut next = 0;
+        for (entry, theirs) in self.spine().iter().zip(&matches) {
+            if let Some(index) = *theirs {
+                pairs.extend(unmatched(next..index));
+                next = next.max(index + 1);
+            }
+            pairs.push((Some(entry.id), theirs.map(|index| other.spine()[index].id)));
+        }
+        pairs.extend(unmatched(next..other.spine().len()));
+        pairs
+    }
+
+    /// Diff header name (source path under `side/`) and plain text of a
+    /// spine chapter. Unloadable chapters are empty; a missing one is
+    /// named `/dev/null`.
+    fn chapter_text(&self, id: Option<ChapterId>, side: &str) -> (String, String) {
+        let Some(id) = id else {
+            return ("/dev/null".to_string(), String::new());
+        };
+        let name = match self.source_id(id) {
+            Some(path) => format!("{side}/{path}"),
+            None => format!("{side}/chapter-{}", id.0),
+        };
+        let text = self
+            .load_chapter_cached(id)
+            .map(|chapter| block_lines(&chapter))
+            .unwrap_or_default();
+        (name, text)
+    }
+
     /// Whether this book requires normalized export for HTML-based formats.
     ///
     /// Returns true for binary formats (KFX) where the raw content is not HTML.
@@ -419,6 +901,7 @@ impl Book {
     /// | MOBI     | ✗       |
     /// | Text     | ✓       |
     /// | Markdown | ✓       |
+    /// | IR dump  | ✓       |
     ///
     /// # Example
     ///
@@ -432,14 +915,97 @@ impl Book {
     /// # Ok::<(), boko::Error>(())
     /// ```
     pub fn export<W: Write + Seek>(&self, format: Format, writer: &mut W) -> crate::Result<()> {
+        self.export_with_progress(format, writer, &mut |_| {})
+    }
+
+    /// [`export`](Self::export), calling `progress` as each spine chapter
+    /// and asset is written — once per chapter, in spine order (see
+    /// [`ProgressEvent`]).
+    ///
+    /// ```no_run
+    /// use boko::{Book, Format, ProgressEvent};
+    /// use std::fs::File;
+    ///
+    /// let book = Book::open("input.epub")?;
+    /// let mut file = File::create("output.kfx")?;
+    /// book.export_with_progress(Format::Kfx, &mut file, &mut |event| {
+    ///     if let ProgressEvent::ChapterFinished { index, total } = event {
+    ///         eprintln!("{}/{total}", index + 1);
+    ///     }
+    /// })?;
+    /// # Ok::<(), boko::Error>(())
+    /// ```
+    pub fn export_with_progress<W: Write + Seek>(
+        &self,
+        format: Format,
+        writer: &mut W,
+        progress: &mut dyn FnMut(ProgressEvent),
+    ) -> crate::Result<()> {
         match format {
-            Format::Epub => EpubExporter::new().export(self, writer),
-            Format::Azw3 => Azw3Exporter::new().export(self, writer),
-            Format::Markdown => MarkdownExporter::new().export(self, writer),
-            Format::Kfx => KfxExporter::new().export(self, writer),
-            Format::Mobi => Err(crate::Error::UnsupportedFormat {
+            Format::Epub => EpubExporter::new().export_with_progress(self, writer, progress),
+            Format::Azw3 => Azw3Exporter::new().export_with_progress(self, writer, progress),
+            Format::Markdown => {
+                MarkdownExporter::new().export_with_progress(self, writer, progress)
+            }
+            Format::Kfx => KfxExporter::new().export_with_progress(self, writer, progress),
+            Format::Ir => IrExporter::new().export_with_progress(self, writer, progress),
+            Format::Mobi | Format::Fb2 | Format::Cbz => Err(crate::Error::UnsupportedFormat {
                 detail: format!("{:?} export is not supported", format),
             }),
         }
     }
 }
+
+/// Words in a chapter's text nodes. Text is joined in document order, with
+/// block boundaries and line breaks acting as separators, so a word split
+/// across inline runs (`<b>wo</b>rd`) counts once.
+fn count_words(chapter: &Chapter) -> usize {
+    let mut words = 0;
+    let mut in_word = false;
+    for id in chapter.iter_dfs() {
+        let Some(node) = chapter.node(id) else {
+            continue;
+        };
+        if node.role == Role::Text {
+            for c in chapter.text(node.text).chars() {
+                if c.is_whitespace() {
+                    in_word = false;
+                } else if !in_word {
+                    in_word = true;
+                    words += 1;
+                }
+            }
+        } else if node.role == Role::Break || !is_inline_role(node.role) {
+            in_word = false;
+        }
+    }
+    words
+}
+
+/// A chapter's text with one line per block: inline runs are joined,
+/// whitespace is collapsed, and block boundaries and line breaks end the
+/// current line. Empty lines are dropped.
+fn block_lines(chapter: &Chapter) -> String {
+    let mut out = String::new();
+    let mut line = String::new();
+    fn flush(line: &mut String, out: &mut String) {
+        let collapsed = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !collapsed.is_empty() {
+            out.push_str(&collapsed);
+            out.push('\n');
+        }
+        line.clear();
+    }
+    for id in chapter.iter_dfs() {
+        let Some(node) = chapter.node(id) else {
+            continue;
+        };
+        if node.role == Role::Text {
+            line.push_str(chapter.text(node.text));
+        } else if node.role == Role::Break || !is_inline_role(node.role) {
+            flush(&mut line, &mut out);
+        }
+    }
+    flush(&mut line, &mut out);
+    out
+}
@@ -0,0 +1,179 @@
+//! Line-based unified diffs, used by [`crate::Book::diff_text`].
+//!
+//! The edit script comes from Myers' O((n+m)·d) algorithm (via `similar`),
+//! so a whole-chapter diff stays cheap when a few paragraphs differ out of
+//! thousands.
+
+/// Lines of unchanged context around each change.
+pub(crate) const CONTEXT: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Append a unified diff of `old` against `new` to `out`, with `---`/`+++`
+/// headers naming the two sides. Appends nothing when the lines are equal.
+pub(crate) fn unified_diff(
+    out: &mut String,
+    old_name: &str,
+    new_name: &str,
+    old: &[&str],
+    new: &[&str],
+    context: usize,
+) {
+    let ops = edit_script(old, new);
+    if ops.iter().all(|&op| op == Op::Equal) {
+        return;
+    }
+    out.push_str(&format!("--- {old_name}\n+++ {new_name}\n"));
+
+    // Positions in `ops` of every change, grouped into hunks whose context
+    // windows touch.
+    let changes: Vec<usize> = (0..ops.len()).filter(|&i| ops[i] != Op::Equal).collect();
+    let mut groups: Vec<(usize, usize)> = Vec::new();
+    for &i in &changes {
+        match groups.last_mut() {
+            Some((_, end)) if i <= *end + 2 * context + 1 => *end = i,
+            _ => groups.push((i, i)),
+        }
+    }
+
+    for (first, last) in groups {
+        let start = first.saturating_sub(context);
+        let end = (last + context + 1).min(ops.len());
+
+        // Line numbers (0-based) of each side at `start`.
+        let (mut o, mut n) = (0, 0);
+        for &op in &ops[..start] {
+            match op {
+                Op::Equal => (o, n) = (o + 1, n + 1),
+                Op::Delete => o += 1,
+                Op::Insert => n += 1,
+            }
+        }
+        let old_len = ops[start..end]
+            .iter()
+            .filter(|&&op| op != Op::Insert)
+            .count();
+        let new_len = ops[start..end]
+            .iter()
+            .filter(|&&op| op != Op::Delete)
+            .count();
+        out.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(o, old_len),
+            hunk_range(n, new_len)
+        ));
+        for &op in &ops[start..end] {
+            let (sign, line) = match op {
+                Op::Equal => {
+                    o += 1;
+                    n += 1;
+                    (' ', old[o - 1])
+                }
+                Op::Delete => {
+                    o += 1;
+                    ('-', old[o - 1])
+                }
+                Op::Insert => {
+                    n += 1;
+                    ('+', new[n - 1])
+                }
+            };
+            out.push(sign);
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+}
+
+/// `start,len` in unified-diff form: 1-based, and an empty range names
+/// the line before it.
+fn hunk_range(start: usize, len: usize) -> String {
+    match len {
+        0 => format!("{start},0"),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{len}", start + 1),
+    }
+}
+
+/// Shortest edit script turning `old` into `new`, one op per line.
+fn edit_script(old: &[&str], new: &[&str]) -> Vec<Op> {
+    let mut ops = Vec::with_capacity(old.len().max(new.len()));
+    for op in similar::capture_diff_slices(similar::Algorithm::Myers, old, new) {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+        match tag {
+            similar::DiffTag::Equal => ops.extend(old_range.map(|_| Op::Equal)),
+            similar::DiffTag::Delete => ops.extend(old_range.map(|_| Op::Delete)),
+            similar::DiffTag::Insert => ops.extend(new_range.map(|_| Op::Insert)),
+            similar::DiffTag::Replace => {
+                ops.extend(old_range.map(|_| Op::Delete));
+                ops.extend(new_range.map(|_| Op::Insert));
+            }
+        }
+    }
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff(old: &[&str], new: &[&str], context: usize) -> String {
+        let mut out = String::new();
+        unified_diff(&mut out, "a", "b", old, new, context);
+        out
+    }
+
+    #[test]
+    fn equal_inputs_produce_nothing() {
+        assert_eq!(diff(&["x", "y"], &["x", "y"], CONTEXT), "");
+    }
+
+    #[test]
+    fn changed_line_gets_a_hunk_with_context() {
+        let old = ["one", "two", "three", "four", "five", "six"];
+        let new = ["one", "two", "THREE", "four", "five", "six"];
+        assert_eq!(
+            diff(&old, &new, 1),
+            "--- a\n+++ b\n@@ -2,3 +2,3 @@\n two\n-three\n+THREE\n four\n"
+        );
+    }
+
+    #[test]
+    fn distant_changes_get_separate_hunks() {
+        let old = ["a", "b", "c", "d", "e", "f", "g", "h"];
+        let new = ["A", "b", "c", "d", "e", "f", "g"];
+        assert_eq!(
+            diff(&old, &new, 1),
+            "--- a\n+++ b\n@@ -1,2 +1,2 @@\n-a\n+A\n b\n@@ -7,2 +7 @@\n g\n-h\n"
+        );
+    }
+
+    #[test]
+    fn insertion_into_empty_side() {
+        assert_eq!(
+            diff(&[], &["new"], CONTEXT),
+            "--- a\n+++ b\n@@ -0,0 +1 @@\n+new\n"
+        );
+    }
+
+    #[test]
+    fn long_inputs_with_distant_changes_stay_cheap() {
+        // No common prefix or suffix to trim: a quadratic table over these
+        // would need billions of cells.
+        let old: Vec<String> = (0..60_000).map(|i| format!("line {i}")).collect();
+        let mut new = old.clone();
+        new[0] = "first".to_string();
+        new[59_999] = "last".to_string();
+        let old: Vec<&str> = old.iter().map(String::as_str).collect();
+        let new: Vec<&str> = new.iter().map(String::as_str).collect();
+        assert_eq!(
+            diff(&old, &new, 0),
+            "--- a\n+++ b\n@@ -1 +1 @@\n-line 0\n+first\n@@ -60000 +60000 @@\n-line 59999\n+last\n"
+        );
+    }
+}
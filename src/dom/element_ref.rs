@@ -133,13 +133,45 @@ impl<'a> From<&'a str> for CssNamespace {
     }
 }
 
-/// Pseudo-element type (not used but required by trait).
+/// Pseudo-elements recognized in selectors.
+///
+/// They parse so that a rule like `p::before, .aside { ... }` still compiles,
+/// but they never match: the stylesheet parser drops selectors that carry
+/// one, since generated content has no node in the DOM to style.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub enum PseudoElement {}
+pub enum PseudoElement {
+    Before,
+    After,
+    FirstLine,
+    FirstLetter,
+    Marker,
+    Selection,
+}
+
+impl PseudoElement {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name.to_ascii_lowercase().as_str() {
+            "before" => Self::Before,
+            "after" => Self::After,
+            "first-line" => Self::FirstLine,
+            "first-letter" => Self::FirstLetter,
+            "marker" => Self::Marker,
+            "selection" => Self::Selection,
+            _ => return None,
+        })
+    }
+}
 
 impl cssparser::ToCss for PseudoElement {
-    fn to_css<W: fmt::Write>(&self, _dest: &mut W) -> fmt::Result {
-        match *self {}
+    fn to_css<W: fmt::Write>(&self, dest: &mut W) -> fmt::Result {
+        dest.write_str(match self {
+            Self::Before => "::before",
+            Self::After => "::after",
+            Self::FirstLine => "::first-line",
+            Self::FirstLetter => "::first-letter",
+            Self::Marker => "::marker",
+            Self::Selection => "::selection",
+        })
     }
 }
 
@@ -192,6 +224,41 @@ impl cssparser::ToCss for NonTSPseudoClass {
 impl<'i> selectors::parser::Parser<'i> for BokoSelectors {
     type Impl = BokoSelectors;
     type Error = SelectorParseErrorKind<'i>;
+
+    fn parse_is_and_where(&self) -> bool {
+        true
+    }
+
+    fn parse_non_ts_pseudo_class(
+        &self,
+        location: cssparser::SourceLocation,
+        name: cssparser::CowRcStr<'i>,
+    ) -> Result<NonTSPseudoClass, cssparser::ParseError<'i, Self::Error>> {
+        Ok(match name.to_ascii_lowercase().as_str() {
+            "link" | "any-link" => NonTSPseudoClass::Link,
+            "visited" => NonTSPseudoClass::Visited,
+            "hover" => NonTSPseudoClass::Hover,
+            "active" => NonTSPseudoClass::Active,
+            "focus" => NonTSPseudoClass::Focus,
+            _ => {
+                return Err(location.new_custom_error(
+                    SelectorParseErrorKind::UnsupportedPseudoClassOrElement(name),
+                ));
+            }
+        })
+    }
+
+    fn parse_pseudo_element(
+        &self,
+        location: cssparser::SourceLocation,
+        name: cssparser::CowRcStr<'i>,
+    ) -> Result<PseudoElement, cssparser::ParseError<'i, Self::Error>> {
+        PseudoElement::from_name(&name).ok_or_else(|| {
+            location.new_custom_error(SelectorParseErrorKind::UnsupportedPseudoClassOrElement(
+                name,
+            ))
+        })
+    }
 }
 
 impl SelectorImpl for BokoSelectors {
@@ -106,7 +106,7 @@ pub fn compile_html(html: &str, author_stylesheets: &[(Stylesheet, Origin)]) ->
     let dom = parse_dom(html);
     let refs: Vec<(&Stylesheet, Origin)> =
         author_stylesheets.iter().map(|(s, o)| (s, *o)).collect();
-    compile_dom(&dom, &refs)
+    compile_dom(&dom, &refs, None)
 }
 
 /// Compile an already-parsed DOM to IR with borrowed stylesheets.
@@ -114,7 +114,13 @@ pub fn compile_html(html: &str, author_stylesheets: &[(Stylesheet, Origin)]) ->
 /// Internal hot path shared by [`compile_html`] and the importers: no
 /// stylesheet is cloned — the UA sheet is shared per thread and author
 /// sheets are borrowed (typically from `Arc<Stylesheet>` caches).
-pub(crate) fn compile_dom(dom: &ArenaDom, author_stylesheets: &[(&Stylesheet, Origin)]) -> Chapter {
+/// `asset_exists`, when given, limits responsive image sources to URLs
+/// naming an asset of the book.
+pub(crate) fn compile_dom(
+    dom: &ArenaDom,
+    author_stylesheets: &[(&Stylesheet, Origin)],
+    asset_exists: Option<&dyn Fn(&str) -> bool>,
+) -> Chapter {
     // Build complete stylesheet list with UA defaults
     let ua = transform::user_agent_stylesheet_arc();
     let mut all_stylesheets: Vec<(&Stylesheet, Origin)> =
@@ -123,7 +129,7 @@ pub(crate) fn compile_dom(dom: &ArenaDom, author_stylesheets: &[(&Stylesheet, Or
     all_stylesheets.extend_from_slice(author_stylesheets);
 
     // Transform to IR
-    let mut chapter = transform::transform(dom, &all_stylesheets);
+    let mut chapter = transform::transform(dom, &all_stylesheets, asset_exists);
 
     // Optimize: merge adjacent text nodes with identical styles
     optimize::optimize(&mut chapter);
@@ -50,7 +50,7 @@ pub fn is_block_container(role: Option<Role>) -> bool {
 pub fn is_inline_role(role: Role) -> bool {
     matches!(
         role,
-        Role::Text | Role::Inline | Role::Link | Role::Image | Role::Break
+        Role::Text | Role::Inline | Role::Quote | Role::Link | Role::Image | Role::Break
     )
 }
 
@@ -88,7 +88,11 @@ pub fn has_semantic_attrs(chapter: &Chapter, node_id: NodeId) -> bool {
         || s.datetime(node_id).is_some()
         || s.language(node_id).is_some()
         || s.list_start(node_id).is_some()
+        || s.list_value(node_id).is_some()
         || s.row_span(node_id).is_some()
         || s.col_span(node_id).is_some()
         || s.is_header_cell(node_id)
+        || s.scope(node_id).is_some()
+        || s.headers(node_id).is_some()
+        || s.is_decorative(node_id)
 }
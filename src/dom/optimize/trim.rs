@@ -11,6 +11,12 @@
 //! This trims leading whitespace off the block's first text run(s) and
 //! trailing whitespace off its last, descending through inline wrappers but
 //! stopping at nested block children (each block trims its own edges).
+//!
+//! A newline that survives into the IR text is a line break, not
+//! collapsible space: whitespace normalization has already turned source
+//! newlines into spaces, so what's left comes from KFX content (which spells
+//! `<br>` as `\n`) or a preserved-whitespace block. Trimming stops at it,
+//! keeping a verse line's trailing break.
 
 use crate::model::{Chapter, NodeId, Role, TextRange};
 
@@ -126,16 +132,21 @@ fn collect(chapter: &Chapter, id: NodeId, is_root: bool, out: &mut Vec<Leaf>) {
     }
 }
 
-/// Trim leading ASCII whitespace from a text node. Returns `true` when the
-/// node still holds non-whitespace text afterwards (the block edge is
-/// resolved), `false` when it was entirely whitespace.
+/// Whitespace that block edges shed: everything but a line break.
+fn is_edge_space(c: char) -> bool {
+    c.is_whitespace() && c != '\n'
+}
+
+/// Trim leading whitespace from a text node. Returns `true` when the node
+/// still holds non-whitespace text (or a line break) afterwards — the block
+/// edge is resolved — and `false` when it was entirely whitespace.
 fn trim_leading(chapter: &mut Chapter, id: NodeId) -> bool {
     let range = match chapter.node(id) {
         Some(n) if n.role == Role::Text => n.text,
         _ => return false,
     };
     let text = chapter.text(range);
-    let trimmed = text.trim_start();
+    let trimmed = text.trim_start_matches(is_edge_space);
     let removed = text.len() - trimmed.len();
     let has_content = !trimmed.is_empty();
     if removed > 0 {
@@ -147,15 +158,15 @@ fn trim_leading(chapter: &mut Chapter, id: NodeId) -> bool {
     has_content
 }
 
-/// Trim trailing ASCII whitespace. Returns `true` when non-whitespace text
-/// remains.
+/// Trim trailing whitespace. Returns `true` when non-whitespace text (or a
+/// line break) remains.
 fn trim_trailing(chapter: &mut Chapter, id: NodeId) -> bool {
     let range = match chapter.node(id) {
         Some(n) if n.role == Role::Text => n.text,
         _ => return false,
     };
     let text = chapter.text(range);
-    let trimmed = text.trim_end();
+    let trimmed = text.trim_end_matches(is_edge_space);
     let removed = text.len() - trimmed.len();
     let has_content = !trimmed.is_empty();
     if removed > 0 {
@@ -238,4 +249,19 @@ mod tests {
         trim_block_edges(&mut chapter);
         assert_eq!(text_of(&chapter, t_node), "see ");
     }
+
+    #[test]
+    fn line_breaks_at_block_edges_are_kept() {
+        // KFX spells `<br>` as a newline in the text: a verse line's
+        // trailing break is content, the spaces around it are not.
+        let mut chapter = Chapter::new();
+        let para = chapter.alloc_node(Node::new(Role::Paragraph));
+        chapter.append_child(NodeId::ROOT, para);
+        let t = chapter.append_text(" \nLine one\nLine two\n ");
+        let t_node = chapter.alloc_node(Node::text(t));
+        chapter.append_child(para, t_node);
+
+        trim_block_edges(&mut chapter);
+        assert_eq!(text_of(&chapter, t_node), "\nLine one\nLine two\n");
+    }
 }
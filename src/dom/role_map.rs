@@ -35,7 +35,10 @@ pub fn element_to_role(local_name: &LocalName) -> Role {
         // Inline elements with styling (rendered via ComputedStyle)
         "span" | "em" | "i" | "cite" | "var" | "dfn" | "strong" | "b" | "code" | "kbd" | "samp"
         | "font" | "tt" | "sup" | "sub" | "u" | "ins" | "s" | "strike" | "del" | "small"
-        | "mark" | "abbr" | "time" | "q" => Role::Inline,
+        | "mark" | "abbr" | "time" => Role::Inline,
+
+        // Inline quotation
+        "q" => Role::Quote,
 
         // Headings with level
         "h1" => Role::Heading(1),
@@ -48,8 +51,9 @@ pub fn element_to_role(local_name: &LocalName) -> Role {
         // Links
         "a" => Role::Link,
 
-        // Images
+        // Images (`<picture>` wraps an `<img>` and its alternative sources)
         "img" => Role::Image,
+        "picture" => Role::Inline,
 
         // Lists
         "ul" => Role::UnorderedList,
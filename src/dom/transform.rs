@@ -1,5 +1,6 @@
 //! Transform ArenaDom to Chapter.
 
+use html5ever::QualName;
 use selectors::Element as _;
 use selectors::bloom::BloomFilter;
 
@@ -8,8 +9,8 @@ use super::element_ref::ElementRef;
 use super::role_map::element_to_role;
 use crate::model::{Chapter, Node, NodeId, Role};
 use crate::style::{
-    CascadeIndex, CascadeScratch, ComputedStyle, Display, Origin, Stylesheet, WhiteSpace,
-    compute_styles_indexed,
+    CascadeIndex, CascadeScratch, ComputedStyle, Display, Length, Origin, Stylesheet, TextAlign,
+    TextTransform, WhiteSpace, compute_styles_indexed,
 };
 
 /// User agent stylesheet (browser defaults).
@@ -33,7 +34,7 @@ pub(crate) fn user_agent_stylesheet_arc() -> std::sync::Arc<Stylesheet> {
 }
 
 /// Synthesize CSS declarations from legacy presentational attributes
-/// (`align=`, `valign=`), which many older EPUB/MOBI-derived books rely on.
+/// (`align=`, `valign=`, list `type=`), which many older EPUB/MOBI-derived books rely on.
 /// Per CSS these are presentational hints: they apply before author rules
 /// (any matching selector overrides them) but beat inherited values.
 fn presentational_hints(
@@ -62,10 +63,11 @@ fn presentational_hints(
     );
     let cellish = matches!(name, "td" | "th" | "tr" | "tbody" | "thead" | "tfoot");
     let font = name == "font";
+    let list = matches!(name, "ol" | "ul" | "li");
     // MOBI-7 spacing convention: height= is vertical space before the
     // block, width= is the first-line indent.
     let mobi_spaced = matches!(name, "p" | "div" | "blockquote");
-    if !aligned && !cellish && !font && !mobi_spaced {
+    if !aligned && !cellish && !font && !list && !mobi_spaced {
         return None;
     }
     let clean_len = |v: &str| {
@@ -130,8 +132,10 @@ fn presentational_hints(
             "face" if font => {
                 let v = attr.value.trim();
                 if !v.is_empty() && v.chars().all(|c| !c.is_control() && c != ';') {
+                    // face= is a bare comma list; names like `001 Sans`
+                    // need quotes to parse as CSS.
                     css.push_str("font-family: ");
-                    css.push_str(v);
+                    crate::style::quote_font_family(&mut css, v);
                     css.push(';');
                 }
             }
@@ -159,6 +163,26 @@ fn presentational_hints(
                     }
                 }
             }
+            // HTML list `type=`: numbering values are case-sensitive
+            // (`a` vs `A`), bullet values are not.
+            "type" if list => {
+                let v = attr.value.trim();
+                let style = match v {
+                    "1" => Some("decimal"),
+                    "a" => Some("lower-alpha"),
+                    "A" => Some("upper-alpha"),
+                    "i" => Some("lower-roman"),
+                    "I" => Some("upper-roman"),
+                    _ => ["disc", "circle", "square", "none"]
+                        .into_iter()
+                        .find(|kw| v.eq_ignore_ascii_case(kw)),
+                };
+                if let Some(style) = style {
+                    css.push_str("list-style-type: ");
+                    css.push_str(style);
+                    css.push(';');
+                }
+            }
             _ => {}
         }
     }
@@ -186,11 +210,18 @@ struct TransformContext<'a> {
     /// ancestor requirements (then `None` is passed and matching skips the
     /// bloom checks entirely).
     use_bloom: bool,
+    /// Whether an image URL (as written in the markup) names an asset of the
+    /// book. `None` when compiling markup outside a book: every URL counts.
+    asset_exists: Option<&'a dyn Fn(&str) -> bool>,
     chapter: Chapter,
 }
 
 impl<'a> TransformContext<'a> {
-    fn new(dom: &'a ArenaDom, stylesheets: &'a [(&'a Stylesheet, Origin)]) -> Self {
+    fn new(
+        dom: &'a ArenaDom,
+        stylesheets: &'a [(&'a Stylesheet, Origin)],
+        asset_exists: Option<&'a dyn Fn(&str) -> bool>,
+    ) -> Self {
         let cascade_index = CascadeIndex::build(stylesheets);
         let use_bloom = cascade_index.has_complex_selectors();
         Self {
@@ -199,6 +230,7 @@ impl<'a> TransformContext<'a> {
             cascade_scratch: CascadeScratch::default(),
             bloom: BloomFilter::new(),
             use_bloom,
+            asset_exists,
             chapter: Chapter::new(),
         }
     }
@@ -214,7 +246,7 @@ impl<'a> TransformContext<'a> {
                 && let ArenaNodeData::Element { attrs, .. } = &node.data
             {
                 for attr in attrs {
-                    if attr.name.local.as_ref() == "lang" && !attr.value.is_empty() {
+                    if is_lang_attr(attr) && !attr.value.is_empty() {
                         return Some(attr.value.clone());
                     }
                 }
@@ -311,6 +343,65 @@ impl<'a> TransformContext<'a> {
         }
     }
 
+    /// Process an `<epub:switch>`.
+    ///
+    /// A MathML `<epub:case>` becomes the usual `Role::Math` node, and the
+    /// `<epub:default>` content (typically an image) is compiled as that
+    /// node's children: its fallback, which exporters use when they can't
+    /// render the equation and which lets EPUB export rebuild the switch.
+    /// Without a MathML case the default content stands in for the switch.
+    fn process_switch(
+        &mut self,
+        dom_id: ArenaNodeId,
+        ir_parent: NodeId,
+        style: &ComputedStyle,
+        depth: usize,
+    ) {
+        let dom = self.dom;
+        let is_math = |id: ArenaNodeId| {
+            dom.element_namespace(id)
+                .is_some_and(|ns| ns.as_ref() == crate::math::mathml::MATHML_NS)
+                || dom.element_name(id).is_some_and(|n| n.as_ref() == "math")
+        };
+        let is_epub = |id: ArenaNodeId, local: &str| {
+            dom.get(id).is_some_and(|node| match &node.data {
+                ArenaNodeData::Element { name, .. } => is_epub_element(name, local),
+                _ => false,
+            })
+        };
+        let math = dom
+            .children(dom_id)
+            .filter(|&case| is_epub(case, "case"))
+            .find_map(|case| dom.children(case).find(|&c| is_math(c)));
+        let default = dom.children(dom_id).find(|&c| is_epub(c, "default"));
+
+        let elem_ref = ElementRef::new(dom, dom_id);
+        if self.use_bloom {
+            elem_ref.each_bloom_hash(|hash| self.bloom.insert_hash(hash));
+        }
+        let mut fallback_parent = ir_parent;
+        if let Some(math) = math {
+            let before = self.chapter.node(ir_parent).and_then(|n| n.last_child);
+            self.process_node(math, ir_parent, Some(style), depth + 1);
+            let after = self.chapter.node(ir_parent).and_then(|n| n.last_child);
+            if let Some(math_node) = after
+                && after != before
+                && self
+                    .chapter
+                    .node(math_node)
+                    .is_some_and(|n| n.role == Role::Math)
+            {
+                fallback_parent = math_node;
+            }
+        }
+        if let Some(default) = default {
+            self.process_children(default, fallback_parent, Some(style), depth + 1);
+        }
+        if self.use_bloom {
+            elem_ref.each_bloom_hash(|hash| self.bloom.remove_hash(hash));
+        }
+    }
+
     /// Parse the `style` attribute of a DOM element, if present and non-empty.
     fn inline_style_of(&self, dom_id: ArenaNodeId) -> Option<crate::style::InlineStyle> {
         let node = self.dom.get(dom_id)?;
@@ -345,6 +436,262 @@ impl<'a> TransformContext<'a> {
         }
     }
 
+    /// The highest-resolution source for an `<img>`, considering its
+    /// `srcset` and, inside `<picture>`, the `<source srcset>` elements
+    /// before it. `None` when there is no `srcset` to choose from, leaving
+    /// the plain `src`.
+    ///
+    /// Media conditions and `sizes` are ignored: a book is rendered at one
+    /// size per device, and the largest image scales down cleanly. A
+    /// `<source>` whose `type` no exporter can ship (AVIF, JPEG XL) is
+    /// skipped, as is any candidate that isn't an asset of the book.
+    fn best_image_source(&self, img: ArenaNodeId) -> Option<&'a str> {
+        let dom = self.dom;
+        let mut srcsets: Vec<&str> = Vec::new();
+        if let Some(parent) = dom.get(img).map(|n| n.parent)
+            && dom
+                .element_name(parent)
+                .is_some_and(|n| n.as_ref() == "picture")
+        {
+            srcsets.extend(
+                dom.children(parent)
+                    .take_while(|&c| c != img)
+                    .filter(|&c| dom.element_name(c).is_some_and(|n| n.as_ref() == "source"))
+                    .filter(|&c| dom.get_attr(c, "type").is_none_or(is_exported_image_type))
+                    .filter_map(|c| dom.get_attr(c, "srcset")),
+            );
+        }
+        srcsets.extend(dom.get_attr(img, "srcset"));
+
+        let mut candidates: Vec<(&str, SrcsetDescriptor)> =
+            srcsets.into_iter().flat_map(parse_srcset).collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        // The plain `src` competes as a `1x` candidate.
+        candidates.extend(
+            dom.get_attr(img, "src")
+                .filter(|src| !src.is_empty())
+                .map(|src| (src, SrcsetDescriptor::Density(1.0))),
+        );
+        if let Some(asset_exists) = self.asset_exists {
+            candidates.retain(|(url, _)| asset_exists(url));
+        }
+
+        // Widths say more than densities; compare like with like, and let
+        // the earlier candidate win ties (`<source>`s come first).
+        let by_width = candidates
+            .iter()
+            .any(|(_, d)| matches!(d, SrcsetDescriptor::Width(_)));
+        let mut best: Option<(&str, f32)> = None;
+        for &(url, descriptor) in &candidates {
+            let score = match (descriptor, by_width) {
+                (SrcsetDescriptor::Width(w), true) => w,
+                (SrcsetDescriptor::Density(x), false) => x,
+                _ => continue,
+            };
+            if best.is_none_or(|(_, b)| score > b) {
+                best = Some((url, score));
+            }
+        }
+        best.map(|(url, _)| url)
+    }
+
+    /// The raster an inline `<svg>` wraps: the href (`href` or
+    /// `xlink:href`) of its first `<image>`, plus the SVG's own `<title>` as
+    /// a text alternative.
+    fn svg_image(&self, svg: ArenaNodeId) -> Option<(&'a str, Option<&'a str>)> {
+        let dom = self.dom;
+        let is =
+            |id: ArenaNodeId, tag: &str| dom.element_name(id).is_some_and(|n| n.as_ref() == tag);
+        let mut stack: Vec<ArenaNodeId> = dom.children(svg).collect();
+        stack.reverse();
+        let mut href = None;
+        while let Some(id) = stack.pop() {
+            if is(id, "image") {
+                href = dom
+                    .get_attr(id, "href")
+                    .filter(|href| !href.trim().is_empty());
+                if href.is_some() {
+                    break;
+                }
+            }
+            let mut children: Vec<ArenaNodeId> = dom.children(id).collect();
+            children.reverse();
+            stack.extend(children);
+        }
+        let title = dom
+            .children(svg)
+            .find(|&c| is(c, "title"))
+            .and_then(|title| dom.children(title).find_map(|c| dom.text_content(c)))
+            .map(str::trim)
+            .filter(|title| !title.is_empty());
+        Some((href?.trim(), title))
+    }
+
+    /// Emit an inline `<svg>` that wraps a raster as a `Role::Image` of it,
+    /// returning whether it did. Kept out of [`process_node`](Self::process_node)
+    /// so the recursive frame stays small.
+    #[inline(never)]
+    fn process_svg_image(
+        &mut self,
+        svg: ArenaNodeId,
+        ir_parent: NodeId,
+        computed: &ComputedStyle,
+    ) -> bool {
+        let Some((href, title)) = self.svg_image(svg) else {
+            return false;
+        };
+        if computed.display == Display::None {
+            self.process_hidden_anchors(svg, ir_parent);
+            return true;
+        }
+        let mut ir_node = Node::new(Role::Image);
+        ir_node.style = self.chapter.styles.intern_ref(computed);
+        let ir_id = self.chapter.alloc_node(ir_node);
+        self.chapter.append_child(ir_parent, ir_id);
+        self.chapter.semantics.set_src(ir_id, href);
+        let label = self
+            .dom
+            .get_attr(svg, "aria-label")
+            .map(str::trim)
+            .filter(|label| !label.is_empty());
+        if let Some(alt) = label.or(title) {
+            self.chapter.semantics.set_alt(ir_id, alt);
+        }
+        if let Some(id) = self.dom.element_id(svg) {
+            self.chapter.semantics.set_id(ir_id, id);
+        }
+        true
+    }
+
+    /// Keep the ids inside a hidden (`display: none`) subtree as empty
+    /// anchor nodes, so links into hidden content (footnotes shown only as
+    /// popups, say) still resolve. Each anchor is inline when the hidden
+    /// element is, so it doesn't split the surrounding text into blocks.
+    #[inline(never)]
+    fn process_hidden_anchors(&mut self, hidden: ArenaNodeId, ir_parent: NodeId) {
+        let role = if self.is_inline_level(hidden) {
+            Role::Inline
+        } else {
+            Role::Container
+        };
+        let mut stack = vec![hidden];
+        while let Some(id) = stack.pop() {
+            if let Some(anchor) = self.dom.element_id(id) {
+                let ir_id = self.chapter.alloc_node(Node::new(role));
+                self.chapter.append_child(ir_parent, ir_id);
+                self.chapter.semantics.set_id(ir_id, anchor);
+            }
+            let mut children: Vec<ArenaNodeId> = self.dom.children(id).collect();
+            children.reverse();
+            stack.extend(children);
+        }
+    }
+
+    /// Copy an element's semantic attributes onto its IR node.
+    ///
+    /// Kept out of `process_node` so the recursion's stack frame stays small.
+    fn store_semantic_attrs(
+        &mut self,
+        ir_id: NodeId,
+        name: &QualName,
+        attrs: &[crate::dom::arena::Attribute],
+    ) {
+        for attr in attrs {
+            let attr_name = attr.name.local.as_ref();
+            let attr_ns = attr.name.ns.as_ref();
+            match attr_name {
+                // Core layout attributes
+                "href" => {
+                    self.chapter.semantics.set_href(ir_id, &attr.value);
+                }
+                "src" => self.chapter.semantics.set_src(ir_id, &attr.value),
+                "alt" => self.chapter.semantics.set_alt(ir_id, &attr.value),
+                "id" => self.chapter.semantics.set_id(ir_id, &attr.value),
+                "title" => self.chapter.semantics.set_title(ir_id, &attr.value),
+                // Language (both lang and xml:lang)
+                "lang" | "xml:lang" => self.chapter.semantics.set_lang(ir_id, &attr.value),
+                // List start attribute (ol@start)
+                "start" if name.local.as_ref() == "ol" => {
+                    if let Ok(start) = attr.value.parse::<u32>() {
+                        self.chapter.semantics.set_list_start(ir_id, start);
+                    }
+                }
+                // List item ordinal (li@value)
+                "value" if name.local.as_ref() == "li" => {
+                    if let Ok(value) = attr.value.trim().parse::<u32>() {
+                        self.chapter.semantics.set_list_value(ir_id, value);
+                    }
+                }
+                // Semantic fidelity attributes
+                // epub:type attribute - handle both namespaced and prefixed forms
+                // html5ever parses "epub:type" as literal name with empty namespace
+                "type" if attr_ns == "http://www.idpf.org/2007/ops" => {
+                    self.chapter.semantics.set_epub_type(ir_id, &attr.value);
+                }
+                "epub:type" => {
+                    self.chapter.semantics.set_epub_type(ir_id, &attr.value);
+                }
+                "role" => {
+                    self.chapter.semantics.set_aria_role(ir_id, &attr.value);
+                }
+                "datetime" => {
+                    self.chapter.semantics.set_datetime(ir_id, &attr.value);
+                }
+                "cite" if matches!(name.local.as_ref(), "blockquote" | "q") => {
+                    self.chapter.semantics.set_cite(ir_id, &attr.value);
+                }
+                // Table cell attributes
+                "rowspan" if matches!(name.local.as_ref(), "td" | "th") => {
+                    if let Ok(span) = attr.value.parse::<u32>() {
+                        self.chapter.semantics.set_row_span(ir_id, span);
+                    }
+                }
+                "colspan" if matches!(name.local.as_ref(), "td" | "th") => {
+                    if let Ok(span) = attr.value.parse::<u32>() {
+                        self.chapter.semantics.set_col_span(ir_id, span);
+                    }
+                }
+                "scope" if name.local.as_ref() == "th" => {
+                    self.chapter.semantics.set_scope(ir_id, attr.value.trim());
+                }
+                "headers" if matches!(name.local.as_ref(), "td" | "th") => {
+                    self.chapter.semantics.set_headers(ir_id, attr.value.trim());
+                }
+                // Extract language from class for code elements
+                "class" if matches!(name.local.as_ref(), "code" | "pre") => {
+                    for class in attr.value.split_whitespace() {
+                        if let Some(lang) = class.strip_prefix("language-") {
+                            self.chapter.semantics.set_language(ir_id, lang);
+                            break;
+                        }
+                        if let Some(lang) = class.strip_prefix("lang-") {
+                            self.chapter.semantics.set_language(ir_id, lang);
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Whether `dom_id` is its parent's only content, apart from
+    /// whitespace-only text.
+    fn is_sole_content(&self, dom_id: ArenaNodeId) -> bool {
+        let Some(parent) = self.dom.get(dom_id).map(|n| n.parent) else {
+            return false;
+        };
+        self.dom.children(parent).all(|c| {
+            c == dom_id
+                || self
+                    .dom
+                    .text_content(c)
+                    .is_some_and(|t| t.trim().is_empty())
+        })
+    }
+
     /// Process a single DOM node.
     fn process_node(
         &mut self,
@@ -414,6 +761,16 @@ impl<'a> TransformContext<'a> {
                 // Normalize whitespace unless we're in a pre-like context.
                 // Both paths append straight into the chapter's text buffer,
                 // avoiding an intermediate per-text-node String.
+                // KFX and Markdown have no equivalent of the CJK transforms,
+                // so the mapped characters go into the text itself.
+                let mapped;
+                let text = match parent_style.map(|s| s.text_transform) {
+                    Some(transform @ (TextTransform::FullWidth | TextTransform::FullSizeKana)) => {
+                        mapped = apply_cjk_transform(text, transform);
+                        mapped.as_str()
+                    }
+                    _ => text,
+                };
                 let range = if preserve_whitespace {
                     self.chapter.append_text(text)
                 } else {
@@ -457,7 +814,7 @@ impl<'a> TransformContext<'a> {
                 // Merge lang attribute into style (for KFX language property)
                 // This must happen before interning so the style includes the language
                 for attr in attrs {
-                    if attr.name.local.as_ref() == "lang" && !attr.value.is_empty() {
+                    if is_lang_attr(attr) && !attr.value.is_empty() {
                         computed.language = Some(attr.value.to_string());
                         break;
                     }
@@ -472,6 +829,7 @@ impl<'a> TransformContext<'a> {
                     || name.local.as_ref() == "math"
                 {
                     if computed.display == Display::None {
+                        self.process_hidden_anchors(dom_id, ir_parent);
                         return;
                     }
                     let mut ir_node = Node::new(Role::Math);
@@ -490,24 +848,31 @@ impl<'a> TransformContext<'a> {
                     // equation even without display="block" (the common
                     // publisher shape — only ~2% of equations carry the
                     // attribute in practice).
-                    if !expr.display
-                        && let Some(parent) = self.dom.get(dom_id).map(|n| n.parent)
-                    {
-                        let alone = self.dom.children(parent).all(|c| {
-                            c == dom_id
-                                || self
-                                    .dom
-                                    .text_content(c)
-                                    .is_some_and(|t| t.trim().is_empty())
-                        });
-                        if alone {
-                            expr.display = true;
-                        }
+                    if !expr.display && self.is_sole_content(dom_id) {
+                        expr.display = true;
                     }
                     self.chapter.math.insert(ir_id, expr);
                     return;
                 }
 
+                // Inline SVG wrapping a raster — the usual EPUB cover page,
+                // `<svg><image xlink:href="cover.jpg"/></svg>` — is that
+                // image: the vector wrapper only scales it to the page.
+                if name.local.as_ref() == "svg"
+                    && self.process_svg_image(dom_id, ir_parent, &computed)
+                {
+                    return;
+                }
+
+                if is_epub_element(name, "switch") {
+                    if computed.display == Display::None {
+                        self.process_hidden_anchors(dom_id, ir_parent);
+                    } else {
+                        self.process_switch(dom_id, ir_parent, &computed, depth);
+                    }
+                    return;
+                }
+
                 // Map to role first (needed for Break check)
                 let role = element_to_role(&name.local);
 
@@ -515,9 +880,24 @@ impl<'a> TransformContext<'a> {
                 // CSS may hide <br> (e.g., in verse: "span + br { display: none }") but
                 // we still need them for line breaks in text/markdown export
                 if computed.display == Display::None && role != Role::Break {
+                    self.process_hidden_anchors(dom_id, ir_parent);
                     return;
                 }
 
+                // A centering container centers a block image, or one alone
+                // on its line, by auto margins too, so it stays centered
+                // where only block centering applies. An image inline with
+                // text or other images keeps zero margins.
+                if role == Role::Image
+                    && parent_style.is_some_and(|s| s.text_align == TextAlign::Center)
+                    && computed.margin_left == Length::Px(0.0)
+                    && computed.margin_right == Length::Px(0.0)
+                    && (computed.display.lays_out_as_block() || self.is_sole_content(dom_id))
+                {
+                    computed.margin_left = Length::Auto;
+                    computed.margin_right = Length::Auto;
+                }
+
                 // Create IR node
                 let mut ir_node = Node::new(role);
                 ir_node.style = self.chapter.styles.intern_ref(&computed);
@@ -525,67 +905,39 @@ impl<'a> TransformContext<'a> {
                 let ir_id = self.chapter.alloc_node(ir_node);
                 self.chapter.append_child(ir_parent, ir_id);
 
-                // Store semantic attributes
-                for attr in attrs {
-                    let attr_name = attr.name.local.as_ref();
-                    let attr_ns = attr.name.ns.as_ref();
-                    match attr_name {
-                        // Core layout attributes
-                        "href" => {
-                            self.chapter.semantics.set_href(ir_id, &attr.value);
-                        }
-                        "src" => self.chapter.semantics.set_src(ir_id, &attr.value),
-                        "alt" => self.chapter.semantics.set_alt(ir_id, &attr.value),
-                        "id" => self.chapter.semantics.set_id(ir_id, &attr.value),
-                        "title" => self.chapter.semantics.set_title(ir_id, &attr.value),
-                        // Language (both lang and xml:lang)
-                        "lang" => self.chapter.semantics.set_lang(ir_id, &attr.value),
-                        // List start attribute (ol@start)
-                        "start" if name.local.as_ref() == "ol" => {
-                            if let Ok(start) = attr.value.parse::<u32>() {
-                                self.chapter.semantics.set_list_start(ir_id, start);
-                            }
-                        }
-                        // Semantic fidelity attributes
-                        // epub:type attribute - handle both namespaced and prefixed forms
-                        // html5ever parses "epub:type" as literal name with empty namespace
-                        "type" if attr_ns == "http://www.idpf.org/2007/ops" => {
-                            self.chapter.semantics.set_epub_type(ir_id, &attr.value);
-                        }
-                        "epub:type" => {
-                            self.chapter.semantics.set_epub_type(ir_id, &attr.value);
-                        }
-                        "role" => {
-                            self.chapter.semantics.set_aria_role(ir_id, &attr.value);
-                        }
-                        "datetime" => {
-                            self.chapter.semantics.set_datetime(ir_id, &attr.value);
-                        }
-                        // Table cell attributes
-                        "rowspan" if matches!(name.local.as_ref(), "td" | "th") => {
-                            if let Ok(span) = attr.value.parse::<u32>() {
-                                self.chapter.semantics.set_row_span(ir_id, span);
-                            }
-                        }
-                        "colspan" if matches!(name.local.as_ref(), "td" | "th") => {
-                            if let Ok(span) = attr.value.parse::<u32>() {
-                                self.chapter.semantics.set_col_span(ir_id, span);
-                            }
-                        }
-                        // Extract language from class for code elements
-                        "class" if matches!(name.local.as_ref(), "code" | "pre") => {
-                            for class in attr.value.split_whitespace() {
-                                if let Some(lang) = class.strip_prefix("language-") {
-                                    self.chapter.semantics.set_language(ir_id, lang);
-                                    break;
-                                }
-                                if let Some(lang) = class.strip_prefix("lang-") {
-                                    self.chapter.semantics.set_language(ir_id, lang);
-                                    break;
-                                }
-                            }
-                        }
-                        _ => {}
+                self.store_semantic_attrs(ir_id, name, attrs);
+
+                // Responsive images: the best `srcset`/`<picture>` candidate
+                // replaces the fallback `src`.
+                if role == Role::Image
+                    && let Some(src) = self.best_image_source(dom_id)
+                {
+                    self.chapter.semantics.set_src(ir_id, src);
+                }
+
+                // Image text alternatives: `aria-label` stands in for a
+                // missing or empty `alt`; an image left with `alt=""` (or
+                // marked presentational) is decorative.
+                if role == Role::Image {
+                    let attr_value = |wanted: &str| {
+                        attrs
+                            .iter()
+                            .find(|attr| attr.name.local.as_ref() == wanted)
+                            .map(|attr| attr.value.trim())
+                    };
+                    let label = attr_value("aria-label").filter(|label| !label.is_empty());
+                    if self.chapter.semantics.alt(ir_id).is_none()
+                        && let Some(label) = label
+                    {
+                        self.chapter.semantics.set_alt(ir_id, label);
+                    }
+                    let presentational = attr_value("role").is_some_and(|role| {
+                        role.eq_ignore_ascii_case("presentation")
+                            || role.eq_ignore_ascii_case("none")
+                    });
+                    let empty_alt = attr_value("alt") == Some("") && label.is_none();
+                    if presentational || empty_alt {
+                        self.chapter.semantics.set_decorative(ir_id, true);
                     }
                 }
 
@@ -614,9 +966,130 @@ impl<'a> TransformContext<'a> {
     }
 }
 
-/// Transform an ArenaDom to Chapter.
-pub fn transform(dom: &ArenaDom, stylesheets: &[(&Stylesheet, Origin)]) -> Chapter {
-    let ctx = TransformContext::new(dom, stylesheets);
+/// A `srcset` candidate's descriptor: `800w` or `2x` (`1x` when absent).
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SrcsetDescriptor {
+    Width(f32),
+    Density(f32),
+}
+
+/// Parse a `srcset` attribute into `(url, descriptor)` candidates.
+///
+/// Candidates are comma-separated; a URL runs to the next whitespace, so
+/// commas inside URLs survive, but a trailing comma ends the candidate.
+/// Unparseable descriptors drop the candidate.
+fn parse_srcset(srcset: &str) -> Vec<(&str, SrcsetDescriptor)> {
+    let mut candidates = Vec::new();
+    let mut rest = srcset;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == ',');
+        if rest.is_empty() {
+            break;
+        }
+        let url_end = rest
+            .find(|c: char| c.is_ascii_whitespace())
+            .unwrap_or(rest.len());
+        let (url, after) = rest.split_at(url_end);
+        let trimmed = url.trim_end_matches(',');
+        let descriptors = if trimmed.len() < url.len() {
+            rest = after;
+            ""
+        } else {
+            let end = after.find(',').unwrap_or(after.len());
+            rest = &after[end..];
+            after[..end].trim()
+        };
+
+        let descriptor = match descriptors.split_ascii_whitespace().next() {
+            None => Some(SrcsetDescriptor::Density(1.0)),
+            Some(d) => {
+                let (value, unit) = d.split_at(d.len().saturating_sub(1));
+                match (unit, value.parse::<f32>()) {
+                    ("w", Ok(w)) if w > 0.0 => Some(SrcsetDescriptor::Width(w)),
+                    ("x", Ok(x)) if x > 0.0 => Some(SrcsetDescriptor::Density(x)),
+                    _ => None,
+                }
+            }
+        };
+        if let Some(descriptor) = descriptor
+            && !trimmed.is_empty()
+        {
+            candidates.push((trimmed, descriptor));
+        }
+    }
+    candidates
+}
+
+/// Whether `name` is the EPUB structural element `epub:<local>`. html5ever
+/// keeps the prefix in the local name; xml5ever resolves the namespace.
+fn is_epub_element(name: &QualName, local: &str) -> bool {
+    if name.ns.as_ref() == "http://www.idpf.org/2007/ops" {
+        name.local.as_ref() == local
+    } else {
+        name.local
+            .as_ref()
+            .strip_prefix("epub:")
+            .is_some_and(|l| l == local)
+    }
+}
+
+/// Whether `attr` is `lang` or `xml:lang`. html5ever keeps the `xml:`
+/// prefix in the local name; xml5ever resolves it to the XML namespace.
+fn is_lang_attr(attr: &crate::dom::arena::Attribute) -> bool {
+    matches!(attr.name.local.as_ref(), "lang" | "xml:lang")
+}
+
+/// Apply `text-transform: full-width` or `full-size-kana` to `text`.
+/// Spaces stay ASCII so lines can still break (and whitespace collapses)
+/// between words.
+fn apply_cjk_transform(text: &str, transform: TextTransform) -> String {
+    text.chars()
+        .map(|c| match transform {
+            TextTransform::FullWidth => match c {
+                '!'..='~' => char::from_u32(c as u32 + 0xFEE0).unwrap_or(c),
+                _ => c,
+            },
+            TextTransform::FullSizeKana => full_size_kana(c),
+            _ => c,
+        })
+        .collect()
+}
+
+/// The full-size form of a small kana, per the CSS `full-size-kana` table.
+fn full_size_kana(c: char) -> char {
+    const SMALL: &str =
+        "ぁぃぅぇぉっゃゅょゎゕゖァィゥェォッャュョヮヵヶㇰㇱㇲㇳㇴㇵㇶㇷㇸㇹㇺㇻㇼㇽㇾㇿｧｨｩｪｫｯｬｭｮ";
+    const FULL: &str =
+        "あいうえおつやゆよわかけアイウエオツヤユヨワカケクシストヌハヒフヘホムラリルレロｱｲｳｴｵﾂﾔﾕﾖ";
+    SMALL
+        .chars()
+        .zip(FULL.chars())
+        .find_map(|(small, full)| (small == c).then_some(full))
+        .unwrap_or(c)
+}
+
+/// Whether a `<source type>` names an image format the exporters ship.
+fn is_exported_image_type(media_type: &str) -> bool {
+    let essence = media_type.split(';').next().unwrap_or_default().trim();
+    [
+        "image/jpeg",
+        "image/png",
+        "image/gif",
+        "image/webp",
+        "image/svg+xml",
+    ]
+    .iter()
+    .any(|t| essence.eq_ignore_ascii_case(t))
+}
+
+/// Transform an ArenaDom to Chapter. Responsive image sources are chosen
+/// only among URLs `asset_exists` accepts, when given.
+pub fn transform(
+    dom: &ArenaDom,
+    stylesheets: &[(&Stylesheet, Origin)],
+    asset_exists: Option<&dyn Fn(&str) -> bool>,
+) -> Chapter {
+    let ctx = TransformContext::new(dom, stylesheets, asset_exists);
     ctx.transform()
 }
 
@@ -643,7 +1116,7 @@ mod tests {
         let ua = user_agent_stylesheet();
         let stylesheets = vec![(&ua, Origin::UserAgent)];
 
-        let chapter = transform(&dom, &stylesheets);
+        let chapter = transform(&dom, &stylesheets, None);
 
         // Should have root + paragraph (Text) + text content
         assert!(chapter.node_count() >= 3);
@@ -661,13 +1134,53 @@ mod tests {
         assert!(found_text);
     }
 
+    #[test]
+    fn epub_switch_keeps_one_branch() {
+        let ua = user_agent_stylesheet();
+        let stylesheets = vec![(&ua, Origin::UserAgent)];
+        let roles = |html: &str| {
+            let chapter = transform(&parse_html(html), &stylesheets, None);
+            chapter
+                .iter_dfs()
+                .filter_map(|id| chapter.node(id))
+                .filter(|n| matches!(n.role, Role::Math | Role::Image))
+                .map(|n| {
+                    (
+                        n.role,
+                        n.parent.and_then(|p| chapter.node(p)).map(|p| p.role),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // MathML case: the default image hangs off the math node.
+        assert_eq!(
+            roles(
+                "<body><epub:switch><epub:case required-namespace=\"x\"><math><mi>x</mi></math>\
+                 </epub:case><epub:default><img src=\"x.png\"/></epub:default></epub:switch></body>"
+            ),
+            vec![
+                (Role::Math, Some(Role::Root)),
+                (Role::Image, Some(Role::Math))
+            ]
+        );
+        // No MathML case: only the default content, in place of the switch.
+        assert_eq!(
+            roles(
+                "<body><epub:switch><epub:case required-namespace=\"y\"><chem/></epub:case>\
+                 <epub:default><img src=\"y.png\"/></epub:default></epub:switch></body>"
+            ),
+            vec![(Role::Image, Some(Role::Root))]
+        );
+    }
+
     #[test]
     fn test_heading_levels() {
         let dom = parse_html("<html><body><h1>Title</h1><h2>Subtitle</h2></body></html>");
         let ua = user_agent_stylesheet();
         let stylesheets = vec![(&ua, Origin::UserAgent)];
 
-        let chapter = transform(&dom, &stylesheets);
+        let chapter = transform(&dom, &stylesheets, None);
 
         let mut h1_count = 0;
         let mut h2_count = 0;
@@ -688,7 +1201,7 @@ mod tests {
         let ua = user_agent_stylesheet();
         let stylesheets = vec![(&ua, Origin::UserAgent)];
 
-        let chapter = transform(&dom, &stylesheets);
+        let chapter = transform(&dom, &stylesheets, None);
 
         // Find link node
         for id in chapter.iter_dfs() {
@@ -700,6 +1213,150 @@ mod tests {
         panic!("Link not found");
     }
 
+    #[test]
+    fn picture_selects_largest_source() {
+        let ua = user_agent_stylesheet();
+        let stylesheets = vec![(&ua, Origin::UserAgent)];
+        let images = |html: &str| {
+            let chapter = transform(&parse_html(html), &stylesheets, None);
+            chapter
+                .iter_dfs()
+                .filter(|&id| chapter.node(id).unwrap().role == Role::Image)
+                .map(|id| chapter.semantics.src(id).unwrap_or("").to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            images(
+                r#"<p><picture>
+                     <source media="(max-width: 600px)" srcset="small.jpg 600w"/>
+                     <source srcset="large.jpg 1600w"/>
+                     <img src="fallback.jpg" alt=""/>
+                   </picture></p>"#
+            ),
+            ["large.jpg"]
+        );
+        assert_eq!(
+            images(r#"<img src="a.png" srcset="a@2x.png 2x, a@3x.png 3x"/>"#),
+            ["a@3x.png"]
+        );
+        assert_eq!(
+            images(r#"<img src="big.png" srcset="small.png 0.5x"/>"#),
+            ["big.png"]
+        );
+        assert_eq!(images(r#"<img src="plain.png"/>"#), ["plain.png"]);
+    }
+
+    #[test]
+    fn picture_skips_unexported_types_and_missing_assets() {
+        let ua = user_agent_stylesheet();
+        let stylesheets = vec![(&ua, Origin::UserAgent)];
+        let in_book = |url: &str| url != "missing.jpg";
+        let images = |html: &str| {
+            let chapter = transform(&parse_html(html), &stylesheets, Some(&in_book));
+            chapter
+                .iter_dfs()
+                .filter(|&id| chapter.node(id).unwrap().role == Role::Image)
+                .map(|id| chapter.semantics.src(id).unwrap_or("").to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            images(
+                r#"<picture>
+                     <source type="image/avif" srcset="photo.avif 2000w"/>
+                     <source type="image/jpeg" srcset="photo-large.jpg 1600w"/>
+                     <img src="photo.jpg" alt=""/>
+                   </picture>"#
+            ),
+            ["photo-large.jpg"]
+        );
+        assert_eq!(
+            images(
+                r#"<picture>
+                     <source type="image/avif" srcset="photo.avif"/>
+                     <img src="photo.jpg" alt=""/>
+                   </picture>"#
+            ),
+            ["photo.jpg"]
+        );
+        assert_eq!(
+            images(r#"<img src="a.png" srcset="missing.jpg 3x, a@2x.png 2x"/>"#),
+            ["a@2x.png"]
+        );
+    }
+
+    #[test]
+    fn srcset_candidates_parse() {
+        assert_eq!(
+            parse_srcset("a.jpg 480w, b,c.jpg 2x,d.jpg,, e.jpg 10q"),
+            [
+                ("a.jpg", SrcsetDescriptor::Width(480.0)),
+                ("b,c.jpg", SrcsetDescriptor::Density(2.0)),
+                ("d.jpg", SrcsetDescriptor::Density(1.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn list_type_attribute_sets_list_style_type() {
+        use crate::style::ListStyleType;
+
+        let ua = user_agent_stylesheet();
+        let item_types = |html: &str, author: &str| {
+            let author = Stylesheet::parse(author);
+            let stylesheets = vec![(&ua, Origin::UserAgent), (&author, Origin::Author)];
+            let chapter = transform(&parse_html(html), &stylesheets, None);
+            chapter
+                .iter_dfs()
+                .filter(|&id| chapter.node(id).unwrap().role == Role::ListItem)
+                .map(|id| {
+                    let node = chapter.node(id).unwrap();
+                    chapter.styles.get(node.style).unwrap().list_style_type
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            item_types(r#"<ol type="i"><li>a</li><li type="A">b</li></ol>"#, ""),
+            [ListStyleType::LowerRoman, ListStyleType::UpperAlpha]
+        );
+        assert_eq!(
+            item_types(r#"<ul type="SQUARE"><li>a</li></ul>"#, ""),
+            [ListStyleType::Square]
+        );
+        // Author CSS overrides the attribute.
+        assert_eq!(
+            item_types(
+                r#"<ol type="I"><li>a</li></ol>"#,
+                "ol { list-style-type: lower-alpha; }"
+            ),
+            [ListStyleType::LowerAlpha]
+        );
+    }
+
+    #[test]
+    fn font_face_with_digit_prefixed_name_survives() {
+        let ua = user_agent_stylesheet();
+        let stylesheets = vec![(&ua, Origin::UserAgent)];
+        let dom = parse_html(r#"<p><font face="001 Cover Sans, serif">x</font></p>"#);
+        let chapter = transform(&dom, &stylesheets, None);
+        let families: Vec<_> = chapter
+            .iter_dfs()
+            .filter_map(|id| {
+                chapter
+                    .styles
+                    .get(chapter.node(id)?.style)?
+                    .font_family
+                    .clone()
+            })
+            .collect();
+        assert!(
+            families.iter().any(|f| f == "001 Cover Sans, serif"),
+            "{families:?}"
+        );
+    }
+
     #[test]
     fn test_style_inheritance() {
         let dom = parse_html(
@@ -711,7 +1368,7 @@ mod tests {
         let author = Stylesheet::parse("div { color: red; }");
         let stylesheets = vec![(&ua, Origin::UserAgent), (&author, Origin::Author)];
 
-        let chapter = transform(&dom, &stylesheets);
+        let chapter = transform(&dom, &stylesheets, None);
 
         // The paragraph should inherit the red color from div
         // (This is implicit in the cascade since we pass parent_style)
@@ -726,7 +1383,7 @@ mod tests {
         let ua = user_agent_stylesheet();
         let stylesheets = vec![(&ua, Origin::UserAgent)];
 
-        let chapter = transform(&dom, &stylesheets);
+        let chapter = transform(&dom, &stylesheets, None);
 
         // Should not contain title element (display: none)
         for id in chapter.iter_dfs() {
@@ -738,13 +1395,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn hidden_elements_keep_their_ids_as_anchors() {
+        let dom = parse_html(
+            r#"<html><body><p>Text<span style="display:none" id="a">gone</span></p>
+            <aside style="display:none" id="fn1"><p id="fn1-p">Note</p></aside></body></html>"#,
+        );
+        let ua = user_agent_stylesheet();
+        let stylesheets = vec![(&ua, Origin::UserAgent)];
+
+        let chapter = transform(&dom, &stylesheets, None);
+
+        let anchor = |id: &str| {
+            chapter
+                .iter_dfs()
+                .find(|&n| chapter.semantics.id(n) == Some(id))
+                .map(|n| chapter.node(n).unwrap().role)
+        };
+        assert_eq!(anchor("a"), Some(Role::Inline));
+        assert_eq!(anchor("fn1"), Some(Role::Container));
+        assert_eq!(anchor("fn1-p"), Some(Role::Container));
+        assert!(!chapter.text_buffer().contains("gone"));
+        assert!(!chapter.text_buffer().contains("Note"));
+    }
+
     #[test]
     fn test_br_element() {
         let dom = parse_html(r#"<html><body><p>Line one<br/>Line two</p></body></html>"#);
         let ua = user_agent_stylesheet();
         let stylesheets = vec![(&ua, Origin::UserAgent)];
 
-        let chapter = transform(&dom, &stylesheets);
+        let chapter = transform(&dom, &stylesheets, None);
 
         // Should have a Break node
         let mut found_break = false;
@@ -768,7 +1449,7 @@ mod tests {
         let ua = user_agent_stylesheet();
         let stylesheets = vec![(&ua, Origin::UserAgent)];
 
-        let chapter = transform(&dom, &stylesheets);
+        let chapter = transform(&dom, &stylesheets, None);
 
         // Should have a Break node
         let mut found_break = false;
@@ -799,7 +1480,7 @@ mod tests {
         let ua = user_agent_stylesheet();
         let stylesheets = vec![(&ua, Origin::UserAgent)];
 
-        let chapter = transform(&dom, &stylesheets);
+        let chapter = transform(&dom, &stylesheets, None);
 
         // Should have a Break node
         let mut found_break = false;
@@ -811,4 +1492,16 @@ mod tests {
         }
         assert!(found_break, "Break node not found in blockquote verse");
     }
+
+    #[test]
+    fn cjk_text_transforms_map_characters() {
+        assert_eq!(
+            apply_cjk_transform("AB 12!", TextTransform::FullWidth),
+            "ＡＢ １２！"
+        );
+        assert_eq!(
+            apply_cjk_transform("きゃっト ｧ", TextTransform::FullSizeKana),
+            "きやつト ｱ"
+        );
+    }
 }
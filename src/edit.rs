@@ -0,0 +1,493 @@
+//! Programmatic resource editing on [`Book`].
+//!
+//! [`Book::resources`] and [`Book::resource`] read assets as [`Resource`]s;
+//! [`Book::remove_resource`] drops one; [`Book::add_image`] and
+//! [`Book::set_cover`] add images. Backends are read-only views of the
+//! source file, so each edit wraps the backend in an overlay (hiding a
+//! removed asset and any spine chapter stored at that path, or serving the
+//! added one), the same approach [`Book::prune_unused_assets`] uses.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::Book;
+use crate::dom::Stylesheet;
+use crate::import::{ChapterId, Importer, SpineEntry};
+use crate::model::{
+    AnchorTarget, Chapter, FontFace, GlobalNodeId, Landmark, Metadata, Resource, TocEntry,
+};
+use crate::optimize::EmptyBackend;
+
+impl Book {
+    /// Iterate over every asset as a [`Resource`], in
+    /// [`list_assets`](Self::list_assets) order. Assets are read lazily, one
+    /// per step, so a failed read surfaces as that item's error.
+    pub fn resources(&self) -> impl Iterator<Item = (&str, crate::Result<Resource>)> + '_ {
+        self.list_assets()
+            .iter()
+            .map(|path| (path.as_str(), self.read_resource(path)))
+    }
+
+    /// Read one asset as a [`Resource`].
+    ///
+    /// Fails with [`Error::NotFound`](crate::Error::NotFound) for a path that
+    /// isn't an asset.
+    pub fn resource(&self, path: &str) -> crate::Result<Resource> {
+        if !self.list_assets().iter().any(|asset| asset == path) {
+            return Err(crate::Error::NotFound {
+                what: format!("asset {path}"),
+            });
+        }
+        self.read_resource(path)
+    }
+
+    /// Remove an asset from the book, returning its contents. A spine
+    /// chapter stored at the same path leaves the reading order with it.
+    ///
+    /// Fails with [`Error::NotFound`](crate::Error::NotFound) for a path that
+    /// isn't an asset, and with [`Error::InUse`](crate::Error::InUse) while
+    /// the cover, a remaining chapter (a `src`/`href`, a stylesheet `<link>`
+    /// or an inline CSS `url()`) or a stylesheet's `url()` still points at
+    /// it; the book is unchanged in both cases.
+    pub fn remove_resource(&mut self, path: &str) -> crate::Result<Resource> {
+        let resource = self.resource(path)?;
+        if self.metadata().cover_image.as_deref() == Some(path) {
+            return Err(crate::Error::InUse {
+                what: format!("{path} is the cover image"),
+            });
+        }
+
+        let (dropped, kept): (Vec<SpineEntry>, Vec<SpineEntry>) = self
+            .spine()
+            .iter()
+            .cloned()
+            .partition(|entry| self.source_id(entry.id) == Some(path));
+        for entry in &kept {
+            if chapter_references(self, entry.id, path)? {
+                let from = self.source_id(entry.id).unwrap_or("a chapter");
+                return Err(crate::Error::InUse {
+                    what: format!("{path} is referenced by {from}"),
+                });
+            }
+        }
+        for sheet in self.list_assets() {
+            if sheet == path || self.declared_media_type(sheet) != "text/css" {
+                continue;
+            }
+            if crate::util::refers_to(&self.load_asset(sheet)?, sheet, path) {
+                return Err(crate::Error::InUse {
+                    what: format!("{path} is referenced by {sheet}"),
+                });
+            }
+        }
+
+        let backend = self.replace_backend(Box::new(RemovedImporter::placeholder()));
+        let assets = backend
+            .list_assets()
+            .iter()
+            .filter(|asset| *asset != path)
+            .cloned()
+            .collect();
+        let dropped = dropped.into_iter().map(|entry| entry.id).collect();
+        self.replace_backend(Box::new(RemovedImporter {
+            inner: backend,
+            assets,
+            spine: kept,
+            dropped,
+        }));
+        self.clear_media_type_override(path);
+        Ok(resource)
+    }
+
+    /// Add an image asset at `path`. The media type is sniffed from the
+    /// bytes (JPEG, PNG, GIF, WebP or SVG), so a mislabeled extension
+    /// doesn't leak into exported manifests.
+    ///
+    /// Fails with [`Error::UnsupportedFormat`](crate::Error::UnsupportedFormat)
+    /// when `data` isn't a recognized image, and with
+    /// [`Error::InUse`](crate::Error::InUse) when `path` is already an asset;
+    /// the book is unchanged in both cases.
+    pub fn add_image(&mut self, path: &str, data: Vec<u8>) -> crate::Result<()> {
+        let media_type = sniff_image(path, &data)?;
+        if self.list_assets().iter().any(|asset| asset == path) {
+            return Err(crate::Error::InUse {
+                what: format!("{path} is already an asset"),
+            });
+        }
+        self.add_asset(path, Resource { data, media_type }, |_| {});
+        Ok(())
+    }
+
+    /// Replace the cover image. `name` is the image's file name (e.g.
+    /// `cover.png`); it is stored beside the current cover, or at the book
+    /// root when there is none, with its media type sniffed from the bytes.
+    /// Chapters showing the old cover (a cover page) are pointed at the new
+    /// one; the old image stays an asset until
+    /// [`prune_unused_assets`](Self::prune_unused_assets).
+    ///
+    /// Fails with [`Error::UnsupportedFormat`](crate::Error::UnsupportedFormat)
+    /// when `data` isn't an image; the book is unchanged.
+    pub fn set_cover(&mut self, name: &str, data: Vec<u8>) -> crate::Result<()> {
+        let media_type = sniff_image(name, &data)?;
+
+        let old = self.metadata().cover_image.clone();
+        let dir = old
+            .as_deref()
+            .and_then(|cover| cover.rfind('/').map(|i| &cover[..=i]))
+            .unwrap_or("");
+        let file = name.rsplit(['/', '\\']).next().unwrap_or(name);
+        let mut path = format!("{dir}{file}");
+        let mut n = 1;
+        while old.as_deref() != Some(path.as_str()) && self.list_assets().contains(&path) {
+            path = format!("{dir}{n}-{file}");
+            n += 1;
+        }
+
+        let replaced = old.filter(|old| *old != path);
+        self.add_asset(&path, Resource { data, media_type }, |overlay| {
+            overlay.metadata.cover_image = Some(overlay.path.clone());
+            overlay.replaced = replaced;
+        });
+        Ok(())
+    }
+
+    /// Serve `resource` at `path` through an [`AddedImporter`], letting
+    /// `configure` adjust the overlay before it is installed.
+    fn add_asset(
+        &mut self,
+        path: &str,
+        resource: Resource,
+        configure: impl FnOnce(&mut AddedImporter),
+    ) {
+        let backend = self.replace_backend(Box::new(RemovedImporter::placeholder()));
+        let mut assets = backend.list_assets().to_vec();
+        if !assets.iter().any(|asset| asset == path) {
+            assets.push(path.to_string());
+        }
+        let mut overlay = AddedImporter {
+            metadata: backend.metadata().clone(),
+            inner: backend,
+            assets,
+            replaced: None,
+            path: path.to_string(),
+            media_type: resource.media_type,
+            data: resource.data,
+        };
+        configure(&mut overlay);
+        self.replace_backend(Box::new(overlay));
+        // Exported manifests type assets by extension first; the sniffed
+        // type must win over a mislabeled one.
+        self.set_media_type_override(path, resource.media_type);
+    }
+
+    fn read_resource(&self, path: &str) -> crate::Result<Resource> {
+        Ok(Resource {
+            data: self.load_asset(path)?,
+            media_type: crate::util::intern_media_type(self.asset_type(path)),
+        })
+    }
+}
+
+/// The sniffed media type of an image, or `UnsupportedFormat` naming `name`.
+fn sniff_image(name: &str, data: &[u8]) -> crate::Result<&'static str> {
+    crate::util::sniff_image_mime(data).ok_or_else(|| crate::Error::UnsupportedFormat {
+        detail: format!("{name} is not a recognized image"),
+    })
+}
+
+/// Whether chapter `id` of `book` embeds or links to `path`: through a
+/// node's `src`/`href`, or, when its raw markup is exported as is, through
+/// references the IR drops (stylesheet `<link>`s, CSS `url()`s).
+fn chapter_references(book: &Book, id: ChapterId, path: &str) -> crate::Result<bool> {
+    if references(&*book.load_chapter_cached(id)?, path) {
+        return Ok(true);
+    }
+    if book.requires_normalized_export() {
+        return Ok(false);
+    }
+    let base = book.source_id(id).unwrap_or_default();
+    Ok(crate::util::refers_to(&book.load_raw(id)?, base, path))
+}
+
+/// Whether any node in `chapter` embeds or links to `path`.
+fn references(chapter: &Chapter, path: &str) -> bool {
+    chapter.iter_dfs().any(|id| {
+        chapter.semantics.src(id) == Some(path)
+            || chapter
+                .semantics
+                .href(id)
+                .is_some_and(|href| href.split('#').next() == Some(path))
+    })
+}
+
+/// Importer overlay hiding removed assets and the spine chapters stored at
+/// their paths. Everything else is served by the inner backend.
+struct RemovedImporter {
+    inner: Box<dyn Importer>,
+    /// Inner asset list minus the removed paths, same order.
+    assets: Vec<String>,
+    /// Inner spine minus the removed chapters, same order.
+    spine: Vec<SpineEntry>,
+    /// Chapters no longer in the spine.
+    dropped: HashSet<ChapterId>,
+}
+
+impl RemovedImporter {
+    /// An empty overlay, held by the book only while the real one is built.
+    fn placeholder() -> Self {
+        Self {
+            inner: Box::new(EmptyBackend(Metadata::default())),
+            assets: Vec::new(),
+            spine: Vec::new(),
+            dropped: HashSet::new(),
+        }
+    }
+
+    fn is_removed(&self, path: &str) -> bool {
+        !self.assets.iter().any(|asset| asset == path)
+            && self.inner.list_assets().iter().any(|asset| asset == path)
+    }
+
+    fn check_chapter(&self, id: ChapterId) -> crate::Result<()> {
+        if self.dropped.contains(&id) {
+            return Err(crate::Error::NotFound {
+                what: format!("chapter {} (removed)", id.0),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Importer for RemovedImporter {
+    fn open(_path: &Path) -> crate::Result<Self>
+    where
+        Self: Sized,
+    {
+        Err(crate::Error::UnsupportedFormat {
+            detail: "RemovedImporter wraps an existing backend".to_string(),
+        })
+    }
+
+    fn metadata(&self) -> &Metadata {
+        self.inner.metadata()
+    }
+
+    fn toc(&self) -> &[TocEntry] {
+        self.inner.toc()
+    }
+
+    fn landmarks(&self) -> &[Landmark] {
+        self.inner.landmarks()
+    }
+
+    fn spine(&self) -> &[SpineEntry] {
+        &self.spine
+    }
+
+    fn load_chapter(&self, id: ChapterId) -> crate::Result<Chapter> {
+        self.check_chapter(id)?;
+        self.inner.load_chapter(id)
+    }
+
+    fn load_chapters(&self, ids: &[ChapterId]) -> Vec<crate::Result<Chapter>> {
+        ids.iter().map(|&id| self.load_chapter(id)).collect()
+    }
+
+    fn source_id(&self, id: ChapterId) -> Option<&str> {
+        self.inner.source_id(id)
+    }
+
+    fn load_raw(&self, id: ChapterId) -> crate::Result<Vec<u8>> {
+        self.check_chapter(id)?;
+        self.inner.load_raw(id)
+    }
+
+    fn list_assets(&self) -> &[String] {
+        &self.assets
+    }
+
+    fn load_asset(&self, path: &str) -> crate::Result<Vec<u8>> {
+        if self.is_removed(path) {
+            return Err(crate::Error::NotFound {
+                what: format!("asset {path} (removed)"),
+            });
+        }
+        self.inner.load_asset(path)
+    }
+
+    fn contains_asset(&self, path: &str) -> bool {
+        crate::import::overlay_contains_asset(&self.assets, self.inner.as_ref(), path)
+    }
+
+    fn asset_media_type(&self, path: &str) -> Option<&str> {
+        self.inner.asset_media_type(path)
+    }
+
+    fn load_stylesheet(&self, path: &str) -> Option<Arc<Stylesheet>> {
+        if self.is_removed(path) {
+            return None;
+        }
+        self.inner.load_stylesheet(path)
+    }
+
+    fn font_faces(&self) -> Vec<FontFace> {
+        self.inner.font_faces()
+    }
+
+    fn requires_normalized_export(&self) -> bool {
+        self.inner.requires_normalized_export()
+    }
+
+    fn index_anchor_ids(&self, ids: &[(GlobalNodeId, &str)]) {
+        self.inner.index_anchor_ids(ids)
+    }
+
+    fn resolve_toc(&self) -> Option<Vec<TocEntry>> {
+        self.inner.resolve_toc()
+    }
+
+    fn resolve_href(&self, from_chapter: ChapterId, href: &str) -> Option<AnchorTarget> {
+        self.inner.resolve_href(from_chapter, href)
+    }
+}
+
+/// Importer overlay serving one added asset (a new image or cover). When it
+/// replaces the cover, chapter references to the old cover (in the IR and
+/// in raw markup) are rewritten to the new path; everything else is served
+/// by the inner backend.
+struct AddedImporter {
+    inner: Box<dyn Importer>,
+    /// Inner asset list, plus the added path when it is new.
+    assets: Vec<String>,
+    /// Inner metadata, with the cover path set when the asset is the cover.
+    metadata: Metadata,
+    /// The previous cover, when it lived at a different path.
+    replaced: Option<String>,
+    /// Path the added asset is served at.
+    path: String,
+    data: Vec<u8>,
+    media_type: &'static str,
+}
+
+impl AddedImporter {
+    fn rewrite_chapter(&self, mut chapter: Chapter) -> Chapter {
+        let Some(old) = &self.replaced else {
+            return chapter;
+        };
+        let nodes: Vec<_> = chapter
+            .iter_dfs()
+            .filter(|&id| chapter.semantics.src(id) == Some(old.as_str()))
+            .collect();
+        for id in nodes {
+            chapter.semantics.set_src(id, &self.path);
+        }
+        chapter
+    }
+}
+
+impl Importer for AddedImporter {
+    fn open(_path: &Path) -> crate::Result<Self>
+    where
+        Self: Sized,
+    {
+        Err(crate::Error::UnsupportedFormat {
+            detail: "AddedImporter wraps an existing backend".to_string(),
+        })
+    }
+
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn toc(&self) -> &[TocEntry] {
+        self.inner.toc()
+    }
+
+    fn landmarks(&self) -> &[Landmark] {
+        self.inner.landmarks()
+    }
+
+    fn spine(&self) -> &[SpineEntry] {
+        self.inner.spine()
+    }
+
+    fn load_chapter(&self, id: ChapterId) -> crate::Result<Chapter> {
+        self.inner
+            .load_chapter(id)
+            .map(|ch| self.rewrite_chapter(ch))
+    }
+
+    fn load_chapters(&self, ids: &[ChapterId]) -> Vec<crate::Result<Chapter>> {
+        self.inner
+            .load_chapters(ids)
+            .into_iter()
+            .map(|res| res.map(|ch| self.rewrite_chapter(ch)))
+            .collect()
+    }
+
+    fn source_id(&self, id: ChapterId) -> Option<&str> {
+        self.inner.source_id(id)
+    }
+
+    fn load_raw(&self, id: ChapterId) -> crate::Result<Vec<u8>> {
+        let raw = self.inner.load_raw(id)?;
+        let (Some(old), Some(base)) = (&self.replaced, self.inner.source_id(id)) else {
+            return Ok(raw);
+        };
+        if self.inner.requires_normalized_export() {
+            return Ok(raw);
+        }
+        // The new cover sits in the old one's directory, so a cover page
+        // only needs the file name in its reference changed.
+        Ok(crate::util::rewrite_moved_references(raw, base, |path| {
+            (path == old).then(|| self.path.clone())
+        }))
+    }
+
+    fn list_assets(&self) -> &[String] {
+        &self.assets
+    }
+
+    fn load_asset(&self, path: &str) -> crate::Result<Vec<u8>> {
+        if path == self.path {
+            return Ok(self.data.clone());
+        }
+        self.inner.load_asset(path)
+    }
+
+    fn contains_asset(&self, path: &str) -> bool {
+        crate::import::overlay_contains_asset(&self.assets, self.inner.as_ref(), path)
+    }
+
+    fn asset_media_type(&self, path: &str) -> Option<&str> {
+        if path == self.path {
+            return Some(self.media_type);
+        }
+        self.inner.asset_media_type(path)
+    }
+
+    fn load_stylesheet(&self, path: &str) -> Option<Arc<Stylesheet>> {
+        self.inner.load_stylesheet(path)
+    }
+
+    fn font_faces(&self) -> Vec<FontFace> {
+        self.inner.font_faces()
+    }
+
+    fn requires_normalized_export(&self) -> bool {
+        self.inner.requires_normalized_export()
+    }
+
+    fn index_anchor_ids(&self, ids: &[(GlobalNodeId, &str)]) {
+        self.inner.index_anchor_ids(ids)
+    }
+
+    fn resolve_toc(&self) -> Option<Vec<TocEntry>> {
+        self.inner.resolve_toc()
+    }
+
+    fn resolve_href(&self, from_chapter: ChapterId, href: &str) -> Option<AnchorTarget> {
+        self.inner.resolve_href(from_chapter, href)
+    }
+}
@@ -3,3 +3,4 @@
 mod parser;
 
 pub use parser::{parse_container_xml, parse_nav_landmarks, parse_nav_toc, parse_ncx, parse_opf};
+pub(crate) use parser::{parse_cover_page_image, parse_viewport, resolve_entity};
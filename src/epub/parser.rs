@@ -6,7 +6,9 @@ use std::io;
 use quick_xml::Reader;
 use quick_xml::events::{BytesStart, Event};
 
-use crate::model::{CollectionInfo, Contributor, Landmark, LandmarkType, Metadata, TocEntry};
+use crate::model::{
+    CollectionInfo, Contributor, Identifier, Landmark, LandmarkType, Metadata, TocEntry,
+};
 
 /// Parsed OPF package data.
 pub struct OpfData {
@@ -58,6 +60,8 @@ enum MetaElement {
     Creator(String),
     Contributor(String),
     Collection,
+    /// Index into `Metadata::identifiers`.
+    Identifier(usize),
 }
 
 /// A refinement from an EPUB3 meta element.
@@ -126,6 +130,16 @@ struct OpfParser {
     /// Local name of the DC metadata element currently being read.
     current_element: Option<String>,
     current_element_id: Option<String>,
+    /// `opf:scheme` of the DC element being read (identifiers).
+    current_element_scheme: Option<String>,
+    /// `opf:event` of the DC element being read (dates), lowercased.
+    current_element_event: Option<String>,
+    /// `Metadata::date` came from a `dc:date` whose event is neither
+    /// publication nor unspecified; a publication date replaces it.
+    date_from_other_event: bool,
+    /// `unique-identifier` of the package: the id of the `dc:identifier`
+    /// that becomes `Metadata::identifier`.
+    unique_identifier: Option<String>,
     buf_text: String,
 
     /// For meta elements with text content (non-empty tags).
@@ -151,12 +165,19 @@ impl OpfParser {
         match local {
             b"metadata" => self.in_metadata = true,
             b"title" | b"creator" | b"language" | b"identifier" | b"publisher" | b"description"
-            | b"subject" | b"date" | b"rights" | b"contributor"
+            | b"subject" | b"date" | b"rights" | b"contributor" | b"coverage" | b"source"
+            | b"relation" | b"type" | b"format"
                 if self.in_metadata =>
             {
                 self.start_dc_element(local, e)?;
             }
             b"meta" if self.in_metadata => self.start_meta(e)?,
+            b"package" => {
+                if let Some(prefixes) = attr(e, b"prefix")? {
+                    self.metadata.custom_prefixes = parse_prefix_attr(&prefixes);
+                }
+                self.unique_identifier = attr(e, b"unique-identifier")?;
+            }
             // <item ...></item> is XML-equivalent to <item .../>; an OPF
             // authored with explicit close tags must not lose its entire
             // manifest and spine.
@@ -216,6 +237,18 @@ impl OpfParser {
         self.current_element = Some(String::from_utf8_lossy(local).to_string());
         self.buf_text.clear();
         self.current_element_id = attr(e, b"id")?;
+        // `opf:scheme` and `opf:event`, however the OPF namespace is
+        // prefixed.
+        let opf_attr = |key: &[u8]| {
+            e.attributes()
+                .flatten()
+                .find(|a| local_name(a.key.as_ref()) == key)
+                .and_then(|a| a.unescape_value().ok())
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        self.current_element_scheme = opf_attr(b"scheme");
+        self.current_element_event = opf_attr(b"event").map(|v| v.to_ascii_lowercase());
         Ok(())
     }
 
@@ -228,21 +261,87 @@ impl OpfParser {
         self.meta_id = attr(e, b"id")?;
         self.meta_content = attr(e, b"content")?;
 
-        // EPUB2 <meta name="cover" content="..."> may be written with an
+        // EPUB2 <meta name="..." content="..."> may be written with an
         // explicit close tag; its data lives entirely in attributes.
-        let is_cover = e
-            .attributes()
-            .flatten()
-            .any(|a| a.key.as_ref() == b"name" && &*a.value == b"cover");
-        if is_cover
-            && let Some(ref cover_id) = self.meta_content
-            && !cover_id.is_empty()
+        if let Some(name) = attr(e, b"name")?
+            && let Some(content) = self.meta_content.clone()
         {
-            self.epub2_cover_id = Some(cover_id.clone());
+            self.handle_meta_name(&name, content);
         }
         Ok(())
     }
 
+    /// Handle an EPUB2-style `<meta name="..." content="..."/>`: the cover
+    /// pointer and Calibre's `calibre:*` metadata.
+    fn handle_meta_name(&mut self, name: &str, content: String) {
+        let metadata = &mut self.metadata;
+        match name {
+            "cover" if !content.is_empty() => self.epub2_cover_id = Some(content),
+            // Calibre's date-added; a real dcterms:modified wins.
+            "calibre:timestamp" if metadata.modified_date.is_none() => {
+                metadata.modified_date = Some(content);
+            }
+            "calibre:rating" => {
+                metadata.rating = content
+                    .trim()
+                    .parse()
+                    .ok()
+                    .filter(|rating: &f32| rating.is_finite());
+            }
+            "calibre:title_sort" if metadata.title_sort.is_none() => {
+                metadata.title_sort = Some(content);
+            }
+            // EPUB 3 belongs-to-collection wins over Calibre's series.
+            "calibre:series" if metadata.collection.is_none() => {
+                metadata.collection = Some(CollectionInfo {
+                    name: content,
+                    collection_type: Some("series".to_string()),
+                    position: None,
+                });
+            }
+            "calibre:series_index" => {
+                if let Some(collection) = metadata.collection.as_mut()
+                    && collection.position.is_none()
+                {
+                    collection.position = content.trim().parse().ok();
+                }
+            }
+            // Modeled above but already set from the EPUB 3 equivalent.
+            "calibre:timestamp" | "calibre:title_sort" | "calibre:series" => {}
+            // Kindle's pre-EPUB 3 fixed-layout declarations.
+            "fixed-layout" if content.trim() == "true" => {
+                metadata
+                    .rendition
+                    .layout
+                    .get_or_insert("pre-paginated".to_string());
+            }
+            "original-resolution" if metadata.rendition.viewport.is_none() => {
+                metadata.rendition.viewport = content
+                    .split_once('x')
+                    .and_then(|(w, h)| Some((w.trim().parse().ok()?, h.trim().parse().ok()?)));
+            }
+            // Kindle's `none` (no lock) is EPUB 3's `auto`.
+            "orientation-lock" if metadata.rendition.orientation.is_none() => {
+                match content.trim() {
+                    "none" => set_rendition_property(metadata, "orientation", "auto"),
+                    lock @ ("portrait" | "landscape") => {
+                        set_rendition_property(metadata, "orientation", lock)
+                    }
+                    _ => {}
+                }
+            }
+            _ if name.starts_with("calibre:") => metadata.custom.push((name.to_string(), content)),
+            // Accessibility metadata, EPUB 2 style.
+            _ if name
+                .strip_prefix("schema:")
+                .is_some_and(is_accessibility_property) =>
+            {
+                push_accessibility_property(metadata, &name["schema:".len()..], &content);
+            }
+            _ => {}
+        }
+    }
+
     /// Parse a `<manifest>` `<item>` entry.
     fn parse_manifest_item(&mut self, e: &BytesStart) -> io::Result<()> {
         let id = attr(e, b"id")?.unwrap_or_default();
@@ -274,20 +373,16 @@ impl OpfParser {
     /// Parse a self-closing `<meta/>`: EPUB2 style (name/content) and EPUB3
     /// empty meta with a content attribute.
     fn parse_empty_meta(&mut self, e: &BytesStart) -> io::Result<()> {
-        let is_cover = e
-            .attributes()
-            .flatten()
-            .any(|a| a.key.as_ref() == b"name" && &*a.value == b"cover");
+        let name = attr(e, b"name")?;
         let content = attr(e, b"content")?;
         let property = attr(e, b"property")?;
         let refines = attr(e, b"refines")?;
         let elem_id = attr(e, b"id")?;
 
-        if is_cover
-            && let Some(ref cover_id) = content
-            && !cover_id.is_empty()
+        if let Some(ref name) = name
+            && let Some(ref content) = content
         {
-            self.epub2_cover_id = Some(cover_id.clone());
+            self.handle_meta_name(name, content.clone());
         }
 
         if let Some(ref prop) = property {
@@ -392,18 +487,64 @@ impl OpfParser {
                 });
             }
             "language" => self.metadata.language = text,
-            "identifier" if self.metadata.identifier.is_empty() => {
-                self.metadata.identifier = text;
+            "identifier" => {
+                let scheme = self.current_element_scheme.take();
+                if text.is_empty() {
+                    return;
+                }
+                let is_unique = elem_id.is_some() && elem_id == self.unique_identifier;
+                if self.metadata.identifier.is_empty() || is_unique {
+                    self.metadata.identifier = text.clone();
+                }
+                if let Some(id) = elem_id {
+                    self.element_ids
+                        .insert(id, MetaElement::Identifier(self.metadata.identifiers.len()));
+                }
+                self.metadata.identifiers.push(Identifier {
+                    scheme,
+                    value: text,
+                });
             }
             "publisher" => self.metadata.publisher = Some(text),
             "description" => self.metadata.description = Some(text),
             "subject" => self.metadata.subjects.push(text),
-            "date" => self.metadata.date = Some(text),
+            "date" => self.end_date(text),
             "rights" => self.metadata.rights = Some(text),
+            // Dublin Core elements boko doesn't model round-trip verbatim.
+            _ if !text.is_empty() => self.metadata.custom.push((format!("dc:{elem}"), text)),
             _ => {}
         }
     }
 
+    /// Route a `dc:date` by its `opf:event`: modification dates go to
+    /// `modified_date` (unless `dcterms:modified` or an earlier one set it),
+    /// the first publication or unqualified date to `date`. Other events
+    /// (`creation`, ...) fill `date` only until a publication date appears.
+    fn end_date(&mut self, text: String) {
+        if text.is_empty() {
+            return;
+        }
+        match self.current_element_event.take().as_deref() {
+            Some("modification") => {
+                if self.metadata.modified_date.is_none() {
+                    self.metadata.modified_date = Some(text);
+                }
+            }
+            None | Some("publication") => {
+                if self.metadata.date.is_none() || self.date_from_other_event {
+                    self.metadata.date = Some(text);
+                    self.date_from_other_event = false;
+                }
+            }
+            Some(_) => {
+                if self.metadata.date.is_none() {
+                    self.metadata.date = Some(text);
+                    self.date_from_other_event = true;
+                }
+            }
+        }
+    }
+
     /// Apply refinements, resolve cover/nav/NCX references and produce OpfData.
     fn finish(mut self) -> OpfData {
         // Apply refinements to their target elements
@@ -472,6 +613,12 @@ fn handle_meta_property(
         "modified" => {
             metadata.modified_date = Some(value.to_string());
         }
+        "layout" | "orientation" | "spread" if property.starts_with("rendition:") => {
+            set_rendition_property(metadata, prop_local, value);
+        }
+        _ if property.starts_with("schema:") && is_accessibility_property(prop_local) => {
+            push_accessibility_property(metadata, prop_local, value);
+        }
         "belongs-to-collection" => {
             // Initialize collection if not present
             if metadata.collection.is_none() {
@@ -488,8 +635,141 @@ fn handle_meta_property(
                 element_ids.insert(id.to_string(), MetaElement::Collection);
             }
         }
-        _ => {}
+        _ => metadata
+            .custom
+            .push((property.to_string(), value.to_string())),
+    }
+}
+
+/// Record a `rendition:layout`/`orientation`/`spread` value; the first
+/// declaration wins.
+fn set_rendition_property(metadata: &mut Metadata, property: &str, value: &str) {
+    let rendition = &mut metadata.rendition;
+    let slot = match property {
+        "layout" => &mut rendition.layout,
+        "orientation" => &mut rendition.orientation,
+        _ => &mut rendition.spread,
+    };
+    let value = value.trim();
+    if slot.is_none() && !value.is_empty() {
+        *slot = Some(value.to_string());
+    }
+}
+
+/// Whether a `schema:` property is one [`Accessibility`](crate::model::Accessibility)
+/// models.
+fn is_accessibility_property(property: &str) -> bool {
+    matches!(
+        property,
+        "accessibilityFeature"
+            | "accessMode"
+            | "accessModeSufficient"
+            | "accessibilityHazard"
+            | "accessibilitySummary"
+    )
+}
+
+/// Record a `schema:` accessibility property; list properties accumulate
+/// and the first summary wins.
+fn push_accessibility_property(metadata: &mut Metadata, property: &str, value: &str) {
+    let accessibility = &mut metadata.accessibility;
+    let value = value.trim();
+    if value.is_empty() {
+        return;
+    }
+    let list = match property {
+        "accessibilityFeature" => &mut accessibility.features,
+        "accessMode" => &mut accessibility.access_modes,
+        "accessModeSufficient" => &mut accessibility.access_modes_sufficient,
+        "accessibilityHazard" => &mut accessibility.hazards,
+        _ => {
+            accessibility
+                .summary
+                .get_or_insert_with(|| value.to_string());
+            return;
+        }
+    };
+    list.push(value.to_string());
+}
+
+/// Page size from an XHTML document's `<meta name="viewport"
+/// content="width=..., height=...">`, as used by fixed-layout EPUBs.
+pub(crate) fn parse_viewport(html: &str) -> Option<(u32, u32)> {
+    let mut reader = Reader::from_str(html);
+    loop {
+        match reader.read_event().ok()? {
+            Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                b"meta" => {
+                    let name = attr(&e, b"name").ok().flatten();
+                    if !name.is_some_and(|name| name.eq_ignore_ascii_case("viewport")) {
+                        continue;
+                    }
+                    let content = attr(&e, b"content").ok()??;
+                    let mut width = None;
+                    let mut height = None;
+                    for pair in content.split([',', ';']) {
+                        let Some((key, value)) = pair.split_once('=') else {
+                            continue;
+                        };
+                        let value = value.trim().trim_end_matches("px").parse().ok();
+                        match key.trim() {
+                            "width" => width = value,
+                            "height" => height = value,
+                            _ => {}
+                        }
+                    }
+                    return Some((width?, height?));
+                }
+                b"body" => return None,
+                _ => {}
+            },
+            Event::Eof => return None,
+            _ => {}
+        }
+    }
+}
+
+/// The image a cover page shows: the first `<img src>`, or `<image>`
+/// `href`/`xlink:href` inside an inline `<svg>` (the common EPUB cover
+/// markup), as written in the document.
+pub(crate) fn parse_cover_page_image(html: &str) -> Option<String> {
+    let mut reader = Reader::from_str(html);
+    loop {
+        match reader.read_event().ok()? {
+            Event::Start(e) | Event::Empty(e) => {
+                let href = match e.local_name().as_ref() {
+                    b"img" => attr(&e, b"src").ok().flatten(),
+                    b"image" => e
+                        .attributes()
+                        .flatten()
+                        .find(|a| a.key.local_name().as_ref() == b"href")
+                        .and_then(|a| a.unescape_value().ok())
+                        .map(|v| v.into_owned()),
+                    _ => continue,
+                };
+                if let Some(href) = href.filter(|href| !href.trim().is_empty()) {
+                    return Some(href.trim().to_string());
+                }
+            }
+            Event::Eof => return None,
+            _ => {}
+        }
+    }
+}
+
+/// Parse a package `prefix` attribute (`"foo: http://... bar: http://..."`)
+/// into `(prefix, IRI)` pairs.
+fn parse_prefix_attr(value: &str) -> Vec<(String, String)> {
+    let mut prefixes = Vec::new();
+    let mut tokens = value.split_ascii_whitespace();
+    while let Some(token) = tokens.next() {
+        if let Some(prefix) = token.strip_suffix(':')
+            && let Some(iri) = tokens.next()
+        {
+            prefixes.push((prefix.to_string(), iri.to_string()));
+        }
     }
+    prefixes
 }
 
 /// Apply collected refinements to their target elements.
@@ -533,6 +813,13 @@ fn apply_refinements(
                         }
                     }
                 }
+                MetaElement::Identifier(index) => {
+                    if prop_local == "identifier-type"
+                        && let Some(identifier) = metadata.identifiers.get_mut(*index)
+                    {
+                        identifier.scheme = Some(refinement.value.clone());
+                    }
+                }
                 MetaElement::Collection => {
                     if let Some(ref mut coll) = metadata.collection {
                         match prop_local {
@@ -799,12 +1086,10 @@ pub fn parse_nav_toc(content: &str) -> io::Result<Vec<TocEntry>> {
                 let local = local_name(name.as_ref());
 
                 match local {
-                    b"a" | b"span" => {
-                        if in_label {
-                            label_depth = label_depth.saturating_sub(1);
-                            if label_depth == 0 {
-                                in_label = false;
-                            }
+                    b"a" | b"span" if in_label => {
+                        label_depth = label_depth.saturating_sub(1);
+                        if label_depth == 0 {
+                            in_label = false;
                         }
                     }
                     b"li" if in_toc_nav => {
@@ -1022,7 +1307,7 @@ fn attr(e: &BytesStart, key: &[u8]) -> io::Result<Option<String>> {
 /// Unknown entities resolve to `None` and are dropped by callers — the same
 /// lenient, best-effort stance the surrounding parsers take toward malformed
 /// attributes and invalid UTF-8.
-fn resolve_entity(entity: &str) -> Option<String> {
+pub(crate) fn resolve_entity(entity: &str) -> Option<String> {
     if let Some(named) = resolve_named_entity(entity) {
         return Some(named.to_string());
     }
@@ -1137,6 +1422,26 @@ mod tests {
         assert_eq!(data.metadata.cover_image.as_deref(), Some("cover.jpg"));
     }
 
+    #[test]
+    fn parse_viewport_reads_width_and_height() {
+        let html = r#"<html><head><meta charset="utf-8"/><title>P</title>
+<meta name="viewport" content="width=1072px; height=1448"/></head>
+<body><meta name="viewport" content="width=1, height=1"/></body></html>"#;
+        assert_eq!(parse_viewport(html), Some((1072, 1448)));
+        assert_eq!(
+            parse_viewport(
+                r#"<html><head><meta name="viewport" content="width=device-width"/></head></html>"#
+            ),
+            None
+        );
+        assert_eq!(
+            parse_viewport(
+                "<html><head></head><body><meta name=\"viewport\" content=\"width=1, height=1\"/></body></html>"
+            ),
+            None
+        );
+    }
+
     #[test]
     fn parse_opf_reads_page_progression_direction() {
         let opf = r#"<package xmlns="http://www.idpf.org/2007/opf">
@@ -1151,6 +1456,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_opf_reads_calibre_metas() {
+        let opf = r#"<package xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>T</dc:title>
+    <meta name="calibre:timestamp" content="2019-03-02T10:00:00+00:00"/>
+    <meta name="calibre:rating" content="8"/>
+    <meta name="calibre:series" content="Discworld"/>
+    <meta name="calibre:series_index" content="3"/>
+    <meta name="calibre:author_link_map" content="{}"></meta>
+    <meta name="generator" content="Sigil"/>
+  </metadata>
+  <manifest/><spine/>
+</package>"#;
+        let metadata = parse_opf(opf).unwrap().metadata;
+        assert_eq!(metadata.rating, Some(8.0));
+        assert_eq!(
+            metadata.modified_date.as_deref(),
+            Some("2019-03-02T10:00:00+00:00")
+        );
+        let series = metadata.collection.unwrap();
+        assert_eq!(series.name, "Discworld");
+        assert_eq!(series.position, Some(3.0));
+        assert_eq!(
+            metadata.custom,
+            vec![("calibre:author_link_map".to_string(), "{}".to_string())]
+        );
+    }
+
+    #[test]
+    fn parse_opf_rejects_non_finite_calibre_rating() {
+        for rating in ["NaN", "inf", "-infinity"] {
+            let opf = format!(
+                r#"<package xmlns="http://www.idpf.org/2007/opf">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>T</dc:title>
+    <meta name="calibre:rating" content="{rating}"/>
+  </metadata>
+  <manifest/><spine/>
+</package>"#
+            );
+            assert_eq!(parse_opf(&opf).unwrap().metadata.rating, None, "{rating}");
+        }
+    }
+
     #[test]
     fn parse_opf_keeps_text_after_nested_markup() {
         // Nested inline markup inside a DC element must not commit the value
@@ -1812,6 +2162,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_opf_date_events() {
+        let opf = r#"<?xml version="1.0"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="2.0">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:opf="http://www.idpf.org/2007/opf">
+    <dc:title>Test Book</dc:title>
+    <dc:date opf:event="creation">2019-03-01</dc:date>
+    <dc:date opf:event="publication">2020-05-17</dc:date>
+    <dc:date opf:event="Modification">2024-01-15</dc:date>
+    <dc:date>2021-01-01</dc:date>
+  </metadata>
+  <manifest>
+    <item id="ch1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="ch1"/></spine>
+</package>"#;
+
+        let result = parse_opf(opf).unwrap();
+        assert_eq!(result.metadata.date.as_deref(), Some("2020-05-17"));
+        assert_eq!(result.metadata.modified_date.as_deref(), Some("2024-01-15"));
+    }
+
     #[test]
     fn test_parse_opf_contributor_with_role() {
         let opf = r##"<?xml version="1.0"?>
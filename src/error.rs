@@ -1,7 +1,8 @@
 //! Typed error handling for boko's importers, exporters, and [`Book`] API.
 //!
 //! [`Error`] lets callers programmatically distinguish failure classes
-//! (unsupported format, malformed input, DRM protection, missing resources)
+//! (unsupported format, malformed input, DRM protection, missing or
+//! still-referenced resources)
 //! instead of string-matching on `io::Error`. Internal code that produces
 //! plain I/O errors keeps working via `From<std::io::Error>` and `?`.
 //!
@@ -34,12 +35,25 @@ pub enum Error {
     /// The input is DRM-protected / encrypted; boko does not decrypt.
     #[error("{0:?} file is DRM-protected; boko does not decrypt")]
     DrmProtected(Format),
+    /// The archive's entries are password-encrypted (ZIP encryption, not
+    /// DRM) and no password was given; see `Book::open_with_password`.
+    #[error("{0:?} archive is password-protected; open it with a password")]
+    PasswordRequired(Format),
+    /// The given password doesn't decrypt the archive.
+    #[error("wrong password for {0:?} archive")]
+    WrongPassword(Format),
     /// A referenced chapter, asset, or resource does not exist.
     #[error("not found: {what}")]
     NotFound {
         /// The missing chapter, asset, or resource.
         what: String,
     },
+    /// An edit was refused because the book still references the item.
+    #[error("still in use: {what}")]
+    InUse {
+        /// The referenced item and what references it.
+        what: String,
+    },
 }
 
 /// Convenience alias used throughout boko's public API.
@@ -52,10 +66,13 @@ impl From<Error> for std::io::Error {
         match e {
             Error::Io(io) => io,
             Error::NotFound { .. } => std::io::Error::new(std::io::ErrorKind::NotFound, e),
+            Error::InUse { .. } => std::io::Error::new(std::io::ErrorKind::InvalidInput, e),
             Error::UnsupportedFormat { .. } => {
                 std::io::Error::new(std::io::ErrorKind::Unsupported, e)
             }
-            Error::DrmProtected(_) => std::io::Error::new(std::io::ErrorKind::PermissionDenied, e),
+            Error::DrmProtected(_) | Error::PasswordRequired(_) | Error::WrongPassword(_) => {
+                std::io::Error::new(std::io::ErrorKind::PermissionDenied, e)
+            }
             Error::Malformed { .. } => std::io::Error::new(std::io::ErrorKind::InvalidData, e),
         }
     }
@@ -90,6 +107,12 @@ mod tests {
         let err: std::io::Error = Error::DrmProtected(Format::Azw3).into();
         assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
 
+        let err: std::io::Error = Error::InUse {
+            what: "cover.jpg".into(),
+        }
+        .into();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
         let err: std::io::Error = Error::Malformed {
             format: Format::Kfx,
             context: "bad".into(),
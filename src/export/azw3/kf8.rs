@@ -38,8 +38,12 @@ pub(super) struct Kf8Builder {
 }
 
 impl Kf8Builder {
-    pub(super) fn new(book: &Book, normalize: bool) -> crate::Result<Self> {
-        let ctx = BookContext::from_book(book, normalize)?;
+    pub(super) fn new(
+        book: &Book,
+        normalize: bool,
+        progress: &mut Progress<'_>,
+    ) -> crate::Result<Self> {
+        let ctx = BookContext::from_book(book, normalize, progress)?;
 
         let mut builder = Self {
             ctx,
@@ -19,7 +19,7 @@ use crate::mobi::writer_transform::{
 use crate::model::{Book, Resource, TocEntry};
 use crate::util::guess_media_type;
 
-use super::Exporter;
+use super::{Exporter, Progress, ProgressEvent};
 
 // Constants
 const RECORD_SIZE: usize = 4096;
@@ -69,11 +69,21 @@ impl Default for Azw3Exporter {
 
 impl Exporter for Azw3Exporter {
     fn export<W: Write + Seek>(&self, book: &Book, writer: &mut W) -> crate::Result<()> {
+        self.export_with_progress(book, writer, &mut |_| {})
+    }
+
+    fn export_with_progress<W: Write + Seek>(
+        &self,
+        book: &Book,
+        writer: &mut W,
+        progress: &mut dyn FnMut(ProgressEvent),
+    ) -> crate::Result<()> {
         // Normalize when explicitly requested OR when the source format requires
         // it (e.g. KFX raw content is binary Ion, not HTML) — otherwise the
         // builder would chunk and compress that binary as if it were XHTML.
         let normalize = self.config.normalize || book.requires_normalized_export();
-        let builder = Kf8Builder::new(book, normalize)?;
+        let mut progress = Progress::new(progress, book.spine().len());
+        let builder = Kf8Builder::new(book, normalize, &mut progress)?;
         Ok(builder.write(writer)?)
     }
 }
@@ -107,16 +117,16 @@ struct SpineItem {
 
 impl BookContext {
     /// Collect all data from a Book into internal structures.
-    fn from_book(book: &Book, normalize: bool) -> crate::Result<Self> {
+    fn from_book(book: &Book, normalize: bool, progress: &mut Progress<'_>) -> crate::Result<Self> {
         if normalize {
-            Self::from_normalized(book)
+            Self::from_normalized(book, progress)
         } else {
-            Self::from_raw(book)
+            Self::from_raw(book, progress)
         }
     }
 
     /// Collect raw (passthrough) content from the book.
-    fn from_raw(book: &Book) -> crate::Result<Self> {
+    fn from_raw(book: &Book, progress: &mut Progress<'_>) -> crate::Result<Self> {
         // Resolve TOC fragments before snapshotting: MOBI/AZW3 importers
         // leave entries with bare chapter hrefs until this populates the
         // `#fileposN` / `#id` suffixes. Without it every intra-chapter NCX
@@ -133,7 +143,8 @@ impl BookContext {
         let mut spine = Vec::with_capacity(spine_entries.len());
         let mut resources = HashMap::new();
 
-        for entry in spine_entries {
+        for (idx, entry) in spine_entries.iter().enumerate() {
+            progress.chapter_started(idx);
             let href = book
                 .source_id(entry.id)
                 .unwrap_or("unknown.xhtml")
@@ -150,6 +161,7 @@ impl BookContext {
             };
             resources.insert(href.clone(), Resource { data, media_type });
             spine.push(SpineItem { href });
+            progress.chapter_finished(idx);
         }
 
         // Collect assets, skipping spine documents already loaded above.
@@ -162,6 +174,7 @@ impl BookContext {
             let media_type = guess_media_type(path);
 
             resources.insert(path.clone(), Resource { data, media_type });
+            progress.asset_written(path);
         }
 
         Ok(Self {
@@ -174,14 +187,14 @@ impl BookContext {
     }
 
     /// Collect normalized content from the book through IR pipeline.
-    fn from_normalized(book: &Book) -> crate::Result<Self> {
+    fn from_normalized(book: &Book, progress: &mut Progress<'_>) -> crate::Result<Self> {
         use super::html_synth::MathForm;
-        use super::normalize::normalize_book_math;
+        use super::normalize::normalize_book_named;
 
         book.resolve_toc();
         // KF8 renderers cannot display MathML (it stacks one token per
         // line); serialize math as its Unicode linearization instead.
-        let normalized = normalize_book_math(book, MathForm::Text)?;
+        let normalized = normalize_book_named(book, MathForm::Text, None, progress)?;
 
         // Collect metadata and TOC. The TOC (and landmarks below) must be
         // rewritten onto the emitted `chapter_{i}.xhtml` names: the chunker's
@@ -215,6 +228,7 @@ impl BookContext {
 
         // Build spine from normalized chapters; bytes stored once in `resources`.
         let mut spine = Vec::with_capacity(normalized.chapters.len());
+        // (Chapters were reported to `progress` as they were synthesized.)
         for (i, chapter) in normalized.chapters.iter().enumerate() {
             let href = format!("chapter_{}.xhtml", i);
             resources.insert(
@@ -232,6 +246,7 @@ impl BookContext {
             if let Ok(data) = book.load_asset(asset_path) {
                 let media_type = guess_media_type(asset_path);
                 resources.insert(asset_path.clone(), Resource { data, media_type });
+                progress.asset_written(asset_path);
             }
         }
 
@@ -60,6 +60,8 @@ impl CssArtifact {
 /// 1. Deduplicates the provided style IDs
 /// 2. Generates a unique CSS class for each unique style (e.g., `.c1`, `.c2`)
 /// 3. Only outputs properties that differ from defaults
+/// 4. Shares one class between styles whose CSS is identical (they differ
+///    only in fields CSS doesn't carry, such as language)
 ///
 /// # Arguments
 ///
@@ -74,6 +76,10 @@ pub fn generate_css(pool: &StylePool, used_styles: &[StyleId]) -> CssArtifact {
     let mut class_map = HashMap::new();
     let mut class_list = vec![None; pool.len()];
 
+    // Rule body -> class name of the first style that produced it.
+    let mut rule_classes: HashMap<String, String> = HashMap::new();
+    let mut declarations = String::new();
+
     // Deduplicate and sort for deterministic output
     let unique_styles: HashSet<StyleId> = used_styles.iter().copied().collect();
     let mut sorted_styles: Vec<StyleId> = unique_styles.into_iter().collect();
@@ -89,12 +95,21 @@ pub fn generate_css(pool: &StylePool, used_styles: &[StyleId]) -> CssArtifact {
             continue;
         }
 
-        let class_name = format!("c{}", id.0);
+        declarations.clear();
+        style.to_css(&mut declarations);
+        if declarations.is_empty() {
+            continue;
+        }
 
-        // Generate CSS rule
-        write!(stylesheet, ".{} {{ ", class_name).unwrap();
-        style.to_css(&mut stylesheet);
-        stylesheet.push_str("}\n");
+        let class_name = match rule_classes.get(&declarations) {
+            Some(shared) => shared.clone(),
+            None => {
+                let class_name = format!("c{}", id.0);
+                writeln!(stylesheet, ".{} {{ {}}}", class_name, declarations).unwrap();
+                rule_classes.insert(declarations.clone(), class_name.clone());
+                class_name
+            }
+        };
 
         class_list[id.0 as usize] = Some(class_name.clone());
         class_map.insert(id, class_name);
@@ -195,6 +210,42 @@ mod tests {
         assert_eq!(rule_count, 1);
     }
 
+    #[test]
+    fn test_generate_css_shares_class_for_identical_rules() {
+        let mut pool = StylePool::new();
+
+        let bold = ComputedStyle {
+            font_weight: FontWeight::BOLD,
+            ..Default::default()
+        };
+        let bold_id = pool.intern(bold.clone());
+        // Language isn't written to CSS, so this renders the same rule.
+        let bold_fr_id = pool.intern(ComputedStyle {
+            language: Some("fr".to_string()),
+            ..bold
+        });
+        let lang_only_id = pool.intern(ComputedStyle {
+            language: Some("de".to_string()),
+            ..Default::default()
+        });
+        assert_ne!(bold_id, bold_fr_id);
+
+        let artifact = generate_css(&pool, &[bold_id, bold_fr_id, lang_only_id]);
+
+        assert_eq!(artifact.stylesheet.matches("font-weight: bold").count(), 1);
+        assert_eq!(
+            artifact.class_name(bold_id),
+            artifact.class_name(bold_fr_id)
+        );
+        assert_eq!(
+            artifact.class_name_fast(bold_fr_id),
+            artifact.class_name(bold_id)
+        );
+        // A style with no CSS gets no class and no empty rule.
+        assert_eq!(artifact.class_name(lang_only_id), None);
+        assert_eq!(artifact.stylesheet.lines().count(), 1);
+    }
+
     #[test]
     fn test_generate_css_all() {
         let mut pool = StylePool::new();
@@ -2,18 +2,20 @@
 //!
 //! Creates EPUB 2/3 files from Book structures using passthrough for content.
 
+use std::collections::{HashMap, HashSet};
 use std::io::{self, Seek, Write};
 
 use zip::CompressionMethod;
 use zip::ZipWriter;
 use zip::write::SimpleFileOptions;
 
-use crate::model::{Book, TocEntry};
-use crate::util::guess_media_type;
+use crate::model::{Book, Landmark, LandmarkType, TocEntry};
+use crate::util::{guess_media_type, normalized_media_type};
 
 use super::html_synth::escape_xml;
+use super::normalize::{NormalizedContent, missing_cover_page};
 
-use super::Exporter;
+use super::{Exporter, Progress, ProgressEvent};
 
 /// Configuration for EPUB export.
 #[derive(Debug, Clone, Default)]
@@ -23,6 +25,147 @@ pub struct EpubConfig {
     /// If true, normalize content through IR pipeline for clean, consistent output.
     /// Default is false (passthrough mode preserves original HTML/CSS).
     pub normalize: bool,
+    /// Package version to write (default EPUB 3).
+    pub version: EpubVersion,
+    /// How chapter files are named. `None` keeps the defaults: source paths
+    /// in passthrough mode, `chapter_{i}.xhtml` when normalizing.
+    pub chapter_naming: Option<ChapterNaming>,
+    /// Normalize one chapter at a time, writing each chapter and the assets
+    /// it references before loading the next (default false). Peak memory
+    /// follows the largest chapter instead of the whole book, which matters
+    /// for large KFX conversions; each chapter is compiled twice, so it is
+    /// slower. Only affects normalized export.
+    pub low_memory: bool,
+}
+
+/// File naming scheme for exported chapters (see
+/// [`EpubConfig::chapter_naming`]).
+///
+/// Internal links, the TOC and landmarks are rewritten to the new names.
+/// Passthrough export keeps each chapter in its source directory so
+/// relative references to images and stylesheets still resolve.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChapterNaming {
+    /// Keep each chapter's source file name (`text/intro.xhtml` is written
+    /// as `intro.xhtml` by normalized export, unchanged by passthrough).
+    Preserve,
+    /// Number chapters in spine order: `Sequential("ch".into())` writes
+    /// `ch-001.xhtml`, `ch-002.xhtml`, and so on.
+    Sequential(String),
+    /// Name each chapter after its title (`the-journey.xhtml`), taken from
+    /// the TOC or the chapter's first heading. Untitled chapters fall back
+    /// to `chapter-001.xhtml`-style names.
+    FromTitle,
+}
+
+impl ChapterNaming {
+    /// Output file name (without directory) for each spine chapter, in
+    /// spine order. Names are unique, compared case-insensitively, and never
+    /// collide with the package files the exporter writes itself or with an
+    /// asset in the directory the chapter is written to: its source directory
+    /// when `in_source_dirs`, else the package root.
+    fn file_names(&self, book: &Book, in_source_dirs: bool) -> Vec<String> {
+        let mut taken: HashSet<String> = ["content.opf", "toc.ncx", "nav.xhtml", "style.css"]
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        let chapters: HashSet<&str> = book
+            .spine()
+            .iter()
+            .filter_map(|entry| book.source_id(entry.id))
+            .collect();
+        let assets: HashSet<String> = book
+            .list_assets()
+            .iter()
+            .filter(|path| !chapters.contains(path.as_str()))
+            .map(|path| sanitize_path(path).to_lowercase())
+            .collect();
+        let mut names = Vec::new();
+        for (i, entry) in book.spine().iter().enumerate() {
+            let source = book.source_id(entry.id).unwrap_or("unknown.xhtml");
+            let dir = match source.rsplit_once('/') {
+                Some((dir, _)) if in_source_dirs => format!("{}/", sanitize_path(dir)),
+                _ => String::new(),
+            };
+            let numbered = |prefix: &str| format!("{prefix}-{:03}", i + 1);
+            let (stem, ext) = match self {
+                Self::Preserve => {
+                    let name = source.rsplit('/').next().unwrap_or(source);
+                    match name.rsplit_once('.') {
+                        Some((stem, ext)) if !stem.is_empty() => (stem.to_string(), ext),
+                        _ => (name.to_string(), "xhtml"),
+                    }
+                }
+                Self::Sequential(prefix) => (numbered(&file_name_prefix(prefix)), "xhtml"),
+                Self::FromTitle => {
+                    let slug = chapter_title(book, entry.id, source)
+                        .map(|title| crate::markdown::slugify(&title))
+                        .unwrap_or_default();
+                    if slug.is_empty() {
+                        (numbered("chapter"), "xhtml")
+                    } else {
+                        (slug, "xhtml")
+                    }
+                }
+            };
+            let mut name = format!("{stem}.{ext}");
+            let mut n = 2;
+            while assets.contains(&format!("{dir}{name}").to_lowercase())
+                || !taken.insert(name.to_lowercase())
+            {
+                name = format!("{stem}-{n}.{ext}");
+                n += 1;
+            }
+            names.push(name);
+        }
+        names
+    }
+}
+
+/// A [`ChapterNaming::Sequential`] prefix reduced to characters that are
+/// safe in a file name, so it can't add directories (`../`) or characters
+/// readers choke on. Falls back to `chapter` when nothing is left.
+fn file_name_prefix(prefix: &str) -> String {
+    let safe: String = prefix
+        .chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        .collect();
+    let safe = safe.trim_start_matches('.');
+    if safe.is_empty() {
+        "chapter".to_string()
+    } else {
+        safe.to_string()
+    }
+}
+
+/// A chapter's title: the first TOC entry pointing at it, else its first
+/// heading.
+fn chapter_title(book: &Book, id: crate::model::ChapterId, source: &str) -> Option<String> {
+    fn find<'a>(entries: &'a [TocEntry], source: &str) -> Option<&'a str> {
+        entries.iter().find_map(|entry| {
+            let file = entry.href.split('#').next().unwrap_or_default();
+            (file == source && !entry.title.trim().is_empty())
+                .then_some(entry.title.as_str())
+                .or_else(|| find(&entry.children, source))
+        })
+    }
+    find(book.toc(), source).map(str::to_string).or_else(|| {
+        let chapter = book.load_chapter_cached(id).ok()?;
+        super::normalize::extract_chapter_title(&chapter)
+    })
+}
+
+/// EPUB package version targeted by [`EpubExporter`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EpubVersion {
+    /// EPUB 2.0.1: a `version="2.0"` OPF with EPUB 2 metadata forms
+    /// (`opf:` attributes instead of `refines`) and NCX-only navigation,
+    /// for older readers and devices that reject EPUB 3 packages.
+    Epub2,
+    /// EPUB 3: adds the XHTML nav document and EPUB 3 metadata
+    /// refinements, keeping the NCX for backward compatibility.
+    #[default]
+    Epub3,
 }
 
 /// EPUB format exporter.
@@ -68,19 +211,74 @@ impl Default for EpubExporter {
 
 impl Exporter for EpubExporter {
     fn export<W: Write + Seek>(&self, book: &Book, writer: &mut W) -> crate::Result<()> {
+        self.export_with_progress(book, writer, &mut |_| {})
+    }
+
+    fn export_with_progress<W: Write + Seek>(
+        &self,
+        book: &Book,
+        writer: &mut W,
+        progress: &mut dyn FnMut(ProgressEvent),
+    ) -> crate::Result<()> {
+        let mut zip = ZipWriter::new(writer);
+        let mut progress = Progress::new(progress, book.spine().len());
+        self.write_entries(book, &mut zip, &mut progress)?;
+        Ok(())
+    }
+}
+
+impl EpubExporter {
+    /// Export to a writer that can't seek (a socket, pipe, or HTTP response
+    /// body), returning the writer once the archive is complete.
+    ///
+    /// Entries are streamed into the output as they're produced, with sizes
+    /// recorded in trailing data descriptors instead of being patched into
+    /// the local headers afterwards. Passthrough export holds at most one
+    /// chapter or asset in memory at a time; normalized export (see
+    /// [`EpubConfig::normalize`]) builds every chapter's markup first unless
+    /// [`EpubConfig::low_memory`] is set.
+    ///
+    /// ```no_run
+    /// use boko::Book;
+    /// use boko::export::EpubExporter;
+    ///
+    /// let book = Book::open("input.epub")?;
+    /// let stdout = EpubExporter::new().export_stream(&book, std::io::stdout().lock())?;
+    /// # drop(stdout);
+    /// # Ok::<(), boko::Error>(())
+    /// ```
+    pub fn export_stream<W: Write>(&self, book: &Book, writer: W) -> crate::Result<W> {
+        let mut zip = ZipWriter::new_stream(writer);
+        let mut ignore = |_| {};
+        let mut progress = Progress::new(&mut ignore, book.spine().len());
+        self.write_entries(book, &mut zip, &mut progress)?;
+        let stream = zip.finish().map_err(io_error)?;
+        Ok(stream.into_inner())
+    }
+
+    /// Write every archive entry into `zip` (which the caller finishes).
+    fn write_entries<Z: Write + Seek>(
+        &self,
+        book: &Book,
+        zip: &mut ZipWriter<Z>,
+        progress: &mut Progress<'_>,
+    ) -> crate::Result<()> {
         // Use normalized mode if explicitly requested OR if the source format requires it
         // (e.g., KFX raw content is binary Ion, not HTML)
         if self.config.normalize || book.requires_normalized_export() {
-            Ok(self.export_normalized(book, writer)?)
+            Ok(self.export_normalized(book, zip, progress)?)
         } else {
-            Ok(self.export_raw(book, writer)?)
+            self.export_raw(book, zip, progress)
         }
     }
-}
 
-impl EpubExporter {
     /// Export with passthrough mode (preserves original HTML/CSS).
-    fn export_raw<W: Write + Seek>(&self, book: &Book, writer: &mut W) -> crate::Result<()> {
+    fn export_raw<Z: Write + Seek>(
+        &self,
+        book: &Book,
+        zip: &mut ZipWriter<Z>,
+        progress: &mut Progress<'_>,
+    ) -> crate::Result<()> {
         // Resolve TOC fragments before we generate the NCX. AZW3 and MOBI
         // importers leave TOC entries with bare chapter hrefs until
         // `resolve_toc()` populates the `#fileposN` / `#id` suffix from the
@@ -89,7 +287,29 @@ impl EpubExporter {
         // the intended in-chapter targets.
         book.resolve_toc();
 
-        let mut zip = ZipWriter::new(writer);
+        // Renamed chapters (source path -> new path in the same directory).
+        // Passthrough already keeps source names, so `Preserve` is a no-op.
+        let renames: HashMap<String, String> = match &self.config.chapter_naming {
+            None | Some(ChapterNaming::Preserve) => HashMap::new(),
+            Some(naming) => book
+                .spine()
+                .iter()
+                .zip(naming.file_names(book, true))
+                .filter_map(|(entry, name)| {
+                    let source = book.source_id(entry.id)?;
+                    let renamed = match source.rsplit_once('/') {
+                        Some((dir, _)) => format!("{dir}/{name}"),
+                        None => name,
+                    };
+                    Some((source.to_string(), renamed))
+                })
+                .collect(),
+        };
+        let chapter_zip_path = |id| {
+            let source = book.source_id(id).unwrap_or("unknown.xhtml");
+            let path = renames.get(source).map_or(source, String::as_str);
+            format!("OEBPS/{}", sanitize_path(path))
+        };
 
         let compression_level = self.config.compression_level.unwrap_or(6);
         let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
@@ -110,30 +330,32 @@ impl EpubExporter {
         let spine = book.spine();
         let mut manifest_items: Vec<ManifestItem> = Vec::new();
         let mut spine_refs: Vec<String> = Vec::new();
+        let epub3 = self.config.version == EpubVersion::Epub3;
 
         // The importer surfaces every ZIP entry as an asset, including the spine
         // XHTML documents. Those are written as chapters below, so track their
         // output paths and skip them when emitting assets — otherwise each spine
         // file is written (and added to the manifest) twice, which fails with a
         // duplicate-filename error on strict ZIP writers.
-        let chapter_paths: std::collections::HashSet<String> = spine
+        // Source paths of renamed chapters are skipped too.
+        let chapter_paths: HashSet<String> = spine
             .iter()
-            .map(|entry| {
-                format!(
-                    "OEBPS/{}",
-                    sanitize_path(book.source_id(entry.id).unwrap_or("unknown.xhtml"))
-                )
-            })
+            .map(|entry| chapter_zip_path(entry.id))
+            .chain(
+                renames
+                    .keys()
+                    .map(|source| format!("OEBPS/{}", sanitize_path(source))),
+            )
             .collect();
 
-        // Add chapters to manifest (written at their original source paths)
+        // Add chapters to manifest (written at their original source paths
+        // unless renamed)
         for (i, entry) in spine.iter().enumerate() {
-            let source_path = book.source_id(entry.id).unwrap_or("unknown.xhtml");
             let id = format!("chapter_{}", i);
 
             manifest_items.push(ManifestItem {
                 id: id.clone(),
-                href: format!("OEBPS/{}", sanitize_path(source_path)),
+                href: chapter_zip_path(entry.id),
                 media_type: "application/xhtml+xml",
                 properties: None,
             });
@@ -143,19 +365,22 @@ impl EpubExporter {
         // Add assets to manifest. The source's own packaging files (mimetype,
         // META-INF/, OPF, NCX) must not be re-bundled: we generate fresh ones,
         // and shipping the stale originals bloats the book and confuses
-        // validators.
-        let assets = book.list_assets();
+        // validators. An EPUB 2 package also drops the source's EPUB 3 nav
+        // document, whose HTML5 markup isn't valid OPS 2.0 content.
+        let assets: Vec<&String> = book
+            .list_assets()
+            .iter()
+            .filter(|path| !is_source_packaging(path))
+            .filter(|path| epub3 || !is_nav_document(book, path))
+            .collect();
 
         for (i, asset_path) in assets.iter().enumerate() {
-            if is_source_packaging(asset_path) {
-                continue;
-            }
             let href = format!("OEBPS/{}", sanitize_path(asset_path));
             // Skip spine documents already emitted as chapters (see above).
             if chapter_paths.contains(&href) {
                 continue;
             }
-            let media_type = asset_media_type(book, asset_path);
+            let media_type = normalized_media_type(book.declared_media_type(asset_path));
             let id = format!("asset_{}", i);
 
             manifest_items.push(ManifestItem {
@@ -168,33 +393,61 @@ impl EpubExporter {
 
         mark_cover_image(&mut manifest_items, book.metadata().cover_image.as_deref());
 
+        // A cover image no chapter shows gets a synthesized page, ahead of
+        // the chapters in reading order (see `missing_cover_page`).
+        let cover_image = missing_cover_page(book);
+        let cover_file = cover_image.as_ref().map(|_| {
+            let taken: Vec<&str> = manifest_items
+                .iter()
+                .map(|item| item.href.strip_prefix("OEBPS/").unwrap_or(&item.href))
+                .collect();
+            cover_file_name(&taken)
+        });
+        if let Some(file_name) = &cover_file {
+            manifest_items.push(ManifestItem {
+                id: "cover".to_string(),
+                href: format!("OEBPS/{file_name}"),
+                media_type: "application/xhtml+xml",
+                properties: None,
+            });
+            spine_refs.insert(0, "cover".to_string());
+        }
+
         // EPUB 3 requires exactly one manifest item with the `nav` property;
         // synthesize a nav document (at a path no source file occupies).
+        // EPUB 2 navigates by the NCX alone.
         let nav_zip_path = if manifest_items.iter().any(|m| m.href == "OEBPS/nav.xhtml") {
             "OEBPS/boko-nav.xhtml"
         } else {
             "OEBPS/nav.xhtml"
         };
-        manifest_items.push(ManifestItem {
-            id: "nav".to_string(),
-            href: nav_zip_path.to_string(),
-            media_type: "application/xhtml+xml",
-            properties: Some("nav"),
-        });
+        if epub3 {
+            manifest_items.push(ManifestItem {
+                id: "nav".to_string(),
+                href: nav_zip_path.to_string(),
+                media_type: "application/xhtml+xml",
+                properties: Some("nav"),
+            });
+        }
 
         // NCX and nav both require at least one entry; synthesize one for
         // TOC-less books.
         let first_chapter_href = spine
             .first()
-            .map(|entry| sanitize_path(book.source_id(entry.id).unwrap_or("unknown.xhtml")));
+            .map(|entry| chapter_zip_path(entry.id)["OEBPS/".len()..].to_string());
         let toc = toc_or_fallback(
-            book.toc(),
+            &rename_toc(book.toc(), &renames),
             &book.metadata().title,
             first_chapter_href.as_deref(),
         );
 
         // 4. Write content.opf
-        let opf = generate_opf(book.metadata(), &manifest_items, &spine_refs);
+        let opf = generate_opf(
+            book.metadata(),
+            &manifest_items,
+            &spine_refs,
+            self.config.version,
+        );
         zip.start_file("OEBPS/content.opf", deflated)
             .map_err(io_error)?;
         zip.write_all(opf.as_bytes())?;
@@ -206,70 +459,115 @@ impl EpubExporter {
         zip.write_all(ncx.as_bytes())?;
 
         // 5b. Write the EPUB 3 nav document
-        let nav = generate_nav(&book.metadata().title, &toc);
-        zip.start_file(nav_zip_path, deflated).map_err(io_error)?;
-        zip.write_all(nav.as_bytes())?;
+        if epub3 {
+            let cover = cover_file.as_ref().map(|href| Landmark {
+                landmark_type: LandmarkType::Cover,
+                href: href.clone(),
+                label: "Cover".to_string(),
+            });
+            let landmarks: Vec<Landmark> = cover
+                .into_iter()
+                .chain(book.landmarks().iter().map(|lm| Landmark {
+                    href: rename_href(&lm.href, &renames),
+                    ..lm.clone()
+                }))
+                .collect();
+            let spine_hrefs: Vec<String> = spine
+                .iter()
+                .map(|entry| chapter_zip_path(entry.id)["OEBPS/".len()..].to_string())
+                .collect();
+            let landmarks = with_bodymatter(landmarks, &toc, &spine_hrefs);
+            let nav = generate_nav(&book.metadata().title, &toc, &landmarks);
+            zip.start_file(nav_zip_path, deflated).map_err(io_error)?;
+            zip.write_all(nav.as_bytes())?;
+        }
 
-        // 6. Write chapters
-        for entry in spine {
+        // 6. Write the synthesized cover page, then the chapters
+        if let (Some(image), Some(file_name)) = (&cover_image, &cover_file) {
+            let page = generate_cover_page(&sanitize_path(image), epub3);
+            zip.start_file(format!("OEBPS/{file_name}"), deflated)
+                .map_err(io_error)?;
+            zip.write_all(page.as_bytes())?;
+        }
+        for (idx, entry) in spine.iter().enumerate() {
+            progress.chapter_started(idx);
             let source_path = book
                 .source_id(entry.id)
                 .unwrap_or("unknown.xhtml")
                 .to_string();
-            let content = book.load_raw(entry.id)?;
-            let zip_path = format!("OEBPS/{}", sanitize_path(&source_path));
+            // Stray control characters and noncharacters are not valid XML.
+            let (mut content, removed) =
+                crate::util::clean_unexpected_bytes(book.load_raw(entry.id)?);
+            progress.characters_removed(idx, removed);
+            if !renames.is_empty() {
+                content = crate::util::rewrite_moved_references(content, &source_path, |path| {
+                    renames.get(path).cloned()
+                });
+            }
+            let zip_path = chapter_zip_path(entry.id);
 
             zip.start_file(&zip_path, deflated).map_err(io_error)?;
             zip.write_all(&content)?;
+            progress.chapter_finished(idx);
         }
 
         // 7. Write assets (skipping spine documents already written as
         // chapters and the source's packaging files).
         for asset_path in assets {
-            if is_source_packaging(asset_path) {
-                continue;
-            }
             let zip_path = format!("OEBPS/{}", sanitize_path(asset_path));
             if chapter_paths.contains(&zip_path) {
                 continue;
             }
-            let content = book.load_asset(asset_path)?;
+            let mut content = book.load_asset(asset_path)?;
+            if !renames.is_empty()
+                && matches!(
+                    book.declared_media_type(asset_path),
+                    "application/xhtml+xml" | "text/html" | "text/css" | "image/svg+xml"
+                )
+            {
+                content = crate::util::rewrite_moved_references(content, asset_path, |path| {
+                    renames.get(path).cloned()
+                });
+            }
 
             let opts = asset_options(&zip_path, &content, stored, deflated);
             zip.start_file(&zip_path, opts).map_err(io_error)?;
             zip.write_all(&content)?;
+            progress.asset_written(asset_path);
         }
 
-        zip.finish().map_err(io_error)?;
         Ok(())
     }
 
     /// Export with normalized content (IR pipeline produces clean, consistent output).
-    fn export_normalized<W: Write + Seek>(&self, book: &Book, writer: &mut W) -> io::Result<()> {
-        use super::normalize::normalize_book;
+    fn export_normalized<Z: Write + Seek>(
+        &self,
+        book: &Book,
+        zip: &mut ZipWriter<Z>,
+        progress: &mut Progress<'_>,
+    ) -> io::Result<()> {
+        use super::html_synth::MathForm;
+        use super::normalize::normalize_book_named;
+
+        let file_names = self
+            .config
+            .chapter_naming
+            .as_ref()
+            .map(|naming| naming.file_names(book, false));
+        if self.config.low_memory {
+            return self.export_normalized_streaming(book, zip, file_names.as_deref(), progress);
+        }
 
         // Resolve TOC fragments before generating the NCX. Same rationale as
         // `export_raw`: AZW3 / MOBI importers leave TOC entries with bare
         // chapter hrefs until this is called.
         book.resolve_toc();
 
-        // Snapshot every asset the importer surfaces. The normalized content
-        // pipeline only records assets it actively references, which misses
-        // resources like embedded fonts that are referenced from CSS rather
-        // than from the IR DOM. We add those back into the manifest and ZIP
-        // below.
-        let all_assets = book.list_assets();
-
         // Normalize the book content
-        let content = normalize_book(book)?;
+        let content =
+            normalize_book_named(book, MathForm::MathMl, file_names.as_deref(), progress)?;
 
-        let mut zip = ZipWriter::new(writer);
-
-        let compression_level = self.config.compression_level.unwrap_or(6);
-        let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
-        let deflated = SimpleFileOptions::default()
-            .compression_method(CompressionMethod::Deflated)
-            .compression_level(Some(compression_level as i64));
+        let (stored, deflated) = self.zip_options();
 
         // 1. Write mimetype (must be first, uncompressed)
         zip.start_file("mimetype", stored).map_err(io_error)?;
@@ -280,7 +578,195 @@ impl EpubExporter {
             .map_err(io_error)?;
         zip.write_all(CONTAINER_XML)?;
 
-        // 3. Build manifest
+        // 3-5. Write content.opf, toc.ncx and nav.xhtml
+        let chapter_files: Vec<&str> = content
+            .chapters
+            .iter()
+            .map(|chapter| chapter.file_name.as_str())
+            .collect();
+        let cover = missing_cover_page(book);
+        let mut assets: Vec<&str> = content.assets.iter().map(String::as_str).collect();
+        if let Some(image) = cover.as_deref()
+            && !content.assets.contains(image)
+        {
+            assets.push(image);
+        }
+        let fonts = unreferenced_fonts(book, &content.assets);
+        self.write_normalized_package(
+            book,
+            zip,
+            &content,
+            &chapter_files,
+            &assets,
+            &fonts,
+            cover.as_deref(),
+        )?;
+
+        // 6. Write unified stylesheet (always, matching the manifest entry
+        // and the chapters' unconditional link to it).
+        zip.start_file("OEBPS/style.css", deflated)
+            .map_err(io_error)?;
+        zip.write_all(content.css.as_bytes())?;
+
+        // 7. Write synthesized chapters (already reported to `progress` as
+        // they were synthesized)
+        for chapter in &content.chapters {
+            let zip_path = format!("OEBPS/{}", chapter.file_name);
+            zip.start_file(&zip_path, deflated).map_err(io_error)?;
+            zip.write_all(chapter.document.as_bytes())?;
+        }
+
+        // 8. Write assets referenced by normalized content, then the font
+        // assets it doesn't cover (matching the manifest entries).
+        for asset_path in assets.iter().chain(&fonts) {
+            self.write_asset(book, zip, asset_path, progress)?;
+        }
+
+        Ok(())
+    }
+
+    /// Normalized export that holds one chapter at a time (see
+    /// [`EpubConfig::low_memory`]).
+    ///
+    /// Each chapter is synthesized and written straight into the archive,
+    /// followed by any assets it references for the first time; the
+    /// stylesheet and package documents, which need the whole book, come
+    /// last.
+    fn export_normalized_streaming<Z: Write + Seek>(
+        &self,
+        book: &Book,
+        zip: &mut ZipWriter<Z>,
+        file_names: Option<&[String]>,
+        progress: &mut Progress<'_>,
+    ) -> io::Result<()> {
+        use super::html_synth::MathForm;
+        use super::normalize::ChapterStream;
+
+        let stream = ChapterStream::new(book, MathForm::MathMl, file_names, progress)?;
+        let (stored, deflated) = self.zip_options();
+
+        zip.start_file("mimetype", stored).map_err(io_error)?;
+        zip.write_all(b"application/epub+zip")?;
+        zip.start_file("META-INF/container.xml", deflated)
+            .map_err(io_error)?;
+        zip.write_all(CONTAINER_XML)?;
+
+        let mut chapter_files = Vec::with_capacity(stream.len());
+        let mut written: HashSet<String> = HashSet::new();
+        let mut assets: Vec<String> = Vec::new();
+        for idx in 0..stream.len() {
+            progress.chapter_started(idx);
+            let (chapter, mut referenced) = stream.chapter(idx)?;
+            zip.start_file(format!("OEBPS/{}", chapter.file_name), deflated)
+                .map_err(io_error)?;
+            zip.write_all(chapter.document.as_bytes())?;
+            progress.characters_removed(idx, chapter.removed_chars);
+            chapter_files.push(chapter.file_name);
+            progress.chapter_finished(idx);
+
+            // Sort for a deterministic archive order.
+            referenced.retain(|path| !written.contains(path));
+            let mut referenced: Vec<String> = referenced.into_iter().collect();
+            referenced.sort();
+            for asset_path in referenced {
+                self.write_asset(book, zip, &asset_path, progress)?;
+                written.insert(asset_path.clone());
+                assets.push(asset_path);
+            }
+        }
+
+        let content = stream.finish();
+        let mut style_assets: Vec<&String> = content
+            .assets
+            .iter()
+            .filter(|path| !written.contains(*path))
+            .collect();
+        style_assets.sort();
+        for asset_path in style_assets {
+            self.write_asset(book, zip, asset_path, progress)?;
+            written.insert(asset_path.clone());
+            assets.push(asset_path.clone());
+        }
+        let cover = missing_cover_page(book);
+        if let Some(image) = &cover
+            && !written.contains(image)
+        {
+            self.write_asset(book, zip, image, progress)?;
+            written.insert(image.clone());
+            assets.push(image.clone());
+        }
+        let fonts = unreferenced_fonts(book, &written);
+        for asset_path in &fonts {
+            self.write_asset(book, zip, asset_path, progress)?;
+        }
+
+        zip.start_file("OEBPS/style.css", deflated)
+            .map_err(io_error)?;
+        zip.write_all(content.css.as_bytes())?;
+
+        let chapter_files: Vec<&str> = chapter_files.iter().map(String::as_str).collect();
+        let assets: Vec<&str> = assets.iter().map(String::as_str).collect();
+        self.write_normalized_package(
+            book,
+            zip,
+            &content,
+            &chapter_files,
+            &assets,
+            &fonts,
+            cover.as_deref(),
+        )
+    }
+
+    /// Stored and deflated entry options for this export.
+    fn zip_options(&self) -> (SimpleFileOptions, SimpleFileOptions) {
+        let compression_level = self.config.compression_level.unwrap_or(6);
+        let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+        let deflated = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .compression_level(Some(compression_level as i64));
+        (stored, deflated)
+    }
+
+    /// Copy one asset into `OEBPS/`. Assets the book can't load are skipped.
+    fn write_asset<Z: Write + Seek>(
+        &self,
+        book: &Book,
+        zip: &mut ZipWriter<Z>,
+        asset_path: &str,
+        progress: &mut Progress<'_>,
+    ) -> io::Result<()> {
+        let (stored, deflated) = self.zip_options();
+        let zip_path = format!("OEBPS/{}", sanitize_path(asset_path));
+        if let Ok(data) = book.load_asset(asset_path) {
+            let opts = asset_options(&zip_path, &data, stored, deflated);
+            zip.start_file(&zip_path, opts).map_err(io_error)?;
+            zip.write_all(&data)?;
+            progress.asset_written(asset_path);
+        }
+        Ok(())
+    }
+
+    /// Write content.opf, toc.ncx and (EPUB 3) nav.xhtml for a normalized
+    /// export of `chapter_files` (spine order), `assets` and `fonts`.
+    ///
+    /// `cover_image` is the asset path of a cover image no chapter shows
+    /// (see [`missing_cover_page`]); a page displaying it is written and put
+    /// first in the spine, with a cover landmark.
+    #[allow(clippy::too_many_arguments)]
+    fn write_normalized_package<Z: Write + Seek>(
+        &self,
+        book: &Book,
+        zip: &mut ZipWriter<Z>,
+        content: &NormalizedContent,
+        chapter_files: &[&str],
+        assets: &[&str],
+        fonts: &[&str],
+        cover_image: Option<&str>,
+    ) -> io::Result<()> {
+        let (_, deflated) = self.zip_options();
+        let epub3 = self.config.version == EpubVersion::Epub3;
+
+        // Build manifest
         let mut manifest_items: Vec<ManifestItem> = Vec::new();
         let mut spine_refs: Vec<String> = Vec::new();
 
@@ -295,10 +781,26 @@ impl EpubExporter {
             properties: None,
         });
 
+        // Synthesized cover page, ahead of the chapters in reading order.
+        let cover_file = cover_image.map(|_| cover_file_name(chapter_files));
+        if let (Some(image), Some(file_name)) = (cover_image, &cover_file) {
+            let page = generate_cover_page(&sanitize_path(image), epub3);
+            zip.start_file(format!("OEBPS/{file_name}"), deflated)
+                .map_err(io_error)?;
+            zip.write_all(page.as_bytes())?;
+            manifest_items.push(ManifestItem {
+                id: "cover".to_string(),
+                href: format!("OEBPS/{file_name}"),
+                media_type: "application/xhtml+xml",
+                properties: None,
+            });
+            spine_refs.push("cover".to_string());
+        }
+
         // Add chapters to manifest
-        for (i, _) in content.chapters.iter().enumerate() {
+        for (i, file_name) in chapter_files.iter().enumerate() {
             let id = format!("chapter_{}", i);
-            let href = format!("OEBPS/chapter_{}.xhtml", i);
+            let href = format!("OEBPS/{}", file_name);
 
             manifest_items.push(ManifestItem {
                 id: id.clone(),
@@ -310,16 +812,18 @@ impl EpubExporter {
         }
 
         // EPUB 3 requires exactly one manifest item with the `nav` property.
-        manifest_items.push(ManifestItem {
-            id: "nav".to_string(),
-            href: "OEBPS/nav.xhtml".to_string(),
-            media_type: "application/xhtml+xml",
-            properties: Some("nav"),
-        });
+        if epub3 {
+            manifest_items.push(ManifestItem {
+                id: "nav".to_string(),
+                href: "OEBPS/nav.xhtml".to_string(),
+                media_type: "application/xhtml+xml",
+                properties: Some("nav"),
+            });
+        }
 
         // Add assets to manifest (from normalized content)
-        for (asset_idx, asset_path) in content.assets.iter().enumerate() {
-            let media_type = asset_media_type(book, asset_path);
+        for (asset_idx, asset_path) in assets.iter().enumerate() {
+            let media_type = normalized_media_type(book.declared_media_type(asset_path));
             let id = format!("asset_{}", asset_idx);
             let href = format!("OEBPS/{}", sanitize_path(asset_path));
 
@@ -331,103 +835,80 @@ impl EpubExporter {
             });
         }
 
-        // Add font assets the importer surfaced that aren't already covered
-        // by normalized content. Fonts are typically referenced from CSS, not
-        // from DOM nodes, so `normalize_book` doesn't pull them into
-        // `content.assets`. Without this we'd emit `@font-face` rules whose
-        // `src:` URLs point at files we never wrote into the ZIP.
-        let mut extra_font_idx = 0;
-        for asset_path in all_assets {
-            if !asset_path.starts_with("fonts/") {
-                continue;
-            }
-            if content.assets.contains(asset_path) {
-                continue;
-            }
+        for (font_idx, asset_path) in fonts.iter().enumerate() {
             manifest_items.push(ManifestItem {
-                id: format!("font_{}", extra_font_idx),
+                id: format!("font_{}", font_idx),
                 href: format!("OEBPS/{}", sanitize_path(asset_path)),
                 media_type: guess_media_type(asset_path),
                 properties: None,
             });
-            extra_font_idx += 1;
         }
 
         mark_cover_image(&mut manifest_items, book.metadata().cover_image.as_deref());
 
-        // 4. Write content.opf
-        let opf = generate_opf(book.metadata(), &manifest_items, &spine_refs);
+        // Write content.opf
+        let opf = generate_opf(
+            book.metadata(),
+            &manifest_items,
+            &spine_refs,
+            self.config.version,
+        );
         zip.start_file("OEBPS/content.opf", deflated)
             .map_err(io_error)?;
         zip.write_all(opf.as_bytes())?;
 
-        // 5. Write toc.ncx. Rewrite the TOC hrefs (original source paths / bare
+        // Write toc.ncx. Rewrite the TOC hrefs (original source paths / bare
         // `#anchor`s) to the emitted chapter files so navigation resolves.
         // NCX and nav both require at least one entry; synthesize one for
         // TOC-less books.
         let rewritten_toc = toc_or_fallback(
             &content.rewrite_toc(book.toc()),
             &book.metadata().title,
-            (!content.chapters.is_empty()).then_some("chapter_0.xhtml"),
+            chapter_files.first().copied(),
         );
         let ncx = generate_ncx(book.metadata(), &rewritten_toc);
         zip.start_file("OEBPS/toc.ncx", deflated)
             .map_err(io_error)?;
         zip.write_all(ncx.as_bytes())?;
 
-        // 5b. Write the EPUB 3 nav document (same TOC, XHTML form).
-        let nav = generate_nav(&book.metadata().title, &rewritten_toc);
-        zip.start_file("OEBPS/nav.xhtml", deflated)
-            .map_err(io_error)?;
-        zip.write_all(nav.as_bytes())?;
-
-        // 6. Write unified stylesheet (always, matching the manifest entry
-        // and the chapters' unconditional link to it).
-        zip.start_file("OEBPS/style.css", deflated)
-            .map_err(io_error)?;
-        zip.write_all(content.css.as_bytes())?;
-
-        // 7. Write synthesized chapters
-        for (i, chapter) in content.chapters.iter().enumerate() {
-            let zip_path = format!("OEBPS/chapter_{}.xhtml", i);
-            zip.start_file(&zip_path, deflated).map_err(io_error)?;
-            zip.write_all(chapter.document.as_bytes())?;
-        }
-
-        // 8. Write assets referenced by normalized content
-        for asset_path in &content.assets {
-            let zip_path = format!("OEBPS/{}", sanitize_path(asset_path));
-
-            // Try to load the asset from the book
-            if let Ok(data) = book.load_asset(asset_path) {
-                let opts = asset_options(&zip_path, &data, stored, deflated);
-                zip.start_file(&zip_path, opts).map_err(io_error)?;
-                zip.write_all(&data)?;
-            }
-        }
-
-        // 9. Write font assets not already covered by normalized content.
-        // Matches the manifest entries added above.
-        for asset_path in all_assets {
-            if !asset_path.starts_with("fonts/") {
-                continue;
-            }
-            if content.assets.contains(asset_path) {
-                continue;
-            }
-            let zip_path = format!("OEBPS/{}", sanitize_path(asset_path));
-            if let Ok(data) = book.load_asset(asset_path) {
-                let opts = asset_options(&zip_path, &data, stored, deflated);
-                zip.start_file(&zip_path, opts).map_err(io_error)?;
-                zip.write_all(&data)?;
-            }
+        // Write the EPUB 3 nav document (same TOC, XHTML form).
+        if epub3 {
+            let cover = cover_file.map(|href| Landmark {
+                landmark_type: LandmarkType::Cover,
+                href,
+                label: "Cover".to_string(),
+            });
+            let landmarks: Vec<Landmark> = cover
+                .into_iter()
+                .chain(book.landmarks().iter().map(|lm| Landmark {
+                    href: content.rewrite_link(&lm.href),
+                    ..lm.clone()
+                }))
+                .collect();
+            let landmarks = with_bodymatter(landmarks, &rewritten_toc, chapter_files);
+            let nav = generate_nav(&book.metadata().title, &rewritten_toc, &landmarks);
+            zip.start_file("OEBPS/nav.xhtml", deflated)
+                .map_err(io_error)?;
+            zip.write_all(nav.as_bytes())?;
         }
 
-        zip.finish().map_err(io_error)?;
         Ok(())
     }
 }
 
+/// Font assets the importer surfaced that normalized content doesn't
+/// reference. Fonts are typically referenced from CSS, not from DOM nodes,
+/// so `normalize_book` doesn't pull them into its assets. Without these
+/// we'd emit `@font-face` rules whose `src:` URLs point at files we never
+/// wrote into the ZIP.
+fn unreferenced_fonts<'a>(book: &'a Book, referenced: &HashSet<String>) -> Vec<&'a str> {
+    book.list_assets()
+        .iter()
+        .filter(|path| path.starts_with("fonts/") && !referenced.contains(*path))
+        .map(String::as_str)
+        .collect()
+}
+
 /// Convert zip error to io error.
 fn io_error<E: std::error::Error + Send + Sync + 'static>(e: E) -> io::Error {
     io::Error::other(e)
@@ -450,19 +931,6 @@ struct ManifestItem {
     properties: Option<&'static str>,
 }
 
-/// Media type for a manifest asset: extension first, magic-byte sniffing as
-/// fallback for unknown/extensionless names (e.g. KFX short resource names
-/// like "e6") so real images aren't declared as foreign octet-streams.
-fn asset_media_type(book: &Book, path: &str) -> &'static str {
-    let by_ext = guess_media_type(path);
-    if by_ext != "application/octet-stream" {
-        return by_ext;
-    }
-    book.load_asset(path)
-        .map(|data| crate::util::detect_media_format(path, &data).mime_type())
-        .unwrap_or(by_ext)
-}
-
 /// Whether an asset path is part of the *source* EPUB's packaging (its
 /// mimetype, container.xml, OPF, or NCX). These are regenerated on export and
 /// must not be re-bundled as ordinary assets.
@@ -473,6 +941,20 @@ fn is_source_packaging(path: &str) -> bool {
         || path.ends_with(".ncx")
 }
 
+/// Whether an asset is an EPUB 3 navigation document (`<nav epub:type="toc">`).
+fn is_nav_document(book: &Book, path: &str) -> bool {
+    if !matches!(
+        guess_media_type(path),
+        "application/xhtml+xml" | "text/html"
+    ) {
+        return false;
+    }
+    book.load_asset(path).is_ok_and(|data| {
+        let text = String::from_utf8_lossy(&data);
+        text.contains("<nav") && text.contains("epub:type=\"toc\"")
+    })
+}
+
 /// Mark the manifest item holding the book's cover image with
 /// `properties="cover-image"` (EPUB 3). `generate_opf` also emits the EPUB 2
 /// `<meta name="cover">` from this marking.
@@ -490,6 +972,44 @@ fn mark_cover_image(manifest_items: &mut [ManifestItem], cover_image: Option<&st
     }
 }
 
+/// File name for a synthesized cover page: `cover.xhtml`, numbered if one
+/// of the package's `files` already uses that name.
+fn cover_file_name(files: &[&str]) -> String {
+    let taken = |name: &str| files.iter().any(|file| file.eq_ignore_ascii_case(name));
+    let mut name = "cover.xhtml".to_string();
+    let mut n = 2;
+    while taken(&name) {
+        name = format!("cover-{n}.xhtml");
+        n += 1;
+    }
+    name
+}
+
+/// A page showing only the cover image at `image_href`, scaled to fit the
+/// screen. `epub:type="cover"` is EPUB 3 vocabulary, so EPUB 2 omits it.
+fn generate_cover_page(image_href: &str, epub3: bool) -> String {
+    let mut doc = String::new();
+    doc.push_str(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE html>\n\
+         <html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\">\n\
+         <head>\n  <meta charset=\"utf-8\"/>\n  <title>Cover</title>\n  <style>\n\
+         html, body { height: 100%; margin: 0; padding: 0; }\n\
+         body { text-align: center; }\n\
+         img { height: 100%; max-width: 100%; object-fit: contain; }\n\
+         </style>\n</head>\n",
+    );
+    doc.push_str(if epub3 {
+        "<body epub:type=\"cover\">\n"
+    } else {
+        "<body>\n"
+    });
+    doc.push_str("  <img src=\"");
+    doc.push_str(&escape_xml(image_href));
+    doc.push_str("\" alt=\"Cover\"/>\n</body>\n</html>\n");
+    doc
+}
+
 /// Clone the TOC, or synthesize a single entry pointing at the first chapter
 /// when it is empty: both the NCX DTD (`navMap` needs a `navPoint`) and the
 /// EPUB 3 nav spec (`nav` needs an `ol`) reject empty navigation.
@@ -522,20 +1042,30 @@ fn asset_options(
 }
 
 /// Generate content.opf from metadata and manifest.
+///
+/// EPUB 3 attaches sort keys, roles, and series data as `refines` meta
+/// elements; EPUB 2 has no refinements, so the same data is written as
+/// `opf:` attributes and calibre's `calibre:series` metas.
 fn generate_opf(
     metadata: &crate::model::Metadata,
     manifest: &[ManifestItem],
     spine_refs: &[String],
+    version: EpubVersion,
 ) -> String {
+    if version == EpubVersion::Epub2 {
+        return generate_opf2(metadata, manifest, spine_refs);
+    }
+
     let mut opf = String::new();
 
     // Use EPUB3 for extended metadata support
-    opf.push_str(
+    opf.push_str(&format!(
         r#"<?xml version="1.0" encoding="UTF-8"?>
-<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="BookId">
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="BookId"{}>
   <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
 "#,
-    );
+        custom_prefix_attr(metadata)
+    ));
 
     // Track IDs for refinements
     let mut next_id = 1;
@@ -587,21 +1117,17 @@ fn generate_opf(
         opf.push_str("    <dc:language>en</dc:language>\n");
     }
 
-    // Identifier
-    if !metadata.identifier.is_empty() {
-        opf.push_str(&format!(
-            "    <dc:identifier id=\"BookId\">{}</dc:identifier>\n",
-            escape_xml(&metadata.identifier)
-        ));
-    } else {
-        opf.push_str("    <dc:identifier id=\"BookId\">urn:uuid:00000000-0000-0000-0000-000000000000</dc:identifier>\n");
-    }
+    push_identifiers(&mut opf, metadata, EpubVersion::Epub3);
 
-    // dcterms:modified (required for EPUB3)
-    if let Some(ref modified) = metadata.modified_date {
+    // dcterms:modified (required for EPUB3, and only as a UTC timestamp;
+    // sources often carry a bare date or an offset)
+    if let Some(modified) = metadata
+        .modified_date
+        .as_deref()
+        .and_then(crate::util::to_utc_timestamp)
+    {
         opf.push_str(&format!(
-            "    <meta property=\"dcterms:modified\">{}</meta>\n",
-            escape_xml(modified)
+            "    <meta property=\"dcterms:modified\">{modified}</meta>\n"
         ));
     } else {
         // Generate a timestamp for EPUB3 compliance
@@ -665,7 +1191,130 @@ fn generate_opf(
     // Suppress unused variable warning
     let _ = next_id;
 
-    // Optional metadata
+    push_common_metadata(&mut opf, metadata);
+    push_rendition_metadata(&mut opf, metadata, EpubVersion::Epub3);
+    push_accessibility_metadata(&mut opf, metadata, EpubVersion::Epub3);
+    push_custom_metadata(&mut opf, metadata, EpubVersion::Epub3);
+    push_cover_meta(&mut opf, manifest);
+    opf.push_str("  </metadata>\n");
+    push_manifest(&mut opf, manifest, true);
+
+    // Spine. Preserve the global reading direction (RTL books) when the
+    // source declared one and it isn't the default.
+    match metadata.page_progression_direction.as_deref() {
+        Some(dir @ ("rtl" | "ltr")) => {
+            opf.push_str(&format!(
+                "  <spine toc=\"ncx\" page-progression-direction=\"{dir}\">\n"
+            ));
+        }
+        _ => opf.push_str("  <spine toc=\"ncx\">\n"),
+    }
+    push_spine_items(&mut opf, spine_refs);
+    opf.push_str("</package>\n");
+    opf
+}
+
+/// Generate an EPUB 2.0.1 content.opf (see [`generate_opf`]).
+fn generate_opf2(
+    metadata: &crate::model::Metadata,
+    manifest: &[ManifestItem],
+    spine_refs: &[String],
+) -> String {
+    let mut opf = String::new();
+    opf.push_str(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="2.0" unique-identifier="BookId">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:opf="http://www.idpf.org/2007/opf">
+"#,
+    );
+
+    opf.push_str(&format!(
+        "    <dc:title>{}</dc:title>\n",
+        escape_xml(&metadata.title)
+    ));
+    if let Some(ref title_sort) = metadata.title_sort {
+        opf.push_str(&format!(
+            "    <meta name=\"calibre:title_sort\" content=\"{}\"/>\n",
+            escape_xml(title_sort)
+        ));
+    }
+
+    for (i, author) in metadata.authors.iter().enumerate() {
+        let file_as = match metadata.author_sort {
+            Some(ref sort) if i == 0 => format!(" opf:file-as=\"{}\"", escape_xml(sort)),
+            _ => String::new(),
+        };
+        opf.push_str(&format!(
+            "    <dc:creator opf:role=\"aut\"{}>{}</dc:creator>\n",
+            file_as,
+            escape_xml(author)
+        ));
+    }
+
+    if !metadata.language.is_empty() {
+        opf.push_str(&format!(
+            "    <dc:language>{}</dc:language>\n",
+            escape_xml(&metadata.language)
+        ));
+    } else {
+        opf.push_str("    <dc:language>en</dc:language>\n");
+    }
+
+    push_identifiers(&mut opf, metadata, EpubVersion::Epub2);
+
+    // EPUB 2 has no dcterms:modified; the modification date is a dated event.
+    if let Some(ref modified) = metadata.modified_date {
+        opf.push_str(&format!(
+            "    <dc:date opf:event=\"modification\">{}</dc:date>\n",
+            escape_xml(modified)
+        ));
+    }
+
+    for contrib in &metadata.contributors {
+        let mut attrs = String::new();
+        if let Some(ref role) = contrib.role {
+            attrs.push_str(&format!(" opf:role=\"{}\"", escape_xml(role)));
+        }
+        if let Some(ref file_as) = contrib.file_as {
+            attrs.push_str(&format!(" opf:file-as=\"{}\"", escape_xml(file_as)));
+        }
+        opf.push_str(&format!(
+            "    <dc:contributor{}>{}</dc:contributor>\n",
+            attrs,
+            escape_xml(&contrib.name)
+        ));
+    }
+
+    if let Some(ref coll) = metadata.collection {
+        opf.push_str(&format!(
+            "    <meta name=\"calibre:series\" content=\"{}\"/>\n",
+            escape_xml(&coll.name)
+        ));
+        if let Some(pos) = coll.position {
+            opf.push_str(&format!(
+                "    <meta name=\"calibre:series_index\" content=\"{}\"/>\n",
+                pos
+            ));
+        }
+    }
+
+    push_common_metadata(&mut opf, metadata);
+    push_rendition_metadata(&mut opf, metadata, EpubVersion::Epub2);
+    push_accessibility_metadata(&mut opf, metadata, EpubVersion::Epub2);
+    push_custom_metadata(&mut opf, metadata, EpubVersion::Epub2);
+    push_cover_meta(&mut opf, manifest);
+    opf.push_str("  </metadata>\n");
+    push_manifest(&mut opf, manifest, false);
+
+    // page-progression-direction is EPUB 3 only.
+    opf.push_str("  <spine toc=\"ncx\">\n");
+    push_spine_items(&mut opf, spine_refs);
+    opf.push_str("</package>\n");
+    opf
+}
+
+/// Dublin Core elements written identically by EPUB 2 and EPUB 3.
+fn push_common_metadata(opf: &mut String, metadata: &crate::model::Metadata) {
     if let Some(ref publisher) = metadata.publisher {
         opf.push_str(&format!(
             "    <dc:publisher>{}</dc:publisher>\n",
@@ -693,9 +1342,203 @@ fn generate_opf(
             escape_xml(rights)
         ));
     }
+    if let Some(rating) = metadata.rating {
+        opf.push_str(&format!(
+            "    <meta name=\"calibre:rating\" content=\"{rating}\"/>\n"
+        ));
+    }
+}
+
+/// Fixed-layout settings: `rendition:*` properties in EPUB 3; EPUB 2 has
+/// none, so it gets the Kindle `fixed-layout`/`original-resolution` metas.
+fn push_rendition_metadata(
+    opf: &mut String,
+    metadata: &crate::model::Metadata,
+    version: EpubVersion,
+) {
+    let rendition = &metadata.rendition;
+    if version == EpubVersion::Epub2 {
+        if rendition.is_fixed_layout() {
+            opf.push_str("    <meta name=\"fixed-layout\" content=\"true\"/>\n");
+        }
+        if let Some((width, height)) = rendition.viewport {
+            opf.push_str(&format!(
+                "    <meta name=\"original-resolution\" content=\"{width}x{height}\"/>\n"
+            ));
+        }
+        if let Some(orientation @ ("portrait" | "landscape")) = rendition.orientation.as_deref() {
+            opf.push_str(&format!(
+                "    <meta name=\"orientation-lock\" content=\"{orientation}\"/>\n"
+            ));
+        }
+        return;
+    }
+    for (property, value) in [
+        ("layout", &rendition.layout),
+        ("orientation", &rendition.orientation),
+        ("spread", &rendition.spread),
+    ] {
+        if let Some(value) = value {
+            opf.push_str(&format!(
+                "    <meta property=\"rendition:{property}\">{}</meta>\n",
+                escape_xml(value)
+            ));
+        }
+    }
+}
+
+/// Accessibility metadata as `schema:*` properties: `<meta property>` in
+/// EPUB 3, `name`/`content` metas in EPUB 2.
+fn push_accessibility_metadata(
+    opf: &mut String,
+    metadata: &crate::model::Metadata,
+    version: EpubVersion,
+) {
+    let accessibility = &metadata.accessibility;
+    let properties = [
+        ("accessibilityFeature", &accessibility.features),
+        ("accessMode", &accessibility.access_modes),
+        (
+            "accessModeSufficient",
+            &accessibility.access_modes_sufficient,
+        ),
+        ("accessibilityHazard", &accessibility.hazards),
+    ]
+    .into_iter()
+    .flat_map(|(property, values)| values.iter().map(move |value| (property, value)))
+    .chain(
+        accessibility
+            .summary
+            .iter()
+            .map(|summary| ("accessibilitySummary", summary)),
+    );
+    for (property, value) in properties {
+        if version == EpubVersion::Epub3 {
+            opf.push_str(&format!(
+                "    <meta property=\"schema:{property}\">{}</meta>\n",
+                escape_xml(value)
+            ));
+        } else {
+            opf.push_str(&format!(
+                "    <meta name=\"schema:{property}\" content=\"{}\"/>\n",
+                escape_xml(value)
+            ));
+        }
+    }
+}
+
+/// The unique `BookId` identifier followed by the book's other identifiers.
+/// Schemes are `opf:scheme` attributes in EPUB 2 and `identifier-type`
+/// refinements in EPUB 3.
+fn push_identifiers(opf: &mut String, metadata: &crate::model::Metadata, version: EpubVersion) {
+    let unique = if metadata.identifier.is_empty() {
+        "urn:uuid:00000000-0000-0000-0000-000000000000"
+    } else {
+        metadata.identifier.as_str()
+    };
+    let unique_scheme = metadata
+        .identifiers
+        .iter()
+        .find(|i| i.value == unique)
+        .and_then(|i| i.scheme.as_deref());
+    let others = metadata
+        .identifiers
+        .iter()
+        .filter(|i| i.value != unique)
+        .map(|i| (i.value.as_str(), i.scheme.as_deref()));
+
+    let all = std::iter::once((unique, unique_scheme)).chain(others);
+    for (n, (value, scheme)) in all.enumerate() {
+        let id = if n == 0 {
+            "BookId".to_string()
+        } else {
+            format!("identifier{n}")
+        };
+        match (version, scheme) {
+            (EpubVersion::Epub2, Some(scheme)) => opf.push_str(&format!(
+                "    <dc:identifier id=\"{id}\" opf:scheme=\"{}\">{}</dc:identifier>\n",
+                escape_xml(scheme),
+                escape_xml(value)
+            )),
+            _ => opf.push_str(&format!(
+                "    <dc:identifier id=\"{id}\">{}</dc:identifier>\n",
+                escape_xml(value)
+            )),
+        }
+        if version == EpubVersion::Epub3
+            && let Some(scheme) = scheme
+        {
+            opf.push_str(&format!(
+                "    <meta refines=\"#{id}\" property=\"identifier-type\">{}</meta>\n",
+                escape_xml(scheme)
+            ));
+        }
+    }
+}
+
+/// Metadata boko doesn't model (`Metadata::custom`): Dublin Core elements
+/// as-is, other properties as EPUB 3 `<meta property>` or, in EPUB 2,
+/// `name`/`content` metas.
+fn push_custom_metadata(opf: &mut String, metadata: &crate::model::Metadata, version: EpubVersion) {
+    for (property, value) in &metadata.custom {
+        if let Some(element) = property.strip_prefix("dc:") {
+            opf.push_str(&format!(
+                "    <dc:{element}>{}</dc:{element}>\n",
+                escape_xml(value)
+            ));
+        } else if version == EpubVersion::Epub3 && !property.starts_with("calibre:") {
+            // Calibre reads its own metadata only from name/content metas.
+            opf.push_str(&format!(
+                "    <meta property=\"{}\">{}</meta>\n",
+                escape_xml(property),
+                escape_xml(value)
+            ));
+        } else {
+            opf.push_str(&format!(
+                "    <meta name=\"{}\" content=\"{}\"/>\n",
+                escape_xml(property),
+                escape_xml(value)
+            ));
+        }
+    }
+}
 
-    // EPUB 2 cover marker, pointing at the manifest item flagged with the
-    // EPUB 3 `cover-image` property (see `mark_cover_image`).
+/// The package `prefix` attribute (with its leading space) declaring the
+/// vocabularies custom properties use. EPUB 3's reserved prefixes need no
+/// declaration; undeclared ones in the source can't be declared here either.
+fn custom_prefix_attr(metadata: &crate::model::Metadata) -> String {
+    const RESERVED: &[&str] = &[
+        "a11y",
+        "dcterms",
+        "marc",
+        "media",
+        "msv",
+        "onix",
+        "prism",
+        "rendition",
+        "schema",
+        "xsd",
+    ];
+    let mut declared: Vec<String> = Vec::new();
+    for (prefix, iri) in &metadata.custom_prefixes {
+        let used = metadata
+            .custom
+            .iter()
+            .any(|(p, _)| p.split_once(':').is_some_and(|(pre, _)| pre == prefix));
+        if used && !RESERVED.contains(&prefix.as_str()) {
+            declared.push(format!("{prefix}: {iri}"));
+        }
+    }
+    if declared.is_empty() {
+        String::new()
+    } else {
+        format!(" prefix=\"{}\"", escape_xml(&declared.join(" ")))
+    }
+}
+
+/// EPUB 2 cover marker, pointing at the manifest item flagged with the
+/// EPUB 3 `cover-image` property (see `mark_cover_image`).
+fn push_cover_meta(opf: &mut String, manifest: &[ManifestItem]) {
     if let Some(cover_item) = manifest.iter().find(|item| {
         item.properties
             .is_some_and(|p| p.split_ascii_whitespace().any(|p| p == "cover-image"))
@@ -705,10 +1548,10 @@ fn generate_opf(
             escape_xml(&cover_item.id)
         ));
     }
+}
 
-    opf.push_str("  </metadata>\n");
-
-    // Manifest
+/// Write the manifest. Item `properties` are EPUB 3 only.
+fn push_manifest(opf: &mut String, manifest: &[ManifestItem], properties: bool) {
     opf.push_str("  <manifest>\n");
     opf.push_str(
         "    <item id=\"ncx\" href=\"toc.ncx\" media-type=\"application/x-dtbncx+xml\"/>\n",
@@ -718,8 +1561,8 @@ fn generate_opf(
         // Get relative path from OEBPS/
         let href = item.href.strip_prefix("OEBPS/").unwrap_or(&item.href);
         let properties = match item.properties {
-            Some(p) => format!(" properties=\"{p}\""),
-            None => String::new(),
+            Some(p) if properties => format!(" properties=\"{p}\""),
+            _ => String::new(),
         };
         opf.push_str(&format!(
             "    <item id=\"{}\" href=\"{}\" media-type=\"{}\"{}/>\n",
@@ -730,24 +1573,14 @@ fn generate_opf(
         ));
     }
     opf.push_str("  </manifest>\n");
+}
 
-    // Spine. Preserve the global reading direction (RTL books) when the
-    // source declared one and it isn't the default.
-    match metadata.page_progression_direction.as_deref() {
-        Some(dir @ ("rtl" | "ltr")) => {
-            opf.push_str(&format!(
-                "  <spine toc=\"ncx\" page-progression-direction=\"{dir}\">\n"
-            ));
-        }
-        _ => opf.push_str("  <spine toc=\"ncx\">\n"),
-    }
+/// Write the spine's itemrefs and close it.
+fn push_spine_items(opf: &mut String, spine_refs: &[String]) {
     for id in spine_refs {
         opf.push_str(&format!("    <itemref idref=\"{}\"/>\n", escape_xml(id)));
     }
     opf.push_str("  </spine>\n");
-
-    opf.push_str("</package>\n");
-    opf
 }
 
 /// Generate toc.ncx from TOC entries.
@@ -838,8 +1671,9 @@ fn write_nav_points(ncx: &mut String, entries: &[TocEntry], play_order: &mut usi
     }
 }
 
-/// Generate the EPUB 3 nav document (`nav.xhtml`) from TOC entries.
-fn generate_nav(title: &str, toc: &[TocEntry]) -> String {
+/// Generate the EPUB 3 nav document (`nav.xhtml`) from TOC entries, plus a
+/// landmarks nav when the book has any.
+fn generate_nav(title: &str, toc: &[TocEntry], landmarks: &[Landmark]) -> String {
     let mut doc = String::new();
     doc.push_str(
         "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
@@ -852,10 +1686,117 @@ fn generate_nav(title: &str, toc: &[TocEntry]) -> String {
     if !toc.is_empty() {
         write_nav_list(&mut doc, toc, 2);
     }
-    doc.push_str("  </nav>\n</body>\n</html>\n");
+    doc.push_str("  </nav>\n");
+    write_landmarks_nav(&mut doc, landmarks);
+    doc.push_str("</body>\n</html>\n");
     doc
 }
 
+/// Write the `<nav epub:type="landmarks">` section. Landmarks without a
+/// target are dropped, as are repeats of a type already listed (the nav spec
+/// allows one entry per type/target pair, and readers use the first).
+fn write_landmarks_nav(doc: &mut String, landmarks: &[Landmark]) {
+    let mut seen = std::collections::HashSet::new();
+    let entries: Vec<(&str, &Landmark)> = landmarks
+        .iter()
+        .filter(|lm| !lm.href.is_empty())
+        .map(|lm| (landmark_epub_type(lm.landmark_type), lm))
+        .filter(|(epub_type, _)| seen.insert(*epub_type))
+        .collect();
+    if entries.is_empty() {
+        return;
+    }
+    doc.push_str("  <nav epub:type=\"landmarks\" id=\"landmarks\" hidden=\"\">\n    <ol>\n");
+    for (epub_type, lm) in entries {
+        doc.push_str("      <li><a epub:type=\"");
+        doc.push_str(epub_type);
+        doc.push_str("\" href=\"");
+        doc.push_str(&escape_xml(&lm.href));
+        doc.push_str("\">");
+        doc.push_str(&escape_xml(&lm.label));
+        doc.push_str("</a></li>\n");
+    }
+    doc.push_str("    </ol>\n  </nav>\n");
+}
+
+/// `landmarks`, plus a "bodymatter" entry when none starts the content, so
+/// readers can skip the front matter. It points at the first TOC entry
+/// outside the files landmarked as cover, title page, contents or front
+/// matter, or failing that the first such spine file.
+fn with_bodymatter<S: AsRef<str>>(
+    mut landmarks: Vec<Landmark>,
+    toc: &[TocEntry],
+    spine_hrefs: &[S],
+) -> Vec<Landmark> {
+    if landmarks
+        .iter()
+        .any(|lm| landmark_epub_type(lm.landmark_type) == "bodymatter")
+    {
+        return landmarks;
+    }
+    let file = |href: &str| href.split('#').next().unwrap_or_default().to_string();
+    let front: HashSet<String> = landmarks
+        .iter()
+        .filter(|lm| {
+            matches!(
+                lm.landmark_type,
+                LandmarkType::Cover
+                    | LandmarkType::TitlePage
+                    | LandmarkType::Toc
+                    | LandmarkType::FrontMatter
+            )
+        })
+        .map(|lm| file(&lm.href))
+        .collect();
+    let is_content = |href: &str| !href.is_empty() && !front.contains(&file(href));
+
+    fn first_entry<'a>(
+        entries: &'a [TocEntry],
+        is_content: &dyn Fn(&str) -> bool,
+    ) -> Option<&'a str> {
+        entries.iter().find_map(|entry| {
+            Some(entry.href.as_str())
+                .filter(|href| is_content(href))
+                .or_else(|| first_entry(&entry.children, is_content))
+        })
+    }
+    let start = first_entry(toc, &is_content).or_else(|| {
+        spine_hrefs
+            .iter()
+            .map(AsRef::as_ref)
+            .find(|href| is_content(href))
+    });
+    if let Some(href) = start {
+        landmarks.push(Landmark {
+            landmark_type: LandmarkType::BodyMatter,
+            href: href.to_string(),
+            label: "Start of Content".to_string(),
+        });
+    }
+    landmarks
+}
+
+/// The EPUB 3 structural semantics term for a landmark. There's no term for
+/// "start reading", so it's listed as where the body matter begins.
+pub(crate) fn landmark_epub_type(landmark_type: LandmarkType) -> &'static str {
+    match landmark_type {
+        LandmarkType::Cover => "cover",
+        LandmarkType::TitlePage => "titlepage",
+        LandmarkType::Toc => "toc",
+        LandmarkType::StartReading | LandmarkType::BodyMatter => "bodymatter",
+        LandmarkType::FrontMatter => "frontmatter",
+        LandmarkType::BackMatter => "backmatter",
+        LandmarkType::Acknowledgements => "acknowledgments",
+        LandmarkType::Bibliography => "bibliography",
+        LandmarkType::Glossary => "glossary",
+        LandmarkType::Index => "index",
+        LandmarkType::Preface => "preface",
+        LandmarkType::Endnotes => "endnotes",
+        LandmarkType::Loi => "loi",
+        LandmarkType::Lot => "lot",
+    }
+}
+
 fn write_nav_list(doc: &mut String, entries: &[TocEntry], indent: usize) {
     if indent > crate::util::MAX_TREE_DEPTH {
         return;
@@ -892,6 +1833,29 @@ fn write_nav_list(doc: &mut String, entries: &[TocEntry], indent: usize) {
     doc.push_str("</ol>\n");
 }
 
+/// Point a book-global href (TOC entry, landmark) at a renamed chapter.
+fn rename_href(href: &str, renames: &HashMap<String, String>) -> String {
+    let (file, frag) = match href.split_once('#') {
+        Some((file, frag)) => (file, Some(frag)),
+        None => (href, None),
+    };
+    match (renames.get(file), frag) {
+        (Some(renamed), Some(frag)) => format!("{renamed}#{frag}"),
+        (Some(renamed), None) => renamed.clone(),
+        (None, _) => href.to_string(),
+    }
+}
+
+fn rename_toc(toc: &[TocEntry], renames: &HashMap<String, String>) -> Vec<TocEntry> {
+    toc.iter()
+        .map(|entry| TocEntry {
+            href: rename_href(&entry.href, renames),
+            children: rename_toc(&entry.children, renames),
+            ..entry.clone()
+        })
+        .collect()
+}
+
 /// Sanitize a path for use in ZIP (remove leading slashes, normalize).
 fn sanitize_path(path: &str) -> String {
     path.trim_start_matches('/')
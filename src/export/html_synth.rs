@@ -22,6 +22,7 @@
 use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
 
+use super::css_gen::generate_css_all;
 use crate::model::{Chapter, NodeId, Role};
 use crate::style::StyleId;
 
@@ -86,6 +87,11 @@ fn synthesize_html_with_resolver<R: StyleResolver>(
     resolver: &R,
     math_form: MathForm,
 ) -> SynthesisResult {
+    let footnote_ids = ir
+        .iter_dfs()
+        .filter(|&id| ir.node(id).is_some_and(|n| n.role == Role::Footnote))
+        .filter_map(|id| ir.semantics.id(id))
+        .collect();
     let mut ctx = SynthesisContext {
         out: String::new(),
         assets: HashSet::new(),
@@ -93,6 +99,7 @@ fn synthesize_html_with_resolver<R: StyleResolver>(
         resolver,
         indent_level: 0,
         math_form,
+        footnote_ids,
     };
 
     // Walk children of root (skip the root node itself)
@@ -125,7 +132,7 @@ pub fn synthesize_xhtml_document(
     stylesheet_href: Option<&str>,
 ) -> SynthesisResult {
     let body_result = synthesize_html(ir, style_map);
-    synthesize_xhtml_from_body(body_result, title, stylesheet_href)
+    synthesize_xhtml_from_body(body_result, title, stylesheet_href, None)
 }
 
 /// Synthesize a complete XHTML document like [`synthesize_xhtml_document`],
@@ -154,13 +161,23 @@ pub fn synthesize_xhtml_document_with_class_list_math(
     math_form: MathForm,
 ) -> SynthesisResult {
     let body_result = synthesize_html_with_class_list_math(ir, class_list, math_form);
-    synthesize_xhtml_from_body(body_result, title, stylesheet_href)
+    synthesize_xhtml_from_body(body_result, title, stylesheet_href, None)
+}
+
+/// Synthesize a self-contained XHTML document for one chapter: the body
+/// as the EPUB writer emits it, with CSS generated from the chapter's own
+/// style pool in an inline `<style>` element instead of a stylesheet link.
+pub fn synthesize_standalone_xhtml(ir: &Chapter, title: &str) -> SynthesisResult {
+    let css = generate_css_all(&ir.styles);
+    let body_result = synthesize_html(ir, &css.class_map);
+    synthesize_xhtml_from_body(body_result, title, None, Some(&css.stylesheet))
 }
 
 fn synthesize_xhtml_from_body(
     body_result: SynthesisResult,
     title: &str,
     stylesheet_href: Option<&str>,
+    inline_css: Option<&str>,
 ) -> SynthesisResult {
     let mut doc = String::new();
 
@@ -184,6 +201,12 @@ fn synthesize_xhtml_from_body(
         doc.push_str("\"/>\n");
     }
 
+    if let Some(css) = inline_css.filter(|css| !css.is_empty()) {
+        doc.push_str("  <style>\n");
+        escape_xml_into(&mut doc, css);
+        doc.push_str("  </style>\n");
+    }
+
     doc.push_str("</head>\n<body>\n");
     doc.push_str(&body_result.body);
     doc.push_str("</body>\n</html>\n");
@@ -202,6 +225,9 @@ struct SynthesisContext<'a, R: StyleResolver> {
     resolver: &'a R,
     indent_level: usize,
     math_form: MathForm,
+    /// Ids of this chapter's `Role::Footnote` nodes, so links to them can
+    /// be marked as note references.
+    footnote_ids: HashSet<&'a str>,
 }
 
 impl<R: StyleResolver> SynthesisContext<'_, R> {
@@ -212,6 +238,39 @@ impl<R: StyleResolver> SynthesisContext<'_, R> {
     }
 }
 
+/// The `epub:type` implied by a node's role, ARIA role, or link target.
+fn default_epub_type<R: StyleResolver>(
+    ctx: &SynthesisContext<'_, R>,
+    id: NodeId,
+    role: Role,
+) -> Option<&'static str> {
+    match ctx.ir.semantics.aria_role(id) {
+        Some("doc-noteref") => return Some("noteref"),
+        Some("doc-footnote") => return Some("footnote"),
+        Some("doc-endnote") => return Some("endnote"),
+        _ => {}
+    }
+    match role {
+        Role::Footnote => Some("footnote"),
+        Role::Link => {
+            let target = ctx.ir.semantics.href(id)?.strip_prefix('#')?;
+            ctx.footnote_ids.contains(target).then_some("noteref")
+        }
+        _ => None,
+    }
+}
+
+/// Emit the fallback content (children) of a `Role::Math` node.
+fn walk_math_fallback<R: StyleResolver>(
+    id: NodeId,
+    ctx: &mut SynthesisContext<'_, R>,
+    depth: usize,
+) {
+    for child_id in ctx.ir.children(id) {
+        walk_node(child_id, ctx, depth + 1);
+    }
+}
+
 /// Walk a node and emit its HTML.
 fn walk_node<R: StyleResolver>(id: NodeId, ctx: &mut SynthesisContext<'_, R>, depth: usize) {
     if depth > crate::util::MAX_TREE_DEPTH {
@@ -226,21 +285,36 @@ fn walk_node<R: StyleResolver>(id: NodeId, ctx: &mut SynthesisContext<'_, R>, de
 
     // Math serializes in the target's native form: MathML for EPUB, the
     // Unicode linearization for KF8/MOBI (whose renderers stack raw MathML
-    // one token per line). An anchor id on it stays addressable.
+    // one token per line). An anchor id on it stays addressable. Children
+    // are the `<epub:switch>` fallback: EPUB rebuilds the switch around
+    // them, KF8 shows them in place of the linearization.
     if role == Role::Math {
         if let Some(math) = ctx.ir.math.get(&id) {
-            let rendered = match ctx.math_form {
-                MathForm::MathMl => crate::math::mathml::to_mathml(math),
-                MathForm::Text => escape_xml(&math.to_text()),
-            };
+            let has_fallback = node.first_child.is_some();
             if let Some(anchor) = ctx.ir.semantics.id(id) {
                 ctx.out.push_str("<span id=\"");
                 ctx.out.push_str(&escape_xml(anchor));
                 ctx.out.push_str("\">");
-                ctx.out.push_str(&rendered);
+            }
+            match (ctx.math_form, has_fallback) {
+                (MathForm::MathMl, false) => {
+                    ctx.out.push_str(&crate::math::mathml::to_mathml(math));
+                }
+                (MathForm::MathMl, true) => {
+                    ctx.out
+                        .push_str("<epub:switch><epub:case required-namespace=\"");
+                    ctx.out.push_str(crate::math::mathml::MATHML_NS);
+                    ctx.out.push_str("\">");
+                    ctx.out.push_str(&crate::math::mathml::to_mathml(math));
+                    ctx.out.push_str("</epub:case><epub:default>");
+                    walk_math_fallback(id, ctx, depth);
+                    ctx.out.push_str("</epub:default></epub:switch>");
+                }
+                (MathForm::Text, false) => ctx.out.push_str(&escape_xml(&math.to_text())),
+                (MathForm::Text, true) => walk_math_fallback(id, ctx, depth),
+            }
+            if ctx.ir.semantics.id(id).is_some() {
                 ctx.out.push_str("</span>");
-            } else {
-                ctx.out.push_str(&rendered);
             }
         }
         return;
@@ -248,7 +322,8 @@ fn walk_node<R: StyleResolver>(id: NodeId, ctx: &mut SynthesisContext<'_, R>, de
 
     // Handle leaf text nodes (Text role with text content, no children)
     if role == Role::Text && !node.text.is_empty() && node.first_child.is_none() {
-        let text = ctx.ir.text(node.text);
+        // Stray control characters and noncharacters are not valid XML.
+        let text = crate::util::clean_unexpected_chars(ctx.ir.text(node.text));
         // A text node carrying an anchor id must stay addressable: KFX
         // anchors land on text nodes, and emitting bare text would leave
         // every internal link to them (`chapter_5.xhtml#a19F`) dangling.
@@ -269,7 +344,7 @@ fn walk_node<R: StyleResolver>(id: NodeId, ctx: &mut SynthesisContext<'_, R>, de
                 escape_xml_into(&mut ctx.out, segment);
             }
         } else {
-            escape_xml_into(&mut ctx.out, text);
+            escape_xml_into(&mut ctx.out, &text);
         }
         if anchor.is_some() {
             ctx.out.push_str("</span>");
@@ -285,6 +360,16 @@ fn walk_node<R: StyleResolver>(id: NodeId, ctx: &mut SynthesisContext<'_, R>, de
         tag = "th";
     }
 
+    // A table's caption is <caption>; <figcaption> only belongs in <figure>.
+    if role == Role::Caption
+        && node
+            .parent
+            .and_then(|parent| ctx.ir.node(parent))
+            .is_some_and(|parent| parent.role == Role::Table)
+    {
+        tag = "caption";
+    }
+
     // <p> and headings cannot legally contain block-level children in
     // (X)HTML; KFX containers routinely import as Paragraph with nested
     // headings/paragraphs. Demote to <div> — presentation comes from the
@@ -331,6 +416,8 @@ fn walk_node<R: StyleResolver>(id: NodeId, ctx: &mut SynthesisContext<'_, R>, de
         attrs.push_str(" alt=\"");
         escape_xml_into(&mut attrs, alt);
         attrs.push('"');
+    } else if ctx.ir.semantics.is_decorative(id) {
+        attrs.push_str(" alt=\"\"");
     }
     if let Some(title) = ctx.ir.semantics.title(id) {
         attrs.push_str(" title=\"");
@@ -338,9 +425,12 @@ fn walk_node<R: StyleResolver>(id: NodeId, ctx: &mut SynthesisContext<'_, R>, de
         attrs.push('"');
     }
     if let Some(lang) = ctx.ir.semantics.lang(id) {
-        attrs.push_str(" xml:lang=\"");
-        escape_xml_into(&mut attrs, lang);
-        attrs.push('"');
+        // XHTML5 wants both, with the same value.
+        for name in ["lang", "xml:lang"] {
+            write!(attrs, " {name}=\"").unwrap();
+            escape_xml_into(&mut attrs, lang);
+            attrs.push('"');
+        }
     }
     // Emit start attribute for ordered lists
     if role == Role::OrderedList
@@ -348,6 +438,11 @@ fn walk_node<R: StyleResolver>(id: NodeId, ctx: &mut SynthesisContext<'_, R>, de
     {
         write!(attrs, " start=\"{}\"", start).unwrap();
     }
+    if role == Role::ListItem
+        && let Some(value) = ctx.ir.semantics.list_value(id)
+    {
+        write!(attrs, " value=\"{}\"", value).unwrap();
+    }
     // Emit rowspan/colspan for table cells
     if role == Role::TableCell {
         if let Some(rowspan) = ctx.ir.semantics.row_span(id) {
@@ -356,13 +451,33 @@ fn walk_node<R: StyleResolver>(id: NodeId, ctx: &mut SynthesisContext<'_, R>, de
         if let Some(colspan) = ctx.ir.semantics.col_span(id) {
             write!(attrs, " colspan=\"{}\"", colspan).unwrap();
         }
+        if tag == "th"
+            && let Some(scope) = ctx.ir.semantics.scope(id)
+        {
+            attrs.push_str(" scope=\"");
+            escape_xml_into(&mut attrs, scope);
+            attrs.push('"');
+        }
+        if let Some(headers) = ctx.ir.semantics.headers(id) {
+            attrs.push_str(" headers=\"");
+            escape_xml_into(&mut attrs, headers);
+            attrs.push('"');
+        }
     }
 
     // Preserve semantic markers captured on import: epub:type (footnote /
     // noteref / pagebreak — drives reader footnote popups and page lists),
     // ARIA role, and <time datetime>. Without these the round trip demotes
     // EPUB3 footnotes to plain links and loses pagebreak semantics.
-    if let Some(epub_type) = ctx.ir.semantics.epub_type(id) {
+    // Footnotes and the links to them get their EPUB 3 types even when the
+    // source didn't declare any (IR built by hand, Markdown, ARIA-only
+    // markup), so readers can show them as popups.
+    if let Some(epub_type) = ctx
+        .ir
+        .semantics
+        .epub_type(id)
+        .or_else(|| default_epub_type(ctx, id, role))
+    {
         attrs.push_str(" epub:type=\"");
         escape_xml_into(&mut attrs, epub_type);
         attrs.push('"');
@@ -371,6 +486,8 @@ fn walk_node<R: StyleResolver>(id: NodeId, ctx: &mut SynthesisContext<'_, R>, de
         attrs.push_str(" role=\"");
         escape_xml_into(&mut attrs, aria_role);
         attrs.push('"');
+    } else if ctx.ir.semantics.is_decorative(id) {
+        attrs.push_str(" role=\"presentation\"");
     }
     // datetime is only ever set on <time> elements, so emitting it wherever
     // present reproduces it on the right element without a role check.
@@ -379,6 +496,12 @@ fn walk_node<R: StyleResolver>(id: NodeId, ctx: &mut SynthesisContext<'_, R>, de
         escape_xml_into(&mut attrs, datetime);
         attrs.push('"');
     }
+    // Likewise cite, which is only captured from <blockquote> and <q>.
+    if let Some(cite) = ctx.ir.semantics.cite(id) {
+        attrs.push_str(" cite=\"");
+        escape_xml_into(&mut attrs, cite);
+        attrs.push('"');
+    }
 
     // Emit opening tag
     if is_block {
@@ -497,6 +620,7 @@ fn role_to_tag(role: Role) -> (&'static str, bool, bool) {
 
         // Inline elements
         Role::Inline => ("span", false, false),
+        Role::Quote => ("q", false, false),
         Role::Link => ("a", false, false),
 
         // Math is re-serialized verbatim in walk_node; this arm exists only
@@ -566,6 +690,30 @@ mod tests {
         assert!(result.body.contains("</p>"));
     }
 
+    #[test]
+    fn footnotes_and_their_references_get_epub_types() {
+        // Hand-built IR has no epub:type; the roles alone must be enough for
+        // readers to show footnote popups.
+        let mut chapter = Chapter::new();
+        let a = chapter.alloc_node(Node::new(Role::Link));
+        chapter.append_child(NodeId::ROOT, a);
+        chapter.semantics.set_href(a, "#fn1");
+        let note = chapter.alloc_node(Node::new(Role::Footnote));
+        chapter.append_child(NodeId::ROOT, note);
+        chapter.semantics.set_id(note, "fn1");
+        let other = chapter.alloc_node(Node::new(Role::Link));
+        chapter.append_child(NodeId::ROOT, other);
+        chapter.semantics.set_href(other, "#elsewhere");
+
+        let body = synthesize_html(&chapter, &HashMap::new()).body;
+        assert!(
+            body.contains("id=\"fn1\" epub:type=\"footnote\"")
+                || body.contains("epub:type=\"footnote\" id=\"fn1\""),
+            "{body}"
+        );
+        assert_eq!(body.matches("epub:type=\"noteref\"").count(), 1, "{body}");
+    }
+
     #[test]
     fn epub_type_and_role_are_preserved() {
         // Semantic markers captured on import must survive synthesis, or
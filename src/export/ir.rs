@@ -0,0 +1,36 @@
+//! IR dump exporter - I/O orchestration for [`Format::Ir`](crate::Format::Ir).
+//!
+//! The format itself (writing and parsing) lives in [`crate::ir`].
+
+use std::io::{Seek, Write};
+
+use crate::model::Book;
+
+use super::{Exporter, Progress, ProgressEvent};
+
+/// Exporter for the textual IR dump.
+#[derive(Debug, Clone, Default)]
+pub struct IrExporter;
+
+impl IrExporter {
+    /// Create a new IrExporter.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Exporter for IrExporter {
+    fn export<W: Write + Seek>(&self, book: &Book, writer: &mut W) -> crate::Result<()> {
+        self.export_with_progress(book, writer, &mut |_| {})
+    }
+
+    fn export_with_progress<W: Write + Seek>(
+        &self,
+        book: &Book,
+        writer: &mut W,
+        progress: &mut dyn FnMut(ProgressEvent),
+    ) -> crate::Result<()> {
+        let mut progress = Progress::new(progress, book.spine().len());
+        crate::ir::write_book(book, writer, &mut progress)
+    }
+}
@@ -60,7 +60,17 @@ pub(super) fn build_book_metadata_fragment(
     container_id: &str,
     ctx: &ExportContext,
 ) -> KfxFragment {
-    let meta = book.metadata();
+    let overridden;
+    let meta = match ctx.language_override() {
+        Some(language) => {
+            overridden = crate::model::Metadata {
+                language: language.to_string(),
+                ..book.metadata().clone()
+            };
+            &overridden
+        }
+        None => book.metadata(),
+    };
 
     // Build metadata context with transformed values
     // Cover path in metadata may not match the registered resource path exactly.
@@ -269,10 +279,23 @@ pub(super) fn build_document_data_fragment(ctx: &ExportContext) -> KfxFragment {
     // Calculate max_id from context (highest EID used)
     let max_id = ctx.max_eid();
 
+    // Page-turn direction follows the OPF's page-progression-direction, or
+    // the book's vertical-rl body text when the OPF doesn't declare one.
+    let direction = if ctx.is_rtl() {
+        KfxSymbol::Rtl
+    } else {
+        KfxSymbol::Ltr
+    };
+    let writing_mode = match ctx.writing_mode() {
+        WritingMode::HorizontalTb => KfxSymbol::HorizontalTb,
+        WritingMode::VerticalRl => KfxSymbol::VerticalRl,
+        WritingMode::VerticalLr => KfxSymbol::VerticalLr,
+    };
+
     let document_data = IonValue::Struct(vec![
         (
             KfxSymbol::Direction as u64,
-            IonValue::Symbol(KfxSymbol::Ltr as u64),
+            IonValue::Symbol(direction as u64),
         ),
         (
             KfxSymbol::ColumnCount as u64,
@@ -290,7 +313,7 @@ pub(super) fn build_document_data_fragment(ctx: &ExportContext) -> KfxFragment {
         ),
         (
             KfxSymbol::WritingMode as u64,
-            IonValue::Symbol(KfxSymbol::HorizontalTb as u64),
+            IonValue::Symbol(writing_mode as u64),
         ),
         (
             KfxSymbol::Selection as u64,
@@ -20,7 +20,7 @@ use survey::*;
 use std::collections::{BTreeSet, HashMap};
 use std::io::{self, Seek, Write};
 
-use crate::export::Exporter;
+use crate::export::{Exporter, Progress, ProgressEvent};
 use crate::import::ChapterId;
 use crate::kfx::auxiliary::build_auxiliary_data_fragment;
 use crate::kfx::context::{ExportContext, LandmarkTarget};
@@ -43,25 +43,73 @@ use crate::kfx::transforms::format_to_kfx_symbol;
 use crate::model::{
     AnchorTarget, Book, Chapter, GlobalNodeId, LandmarkType, NodeId, ResolvedLinks, Role,
 };
+use crate::style::{Length, WritingMode};
 use crate::util::detect_media_format;
 
+/// Configuration for KFX export.
+#[derive(Debug, Clone)]
+pub struct KfxConfig {
+    /// If true, disable size-based content chunking so each chapter's text
+    /// is emitted as one content fragment (subject to a hard size cap).
+    /// Useful for diagnosing chunk-boundary bugs; default output follows
+    /// the reference chunker.
+    pub single_section: bool,
+    /// Content language to declare instead of the book's `dc:language`
+    /// (e.g. `"en-GB"`), for books whose metadata names the wrong language.
+    /// Kindle picks hyphenation and text-to-speech voices from it.
+    /// Languages set on individual elements (`lang` attributes) are kept.
+    pub language_override: Option<String>,
+    /// If true (the default), strip control characters and Unicode
+    /// noncharacters from text content; Kindle renders them as boxes or
+    /// rejects the section. Disable to see exactly what the source text
+    /// carries when diagnosing differences.
+    pub clean_content: bool,
+}
+
+impl Default for KfxConfig {
+    fn default() -> Self {
+        Self {
+            single_section: false,
+            language_override: None,
+            clean_content: true,
+        }
+    }
+}
+
 /// KFX format exporter.
 ///
 /// Converts books to Amazon's KFX format for Kindle devices.
 #[derive(Default)]
-pub struct KfxExporter;
+pub struct KfxExporter {
+    config: KfxConfig,
+}
 
 impl KfxExporter {
     /// Create a new KfxExporter.
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Configure the exporter with custom settings.
+    pub fn with_config(mut self, config: KfxConfig) -> Self {
+        self.config = config;
+        self
     }
 }
 
 impl Exporter for KfxExporter {
     fn export<W: Write + Seek>(&self, book: &Book, writer: &mut W) -> crate::Result<()> {
+        self.export_with_progress(book, writer, &mut |_| {})
+    }
+
+    fn export_with_progress<W: Write + Seek>(
+        &self,
+        book: &Book,
+        writer: &mut W,
+        progress: &mut dyn FnMut(ProgressEvent),
+    ) -> crate::Result<()> {
         // Build the KFX container
-        let data = build_kfx_container(book)?;
+        let data = build_kfx_container(book, &self.config, progress)?;
         writer.write_all(&data)?;
         Ok(())
     }
@@ -72,13 +120,23 @@ impl Exporter for KfxExporter {
 /// This follows a strict Two-Pass architecture:
 /// - Pass 1 (Survey): Walk IR, build position map, intern symbols - NO ION GENERATION
 /// - Pass 2 (Synthesis): Generate Ion using pre-computed positions
-fn build_kfx_container(book: &Book) -> crate::Result<Vec<u8>> {
+///
+/// Chapters are reported to `progress` as Pass 2 synthesizes them.
+fn build_kfx_container(
+    book: &Book,
+    config: &KfxConfig,
+    progress: &mut dyn FnMut(ProgressEvent),
+) -> crate::Result<Vec<u8>> {
+    let mut progress = Progress::new(progress, book.spine().len());
     // Seed the container ID from the book's identity so the same book always
     // exports byte-identically; the title is included so books without an
     // identifier still diverge from each other.
     let meta = book.metadata();
     let container_id = generate_container_id(&format!("{}\n{}", meta.identifier, meta.title));
     let mut ctx = ExportContext::new();
+    ctx.set_single_section(config.single_section);
+    ctx.set_language_override(config.language_override.clone());
+    ctx.set_clean_content(config.clean_content);
 
     // ========================================================================
     // PASS 1: SURVEY (Read-Only / State Accumulation)
@@ -86,7 +144,7 @@ fn build_kfx_container(book: &Book) -> crate::Result<Vec<u8>> {
     // NO ION GENERATION HERE!
     // ========================================================================
 
-    let (standalone_cover_path, spine_info) = survey_book(book, &mut ctx)?;
+    let (standalone_cover_path, spine_info) = survey_book(book, &mut ctx, &mut progress)?;
 
     // Body-size normalization: the text-dominant font size renders at 1rem
     // (the user's device font-size setting), like reference output. Must be
@@ -134,6 +192,7 @@ fn build_kfx_container(book: &Book) -> crate::Result<Vec<u8>> {
         &spine_info,
         standalone_cover_path.as_deref(),
         &mut ctx,
+        &mut progress,
     );
 
     // Fix landmark IDs to use storyline content IDs instead of section IDs
@@ -168,7 +227,7 @@ fn build_kfx_container(book: &Book) -> crate::Result<Vec<u8>> {
     );
 
     // 2j. Resource fragments (images, fonts, etc.)
-    append_resource_fragments(book, &mut fragments, &mut ctx);
+    let fonts = append_resource_fragments(book, &mut fragments, &mut ctx, &mut progress);
 
     // 2j-2. Font entity fragments ($262)
     // These link font_family names to resource locations (from @font-face rules)
@@ -193,6 +252,11 @@ fn build_kfx_container(book: &Book) -> crate::Result<Vec<u8>> {
         .collect();
     fragments
         .retain(|f| f.ftype != KfxSymbol::Bcrawfont as u64 || referenced_fonts.contains(&f.fid));
+    for (fid, asset_path) in &fonts {
+        if referenced_fonts.contains(fid) {
+            progress.asset_written(asset_path);
+        }
+    }
 
     // Rebuild content_features ($585) now that the resource pass has
     // determined the media-derived flags (HDV images, JPEG restart markers);
@@ -263,8 +327,19 @@ type SpineInfo = Vec<(ChapterId, String)>;
 /// Pass 1: survey the book, populating the export context.
 ///
 /// Returns the standalone cover path (if any) and the spine info
-/// (chapter ID, short section name) pairs.
-fn survey_book(book: &Book, ctx: &mut ExportContext) -> crate::Result<(Option<String>, SpineInfo)> {
+/// (chapter ID, short section name) pairs. Each chapter is reported to
+/// `progress` as prepared once surveyed.
+fn survey_book(
+    book: &Book,
+    ctx: &mut ExportContext,
+    progress: &mut Progress<'_>,
+) -> crate::Result<(Option<String>, SpineInfo)> {
+    ctx.page_progression_rtl = match book.metadata().page_progression_direction.as_deref() {
+        Some("rtl") => Some(true),
+        Some("ltr") => Some(false),
+        _ => None,
+    };
+
     // Check if we need a standalone cover section
     let standalone_cover_path = detect_standalone_cover(book);
 
@@ -285,7 +360,7 @@ fn survey_book(book: &Book, ctx: &mut ExportContext) -> crate::Result<(Option<St
     register_link_targets(book, &spine_info, &resolved, ctx)?;
 
     // 1c. Survey each chapter: assign fragment IDs, build position map
-    let source_to_chapter = survey_spine_chapters(book, &spine_info, ctx);
+    let source_to_chapter = survey_spine_chapters(book, &spine_info, ctx, progress);
 
     // 1d. Resolve landmarks to fragment IDs
     resolve_landmarks(book, &spine_info, &source_to_chapter, &resolved, ctx);
@@ -366,10 +441,11 @@ fn survey_spine_chapters(
     book: &Book,
     spine_info: &[(ChapterId, String)],
     ctx: &mut ExportContext,
+    progress: &mut Progress<'_>,
 ) -> HashMap<String, ChapterId> {
     let mut source_to_chapter: HashMap<String, ChapterId> = HashMap::new();
 
-    for (chapter_id, section_name) in spine_info {
+    for (idx, (chapter_id, section_name)) in spine_info.iter().enumerate() {
         // Register section name as symbol, keyed to its chapter so the
         // position map can pair them even if a later chapter fails to load.
         let _section_id = ctx.register_spine_section(section_name, *chapter_id);
@@ -391,6 +467,7 @@ fn survey_spine_chapters(
             ctx.position_map.reserve(chapter.node_count());
             survey_chapter(&chapter, *chapter_id, &source_path, ctx);
         }
+        progress.chapter_prepared(idx);
     }
 
     source_to_chapter
@@ -496,6 +573,7 @@ fn build_spine_entities(
     spine_info: &[(ChapterId, String)],
     standalone_cover_path: Option<&str>,
     ctx: &mut ExportContext,
+    progress: &mut Progress<'_>,
 ) -> (Vec<KfxFragment>, Vec<KfxFragment>, Vec<KfxFragment>) {
     let mut section_fragments = Vec::new();
     let mut storyline_fragments = Vec::new();
@@ -520,13 +598,21 @@ fn build_spine_entities(
         }
     }
 
-    for (chapter_id, section_name) in spine_info {
+    let mut image_sizes = HashMap::new();
+    for (idx, (chapter_id, section_name)) in spine_info.iter().enumerate() {
+        progress.chapter_started(idx);
         if let Ok(chapter) = book.load_chapter_cached(*chapter_id) {
             // Set up chapter-start anchor before generating content
             ctx.begin_chapter_export(*chapter_id);
-
-            let (section, storyline) =
-                build_chapter_entities_grouped(&chapter, *chapter_id, section_name, ctx);
+            progress.characters_removed(idx, ctx.removed_chars(chapter.text_buffer()));
+
+            let sized = size_images_intrinsically(book, &chapter, &mut image_sizes);
+            let (section, storyline) = build_chapter_entities_grouped(
+                sized.as_ref().unwrap_or(&chapter),
+                *chapter_id,
+                section_name,
+                ctx,
+            );
             section_fragments.push(section);
             storyline_fragments.push(storyline);
 
@@ -543,6 +629,7 @@ fn build_spine_entities(
                 }
             }
         }
+        progress.chapter_finished(idx);
     }
 
     // Text accumulated across all chapters; emit the global content chunks.
@@ -551,6 +638,58 @@ fn build_spine_entities(
     (section_fragments, storyline_fragments, content_fragments)
 }
 
+/// Copy of `chapter` whose images with neither a CSS width nor height are
+/// given their intrinsic pixel width (capped at the page width), or `None`
+/// when no image needs it. Without a width the reader has no size hint for
+/// the image. `sizes` caches dimensions by asset path across chapters.
+fn size_images_intrinsically(
+    book: &Book,
+    chapter: &Chapter,
+    sizes: &mut HashMap<String, Option<(u32, u32)>>,
+) -> Option<Chapter> {
+    let unsized_images: Vec<(NodeId, u32)> = chapter
+        .iter_dfs()
+        .filter_map(|id| {
+            let node = chapter.node(id)?;
+            let style = chapter.styles.get(node.style)?;
+            if node.role != Role::Image
+                || style.width != Length::Auto
+                || style.height != Length::Auto
+            {
+                return None;
+            }
+            let src = chapter.semantics.src(id)?;
+            let size = *sizes.entry(src.to_string()).or_insert_with(|| {
+                book.load_asset(src)
+                    .ok()
+                    .and_then(|data| crate::util::image_dimensions(&data))
+            });
+            size.filter(|&(width, _)| width > 0)
+                .map(|(width, _)| (id, width))
+        })
+        .collect();
+    if unsized_images.is_empty() {
+        return None;
+    }
+
+    let mut chapter = chapter.clone();
+    for (id, width) in unsized_images {
+        let Some(style_id) = chapter.node(id).map(|n| n.style) else {
+            continue;
+        };
+        let mut style = chapter.styles.get(style_id).cloned().unwrap_or_default();
+        style.width = Length::Px(width as f32);
+        if style.max_width == Length::Auto {
+            style.max_width = Length::Percent(100.0);
+        }
+        let sized = chapter.styles.intern(style);
+        if let Some(node) = chapter.node_mut(id) {
+            node.style = sized;
+        }
+    }
+    Some(chapter)
+}
+
 /// Append auxiliary data fragments - mark sections as navigation targets.
 ///
 /// Generates one auxiliary_data entity per section.
@@ -571,11 +710,16 @@ fn append_auxiliary_data_fragments(
 /// Append resource fragments (images, fonts, etc.).
 ///
 /// Each resource gets two entities: external_resource (metadata) + bcRawMedia (bytes).
+///
+/// Returns the `(fragment id, asset path)` of every font payload; those are
+/// reported to `progress` once unreferenced fonts have been pruned.
 fn append_resource_fragments(
     book: &Book,
     fragments: &mut Vec<KfxFragment>,
     ctx: &mut ExportContext,
-) {
+    progress: &mut Progress<'_>,
+) -> Vec<(String, String)> {
+    let mut fonts = Vec::new();
     // Images actually referenced from emitted Ion (storyline image elements,
     // cover section templates). Registry membership is not enough: srcs are
     // registered during tokenization even when the element never reaches the
@@ -594,7 +738,9 @@ fn append_resource_fragments(
                 // ends up referencing are pruned after font entities are
                 // built (see build_kfx_container), keeping the two resolution
                 // paths from ever disagreeing.
-                fragments.push(build_font_data_fragment(asset_path, data, ctx));
+                let fragment = build_font_data_fragment(asset_path, data, ctx);
+                fonts.push((fragment.fid.clone(), asset_path.clone()));
+                fragments.push(fragment);
                 continue;
             }
             let referenced = ctx
@@ -610,8 +756,10 @@ fn append_resource_fragments(
             fragments.push(build_external_resource_fragment(asset_path, &data, ctx));
             // bcRawMedia ($417) - the actual bytes (moved, not copied)
             fragments.push(build_resource_fragment(asset_path, data, ctx));
+            progress.asset_written(asset_path);
         }
     }
+    fonts
 }
 
 /// Symbols referenced from already-built fragments, by kind.
@@ -669,7 +817,7 @@ fn collect_used_symbols(fragments: &[KfxFragment]) -> UsedSymbols {
 /// Record media facts that drive conditional content features ($585).
 fn record_media_features(data: &[u8], ctx: &mut ExportContext) {
     if !ctx.has_hdv_image
-        && let Some((width, height)) = crate::util::extract_image_dimensions(data)
+        && let Some((width, height)) = crate::util::image_dimensions(data)
         && (width > 1920 || height > 1920)
     {
         ctx.has_hdv_image = true;
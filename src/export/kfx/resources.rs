@@ -27,7 +27,7 @@ pub(super) fn build_external_resource_fragment(
     fields.push((KfxSymbol::Format as u64, IonValue::Symbol(format_symbol)));
 
     // For images, try to extract dimensions
-    if let Some((width, height)) = crate::util::extract_image_dimensions(data) {
+    if let Some((width, height)) = crate::util::image_dimensions(data) {
         fields.push((KfxSymbol::ResourceWidth as u64, IonValue::Int(width as i64)));
         fields.push((
             KfxSymbol::ResourceHeight as u64,
@@ -85,7 +85,8 @@ pub(super) fn build_font_data_fragment(
 /// Font entities link font_family names (e.g., "cover-Ubuntu") to resource locations.
 /// This enables Kindle to properly render custom fonts.
 pub(super) fn build_font_fragments(book: &Book, ctx: &mut ExportContext) -> Vec<KfxFragment> {
-    use crate::style::{FontStyle, FontWeight};
+    use crate::kfx::style_schema::KfxValue;
+    use crate::style::FontStyle;
 
     let mut fragments = Vec::new();
     let font_faces = book.font_faces();
@@ -123,11 +124,17 @@ pub(super) fn build_font_fragments(book: &Book, ctx: &mut ExportContext) -> Vec<
         // This matches how styles reference fonts and is source-faithful
         let font_family = font_face.font_family.clone();
 
-        // Convert font_weight to KFX symbol
-        let weight_symbol = match font_face.font_weight {
-            FontWeight(w) if w >= 700 => KfxSymbol::Bold,
-            _ => KfxSymbol::Normal,
-        };
+        // Convert font_weight to KFX symbol with the same mapping as
+        // styles, so a 600-weight face is registered as semibold and
+        // matches the text that asks for it.
+        let weight_symbol = crate::kfx::style_schema::StyleSchema::standard()
+            .get_first("font-weight")
+            .and_then(|rule| rule.transform.apply(&font_face.font_weight.0.to_string()))
+            .and_then(|value| match value {
+                KfxValue::Symbol(symbol) => Some(symbol),
+                _ => None,
+            })
+            .unwrap_or(KfxSymbol::Normal);
 
         // Convert font_style to KFX symbol
         let style_symbol = match font_face.font_style {
@@ -335,7 +342,7 @@ mod resource_export_tests {
     #[test]
     fn test_kfx_export_includes_images() {
         let book = Book::open("tests/fixtures/epictetus.epub").unwrap();
-        let data = build_kfx_container(&book).unwrap();
+        let data = build_kfx_container(&book, &KfxConfig::default(), &mut |_| {}).unwrap();
 
         // KFX should be > 400KB (images alone are ~401KB)
         assert!(
@@ -349,7 +356,7 @@ mod resource_export_tests {
     fn test_kfx_asset_roundtrip() {
         // Export EPUB to KFX
         let book = Book::open("tests/fixtures/epictetus.epub").unwrap();
-        let kfx_data = build_kfx_container(&book).unwrap();
+        let kfx_data = build_kfx_container(&book, &KfxConfig::default(), &mut |_| {}).unwrap();
 
         // Write to temp file and re-open
         let temp_path = std::env::temp_dir().join("test_roundtrip.kfx");
@@ -456,7 +463,7 @@ mod anchor_resolution_tests {
     fn test_anchor_entities_created_in_full_export() {
         // Test that anchor entities are actually created during full export
         let book = Book::open("tests/fixtures/epictetus.epub").unwrap();
-        let kfx_data = build_kfx_container(&book).unwrap();
+        let kfx_data = build_kfx_container(&book, &KfxConfig::default(), &mut |_| {}).unwrap();
 
         // Parse the KFX container to find anchor entities
         use crate::kfx::container::{
@@ -18,6 +18,8 @@ pub(super) fn survey_chapter(
     // Begin surveying this chapter (with source path for TOC resolution)
     let _fragment_id = ctx.begin_chapter_survey(chapter_id, source_path);
 
+    ctx.body_writing_modes.push(body_writing_mode(chapter));
+
     // Walk the IR tree
     survey_node(chapter, chapter.root(), (1.0, 1.2), ctx);
 
@@ -25,6 +27,18 @@ pub(super) fn survey_chapter(
     ctx.end_chapter_survey();
 }
 
+/// The writing mode of a chapter's body, read off its first styled
+/// top-level node (body's own style is inherited by every child).
+fn body_writing_mode(chapter: &Chapter) -> WritingMode {
+    chapter
+        .children(chapter.root())
+        .filter_map(|id| chapter.node(id))
+        .find(|node| node.style != crate::style::StyleId::DEFAULT)
+        .and_then(|node| chapter.styles.get(node.style))
+        .map(|style| style.writing_mode)
+        .unwrap_or_default()
+}
+
 /// Recursively survey a node and its children. `inherited` is the nearest
 /// styled ancestor's (absolute font size, line-height in em of its font) —
 /// text leaves often carry the default StyleId while their metrics live on
@@ -94,7 +108,10 @@ pub(super) fn survey_node(
     // Math contributes its readable linearization in Pass 2 only on the
     // text-run path (no math font); the KVG container path emits vector
     // shapes instead. Keep the accounting in step with what Pass 2 emits.
+    // Math with an `<epub:switch>` fallback exports the fallback instead,
+    // which the recursion below surveys.
     if node.role == Role::Math
+        && node.first_child.is_none()
         && !ctx.math_renders_as_container()
         && let Some(math) = chapter.math.get(&node_id)
     {
@@ -105,10 +122,20 @@ pub(super) fn survey_node(
         }
     }
 
+    // Quotations are exported with their marks as text around the content.
+    let marks = (node.role == Role::Quote).then(|| chapter.quote_marks(node_id));
+    if let Some((open, _)) = marks {
+        ctx.advance_text_offset(open.len());
+    }
+
     // Recurse into children
     for child in chapter.children(node_id) {
         survey_node(chapter, child, metrics, ctx);
     }
+
+    if let Some((_, close)) = marks {
+        ctx.advance_text_offset(close.len());
+    }
 }
 
 /// Register link targets from ResolvedLinks with the AnchorRegistry.
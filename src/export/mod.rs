@@ -35,20 +35,26 @@ mod azw3;
 mod css_gen;
 mod epub;
 mod html_synth;
+mod ir;
 mod kfx;
 mod normalize;
+mod progress;
 mod text;
 
 pub use azw3::{Azw3Config, Azw3Exporter};
 pub use css_gen::{CssArtifact, generate_css, generate_css_all};
-pub use epub::{EpubConfig, EpubExporter};
+pub use epub::{ChapterNaming, EpubConfig, EpubExporter, EpubVersion};
 pub use html_synth::{
     MathForm, SynthesisResult, escape_xml, escape_xml_into, synthesize_html,
-    synthesize_html_with_class_list, synthesize_xhtml_document,
+    synthesize_html_with_class_list, synthesize_standalone_xhtml, synthesize_xhtml_document,
     synthesize_xhtml_document_with_class_list, synthesize_xhtml_document_with_class_list_math,
 };
-pub use kfx::KfxExporter;
+pub use ir::IrExporter;
+pub use kfx::{KfxConfig, KfxExporter};
+pub(crate) use normalize::extract_chapter_title;
 pub use normalize::{ChapterContent, GlobalStylePool, NormalizedContent, normalize_book};
+pub(crate) use progress::Progress;
+pub use progress::ProgressEvent;
 pub use text::{MarkdownConfig, MarkdownExporter};
 
 /// Trait for exporting books to specific formats.
@@ -64,4 +70,19 @@ pub trait Exporter {
     /// - `std::io::Cursor<Vec<u8>>` for seekable in-memory output
     /// - Any other type implementing `Write + Seek`
     fn export<W: Write + Seek>(&self, book: &Book, writer: &mut W) -> crate::Result<()>;
+
+    /// Export like [`export`](Self::export), reporting each chapter and
+    /// asset to `progress` as it is written (see [`ProgressEvent`]).
+    ///
+    /// The built-in exporters all report progress; the default
+    /// implementation exports without reporting anything.
+    fn export_with_progress<W: Write + Seek>(
+        &self,
+        book: &Book,
+        writer: &mut W,
+        progress: &mut dyn FnMut(ProgressEvent),
+    ) -> crate::Result<()> {
+        let _ = progress;
+        self.export(book, writer)
+    }
 }
@@ -9,6 +9,9 @@
 //! 1. **Pass 1**: Load all chapters as IR, merge styles into GlobalStylePool
 //! 2. **Pass 2**: Generate unified CSS, synthesize XHTML per chapter with remapped styles
 //!
+//! Low-memory EPUB export runs the same passes through `ChapterStream`, which
+//! drops each chapter after pass 1 and re-loads it for pass 2.
+//!
 //! # Example
 //!
 //! ```no_run
@@ -28,11 +31,12 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use crate::import::ChapterId;
-use crate::model::{Book, Chapter, NodeId, Role};
-use crate::style::{StyleId, StylePool};
+use crate::kfx::cover::{is_image_only_chapter, needs_standalone_cover, normalize_cover_path};
+use crate::model::{AnchorTarget, Book, Chapter, GlobalNodeId, LandmarkType, NodeId, Role};
+use crate::style::{ComputedStyle, Length, StyleId, StylePool, TextAlign, ToCss};
 
 use super::html_synth::MathForm;
-use super::{generate_css, synthesize_xhtml_document_with_class_list_math};
+use super::{CssArtifact, Progress, generate_css, synthesize_xhtml_document_with_class_list_math};
 
 /// Collects styles from all chapters into a unified pool.
 ///
@@ -130,8 +134,14 @@ pub struct ChapterContent {
     pub id: ChapterId,
     /// Original source path within the ebook.
     pub source_path: String,
+    /// File name the chapter is emitted under (`chapter_{i}.xhtml` unless
+    /// the exporter chose its own names).
+    pub file_name: String,
     /// Complete synthesized XHTML document.
     pub document: String,
+    /// Control characters and noncharacters dropped from the chapter's
+    /// text while synthesizing it.
+    pub removed_chars: usize,
 }
 
 /// Result of normalizing all chapters in a book.
@@ -335,46 +345,55 @@ pub fn normalize_book(book: &Book) -> crate::Result<NormalizedContent> {
 /// targets pass [`MathForm::Text`] because their renderers cannot display
 /// MathML.
 pub fn normalize_book_math(book: &Book, math_form: MathForm) -> crate::Result<NormalizedContent> {
+    let mut ignore = |_| {};
+    let mut progress = Progress::new(&mut ignore, book.spine().len());
+    normalize_book_named(book, math_form, None, &mut progress)
+}
+
+/// [`normalize_book_math`] with caller-chosen output file names, one per
+/// spine chapter in spine order. Internal links, TOC and landmark rewrites
+/// all target these names instead of `chapter_{i}.xhtml`.
+///
+/// Chapters are reported to `progress` as they are loaded (prepared) and
+/// as their XHTML is synthesized (started and finished), a batch at a time.
+pub(crate) fn normalize_book_named(
+    book: &Book,
+    math_form: MathForm,
+    file_names: Option<&[String]>,
+    progress: &mut Progress<'_>,
+) -> crate::Result<NormalizedContent> {
     let spine = book.spine();
+    let output_names = output_names(book, file_names);
 
     // =========================================================================
     // Pass 1: Load all chapters and merge styles
     // =========================================================================
 
-    let mut global_styles = GlobalStylePool::new();
+    let mut merged = MergedChapters::default();
     let mut ir_chapters: Vec<(ChapterId, String, Arc<Chapter>)> = Vec::with_capacity(spine.len());
-    // Link-rewrite maps: original source path / anchor id -> emitted filename.
-    let mut source_to_output: HashMap<String, String> = HashMap::new();
-    let mut anchor_to_output: HashMap<String, String> = HashMap::new();
 
     // Compile every spine chapter up front as one batch — importers with
     // thread-safe IO (EPUB) parallelize the HTML parse + cascade + IR
     // transform across chapters, which dominates cold conversion.
     let spine_ids: Vec<ChapterId> = spine.iter().map(|e| e.id).collect();
-    let loaded = book.load_chapters_cached(&spine_ids)?;
-
-    for ((idx, entry), chapter) in spine.iter().enumerate().zip(loaded) {
-        let source_path = book
-            .source_id(entry.id)
-            .unwrap_or("unknown.xhtml")
-            .to_string();
+    let mut loaded = Vec::with_capacity(spine_ids.len());
+    for batch in spine_ids.chunks(batch_len()) {
+        loaded.extend(book.load_chapters_cached(batch)?);
+        for idx in loaded.len() - batch.len()..loaded.len() {
+            progress.chapter_prepared(idx);
+        }
+    }
+    let mut fixups = ChapterFixups::new(book);
+    fixups.cover_idx = cover_page_index(book, &loaded);
 
-        // Merge styles into global pool
-        global_styles.merge(idx, &chapter);
+    for ((idx, entry), mut chapter) in spine.iter().enumerate().zip(loaded) {
+        let source_path = source_path(book, entry.id);
 
-        // Record where this chapter and its anchors will live in the output, so
-        // TOC entries and internal links can be remapped from the original
-        // source paths / bare `#anchor`s to the emitted `chapter_{i}.xhtml`.
-        let output_name = format!("chapter_{idx}.xhtml");
-        source_to_output.insert(source_path.clone(), output_name.clone());
-        for node_id in chapter.iter_dfs() {
-            if let Some(id) = chapter.semantics.id(node_id) {
-                anchor_to_output
-                    .entry(id.to_string())
-                    .or_insert_with(|| output_name.clone());
-            }
+        if let Some(fixed) = fixups.apply(idx, &chapter) {
+            chapter = Arc::new(fixed);
         }
 
+        merged.add(idx, &source_path, &output_names[idx], &chapter);
         ir_chapters.push((entry.id, source_path, chapter));
     }
 
@@ -404,25 +423,14 @@ pub fn normalize_book_math(book: &Book, math_form: MathForm) -> crate::Result<No
                 if href.is_empty() || href.contains("://") || href.starts_with("mailto:") {
                     continue;
                 }
-                let target = resolved.get(crate::model::GlobalNodeId::new(*chapter_id, node_id));
-                let output = match target {
-                    Some(crate::model::AnchorTarget::Internal(gid)) => {
-                        let Some(&tidx) = chapter_pos.get(&gid.chapter) else {
-                            continue;
-                        };
-                        let frag = ir_chapters[tidx].2.semantics.id(gid.node);
-                        match frag {
-                            Some(frag) => format!("chapter_{tidx}.xhtml#{frag}"),
-                            None => format!("chapter_{tidx}.xhtml"),
-                        }
-                    }
-                    Some(crate::model::AnchorTarget::Chapter(cid)) => {
-                        let Some(&tidx) = chapter_pos.get(cid) else {
-                            continue;
-                        };
-                        format!("chapter_{tidx}.xhtml")
-                    }
-                    _ => continue,
+                let target = resolved.get(GlobalNodeId::new(*chapter_id, node_id));
+                let Some(output) = link_output(target, &chapter_pos, &output_names, |gid| {
+                    ir_chapters[chapter_pos[&gid.chapter]]
+                        .2
+                        .semantics
+                        .id(gid.node)
+                }) else {
+                    continue;
                 };
                 per_chapter_remap[idx].insert(href.to_string(), output.clone());
                 href_remap.entry(href.to_string()).or_insert(output);
@@ -434,25 +442,343 @@ pub fn normalize_book_math(book: &Book, math_form: MathForm) -> crate::Result<No
     // Generate unified CSS
     // =========================================================================
 
-    let used_styles = global_styles.used_styles();
-    let css_artifact = generate_css(global_styles.pool(), &used_styles);
+    let synthesis = merged.into_synthesis(book, math_form, output_names, &fixups);
 
     // =========================================================================
     // Pass 2: Synthesize XHTML with remapped styles
     // =========================================================================
 
-    // Each chapter's synthesis reads only shared immutable state, so this
-    // fans out across chapters when the `parallel` feature is on.
-    let synthesize_one = |(idx, (chapter_id, source_path, ir)): (
-        usize,
-        &(ChapterId, String, Arc<Chapter>),
-    )|
-     -> (ChapterContent, HashSet<String>) {
+    // Each chapter's synthesis reads only shared immutable state, so each
+    // batch fans out across chapters when the `parallel` feature is on.
+    let synthesize_one =
+        |(idx, (chapter_id, source_path, ir)): (usize, &(ChapterId, String, Arc<Chapter>))| {
+            synthesis.chapter(idx, *chapter_id, source_path, ir, &per_chapter_remap[idx])
+        };
+
+    let mut chapters: Vec<ChapterContent> = Vec::with_capacity(ir_chapters.len());
+    let mut all_assets = synthesis.style_assets();
+    for batch in ir_chapters.chunks(batch_len()) {
+        let first = chapters.len();
+        #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+        let synthesized: Vec<(ChapterContent, HashSet<String>)> = {
+            use rayon::prelude::*;
+            batch
+                .par_iter()
+                .enumerate()
+                .map(|(i, chapter)| synthesize_one((first + i, chapter)))
+                .collect()
+        };
+        #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+        let synthesized: Vec<(ChapterContent, HashSet<String>)> = batch
+            .iter()
+            .enumerate()
+            .map(|(i, chapter)| synthesize_one((first + i, chapter)))
+            .collect();
+
+        for (content, assets) in synthesized {
+            let idx = chapters.len();
+            progress.chapter_started(idx);
+            progress.characters_removed(idx, content.removed_chars);
+            progress.chapter_finished(idx);
+            all_assets.extend(assets);
+            chapters.push(content);
+        }
+    }
+
+    Ok(synthesis.into_content(chapters, all_assets, href_remap))
+}
+
+/// Chapters loaded or synthesized per batch in [`normalize_book_named`].
+/// Each batch fans out across threads with the `parallel` feature; progress
+/// is reported between batches, so they stay small enough to keep it moving.
+fn batch_len() -> usize {
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    {
+        4 * rayon::current_num_threads()
+    }
+    #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+    {
+        1
+    }
+}
+
+/// Chapter-at-a-time normalization, for exports that must not hold the
+/// whole book in memory.
+///
+/// [`normalize_book_named`] keeps every chapter's IR and XHTML alive until
+/// the export is written. `ChapterStream` instead makes a first pass that
+/// loads each chapter, merges its styles, records its ids and links, and
+/// drops it again; [`chapter`](Self::chapter) then re-loads and synthesizes
+/// one chapter on demand. Peak memory is the largest chapter plus the
+/// book-wide style and anchor maps, at the cost of compiling every chapter
+/// twice. Chapters are loaded uncached, so the book's IR cache stays empty.
+pub(crate) struct ChapterStream<'a> {
+    book: &'a Book,
+    synthesis: Synthesis,
+    /// Spine chapter ids with their source paths.
+    chapters: Vec<(ChapterId, String)>,
+    fixups: ChapterFixups,
+    per_chapter_remap: Vec<HashMap<String, String>>,
+    href_remap: HashMap<String, String>,
+}
+
+impl<'a> ChapterStream<'a> {
+    /// Run the first pass over `book`: styles, anchors and link targets.
+    /// Each chapter is reported to `progress` as prepared once loaded.
+    pub(crate) fn new(
+        book: &'a Book,
+        math_form: MathForm,
+        file_names: Option<&[String]>,
+        progress: &mut Progress<'_>,
+    ) -> crate::Result<Self> {
+        let output_names = output_names(book, file_names);
+        let cover_candidate = cover_candidate(book);
+        let mut fixups = ChapterFixups::new(book);
+        let mut merged = MergedChapters::default();
+        let mut chapters = Vec::with_capacity(output_names.len());
+        let mut ids: Vec<(GlobalNodeId, String)> = Vec::new();
+        let mut links: Vec<(usize, ChapterId, String)> = Vec::new();
+
+        for (idx, entry) in book.spine().iter().enumerate() {
+            let source_path = source_path(book, entry.id);
+            let mut chapter = book.load_chapter(entry.id)?;
+            if idx == cover_candidate && is_image_only_chapter(&chapter) {
+                fixups.cover_idx = Some(idx);
+            }
+            if let Some(fixed) = fixups.apply(idx, &chapter) {
+                chapter = fixed;
+            }
+            merged.add(idx, &source_path, &output_names[idx], &chapter);
+
+            for node_id in chapter.iter_dfs() {
+                if let Some(id) = chapter.semantics.id(node_id) {
+                    ids.push((GlobalNodeId::new(entry.id, node_id), id.to_string()));
+                }
+                if chapter.node(node_id).is_some_and(|n| n.role == Role::Link)
+                    && let Some(href) = chapter.semantics.href(node_id)
+                    && !href.is_empty()
+                    && !href.contains("://")
+                    && !href.starts_with("mailto:")
+                {
+                    links.push((idx, entry.id, href.to_string()));
+                }
+            }
+            chapters.push((entry.id, source_path));
+            progress.chapter_prepared(idx);
+        }
+
+        // The importer's anchor maps need only the ids, so they're indexed
+        // without the chapters that carried them.
+        let id_refs: Vec<(GlobalNodeId, &str)> =
+            ids.iter().map(|(gid, id)| (*gid, id.as_str())).collect();
+        book.index_anchor_ids(&id_refs);
+        book.resolve_toc();
+        let id_of: HashMap<GlobalNodeId, &str> = id_refs.into_iter().collect();
+
+        let chapter_pos: HashMap<ChapterId, usize> = chapters
+            .iter()
+            .enumerate()
+            .map(|(i, (id, _))| (*id, i))
+            .collect();
+        let mut per_chapter_remap: Vec<HashMap<String, String>> =
+            vec![HashMap::new(); chapters.len()];
+        let mut href_remap: HashMap<String, String> = HashMap::new();
+        for (idx, chapter_id, href) in links {
+            let target = book.resolve_href(chapter_id, &href);
+            let Some(output) = link_output(target.as_ref(), &chapter_pos, &output_names, |gid| {
+                id_of.get(&gid).copied()
+            }) else {
+                continue;
+            };
+            per_chapter_remap[idx].insert(href.clone(), output.clone());
+            href_remap.entry(href).or_insert(output);
+        }
+
+        Ok(Self {
+            book,
+            synthesis: merged.into_synthesis(book, math_form, output_names, &fixups),
+            chapters,
+            fixups,
+            per_chapter_remap,
+            href_remap,
+        })
+    }
+
+    /// Number of spine chapters.
+    pub(crate) fn len(&self) -> usize {
+        self.chapters.len()
+    }
+
+    /// Load and synthesize the chapter at spine index `idx`, returning it
+    /// with the asset paths it references.
+    pub(crate) fn chapter(&self, idx: usize) -> crate::Result<(ChapterContent, HashSet<String>)> {
+        let (chapter_id, source_path) = &self.chapters[idx];
+        let mut chapter = self.book.load_chapter(*chapter_id)?;
+        if let Some(fixed) = self.fixups.apply(idx, &chapter) {
+            chapter = fixed;
+        }
+        Ok(self.synthesis.chapter(
+            idx,
+            *chapter_id,
+            source_path,
+            &chapter,
+            &self.per_chapter_remap[idx],
+        ))
+    }
+
+    /// Finish the stream: the stylesheet and link maps, with no chapters
+    /// and only the assets the stylesheet itself references.
+    pub(crate) fn finish(self) -> NormalizedContent {
+        let assets = self.synthesis.style_assets();
+        self.synthesis
+            .into_content(Vec::new(), assets, self.href_remap)
+    }
+}
+
+/// Output file name for every spine chapter.
+fn output_names(book: &Book, file_names: Option<&[String]>) -> Vec<String> {
+    match file_names {
+        Some(names) => names.to_vec(),
+        None => (0..book.spine().len())
+            .map(|idx| format!("chapter_{idx}.xhtml"))
+            .collect(),
+    }
+}
+
+fn source_path(book: &Book, id: ChapterId) -> String {
+    book.source_id(id).unwrap_or("unknown.xhtml").to_string()
+}
+
+/// Emitted location of a resolved link target, or `None` for targets that
+/// aren't in the spine (external and broken links keep their href).
+fn link_output<'a>(
+    target: Option<&AnchorTarget>,
+    chapter_pos: &HashMap<ChapterId, usize>,
+    output_names: &[String],
+    id_of: impl FnOnce(GlobalNodeId) -> Option<&'a str>,
+) -> Option<String> {
+    match target? {
+        AnchorTarget::Internal(gid) => {
+            let tidx = *chapter_pos.get(&gid.chapter)?;
+            Some(match id_of(*gid) {
+                Some(frag) => format!("{}#{frag}", output_names[tidx]),
+                None => output_names[tidx].clone(),
+            })
+        }
+        AnchorTarget::Chapter(cid) => Some(output_names[*chapter_pos.get(cid)?].clone()),
+        AnchorTarget::External(_) => None,
+    }
+}
+
+/// Pass 1 state: the merged style pool and where each chapter and anchor
+/// will live in the output.
+#[derive(Default)]
+struct MergedChapters {
+    global_styles: GlobalStylePool,
+    // Link-rewrite maps: original source path / anchor id -> emitted filename.
+    source_to_output: HashMap<String, String>,
+    anchor_to_output: HashMap<String, String>,
+}
+
+impl MergedChapters {
+    fn add(&mut self, idx: usize, source_path: &str, output_name: &str, chapter: &Chapter) {
+        // Merge styles into global pool
+        self.global_styles.merge(idx, chapter);
+
+        // Record where this chapter and its anchors will live in the output, so
+        // TOC entries and internal links can be remapped from the original
+        // source paths / bare `#anchor`s to the emitted `chapter_{i}.xhtml`.
+        self.source_to_output
+            .insert(source_path.to_string(), output_name.to_string());
+        for node_id in chapter.iter_dfs() {
+            if let Some(id) = chapter.semantics.id(node_id) {
+                self.anchor_to_output
+                    .entry(id.to_string())
+                    .or_insert_with(|| output_name.to_string());
+            }
+        }
+    }
+
+    /// Generate the unified CSS and freeze the state pass 2 reads.
+    fn into_synthesis(
+        self,
+        book: &Book,
+        math_form: MathForm,
+        output_names: Vec<String>,
+        fixups: &ChapterFixups,
+    ) -> Synthesis {
+        let used_styles = self.global_styles.used_styles();
+        let mut css_artifact = generate_css(self.global_styles.pool(), &used_styles);
+
+        // The page box survives normalization: its rules aren't per-element
+        // styles, so the unified stylesheet carries them up front.
+        let page_rule = book.page_rule().filter(|rule| !rule.is_empty());
+        if let Some(rule) = &page_rule {
+            let at_rule = rule.to_css_string();
+            css_artifact
+                .stylesheet
+                .insert_str(0, &format!("{at_rule}\n"));
+        }
+
+        // Fixed-layout pages keep their page size: the synthesized head only
+        // has the charset meta, so the viewport goes right after it. Without
+        // a viewport in the source, the `@page` size stands in for it.
+        let rendition = &book.metadata().rendition;
+        let page_size = page_rule
+            .and_then(|rule| rule.size)
+            .filter(|_| rendition.is_fixed_layout())
+            .map(|size| (size.width.round() as u32, size.height.round() as u32));
+        let viewport_meta = rendition.viewport.or(page_size).map(|(width, height)| {
+            format!(
+                "<meta charset=\"utf-8\"/>\n  <meta name=\"viewport\" content=\"width={width}, height={height}\"/>"
+            )
+        });
+
+        Synthesis {
+            global_styles: self.global_styles,
+            css_artifact,
+            math_form,
+            output_names,
+            source_to_output: self.source_to_output,
+            anchor_to_output: self.anchor_to_output,
+            viewport_meta,
+            body_types: (0..fixups.landmarks.len())
+                .map(|idx| fixups.body_type(idx))
+                .collect(),
+        }
+    }
+}
+
+/// Book-wide, read-only state for synthesizing any one chapter.
+struct Synthesis {
+    global_styles: GlobalStylePool,
+    css_artifact: CssArtifact,
+    math_form: MathForm,
+    output_names: Vec<String>,
+    source_to_output: HashMap<String, String>,
+    anchor_to_output: HashMap<String, String>,
+    viewport_meta: Option<String>,
+    /// `epub:type` of each chapter's `<body>`, from landmarks that target
+    /// the whole file.
+    body_types: Vec<Option<String>>,
+}
+
+impl Synthesis {
+    /// Synthesize one chapter's XHTML, returning it with the assets it
+    /// references. `href_remap` holds this chapter's resolved-link overrides.
+    fn chapter(
+        &self,
+        idx: usize,
+        chapter_id: ChapterId,
+        source_path: &str,
+        ir: &Chapter,
+        href_remap: &HashMap<String, String>,
+    ) -> (ChapterContent, HashSet<String>) {
         // Build remapped style map for this chapter
         let mut remapped_class_list: Vec<Option<&str>> = vec![None; ir.styles.len()];
         for (local_id, _) in ir.styles.iter() {
-            let global_id = global_styles.remap(idx, local_id);
-            if let Some(class_name) = css_artifact.class_name_fast(global_id) {
+            let global_id = self.global_styles.remap(idx, local_id);
+            if let Some(class_name) = self.css_artifact.class_name_fast(global_id) {
                 let slot = remapped_class_list
                     .get_mut(local_id.0 as usize)
                     .expect("style id out of bounds");
@@ -461,7 +787,7 @@ pub fn normalize_book_math(book: &Book, math_form: MathForm) -> crate::Result<No
         }
 
         // Extract title from first heading or use source path
-        let title = extract_chapter_title(ir).unwrap_or_else(|| source_path.clone());
+        let title = extract_chapter_title(ir).unwrap_or_else(|| source_path.to_string());
 
         // Synthesize XHTML document
         let result = synthesize_xhtml_document_with_class_list_math(
@@ -469,61 +795,270 @@ pub fn normalize_book_math(book: &Book, math_form: MathForm) -> crate::Result<No
             &remapped_class_list,
             &title,
             Some("style.css"),
-            math_form,
+            self.math_form,
         );
 
         // Rewrite internal links to target the emitted chapter files.
-        let document = rewrite_document_hrefs(
+        let mut document = rewrite_document_hrefs(
             &result.body,
             source_path,
-            &source_to_output,
-            &anchor_to_output,
-            &per_chapter_remap[idx],
+            &self.source_to_output,
+            &self.anchor_to_output,
+            href_remap,
         );
+        if let Some(viewport_meta) = &self.viewport_meta {
+            document = document.replacen("<meta charset=\"utf-8\"/>", viewport_meta, 1);
+        }
+        if let Some(Some(epub_type)) = self.body_types.get(idx) {
+            let body = format!("<body epub:type=\"{}\">", super::escape_xml(epub_type));
+            document = document.replacen("<body>", &body, 1);
+        }
 
         (
             ChapterContent {
-                id: *chapter_id,
-                source_path: source_path.clone(),
+                id: chapter_id,
+                source_path: source_path.to_string(),
+                file_name: self.output_names[idx].clone(),
                 document,
+                removed_chars: crate::util::count_unexpected_chars(ir.text_buffer()),
             },
             result.assets,
         )
-    };
+    }
 
-    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
-    let synthesized: Vec<(ChapterContent, HashSet<String>)> = {
-        use rayon::prelude::*;
-        ir_chapters
-            .par_iter()
-            .enumerate()
-            .map(synthesize_one)
+    /// Marker images are referenced from the generated CSS, not the IR DOM.
+    /// style.css sits beside the chapters, so archive paths resolve alike.
+    fn style_assets(&self) -> HashSet<String> {
+        self.global_styles
+            .used_styles()
+            .iter()
+            .filter_map(|&id| self.global_styles.pool().get(id)?.list_style_image.clone())
+            .filter(|href| !href.contains("://") && !href.starts_with("data:"))
             .collect()
-    };
-    #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
-    let synthesized: Vec<(ChapterContent, HashSet<String>)> =
-        ir_chapters.iter().enumerate().map(synthesize_one).collect();
+    }
+
+    fn into_content(
+        self,
+        chapters: Vec<ChapterContent>,
+        assets: HashSet<String>,
+        href_remap: HashMap<String, String>,
+    ) -> NormalizedContent {
+        NormalizedContent {
+            styles: self.global_styles,
+            chapters,
+            assets,
+            css: self.css_artifact.stylesheet,
+            source_to_output: self.source_to_output,
+            anchor_to_output: self.anchor_to_output,
+            href_remap,
+        }
+    }
+}
+
+/// Spine index of the book's cover page: the chapter the cover landmark
+/// points at, else the first chapter, provided it holds nothing but an
+/// image.
+fn cover_page_index(book: &Book, chapters: &[Arc<Chapter>]) -> Option<usize> {
+    let idx = cover_candidate(book);
+    chapters
+        .get(idx)
+        .is_some_and(|chapter| is_image_only_chapter(chapter))
+        .then_some(idx)
+}
+
+/// Asset path of the cover image when the book has one but no page showing
+/// it: no cover landmark, and the cover candidate chapter doesn't display
+/// that image. Sources converted from KFX or MOBI often only carry the
+/// cover in metadata.
+pub(crate) fn missing_cover_page(book: &Book) -> Option<String> {
+    let cover = book.metadata().cover_image.as_deref()?;
+    let image = normalize_cover_path(cover, book.list_assets());
+    if !book.list_assets().contains(&image)
+        || book
+            .landmarks()
+            .iter()
+            .any(|landmark| landmark.landmark_type == LandmarkType::Cover)
+    {
+        return None;
+    }
+    let shown = book
+        .spine()
+        .get(cover_candidate(book))
+        .and_then(|entry| book.load_chapter_cached(entry.id).ok())
+        .is_some_and(|chapter| !needs_standalone_cover(&image, &chapter));
+    (!shown).then_some(image)
+}
+
+/// Spine index that may hold the cover page (see [`cover_page_index`]).
+fn cover_candidate(book: &Book) -> usize {
+    book.landmarks()
+        .iter()
+        .find(|landmark| landmark.landmark_type == LandmarkType::Cover)
+        .and_then(|landmark| landmark_chapter(book, &landmark.href))
+        .unwrap_or(0)
+}
+
+/// Spine index of the chapter a landmark href points into: the chapter at
+/// exactly that path, or else one whose path matches it by whole trailing
+/// segments (`OEBPS/text/cover.xhtml` for `text/cover.xhtml`).
+fn landmark_chapter(book: &Book, href: &str) -> Option<usize> {
+    let path = href.split('#').next().unwrap_or_default();
+    if path.is_empty() {
+        return None;
+    }
+    let sources: Vec<Option<&str>> = book
+        .spine()
+        .iter()
+        .map(|entry| book.source_id(entry.id))
+        .collect();
+    sources
+        .iter()
+        .position(|&source| source == Some(path))
+        .or_else(|| {
+            sources
+                .iter()
+                .position(|source| source.is_some_and(|source| same_file(source, path)))
+        })
+}
+
+/// Whether one path is the other with leading directories dropped,
+/// comparing whole segments from the file name up: `text/cover.xhtml` names
+/// `OEBPS/text/cover.xhtml`, but not `OEBPS/text/mycover.xhtml` or
+/// `OEBPS/context/cover.xhtml`.
+fn same_file(a: &str, b: &str) -> bool {
+    !a.is_empty() && !b.is_empty() && a.rsplit('/').zip(b.rsplit('/')).all(|(a, b)| a == b)
+}
+
+/// Edits applied to chapters as they're loaded, before their styles are
+/// merged: cover-page sizing and landmark semantics.
+struct ChapterFixups {
+    /// Spine index of the image-only cover page, if any.
+    cover_idx: Option<usize>,
+    /// Landmarks landing on each spine chapter: the target fragment (`None`
+    /// for the whole file) and its EPUB 3 structural type.
+    landmarks: Vec<Vec<(Option<String>, &'static str)>>,
+}
+
+impl ChapterFixups {
+    fn new(book: &Book) -> Self {
+        let mut landmarks = vec![Vec::new(); book.spine().len()];
+        for landmark in book.landmarks() {
+            let Some(idx) = landmark_chapter(book, &landmark.href) else {
+                continue;
+            };
+            let fragment = landmark
+                .href
+                .split_once('#')
+                .map(|(_, frag)| frag.to_string())
+                .filter(|frag| !frag.is_empty());
+            let epub_type = super::epub::landmark_epub_type(landmark.landmark_type);
+            if !landmarks[idx].contains(&(fragment.clone(), epub_type)) {
+                landmarks[idx].push((fragment, epub_type));
+            }
+        }
+        Self {
+            cover_idx: None,
+            landmarks,
+        }
+    }
+
+    /// A fixed-up copy of the chapter at spine index `idx`, or `None` when
+    /// it needs no changes. Landmark types go on the element the landmark
+    /// targets, unless the source already typed it.
+    fn apply(&self, idx: usize, chapter: &Chapter) -> Option<Chapter> {
+        let targeted: Vec<(&str, &str)> = self.landmarks[idx]
+            .iter()
+            .filter_map(|(frag, epub_type)| Some((frag.as_deref()?, *epub_type)))
+            .collect();
+        let cover = Some(idx) == self.cover_idx;
+        if !cover && targeted.is_empty() {
+            return None;
+        }
+        let mut chapter = if cover {
+            size_cover_page(chapter)
+        } else {
+            chapter.clone()
+        };
+        for (frag, epub_type) in targeted {
+            let node = chapter
+                .iter_dfs()
+                .find(|&id| chapter.semantics.id(id) == Some(frag));
+            if let Some(node) = node
+                && chapter.semantics.epub_type(node).is_none()
+            {
+                chapter.semantics.set_epub_type(node, epub_type);
+            }
+        }
+        Some(chapter)
+    }
 
-    let mut chapters = Vec::with_capacity(synthesized.len());
-    let mut all_assets = HashSet::new();
-    for (content, assets) in synthesized {
-        all_assets.extend(assets);
-        chapters.push(content);
+    /// Space-separated types of the landmarks that target the whole file.
+    fn body_type(&self, idx: usize) -> Option<String> {
+        let types: Vec<&str> = self.landmarks[idx]
+            .iter()
+            .filter(|(frag, _)| frag.is_none())
+            .map(|(_, epub_type)| *epub_type)
+            .collect();
+        (!types.is_empty()).then(|| types.join(" "))
     }
+}
 
-    Ok(NormalizedContent {
-        styles: global_styles,
-        chapters,
-        assets: all_assets,
-        css: css_artifact.stylesheet,
-        source_to_output,
-        anchor_to_output,
-        href_remap,
-    })
+/// Copy of a cover page whose image fills the page height, scaled to fit
+/// the width and centered by its parent. Cover pages that carry no sizing
+/// of their own otherwise render the image at its intrinsic size, often a
+/// small box in the corner of the screen. An image the author sized keeps
+/// its size, and a parent the author aligned keeps its alignment.
+fn size_cover_page(chapter: &Chapter) -> Chapter {
+    let mut chapter = chapter.clone();
+    let images: Vec<NodeId> = chapter
+        .iter_dfs()
+        .filter(|&id| chapter.node(id).is_some_and(|n| n.role == Role::Image))
+        .collect();
+    for id in images {
+        let sized = chapter
+            .node(id)
+            .and_then(|n| chapter.styles.get(n.style))
+            .is_some_and(|s| s.width != Length::Auto || s.height != Length::Auto);
+        if sized {
+            continue;
+        }
+        restyle(&mut chapter, id, |style| ComputedStyle {
+            height: Length::Percent(100.0),
+            max_width: match style.max_width {
+                Length::Auto => Length::Percent(100.0),
+                authored => authored,
+            },
+            ..style
+        });
+        if let Some(parent) = chapter.node(id).and_then(|n| n.parent)
+            && parent != NodeId::ROOT
+        {
+            restyle(&mut chapter, parent, |style| ComputedStyle {
+                text_align: match style.text_align {
+                    TextAlign::Start => TextAlign::Center,
+                    authored => authored,
+                },
+                ..style
+            });
+        }
+    }
+    chapter
+}
+
+/// Replace a node's style with `f` applied to it.
+fn restyle(chapter: &mut Chapter, id: NodeId, f: impl FnOnce(ComputedStyle) -> ComputedStyle) {
+    let Some(style_id) = chapter.node(id).map(|n| n.style) else {
+        return;
+    };
+    let style = chapter.styles.get(style_id).cloned().unwrap_or_default();
+    let restyled = chapter.styles.intern(f(style));
+    if let Some(node) = chapter.node_mut(id) {
+        node.style = restyled;
+    }
 }
 
 /// Extract a title from the first heading in a chapter.
-fn extract_chapter_title(ir: &Chapter) -> Option<String> {
+pub(crate) fn extract_chapter_title(ir: &Chapter) -> Option<String> {
     for node_id in ir.iter_dfs() {
         if let Some(node) = ir.node(node_id)
             && matches!(node.role, Role::Heading(_))
@@ -691,4 +1226,18 @@ mod tests {
         assert_eq!(rw("#missing"), "#missing");
         assert_eq!(rw("https://example.com/a"), "https://example.com/a");
     }
+
+    #[test]
+    fn same_file_matches_whole_trailing_segments() {
+        assert!(same_file("OEBPS/text/cover.xhtml", "text/cover.xhtml"));
+        assert!(same_file("cover.xhtml", "OEBPS/cover.xhtml"));
+        assert!(same_file("cover.xhtml", "cover.xhtml"));
+        assert!(!same_file("OEBPS/text/mycover.xhtml", "cover.xhtml"));
+        assert!(!same_file("OEBPS/context/cover.xhtml", "text/cover.xhtml"));
+        assert!(!same_file(
+            "OEBPS/text/XOEBPS/cover.xhtml",
+            "OEBPS/cover.xhtml"
+        ));
+        assert!(!same_file("OEBPS/text/cover.xhtml", ""));
+    }
 }
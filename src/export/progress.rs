@@ -0,0 +1,97 @@
+//! Progress reporting for long exports.
+
+/// One step of an export, reported to the callback passed to
+/// [`Exporter::export_with_progress`](super::Exporter::export_with_progress)
+/// or [`Book::export_with_progress`](crate::Book::export_with_progress).
+///
+/// Chapter events arrive once per spine chapter, in spine order, as the
+/// chapter's output is produced. Exporters that need the whole book before
+/// writing any of it (KFX, normalized EPUB, AZW3) first report each chapter
+/// as [`ChapterPrepared`](Self::ChapterPrepared) while that first pass
+/// loads it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ProgressEvent {
+    /// A spine chapter has been loaded by an exporter's first pass over the
+    /// book. All of these come before the first `ChapterStarted`.
+    ChapterPrepared {
+        /// Spine index of the chapter.
+        index: usize,
+        /// Number of spine chapters.
+        total: usize,
+    },
+    /// A spine chapter is being written.
+    ChapterStarted {
+        /// Spine index of the chapter.
+        index: usize,
+        /// Number of spine chapters.
+        total: usize,
+    },
+    /// A spine chapter has been written.
+    ChapterFinished {
+        /// Spine index of the chapter.
+        index: usize,
+        /// Number of spine chapters.
+        total: usize,
+    },
+    /// Control characters or Unicode noncharacters were dropped from a
+    /// spine chapter's text as it was written. Sent between the chapter's
+    /// start and finish events, and only when something was dropped.
+    CharactersRemoved {
+        /// Spine index of the chapter.
+        index: usize,
+        /// Number of characters removed.
+        count: usize,
+    },
+    /// An asset (image, font, ...) was copied into the output.
+    AssetWritten {
+        /// The asset's path in the source book.
+        path: String,
+    },
+}
+
+/// The progress callback as threaded through an exporter, with the spine
+/// length every chapter event carries.
+pub(crate) struct Progress<'a> {
+    callback: &'a mut dyn FnMut(ProgressEvent),
+    total: usize,
+}
+
+impl<'a> Progress<'a> {
+    pub(crate) fn new(callback: &'a mut dyn FnMut(ProgressEvent), total: usize) -> Self {
+        Self { callback, total }
+    }
+
+    pub(crate) fn chapter_prepared(&mut self, index: usize) {
+        (self.callback)(ProgressEvent::ChapterPrepared {
+            index,
+            total: self.total,
+        });
+    }
+
+    pub(crate) fn chapter_started(&mut self, index: usize) {
+        (self.callback)(ProgressEvent::ChapterStarted {
+            index,
+            total: self.total,
+        });
+    }
+
+    pub(crate) fn chapter_finished(&mut self, index: usize) {
+        (self.callback)(ProgressEvent::ChapterFinished {
+            index,
+            total: self.total,
+        });
+    }
+
+    pub(crate) fn characters_removed(&mut self, index: usize, count: usize) {
+        if count > 0 {
+            (self.callback)(ProgressEvent::CharactersRemoved { index, count });
+        }
+    }
+
+    pub(crate) fn asset_written(&mut self, path: &str) {
+        (self.callback)(ProgressEvent::AssetWritten {
+            path: path.to_string(),
+        });
+    }
+}
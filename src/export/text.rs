@@ -5,16 +5,17 @@
 
 use std::io::{self, Seek, Write};
 
-use crate::markdown::{build_heading_slugs, render_chapter};
+use crate::markdown::{build_heading_slugs, render_chapter, wrap_markdown};
 use crate::model::Book;
 
-use super::Exporter;
+use super::{Exporter, Progress, ProgressEvent};
 
 /// Configuration for Markdown export.
 #[derive(Debug, Clone, Default)]
 pub struct MarkdownConfig {
-    /// Line width for wrapping (0 = no wrapping).
-    /// Reserved for future use.
+    /// Line width for wrapping (0 = no wrapping). Paragraphs, blockquotes,
+    /// list items and footnotes are wrapped at word boundaries; headings,
+    /// tables and code blocks are left as-is.
     pub line_width: usize,
 }
 
@@ -38,12 +39,22 @@ impl MarkdownExporter {
 
 impl Exporter for MarkdownExporter {
     fn export<W: Write + Seek>(&self, book: &Book, writer: &mut W) -> crate::Result<()> {
-        let _ = self.config; // Reserved for future use (line wrapping)
+        self.export_with_progress(book, writer, &mut |_| {})
+    }
+
+    fn export_with_progress<W: Write + Seek>(
+        &self,
+        book: &Book,
+        writer: &mut W,
+        progress: &mut dyn FnMut(ProgressEvent),
+    ) -> crate::Result<()> {
+        let width = self.config.line_width;
 
         // 1. Resolve all links (I/O: loads chapters internally)
         let resolved = book.resolve_links()?;
 
         let spine = book.spine();
+        let mut progress = Progress::new(progress, spine.len());
 
         // 2. Load all chapters and build heading slugs
         let chapters: Vec<_> = spine
@@ -58,7 +69,8 @@ impl Exporter for MarkdownExporter {
         // Running footnote count so labels stay unique across the concatenated
         // chapters (each chapter otherwise numbers its own from 1).
         let mut footnote_total = 0;
-        for (chapter_id, chapter) in &chapters {
+        for (idx, (chapter_id, chapter)) in chapters.iter().enumerate() {
+            progress.chapter_started(idx);
             if !first {
                 // Chapter separator
                 writeln!(writer)?;
@@ -78,15 +90,17 @@ impl Exporter for MarkdownExporter {
             footnote_total += result.footnotes.len();
 
             // I/O: write content
-            write!(writer, "{}", result.content)?;
+            write!(writer, "{}", wrap_markdown(&result.content, width))?;
 
             // I/O: write footnotes
             if !result.footnotes.is_empty() {
                 writeln!(writer)?;
                 for note in &result.footnotes {
-                    writeln!(writer, "[^{}]: {}", note.number, note.content)?;
+                    let note = format!("[^{}]: {}\n", note.number, note.content);
+                    write!(writer, "{}", wrap_markdown(&note, width))?;
                 }
             }
+            progress.chapter_finished(idx);
         }
 
         Ok(())
@@ -19,9 +19,10 @@ use crate::mobi::parser::{
 use crate::mobi::{
     Compression, Encoding, HuffCdicReader, MobiFormat, MobiHeader, NULL_INDEX, PdbInfo, TocNode,
     build_toc_from_ncx, decode_font_record, detect_font_type, detect_image_type,
-    is_metadata_record, palmdoc, parse_exth, parse_fdst, strip_trailing_data, transform,
+    is_metadata_record, merge_resc_metadata, palmdoc, parse_exth, parse_fdst, parse_resc_metadata,
+    strip_trailing_data, transform,
 };
-use crate::model::{AnchorTarget, Chapter, GlobalNodeId, Landmark, Metadata, TocEntry};
+use crate::model::{AnchorTarget, GlobalNodeId, Landmark, Metadata, TocEntry};
 
 /// AZW3/KF8 format importer with lazy loading.
 pub struct Azw3Importer {
@@ -201,33 +202,25 @@ impl Importer for Azw3Importer {
             return Some(Arc::clone(sheet));
         }
         let css_bytes = self.load_asset(path).ok()?;
-        let css_str = String::from_utf8_lossy(&css_bytes);
-        let sheet = Arc::new(Stylesheet::parse(&css_str));
+        let sheet = Arc::new(crate::import::parse_stylesheet_at(&css_bytes, path));
         match self.css_cache.write() {
             Ok(mut cache) => Some(Arc::clone(cache.entry(path.to_string()).or_insert(sheet))),
             Err(_) => Some(sheet),
         }
     }
 
-    fn index_anchors(&self, chapters: &[(ChapterId, Arc<Chapter>)]) {
+    fn index_anchor_ids(&self, ids: &[(GlobalNodeId, &str)]) {
         let mut element_id_map = HashMap::new();
-
-        // Build path#id → GlobalNodeId map from chapters (same format as EPUB)
-        for (chapter_id, chapter) in chapters {
-            // Get the chapter's source path
-            let chapter_path = match self.chapter_paths.get(chapter_id.0 as usize) {
-                Some(p) => p.as_str(),
-                None => continue,
+        for &(node, id) in ids {
+            let Some(chapter_path) = self
+                .chapter_paths
+                .get(node.chapter.0 as usize)
+                .map(String::as_str)
+            else {
+                continue;
             };
-
-            for node_id in chapter.iter_dfs() {
-                if let Some(id) = chapter.semantics.id(node_id) {
-                    let key = format!("{}#{}", chapter_path, id);
-                    element_id_map.insert(key, GlobalNodeId::new(*chapter_id, node_id));
-                }
-            }
+            element_id_map.insert(format!("{}#{}", chapter_path, id), node);
         }
-
         if let Ok(mut map) = self.element_id_map.write() {
             *map = element_id_map;
         }
@@ -518,7 +511,16 @@ impl Azw3Importer {
             toc_positions,
         };
 
-        importer.assets = importer.discover_assets();
+        let (assets, resc_record) = importer.discover_assets();
+        importer.assets = assets;
+
+        // Series and other OPF-only metadata ride in the RESC record.
+        if let Some(resc) = resc_record
+            .and_then(|idx| importer.read_record(idx).ok())
+            .and_then(|data| parse_resc_metadata(&data))
+        {
+            merge_resc_metadata(&mut importer.metadata, resc);
+        }
 
         // Find the cover among the discovered assets: their names embed the
         // record index and the record's *real* extension (the cover may be
@@ -639,9 +641,11 @@ impl Azw3Importer {
         Ok(cleaned)
     }
 
-    /// Discover asset paths by scanning image and font records.
-    fn discover_assets(&self) -> Vec<String> {
+    /// Discover asset paths by scanning image and font records. Also returns
+    /// the index of the RESC record if one sits among the resources.
+    fn discover_assets(&self) -> (Vec<String>, Option<usize>) {
         let mut assets = Vec::new();
+        let mut resc_record = None;
 
         // KF8 stylesheet flows (flow 0 is the HTML) — listed so exporters
         // that copy assets ship the CSS the chapters link to.
@@ -650,7 +654,7 @@ impl Azw3Importer {
         }
 
         if self.mobi.first_image_index == NULL_INDEX {
-            return assets;
+            return (assets, resc_record);
         }
 
         let first_img = self.mobi.first_image_index as usize + self.record_offset;
@@ -667,6 +671,9 @@ impl Azw3Importer {
                 {
                     let header = &header[..read_len];
                     if is_metadata_record(header) {
+                        if header.starts_with(b"RESC") {
+                            resc_record.get_or_insert(i);
+                        }
                         continue;
                     }
                     let idx = i - first_img;
@@ -675,6 +682,7 @@ impl Azw3Importer {
                             "image/jpeg" => "jpg",
                             "image/png" => "png",
                             "image/gif" => "gif",
+                            "image/webp" => "webp",
                             _ => "bin",
                         };
                         assets.push(format!("images/image_{idx:04}.{ext}"));
@@ -685,7 +693,7 @@ impl Azw3Importer {
             }
         }
 
-        assets
+        (assets, resc_record)
     }
 
     /// Load an image or font record by index.
@@ -779,6 +787,7 @@ fn build_metadata(
             .or_else(|| exth.asin.clone())
             .or_else(|| exth.source.clone())
             .unwrap_or_default();
+        metadata.identifiers = exth.identifiers();
     }
 
     metadata
@@ -0,0 +1,224 @@
+//! CBZ (comic book ZIP) importer.
+//!
+//! A CBZ is a ZIP of page images and nothing else. Each image, in natural
+//! name order (`p2` before `p10`), becomes one fixed-layout page: a
+//! chapter whose IR is that image alone. The first page is the cover, and
+//! the page size comes from its dimensions. Without text or a manifest,
+//! the title is the file name.
+
+use std::io::Read;
+use std::path::Path;
+use std::sync::Arc;
+
+use zip::ZipArchive;
+
+use crate::import::directory::natural_cmp;
+use crate::import::{ChapterId, Importer, SpineEntry};
+use crate::io::{ByteSource, ByteSourceCursor, FileSource};
+use crate::model::{Chapter, Landmark, LandmarkType, Metadata, Node, NodeId, Role, TocEntry};
+
+/// Importer for CBZ comic archives.
+pub struct CbzImporter {
+    archive: ZipArchive<ByteSourceCursor>,
+    metadata: Metadata,
+    landmarks: Vec<Landmark>,
+    spine: Vec<SpineEntry>,
+    /// Synthesized page document paths, indexed by `ChapterId`.
+    page_paths: Vec<String>,
+    /// Archive paths of the page images, in reading order.
+    images: Vec<String>,
+}
+
+impl CbzImporter {
+    /// Create an importer from a ByteSource.
+    pub fn from_source(source: Arc<dyn ByteSource>) -> crate::Result<Self> {
+        let config = zip::read::Config {
+            archive_offset: zip::read::ArchiveOffset::Detect,
+        };
+        let mut archive =
+            ZipArchive::with_config(config, ByteSourceCursor::new(source)).map_err(zip_error)?;
+
+        let mut images = Vec::new();
+        let mut sizes = Vec::new();
+        for i in 0..archive.len() {
+            let file = archive.by_index_raw(i).map_err(zip_error)?;
+            let name = file.name().to_string();
+            if file.is_dir() || !is_page_image(&name) {
+                continue;
+            }
+            sizes.push((name.clone(), file.size()));
+            images.push(name);
+        }
+        if images.is_empty() {
+            return Err(crate::Error::Malformed {
+                format: crate::Format::Cbz,
+                context: "archive contains no images".to_string(),
+            });
+        }
+        images.sort_by(|a, b| natural_cmp(a, b));
+
+        let page_paths: Vec<String> = (1..=images.len())
+            .map(|n| format!("page_{n}.xhtml"))
+            .collect();
+        let spine = images
+            .iter()
+            .enumerate()
+            .map(|(index, image)| SpineEntry {
+                id: ChapterId(index as u32),
+                size_estimate: sizes
+                    .iter()
+                    .find(|(name, _)| name == image)
+                    .map_or(0, |&(_, size)| size as usize),
+            })
+            .collect();
+
+        let mut metadata = Metadata {
+            cover_image: Some(images[0].clone()),
+            ..Default::default()
+        };
+        metadata.rendition.layout = Some("pre-paginated".to_string());
+        metadata.rendition.viewport = read_entry(&archive, &images[0])
+            .ok()
+            .and_then(|data| crate::util::image_dimensions(&data));
+
+        let landmarks = vec![Landmark {
+            landmark_type: LandmarkType::Cover,
+            href: page_paths[0].clone(),
+            label: "Cover".to_string(),
+        }];
+
+        Ok(Self {
+            archive,
+            metadata,
+            landmarks,
+            spine,
+            page_paths,
+            images,
+        })
+    }
+
+    fn image(&self, id: ChapterId) -> crate::Result<&str> {
+        self.images
+            .get(id.0 as usize)
+            .map(String::as_str)
+            .ok_or_else(|| crate::Error::NotFound {
+                what: format!("page {}", id.0),
+            })
+    }
+}
+
+impl Importer for CbzImporter {
+    fn open(path: &Path) -> crate::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        let mut importer = Self::from_source(Arc::new(FileSource::new(file)?))?;
+        if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+            importer.metadata.title = stem.replace('_', " ").trim().to_string();
+        }
+        Ok(importer)
+    }
+
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn toc(&self) -> &[TocEntry] {
+        &[]
+    }
+
+    fn landmarks(&self) -> &[Landmark] {
+        &self.landmarks
+    }
+
+    fn spine(&self) -> &[SpineEntry] {
+        &self.spine
+    }
+
+    /// The page as IR: the image and nothing else.
+    fn load_chapter(&self, id: ChapterId) -> crate::Result<Chapter> {
+        let image = self.image(id)?;
+        let mut chapter = Chapter::new();
+        let node = chapter.alloc_node(Node::new(Role::Image));
+        chapter.append_child(NodeId::ROOT, node);
+        chapter.semantics.set_src(node, image);
+        chapter
+            .semantics
+            .set_alt(node, &format!("Page {}", id.0 + 1));
+        Ok(chapter)
+    }
+
+    fn source_id(&self, id: ChapterId) -> Option<&str> {
+        self.page_paths.get(id.0 as usize).map(String::as_str)
+    }
+
+    /// A minimal XHTML page showing the image.
+    fn load_raw(&self, id: ChapterId) -> crate::Result<Vec<u8>> {
+        let image = self.image(id)?;
+        let mut src = String::new();
+        crate::export::escape_xml_into(&mut src, image);
+        Ok(format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <html xmlns=\"http://www.w3.org/1999/xhtml\">\n\
+             <head><title>Page {n}</title></head>\n\
+             <body><img src=\"{src}\" alt=\"Page {n}\"/></body>\n\
+             </html>\n",
+            n = id.0 + 1
+        )
+        .into_bytes())
+    }
+
+    fn list_assets(&self) -> &[String] {
+        &self.images
+    }
+
+    fn load_asset(&self, path: &str) -> crate::Result<Vec<u8>> {
+        if !self.images.iter().any(|image| image == path) {
+            return Err(crate::Error::NotFound {
+                what: format!("{path} (in CBZ archive)"),
+            });
+        }
+        read_entry(&self.archive, path)
+    }
+
+    fn requires_normalized_export(&self) -> bool {
+        true
+    }
+}
+
+/// Whether an archive entry is a page: an image that isn't a hidden file
+/// or macOS resource-fork debris.
+fn is_page_image(name: &str) -> bool {
+    let file_name = name.rsplit('/').next().unwrap_or(name);
+    !name.starts_with("__MACOSX/")
+        && !file_name.starts_with('.')
+        && matches!(
+            crate::util::guess_media_type(name),
+            "image/jpeg" | "image/png" | "image/gif" | "image/webp"
+        )
+}
+
+fn read_entry(archive: &ZipArchive<ByteSourceCursor>, path: &str) -> crate::Result<Vec<u8>> {
+    // Clones share the parsed central directory; each read gets its own
+    // cursor.
+    let mut archive = archive.clone();
+    let file = archive.by_name(path).map_err(zip_error)?;
+    let cap = crate::util::MAX_DECOMPRESSED_ENTRY;
+    let mut out = Vec::new();
+    file.take(cap as u64 + 1).read_to_end(&mut out)?;
+    if out.len() > cap {
+        return Err(crate::Error::Malformed {
+            format: crate::Format::Cbz,
+            context: format!("{path}: decompressed entry exceeds size limit"),
+        });
+    }
+    Ok(out)
+}
+
+fn zip_error(e: zip::result::ZipError) -> crate::Error {
+    match e {
+        zip::result::ZipError::Io(io) => crate::Error::Io(io),
+        other => crate::Error::Malformed {
+            format: crate::Format::Cbz,
+            context: other.to_string(),
+        },
+    }
+}
@@ -0,0 +1,200 @@
+//! Packing a directory of loose files into an in-memory EPUB.
+//!
+//! An unpacked EPUB (with `META-INF/container.xml`) is zipped as-is. A
+//! directory holding only an OPF gets a container pointing at it. Anything
+//! else is treated as a folder of content: XHTML files, in natural name
+//! order, become the spine and every other file an asset, described by a
+//! synthesized OPF. The result is opened by the regular EPUB importer.
+
+use std::cmp::Ordering;
+use std::io::{Cursor, Write};
+use std::path::Path;
+
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+use crate::export::escape_xml;
+
+/// Archive path of the synthesized package document.
+const SYNTH_OPF: &str = "content.opf";
+
+/// Zip the files under `dir` into an EPUB, synthesizing the container and
+/// package document when the directory doesn't have them.
+pub(crate) fn pack_directory(dir: &Path) -> crate::Result<Vec<u8>> {
+    let mut files = Vec::new();
+    collect_files(dir, "", &mut files)?;
+    files.retain(|path| path != "mimetype");
+    files.sort_by(|a, b| natural_cmp(a, b));
+
+    let stored = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    zip.start_file("mimetype", stored).map_err(io_error)?;
+    zip.write_all(b"application/epub+zip")?;
+
+    if !files.iter().any(|path| path == "META-INF/container.xml") {
+        let opfs: Vec<&String> = files.iter().filter(|path| is_opf(path)).collect();
+        let rootfile = match opfs.as_slice() {
+            [opf] => opf.as_str(),
+            _ => {
+                let opf = synthesize_opf(dir, &files)?;
+                zip.start_file(SYNTH_OPF, stored).map_err(io_error)?;
+                zip.write_all(opf.as_bytes())?;
+                SYNTH_OPF
+            }
+        };
+        zip.start_file("META-INF/container.xml", stored)
+            .map_err(io_error)?;
+        zip.write_all(container_xml(rootfile).as_bytes())?;
+    }
+
+    for path in &files {
+        zip.start_file(path.as_str(), stored).map_err(io_error)?;
+        zip.write_all(&std::fs::read(dir.join(path))?)?;
+    }
+    Ok(zip.finish().map_err(io_error)?.into_inner())
+}
+
+fn io_error(e: zip::result::ZipError) -> std::io::Error {
+    std::io::Error::other(e)
+}
+
+/// Relative paths (forward slashes) of the regular files under `dir`.
+/// Hidden entries (`.DS_Store`, `.git/`) and symlinks are skipped.
+fn collect_files(dir: &Path, prefix: &str, out: &mut Vec<String>) -> crate::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else {
+            continue;
+        };
+        if name.starts_with('.') {
+            continue;
+        }
+        let path = format!("{prefix}{name}");
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_files(&entry.path(), &format!("{path}/"), out)?;
+        } else if file_type.is_file() {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn is_opf(path: &str) -> bool {
+    path.rsplit('.')
+        .next()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("opf"))
+}
+
+fn is_xhtml(path: &str) -> bool {
+    crate::util::guess_media_type(path) == "application/xhtml+xml"
+}
+
+fn container_xml(rootfile: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="{}" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#,
+        escape_xml(rootfile)
+    )
+}
+
+/// A minimal EPUB 3 package: the directory name as title, XHTML files as
+/// the spine, and every file in the manifest with its extension's type.
+fn synthesize_opf(dir: &Path, files: &[String]) -> crate::Result<String> {
+    if !files.iter().any(|path| is_xhtml(path)) {
+        return Err(crate::Error::NotFound {
+            what: format!("XHTML documents in {}", dir.display()),
+        });
+    }
+    let title = dir
+        .canonicalize()
+        .ok()
+        .and_then(|dir| dir.file_name()?.to_str().map(str::to_string))
+        .unwrap_or_else(|| "Untitled".to_string());
+
+    let mut manifest = String::new();
+    let mut spine = String::new();
+    for (i, path) in files.iter().enumerate() {
+        let id = format!("item{i}");
+        manifest.push_str(&format!(
+            "    <item id=\"{id}\" href=\"{}\" media-type=\"{}\"/>\n",
+            escape_xml(path),
+            crate::util::guess_media_type(path)
+        ));
+        if is_xhtml(path) {
+            spine.push_str(&format!("    <itemref idref=\"{id}\"/>\n"));
+        }
+    }
+    Ok(format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="uid">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:identifier id="uid">urn:boko:directory</dc:identifier>
+    <dc:title>{}</dc:title>
+  </metadata>
+  <manifest>
+{manifest}  </manifest>
+  <spine>
+{spine}  </spine>
+</package>
+"#,
+        escape_xml(&title)
+    ))
+}
+
+/// Compare paths with digit runs ordered numerically, so `ch2.xhtml` sorts
+/// before `ch10.xhtml`.
+pub(crate) fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a, b);
+    loop {
+        match (a.chars().next(), b.chars().next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let a_end = a.find(|c: char| !c.is_ascii_digit()).unwrap_or(a.len());
+                let b_end = b.find(|c: char| !c.is_ascii_digit()).unwrap_or(b.len());
+                let (a_num, b_num) = (
+                    a[..a_end].trim_start_matches('0'),
+                    b[..b_end].trim_start_matches('0'),
+                );
+                let order = a_num
+                    .len()
+                    .cmp(&b_num.len())
+                    .then_with(|| a_num.cmp(b_num))
+                    .then_with(|| a_end.cmp(&b_end));
+                if order != Ordering::Equal {
+                    return order;
+                }
+                (a, b) = (&a[a_end..], &b[b_end..]);
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(&y);
+                }
+                (a, b) = (&a[x.len_utf8()..], &b[y.len_utf8()..]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn natural_order_compares_numbers_by_value() {
+        let mut paths = vec!["ch10.xhtml", "ch2.xhtml", "ch1.xhtml", "appendix.xhtml"];
+        paths.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(
+            paths,
+            ["appendix.xhtml", "ch1.xhtml", "ch2.xhtml", "ch10.xhtml"]
+        );
+    }
+}
@@ -1,6 +1,7 @@
 //! EPUB format importer - handles all IO.
 
 use std::collections::HashMap;
+use std::io::Read;
 use std::path::Path;
 use std::sync::{Arc, RwLock};
 
@@ -9,8 +10,8 @@ use zip::ZipArchive;
 use crate::dom::Stylesheet;
 use crate::epub::{parse_container_xml, parse_nav_landmarks, parse_nav_toc, parse_ncx, parse_opf};
 use crate::import::{ChapterId, Importer, SpineEntry, resolve_path_based_href};
-use crate::io::{ByteSource, ByteSourceCursor, FileSource};
-use crate::model::{AnchorTarget, Chapter, GlobalNodeId, Landmark, Metadata, TocEntry};
+use crate::io::{ByteSource, ByteSourceCursor, FileSource, MemorySource};
+use crate::model::{AnchorTarget, GlobalNodeId, Landmark, LandmarkType, Metadata, TocEntry};
 
 impl From<zip::result::ZipError> for crate::Error {
     fn from(e: zip::result::ZipError) -> Self {
@@ -35,6 +36,10 @@ pub struct EpubImporter {
     /// Cached ZIP entry locations: path -> ZipEntryLoc.
     zip_index: HashMap<String, ZipEntryLoc>,
 
+    /// The zip crate's reader, for entries the random-access path can't
+    /// read (encrypted, or with an unresolved offset or other compression).
+    fallback: ZipReader,
+
     /// Book metadata.
     metadata: Metadata,
 
@@ -53,6 +58,9 @@ pub struct EpubImporter {
     /// All asset paths in the ZIP (archive entry names, forward slashes).
     assets: Vec<String>,
 
+    /// Manifest `media-type` by archive path.
+    media_types: HashMap<String, String>,
+
     /// Cached parsed stylesheets. Behind a lock so parallel chapter
     /// compilation ([`Importer::load_chapters`]) can share it through `&self`.
     css_cache: RwLock<HashMap<String, Arc<Stylesheet>>>,
@@ -72,10 +80,63 @@ pub struct EpubImporter {
 
 #[derive(Clone, Copy)]
 struct ZipEntryLoc {
-    data_offset: u64,
+    /// Start of the entry's data, past its local header; `None` when the
+    /// zip crate could not resolve it.
+    data_offset: Option<u64>,
     compressed_size: u64,
     uncompressed_size: u64,
-    compression: u16, // 0 = Store, 8 = Deflate
+    compression: zip::CompressionMethod,
+    /// ZIP-encrypted (ZipCrypto or AES): read through [`ZipReader`].
+    encrypted: bool,
+}
+
+impl ZipEntryLoc {
+    /// Whether the entry can be read with one `read_at` of its data and an
+    /// inflate, bypassing the zip crate.
+    fn is_direct(&self) -> bool {
+        !self.encrypted
+            && self.data_offset.is_some()
+            && matches!(
+                self.compression,
+                zip::CompressionMethod::Stored | zip::CompressionMethod::Deflated
+            )
+    }
+}
+
+/// Reads entries through the zip crate: password-encrypted ones (both
+/// traditional ZipCrypto and AES, with the `zip-aes` feature), and any the
+/// random-access path can't.
+struct ZipReader {
+    archive: ZipArchive<ByteSourceCursor>,
+    password: Option<Vec<u8>>,
+}
+
+impl ZipReader {
+    fn read(&self, path: &str) -> crate::Result<Vec<u8>> {
+        // Clones share the parsed central directory; each read gets its own
+        // cursor so parallel chapter loads don't contend.
+        let mut archive = self.archive.clone();
+        let file = match &self.password {
+            Some(password) => archive.by_name_decrypt(path, password),
+            None => archive.by_name(path),
+        }
+        .map_err(|e| match e {
+            zip::result::ZipError::InvalidPassword => {
+                crate::Error::WrongPassword(crate::Format::Epub)
+            }
+            other => other.into(),
+        })?;
+        let cap = crate::util::MAX_DECOMPRESSED_ENTRY;
+        let mut out = Vec::new();
+        file.take(cap as u64 + 1).read_to_end(&mut out)?;
+        if out.len() > cap {
+            return Err(crate::Error::Malformed {
+                format: crate::Format::Epub,
+                context: format!("{path}: decompressed entry exceeds size limit"),
+            });
+        }
+        Ok(out)
+    }
 }
 
 impl Importer for EpubImporter {
@@ -119,6 +180,10 @@ impl Importer for EpubImporter {
         &self.assets
     }
 
+    fn asset_media_type(&self, path: &str) -> Option<&str> {
+        self.media_types.get(path).map(String::as_str)
+    }
+
     fn load_asset(&self, path: &str) -> crate::Result<Vec<u8>> {
         let data = self.read_entry(path)?;
         if let Some(obfuscation) = self.obfuscated_fonts.get(path) {
@@ -133,9 +198,14 @@ impl Importer for EpubImporter {
         {
             return Some(Arc::clone(sheet));
         }
-        let css_bytes = self.read_entry(path).ok()?;
-        let css_str = String::from_utf8_lossy(&css_bytes);
-        let sheet = Arc::new(Stylesheet::parse(&css_str));
+        let mut css_bytes = self.read_entry(path).ok()?;
+        // Some pipelines store stylesheets gzipped inside the archive.
+        if css_bytes.starts_with(&crate::util::GZIP_MAGIC) {
+            css_bytes =
+                crate::util::bounded_gunzip(&css_bytes, crate::util::MAX_DECOMPRESSED_ENTRY)
+                    .ok()?;
+        }
+        let sheet = Arc::new(crate::import::parse_stylesheet_at(&css_bytes, path));
         // Two threads may race to parse the same sheet; the first insert wins
         // so every chapter ends up sharing one Arc.
         match self.css_cache.write() {
@@ -144,25 +214,18 @@ impl Importer for EpubImporter {
         }
     }
 
-    fn index_anchors(&self, chapters: &[(ChapterId, Arc<Chapter>)]) {
+    fn index_anchor_ids(&self, ids: &[(GlobalNodeId, &str)]) {
         let mut anchor_map = HashMap::new();
-
-        for (chapter_id, chapter) in chapters {
-            // Get the chapter's source path
-            let chapter_path = match self.spine_paths.get(chapter_id.0 as usize) {
-                Some(p) => p.split('#').next().unwrap_or(p),
-                None => continue,
+        for &(node, id) in ids {
+            let Some(chapter_path) = self
+                .spine_paths
+                .get(node.chapter.0 as usize)
+                .map(|p| p.split('#').next().unwrap_or(p))
+            else {
+                continue;
             };
-
-            // Walk the chapter and record all nodes with IDs
-            for node_id in chapter.iter_dfs() {
-                if let Some(id) = chapter.semantics.id(node_id) {
-                    let key = format!("{}#{}", chapter_path, id);
-                    anchor_map.insert(key, GlobalNodeId::new(*chapter_id, node_id));
-                }
-            }
+            anchor_map.insert(format!("{}#{}", chapter_path, id), node);
         }
-
         if let Ok(mut map) = self.anchor_map.write() {
             *map = anchor_map;
         }
@@ -180,26 +243,64 @@ impl Importer for EpubImporter {
 }
 
 impl EpubImporter {
+    /// Create an importer over an EPUB already in memory.
+    ///
+    /// The buffer is read in place, without a temp file or a second copy:
+    /// pass an owned `Vec<u8>`, an `Arc<[u8]>` shared with the caller, or a
+    /// `&'static [u8]` from `include_bytes!`. Callers holding only a
+    /// borrowed slice can use [`Book::from_bytes`](crate::Book::from_bytes),
+    /// which copies it once.
+    pub fn from_bytes<B>(data: B) -> crate::Result<Self>
+    where
+        B: AsRef<[u8]> + Send + Sync + 'static,
+    {
+        Self::from_source(Arc::new(MemorySource::new(data)))
+    }
+
     /// Create an importer from a ByteSource.
+    ///
+    /// Fails with [`Error::PasswordRequired`](crate::Error::PasswordRequired)
+    /// if the archive's entries are encrypted.
     pub fn from_source(source: Arc<dyn ByteSource>) -> crate::Result<Self> {
-        // 1. Scan ZIP central directory and cache entry locations
+        Self::from_source_with_password(source, None)
+    }
+
+    /// Create an importer from a ByteSource, decrypting ZIP-encrypted
+    /// entries with `password`. Unencrypted entries are read as usual.
+    pub fn from_source_with_password(
+        source: Arc<dyn ByteSource>,
+        password: Option<&str>,
+    ) -> crate::Result<Self> {
+        // 1. Scan ZIP central directory and cache entry locations. The End of
+        // Central Directory record is located from the end of the file, and
+        // the archive's true start is detected from where the central
+        // directory actually sits, so EPUBs behind a leading stub (SFX
+        // wrappers, polyglots, junk from a broken download) still open —
+        // whether or not the stub's length was added to the stored offsets.
         let cursor = ByteSourceCursor::new(source.clone());
-        let mut archive = ZipArchive::new(cursor)?;
+        let config = zip::read::Config {
+            archive_offset: zip::read::ArchiveOffset::Detect,
+        };
+        let mut archive = ZipArchive::with_config(config, cursor)?;
 
         let mut zip_index = HashMap::new();
         let mut assets = Vec::new();
 
         for i in 0..archive.len() {
-            let file = archive.by_index(i)?;
+            // Raw access: only the entry's location is needed here, and an
+            // encrypted entry can't be opened for reading without the
+            // password.
+            let file = archive.by_index_raw(i)?;
             let name = file.name().to_string();
 
             zip_index.insert(
                 name.clone(),
                 ZipEntryLoc {
-                    data_offset: file.data_start().unwrap(),
+                    data_offset: file.data_start(),
                     compressed_size: file.compressed_size(),
                     uncompressed_size: file.size(),
-                    compression: compression_to_u16(file.compression()),
+                    compression: file.compression(),
+                    encrypted: file.encrypted(),
                 },
             );
             // Directory entries are ZIP bookkeeping, not assets; surfacing
@@ -209,8 +310,16 @@ impl EpubImporter {
             }
         }
 
+        if password.is_none() && zip_index.values().any(|loc| loc.encrypted) {
+            return Err(crate::Error::PasswordRequired(crate::Format::Epub));
+        }
+        let fallback = ZipReader {
+            archive,
+            password: password.map(|p| p.as_bytes().to_vec()),
+        };
+
         // 2. Find OPF path from container.xml
-        let container_bytes = read_entry(&source, &zip_index, "META-INF/container.xml")?;
+        let container_bytes = read_entry(&source, &zip_index, &fallback, "META-INF/container.xml")?;
         let opf_path = parse_container_xml(&container_bytes)?;
         // Directory of the OPF (including trailing slash), or "" for root.
         let opf_base = match opf_path.rfind('/') {
@@ -219,7 +328,7 @@ impl EpubImporter {
         };
 
         // 3. Parse OPF
-        let opf_bytes = read_entry(&source, &zip_index, &opf_path)?;
+        let opf_bytes = read_entry(&source, &zip_index, &fallback, &opf_path)?;
         let hint_encoding = crate::util::extract_xml_encoding(&opf_bytes);
         let opf_str = crate::util::decode_text(&opf_bytes, hint_encoding);
         let opf = parse_opf(&opf_str)?;
@@ -253,7 +362,7 @@ impl EpubImporter {
         // TOC fallback (step 5) and landmarks (step 6).
         let nav_str: Option<String> = opf.nav_href.as_ref().and_then(|nav_href| {
             let nav_path = crate::import::resolve_relative_path(&opf_path, nav_href);
-            read_entry(&source, &zip_index, &nav_path)
+            read_entry(&source, &zip_index, &fallback, &nav_path)
                 .ok()
                 .map(|nav_bytes| {
                     let hint_encoding = crate::util::extract_xml_encoding(&nav_bytes);
@@ -267,7 +376,7 @@ impl EpubImporter {
         // usable NCX fall back to `<nav epub:type="toc">`.
         let mut toc = if let Some(ncx_href) = &opf.ncx_href {
             let ncx_path = crate::import::resolve_relative_path(&opf_path, ncx_href);
-            if let Ok(ncx_bytes) = read_entry(&source, &zip_index, &ncx_path) {
+            if let Ok(ncx_bytes) = read_entry(&source, &zip_index, &fallback, &ncx_path) {
                 let hint_encoding = crate::util::extract_xml_encoding(&ncx_bytes);
                 let ncx_str = crate::util::decode_text(&ncx_bytes, hint_encoding);
                 // Navigation is auxiliary: a malformed NCX degrades to an
@@ -304,6 +413,15 @@ impl EpubImporter {
             Vec::new()
         };
 
+        let media_types = opf
+            .manifest
+            .values()
+            .map(|(href, media_type)| {
+                let path = crate::import::resolve_relative_path(&opf_path, href);
+                (path, media_type.clone())
+            })
+            .collect();
+
         // Build path -> ChapterId map
         let mut path_to_chapter = HashMap::new();
         for (i, path) in spine_paths.iter().enumerate() {
@@ -323,26 +441,67 @@ impl EpubImporter {
             metadata.cover_image = Some(crate::import::resolve_relative_path(&opf_path, href));
         }
 
+        // Without a declared cover image, the cover page's image stands in:
+        // the page the cover landmark targets, else a first spine document
+        // named like a cover.
+        if metadata.cover_image.is_none() {
+            let cover_page = landmarks
+                .iter()
+                .find(|landmark| landmark.landmark_type == LandmarkType::Cover)
+                .map(|landmark| landmark.href.split('#').next().unwrap_or_default())
+                .filter(|page| !page.is_empty())
+                .or_else(|| {
+                    spine_paths.first().map(String::as_str).filter(|path| {
+                        let name = path.rsplit('/').next().unwrap_or(path);
+                        name.to_ascii_lowercase().contains("cover")
+                    })
+                });
+            metadata.cover_image = cover_page.and_then(|page| {
+                let bytes = read_entry(&source, &zip_index, &fallback, page).ok()?;
+                let hint_encoding = crate::util::extract_xml_encoding(&bytes);
+                let html = crate::util::decode_text(&bytes, hint_encoding);
+                let href = crate::epub::parse_cover_page_image(&html)?;
+                let href = href.split('#').next().unwrap_or_default();
+                let image = crate::import::resolve_relative_path(page, href);
+                zip_index.contains_key(&image).then_some(image)
+            });
+        }
+
+        // Fixed-layout documents each declare their page size; the first
+        // one's stands for the book.
+        if metadata.rendition.is_fixed_layout()
+            && metadata.rendition.viewport.is_none()
+            && let Some(first) = spine_paths.first()
+            && let Ok(bytes) = read_entry(&source, &zip_index, &fallback, first)
+        {
+            let hint_encoding = crate::util::extract_xml_encoding(&bytes);
+            let html = crate::util::decode_text(&bytes, hint_encoding);
+            metadata.rendition.viewport = crate::epub::parse_viewport(&html);
+        }
+
         // Font obfuscation manifest (META-INF/encryption.xml), if any. Every
         // dc:identifier is a key candidate: the obfuscation key derives from
         // the package unique-identifier, which is not always the first (or
         // only) identifier declared.
-        let obfuscated_fonts = read_entry(&source, &zip_index, "META-INF/encryption.xml")
-            .map(|xml| {
-                let identifiers = collect_identifiers(&opf_str);
-                parse_encryption_xml(&xml, &identifiers, &opf_base)
-            })
-            .unwrap_or_default();
+        let obfuscated_fonts =
+            read_entry(&source, &zip_index, &fallback, "META-INF/encryption.xml")
+                .map(|xml| {
+                    let identifiers = collect_identifiers(&opf_str);
+                    parse_encryption_xml(&xml, &identifiers, &opf_base)
+                })
+                .unwrap_or_default();
 
         Ok(Self {
             source,
             zip_index,
+            fallback,
             metadata,
             toc,
             landmarks,
             spine,
             spine_paths,
             assets,
+            media_types,
             path_to_chapter,
             anchor_map: RwLock::new(HashMap::new()),
             css_cache: RwLock::new(HashMap::new()),
@@ -352,7 +511,7 @@ impl EpubImporter {
 
     /// Read and decompress a ZIP entry by path.
     fn read_entry(&self, path: &str) -> crate::Result<Vec<u8>> {
-        read_entry(&self.source, &self.zip_index, path)
+        read_entry(&self.source, &self.zip_index, &self.fallback, path)
     }
 }
 
@@ -363,34 +522,40 @@ impl EpubImporter {
 fn read_entry(
     source: &Arc<dyn ByteSource>,
     index: &HashMap<String, ZipEntryLoc>,
+    fallback: &ZipReader,
     path: &str,
 ) -> crate::Result<Vec<u8>> {
     let loc = index.get(path).ok_or_else(|| crate::Error::NotFound {
         what: format!("{} (in EPUB archive)", path),
     })?;
+    let Some(data_offset) = loc.data_offset.filter(|_| loc.is_direct()) else {
+        // Name the method up front: the zip crate only says "not supported".
+        if !zip::SUPPORTED_COMPRESSION_METHODS.contains(&loc.compression) {
+            return Err(crate::Error::UnsupportedFormat {
+                detail: format!(
+                    "{path}: {} compression is not supported",
+                    compression_method_name(loc.compression)
+                ),
+            });
+        }
+        return fallback.read(path);
+    };
 
     // Read compressed data via random access
-    let compressed = source.read_at(loc.data_offset, loc.compressed_size as usize)?;
-
-    // Decompress
-    match loc.compression {
-        0 => Ok(compressed), // Stored
-        8 => {
-            // Deflate. The uncompressed size is an untrusted central-directory
-            // field, so cap the output to stop decompression bombs rather than
-            // trusting it (see `bounded_inflate`).
-            let out = crate::util::bounded_inflate(
-                &compressed,
-                loc.uncompressed_size,
-                crate::util::MAX_DECOMPRESSED_ENTRY,
-            )?;
-            Ok(out)
-        }
-        method => Err(crate::Error::Malformed {
-            format: crate::Format::Epub,
-            context: format!("unsupported compression method: {}", method),
-        }),
+    let compressed = source.read_at(data_offset, loc.compressed_size as usize)?;
+
+    if loc.compression == zip::CompressionMethod::Stored {
+        return Ok(compressed); // Stored
     }
+    // Deflate. The uncompressed size is an untrusted central-directory
+    // field, so cap the output to stop decompression bombs rather than
+    // trusting it (see `bounded_inflate`).
+    let out = crate::util::bounded_inflate(
+        &compressed,
+        loc.uncompressed_size,
+        crate::util::MAX_DECOMPRESSED_ENTRY,
+    )?;
+    Ok(out)
 }
 
 // ============================================================================
@@ -585,12 +750,38 @@ fn deobfuscate_font(data: Vec<u8>, obfuscation: &FontObfuscation) -> Vec<u8> {
     data
 }
 
-fn compression_to_u16(method: zip::CompressionMethod) -> u16 {
-    match method {
-        zip::CompressionMethod::Stored => 0,
-        zip::CompressionMethod::Deflated => 8,
-        _ => 255,
-    }
+/// Human-readable name of a ZIP compression method (APPNOTE 4.4.5), for
+/// errors about methods boko can't read.
+fn compression_method_name(method: zip::CompressionMethod) -> String {
+    use zip::CompressionMethod as M;
+
+    const NAMES: &[(M, &str)] = &[
+        (M::SHRINK, "Shrink"),
+        (M::REDUCE_1, "Reduce"),
+        (M::REDUCE_2, "Reduce"),
+        (M::REDUCE_3, "Reduce"),
+        (M::REDUCE_4, "Reduce"),
+        (M::IMPLODE, "Implode"),
+        (M::DEFLATE64, "Deflate64"),
+        (M::PKWARE_IMPLODE, "PKWARE DCL Implode"),
+        (M::BZIP2, "bzip2"),
+        (M::LZMA, "LZMA"),
+        (M::IBM_ZOS_CMPSC, "IBM z/OS CMPSC"),
+        (M::IBM_TERSE, "IBM TERSE"),
+        (M::ZSTD_DEPRECATED, "Zstandard"),
+        (M::ZSTD, "Zstandard"),
+        (M::MP3, "MP3"),
+        (M::XZ, "XZ"),
+        (M::JPEG, "JPEG"),
+        (M::WAVPACK, "WavPack"),
+        (M::PPMD, "PPMd"),
+        (M::AES, "AES"),
+    ];
+    NAMES
+        .iter()
+        .find(|(m, _)| *m == method)
+        .map(|(_, name)| (*name).to_string())
+        .unwrap_or_else(|| format!("unknown ({method:?})"))
 }
 
 /// Prepend base path to TOC entry hrefs (NCX/nav use relative paths).
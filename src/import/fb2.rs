@@ -0,0 +1,951 @@
+//! FictionBook 2 (`.fb2`) importer.
+//!
+//! An FB2 file is one XML document: a `<description>` carrying the
+//! metadata, a main `<body>` of nested `<section>`s (optionally followed by
+//! a notes body), and the images as base64 `<binary>` blocks. The whole
+//! file is parsed on open. Each top-level section of the main body becomes
+//! an XHTML chapter that goes through the normal HTML pipeline, section
+//! titles become the TOC, every further body becomes a chapter of
+//! footnotes, and binaries become `images/` assets.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use base64::Engine;
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+use crate::export::escape_xml_into;
+use crate::import::{ChapterId, Importer, SpineEntry, resolve_path_based_href};
+use crate::io::{ByteSource, FileSource};
+use crate::model::{
+    AnchorTarget, CollectionInfo, Contributor, GlobalNodeId, Identifier, Landmark, Metadata,
+    TocEntry,
+};
+use crate::util::MAX_TREE_DEPTH;
+
+/// Styles for the FB2 constructs that have no HTML element of their own.
+const STYLE: &str = "\
+.subtitle, .poem-title { font-weight: bold; text-align: center; }
+.epigraph { margin-left: 30%; font-style: italic; }
+.text-author { text-align: right; font-style: italic; }
+.poem { margin: 1em 2em; }
+.stanza { margin: 1em 0; }
+.v { margin: 0; text-indent: 0; }
+.image { text-align: center; }
+";
+
+/// Importer for FictionBook 2 documents.
+pub struct Fb2Importer {
+    metadata: Metadata,
+    toc: Vec<TocEntry>,
+    spine: Vec<SpineEntry>,
+    /// Chapter file paths, indexed by `ChapterId`.
+    chapter_paths: Vec<String>,
+    /// Generated XHTML per chapter, indexed by `ChapterId`.
+    chapters: Vec<Vec<u8>>,
+    /// Asset paths of the decoded binaries, in document order.
+    assets: Vec<String>,
+    /// Asset path → (bytes, declared media type).
+    binaries: HashMap<String, (Vec<u8>, String)>,
+    path_to_chapter: HashMap<String, ChapterId>,
+    /// `path#id` → node, built by `index_anchors`.
+    anchor_map: RwLock<HashMap<String, GlobalNodeId>>,
+}
+
+impl Fb2Importer {
+    /// Create an importer from a ByteSource.
+    pub fn from_source(source: Arc<dyn ByteSource>) -> crate::Result<Self> {
+        let bytes = source.read_at(0, source.len() as usize)?;
+        let text = crate::util::decode_text(&bytes, crate::util::extract_xml_encoding(&bytes));
+        let root = parse_tree(&text)?;
+        if root.name != "FictionBook" {
+            return Err(malformed(format!(
+                "root element is <{}>, not <FictionBook>",
+                root.name
+            )));
+        }
+
+        let mut binaries = HashMap::new();
+        let mut assets = Vec::new();
+        // Binary id → asset path, for `<image l:href="#id">`.
+        let mut images = HashMap::new();
+        for binary in root.elements().filter(|e| e.name == "binary") {
+            let Some(id) = binary.attr("id").filter(|id| !id.is_empty()) else {
+                continue;
+            };
+            let encoded: String = binary
+                .text()
+                .chars()
+                .filter(|c| !c.is_ascii_whitespace())
+                .collect();
+            let Ok(data) = base64::engine::general_purpose::STANDARD.decode(encoded) else {
+                continue;
+            };
+            let media_type = binary.attr("content-type").unwrap_or_default().to_string();
+            let path = image_path(id, &media_type, &assets);
+            images.insert(id.to_string(), path.clone());
+            binaries.insert(path.clone(), (data, media_type));
+            assets.push(path);
+        }
+
+        let metadata = root
+            .child("description")
+            .map(|description| parse_description(description, &images))
+            .unwrap_or_default();
+
+        let mut converter = Converter {
+            images,
+            ..Default::default()
+        };
+        converter.convert(&root);
+        if converter.chapters.is_empty() {
+            return Err(malformed("no <body> content".to_string()));
+        }
+
+        let mut spine = Vec::with_capacity(converter.chapters.len());
+        let mut chapter_paths = Vec::with_capacity(converter.chapters.len());
+        let mut chapters = Vec::with_capacity(converter.chapters.len());
+        let mut path_to_chapter = HashMap::new();
+        for (index, (path, xhtml)) in converter.chapters.into_iter().enumerate() {
+            let id = ChapterId(index as u32);
+            spine.push(SpineEntry {
+                id,
+                size_estimate: xhtml.len(),
+            });
+            path_to_chapter.insert(path.clone(), id);
+            chapter_paths.push(path);
+            chapters.push(xhtml.into_bytes());
+        }
+
+        Ok(Self {
+            metadata,
+            toc: converter.toc,
+            spine,
+            chapter_paths,
+            chapters,
+            assets,
+            binaries,
+            path_to_chapter,
+            anchor_map: RwLock::new(HashMap::new()),
+        })
+    }
+}
+
+impl Importer for Fb2Importer {
+    fn open(path: &Path) -> crate::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        Self::from_source(Arc::new(FileSource::new(file)?))
+    }
+
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn toc(&self) -> &[TocEntry] {
+        &self.toc
+    }
+
+    fn landmarks(&self) -> &[Landmark] {
+        &[]
+    }
+
+    fn spine(&self) -> &[SpineEntry] {
+        &self.spine
+    }
+
+    fn source_id(&self, id: ChapterId) -> Option<&str> {
+        self.chapter_paths.get(id.0 as usize).map(String::as_str)
+    }
+
+    fn load_raw(&self, id: ChapterId) -> crate::Result<Vec<u8>> {
+        self.chapters
+            .get(id.0 as usize)
+            .cloned()
+            .ok_or_else(|| crate::Error::NotFound {
+                what: format!("chapter {}", id.0),
+            })
+    }
+
+    fn list_assets(&self) -> &[String] {
+        &self.assets
+    }
+
+    fn load_asset(&self, path: &str) -> crate::Result<Vec<u8>> {
+        self.binaries
+            .get(path)
+            .map(|(data, _)| data.clone())
+            .ok_or_else(|| crate::Error::NotFound {
+                what: format!("asset {path}"),
+            })
+    }
+
+    fn asset_media_type(&self, path: &str) -> Option<&str> {
+        self.binaries
+            .get(path)
+            .map(|(_, media_type)| media_type.as_str())
+            .filter(|media_type| !media_type.is_empty())
+    }
+
+    fn index_anchor_ids(&self, ids: &[(GlobalNodeId, &str)]) {
+        let mut anchor_map = HashMap::new();
+        for &(node, id) in ids {
+            let Some(chapter_path) = self.source_id(node.chapter) else {
+                continue;
+            };
+            anchor_map.insert(format!("{}#{}", chapter_path, id), node);
+        }
+        if let Ok(mut map) = self.anchor_map.write() {
+            *map = anchor_map;
+        }
+    }
+
+    fn resolve_href(&self, from_chapter: ChapterId, href: &str) -> Option<AnchorTarget> {
+        let from_path = self.source_id(from_chapter)?;
+        resolve_path_based_href(
+            from_path,
+            href,
+            |p| self.path_to_chapter.get(p).copied(),
+            |k| self.anchor_map.read().ok().and_then(|m| m.get(k).copied()),
+        )
+    }
+}
+
+fn malformed(context: String) -> crate::Error {
+    crate::Error::Malformed {
+        format: crate::Format::Fb2,
+        context,
+    }
+}
+
+/// Asset path for a binary: its id under `images/`, with an extension from
+/// the content type when the id has none, made unique among `taken`.
+fn image_path(id: &str, media_type: &str, taken: &[String]) -> String {
+    let name: String = id
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let ext = match media_type {
+        "image/jpeg" => "jpg",
+        "image/png" => "png",
+        "image/gif" => "gif",
+        "image/svg+xml" => "svg",
+        "image/webp" => "webp",
+        _ => "",
+    };
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem.to_string(), ext.to_string()),
+        _ => (name, ext.to_string()),
+    };
+    let with_ext = |stem: &str| {
+        if ext.is_empty() {
+            format!("images/{stem}")
+        } else {
+            format!("images/{stem}.{ext}")
+        }
+    };
+    let mut path = with_ext(&stem);
+    let mut n = 2;
+    while taken.contains(&path) {
+        path = with_ext(&format!("{stem}-{n}"));
+        n += 1;
+    }
+    path
+}
+
+// --- Document tree ---
+
+/// An element of the parsed document, with namespace prefixes dropped from
+/// element and attribute names (`l:href` is `href`).
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<Content>,
+}
+
+#[derive(Debug)]
+enum Content {
+    Element(Element),
+    Text(String),
+}
+
+impl Element {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|child| match child {
+            Content::Element(element) => Some(element),
+            Content::Text(_) => None,
+        })
+    }
+
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.elements().find(|e| e.name == name)
+    }
+
+    /// All descendant text, concatenated.
+    fn text(&self) -> String {
+        let mut out = String::new();
+        self.collect_text(&mut out);
+        out
+    }
+
+    fn collect_text(&self, out: &mut String) {
+        for child in &self.children {
+            match child {
+                Content::Text(text) => out.push_str(text),
+                Content::Element(element) => element.collect_text(out),
+            }
+        }
+    }
+
+    /// Descendant text with runs of whitespace collapsed and ends trimmed.
+    fn clean_text(&self) -> String {
+        self.text().split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+/// Parse the document into an element tree rooted at its root element.
+fn parse_tree(text: &str) -> crate::Result<Element> {
+    let mut reader = Reader::from_str(text);
+    reader.config_mut().trim_text(false);
+
+    fn open(e: &BytesStart) -> Element {
+        let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+        let attrs = e
+            .attributes()
+            .flatten()
+            .map(|attr| {
+                let key = String::from_utf8_lossy(attr.key.local_name().as_ref()).into_owned();
+                let value = attr
+                    .unescape_value()
+                    .map(|v| v.into_owned())
+                    .unwrap_or_default();
+                (key, value)
+            })
+            .collect();
+        Element {
+            name,
+            attrs,
+            children: Vec::new(),
+        }
+    }
+
+    fn push_text(stack: &mut [Element], text: &str) {
+        let Some(parent) = stack.last_mut() else {
+            return;
+        };
+        match parent.children.last_mut() {
+            Some(Content::Text(existing)) => existing.push_str(text),
+            _ => parent.children.push(Content::Text(text.to_string())),
+        }
+    }
+
+    let mut stack: Vec<Element> = Vec::new();
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                if stack.len() >= MAX_TREE_DEPTH {
+                    return Err(malformed("elements nested too deeply".to_string()));
+                }
+                stack.push(open(&e));
+            }
+            Ok(Event::Empty(e)) => {
+                let element = open(&e);
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(Content::Element(element)),
+                    None => return Ok(element),
+                }
+            }
+            Ok(Event::End(_)) => {
+                let Some(element) = stack.pop() else {
+                    return Err(malformed("unbalanced end tag".to_string()));
+                };
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(Content::Element(element)),
+                    None => return Ok(element),
+                }
+            }
+            Ok(Event::Text(e)) => push_text(&mut stack, &String::from_utf8_lossy(e.as_ref())),
+            Ok(Event::CData(e)) => push_text(&mut stack, &String::from_utf8_lossy(e.as_ref())),
+            Ok(Event::GeneralRef(e)) => {
+                let entity = String::from_utf8_lossy(e.as_ref());
+                if let Some(resolved) = crate::epub::resolve_entity(&entity) {
+                    push_text(&mut stack, &resolved);
+                }
+            }
+            Ok(Event::Eof) => return Err(malformed("no root element".to_string())),
+            Err(e) => return Err(malformed(e.to_string())),
+            _ => {}
+        }
+    }
+}
+
+// --- Metadata ---
+
+fn parse_description(description: &Element, images: &HashMap<String, String>) -> Metadata {
+    let mut metadata = Metadata::default();
+
+    if let Some(info) = description.child("title-info") {
+        for element in info.elements() {
+            match element.name.as_str() {
+                "book-title" => metadata.title = element.clean_text(),
+                "author" => {
+                    if let Some((name, sort)) = person_name(element) {
+                        if metadata.authors.is_empty() {
+                            metadata.author_sort = sort;
+                        }
+                        metadata.authors.push(name);
+                    }
+                }
+                "translator" => {
+                    if let Some((name, file_as)) = person_name(element) {
+                        metadata.contributors.push(Contributor {
+                            name,
+                            file_as,
+                            role: Some("trl".to_string()),
+                        });
+                    }
+                }
+                "genre" => {
+                    let genre = element.clean_text();
+                    if !genre.is_empty() {
+                        metadata.subjects.push(genre);
+                    }
+                }
+                "keywords" => metadata.subjects.extend(
+                    element
+                        .text()
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|keyword| !keyword.is_empty())
+                        .map(str::to_string),
+                ),
+                "lang" => metadata.language = element.clean_text(),
+                "date" => {
+                    let date = element
+                        .attr("value")
+                        .map(str::to_string)
+                        .unwrap_or_else(|| element.clean_text());
+                    if !date.is_empty() {
+                        metadata.date = Some(date);
+                    }
+                }
+                "annotation" => {
+                    let mut html = String::new();
+                    let mut converter = Converter::default();
+                    converter.write_blocks(&mut html, element, "", 1);
+                    if !html.is_empty() {
+                        metadata.description = Some(html);
+                    }
+                }
+                "coverpage" => {
+                    metadata.cover_image = element
+                        .elements()
+                        .filter(|e| e.name == "image")
+                        .find_map(|image| images.get(image.attr("href")?.strip_prefix('#')?))
+                        .cloned();
+                }
+                "sequence" => {
+                    if let Some(name) = element.attr("name").filter(|name| !name.is_empty()) {
+                        metadata.collection = Some(CollectionInfo {
+                            name: name.to_string(),
+                            collection_type: Some("series".to_string()),
+                            position: element.attr("number").and_then(|n| n.trim().parse().ok()),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if let Some(id) = description
+        .child("document-info")
+        .and_then(|info| info.child("id"))
+        .map(Element::clean_text)
+        .filter(|id| !id.is_empty())
+    {
+        metadata.identifiers.push(Identifier {
+            scheme: None,
+            value: id,
+        });
+    }
+
+    if let Some(info) = description.child("publish-info") {
+        for element in info.elements() {
+            let text = element.clean_text();
+            if text.is_empty() {
+                continue;
+            }
+            match element.name.as_str() {
+                "publisher" => metadata.publisher = Some(text),
+                "isbn" => metadata.identifiers.push(Identifier {
+                    scheme: Some("ISBN".to_string()),
+                    value: text,
+                }),
+                "year" if metadata.date.is_none() => metadata.date = Some(text),
+                _ => {}
+            }
+        }
+    }
+
+    if let Some(first) = metadata.identifiers.first() {
+        metadata.identifier = first.value.clone();
+    }
+    metadata
+}
+
+/// Display name and sort key (`Last, First`) of an `<author>` or
+/// `<translator>`, falling back to the nickname.
+fn person_name(person: &Element) -> Option<(String, Option<String>)> {
+    let part = |name: &str| {
+        person
+            .child(name)
+            .map(Element::clean_text)
+            .filter(|text| !text.is_empty())
+    };
+    let given: Vec<String> = ["first-name", "middle-name"]
+        .into_iter()
+        .filter_map(part)
+        .collect();
+    let last = part("last-name");
+    match (given.is_empty(), last) {
+        (true, None) => part("nickname").map(|nickname| (nickname, None)),
+        (true, Some(last)) => Some((last, None)),
+        (false, None) => Some((given.join(" "), None)),
+        (false, Some(last)) => Some((
+            format!("{} {}", given.join(" "), last),
+            Some(format!("{}, {}", last, given.join(" "))),
+        )),
+    }
+}
+
+// --- Body → XHTML ---
+
+/// Renders the bodies into chapters and builds the TOC.
+#[derive(Default)]
+struct Converter {
+    /// Binary id → asset path.
+    images: HashMap<String, String>,
+    /// Element id → path of the chapter it lands in.
+    id_paths: HashMap<String, String>,
+    /// (path, XHTML) per chapter, in reading order.
+    chapters: Vec<(String, String)>,
+    toc: Vec<TocEntry>,
+    /// Counter for ids given to titled sections that lack one.
+    section_ids: usize,
+}
+
+/// A chapter to be rendered: its path, the body elements that go into it,
+/// and whether those are footnotes.
+struct Piece<'a> {
+    path: String,
+    elements: Vec<&'a Element>,
+    notes: bool,
+}
+
+impl Converter {
+    fn convert(&mut self, root: &Element) {
+        let mut pieces: Vec<Piece> = Vec::new();
+        for (index, body) in root.elements().filter(|e| e.name == "body").enumerate() {
+            if index == 0 {
+                // Title, epigraphs, and images before the first section form
+                // an opening page; each top-level section is a chapter.
+                let front: Vec<&Element> =
+                    body.elements().filter(|e| e.name != "section").collect();
+                if !front.is_empty() {
+                    pieces.push(Piece {
+                        path: "chapter_0.xhtml".to_string(),
+                        elements: front,
+                        notes: false,
+                    });
+                }
+                for section in body.elements().filter(|e| e.name == "section") {
+                    pieces.push(Piece {
+                        path: format!("chapter_{}.xhtml", pieces.len()),
+                        elements: vec![section],
+                        notes: false,
+                    });
+                }
+            } else {
+                let path = match pieces.iter().filter(|piece| piece.notes).count() {
+                    0 => "notes.xhtml".to_string(),
+                    n => format!("notes_{}.xhtml", n + 1),
+                };
+                pieces.push(Piece {
+                    path,
+                    elements: body.elements().collect(),
+                    notes: true,
+                });
+            }
+        }
+
+        // Links may point forward, so every id's chapter is known before
+        // anything is rendered.
+        for piece in &pieces {
+            for element in &piece.elements {
+                self.collect_ids(element, &piece.path);
+            }
+        }
+
+        for piece in &pieces {
+            let mut html = String::new();
+            if piece.notes {
+                for element in &piece.elements {
+                    self.write_note_block(&mut html, element, &piece.path);
+                }
+                let title = piece
+                    .elements
+                    .iter()
+                    .find(|e| e.name == "title")
+                    .map(|title| title_text(title))
+                    .filter(|title| !title.is_empty())
+                    .unwrap_or_else(|| "Notes".to_string());
+                self.toc.push(TocEntry::new(title, piece.path.clone()));
+            } else {
+                let mut toc = Vec::new();
+                for element in &piece.elements {
+                    if element.name == "section" {
+                        self.write_section(&mut html, element, &piece.path, 1, &mut toc, true);
+                    } else {
+                        self.write_block(&mut html, element, &piece.path, 1);
+                    }
+                }
+                self.toc.extend(toc);
+            }
+            self.chapters
+                .push((piece.path.clone(), wrap_document(&html)));
+        }
+    }
+
+    fn collect_ids(&mut self, element: &Element, path: &str) {
+        if let Some(id) = element.attr("id") {
+            self.id_paths
+                .entry(id.to_string())
+                .or_insert_with(|| path.to_string());
+        }
+        for child in element.elements() {
+            self.collect_ids(child, path);
+        }
+    }
+
+    /// Write a section and push its TOC entry (or, for an untitled
+    /// section, its titled subsections') onto `toc`.
+    fn write_section(
+        &mut self,
+        out: &mut String,
+        section: &Element,
+        path: &str,
+        depth: usize,
+        toc: &mut Vec<TocEntry>,
+        top_level: bool,
+    ) {
+        let title = section
+            .child("title")
+            .map(title_text)
+            .filter(|title| !title.is_empty());
+        let id = match (section.attr("id"), &title) {
+            (Some(id), _) => Some(id.to_string()),
+            (None, Some(_)) if !top_level => {
+                self.section_ids += 1;
+                Some(format!("section-{}", self.section_ids))
+            }
+            _ => None,
+        };
+
+        out.push_str("<section");
+        if let Some(id) = &id {
+            out.push_str(" id=\"");
+            escape_xml_into(out, id);
+            out.push('"');
+        }
+        out.push('>');
+
+        let mut children = Vec::new();
+        for child in section.elements() {
+            if child.name == "section" {
+                if depth >= MAX_TREE_DEPTH {
+                    continue;
+                }
+                self.write_section(out, child, path, depth + 1, &mut children, false);
+            } else {
+                self.write_block(out, child, path, depth);
+            }
+        }
+        out.push_str("</section>");
+
+        match title {
+            Some(title) => {
+                let href = match (&id, top_level) {
+                    (Some(id), false) => format!("{path}#{id}"),
+                    _ => path.to_string(),
+                };
+                let mut entry = TocEntry::new(title, href);
+                entry.children = children;
+                toc.push(entry);
+            }
+            None => toc.extend(children),
+        }
+    }
+
+    /// Write a top-level element of a notes body: sections become footnotes.
+    fn write_note_block(&mut self, out: &mut String, element: &Element, path: &str) {
+        if element.name != "section" {
+            self.write_block(out, element, path, 1);
+            return;
+        }
+        out.push_str("<aside epub:type=\"footnote\"");
+        if let Some(id) = element.attr("id") {
+            out.push_str(" id=\"");
+            escape_xml_into(out, id);
+            out.push('"');
+        }
+        out.push('>');
+        for child in element.elements() {
+            if child.name == "title" {
+                // Note titles are just the note's number.
+                out.push_str("<p class=\"subtitle\">");
+                self.write_lines(out, child, path);
+                out.push_str("</p>");
+            } else {
+                self.write_block(out, child, path, 2);
+            }
+        }
+        out.push_str("</aside>");
+    }
+
+    fn write_blocks(&mut self, out: &mut String, parent: &Element, path: &str, depth: usize) {
+        for child in &parent.children {
+            match child {
+                Content::Element(element) => self.write_block(out, element, path, depth),
+                Content::Text(text) if !text.trim().is_empty() => {
+                    out.push_str("<p>");
+                    escape_xml_into(out, text.trim());
+                    out.push_str("</p>");
+                }
+                Content::Text(_) => {}
+            }
+        }
+    }
+
+    fn write_block(&mut self, out: &mut String, element: &Element, path: &str, depth: usize) {
+        let (tag, class) = match element.name.as_str() {
+            "title" => {
+                let level = depth.min(6);
+                write_open(out, &format!("h{level}"), None, element.attr("id"));
+                self.write_lines(out, element, path);
+                out.push_str(&format!("</h{level}>"));
+                return;
+            }
+            "image" => {
+                out.push_str("<div class=\"image\">");
+                self.write_image(out, element);
+                out.push_str("</div>");
+                return;
+            }
+            "empty-line" => {
+                out.push_str("<p class=\"empty-line\"><br/></p>");
+                return;
+            }
+            "p" => ("p", None),
+            "subtitle" => ("p", Some("subtitle")),
+            "text-author" => ("p", Some("text-author")),
+            "v" => ("p", Some("v")),
+            "date" => ("p", Some("text-author")),
+            "epigraph" => ("blockquote", Some("epigraph")),
+            "cite" => ("blockquote", None),
+            "annotation" => ("div", Some("annotation")),
+            "poem" => ("div", Some("poem")),
+            "stanza" => ("div", Some("stanza")),
+            "table" => ("table", None),
+            "tr" => ("tr", None),
+            "th" | "td" => {
+                let tag = element.name.as_str();
+                out.push('<');
+                out.push_str(tag);
+                for name in ["id", "colspan", "rowspan"] {
+                    if let Some(value) = element.attr(name) {
+                        out.push_str(&format!(" {name}=\""));
+                        escape_xml_into(out, value);
+                        out.push('"');
+                    }
+                }
+                out.push('>');
+                self.write_inlines(out, element, path);
+                out.push_str(&format!("</{tag}>"));
+                return;
+            }
+            // A section nested somewhere sections don't belong.
+            "section" if depth < MAX_TREE_DEPTH => {
+                let mut toc = Vec::new();
+                self.write_section(out, element, path, depth + 1, &mut toc, false);
+                return;
+            }
+            _ => {
+                self.write_blocks(out, element, path, depth);
+                return;
+            }
+        };
+
+        write_open(out, tag, class, element.attr("id"));
+        if tag == "p" {
+            self.write_inlines(out, element, path);
+        } else if element.name == "poem" || element.name == "stanza" {
+            for child in element.elements() {
+                if child.name == "title" {
+                    out.push_str("<p class=\"poem-title\">");
+                    self.write_lines(out, child, path);
+                    out.push_str("</p>");
+                } else {
+                    self.write_block(out, child, path, depth);
+                }
+            }
+        } else {
+            self.write_blocks(out, element, path, depth);
+        }
+        out.push_str(&format!("</{tag}>"));
+    }
+
+    /// The paragraphs of a title, as lines of one block.
+    fn write_lines(&mut self, out: &mut String, title: &Element, path: &str) {
+        let mut first = true;
+        for line in title.elements().filter(|e| e.name == "p") {
+            if !first {
+                out.push_str("<br/>");
+            }
+            first = false;
+            self.write_inlines(out, line, path);
+        }
+    }
+
+    fn write_inlines(&mut self, out: &mut String, parent: &Element, path: &str) {
+        for child in &parent.children {
+            match child {
+                Content::Text(text) => escape_xml_into(out, text),
+                Content::Element(element) => self.write_inline(out, element, path),
+            }
+        }
+    }
+
+    fn write_inline(&mut self, out: &mut String, element: &Element, path: &str) {
+        let tag = match element.name.as_str() {
+            "emphasis" => "em",
+            "strong" => "strong",
+            "strikethrough" => "s",
+            "sub" => "sub",
+            "sup" => "sup",
+            "code" => "code",
+            "style" => "span",
+            "image" => {
+                self.write_image(out, element);
+                return;
+            }
+            "a" => {
+                out.push_str("<a");
+                if let Some(href) = element.attr("href") {
+                    out.push_str(" href=\"");
+                    escape_xml_into(out, &self.link(href, path));
+                    out.push('"');
+                }
+                if element.attr("type") == Some("note") {
+                    out.push_str(" epub:type=\"noteref\"");
+                }
+                out.push('>');
+                self.write_inlines(out, element, path);
+                out.push_str("</a>");
+                return;
+            }
+            _ => {
+                self.write_inlines(out, element, path);
+                return;
+            }
+        };
+        write_open(out, tag, None, element.attr("id"));
+        self.write_inlines(out, element, path);
+        out.push_str(&format!("</{tag}>"));
+    }
+
+    fn write_image(&self, out: &mut String, image: &Element) {
+        let Some(src) = image
+            .attr("href")
+            .and_then(|href| href.strip_prefix('#'))
+            .and_then(|id| self.images.get(id))
+        else {
+            return;
+        };
+        out.push_str("<img src=\"");
+        escape_xml_into(out, src);
+        out.push_str("\" alt=\"");
+        escape_xml_into(out, image.attr("alt").unwrap_or_default());
+        out.push('"');
+        if let Some(title) = image.attr("title") {
+            out.push_str(" title=\"");
+            escape_xml_into(out, title);
+            out.push('"');
+        }
+        out.push_str("/>");
+    }
+
+    /// An FB2 link target as an href from the chapter at `path`: internal
+    /// `#id` links point into whichever chapter holds the id.
+    fn link(&self, href: &str, path: &str) -> String {
+        match href.strip_prefix('#') {
+            Some(id) => match self.id_paths.get(id) {
+                Some(target) if target != path => format!("{target}#{id}"),
+                _ => href.to_string(),
+            },
+            None => href.to_string(),
+        }
+    }
+}
+
+fn write_open(out: &mut String, tag: &str, class: Option<&str>, id: Option<&str>) {
+    out.push('<');
+    out.push_str(tag);
+    if let Some(class) = class {
+        out.push_str(" class=\"");
+        out.push_str(class);
+        out.push('"');
+    }
+    if let Some(id) = id {
+        out.push_str(" id=\"");
+        escape_xml_into(out, id);
+        out.push('"');
+    }
+    out.push('>');
+}
+
+/// A title's paragraphs joined into one line, for the TOC.
+fn title_text(title: &Element) -> String {
+    title
+        .elements()
+        .filter(|e| e.name == "p")
+        .map(Element::clean_text)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn wrap_document(body: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\">\n\
+         <head><style>{STYLE}</style></head>\n\
+         <body>{body}</body>\n\
+         </html>\n"
+    )
+}
@@ -0,0 +1,152 @@
+//! IR dump importer: reads [`Format::Ir`](crate::Format::Ir) back into a
+//! book, for regression tests and reproducing bug reports.
+//!
+//! Chapters are parsed up front (a dump is a fixture, not a library-sized
+//! book) and served from memory. A dump carries no resources, so the book
+//! has no assets.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use crate::import::{ChapterId, Importer, SpineEntry, resolve_path_based_href};
+use crate::io::{ByteSource, FileSource};
+use crate::model::{AnchorTarget, Chapter, GlobalNodeId, Landmark, Metadata, TocEntry};
+
+/// Importer for the textual IR dump.
+pub struct IrImporter {
+    metadata: Metadata,
+    toc: Vec<TocEntry>,
+    spine: Vec<SpineEntry>,
+    /// Source path per spine chapter, indexed by `ChapterId`.
+    sources: Vec<String>,
+    /// Parsed chapters, indexed by `ChapterId`.
+    chapters: Vec<Chapter>,
+    path_to_chapter: HashMap<String, ChapterId>,
+    /// `path#id` → node, built by `index_anchors`.
+    anchor_map: RwLock<HashMap<String, GlobalNodeId>>,
+}
+
+impl IrImporter {
+    /// Create an importer from a ByteSource.
+    pub fn from_source(source: Arc<dyn ByteSource>) -> crate::Result<Self> {
+        let bytes = source.read_at(0, source.len() as usize)?;
+        let text = String::from_utf8(bytes).map_err(|_| crate::Error::Malformed {
+            format: crate::Format::Ir,
+            context: "dump is not UTF-8".to_string(),
+        })?;
+        let book = crate::ir::parse(&text)?;
+
+        let mut spine = Vec::with_capacity(book.chapters.len());
+        let mut sources = Vec::with_capacity(book.chapters.len());
+        let mut chapters = Vec::with_capacity(book.chapters.len());
+        let mut path_to_chapter = HashMap::new();
+        for (index, (source, chapter)) in book.chapters.into_iter().enumerate() {
+            let id = ChapterId(index as u32);
+            if !source.is_empty() {
+                path_to_chapter.entry(source.clone()).or_insert(id);
+            }
+            spine.push(SpineEntry {
+                id,
+                size_estimate: chapter.text_buffer().len(),
+            });
+            sources.push(source);
+            chapters.push(chapter);
+        }
+
+        Ok(Self {
+            metadata: book.metadata,
+            toc: book.toc,
+            spine,
+            sources,
+            chapters,
+            path_to_chapter,
+            anchor_map: RwLock::new(HashMap::new()),
+        })
+    }
+
+    fn chapter(&self, id: ChapterId) -> crate::Result<&Chapter> {
+        self.chapters
+            .get(id.0 as usize)
+            .ok_or_else(|| crate::Error::NotFound {
+                what: format!("chapter {}", id.0),
+            })
+    }
+}
+
+impl Importer for IrImporter {
+    fn open(path: &Path) -> crate::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        Self::from_source(Arc::new(FileSource::new(file)?))
+    }
+
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn toc(&self) -> &[TocEntry] {
+        &self.toc
+    }
+
+    fn landmarks(&self) -> &[Landmark] {
+        &[]
+    }
+
+    fn spine(&self) -> &[SpineEntry] {
+        &self.spine
+    }
+
+    fn load_chapter(&self, id: ChapterId) -> crate::Result<Chapter> {
+        self.chapter(id).cloned()
+    }
+
+    fn source_id(&self, id: ChapterId) -> Option<&str> {
+        self.sources
+            .get(id.0 as usize)
+            .map(String::as_str)
+            .filter(|source| !source.is_empty())
+    }
+
+    /// The chapter's block of the dump.
+    fn load_raw(&self, id: ChapterId) -> crate::Result<Vec<u8>> {
+        let source = self.source_id(id).unwrap_or_default();
+        Ok(crate::ir::write_chapter(source, self.chapter(id)?).into_bytes())
+    }
+
+    fn list_assets(&self) -> &[String] {
+        &[]
+    }
+
+    fn load_asset(&self, path: &str) -> crate::Result<Vec<u8>> {
+        Err(crate::Error::NotFound {
+            what: format!("asset {path} (IR dumps carry no resources)"),
+        })
+    }
+
+    fn requires_normalized_export(&self) -> bool {
+        true
+    }
+
+    fn index_anchor_ids(&self, ids: &[(GlobalNodeId, &str)]) {
+        let mut anchor_map = HashMap::new();
+        for &(node, id) in ids {
+            let Some(chapter_path) = self.source_id(node.chapter) else {
+                continue;
+            };
+            anchor_map.insert(format!("{}#{}", chapter_path, id), node);
+        }
+        if let Ok(mut map) = self.anchor_map.write() {
+            *map = anchor_map;
+        }
+    }
+
+    fn resolve_href(&self, from_chapter: ChapterId, href: &str) -> Option<AnchorTarget> {
+        let from_path = self.source_id(from_chapter)?;
+        resolve_path_based_href(
+            from_path,
+            href,
+            |p| self.path_to_chapter.get(p).copied(),
+            |k| self.anchor_map.read().ok().and_then(|m| m.get(k).copied()),
+        )
+    }
+}
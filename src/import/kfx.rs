@@ -12,8 +12,9 @@ use std::sync::{Arc, OnceLock, RwLock};
 use crate::import::{ChapterId, Importer, SpineEntry};
 use crate::io::{ByteSource, FileSource};
 use crate::kfx::container::{
-    self, ContainerError, EntityLoc, extract_doc_symbols, get_field, get_symbol_text,
-    parse_container_header, parse_container_info, parse_index_table, skip_enty_header,
+    self, ContainerError, EntityLoc, enty_header_len, extract_doc_symbols, get_field,
+    get_symbol_text, parse_container_header, parse_container_info, parse_index_table,
+    skip_enty_header,
 };
 use crate::kfx::ion::{IonParser, IonValue};
 use crate::kfx::schema::schema;
@@ -31,6 +32,9 @@ macro_rules! sym {
     };
 }
 
+/// Content entities kept in the text cache before it is flushed.
+const CONTENT_CACHE_ENTITIES: usize = 16;
+
 /// KFX format importer.
 pub struct KfxImporter {
     /// Random-access byte source.
@@ -76,7 +80,8 @@ pub struct KfxImporter {
     /// content entity on each miss — O(C^2) parses per book.
     content_index: OnceLock<HashMap<String, EntityLoc>>,
 
-    /// Content cache: name -> list of strings (lazily populated)
+    /// Content cache: name -> list of strings (lazily populated, bounded by
+    /// [`CONTENT_CACHE_ENTITIES`])
     content_cache: RwLock<HashMap<String, Vec<String>>>,
 
     /// Anchor map: anchor_name -> uri (for external link resolution)
@@ -208,23 +213,33 @@ impl Importer for KfxImporter {
         self.read_entity(*loc)
     }
 
+    fn contains_asset(&self, path: &str) -> bool {
+        // Chapters reference media by resource name, which isn't listed.
+        self.asset_paths.iter().any(|asset| asset == path)
+            || self.resource_index().contains_key(path)
+    }
+
+    fn asset_media_type(&self, path: &str) -> Option<&str> {
+        // Media entities are named by id ("#1102"), with no extension to
+        // guess from; the payload's magic bytes are the only reliable type.
+        // Only the head is read: enough for the magic bytes and an SVG root.
+        let id: u32 = path.strip_prefix('#')?.parse().ok()?;
+        let loc = self.entities.iter().find(|e| e.id == id)?;
+        let head = self.read_entity_head(*loc, 1024).ok()?;
+        crate::util::sniff_image_mime(&head)
+    }
+
     fn requires_normalized_export(&self) -> bool {
         // KFX load_raw returns binary Ion data, not HTML
         true
     }
 
-    fn index_anchors(&self, chapters: &[(ChapterId, Arc<Chapter>)]) {
-        let mut element_id_map = HashMap::new();
-
-        // Build element_id → GlobalNodeId map from chapters
-        for (chapter_id, chapter) in chapters {
-            for node_id in chapter.iter_dfs() {
-                if let Some(id) = chapter.semantics.id(node_id) {
-                    element_id_map.insert(id.to_string(), GlobalNodeId::new(*chapter_id, node_id));
-                }
-            }
-        }
-
+    fn index_anchor_ids(&self, ids: &[(GlobalNodeId, &str)]) {
+        // Build element_id → GlobalNodeId map
+        let element_id_map = ids
+            .iter()
+            .map(|&(node, id)| (id.to_string(), node))
+            .collect();
         if let Ok(mut map) = self.element_id_map.write() {
             *map = element_id_map;
         }
@@ -398,6 +413,15 @@ impl KfxImporter {
         }
     }
 
+    /// The first `len` payload bytes of an entity (past its ENTY header),
+    /// without reading the rest.
+    fn read_entity_head(&self, loc: EntityLoc, len: usize) -> crate::Result<Vec<u8>> {
+        let head = self.source.read_at(loc.offset as u64, loc.length.min(10))?;
+        let skip = enty_header_len(&head, loc.length);
+        let len = len.min(loc.length - skip);
+        Ok(self.source.read_at((loc.offset + skip) as u64, len)?)
+    }
+
     /// Parse an entity as Ion and return the parsed value.
     ///
     /// Strips any top-level Ion type annotation (e.g. `$490::{ ... }`) so
@@ -875,6 +899,13 @@ impl KfxImporter {
         if let Some(content_list) = self.load_content_entity(name) {
             let result = content_list.get(index).cloned();
             if let Ok(mut cache) = self.content_cache.write() {
+                // Content entities are read in reading order, so once a
+                // handful are cached the older ones are rarely needed again;
+                // dropping them keeps a full conversion from holding the
+                // whole book's text.
+                if cache.len() >= CONTENT_CACHE_ENTITIES {
+                    cache.clear();
+                }
                 cache.insert(name.to_string(), content_list);
             }
             return result;
@@ -20,7 +20,7 @@ use crate::mobi::{
     build_toc_from_ncx, decode_font_record, detect_font_type, detect_image_type, filepos,
     is_metadata_record, palmdoc, parse_exth, parse_ncx_index, read_index, strip_trailing_data,
 };
-use crate::model::{AnchorTarget, Chapter, GlobalNodeId, Landmark, Metadata, TocEntry};
+use crate::model::{AnchorTarget, GlobalNodeId, Landmark, Metadata, TocEntry};
 
 /// MOBI6 format importer with chapter splitting.
 ///
@@ -139,23 +139,18 @@ impl Importer for MobiImporter {
         }
     }
 
-    fn index_anchors(&self, chapters: &[(ChapterId, Arc<Chapter>)]) {
+    fn index_anchor_ids(&self, ids: &[(GlobalNodeId, &str)]) {
         let mut element_id_map = HashMap::new();
-
-        for (chapter_id, chapter) in chapters {
-            let chapter_path = match self.chapter_paths.get(chapter_id.0 as usize) {
-                Some(p) => p.as_str(),
-                None => continue,
+        for &(node, id) in ids {
+            let Some(chapter_path) = self
+                .chapter_paths
+                .get(node.chapter.0 as usize)
+                .map(String::as_str)
+            else {
+                continue;
             };
-
-            for node_id in chapter.iter_dfs() {
-                if let Some(id) = chapter.semantics.id(node_id) {
-                    let key = format!("{}#{}", chapter_path, id);
-                    element_id_map.insert(key, GlobalNodeId::new(*chapter_id, node_id));
-                }
-            }
+            element_id_map.insert(format!("{}#{}", chapter_path, id), node);
         }
-
         if let Ok(mut map) = self.element_id_map.write() {
             *map = element_id_map;
         }
@@ -477,6 +472,7 @@ fn discover_assets_from_source(
                         "image/jpeg" => "jpg",
                         "image/png" => "png",
                         "image/gif" => "gif",
+                        "image/webp" => "webp",
                         _ => "bin",
                     };
                     assets.push(format!("images/image_{idx:04}.{ext}"));
@@ -526,6 +522,7 @@ fn build_metadata(
             .or_else(|| exth.asin.clone())
             .or_else(|| exth.source.clone())
             .unwrap_or_default();
+        metadata.identifiers = exth.identifiers();
     }
 
     metadata
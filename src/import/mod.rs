@@ -5,12 +5,20 @@
 //! - **Track 2 (Raw Access)**: Provide raw bytes for high-fidelity conversion
 
 mod azw3;
+mod cbz;
+mod directory;
 mod epub;
+mod fb2;
+mod ir;
 mod kfx;
 mod mobi;
 
 pub use azw3::Azw3Importer;
+pub use cbz::CbzImporter;
+pub(crate) use directory::pack_directory;
 pub use epub::EpubImporter;
+pub use fb2::Fb2Importer;
+pub use ir::IrImporter;
 pub use kfx::KfxImporter;
 pub use mobi::MobiImporter;
 
@@ -19,6 +27,7 @@ use std::sync::Arc;
 
 use crate::dom::{Origin, Stylesheet};
 use crate::model::{AnchorTarget, Chapter, FontFace, GlobalNodeId, Landmark, Metadata, TocEntry};
+use crate::style::PageRule;
 
 // `ChapterId` is a pure identifier defined in the data model; re-exported
 // here for backwards compatibility (`crate::import::ChapterId`).
@@ -26,6 +35,7 @@ pub use crate::model::ChapterId;
 
 /// Entry in the reading order (spine).
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SpineEntry {
     /// Unique identifier for this chapter.
     pub id: ChapterId,
@@ -75,6 +85,7 @@ pub trait Importer: Send + Sync {
         Ok(compile_chapter_html(
             &html_bytes,
             base_path.as_deref(),
+            self.list_assets(),
             &mut |path| self.load_stylesheet(path),
         ))
     }
@@ -117,17 +128,28 @@ pub trait Importer: Send + Sync {
     /// Load an asset by archive entry name.
     fn load_asset(&self, path: &str) -> crate::Result<Vec<u8>>;
 
+    /// Whether [`load_asset`](Self::load_asset) serves `path`, without
+    /// reading it. Defaults to [`list_assets`](Self::list_assets)
+    /// membership; backends that also serve unlisted names (KFX resource
+    /// names like `"e6"`) override it.
+    fn contains_asset(&self, path: &str) -> bool {
+        self.list_assets().iter().any(|asset| asset == path)
+    }
+
+    /// The media type the package declares for an asset (e.g. the EPUB
+    /// manifest's `media-type`), or `None` for formats without a manifest.
+    fn asset_media_type(&self, _path: &str) -> Option<&str> {
+        None
+    }
+
     /// Load and parse a stylesheet, optionally using a cache.
     ///
     /// The default implementation loads the asset bytes and parses CSS.
     /// Returns an `Arc` so cached sheets are shared across chapters instead
     /// of deep-cloning the parsed rules per chapter.
     fn load_stylesheet(&self, path: &str) -> Option<Arc<Stylesheet>> {
-        if let Ok(css_bytes) = self.load_asset(path) {
-            let css_str = String::from_utf8_lossy(&css_bytes);
-            return Some(Arc::new(Stylesheet::parse(&css_str)));
-        }
-        None
+        let css_bytes = self.load_asset(path).ok()?;
+        Some(Arc::new(parse_stylesheet_at(&css_bytes, path)))
     }
 
     /// Collect all @font-face definitions from CSS files.
@@ -141,20 +163,7 @@ pub trait Importer: Send + Sync {
     fn font_faces(&self) -> Vec<FontFace> {
         let mut font_faces = Vec::new();
 
-        // Find all CSS files
-        let css_paths: Vec<_> = self
-            .list_assets()
-            .iter()
-            .filter(|p| {
-                Path::new(p.as_str())
-                    .extension()
-                    .map(|e| e.eq_ignore_ascii_case("css"))
-                    .unwrap_or(false)
-            })
-            .cloned()
-            .collect();
-
-        for css_path in css_paths {
+        for css_path in css_assets(self.list_assets()) {
             if let Some(stylesheet) = self.load_stylesheet(&css_path) {
                 // Resolve relative font paths to canonical paths
                 for font_face in &stylesheet.font_faces {
@@ -169,6 +178,21 @@ pub trait Importer: Send + Sync {
         font_faces
     }
 
+    /// The book's `@page` geometry: every CSS asset's `@page` rules,
+    /// merged in asset order. `None` when no stylesheet has one.
+    fn page_rule(&self) -> Option<PageRule> {
+        let mut page_rule: Option<PageRule> = None;
+        for css_path in css_assets(self.list_assets()) {
+            if let Some(rule) = self
+                .load_stylesheet(&css_path)
+                .and_then(|stylesheet| stylesheet.page_rule)
+            {
+                page_rule.get_or_insert_default().merge(&rule);
+            }
+        }
+        page_rule
+    }
+
     /// Whether this importer requires normalized export for HTML-based formats.
     ///
     /// Returns true for binary formats (KFX) where load_raw returns non-HTML data.
@@ -182,12 +206,29 @@ pub trait Importer: Send + Sync {
     /// Index all anchor targets after chapters are loaded.
     ///
     /// This method is called once with all loaded chapters, allowing the importer
-    /// to build format-specific anchor maps. The default implementation builds
-    /// a path#id → GlobalNodeId map suitable for EPUB-style linking.
+    /// to build format-specific anchor maps. The default implementation collects
+    /// every `(node, id)` pair in document order and hands them to
+    /// [`index_anchor_ids`](Self::index_anchor_ids).
+    fn index_anchors(&self, chapters: &[(ChapterId, Arc<Chapter>)]) {
+        let ids: Vec<(GlobalNodeId, &str)> = chapters
+            .iter()
+            .flat_map(|(chapter_id, chapter)| {
+                chapter.iter_dfs().filter_map(move |node_id| {
+                    let id = chapter.semantics.id(node_id)?;
+                    Some((GlobalNodeId::new(*chapter_id, node_id), id))
+                })
+            })
+            .collect();
+        self.index_anchor_ids(&ids);
+    }
+
+    /// Build anchor maps from element ids gathered across the whole book.
     ///
-    /// Importers should override this to handle format-specific anchor systems
-    /// (e.g., KFX anchor entities, AZW3 fragment IDs).
-    fn index_anchors(&self, _chapters: &[(ChapterId, Arc<Chapter>)]) {
+    /// Anchor indexing only needs each node's id, so callers that don't keep
+    /// every chapter in memory (chapter-at-a-time export) collect the pairs
+    /// while streaming and call this directly. Importers override this to
+    /// build their anchor maps (e.g. `path#id` for EPUB, bare ids for KFX).
+    fn index_anchor_ids(&self, _ids: &[(GlobalNodeId, &str)]) {
         // Default: no-op. Path-based resolution in resolve_href() handles EPUB.
         // Format-specific importers override to build their anchor maps.
     }
@@ -284,10 +325,11 @@ pub fn resolve_path_based_href(
 /// implementation and parallel [`Importer::load_chapters`] overrides:
 /// decode, parse the DOM exactly once (the same parse serves stylesheet
 /// discovery and IR compilation), resolve linked CSS through `load_sheet`,
-/// and compile to IR.
+/// and compile to IR. Responsive image sources are picked among `assets`.
 pub(crate) fn compile_chapter_html(
     html_bytes: &[u8],
     base_path: Option<&str>,
+    assets: &[String],
     load_sheet: &mut dyn FnMut(&str) -> Option<Arc<Stylesheet>>,
 ) -> Chapter {
     let hint_encoding = crate::util::extract_xml_encoding(html_bytes);
@@ -313,15 +355,27 @@ pub(crate) fn compile_chapter_html(
         }
     }
 
-    // Parse inline styles
+    // Parse inline styles; their urls are relative to the chapter itself
     for css in inline {
-        stylesheets.push((Arc::new(Stylesheet::parse(&css)), Origin::Author));
+        let mut sheet = Stylesheet::parse(&css);
+        if let Some(base) = base_path {
+            sheet.map_urls(|url| resolve_css_url(base, url));
+        }
+        stylesheets.push((Arc::new(sheet), Origin::Author));
     }
 
     // Compile to IR from the DOM parsed above
     let sheet_refs: Vec<(&Stylesheet, Origin)> =
         stylesheets.iter().map(|(s, o)| (s.as_ref(), *o)).collect();
-    let mut chapter = crate::dom::compile_dom(&dom, &sheet_refs);
+    let asset_exists = |url: &str| {
+        let path = match base_path {
+            Some(chapter_path) => resolve_relative_path(chapter_path, url),
+            None => normalize_separators(url.to_string()),
+        };
+        let path = path.trim_start_matches('/');
+        assets.iter().any(|asset| asset == path)
+    };
+    let mut chapter = crate::dom::compile_dom(&dom, &sheet_refs, Some(&asset_exists));
 
     // Post-process: Resolve relative paths in semantic attributes (src, href)
     // This canonicalizes paths like "../images/photo.jpg" to "OEBPS/images/photo.jpg"
@@ -353,6 +407,14 @@ fn normalize_separators(path: String) -> String {
 ///
 /// Both inputs are archive entry names separated by forward slashes; the
 /// result is normalized to forward slashes as well.
+/// [`Importer::contains_asset`] for an overlay that lists `assets` over
+/// `inner`: a path the overlay lists, or one `inner` serves without listing
+/// it. Paths `inner` lists but the overlay doesn't are hidden.
+pub(crate) fn overlay_contains_asset(assets: &[String], inner: &dyn Importer, path: &str) -> bool {
+    assets.iter().any(|asset| asset == path)
+        || (!inner.list_assets().iter().any(|asset| asset == path) && inner.contains_asset(path))
+}
+
 pub(crate) fn resolve_relative_path(base: &str, relative: &str) -> String {
     // Handle absolute paths and URLs
     if relative.starts_with('/') || relative.contains("://") {
@@ -395,6 +457,37 @@ pub(crate) fn resolve_relative_path(base: &str, relative: &str) -> String {
     normalize_separators(format!("{}{}", leading, result.join("/")))
 }
 
+/// The CSS files among `assets`.
+fn css_assets(assets: &[String]) -> Vec<String> {
+    assets
+        .iter()
+        .filter(|p| {
+            Path::new(p.as_str())
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("css"))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Parse a stylesheet stored at archive path `path`, resolving its `url()`
+/// references to archive paths so they can be loaded as assets.
+pub(crate) fn parse_stylesheet_at(css_bytes: &[u8], path: &str) -> Stylesheet {
+    let mut sheet = Stylesheet::parse(&String::from_utf8_lossy(css_bytes));
+    sheet.map_urls(|url| resolve_css_url(path, url));
+    sheet
+}
+
+/// Resolve one CSS `url()` against `base`, leaving external URLs and data
+/// URIs untouched.
+fn resolve_css_url(base: &str, url: &str) -> String {
+    if url.contains("://") || url.starts_with("data:") {
+        url.to_string()
+    } else {
+        resolve_relative_path(base, url)
+    }
+}
+
 /// Resolve relative paths in a chapter's semantic attributes.
 ///
 /// This canonicalizes paths like `../images/photo.jpg` relative to the
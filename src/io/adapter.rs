@@ -4,6 +4,7 @@ use std::sync::Arc;
 
 /// Wraps an `Arc<ByteSource>` into a stateful `Read + Seek` stream.
 /// Used to pass our ByteSource into libraries like `zip::ZipArchive`.
+#[derive(Clone)]
 pub struct ByteSourceCursor {
     inner: Arc<dyn ByteSource>,
     position: u64,
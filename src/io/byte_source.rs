@@ -110,42 +110,45 @@ impl ByteSource for FileSource {
 
 // --- Implementation: In-Memory ---
 
-/// An in-memory ByteSource backed by a `Vec<u8>`.
-pub struct MemorySource {
-    data: Vec<u8>,
+/// An in-memory ByteSource over any owned byte buffer: a `Vec<u8>`, an
+/// `Arc<[u8]>` shared with the caller, or a `&'static [u8]` from
+/// `include_bytes!`. Reads copy straight out of the buffer.
+pub struct MemorySource<B = Vec<u8>> {
+    data: B,
 }
 
-impl MemorySource {
-    pub fn new(data: Vec<u8>) -> Self {
+impl<B: AsRef<[u8]>> MemorySource<B> {
+    pub fn new(data: B) -> Self {
         Self { data }
     }
 }
 
-impl ByteSource for MemorySource {
+impl<B: AsRef<[u8]> + Send + Sync> ByteSource for MemorySource<B> {
     fn len(&self) -> u64 {
-        self.data.len() as u64
+        self.data.as_ref().len() as u64
     }
 
     fn read_at_into(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let data = self.data.as_ref();
         // try_from, not `as`: on 32-bit targets a >4 GiB offset would
         // truncate, pass the bounds check, and silently read wrong bytes.
         let offset = usize::try_from(offset).map_err(|_| {
             io::Error::new(io::ErrorKind::UnexpectedEof, "offset beyond end of data")
         })?;
-        if offset > self.data.len() {
+        if offset > data.len() {
             return Err(io::Error::new(
                 io::ErrorKind::UnexpectedEof,
                 "offset beyond end of data",
             ));
         }
-        let end = (offset + buf.len()).min(self.data.len());
+        let end = (offset + buf.len()).min(data.len());
         if end - offset < buf.len() {
             return Err(io::Error::new(
                 io::ErrorKind::UnexpectedEof,
                 "not enough data",
             ));
         }
-        buf.copy_from_slice(&self.data[offset..end]);
+        buf.copy_from_slice(&data[offset..end]);
         Ok(buf.len())
     }
 }
@@ -170,6 +173,16 @@ mod tests {
         assert_eq!(&data, b"bcd");
     }
 
+    #[test]
+    fn test_memory_source_reads_borrowed_and_shared_buffers() {
+        let borrowed: &'static [u8] = b"abcdef";
+        assert_eq!(MemorySource::new(borrowed).read_at(2, 3).unwrap(), b"cde");
+        let shared: std::sync::Arc<[u8]> = borrowed.into();
+        let source = MemorySource::new(shared.clone());
+        assert_eq!(source.read_at(4, 2).unwrap(), b"ef");
+        assert!(source.read_at(5, 2).is_err());
+    }
+
     #[test]
     fn test_read_at_rejects_oversized_len_without_allocating() {
         // A length far larger than the source (e.g. from a crafted length field)
@@ -0,0 +1,165 @@
+use super::byte_source::{ByteSource, MemorySource};
+use super::range_source::{RangeFetcher, RangeSource};
+use std::io;
+
+/// A [`ByteSource`] over an HTTP(S) URL, reading byte ranges on demand.
+///
+/// Opening probes the server with a one-byte `Range` request. When the
+/// server answers `206 Partial Content`, reads become `Range` requests
+/// (batched into blocks, as with [`crate::Book::open_url`]), so a ZIP-based format loads only its
+/// central directory and the entries it needs. A server that ignores
+/// `Range` and sends the whole file is handled by keeping that response in
+/// memory instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use boko::HttpRangeSource;
+/// use boko::import::EpubImporter;
+///
+/// let source = HttpRangeSource::open("https://example.com/book.epub")?;
+/// let importer = EpubImporter::from_source(Arc::new(source))?;
+/// # Ok::<(), boko::Error>(())
+/// ```
+pub struct HttpRangeSource {
+    inner: Remote,
+}
+
+enum Remote {
+    Ranged(RangeSource<HttpFetcher>),
+    Whole(MemorySource<Vec<u8>>),
+}
+
+impl HttpRangeSource {
+    /// Open `url`, falling back to a full download when the server does not
+    /// support range requests.
+    pub fn open(url: impl Into<String>) -> io::Result<Self> {
+        let url = url.into();
+        let fetcher = HttpFetcher {
+            agent: ureq::Agent::new_with_defaults(),
+        };
+        let inner = match fetcher.probe(&url)? {
+            Probe::Ranged(len) => Remote::Ranged(RangeSource::with_len(url, fetcher, len)),
+            Probe::Whole(data) => Remote::Whole(MemorySource::new(data)),
+        };
+        Ok(Self { inner })
+    }
+
+    /// Whether reads are served by `Range` requests rather than from a
+    /// full download.
+    pub fn supports_ranges(&self) -> bool {
+        matches!(self.inner, Remote::Ranged(_))
+    }
+}
+
+impl ByteSource for HttpRangeSource {
+    fn len(&self) -> u64 {
+        match &self.inner {
+            Remote::Ranged(source) => source.len(),
+            Remote::Whole(source) => source.len(),
+        }
+    }
+
+    fn read_at_into(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        match &self.inner {
+            Remote::Ranged(source) => source.read_at_into(offset, buf),
+            Remote::Whole(source) => source.read_at_into(offset, buf),
+        }
+    }
+}
+
+/// What the opening `Range: bytes=0-0` request revealed.
+enum Probe {
+    /// Ranges are supported; the resource's total length.
+    Ranged(u64),
+    /// The server sent the whole body.
+    Whole(Vec<u8>),
+}
+
+struct HttpFetcher {
+    agent: ureq::Agent,
+}
+
+impl HttpFetcher {
+    fn probe(&self, url: &str) -> io::Result<Probe> {
+        let mut response = self
+            .agent
+            .get(url)
+            .header("Range", "bytes=0-0")
+            .call()
+            .map_err(ureq::Error::into_io)?;
+        let total = response
+            .headers()
+            .get("content-range")
+            .and_then(|value| value.to_str().ok())
+            .and_then(content_range_total);
+        match total {
+            Some(len) if response.status() == 206 => Ok(Probe::Ranged(len)),
+            _ => {
+                let data = response
+                    .body_mut()
+                    .with_config()
+                    .limit(crate::util::MAX_DECOMPRESSED_ENTRY as u64)
+                    .read_to_vec()
+                    .map_err(ureq::Error::into_io)?;
+                Ok(Probe::Whole(data))
+            }
+        }
+    }
+}
+
+impl RangeFetcher for HttpFetcher {
+    fn content_length(&self, url: &str) -> io::Result<u64> {
+        match self.probe(url)? {
+            Probe::Ranged(len) => Ok(len),
+            Probe::Whole(data) => Ok(data.len() as u64),
+        }
+    }
+
+    fn read_at(&self, url: &str, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+        if len == 0 {
+            return Ok(Vec::new());
+        }
+        let last = offset + len as u64 - 1;
+        let mut response = self
+            .agent
+            .get(url)
+            .header("Range", format!("bytes={offset}-{last}"))
+            .call()
+            .map_err(ureq::Error::into_io)?;
+        if response.status() != 206 {
+            return Err(io::Error::other(format!(
+                "range request for bytes {offset}-{last} answered with status {}",
+                response.status()
+            )));
+        }
+        // ureq fails the final EOF read once the limit is used up, so allow
+        // one byte more; a longer body is caught by `RangeSource`.
+        response
+            .body_mut()
+            .with_config()
+            .limit(len as u64 + 1)
+            .read_to_vec()
+            .map_err(ureq::Error::into_io)
+    }
+}
+
+/// The complete length from a `Content-Range: bytes <first>-<last>/<total>`
+/// header; `None` when it is malformed or the total is unknown (`*`).
+fn content_range_total(value: &str) -> Option<u64> {
+    let (_, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+    total.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_range_total_parses_the_complete_length() {
+        assert_eq!(content_range_total("bytes 0-0/1234"), Some(1234));
+        assert_eq!(content_range_total("bytes 0-0/*"), None);
+        assert_eq!(content_range_total("items 0-0/10"), None);
+    }
+}
@@ -2,6 +2,12 @@
 
 mod adapter;
 mod byte_source;
+#[cfg(all(feature = "http", not(target_arch = "wasm32")))]
+mod http_source;
+mod range_source;
 
 pub use adapter::ByteSourceCursor;
 pub use byte_source::{ByteSource, FileSource, MemorySource};
+#[cfg(all(feature = "http", not(target_arch = "wasm32")))]
+pub use http_source::HttpRangeSource;
+pub use range_source::{RangeFetcher, RangeSource};
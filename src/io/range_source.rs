@@ -0,0 +1,162 @@
+use super::byte_source::ByteSource;
+use std::io;
+use std::sync::Mutex;
+
+/// Fetches byte ranges of a remote resource, typically with HTTP `Range`
+/// requests. Implement this over your HTTP client to open books with
+/// [`crate::Book::open_url`] without downloading them first.
+pub trait RangeFetcher: Send + Sync {
+    /// Total size of the resource at `url` in bytes (the `Content-Length`
+    /// of a `HEAD` request, or the total in a `Content-Range` header).
+    fn content_length(&self, url: &str) -> io::Result<u64>;
+
+    /// Fetch exactly `len` bytes starting at `offset`: a request with
+    /// `Range: bytes=<offset>-<offset + len - 1>`.
+    fn read_at(&self, url: &str, offset: u64, len: usize) -> io::Result<Vec<u8>>;
+}
+
+/// Smallest range requested from the fetcher. ZIP parsing issues many
+/// small reads close together (headers, the central directory); fetching
+/// a block and serving neighbouring reads from it keeps the request count
+/// low.
+const MIN_FETCH: usize = 64 * 1024;
+
+/// A [`ByteSource`] reading a remote resource through a [`RangeFetcher`].
+///
+/// Only the ranges readers ask for are fetched, so ZIP-based formats load
+/// just the entries they need. The most recently fetched block is kept to
+/// serve nearby reads.
+pub struct RangeSource<F> {
+    url: String,
+    fetcher: F,
+    len: u64,
+    block: Mutex<Option<(u64, Vec<u8>)>>,
+}
+
+impl<F: RangeFetcher> RangeSource<F> {
+    /// Wrap `fetcher` for `url`, asking it for the resource's length.
+    pub fn new(url: impl Into<String>, fetcher: F) -> io::Result<Self> {
+        let url = url.into();
+        let len = fetcher.content_length(&url)?;
+        Ok(Self::with_len(url, fetcher, len))
+    }
+
+    /// Wrap `fetcher` for `url` when the length is already known.
+    pub(crate) fn with_len(url: String, fetcher: F, len: u64) -> Self {
+        Self {
+            url,
+            fetcher,
+            len,
+            block: Mutex::new(None),
+        }
+    }
+}
+
+impl<F: RangeFetcher> ByteSource for RangeSource<F> {
+    fn len(&self) -> u64 {
+        self.len
+    }
+
+    fn read_at_into(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let end = offset
+            .checked_add(buf.len() as u64)
+            .filter(|&end| end <= self.len)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "not enough data"))?;
+        // An empty range has no valid `Range` header (`bytes=N-(N-1)`).
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        if let Some((start, data)) = self
+            .block
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            && offset >= *start
+            && end <= start + data.len() as u64
+        {
+            let from = (offset - start) as usize;
+            buf.copy_from_slice(&data[from..from + buf.len()]);
+            return Ok(buf.len());
+        }
+
+        // The lock isn't held across the fetch, so other readers (parallel
+        // chapter loads) aren't stalled behind a network round trip.
+
+        let fetch_len = buf.len().max(MIN_FETCH).min((self.len - offset) as usize);
+        let data = self.fetcher.read_at(&self.url, offset, fetch_len)?;
+        if data.len() != fetch_len {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!(
+                    "range fetch returned {} bytes, expected {fetch_len}",
+                    data.len()
+                ),
+            ));
+        }
+        buf.copy_from_slice(&data[..buf.len()]);
+        *self.block.lock().unwrap_or_else(|e| e.into_inner()) = Some((offset, data));
+        Ok(buf.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Counting {
+        data: Vec<u8>,
+        requests: AtomicUsize,
+    }
+
+    impl RangeFetcher for Counting {
+        fn content_length(&self, _url: &str) -> io::Result<u64> {
+            Ok(self.data.len() as u64)
+        }
+
+        fn read_at(&self, _url: &str, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+            self.requests.fetch_add(1, Ordering::Relaxed);
+            let offset = offset as usize;
+            Ok(self.data[offset..offset + len].to_vec())
+        }
+    }
+
+    #[test]
+    fn nearby_reads_share_one_fetch() {
+        let data: Vec<u8> = (0..200_000u32).map(|i| i as u8).collect();
+        let source = RangeSource::new(
+            "https://example.com/book.epub",
+            Counting {
+                data: data.clone(),
+                requests: AtomicUsize::new(0),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(source.read_at(10, 4).unwrap(), &data[10..14]);
+        assert_eq!(source.read_at(1000, 8).unwrap(), &data[1000..1008]);
+        assert_eq!(source.fetcher.requests.load(Ordering::Relaxed), 1);
+
+        assert_eq!(source.read_at(199_990, 10).unwrap(), &data[199_990..]);
+        assert_eq!(source.fetcher.requests.load(Ordering::Relaxed), 2);
+        assert!(source.read_at(199_995, 10).is_err());
+    }
+
+    #[test]
+    fn empty_reads_fetch_nothing() {
+        let source = RangeSource::new(
+            "https://example.com/book.epub",
+            Counting {
+                data: vec![0; 100],
+                requests: AtomicUsize::new(0),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(source.read_at_into(50, &mut []).unwrap(), 0);
+        assert_eq!(source.read_at_into(100, &mut []).unwrap(), 0);
+        assert_eq!(source.fetcher.requests.load(Ordering::Relaxed), 0);
+        assert!(source.read_at_into(101, &mut []).is_err());
+    }
+}
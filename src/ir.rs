@@ -0,0 +1,729 @@
+//! Textual IR dump format ([`Format::Ir`](crate::Format::Ir)).
+//!
+//! A stable, line-oriented rendering of a book's IR, meant as a shareable
+//! artifact for bug reports and as a fixture format for regression tests.
+//! It holds the core metadata, the table of contents, and every spine
+//! chapter's node tree with its styles and semantic attributes.
+//! [`IrExporter`](crate::export::IrExporter) writes it and
+//! [`IrImporter`](crate::import::IrImporter) reads it back, so a dump opens
+//! as a [`Book`] like any other format.
+//!
+//! ```text
+//! boko-ir 1
+//! title "A Book"
+//! author "Jane Doe"
+//! language "en"
+//! toc "Chapter One" "OEBPS/ch1.xhtml"
+//!   toc "Part A" "OEBPS/ch1.xhtml#a"
+//! chapter "OEBPS/ch1.xhtml"
+//!   style 1 "font-weight: bold;"
+//!   Root
+//!     Heading(1) s1 id="a"
+//!       Text "Chapter One"
+//!     Image src="OEBPS/images/plate.png" alt="A plate"
+//! ```
+//!
+//! Every line is a keyword followed by space-separated fields. Strings are
+//! double-quoted, with `\\`, `\"`, `\n`, `\r`, `\t` and `\u{..}` escapes.
+//! Indentation (two spaces per level) carries TOC and node nesting. A node
+//! line is its role, an optional `s<N>` style reference, an optional quoted
+//! text, then its semantic attributes as `key=value` pairs and bare flags.
+//!
+//! Styles are numbered in order of first use and written as their CSS
+//! declarations, so a style survives a round trip as far as its CSS form
+//! does. Resources (images, fonts, stylesheets) are not included; image
+//! `src`s keep their archive paths.
+
+use std::fmt::Write as _;
+use std::io::Write;
+
+use rustc_hash::FxHashMap as HashMap;
+
+use crate::Book;
+use crate::export::Progress;
+use crate::model::{Chapter, Format, Metadata, Node, NodeId, Role, TocEntry};
+use crate::style::{
+    CascadeIndex, CascadeScratch, ComputedStyle, InlineStyle, StyleId, StylePool, ToCss,
+    compute_styles_indexed,
+};
+
+/// First line of every dump: format name and version.
+const HEADER: &str = "boko-ir 1";
+
+/// A parsed dump: everything [`IrImporter`](crate::import::IrImporter)
+/// serves.
+#[derive(Debug, Default)]
+pub(crate) struct IrBook {
+    pub metadata: Metadata,
+    pub toc: Vec<TocEntry>,
+    /// Spine chapters in reading order, with their source paths (empty
+    /// when the source book had none).
+    pub chapters: Vec<(String, Chapter)>,
+}
+
+// ----------------------------------------------------------------------------
+// Writing
+// ----------------------------------------------------------------------------
+
+/// Write `book` as a dump.
+pub(crate) fn write_book(
+    book: &Book,
+    out: &mut dyn Write,
+    progress: &mut Progress<'_>,
+) -> crate::Result<()> {
+    let mut text = String::new();
+    text.push_str(HEADER);
+    text.push('\n');
+
+    let metadata = book.metadata();
+    if !metadata.title.is_empty() {
+        push_line(&mut text, 0, "title", &[&metadata.title]);
+    }
+    for author in &metadata.authors {
+        push_line(&mut text, 0, "author", &[author]);
+    }
+    let fields = [
+        ("language", Some(&metadata.language)),
+        ("identifier", Some(&metadata.identifier)),
+        ("publisher", metadata.publisher.as_ref()),
+        ("description", metadata.description.as_ref()),
+        ("date", metadata.date.as_ref()),
+    ];
+    for (key, value) in fields {
+        if let Some(value) = value.filter(|v| !v.is_empty()) {
+            push_line(&mut text, 0, key, &[value]);
+        }
+    }
+    for entry in book.toc() {
+        write_toc_entry(&mut text, entry, 0);
+    }
+    out.write_all(text.as_bytes())?;
+
+    for (idx, entry) in book.spine().iter().enumerate() {
+        progress.chapter_started(idx);
+        let chapter = book.load_chapter_cached(entry.id)?;
+        let source = book.source_id(entry.id).unwrap_or_default();
+        out.write_all(write_chapter(source, &chapter).as_bytes())?;
+        progress.chapter_finished(idx);
+    }
+    Ok(())
+}
+
+/// Render one chapter block: the `chapter` line, its style table and its
+/// node tree.
+pub(crate) fn write_chapter(source: &str, chapter: &Chapter) -> String {
+    let mut out = String::new();
+    push_line(&mut out, 0, "chapter", &[source]);
+
+    // Number styles by first use, merging styles with the same CSS so the
+    // numbering is canonical (a re-read dump writes out identically).
+    let mut numbers: HashMap<StyleId, usize> = HashMap::default();
+    let mut by_css: HashMap<String, usize> = HashMap::default();
+    for id in chapter.iter_dfs() {
+        let Some(node) = chapter.node(id) else {
+            continue;
+        };
+        if node.style == StyleId::DEFAULT || numbers.contains_key(&node.style) {
+            continue;
+        }
+        let css = chapter
+            .styles
+            .get(node.style)
+            .map(|style| style.to_css_string().trim_end().to_string())
+            .unwrap_or_default();
+        if css.is_empty() {
+            continue;
+        }
+        let next = by_css.len() + 1;
+        let number = *by_css.entry(css.clone()).or_insert_with(|| {
+            out.push_str("  style ");
+            let _ = write!(out, "{next} ");
+            push_quoted(&mut out, &css);
+            out.push('\n');
+            next
+        });
+        numbers.insert(node.style, number);
+    }
+
+    write_node(&mut out, chapter, NodeId::ROOT, &numbers, 1);
+    out
+}
+
+fn write_toc_entry(out: &mut String, entry: &TocEntry, depth: usize) {
+    if depth > crate::util::MAX_TREE_DEPTH {
+        return;
+    }
+    push_line(out, depth, "toc", &[&entry.title, &entry.href]);
+    for child in &entry.children {
+        write_toc_entry(out, child, depth + 1);
+    }
+}
+
+fn write_node(
+    out: &mut String,
+    chapter: &Chapter,
+    id: NodeId,
+    styles: &HashMap<StyleId, usize>,
+    depth: usize,
+) {
+    if depth > crate::util::MAX_TREE_DEPTH {
+        return;
+    }
+    let Some(node) = chapter.node(id) else {
+        return;
+    };
+    indent(out, depth);
+    out.push_str(&role_name(node.role));
+    if let Some(number) = styles.get(&node.style) {
+        let _ = write!(out, " s{number}");
+    }
+    if node.text.len > 0 {
+        out.push(' ');
+        push_quoted(out, chapter.text(node.text));
+    }
+
+    let s = &chapter.semantics;
+    let strings = [
+        ("id", s.id(id)),
+        ("href", s.href(id)),
+        ("src", s.src(id)),
+        ("alt", s.alt(id)),
+        ("title", s.title(id)),
+        ("lang", s.lang(id)),
+        ("epub-type", s.epub_type(id)),
+        ("role", s.aria_role(id)),
+        ("datetime", s.datetime(id)),
+        ("cite", s.cite(id)),
+        ("scope", s.scope(id)),
+        ("headers", s.headers(id)),
+        ("language", s.language(id)),
+    ];
+    for (key, value) in strings {
+        if let Some(value) = value {
+            let _ = write!(out, " {key}=");
+            push_quoted(out, value);
+        }
+    }
+    let numbers = [
+        ("start", s.list_start(id)),
+        ("value", s.list_value(id)),
+        ("rowspan", s.row_span(id)),
+        ("colspan", s.col_span(id)),
+    ];
+    for (key, value) in numbers {
+        if let Some(value) = value {
+            let _ = write!(out, " {key}={value}");
+        }
+    }
+    if s.is_header_cell(id) {
+        out.push_str(" header");
+    }
+    if s.is_decorative(id) {
+        out.push_str(" decorative");
+    }
+    if let Some(math) = chapter.math.get(&id) {
+        out.push_str(" math=");
+        push_quoted(out, &crate::math::mathml::to_mathml(math));
+    }
+    out.push('\n');
+
+    for child in chapter.children(id) {
+        write_node(out, chapter, child, styles, depth + 1);
+    }
+}
+
+fn indent(out: &mut String, depth: usize) {
+    for _ in 0..depth {
+        out.push_str("  ");
+    }
+}
+
+/// Append `keyword "field" "field"...` at `depth`.
+fn push_line(out: &mut String, depth: usize, keyword: &str, fields: &[&str]) {
+    indent(out, depth);
+    out.push_str(keyword);
+    for field in fields {
+        out.push(' ');
+        push_quoted(out, field);
+    }
+    out.push('\n');
+}
+
+fn push_quoted(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{{{:x}}}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Role keyword. Exhaustive so a new [`Role`] can't be added without
+/// deciding its name here and in [`parse_role`].
+fn role_name(role: Role) -> String {
+    let name = match role {
+        Role::Heading(level) => return format!("Heading({level})"),
+        Role::Text => "Text",
+        Role::Paragraph => "Paragraph",
+        Role::Container => "Container",
+        Role::Image => "Image",
+        Role::Link => "Link",
+        Role::OrderedList => "OrderedList",
+        Role::UnorderedList => "UnorderedList",
+        Role::ListItem => "ListItem",
+        Role::Table => "Table",
+        Role::TableHead => "TableHead",
+        Role::TableBody => "TableBody",
+        Role::TableRow => "TableRow",
+        Role::TableCell => "TableCell",
+        Role::Sidebar => "Sidebar",
+        Role::Footnote => "Footnote",
+        Role::Figure => "Figure",
+        Role::Inline => "Inline",
+        Role::BlockQuote => "BlockQuote",
+        Role::Quote => "Quote",
+        Role::Root => "Root",
+        Role::Break => "Break",
+        Role::Rule => "Rule",
+        Role::DefinitionList => "DefinitionList",
+        Role::DefinitionTerm => "DefinitionTerm",
+        Role::DefinitionDescription => "DefinitionDescription",
+        Role::CodeBlock => "CodeBlock",
+        Role::Caption => "Caption",
+        Role::Math => "Math",
+    };
+    name.to_string()
+}
+
+fn parse_role(name: &str) -> Option<Role> {
+    if let Some(level) = name
+        .strip_prefix("Heading(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        return level.parse().ok().map(Role::Heading);
+    }
+    Some(match name {
+        "Text" => Role::Text,
+        "Paragraph" => Role::Paragraph,
+        "Container" => Role::Container,
+        "Image" => Role::Image,
+        "Link" => Role::Link,
+        "OrderedList" => Role::OrderedList,
+        "UnorderedList" => Role::UnorderedList,
+        "ListItem" => Role::ListItem,
+        "Table" => Role::Table,
+        "TableHead" => Role::TableHead,
+        "TableBody" => Role::TableBody,
+        "TableRow" => Role::TableRow,
+        "TableCell" => Role::TableCell,
+        "Sidebar" => Role::Sidebar,
+        "Footnote" => Role::Footnote,
+        "Figure" => Role::Figure,
+        "Inline" => Role::Inline,
+        "BlockQuote" => Role::BlockQuote,
+        "Quote" => Role::Quote,
+        "Root" => Role::Root,
+        "Break" => Role::Break,
+        "Rule" => Role::Rule,
+        "DefinitionList" => Role::DefinitionList,
+        "DefinitionTerm" => Role::DefinitionTerm,
+        "DefinitionDescription" => Role::DefinitionDescription,
+        "CodeBlock" => Role::CodeBlock,
+        "Caption" => Role::Caption,
+        "Math" => Role::Math,
+        _ => return None,
+    })
+}
+
+// ----------------------------------------------------------------------------
+// Reading
+// ----------------------------------------------------------------------------
+
+/// One field of a line.
+#[derive(Debug, PartialEq)]
+enum Field {
+    /// A bare word (keyword, role, style reference or flag).
+    Word(String),
+    /// A quoted string.
+    Str(String),
+    /// `key=value`, the value quoted or bare.
+    Attr(String, String),
+}
+
+/// Parse a dump.
+pub(crate) fn parse(text: &str) -> crate::Result<IrBook> {
+    let mut lines = text.lines().enumerate().map(|(i, line)| (i + 1, line));
+    match lines.next() {
+        Some((_, first)) if first.trim_end() == HEADER => {}
+        _ => return Err(malformed(1, format!("expected `{HEADER}` header"))),
+    }
+
+    let mut book = IrBook::default();
+    let mut toc = Vec::new();
+    let mut chapter: Option<ChapterReader> = None;
+
+    for (number, line) in lines {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let depth = (line.len() - line.trim_start_matches(' ').len()) / 2;
+        let fields = split_fields(line.trim()).map_err(|e| malformed(number, e))?;
+        let Some(Field::Word(keyword)) = fields.first() else {
+            return Err(malformed(number, "expected a keyword".into()));
+        };
+        let args = &fields[1..];
+
+        if depth == 0
+            && let Some(done) = chapter.take()
+        {
+            book.chapters.push(done.finish());
+        }
+        match (depth, keyword.as_str()) {
+            (0, "chapter") => {
+                let source = one_string(args).map_err(|e| malformed(number, e))?;
+                chapter = Some(ChapterReader::new(source));
+            }
+            (_, "toc") if chapter.is_none() => {
+                let [Field::Str(title), Field::Str(href)] = args else {
+                    return Err(malformed(number, "toc takes a title and an href".into()));
+                };
+                toc.push((depth, TocEntry::new(title.as_str(), href.as_str())));
+            }
+            (0, key) => {
+                let value = one_string(args).map_err(|e| malformed(number, e))?;
+                set_metadata(&mut book.metadata, key, value).map_err(|e| malformed(number, e))?;
+            }
+            (_, _) => {
+                let Some(reader) = chapter.as_mut() else {
+                    return Err(malformed(number, "node outside a chapter".into()));
+                };
+                reader
+                    .line(depth, keyword, args)
+                    .map_err(|e| malformed(number, e))?;
+            }
+        }
+    }
+    if let Some(done) = chapter.take() {
+        book.chapters.push(done.finish());
+    }
+    book.toc = nest_toc(toc);
+    Ok(book)
+}
+
+fn malformed(line: usize, context: String) -> crate::Error {
+    crate::Error::Malformed {
+        format: Format::Ir,
+        context: format!("line {line}: {context}"),
+    }
+}
+
+fn one_string(args: &[Field]) -> Result<String, String> {
+    match args {
+        [Field::Str(value)] => Ok(value.clone()),
+        _ => Err("expected one quoted string".into()),
+    }
+}
+
+fn set_metadata(metadata: &mut Metadata, key: &str, value: String) -> Result<(), String> {
+    match key {
+        "title" => metadata.title = value,
+        "author" => metadata.authors.push(value),
+        "language" => metadata.language = value,
+        "identifier" => metadata.identifier = value,
+        "publisher" => metadata.publisher = Some(value),
+        "description" => metadata.description = Some(value),
+        "date" => metadata.date = Some(value),
+        _ => return Err(format!("unknown keyword `{key}`")),
+    }
+    Ok(())
+}
+
+/// Rebuild the TOC tree from `(depth, entry)` pairs in document order.
+fn nest_toc(flat: Vec<(usize, TocEntry)>) -> Vec<TocEntry> {
+    let mut roots = Vec::new();
+    let mut open: Vec<(usize, TocEntry)> = Vec::new();
+    let close = |open: &mut Vec<(usize, TocEntry)>, roots: &mut Vec<TocEntry>| {
+        let (_, entry) = open.pop().expect("caller checked non-empty");
+        match open.last_mut() {
+            Some((_, parent)) => parent.children.push(entry),
+            None => roots.push(entry),
+        }
+    };
+    for (depth, entry) in flat {
+        while open.last().is_some_and(|(d, _)| *d >= depth) {
+            close(&mut open, &mut roots);
+        }
+        open.push((depth, entry));
+    }
+    while !open.is_empty() {
+        close(&mut open, &mut roots);
+    }
+    roots
+}
+
+/// Builds one chapter from its block's lines.
+struct ChapterReader {
+    source: String,
+    chapter: Chapter,
+    /// Style number → interned style.
+    styles: HashMap<usize, StyleId>,
+    /// Open ancestors of the next node line; `path[d]` sits at depth `d + 1`.
+    path: Vec<NodeId>,
+}
+
+impl ChapterReader {
+    fn new(source: String) -> Self {
+        Self {
+            source,
+            chapter: Chapter::new(),
+            styles: HashMap::default(),
+            path: Vec::new(),
+        }
+    }
+
+    fn finish(self) -> (String, Chapter) {
+        (self.source, self.chapter)
+    }
+
+    fn line(&mut self, depth: usize, keyword: &str, args: &[Field]) -> Result<(), String> {
+        if keyword == "style" {
+            let [Field::Word(number), Field::Str(css)] = args else {
+                return Err("style takes a number and quoted CSS".into());
+            };
+            let number = number
+                .parse()
+                .map_err(|_| format!("bad style number `{number}`"))?;
+            let id = self.chapter.styles.intern(style_from_css(css));
+            self.styles.insert(number, id);
+            return Ok(());
+        }
+
+        let role = parse_role(keyword).ok_or_else(|| format!("unknown role `{keyword}`"))?;
+        let id = if depth == 1 {
+            if role != Role::Root || !self.path.is_empty() {
+                return Err("a chapter has exactly one Root".into());
+            }
+            NodeId::ROOT
+        } else {
+            if depth > self.path.len() + 1 {
+                return Err("node is indented past its parent".into());
+            }
+            self.path.truncate(depth - 1);
+            let parent = *self.path.last().ok_or("node outside the Root")?;
+            let id = self.chapter.alloc_node(Node::new(role));
+            self.chapter.append_child(parent, id);
+            id
+        };
+        self.path.push(id);
+
+        for field in args {
+            self.node_field(id, field)?;
+        }
+        Ok(())
+    }
+
+    fn node_field(&mut self, id: NodeId, field: &Field) -> Result<(), String> {
+        let chapter = &mut self.chapter;
+        match field {
+            Field::Str(text) => {
+                let range = chapter.append_text(text);
+                chapter.node_mut(id).expect("node was just allocated").text = range;
+            }
+            Field::Word(word) if word == "header" => chapter.semantics.set_header_cell(id, true),
+            Field::Word(word) if word == "decorative" => chapter.semantics.set_decorative(id, true),
+            Field::Word(word) => {
+                let number = word
+                    .strip_prefix('s')
+                    .and_then(|n| n.parse().ok())
+                    .ok_or_else(|| format!("unexpected `{word}`"))?;
+                let style = *self
+                    .styles
+                    .get(&number)
+                    .ok_or_else(|| format!("undeclared style s{number}"))?;
+                chapter.node_mut(id).expect("node was just allocated").style = style;
+            }
+            Field::Attr(key, value) => {
+                let number = || {
+                    value
+                        .parse::<u32>()
+                        .map_err(|_| format!("`{key}` takes a number"))
+                };
+                let s = &mut chapter.semantics;
+                match key.as_str() {
+                    "id" => s.set_id(id, value),
+                    "href" => s.set_href(id, value),
+                    "src" => s.set_src(id, value),
+                    "alt" => s.set_alt(id, value),
+                    "title" => s.set_title(id, value),
+                    "lang" => s.set_lang(id, value),
+                    "epub-type" => s.set_epub_type(id, value),
+                    "role" => s.set_aria_role(id, value),
+                    "datetime" => s.set_datetime(id, value),
+                    "cite" => s.set_cite(id, value),
+                    "scope" => s.set_scope(id, value),
+                    "headers" => s.set_headers(id, value),
+                    "language" => s.set_language(id, value),
+                    "start" => s.set_list_start(id, number()?),
+                    "value" => s.set_list_value(id, number()?),
+                    "rowspan" => s.set_row_span(id, number()?),
+                    "colspan" => s.set_col_span(id, number()?),
+                    "math" => {
+                        let math = crate::math::mathml::parse_math_str(value)
+                            .ok_or("math is not a <math> element")?;
+                        chapter.math.insert(id, math);
+                    }
+                    _ => return Err(format!("unknown attribute `{key}`")),
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compute a style from a CSS declaration list, as an element with that
+/// `style` attribute and no parent would get.
+fn style_from_css(css: &str) -> ComputedStyle {
+    let dom = crate::dom::parse_dom("<div></div>");
+    let div = dom.find_by_tag("div").expect("parsed a div");
+    compute_styles_indexed(
+        crate::dom::element_ref::ElementRef::new(&dom, div),
+        &CascadeIndex::build(&[]),
+        None,
+        &mut StylePool::new(),
+        &mut CascadeScratch::default(),
+        None,
+        Some(&InlineStyle::parse(css)),
+        None,
+    )
+}
+
+/// Split a trimmed line into fields.
+fn split_fields(line: &str) -> Result<Vec<Field>, String> {
+    let mut fields = Vec::new();
+    let mut rest = line;
+    while !rest.is_empty() {
+        if rest.starts_with('"') {
+            let (value, after) = take_quoted(rest)?;
+            fields.push(Field::Str(value));
+            rest = after;
+        } else {
+            let end = rest.find([' ', '=']).unwrap_or(rest.len());
+            let word = rest[..end].to_string();
+            rest = &rest[end..];
+            if let Some(after) = rest.strip_prefix('=') {
+                let (value, after) = if after.starts_with('"') {
+                    take_quoted(after)?
+                } else {
+                    let end = after.find(' ').unwrap_or(after.len());
+                    (after[..end].to_string(), &after[end..])
+                };
+                fields.push(Field::Attr(word, value));
+                rest = after;
+            } else {
+                fields.push(Field::Word(word));
+            }
+        }
+        rest = match rest.strip_prefix(' ') {
+            Some(after) => after.trim_start_matches(' '),
+            None if rest.is_empty() => rest,
+            None => return Err(format!("expected a space before `{rest}`")),
+        };
+    }
+    Ok(fields)
+}
+
+/// Read a quoted string at the start of `s`, returning it unescaped and the
+/// remainder after the closing quote.
+fn take_quoted(s: &str) -> Result<(String, &str), String> {
+    let mut value = String::new();
+    let mut chars = s.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((value, &s[i + 1..])),
+            '\\' => match chars.next().map(|(_, c)| c) {
+                Some('"') => value.push('"'),
+                Some('\\') => value.push('\\'),
+                Some('n') => value.push('\n'),
+                Some('r') => value.push('\r'),
+                Some('t') => value.push('\t'),
+                Some('u') => {
+                    let hex: String = chars
+                        .by_ref()
+                        .map(|(_, c)| c)
+                        .skip_while(|&c| c == '{')
+                        .take_while(|&c| c != '}')
+                        .collect();
+                    let c = u32::from_str_radix(&hex, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .ok_or_else(|| format!("bad escape \\u{{{hex}}}"))?;
+                    value.push(c);
+                }
+                other => return Err(format!("bad escape \\{}", other.unwrap_or(' '))),
+            },
+            c => value.push(c),
+        }
+    }
+    Err("unterminated string".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quoted_strings_round_trip_escapes() {
+        let original = "say \"hi\"\\\n\tend\u{1}é";
+        let mut quoted = String::new();
+        push_quoted(&mut quoted, original);
+        assert_eq!(quoted, "\"say \\\"hi\\\"\\\\\\n\\tend\\u{1}é\"");
+        assert_eq!(take_quoted(&quoted), Ok((original.to_string(), "")));
+    }
+
+    #[test]
+    fn fields_split_words_strings_and_attributes() {
+        let fields = split_fields(r#"Image s2 "a b" src="x y.png" start=3 header"#).unwrap();
+        assert_eq!(
+            fields,
+            vec![
+                Field::Word("Image".into()),
+                Field::Word("s2".into()),
+                Field::Str("a b".into()),
+                Field::Attr("src".into(), "x y.png".into()),
+                Field::Attr("start".into(), "3".into()),
+                Field::Word("header".into()),
+            ]
+        );
+        assert!(split_fields(r#""unterminated"#).is_err());
+    }
+
+    #[test]
+    fn role_names_parse_back() {
+        for role in [
+            Role::Heading(3),
+            Role::Text,
+            Role::DefinitionDescription,
+            Role::Math,
+            Role::Root,
+        ] {
+            assert_eq!(parse_role(&role_name(role)), Some(role));
+        }
+        assert_eq!(parse_role("Heading(x)"), None);
+    }
+
+    #[test]
+    fn bad_input_reports_the_line() {
+        let err = parse("boko-ir 1\nchapter \"a\"\n  Root\n    Bogus\n").unwrap_err();
+        assert!(err.to_string().contains("line 4"), "{err}");
+        assert!(parse("not a dump\n").is_err());
+    }
+}
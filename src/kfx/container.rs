@@ -228,17 +228,23 @@ pub fn parse_index_table(data: &[u8], header_len: usize) -> Vec<EntityLoc> {
 ///
 /// Returns the slice after the ENTY header, or the original slice if no header.
 pub fn skip_enty_header(data: &[u8]) -> &[u8] {
+    &data[enty_header_len(data, data.len())..]
+}
+
+/// Length of the ENTY header of an `entity_len`-byte entity starting with
+/// `head` (at least its first 10 bytes), or 0 when it has none.
+pub fn enty_header_len(head: &[u8], entity_len: usize) -> usize {
     // A valid ENTY header is at least 10 bytes (magic + version + length);
     // a corrupt smaller value would return a slice starting inside the
     // magic bytes and feed garbage to the Ion parser.
-    if data.len() >= 10
-        && &data[0..4] == b"ENTY"
-        && let Some(header_len) = read_u32_le(data, 6)
-        && (10..data.len() as u64).contains(&(header_len as u64))
+    if head.len() >= 10
+        && &head[0..4] == b"ENTY"
+        && let Some(header_len) = read_u32_le(head, 6)
+        && (10..entity_len as u64).contains(&(header_len as u64))
     {
-        return &data[header_len as usize..];
+        return header_len as usize;
     }
-    data
+    0
 }
 
 // --- Document symbols parsing ---
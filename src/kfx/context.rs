@@ -4,13 +4,15 @@
 //! All shared state flows through this context, avoiding the pitfalls of
 //! scattered symbol tables, ID collision, and orphaned references.
 
+use std::borrow::Cow;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::ops::Range;
 
 use rustc_hash::FxHashMap;
 
 use crate::import::ChapterId;
 use crate::model::{GlobalNodeId, LandmarkType, NodeId};
-use crate::style::StyleId;
+use crate::style::{StyleId, WritingMode};
 
 use super::style_registry::StyleRegistry;
 use super::symbols::KFX_SYMBOL_TABLE_SIZE;
@@ -223,12 +225,17 @@ impl Default for ResourceRegistry {
 /// and its format checks treat larger fragments as errors.
 pub const MAX_CONTENT_CHUNK_BYTES: usize = 8192;
 
+/// Hard upper bound per $145 content fragment when size-based chunking is
+/// disabled ([`ExportContext::set_single_section`]). A chapter larger than
+/// this still rolls over, so a pathological chapter cannot produce an
+/// unbounded fragment.
+pub const MAX_SINGLE_SECTION_CHUNK_BYTES: usize = 1 << 20;
+
 /// Book-global text accumulator for content entities ($145).
 ///
 /// Text is packed into `content_1..content_N` chunks of at most
 /// [`MAX_CONTENT_CHUNK_BYTES`] (measured in UTF-8 bytes), spanning chapter
 /// boundaries, matching Amazon-produced KFX.
-#[derive(Default)]
 pub struct TextAccumulator {
     /// Finished chunks: (chunk_number, segments).
     finished: Vec<(usize, Vec<String>)>,
@@ -238,6 +245,20 @@ pub struct TextAccumulator {
     current_bytes: usize,
     /// Number of the current chunk (0 = none started yet).
     current_chunk: usize,
+    /// Size bound at which a new chunk starts.
+    limit: usize,
+}
+
+impl Default for TextAccumulator {
+    fn default() -> Self {
+        Self {
+            finished: Vec::new(),
+            segments: Vec::new(),
+            current_bytes: 0,
+            current_chunk: 0,
+            limit: MAX_CONTENT_CHUNK_BYTES,
+        }
+    }
 }
 
 impl TextAccumulator {
@@ -246,6 +267,28 @@ impl TextAccumulator {
         Self::default()
     }
 
+    /// Change the size bound at which a new chunk starts.
+    pub fn set_limit(&mut self, bytes: usize) {
+        self.limit = bytes;
+    }
+
+    /// The size bound at which a new chunk starts.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Close the chunk in progress so the next push starts a new one.
+    /// No-op when the current chunk is empty.
+    pub fn finish_chunk(&mut self) {
+        if self.segments.is_empty() {
+            return;
+        }
+        self.finished
+            .push((self.current_chunk, std::mem::take(&mut self.segments)));
+        self.current_chunk += 1;
+        self.current_bytes = 0;
+    }
+
     /// Push text, returning `(chunk_number, index_within_chunk)`.
     ///
     /// Mirrors the reference chunker: a new chunk starts when the current one
@@ -254,7 +297,7 @@ impl TextAccumulator {
     pub fn push(&mut self, text: &str) -> (usize, usize) {
         if self.current_chunk == 0 {
             self.current_chunk = 1;
-        } else if self.current_bytes >= MAX_CONTENT_CHUNK_BYTES {
+        } else if self.current_bytes >= self.limit {
             self.finished
                 .push((self.current_chunk, std::mem::take(&mut self.segments)));
             self.current_chunk += 1;
@@ -567,6 +610,25 @@ impl AnchorRegistry {
         }
     }
 
+    /// Move every recorded offset in `range` of `fragment_id` onto
+    /// `target`, rebased to the start of the range.
+    ///
+    /// Called when oversized text is split into sibling runs: positions
+    /// counted against the parent's text now live in the run that holds it.
+    pub fn rebase_offsets(&mut self, fragment_id: u64, range: Range<usize>, target: u64) {
+        for pos in self.node_positions.values_mut() {
+            if pos.0 == fragment_id && range.contains(&pos.1) {
+                *pos = (target, pos.1 - range.start);
+            }
+        }
+        for anchor in &mut self.resolved {
+            if anchor.fragment_id == fragment_id && range.contains(&anchor.offset) {
+                anchor.fragment_id = target;
+                anchor.offset -= range.start;
+            }
+        }
+    }
+
     /// Record the position of a chapter start.
     pub fn record_chapter_position(&mut self, chapter: ChapterId, fragment_id: u64) {
         self.chapter_positions.entry(chapter).or_insert(fragment_id);
@@ -670,6 +732,18 @@ pub struct ExportContext {
     /// Number of the content chunk `current_content_name` refers to.
     current_content_chunk: usize,
 
+    /// Start a fresh content chunk at every chapter and lift the per-chunk
+    /// size bound (see [`set_single_section`](Self::set_single_section)).
+    single_section: bool,
+
+    /// Content language written to the book metadata in place of the
+    /// book's own (see [`set_language_override`](Self::set_language_override)).
+    language_override: Option<String>,
+
+    /// Strip unexpected characters from text content
+    /// (see [`set_clean_content`](Self::set_clean_content)).
+    clean_content: bool,
+
     /// Position map: (ChapterId, NodeId) → Position.
     /// Populated during Pass 1 survey for landmark resolution.
     pub position_map: FxHashMap<(ChapterId, NodeId), Position>,
@@ -757,6 +831,14 @@ pub struct ExportContext {
     /// `yj_table_viewer` content features). Set during storyline export.
     pub has_tables: bool,
 
+    /// Body `writing-mode` of each surveyed chapter, in survey order. Set
+    /// during the survey pass; see [`Self::writing_mode`].
+    pub body_writing_modes: Vec<WritingMode>,
+
+    /// The OPF's `page-progression-direction`: `Some(true)` for `rtl`,
+    /// `Some(false)` for `ltr`, `None` when undeclared or `default`.
+    pub page_progression_rtl: Option<bool>,
+
     /// Something referenced the default style (s0); when nothing does, the
     /// fragment is not emitted (an unreferenced style is a conformance error).
     pub default_style_used: bool,
@@ -861,6 +943,9 @@ impl ExportContext {
             text_accumulator: TextAccumulator::new(),
             current_content_name: 0,
             current_content_chunk: 0,
+            single_section: false,
+            language_override: None,
+            clean_content: true,
             position_map: FxHashMap::default(),
             chapter_fragments: FxHashMap::default(),
             current_chapter: None,
@@ -881,6 +966,8 @@ impl ExportContext {
             has_hdv_image: false,
             jpg_rst_marker_present: false,
             has_tables: false,
+            body_writing_modes: Vec::new(),
+            page_progression_rtl: None,
             default_style_used: false,
             dropcap_suppress: false,
             font_size_weights: FxHashMap::default(),
@@ -898,6 +985,29 @@ impl ExportContext {
         }
     }
 
+    /// Block flow of the book's body text: a vertical mode set on the first
+    /// chapter's body or on most chapters' bodies. A few vertical chapters
+    /// (a poem, a facsimile page) leave a horizontal book horizontal.
+    pub fn writing_mode(&self) -> WritingMode {
+        let modes = &self.body_writing_modes;
+        let first = modes.first().copied().unwrap_or_default();
+        if first.is_vertical() {
+            return first;
+        }
+        [WritingMode::VerticalRl, WritingMode::VerticalLr]
+            .into_iter()
+            .find(|&mode| 2 * modes.iter().filter(|&&m| m == mode).count() > modes.len())
+            .unwrap_or_default()
+    }
+
+    /// Whether pages turn right to left: as the OPF declares, or else
+    /// implied by vertical-rl body text, whose lines (and so pages) advance
+    /// leftward.
+    pub fn is_rtl(&self) -> bool {
+        self.page_progression_rtl
+            .unwrap_or_else(|| self.writing_mode() == WritingMode::VerticalRl)
+    }
+
     /// Record that a section references a given image resource (by short name).
     pub fn record_section_image_ref(&mut self, section_name: &str, short_name: &str) {
         self.section_resource_deps
@@ -925,9 +1035,62 @@ impl ExportContext {
         self.ir_style_memo.clear();
     }
 
+    /// Write `language` as the book's content language instead of the
+    /// metadata's `dc:language`.
+    pub fn set_language_override(&mut self, language: Option<String>) {
+        self.language_override = language;
+    }
+
+    /// Content language override, if one was set.
+    pub fn language_override(&self) -> Option<&str> {
+        self.language_override.as_deref()
+    }
+
+    /// Whether text content is stripped of control characters and
+    /// noncharacters on its way into content fragments. On by default.
+    pub fn set_clean_content(&mut self, enabled: bool) {
+        self.clean_content = enabled;
+    }
+
+    /// `text` as it should land in a content fragment: without control
+    /// characters (other than tab and line breaks) and noncharacters, unless
+    /// cleaning is disabled. Applied per text run, before style-event
+    /// offsets are counted.
+    pub fn clean_text<'t>(&self, text: &'t str) -> Cow<'t, str> {
+        if !self.clean_content {
+            return Cow::Borrowed(text);
+        }
+        crate::util::clean_unexpected_chars(text)
+    }
+
+    /// How many characters [`clean_text`](Self::clean_text) drops from
+    /// `text`.
+    pub fn removed_chars(&self, text: &str) -> usize {
+        if !self.clean_content {
+            return 0;
+        }
+        crate::util::count_unexpected_chars(text)
+    }
+
+    /// Disable size-based content chunking: each chapter's text lands in a
+    /// single content fragment (up to [`MAX_SINGLE_SECTION_CHUNK_BYTES`]),
+    /// with chunks rolling over only at chapter boundaries. Must be set
+    /// before any text is appended.
+    pub fn set_single_section(&mut self, enabled: bool) {
+        self.single_section = enabled;
+        self.text_accumulator.set_limit(if enabled {
+            MAX_SINGLE_SECTION_CHUNK_BYTES
+        } else {
+            MAX_CONTENT_CHUNK_BYTES
+        });
+    }
+
     /// Begin Pass 2 export for a chapter.
     pub fn begin_chapter_export(&mut self, chapter_id: ChapterId) {
         self.current_chapter = Some(chapter_id);
+        if self.single_section {
+            self.text_accumulator.finish_chunk();
+        }
 
         // Check if this chapter needs a chapter-start anchor
         if self.chapters_needing_anchor.contains(&chapter_id) {
@@ -946,7 +1109,8 @@ impl ExportContext {
     ///
     /// Returns `(content_name_symbol, index_within_chunk)` — the pair a
     /// storyline content_ref needs. Rolls to a new `content_{N}` chunk when
-    /// the current one has reached [`MAX_CONTENT_CHUNK_BYTES`].
+    /// the current one has reached [`MAX_CONTENT_CHUNK_BYTES`] (or the
+    /// single-section bound, see [`set_single_section`](Self::set_single_section)).
     pub fn append_text(&mut self, text: &str) -> (u64, usize) {
         let (chunk, index) = self.text_accumulator.push(text);
         if chunk != self.current_content_chunk {
@@ -956,6 +1120,18 @@ impl ExportContext {
         (self.current_content_name, index)
     }
 
+    /// Size bound of a content chunk: text longer than this cannot fit in
+    /// one content fragment.
+    pub fn content_chunk_limit(&self) -> usize {
+        self.text_accumulator.limit()
+    }
+
+    /// Close the content chunk in progress so the next
+    /// [`append_text`](Self::append_text) starts a new one.
+    pub fn finish_content_chunk(&mut self) {
+        self.text_accumulator.finish_chunk();
+    }
+
     /// Whether math renders as a KVG-bearing container (a math font is
     /// available) or falls back to inline text runs.
     pub fn math_renders_as_container(&mut self) -> bool {
@@ -1053,6 +1229,16 @@ impl ExportContext {
         scaled
     }
 
+    /// `style` with display-size leading fitted to its font (see
+    /// [`fit_display_leading`](crate::kfx::style_schema::fit_display_leading));
+    /// the body text size is the one the font scale maps to 1rem.
+    fn fitted_leading<'s>(
+        &self,
+        style: &'s crate::style::ComputedStyle,
+    ) -> Cow<'s, crate::style::ComputedStyle> {
+        crate::kfx::style_schema::fit_display_leading(style, 1.0 / self.font_scale)
+    }
+
     /// Build the KFX property set for a style under the book's font scale.
     /// `parent_is_default` marks the root inheritance context, which is the
     /// renderer environment — 1rem absolutely, never rescaled.
@@ -1062,6 +1248,7 @@ impl ExportContext {
         parent: &crate::style::ComputedStyle,
         parent_is_default: bool,
     ) -> crate::kfx::style_registry::ComputedStyle {
+        let ir_style = &*self.fitted_leading(ir_style);
         let schema = crate::kfx::style_schema::StyleSchema::standard();
         let mut builder = crate::kfx::style_registry::StyleBuilder::new(schema);
         if self.font_scale != 1.0 || self.line_scale != 1.0 {
@@ -1168,6 +1355,7 @@ impl ExportContext {
         let ir_style = style_pool.get(style_id);
         let parent = style_pool.get(parent_id).unwrap_or(&default);
         let ir_style_ref = ir_style.unwrap_or(&default);
+        let fitted = self.fitted_leading(ir_style_ref);
 
         let mut kfx_style =
             self.build_kfx_style(ir_style_ref, parent, parent_id == StyleId::DEFAULT);
@@ -1181,7 +1369,7 @@ impl ExportContext {
                 if v == 0.0 {
                     kfx_style.remove(symbol);
                 } else {
-                    let lh = crate::kfx::style_schema::margin_abs_em_to_lh(ir_style_ref, v as f64);
+                    let lh = crate::kfx::style_schema::margin_abs_em_to_lh(&fitted, v as f64);
                     // Round like extract_ir_field's dimension formatting.
                     let lh = (lh * 1e6).round() / 1e6;
                     kfx_style.set(
@@ -1512,6 +1700,16 @@ impl Default for ExportContext {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_clean_text_strips_controls_and_noncharacters() {
+        let mut ctx = ExportContext::new();
+        let text = "a\u{0}b\u{85}c\tline\n\u{FFFE}\u{FDD0}\u{1FFFF}é\u{200B}";
+        assert_eq!(ctx.clean_text(text), "abc\tline\né\u{200B}");
+        assert!(matches!(ctx.clean_text("plain"), Cow::Borrowed(_)));
+        ctx.set_clean_content(false);
+        assert_eq!(ctx.clean_text(text), text);
+    }
+
     #[test]
     fn test_symbol_table_shared_symbols() {
         let mut symtab = SymbolTable::new();
@@ -1529,6 +1727,23 @@ mod tests {
         assert_eq!(symtab.get_or_intern("section-1"), id1);
     }
 
+    #[test]
+    fn test_text_accumulator_finish_chunk_and_limit() {
+        let mut acc = TextAccumulator::new();
+        acc.set_limit(MAX_SINGLE_SECTION_CHUNK_BYTES);
+        let big = "x".repeat(MAX_CONTENT_CHUNK_BYTES);
+        assert_eq!(acc.push(&big), (1, 0));
+        // Past the default bound, but the raised limit keeps one chunk.
+        assert_eq!(acc.push(&big), (1, 1));
+        acc.finish_chunk();
+        // Finishing an empty chunk is a no-op.
+        acc.finish_chunk();
+        assert_eq!(acc.push("y"), (2, 0));
+        let chunks = acc.drain_chunks();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].1.len(), 2);
+    }
+
     #[test]
     fn test_id_generator() {
         let mut id_gen = IdGenerator::new();
@@ -918,9 +918,9 @@ impl KfxSchema {
     /// Check if a role should be treated as an inline span during export.
     ///
     /// Inline spans are rendered as style_events in KFX, not as nested containers.
-    /// This includes: Link, Inline (for bold/italic spans).
+    /// This includes: Link, Inline (for bold/italic spans) and Quote.
     pub fn is_inline_role(&self, role: Role) -> bool {
-        matches!(role, Role::Link | Role::Inline)
+        matches!(role, Role::Link | Role::Inline | Role::Quote)
     }
 
     // =========================================================================
@@ -151,7 +151,7 @@ impl<'a> Collapser<'a> {
         match node.role {
             Role::Text => !self.chapter.text(node.text).trim().is_empty(),
             Role::Break => true,
-            Role::Link | Role::Inline => self.has_visible_content(id),
+            Role::Link | Role::Inline | Role::Quote => self.has_visible_content(id),
             _ => false,
         }
     }
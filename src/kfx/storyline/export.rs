@@ -57,7 +57,7 @@ fn link_color_for(chapter: &Chapter, node_id: NodeId) -> Option<u32> {
         let node = chapter.node(id)?;
         let inline = matches!(
             node.role,
-            Role::Link | Role::Inline | Role::Text | Role::Break
+            Role::Link | Role::Inline | Role::Quote | Role::Text | Role::Break
         );
         if depth > 0 && !inline {
             return None;
@@ -108,7 +108,49 @@ pub fn ir_to_tokens(chapter: &Chapter, ctx: &mut ExportContext) -> TokenStream {
         ctx,
         &mut stream,
     );
-    stream
+    coalesce_style_runs(stream)
+}
+
+/// Merge back-to-back style runs that would produce identical style events.
+///
+/// Sibling spans with the same computed style (`<b>a</b><b>b</b>`, or text
+/// the optimizer split) flatten separately into adjacent
+/// `StartSpan, Text, EndSpan` runs. Emitted as is, they bloat
+/// `style_events` and can break kerning and ligatures at the seam. Runs
+/// merge only when their spans are equal: same style, link target,
+/// element id, and noteref display.
+pub(super) fn coalesce_style_runs(stream: TokenStream) -> TokenStream {
+    let mut out: Vec<KfxToken> = Vec::with_capacity(stream.len());
+    for token in stream {
+        if matches!(token, KfxToken::EndSpan)
+            && let [
+                ..,
+                KfxToken::StartSpan(prev),
+                KfxToken::Text(_),
+                KfxToken::EndSpan,
+                KfxToken::StartSpan(span),
+                KfxToken::Text(_),
+            ] = out.as_slice()
+            && prev == span
+        {
+            let Some(KfxToken::Text(text)) = out.pop() else {
+                unreachable!("matched above")
+            };
+            // Drop the previous run's EndSpan and this run's StartSpan, then
+            // extend the previous run's text; the EndSpan below closes it.
+            out.truncate(out.len() - 2);
+            if let Some(KfxToken::Text(prev_text)) = out.last_mut() {
+                prev_text.push_str(&text);
+            }
+        }
+        out.push(token);
+    }
+
+    let mut merged = TokenStream::new();
+    for token in out {
+        merged.push(token);
+    }
+    merged
 }
 
 /// Walk a node and emit tokens for export.
@@ -167,7 +209,7 @@ pub(super) fn walk_node_for_export(
     // Inline elements (Link, Inline): use the flattening algorithm.
     // This produces non-overlapping style_events where each text segment
     // carries the accumulated state from all ancestors.
-    if node.role == Role::Link || node.role == Role::Inline {
+    if matches!(node.role, Role::Link | Role::Inline | Role::Quote) {
         emit_inline_content_flat(chapter, node_id, parent_style, sch, ctx, stream);
         return;
     }
@@ -179,6 +221,14 @@ pub(super) fn walk_node_for_export(
     // text-child container (alt_text annotation only, no mathml). With no
     // font at all, math stays a plain inline text run.
     if node.role == Role::Math {
+        // Children are the source's `<epub:switch>` fallback, which the
+        // publisher prepared for readers without MathML: prefer it.
+        if node.first_child.is_some() {
+            for child in chapter.children(node_id) {
+                walk_node_for_export(chapter, child, parent_style, adjust, sch, ctx, stream);
+            }
+            return;
+        }
         if let Some(math) = chapter.math.get(&node_id) {
             let text = math.to_text();
             if !ctx.math_renders_as_container() {
@@ -283,7 +333,11 @@ pub(super) fn walk_node_for_export(
         |target| match target {
             SemanticTarget::Href => chapter.semantics.href(node_id).map(|s| s.to_string()),
             SemanticTarget::Src => chapter.semantics.src(node_id).map(|s| s.to_string()),
-            SemanticTarget::Alt => chapter.semantics.alt(node_id).map(|s| s.to_string()),
+            // An empty alt_text marks a decorative image.
+            SemanticTarget::Alt => match chapter.semantics.alt(node_id) {
+                Some(alt) => Some(alt.to_string()),
+                None => chapter.semantics.is_decorative(node_id).then(String::new),
+            },
             SemanticTarget::Id => chapter.semantics.id(node_id).map(|s| s.to_string()),
             SemanticTarget::EpubType => chapter.semantics.epub_type(node_id).map(|s| s.to_string()),
         },
@@ -322,6 +376,12 @@ pub(super) fn walk_node_for_export(
     {
         kfx_attrs.push((sym!(ListStartOffset), start.to_string()));
     }
+    // A list item's own ordinal rides on the same field.
+    if node.role == Role::ListItem
+        && let Some(value) = chapter.semantics.list_value(node_id)
+    {
+        kfx_attrs.push((sym!(ListStartOffset), value.to_string()));
+    }
 
     // Store the transformed KFX attributes for tokens_to_ion
     elem.kfx_attrs = kfx_attrs;
@@ -338,6 +398,8 @@ pub(super) fn walk_node_for_export(
     }
     if let Some(alt) = chapter.semantics.alt(node_id) {
         elem.set_semantic(SemanticTarget::Alt, alt.to_string());
+    } else if chapter.semantics.is_decorative(node_id) {
+        elem.set_semantic(SemanticTarget::Alt, String::new());
     }
     if let Some(id) = chapter.semantics.id(node_id) {
         elem.set_semantic(SemanticTarget::Id, id.to_string());
@@ -365,19 +427,23 @@ pub(super) fn walk_node_for_export(
     // (no math font): the run wrapper must enclose it. As a KVG container it
     // is a sibling element, closing any open run — like an inline image.
     let math_is_flow = !ctx.math_renders_as_container();
-    let is_inline_flow = |role: Role| {
-        matches!(role, Role::Text | Role::Break | Role::Link | Role::Inline)
-            || (role == Role::Math && math_is_flow)
+    // Math with an `<epub:switch>` fallback exports the fallback (an
+    // image), which is never flow.
+    let is_inline_flow = |n: &Node| {
+        matches!(
+            n.role,
+            Role::Text | Role::Break | Role::Link | Role::Inline | Role::Quote
+        ) || (n.role == Role::Math && math_is_flow && n.first_child.is_none())
     };
     let children: Vec<NodeId> = chapter.children(node_id).collect();
     let has_own_text = !node.text.is_empty() && !chapter.text(node.text).is_empty();
     let has_flow = has_own_text
         || children
             .iter()
-            .any(|&c| chapter.node(c).is_some_and(|n| is_inline_flow(n.role)));
+            .any(|&c| chapter.node(c).is_some_and(&is_inline_flow));
     let has_elements = children
         .iter()
-        .any(|&c| chapter.node(c).is_some_and(|n| !is_inline_flow(n.role)));
+        .any(|&c| chapter.node(c).is_some_and(|n| !is_inline_flow(n)));
 
     if !(has_flow && has_elements) {
         // Uniform content: emit text and children directly (single-ref or
@@ -416,7 +482,7 @@ pub(super) fn walk_node_for_export(
             stream.push(KfxToken::Text(chapter.text(node.text).to_string()));
         }
         for child in children {
-            let child_is_flow = chapter.node(child).is_some_and(|n| is_inline_flow(n.role));
+            let child_is_flow = chapter.node(child).is_some_and(&is_inline_flow);
             if child_is_flow {
                 open_run(stream, &mut run_open);
             } else {
@@ -495,7 +561,7 @@ pub(super) fn flatten_inline_content(
             .map(|s| s.to_string())
             .or(state.link_to),
         // Styles: innermost wins (child overrides parent)
-        style: if node.role == Role::Inline || node.role == Role::Link {
+        style: if matches!(node.role, Role::Inline | Role::Link | Role::Quote) {
             Some(node.style)
         } else {
             state.style
@@ -552,6 +618,22 @@ pub(super) fn flatten_inline_content(
                 }
             }
         }
+        // QUOTE: KFX has no quotation element, so the marks become text
+        // around the quoted runs, styled like the quotation itself.
+        Role::Quote => {
+            let (open, close) = chapter.quote_marks(node_id);
+            segments.push(FlatSegment {
+                text: open.to_string(),
+                state: effective_state.clone(),
+            });
+            for child_id in chapter.children(node_id) {
+                flatten_inline_content(chapter, child_id, effective_state.clone(), segments);
+            }
+            segments.push(FlatSegment {
+                text: close.to_string(),
+                state: effective_state,
+            });
+        }
         // CONTAINERS (Link, Inline, etc.): Recurse with accumulated state
         _ => {
             let children: Vec<_> = chapter.children(node_id).collect();
@@ -741,15 +823,15 @@ pub(super) fn emit_definition_list(
 
             {
                 let math_is_flow = !ctx.math_renders_as_container();
-                let is_inline_flow = |role: Role| {
-                    matches!(role, Role::Text | Role::Break | Role::Link | Role::Inline)
-                        || (role == Role::Math && math_is_flow)
+                let is_inline_flow = |n: &Node| {
+                    matches!(
+                        n.role,
+                        Role::Text | Role::Break | Role::Link | Role::Inline | Role::Quote
+                    ) || (n.role == Role::Math && math_is_flow && n.first_child.is_none())
                 };
                 let mut run_open = false;
                 for dt_child in chapter.children(child_id) {
-                    let child_is_flow = chapter
-                        .node(dt_child)
-                        .is_some_and(|n| is_inline_flow(n.role));
+                    let child_is_flow = chapter.node(dt_child).is_some_and(&is_inline_flow);
                     if child_is_flow && !run_open {
                         let mut run = ElementStart::new(Role::Text);
                         run.style_symbol = Some(dt_style);
@@ -775,15 +857,15 @@ pub(super) fn emit_definition_list(
             // (ref + children) — the shape the reference model forbids.
             if let Some((dd_id, dd_style_id)) = dd_info {
                 let math_is_flow = !ctx.math_renders_as_container();
-                let is_inline_flow = |role: Role| {
-                    matches!(role, Role::Text | Role::Break | Role::Link | Role::Inline)
-                        || (role == Role::Math && math_is_flow)
+                let is_inline_flow = |n: &Node| {
+                    matches!(
+                        n.role,
+                        Role::Text | Role::Break | Role::Link | Role::Inline | Role::Quote
+                    ) || (n.role == Role::Math && math_is_flow && n.first_child.is_none())
                 };
                 let mut run_open = false;
                 for dd_child in chapter.children(dd_id) {
-                    let child_is_flow = chapter
-                        .node(dd_child)
-                        .is_some_and(|n| is_inline_flow(n.role));
+                    let child_is_flow = chapter.node(dd_child).is_some_and(&is_inline_flow);
                     if child_is_flow && !run_open {
                         let mut run = ElementStart::new(Role::Text);
                         run.style_symbol = Some(wrapper_style);
@@ -477,7 +477,11 @@ where
                     } else if *field_id == sym!(TableRowSpan) {
                         chapter.semantics.set_row_span(node_id, n);
                     } else if *field_id == sym!(ListStartOffset) {
-                        chapter.semantics.set_list_start(node_id, n);
+                        if elem.role == Role::ListItem {
+                            chapter.semantics.set_list_value(node_id, n);
+                        } else {
+                            chapter.semantics.set_list_start(node_id, n);
+                        }
                     }
                 }
 
@@ -573,9 +577,49 @@ where
         }
     }
 
+    restore_languages(&mut chapter);
     chapter
 }
 
+/// KFX stores `lang` only as a style property, written where the language
+/// changes. Restore it as the element's `lang` attribute there, and inherit
+/// it into descendant styles as the HTML cascade does.
+fn restore_languages(chapter: &mut Chapter) {
+    let mut inherited: HashMap<NodeId, String> = HashMap::new();
+    let nodes: Vec<NodeId> = chapter.iter_dfs().collect();
+    for id in nodes {
+        let Some(node) = chapter.node(id) else {
+            continue;
+        };
+        if node.role == Role::Text {
+            continue;
+        }
+        let (style_id, parent) = (node.style, node.parent);
+        let own = chapter
+            .styles
+            .get(style_id)
+            .and_then(|s| s.language.clone());
+        let from_parent = parent.and_then(|p| inherited.get(&p)).cloned();
+        let lang = match (own, from_parent) {
+            (Some(own), _) => {
+                chapter.semantics.set_lang(id, &own);
+                own
+            }
+            (None, Some(lang)) => {
+                let mut style = chapter.styles.get(style_id).cloned().unwrap_or_default();
+                style.language = Some(lang.clone());
+                let style_id = chapter.styles.intern(style);
+                if let Some(node) = chapter.node_mut(id) {
+                    node.style = style_id;
+                }
+                lang
+            }
+            (None, None) => continue,
+        };
+        inherited.insert(id, lang);
+    }
+}
+
 /// Apply semantic attributes to a node from a generic map.
 ///
 /// This is the **only place** that knows about SemanticTarget → IR mapping.
@@ -591,6 +635,9 @@ pub(super) fn apply_semantics_to_node(
             SemanticTarget::Href => {
                 chapter.semantics.set_href(node_id, value);
             }
+            SemanticTarget::Alt if value.is_empty() => {
+                chapter.semantics.set_decorative(node_id, true);
+            }
             SemanticTarget::Alt => chapter.semantics.set_alt(node_id, value),
             SemanticTarget::Id => chapter.semantics.set_id(node_id, value),
             SemanticTarget::EpubType => chapter.semantics.set_epub_type(node_id, value),
@@ -71,14 +71,22 @@ pub fn tokens_to_ion(tokens: &TokenStream, ctx: &mut ExportContext) -> IonValue
                 } else {
                     // === NORMAL ELEMENT PATH ===
                     let (fields, container_id) = start_element_fields(elem, ctx, false);
-                    stack.push(IonBuilder::with_fields(fields, container_id));
+                    let mut builder = IonBuilder::with_fields(fields, container_id);
+                    builder.is_flow_run = elem.role == Role::Text;
+                    stack.push(builder);
                 }
             }
             KfxToken::EndElement => {
                 if let Some(completed) = stack.pop() {
                     let is_inner = completed.is_inner_wrapper_text;
                     if let Some(parent) = stack.last_mut() {
-                        parent.add_child(completed.build(ctx));
+                        if completed.is_flow_run {
+                            for run in completed.build_flow_run(ctx) {
+                                parent.add_child(run);
+                            }
+                        } else {
+                            parent.add_child(completed.build(ctx));
+                        }
                     }
 
                     // If this was an inner wrapper text element, we need to also
@@ -95,7 +103,7 @@ pub fn tokens_to_ion(tokens: &TokenStream, ctx: &mut ExportContext) -> IonValue
                 // Append text to the current element's accumulated content
                 // This ensures all text within an element is concatenated
                 if let Some(current) = stack.last_mut() {
-                    current.append_text(text);
+                    current.append_text(&ctx.clean_text(text));
                 }
             }
             KfxToken::StartSpan(span) => {
@@ -511,6 +519,10 @@ pub(super) struct IonBuilder {
     /// For inner wrapper text elements, stores the outer container's ID.
     /// Anchors inside wrapped elements should use this ID for correct TOC navigation.
     pub(super) outer_container_id: Option<u64>,
+    /// True for a text run wrapping inline flow inside mixed content (see
+    /// the reference content model in export.rs). Its oversized text splits
+    /// into sibling runs rather than nested ones.
+    pub(super) is_flow_run: bool,
 }
 
 impl IonBuilder {
@@ -524,6 +536,7 @@ impl IonBuilder {
             container_id: None,
             is_inner_wrapper_text: false,
             outer_container_id: None,
+            is_flow_run: false,
         }
     }
 
@@ -537,6 +550,7 @@ impl IonBuilder {
             container_id: Some(container_id),
             is_inner_wrapper_text: false,
             outer_container_id: None,
+            is_flow_run: false,
         }
     }
 
@@ -597,11 +611,98 @@ impl IonBuilder {
         self.style_events.push(IonValue::Struct(event_fields));
     }
 
+    /// Move accumulated text that exceeds the content chunk bound into child
+    /// text runs, each with its own id and content ref in a chunk of its
+    /// own. Style events and anchor offsets are rebased onto the run that
+    /// holds their text; an event straddling a break is cut in two.
+    ///
+    /// With `first_id`, the first run takes that id instead of a fresh one.
+    fn split_oversized_text(&mut self, ctx: &mut ExportContext, mut first_id: Option<u64>) {
+        let limit = ctx.content_chunk_limit();
+        let text = std::mem::take(&mut self.accumulated_text);
+        let events = std::mem::take(&mut self.style_events);
+        self.accumulated_char_count = 0;
+
+        let style = self
+            .fields
+            .iter()
+            .find(|(k, _)| *k == sym!(Style))
+            .map(|(_, v)| v.clone())
+            .unwrap_or_else(|| {
+                ctx.default_style_used = true;
+                IonValue::Symbol(ctx.default_style_symbol)
+            });
+
+        let mut rest = text.as_str();
+        let mut run_start = 0;
+        while !rest.is_empty() {
+            let (piece, tail) = rest.split_at(run_break(rest, limit));
+            let run_end = run_start + piece.chars().count();
+
+            let run_id = first_id.take().unwrap_or_else(|| {
+                let id = ctx.fragment_ids.next_id();
+                ctx.record_content_id(id);
+                id
+            });
+            ctx.record_content_length(run_id, run_end - run_start);
+            if let Some(container_id) = self.container_id {
+                // Positions at the very end of the text belong to the last run.
+                let end = if tail.is_empty() { usize::MAX } else { run_end };
+                ctx.anchor_registry
+                    .rebase_offsets(container_id, run_start..end, run_id);
+            }
+
+            ctx.finish_content_chunk();
+            let (content_name, content_idx) = ctx.append_text(piece);
+            let mut fields = vec![
+                (sym!(Id), IonValue::Int(run_id as i64)),
+                (sym!(Style), style.clone()),
+                (sym!(Type), IonValue::Symbol(KfxSymbol::Text as u64)),
+                (
+                    sym!(Content),
+                    IonValue::Struct(vec![
+                        (sym!(Name), IonValue::Symbol(content_name)),
+                        (sym!(Index), IonValue::Int(content_idx as i64)),
+                    ]),
+                ),
+            ];
+            let run_events: Vec<IonValue> = events
+                .iter()
+                .filter_map(|event| clip_style_event(event, run_start, run_end))
+                .collect();
+            if !run_events.is_empty() {
+                fields.push((sym!(StyleEvents), IonValue::List(run_events)));
+            }
+            self.children.push(IonValue::Struct(fields));
+
+            rest = tail;
+            run_start = run_end;
+        }
+    }
+
+    /// Finalize a text run of mixed content. Text too long for one content
+    /// fragment becomes several sibling runs for the parent, the first
+    /// keeping this run's id; otherwise this is [`build`](Self::build).
+    pub(super) fn build_flow_run(mut self, ctx: &mut ExportContext) -> Vec<IonValue> {
+        if self.children.is_empty() && self.accumulated_text.len() > ctx.content_chunk_limit() {
+            let own_id = self.container_id;
+            self.split_oversized_text(ctx, own_id);
+            return self.children;
+        }
+        vec![self.build(ctx)]
+    }
+
     /// Finalize and build the Ion struct, creating content reference if text was accumulated.
     pub(super) fn build(mut self, ctx: &mut ExportContext) -> IonValue {
         // KFX storylines are flat lists of elements, not nested structs
         // Each element is a struct with type, content reference, and possibly nested content_list
         if !self.fields.is_empty() {
+            // Text too long for one content fragment becomes sibling runs,
+            // the same shape as text split around an inline image.
+            if self.children.is_empty() && self.accumulated_text.len() > ctx.content_chunk_limit() {
+                self.split_oversized_text(ctx, None);
+            }
+
             // Record text length for this content ID (used by location_map)
             // Must use char count, not byte count, since location_map divides by characters
             if let Some(container_id) = self.container_id {
@@ -689,3 +790,71 @@ impl IonBuilder {
         }
     }
 }
+
+/// Byte length of the next run of oversized text: up to the last sentence
+/// end within `limit` bytes, else the last word break, else the last whole
+/// character — never inside a UTF-8 sequence, and never zero.
+fn run_break(text: &str, limit: usize) -> usize {
+    if text.len() <= limit {
+        return text.len();
+    }
+    let mut window = limit;
+    while !text.is_char_boundary(window) {
+        window -= 1;
+    }
+    if window == 0 {
+        return text.chars().next().map_or(0, char::len_utf8);
+    }
+    let head = &text[..window];
+    let after = |(i, c): (usize, char)| i + c.len_utf8();
+
+    // A sentence break only wins when it keeps the run reasonably full.
+    let sentence_end = head
+        .char_indices()
+        .rev()
+        .find(|&(i, c)| c.is_whitespace() && head[..i].ends_with(['.', '!', '?']))
+        .map(after)
+        .filter(|&cut| cut > window / 2);
+    sentence_end
+        .or_else(|| {
+            head.char_indices()
+                .rev()
+                .find(|&(_, c)| c.is_whitespace())
+                .map(after)
+        })
+        .unwrap_or(window)
+}
+
+/// Clip a style event to the run covering characters `start..end`, with
+/// its offset made relative to the run. `None` if they don't overlap.
+fn clip_style_event(event: &IonValue, start: usize, end: usize) -> Option<IonValue> {
+    let IonValue::Struct(fields) = event else {
+        return None;
+    };
+    let int_field = |id: u64| {
+        fields.iter().find_map(|(k, v)| match v {
+            IonValue::Int(n) if *k == id => Some(*n as usize),
+            _ => None,
+        })
+    };
+    let offset = int_field(sym!(Offset))?;
+    let length = int_field(sym!(Length))?;
+    let from = offset.max(start);
+    let to = (offset + length).min(end);
+    if from >= to {
+        return None;
+    }
+    let fields = fields
+        .iter()
+        .map(|(k, v)| {
+            if *k == sym!(Offset) {
+                (*k, IonValue::Int((from - start) as i64))
+            } else if *k == sym!(Length) {
+                (*k, IonValue::Int((to - from) as i64))
+            } else {
+                (*k, v.clone())
+            }
+        })
+        .collect();
+    Some(IonValue::Struct(fields))
+}
@@ -1138,6 +1138,194 @@ mod content_model {
         ));
     }
 
+    /// Back-to-back spans with the same style become one style event; a
+    /// differently styled neighbour keeps its own.
+    #[test]
+    fn identical_adjacent_runs_share_one_style_event() {
+        use crate::style::{ComputedStyle, FontStyle, FontWeight};
+
+        let mut chapter = Chapter::new();
+        let bold = chapter.styles.intern(ComputedStyle {
+            font_weight: FontWeight::BOLD,
+            ..Default::default()
+        });
+        let italic = chapter.styles.intern(ComputedStyle {
+            font_style: FontStyle::Italic,
+            ..Default::default()
+        });
+        let para = chapter.alloc_node(Node::new(Role::Paragraph));
+        chapter.append_child(chapter.root(), para);
+        for (style, text) in [(bold, "Hello, "), (bold, "world"), (italic, "!")] {
+            let mut span = Node::new(Role::Inline);
+            span.style = style;
+            let span = chapter.alloc_node(span);
+            chapter.append_child(para, span);
+            text_child(&mut chapter, span, text);
+        }
+
+        let (ion, _ctx) = export(&chapter);
+        let IonValue::List(elems) = ion else { panic!() };
+        let para_ion = as_struct(&elems[0]);
+        let IonValue::List(events) = field(para_ion, KfxSymbol::StyleEvents).expect("events")
+        else {
+            panic!()
+        };
+        let ranges: Vec<(i64, i64)> = events
+            .iter()
+            .map(|ev| {
+                let ev = as_struct(ev);
+                let (Some(IonValue::Int(offset)), Some(IonValue::Int(length))) =
+                    (field(ev, KfxSymbol::Offset), field(ev, KfxSymbol::Length))
+                else {
+                    panic!("event without a range: {ev:?}")
+                };
+                (*offset, *length)
+            })
+            .collect();
+        assert_eq!(ranges, [(0, 12), (12, 1)], "{events:#?}");
+        let styles: Vec<u64> = events
+            .iter()
+            .map(|ev| match field(as_struct(ev), KfxSymbol::Style) {
+                Some(IonValue::Symbol(style)) => *style,
+                other => panic!("event style {other:?}"),
+            })
+            .collect();
+        assert_ne!(styles[0], styles[1]);
+    }
+
+    /// A paragraph too long for one content fragment splits into sibling
+    /// runs at sentence breaks, each in a fragment within the size bound,
+    /// and a bold span straddling a break is cut into run-relative events.
+    #[test]
+    fn oversized_paragraph_splits_into_bounded_runs() {
+        use crate::kfx::context::MAX_CONTENT_CHUNK_BYTES;
+        use crate::style::{ComputedStyle, FontWeight};
+
+        let sentence = "Le café était fermé. ";
+        let mut chapter = Chapter::new();
+        let bold = chapter.styles.intern(ComputedStyle {
+            font_weight: FontWeight::BOLD,
+            ..Default::default()
+        });
+        let para = chapter.alloc_node(Node::new(Role::Paragraph));
+        chapter.append_child(chapter.root(), para);
+        text_child(&mut chapter, para, &sentence.repeat(400));
+        let mut span = Node::new(Role::Inline);
+        span.style = bold;
+        let span = chapter.alloc_node(span);
+        chapter.append_child(para, span);
+        text_child(&mut chapter, span, &sentence.repeat(300));
+        text_child(&mut chapter, para, &sentence.repeat(200));
+        let expected = sentence.repeat(900);
+        assert!(expected.len() > 20_000);
+
+        let (ion, mut ctx) = export(&chapter);
+        let IonValue::List(elems) = ion else { panic!() };
+        let para_ion = as_struct(&elems[0]);
+        assert!(field(para_ion, KfxSymbol::Content).is_none());
+        let IonValue::List(runs) = field(para_ion, KfxSymbol::ContentList).expect("runs") else {
+            panic!()
+        };
+        assert!(
+            runs.len() >= 3,
+            "expected at least 3 runs, got {}",
+            runs.len()
+        );
+
+        let mut ids = std::collections::HashSet::new();
+        let mut bold_chars = 0;
+        for run in runs {
+            let run = as_struct(run);
+            assert!(field(run, KfxSymbol::Content).is_some(), "run has own ref");
+            let Some(IonValue::Int(id)) = field(run, KfxSymbol::Id) else {
+                panic!("run without id")
+            };
+            assert!(ids.insert(*id), "run ids are distinct");
+            if let Some(IonValue::List(events)) = field(run, KfxSymbol::StyleEvents) {
+                for ev in events {
+                    let Some(IonValue::Int(length)) = field(as_struct(ev), KfxSymbol::Length)
+                    else {
+                        panic!()
+                    };
+                    bold_chars += length;
+                }
+            }
+        }
+        assert_eq!(bold_chars, 300 * sentence.chars().count() as i64);
+
+        let chunks = ctx.take_content_chunks();
+        assert!(chunks.len() >= 3);
+        let mut text = String::new();
+        for (_, segments) in &chunks {
+            let size: usize = segments.iter().map(String::len).sum();
+            assert!(size <= MAX_CONTENT_CHUNK_BYTES, "chunk of {size} bytes");
+            for segment in segments {
+                assert!(segment.ends_with(". "), "breaks at a sentence end");
+                text.push_str(segment);
+            }
+        }
+        assert_eq!(text, expected);
+    }
+
+    /// Oversized text on either side of an inline image splits into sibling
+    /// runs of the paragraph, never runs nested inside a run.
+    #[test]
+    fn oversized_text_around_inline_image_splits_into_sibling_runs() {
+        use crate::kfx::context::MAX_CONTENT_CHUNK_BYTES;
+
+        let sentence = "Le café était fermé. ";
+        let mut chapter = Chapter::new();
+        let para = chapter.alloc_node(Node::new(Role::Paragraph));
+        chapter.append_child(chapter.root(), para);
+        text_child(&mut chapter, para, &sentence.repeat(500));
+        image_child(&mut chapter, para, "img.png");
+        text_child(&mut chapter, para, &sentence.repeat(450));
+        let expected = sentence.repeat(950);
+        assert!(expected.len() > 20_000);
+
+        let (ion, mut ctx) = export(&chapter);
+        let IonValue::List(elems) = ion else { panic!() };
+        let para_ion = as_struct(&elems[0]);
+        assert!(field(para_ion, KfxSymbol::Content).is_none());
+        let IonValue::List(kids) = field(para_ion, KfxSymbol::ContentList).expect("children")
+        else {
+            panic!()
+        };
+
+        let image_at = kids
+            .iter()
+            .position(|kid| field(as_struct(kid), KfxSymbol::ResourceName).is_some())
+            .expect("image child");
+        assert!(image_at >= 2, "text before the image splits: {image_at}");
+        assert!(kids.len() - image_at > 2, "text after the image splits");
+        let mut ids = std::collections::HashSet::new();
+        for (i, kid) in kids.iter().enumerate() {
+            let kid = as_struct(kid);
+            let Some(IonValue::Int(id)) = field(kid, KfxSymbol::Id) else {
+                panic!("child without id")
+            };
+            assert!(ids.insert(*id), "child ids are distinct");
+            if i != image_at {
+                assert!(field(kid, KfxSymbol::Content).is_some(), "run has own ref");
+                assert!(
+                    field(kid, KfxSymbol::ContentList).is_none(),
+                    "runs are flat"
+                );
+            }
+        }
+
+        let chunks = ctx.take_content_chunks();
+        let mut text = String::new();
+        for (_, segments) in &chunks {
+            let size: usize = segments.iter().map(String::len).sum();
+            assert!(size <= MAX_CONTENT_CHUNK_BYTES, "chunk of {size} bytes");
+            for segment in segments {
+                text.push_str(segment);
+            }
+        }
+        assert_eq!(text, expected);
+    }
+
     /// A text-typed element containing a block child (a list) becomes a
     /// container: Previewer never emits text-typed parents with block
     /// children ($269 -> $276 does not occur in reference output).
@@ -526,6 +526,38 @@ mod tests {
         assert_eq!(builder.build().get(KfxSymbol::Underline), None);
     }
 
+    #[test]
+    fn test_vertical_align_length_emits_baseline_shift() {
+        use crate::kfx::style_schema::StyleSchema;
+
+        // `vertical-align: 20%` is a fifth of the 1.2em normal line box.
+        let mut ir = crate::style::ComputedStyle::default();
+        ir.baseline_shift = crate::style::Length::Percent(20.0);
+        let mut builder = StyleBuilder::new(StyleSchema::standard());
+        builder.ingest_ir_style(&ir);
+        let shifted = builder.build();
+        match shifted.get(KfxSymbol::BaselineShift) {
+            Some(KfxValue::Dimensioned { value, unit }) => {
+                assert!((value - 0.24).abs() < 1e-9, "{value}");
+                assert_eq!(*unit, KfxSymbol::Em);
+            }
+            other => panic!("expected dimensioned baseline_shift, got {other:?}"),
+        }
+        assert_eq!(shifted.get(KfxSymbol::BaselineStyle), None);
+
+        // The keyword form stays a baseline_style symbol.
+        let mut ir = crate::style::ComputedStyle::default();
+        ir.vertical_align = crate::style::VerticalAlign::Super;
+        let mut builder = StyleBuilder::new(StyleSchema::standard());
+        builder.ingest_ir_style(&ir);
+        let raised = builder.build();
+        assert_eq!(
+            raised.get(KfxSymbol::BaselineStyle),
+            Some(&KfxValue::Symbol(KfxSymbol::Superscript))
+        );
+        assert_eq!(raised.get(KfxSymbol::BaselineShift), None);
+    }
+
     #[test]
     fn test_font_size_is_always_relative() {
         use crate::kfx::style_schema::StyleSchema;
@@ -192,6 +192,8 @@ pub enum IrField {
     Color,
     BackgroundColor,
     VerticalAlign,
+    /// Length form of vertical-align
+    BaselineShift,
     TextDecorationUnderline,
     TextDecorationStrikethrough,
     // Phase 1: Text properties
@@ -241,6 +243,8 @@ pub enum IrField {
     // Phase 8: Amazon properties
     Language,
     Visibility,
+    /// Clipping of a fixed-size box (`overflow: hidden`)
+    Overflow,
     /// Maps CSS box-sizing to KFX sizing_bounds
     SizingBounds,
     // Phase 9: Additional layout properties
@@ -326,7 +330,6 @@ impl StyleSchema {
     }
 
     /// Look up the first rule by IR key (convenience for single-rule properties).
-    #[cfg(test)]
     pub fn get_first(&self, ir_key: &str) -> Option<&StylePropertyRule> {
         self.get(ir_key).next()
     }
@@ -593,6 +596,15 @@ impl StyleSchema {
             ]),
         });
 
+        // baseline_shift: a length-valued vertical-align (fine-tuned
+        // sub/superscripts), pre-converted to em by extract_ir_field
+        schema.register(StylePropertyRule {
+            ir_key: "baseline-shift",
+            ir_field: Some(IrField::BaselineShift),
+            kfx_symbol: KfxSymbol::BaselineShift,
+            transform: ValueTransform::PreserveUnit,
+        });
+
         // vertical-align → yj.vertical_align (for table cell alignment)
         // Multiple rules per key are now supported.
         schema.register(StylePropertyRule {
@@ -780,6 +792,10 @@ impl StyleSchema {
                 ("column".into(), KfxValue::Symbol(KfxSymbol::Column)),
                 // Legacy CSS2 page-break-* values
                 ("page".into(), KfxValue::Symbol(KfxSymbol::Always)),
+                // Kindle has no page-side breaks: a recto/verso break is a
+                // plain page break.
+                ("recto".into(), KfxValue::Symbol(KfxSymbol::Always)),
+                ("verso".into(), KfxValue::Symbol(KfxSymbol::Always)),
             ]),
         });
 
@@ -793,6 +809,8 @@ impl StyleSchema {
                 ("avoid".into(), KfxValue::Symbol(KfxSymbol::Avoid)),
                 ("column".into(), KfxValue::Symbol(KfxSymbol::Column)),
                 ("page".into(), KfxValue::Symbol(KfxSymbol::Always)),
+                ("recto".into(), KfxValue::Symbol(KfxSymbol::Always)),
+                ("verso".into(), KfxValue::Symbol(KfxSymbol::Always)),
             ]),
         });
 
@@ -816,6 +834,8 @@ impl StyleSchema {
                 ("auto".into(), KfxValue::Symbol(KfxSymbol::Auto)),
                 ("always".into(), KfxValue::Symbol(KfxSymbol::Always)),
                 ("avoid".into(), KfxValue::Symbol(KfxSymbol::Avoid)),
+                ("recto".into(), KfxValue::Symbol(KfxSymbol::Always)),
+                ("verso".into(), KfxValue::Symbol(KfxSymbol::Always)),
             ]),
         });
 
@@ -827,6 +847,8 @@ impl StyleSchema {
                 ("auto".into(), KfxValue::Symbol(KfxSymbol::Auto)),
                 ("always".into(), KfxValue::Symbol(KfxSymbol::Always)),
                 ("avoid".into(), KfxValue::Symbol(KfxSymbol::Avoid)),
+                ("recto".into(), KfxValue::Symbol(KfxSymbol::Always)),
+                ("verso".into(), KfxValue::Symbol(KfxSymbol::Always)),
             ]),
         });
 
@@ -1058,6 +1080,19 @@ impl StyleSchema {
             ]),
         });
 
+        // Overflow clipping. Like visibility, an Ion boolean (true = clip
+        // content to the box).
+        schema.register(StylePropertyRule {
+            ir_key: "overflow",
+            ir_field: Some(IrField::Overflow),
+            kfx_symbol: KfxSymbol::Overflow,
+            transform: ValueTransform::Map(vec![
+                ("hidden".into(), KfxValue::Bool(true)),
+                ("clip".into(), KfxValue::Bool(true)),
+                ("visible".into(), KfxValue::Bool(false)),
+            ]),
+        });
+
         // Box-sizing → sizing_bounds
         // Amazon auto-adds content-box when width/height is present
         schema.register(StylePropertyRule {
@@ -1520,6 +1555,39 @@ fn raw_line_height_em(s: &ir_style::ComputedStyle) -> Option<f64> {
     }
 }
 
+/// `s` with a line-height *length* reset to `normal` when it falls below
+/// the 1.2em base line box of a font larger than the body text (`body_abs`).
+///
+/// Per CSS, `em`/`%` line-heights inherit as the computed length, so
+/// `h1 { font-size: 2em }` under `body { line-height: 1.5em }` gets 0.75em
+/// of its own font. That value hits the 0.99lh floor and is then scaled
+/// again by the book's leading normalization (`line_scale`), leaving
+/// headings tighter than unset leading. Unitless factors scale with the
+/// font and are left alone, as is body-size and smaller text. Only
+/// inherited lengths are refitted: a line-height declared on the element
+/// itself is what the author asked for.
+pub(crate) fn fit_display_leading(
+    s: &ir_style::ComputedStyle,
+    body_abs: f32,
+) -> std::borrow::Cow<'_, ir_style::ComputedStyle> {
+    let length = matches!(
+        s.line_height,
+        ir_style::Length::Rem(_) | ir_style::Length::Px(_)
+    );
+    if length
+        && s.line_height_inherited
+        && s.font_size_abs.0 > body_abs * 1.02
+        && raw_line_height_em(s).is_some_and(|em| em < 1.2)
+    {
+        let mut fitted = s.clone();
+        fitted.line_height = ir_style::Length::Auto;
+        fitted.line_height_inherited = false;
+        std::borrow::Cow::Owned(fitted)
+    } else {
+        std::borrow::Cow::Borrowed(s)
+    }
+}
+
 /// Vertical spacing (margin/padding top+bottom) in lh units of the
 /// element's own line box, so block spacing scales with both the font and
 /// the device line-spacing setting — reference output divides by the
@@ -1651,11 +1719,17 @@ pub fn extract_ir_field(
                 None
             }
         }
+        // KFX has no image markers: an image-only bullet (`list-style:
+        // none url(...)`) falls back to a disc rather than vanishing.
         IrField::ListStyleType => {
-            if ir_style.display == ir_style::Display::ListItem {
-                shared("list-style-type")
-            } else {
+            if ir_style.display != ir_style::Display::ListItem {
                 None
+            } else if ir_style.list_style_image.is_some()
+                && ir_style.list_style_type == ir_style::ListStyleType::None
+            {
+                Some("disc".to_string())
+            } else {
+                shared("list-style-type")
             }
         }
         // KNOWN DISCREPANCY: KFX uses the raw family string; to_css quotes
@@ -1678,8 +1752,10 @@ pub fn extract_ir_field(
             }
         }
         // BoxAlign: horizontal centering from `margin: <v> auto`. Unset
-        // margins compute to `Px(0)`, so `Length::Auto` here is always the
-        // author's explicit `auto` — the CSS centering idiom.
+        // margins compute to `Px(0)`, so `Length::Auto` here is either the
+        // author's explicit `auto` (the CSS centering idiom) or the auto
+        // margins the transform gives a block or lone image in a
+        // `text-align: center` parent.
         IrField::BoxAlign => {
             let auto = ir_style::Length::Auto;
             if ir_style.margin_left == auto && ir_style.margin_right == auto {
@@ -1724,12 +1800,17 @@ pub fn extract_ir_field(
         // floored at 0.99 like reference output — never below the readable
         // baseline, and never exactly 1.0, which means "normal". Inherited:
         // emitted only when it differs from the parent; an explicit
-        // `line-height: normal` reset emits 1lh.
+        // `line-height: normal` reset emits 1lh. lh is font-relative, so an
+        // inherited fixed length (px, or a computed em/% value) still emits
+        // when this element's font size changes its lh value.
         IrField::LineHeight => {
-            if ir_style.line_height == parent.line_height {
+            let lh = emitted_line_height_lh(ir_style);
+            if ir_style.line_height == parent.line_height
+                && (lh - emitted_line_height_lh(parent)).abs() < 1e-6
+            {
                 None
             } else {
-                Some(fmt_dim(emitted_line_height_lh(ir_style), "lh"))
+                Some(fmt_dim(lh, "lh"))
             }
         }
         IrField::MarginTop => vertical_spacing_lh(ir_style.margin_top, ir_style),
@@ -1768,7 +1849,16 @@ pub fn extract_ir_field(
         IrField::FontWeight => inherited("font-weight"),
         IrField::FontStyle => inherited("font-style"),
         IrField::FontVariant => inherited("font-variant"),
-        IrField::TextAlign => inherited("text-align"),
+        // start/end resolve against the element's direction; KFX only
+        // understands physical sides for them.
+        IrField::TextAlign => {
+            let align = ir_style.text_align.resolve(ir_style.direction);
+            if align == parent.text_align.resolve(parent.direction) {
+                None
+            } else {
+                Some(align.as_str().to_string())
+            }
+        }
         IrField::Color => {
             if ir_style.color == parent.color {
                 None
@@ -1786,6 +1876,20 @@ pub fn extract_ir_field(
         }
         IrField::BackgroundColor => shared("background-color"),
         IrField::VerticalAlign => shared("vertical-align"),
+        // Em of the element's own font; `%` is of its line height.
+        IrField::BaselineShift => {
+            let abs = ir_style.font_size_abs.0 as f64;
+            let em = match ir_style.baseline_shift {
+                ir_style::Length::Auto => return None,
+                ir_style::Length::Em(x) => x as f64,
+                ir_style::Length::Rem(x) => x as f64 / abs,
+                ir_style::Length::Px(x) => x as f64 / PX_PER_EM / abs,
+                ir_style::Length::Percent(p) => {
+                    p as f64 / 100.0 * raw_line_height_em(ir_style).unwrap_or(1.2)
+                }
+            };
+            (em != 0.0).then(|| fmt_dim(em, "em"))
+        }
         IrField::TextTransform => inherited("text-transform"),
         IrField::Hyphens => inherited("hyphens"),
         IrField::WhiteSpace => shared("white-space"),
@@ -1827,6 +1931,18 @@ pub fn extract_ir_field(
         IrField::BorderRadiusBottomLeft => shared("border-bottom-left-radius"),
         IrField::BorderRadiusBottomRight => shared("border-bottom-right-radius"),
         IrField::Visibility => inherited("visibility"),
+        // Clipping only means something on a box with a fixed size; on an
+        // auto-sized box nothing overflows (and scroll/auto have no paged
+        // equivalent).
+        IrField::Overflow => {
+            let clips = matches!(
+                ir_style.overflow,
+                ir_style::Overflow::Hidden | ir_style::Overflow::Clip
+            );
+            let fixed = ir_style.width != ir_style::Length::Auto
+                || ir_style.height != ir_style::Length::Auto;
+            (clips && fixed).then(|| "hidden".to_string())
+        }
         IrField::Clear => shared("clear"),
         IrField::Orphans => shared("orphans"),
         IrField::Widows => shared("widows"),
@@ -2081,6 +2197,11 @@ pub fn apply_ir_field(ir_style: &mut ir_style::ComputedStyle, field: IrField, cs
                 ir_style.vertical_align = va;
             }
         }
+        IrField::BaselineShift => {
+            if let Some(len) = parse_css_length_to_ir(css_value) {
+                ir_style.baseline_shift = len;
+            }
+        }
         IrField::TextDecorationUnderline => {
             // A concrete decoration style (dotted, dashed, ...) coming back
             // from KFX `underline` also means the underline flag is on.
@@ -2319,6 +2440,12 @@ pub fn apply_ir_field(ir_style: &mut ir_style::ComputedStyle, field: IrField, cs
         IrField::Language => {
             ir_style.language = Some(css_value.to_string());
         }
+        IrField::Overflow => {
+            ir_style.overflow = match css_value {
+                "hidden" | "clip" => ir_style::Overflow::Hidden,
+                _ => ir_style::Overflow::Visible,
+            };
+        }
         IrField::Visibility => {
             ir_style.visibility = match css_value {
                 "hidden" | "collapse" => ir_style::Visibility::Hidden,
@@ -2498,12 +2625,10 @@ pub fn import_kfx_style(
 /// Check if a style should be treated as block-like for KFX export.
 ///
 /// KFX doesn't have native `display: inline-block`. Elements with this
-/// display type should be emitted as block containers instead of inline spans.
+/// display type should be emitted as block containers instead of inline spans,
+/// as should flex, grid and table containers (laid out as plain blocks).
 pub fn is_block_display(style: &ir_style::ComputedStyle) -> bool {
-    matches!(
-        style.display,
-        ir_style::Display::Block | ir_style::Display::InlineBlock
-    )
+    style.display.lays_out_as_block() || style.display == ir_style::Display::InlineBlock
 }
 
 // ============================================================================
@@ -2686,6 +2811,7 @@ mod tests {
             IrField::Color,
             IrField::BackgroundColor,
             IrField::VerticalAlign,
+            IrField::BaselineShift,
             IrField::TextDecorationUnderline,
             IrField::TextDecorationStrikethrough,
             IrField::LetterSpacing,
@@ -2726,6 +2852,7 @@ mod tests {
             IrField::FontFamily,
             IrField::Language,
             IrField::Visibility,
+            IrField::Overflow,
             IrField::SizingBounds,
             IrField::Clear,
             IrField::MinWidth,
@@ -2921,6 +3048,41 @@ mod tests {
         assert!(matches!(result, Some(KfxValue::Integer(4278223103))));
     }
 
+    #[test]
+    fn test_fit_display_leading() {
+        use ir_style::{AbsFontSize, ComputedStyle, Length};
+
+        // 1.5rem inherited by a 2rem heading: below its base line box.
+        let mut heading = ComputedStyle::default();
+        heading.font_size_abs = AbsFontSize(2.0);
+        heading.line_height = Length::Rem(1.5);
+        heading.line_height_inherited = true;
+        assert_eq!(fit_display_leading(&heading, 1.0).line_height, Length::Auto);
+        // Declared on the heading itself, the same length is kept.
+        heading.line_height_inherited = false;
+        assert_eq!(
+            fit_display_leading(&heading, 1.0).line_height,
+            Length::Rem(1.5)
+        );
+        heading.line_height_inherited = true;
+        // Body-size text keeps its length, and factors always scale.
+        assert_eq!(
+            fit_display_leading(&heading, 2.0).line_height,
+            Length::Rem(1.5)
+        );
+        heading.line_height = Length::Em(0.75);
+        assert_eq!(
+            fit_display_leading(&heading, 1.0).line_height,
+            Length::Em(0.75)
+        );
+        // Lengths at or above the base line box are kept.
+        heading.line_height = Length::Px(48.0);
+        assert_eq!(
+            fit_display_leading(&heading, 1.0).line_height,
+            Length::Px(48.0)
+        );
+    }
+
     #[test]
     fn test_baseline_style_field() {
         let schema = StyleSchema::standard();
@@ -3151,6 +3313,10 @@ mod tests {
             rule.transform.apply("auto"),
             Some(KfxValue::Symbol(KfxSymbol::Auto))
         ));
+        assert!(matches!(
+            rule.transform.apply("recto"),
+            Some(KfxValue::Symbol(KfxSymbol::Always))
+        ));
 
         let rule = schema.get_first("break-inside").unwrap();
         assert!(matches!(
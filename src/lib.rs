@@ -39,17 +39,24 @@
 #![warn(missing_docs)]
 
 mod book;
+mod diff;
 pub(crate) mod dom;
+mod edit;
 pub mod error;
 pub mod export;
 pub mod import;
 pub(crate) mod io;
+pub(crate) mod ir;
 pub(crate) mod markdown;
 pub mod math;
 pub mod model;
 pub mod optimize;
 mod resolved;
+#[cfg(feature = "serde")]
+mod snapshot;
 pub mod style;
+mod toc;
+mod validate;
 
 pub(crate) mod epub;
 /// KFX format internals (Ion codec, container layout, symbol tables).
@@ -83,8 +90,11 @@ pub use dom::compile_html;
 
 // Primary exports from other modules
 pub use export::{
-    Azw3Config, Azw3Exporter, EpubConfig, EpubExporter, Exporter, KfxExporter, MarkdownConfig,
-    MarkdownExporter,
+    Azw3Config, Azw3Exporter, ChapterNaming, EpubConfig, EpubExporter, EpubVersion, Exporter,
+    IrExporter, KfxConfig, KfxExporter, MarkdownConfig, MarkdownExporter, ProgressEvent,
 };
 pub use import::{ChapterId, Importer, SpineEntry};
-pub use io::{ByteSource, FileSource};
+#[cfg(all(feature = "http", not(target_arch = "wasm32")))]
+pub use io::HttpRangeSource;
+pub use io::{ByteSource, FileSource, RangeFetcher};
+pub use validate::{BrokenLink, Diagnostic, Severity};
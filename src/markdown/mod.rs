@@ -6,6 +6,7 @@
 //! - `escape`: Pure string transformation utilities for Markdown escaping
 //! - [`slugify`]: GitHub-style slug generation for heading anchors
 //! - `render`: Core IR → Markdown rendering
+//! - `wrap`: Hard-wrapping rendered Markdown to a fixed line width
 //!
 //! The export layer handles I/O orchestration, calling these pure functions
 //! to generate content.
@@ -29,6 +30,8 @@
 mod escape;
 mod render;
 mod slugify;
+mod wrap;
 
 pub use render::render_chapter;
-pub use slugify::build_heading_slugs;
+pub use slugify::{build_heading_slugs, collect_heading_text, slugify};
+pub use wrap::wrap_markdown;
@@ -8,7 +8,6 @@ use std::collections::HashMap;
 
 use crate::import::ChapterId;
 use crate::model::{AnchorTarget, Chapter, GlobalNodeId, NodeId, ResolvedLinks, Role};
-use crate::style::Display;
 use crate::util::strip_ebook_chars;
 
 use super::escape::escape_markdown_at;
@@ -321,8 +320,12 @@ impl<'a> RenderContext<'a> {
                 self.ensure_line_started();
 
                 // Get bullet/number from parent list
+                let value = self.chapter.semantics.list_value(id);
                 let bullet = if let Some(list_ctx) = self.list_stack.last_mut() {
-                    list_ctx.counter += 1;
+                    list_ctx.counter = match value {
+                        Some(value) if list_ctx.is_ordered => value as usize,
+                        _ => list_ctx.counter + 1,
+                    };
                     if list_ctx.is_ordered {
                         format!("{}. ", list_ctx.counter)
                     } else {
@@ -402,7 +405,11 @@ impl<'a> RenderContext<'a> {
 
             Role::Image => {
                 self.start_block();
-                let alt = self.chapter.semantics.alt(id).unwrap_or("image");
+                let alt = match self.chapter.semantics.alt(id) {
+                    Some(alt) => alt,
+                    None if self.chapter.semantics.is_decorative(id) => "",
+                    None => "image",
+                };
                 let src = self.chapter.semantics.src(id).unwrap_or("");
                 // The alt goes inside `[...]`, so escape `]`/`[`/backslash or
                 // it terminates the label early; the src goes inside `(...)`.
@@ -425,6 +432,17 @@ impl<'a> RenderContext<'a> {
             }
 
             Role::Table => {
+                // Captions have no GFM table syntax; they go above the table
+                // as their own paragraph.
+                for child_id in self.chapter.children(id) {
+                    if self
+                        .chapter
+                        .node(child_id)
+                        .is_some_and(|child| child.role == Role::Caption)
+                    {
+                        self.walk_node(child_id);
+                    }
+                }
                 self.start_block();
                 // The optimizer (normalize_table_structure) wraps rows in
                 // TableHead/TableBody, so descend one level into section
@@ -493,15 +511,37 @@ impl<'a> RenderContext<'a> {
 
             Role::Figure => {
                 self.start_block();
-                self.walk_children(id);
+                if self.depth <= crate::util::MAX_TREE_DEPTH {
+                    self.depth += 1;
+                    let mut after_image = false;
+                    for child_id in self.chapter.children(id) {
+                        let is_caption = self
+                            .chapter
+                            .node(child_id)
+                            .is_some_and(|child| child.role == Role::Caption);
+                        // A caption right after its image goes on the next
+                        // line of the same paragraph, keeping the pair
+                        // together.
+                        if after_image && is_caption && self.pending_newline {
+                            self.pending_newline = false;
+                            if !self.at_line_start {
+                                self.write_newline();
+                            }
+                        }
+                        self.walk_node(child_id);
+                        after_image = self.is_lone_image(child_id);
+                    }
+                    self.depth -= 1;
+                }
                 self.end_block(role);
             }
 
             Role::Caption => {
                 self.start_block();
-                self.output.push('*');
+                // `_` so emphasis inside the caption (`*...*`) nests.
+                self.output.push('_');
                 self.walk_children(id);
-                self.output.push('*');
+                self.output.push('_');
                 self.end_block(role);
             }
 
@@ -529,19 +569,28 @@ impl<'a> RenderContext<'a> {
                 self.end_block(role);
             }
 
-            Role::Inline => {
+            Role::Inline | Role::Quote => {
                 let style = self.chapter.styles.get(node.style);
                 let is_bold = style.map(|s| s.is_bold()).unwrap_or(false);
                 let is_italic = style.map(|s| s.is_italic()).unwrap_or(false);
                 let is_code = style.map(|s| s.is_monospace()).unwrap_or(false);
-                let is_block = node.style.0 != 0
-                    && style.map(|s| s.display == Display::Block).unwrap_or(false);
+                let is_block =
+                    node.style.0 != 0 && style.is_some_and(|s| s.display.lays_out_as_block());
 
                 // Handle block-display inlines (e.g., verse lines)
                 if is_block && self.has_line_content {
                     self.write_hard_break();
                 }
 
+                // `<q>` carries no marks of its own; spell them out in the
+                // content language's style, outside any emphasis. A code span
+                // collects its text marks included.
+                let marks = (role == Role::Quote && !is_code).then(|| self.chapter.quote_marks(id));
+                if let Some((open, _)) = marks {
+                    self.ensure_line_started();
+                    self.output.push_str(open);
+                }
+
                 if is_code {
                     self.ensure_line_started();
                     let content = self.collect_text(id);
@@ -576,6 +625,10 @@ impl<'a> RenderContext<'a> {
                         self.output.push_str("**");
                     }
                 }
+
+                if let Some((_, close)) = marks {
+                    self.output.push_str(close);
+                }
             }
 
             Role::DefinitionList => {
@@ -588,11 +641,16 @@ impl<'a> RenderContext<'a> {
             }
 
             Role::DefinitionTerm => {
+                // Several terms sharing a description go on their own lines.
+                if self.last_block_role == Some(Role::DefinitionTerm) && !self.at_line_start {
+                    self.write_newline();
+                }
                 self.start_block();
                 self.output.push_str("**");
                 self.walk_children(id);
                 self.output.push_str("**");
                 self.pending_newline = false;
+                self.last_block_role = Some(role);
             }
 
             Role::DefinitionDescription => {
@@ -688,6 +746,30 @@ impl<'a> RenderContext<'a> {
             .collect()
     }
 
+    /// Whether `id` renders as just one image: an image node, or a chain
+    /// of wrappers each holding only the next (whitespace aside).
+    fn is_lone_image(&self, mut id: NodeId) -> bool {
+        loop {
+            let Some(node) = self.chapter.node(id) else {
+                return false;
+            };
+            match node.role {
+                Role::Image => return true,
+                Role::Text => return false,
+                _ => {}
+            }
+            let mut content = self.chapter.children(id).filter(|&child| {
+                self.chapter.node(child).is_some_and(|n| {
+                    n.role != Role::Text || !self.chapter.text(n.text).trim().is_empty()
+                })
+            });
+            match (content.next(), content.next()) {
+                (Some(only), None) => id = only,
+                _ => return false,
+            }
+        }
+    }
+
     fn walk_children(&mut self, id: NodeId) {
         // Bound recursion depth: a hostile chapter can nest arbitrarily deep.
         // All descent flows through here, so guarding this one site suffices.
@@ -783,9 +865,16 @@ impl<'a> RenderContext<'a> {
             }
         }
 
+        let marks = (node.role == Role::Quote && !verbatim).then(|| self.chapter.quote_marks(id));
+        if let Some((open, _)) = marks {
+            result.push_str(open);
+        }
         for child_id in self.chapter.children(id) {
             self.collect_text_recursive(child_id, result, verbatim);
         }
+        if let Some((_, close)) = marks {
+            result.push_str(close);
+        }
     }
 }
 
@@ -1071,6 +1160,30 @@ mod tests {
         assert!(out.contains("| 1 | 2 |"), "body row: {out}");
     }
 
+    #[test]
+    fn table_caption_renders_above_rows() {
+        let mut chapter = Chapter::new();
+        let table = chapter.alloc_node(Node::new(Role::Table));
+        chapter.append_child(NodeId::ROOT, table);
+        let caption = chapter.alloc_node(Node::new(Role::Caption));
+        chapter.append_child(table, caption);
+        let t = chapter.append_text("Prices");
+        let tn = chapter.alloc_node(Node::text(t));
+        chapter.append_child(caption, tn);
+        let row = chapter.alloc_node(Node::new(Role::TableRow));
+        chapter.append_child(table, row);
+        let cell = chapter.alloc_node(Node::new(Role::TableCell));
+        chapter.append_child(row, cell);
+        let t = chapter.append_text("1");
+        let tn = chapter.alloc_node(Node::text(t));
+        chapter.append_child(cell, tn);
+
+        let resolved = ResolvedLinks::default();
+        let heading_slugs = HashMap::new();
+        let out = render_chapter(&chapter, ChapterId(0), &resolved, &heading_slugs, 0).content;
+        assert!(out.contains("_Prices_\n\n| 1 |"), "{out}");
+    }
+
     #[test]
     fn table_with_head_body_wrappers_renders_rows() {
         // The optimizer (normalize_table_structure) wraps rows in
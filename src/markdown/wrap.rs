@@ -0,0 +1,167 @@
+//! Hard-wrapping rendered Markdown to a fixed width.
+//!
+//! The renderer emits each paragraph as one line. [`wrap_markdown`] reflows
+//! those lines at word boundaries for terminals and fixed-width viewers,
+//! repeating blockquote markers and indenting list continuations so the
+//! wrapped document parses to the same blocks. Headings, table rows and
+//! fenced code are left alone; words longer than the width are never split.
+
+/// Wrap every paragraph line of `markdown` to at most `width` characters.
+/// A width of 0 disables wrapping.
+pub fn wrap_markdown(markdown: &str, width: usize) -> String {
+    if width == 0 {
+        return markdown.to_string();
+    }
+
+    let mut out = String::with_capacity(markdown.len() + markdown.len() / width);
+    let mut fence: Option<&str> = None;
+    for line in markdown.split_inclusive('\n') {
+        let body = line.strip_suffix('\n').unwrap_or(line);
+        let newline = &line[body.len()..];
+        let (prefix, rest) = split_block_prefix(body);
+
+        if let Some(marker) = fence {
+            if rest.starts_with(marker) {
+                fence = None;
+            }
+            out.push_str(line);
+            continue;
+        }
+        if let Some(marker) = ["```", "~~~"].into_iter().find(|m| rest.starts_with(m)) {
+            fence = Some(marker);
+            out.push_str(line);
+            continue;
+        }
+        if rest.starts_with('#') || rest.starts_with('|') || body.chars().count() <= width {
+            out.push_str(line);
+            continue;
+        }
+
+        let continuation = continuation_prefix(prefix);
+        wrap_line(&mut out, prefix, &continuation, rest, width);
+        out.push_str(newline);
+    }
+    out
+}
+
+/// Split a line into its block prefix (indentation, `>` markers and a list
+/// marker) and the inline content after it.
+fn split_block_prefix(line: &str) -> (&str, &str) {
+    let mut rest = line;
+    loop {
+        let trimmed = rest.trim_start_matches(' ');
+        if let Some(after) = trimmed.strip_prefix('>') {
+            rest = after.strip_prefix(' ').unwrap_or(after);
+        } else if let Some(after) = list_marker_len(trimmed).map(|n| &trimmed[n..]) {
+            rest = after;
+        } else {
+            rest = trimmed;
+            break;
+        }
+    }
+    line.split_at(line.len() - rest.len())
+}
+
+/// Length of a leading `- `, `* `, `+ `, or `1. ` list marker.
+fn list_marker_len(text: &str) -> Option<usize> {
+    if ["- ", "* ", "+ "].iter().any(|m| text.starts_with(m)) {
+        return Some(2);
+    }
+    let digits = text.bytes().take_while(u8::is_ascii_digit).count();
+    (digits > 0 && text[digits..].starts_with(". ")).then_some(digits + 2)
+}
+
+/// The prefix for wrapped lines: `>` markers repeat, list markers become
+/// spaces so the text stays inside the item.
+fn continuation_prefix(prefix: &str) -> String {
+    let mut out = String::with_capacity(prefix.len());
+    let mut rest = prefix;
+    while !rest.is_empty() {
+        let trimmed = rest.trim_start_matches(' ');
+        out.push_str(&rest[..rest.len() - trimmed.len()]);
+        if let Some(after) = trimmed.strip_prefix('>') {
+            out.push('>');
+            rest = after;
+        } else if let Some(n) = list_marker_len(trimmed) {
+            out.extend(std::iter::repeat_n(' ', n));
+            rest = &trimmed[n..];
+        } else {
+            break;
+        }
+    }
+    out
+}
+
+/// Write `text` as lines of at most `width` characters, the first starting
+/// with `first` and the rest with `continuation`.
+fn wrap_line(out: &mut String, first: &str, continuation: &str, text: &str, width: usize) {
+    out.push_str(first);
+    let mut column = first.chars().count();
+    let mut line_empty = true;
+    for word in text.split(' ').filter(|w| !w.is_empty()) {
+        let len = word.chars().count();
+        // A word that would open a new block at the start of a line stays
+        // on the current one, even past the width.
+        if !line_empty && column + 1 + len > width && !starts_block(word) {
+            out.push('\n');
+            out.push_str(continuation);
+            column = continuation.chars().count();
+            line_empty = true;
+        }
+        if !line_empty {
+            out.push(' ');
+            column += 1;
+        }
+        out.push_str(word);
+        column += len;
+        line_empty = false;
+    }
+}
+
+/// Whether `word` at the start of a line would be read as block syntax.
+fn starts_block(word: &str) -> bool {
+    word.starts_with(['#', '>', '|', '='])
+        || matches!(word, "-" | "*" | "+")
+        || word.starts_with("```")
+        || word.starts_with("~~~")
+        || list_marker_len(&format!("{word} ")).is_some_and(|n| n > 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_at_word_boundaries() {
+        let text = "The quick brown fox jumps over the lazy dog and keeps running far away.\n";
+        let wrapped = wrap_markdown(text, 20);
+        assert_eq!(
+            wrapped,
+            "The quick brown fox\njumps over the lazy\ndog and keeps\nrunning far away.\n"
+        );
+    }
+
+    #[test]
+    fn quotes_and_list_items_keep_their_prefix() {
+        let text = "> one two three four five six\n\n- alpha beta gamma delta epsilon\n";
+        assert_eq!(
+            wrap_markdown(text, 16),
+            "> one two three\n> four five six\n\n- alpha beta\n  gamma delta\n  epsilon\n"
+        );
+    }
+
+    #[test]
+    fn headings_tables_and_code_are_untouched() {
+        let text = "# A very long heading that goes on\n\n| a long | table row |\n\n```\nlong code line here\n```\n";
+        assert_eq!(wrap_markdown(text, 10), text);
+    }
+
+    #[test]
+    fn block_markers_never_start_a_wrapped_line() {
+        assert_eq!(wrap_markdown("aaaa bbbb - cc\n", 9), "aaaa bbbb -\ncc\n");
+        assert_eq!(
+            wrap_markdown("aaaa bbbb 1999. cc\n", 9),
+            "aaaa bbbb 1999.\ncc\n"
+        );
+    }
+}
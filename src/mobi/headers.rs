@@ -255,6 +255,19 @@ pub struct ExthHeader {
 }
 
 impl ExthHeader {
+    /// The ISBN (EXTH 104) and ASIN (EXTH 113) records as identifiers.
+    pub fn identifiers(&self) -> Vec<crate::model::Identifier> {
+        [("ISBN", &self.isbn), ("ASIN", &self.asin)]
+            .into_iter()
+            .filter_map(|(scheme, value)| {
+                Some(crate::model::Identifier {
+                    scheme: Some(scheme.to_string()),
+                    value: value.clone().filter(|v| !v.is_empty())?,
+                })
+            })
+            .collect()
+    }
+
     pub fn parse(data: &[u8], encoding: Encoding) -> io::Result<Self> {
         if data.len() < 12 {
             return Err(io::Error::new(
@@ -346,6 +359,8 @@ impl ExthHeader {
                     }
                 }
                 503 => exth.title = Some(decode(content).trim().to_string()),
+                // The CDE content key; EXTH 113 holds the ASIN when both exist.
+                504 if exth.asin.is_none() => exth.asin = Some(decode(content).trim().to_string()),
                 524 => exth.language = Some(decode(content).trim().to_string()),
                 _ => {}
             }
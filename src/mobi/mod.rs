@@ -21,5 +21,6 @@ pub mod filepos;
 pub use parser::{
     Compression, Encoding, ExthHeader, HuffCdicReader, MobiFormat, MobiHeader, NULL_INDEX, PdbInfo,
     TocNode, build_toc_from_ncx, decode_font_record, detect_font_type, detect_image_type,
-    is_metadata_record, parse_exth, parse_fdst, parse_ncx_index, read_index, strip_trailing_data,
+    is_metadata_record, merge_resc_metadata, parse_exth, parse_fdst, parse_ncx_index,
+    parse_resc_metadata, read_index, strip_trailing_data,
 };
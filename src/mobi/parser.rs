@@ -241,23 +241,14 @@ pub fn strip_trailing_data(record: &[u8], flags: u16) -> &[u8] {
     &record[..end]
 }
 
-/// Detect image type from magic bytes.
+/// Detect image type from magic bytes: the shared image sniffer, plus BMP,
+/// which old MOBI files still carry.
 pub fn detect_image_type(data: &[u8]) -> Option<&'static str> {
     if data.len() < 4 {
         return None;
     }
 
-    if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
-        Some("image/jpeg")
-    } else if data.starts_with(b"\x89PNG") {
-        Some("image/png")
-    } else if data.starts_with(b"GIF8") {
-        Some("image/gif")
-    } else if data.starts_with(b"BM") {
-        Some("image/bmp")
-    } else {
-        None
-    }
+    crate::util::sniff_image_mime(data).or_else(|| data.starts_with(b"BM").then_some("image/bmp"))
 }
 
 /// Detect font type from magic bytes / structure.
@@ -411,6 +402,52 @@ pub fn is_metadata_record(data: &[u8]) -> bool {
     ) || data.starts_with(b"BOUNDARY")
 }
 
+/// Metadata embedded in a KF8 `RESC` record.
+///
+/// Kindlegen copies the source OPF's `<package>` (metadata and spine) into
+/// the `RESC` record, so it can carry what EXTH has no record for, such as
+/// the series (`calibre:series` or `belongs-to-collection`). Returns `None`
+/// for anything that isn't a `RESC` record with a parseable package.
+pub fn parse_resc_metadata(data: &[u8]) -> Option<crate::model::Metadata> {
+    // "RESC", then version, type and a reserved word before the XML.
+    let xml = data.strip_prefix(b"RESC")?.get(12..)?;
+    let xml = String::from_utf8_lossy(xml);
+    let start = xml.find("<?xml").or_else(|| xml.find("<package"))?;
+    let xml = xml[start..].trim_end_matches('\0');
+    crate::epub::parse_opf(xml).ok().map(|opf| opf.metadata)
+}
+
+/// Fill `metadata` (from EXTH) with what a `RESC` record adds: the series,
+/// the author list when the record names more authors, and any field EXTH
+/// left empty.
+pub fn merge_resc_metadata(metadata: &mut crate::model::Metadata, resc: crate::model::Metadata) {
+    if resc.authors.len() > metadata.authors.len() {
+        metadata.authors = resc.authors;
+    }
+    if metadata.collection.is_none() {
+        metadata.collection = resc.collection;
+    }
+    if metadata.contributors.is_empty() {
+        metadata.contributors = resc.contributors;
+    }
+    if metadata.subjects.is_empty() {
+        metadata.subjects = resc.subjects;
+    }
+    if metadata.language.is_empty() {
+        metadata.language = resc.language;
+    }
+    for (field, value) in [
+        (&mut metadata.publisher, resc.publisher),
+        (&mut metadata.description, resc.description),
+        (&mut metadata.date, resc.date),
+        (&mut metadata.rights, resc.rights),
+    ] {
+        if field.is_none() {
+            *field = value;
+        }
+    }
+}
+
 /// A simple TOC node for intermediate representation.
 /// Importers convert this to `crate::model::TocEntry`.
 #[derive(Debug, Clone)]
@@ -506,6 +543,31 @@ where
 mod tests {
     use super::*;
 
+    #[test]
+    fn resc_metadata_fills_gaps_and_richer_author_list() {
+        let mut exth = crate::model::Metadata {
+            authors: vec!["Terry Pratchett".into()],
+            publisher: Some("Gollancz".into()),
+            ..Default::default()
+        };
+        let mut record = b"RESC".to_vec();
+        record.extend_from_slice(&[0; 12]);
+        record.extend_from_slice(
+            br#"<package><metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:creator>Terry Pratchett</dc:creator><dc:creator>Neil Gaiman</dc:creator><dc:publisher>Corgi</dc:publisher><meta name="calibre:series" content="Good Omens"/></metadata></package>"#,
+        );
+        record.push(0);
+        let resc = parse_resc_metadata(&record).expect("resc metadata");
+        merge_resc_metadata(&mut exth, resc);
+
+        assert_eq!(exth.authors, ["Terry Pratchett", "Neil Gaiman"]);
+        assert_eq!(exth.publisher.as_deref(), Some("Gollancz"));
+        assert_eq!(
+            exth.collection.map(|c| c.name).as_deref(),
+            Some("Good Omens")
+        );
+        assert!(parse_resc_metadata(b"FLIS\0\0\0\0").is_none());
+    }
+
     #[test]
     fn parse_fdst_ignores_bogus_section_count() {
         // Header claims 0xFFFF_FFFF sections but the record is tiny. The old
@@ -724,6 +786,12 @@ mod tests {
         // GIF
         assert_eq!(detect_image_type(b"GIF89a"), Some("image/gif"));
 
+        // WebP
+        assert_eq!(
+            detect_image_type(b"RIFF\x24\x00\x00\x00WEBPVP8 "),
+            Some("image/webp")
+        );
+
         // BMP
         assert_eq!(detect_image_type(b"BM\x00\x00"), Some("image/bmp"));
 
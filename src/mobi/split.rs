@@ -1,6 +1,7 @@
 //! Splitting of monolithic MOBI HTML into per-chapter documents.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
 
 /// Result of splitting MOBI HTML into chapters.
 pub(crate) struct ChapterSplit {
@@ -76,6 +77,10 @@ pub(crate) fn split_mobi_html(html: &[u8], ncx_positions: Option<&[u32]>) -> Cha
     // Neutralize bare filename links (OEB source references that don't exist in EPUB)
     neutralize_bare_filename_links(&mut chapters);
 
+    // Ids repeated across the new chapters (merged source documents) get
+    // renamed so every link target is unambiguous
+    dedupe_ids(&mut chapters, &chapter_paths);
+
     // Ensure at least one chapter
     if chapters.is_empty() {
         chapters.push(html.to_vec());
@@ -136,6 +141,7 @@ pub(crate) fn split_mobi_html_ncx_only(html: &[u8], ncx_positions: &[u32]) -> Ch
 
     rewrite_cross_chapter_links(&mut chapters, &filepos_to_chapter, &chapter_paths);
     neutralize_bare_filename_links(&mut chapters);
+    dedupe_ids(&mut chapters, &chapter_paths);
 
     if chapters.is_empty() {
         chapters.push(html.to_vec());
@@ -516,6 +522,113 @@ fn is_bare_filename_link(href: &[u8]) -> bool {
 // Helpers
 // ============================================================================
 
+/// Make element ids unique across the chapter set.
+///
+/// A monolithic MOBI is often several source documents concatenated, each
+/// with its own `note1`; once split, those ids collide across chapters. An
+/// id already claimed by an earlier chapter is renamed to `{id}-{chapter}`
+/// (suffixed further if that is taken too). The rename is applied to every
+/// occurrence in the chapter, to its `#id` links, and to links from other
+/// chapters naming it by path (`chapter_N.xhtml#id`).
+fn dedupe_ids(chapters: &mut [Vec<u8>], chapter_paths: &[String]) {
+    let ids: Vec<Vec<String>> = chapters
+        .iter()
+        .map(|chapter| {
+            let mut ids = Vec::new();
+            scan_attributes(chapter, |name, value| {
+                if chapter[name].eq_ignore_ascii_case(b"id") && !value.is_empty() {
+                    ids.push(String::from_utf8_lossy(&chapter[value]).into_owned());
+                }
+            });
+            ids
+        })
+        .collect();
+
+    let mut taken: HashSet<String> = ids.iter().flatten().cloned().collect();
+    let mut owner: HashMap<&str, usize> = HashMap::new();
+    let mut renames: Vec<HashMap<String, String>> = vec![HashMap::new(); chapters.len()];
+    for (chapter_idx, chapter_ids) in ids.iter().enumerate() {
+        for id in chapter_ids {
+            let first = *owner.entry(id).or_insert(chapter_idx);
+            if first == chapter_idx || renames[chapter_idx].contains_key(id) {
+                continue;
+            }
+            let mut renamed = format!("{id}-{chapter_idx}");
+            let mut n = 2;
+            while taken.contains(&renamed) {
+                renamed = format!("{id}-{chapter_idx}-{n}");
+                n += 1;
+            }
+            taken.insert(renamed.clone());
+            renames[chapter_idx].insert(id.clone(), renamed);
+        }
+    }
+    if renames.iter().all(HashMap::is_empty) {
+        return;
+    }
+
+    for (chapter_idx, chapter) in chapters.iter_mut().enumerate() {
+        let mut edits = Vec::new();
+        scan_attributes(chapter, |name, value| {
+            let attr = &chapter[name];
+            let value_str = String::from_utf8_lossy(&chapter[value.clone()]);
+            let replacement = if attr.eq_ignore_ascii_case(b"id") {
+                renames[chapter_idx].get(&*value_str).cloned()
+            } else if attr.eq_ignore_ascii_case(b"href") {
+                value_str.split_once('#').and_then(|(path, fragment)| {
+                    let target = if path.is_empty() {
+                        chapter_idx
+                    } else {
+                        chapter_paths.iter().position(|p| p == path)?
+                    };
+                    renames[target]
+                        .get(fragment)
+                        .map(|renamed| format!("{path}#{renamed}"))
+                })
+            } else {
+                None
+            };
+            if let Some(replacement) = replacement {
+                edits.push((value, replacement));
+            }
+        });
+        if edits.is_empty() {
+            continue;
+        }
+        let mut output = Vec::with_capacity(chapter.len());
+        let mut copied = 0;
+        for (value, replacement) in edits {
+            output.extend_from_slice(&chapter[copied..value.start]);
+            output.extend_from_slice(replacement.as_bytes());
+            copied = value.end;
+        }
+        output.extend_from_slice(&chapter[copied..]);
+        *chapter = output;
+    }
+}
+
+/// Call `f(name, value)` with the byte ranges of every double-quoted
+/// attribute in `html`: a `name="value"` whose name follows whitespace, as
+/// inside a tag.
+fn scan_attributes(html: &[u8], mut f: impl FnMut(Range<usize>, Range<usize>)) {
+    let mut pos = 0;
+    while let Some(rel) = memchr::memmem::find(&html[pos..], b"=\"") {
+        let eq = pos + rel;
+        let value_start = eq + 2;
+        let Some(value_len) = memchr::memchr(b'"', &html[value_start..]) else {
+            return;
+        };
+        let name_start = html[..eq]
+            .iter()
+            .rposition(|&b| !(b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b':')))
+            .map_or(0, |p| p + 1);
+        if name_start > 0 && name_start < eq && html[name_start - 1].is_ascii_whitespace() {
+            f(name_start..eq, value_start..value_start + value_len);
+        }
+        pos = value_start + value_len + 1;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -894,6 +1007,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_ids_repeated_across_chapters_are_renamed() {
+        // Two merged source documents, each with its own note1.
+        let html = br##"<html><head></head><body>
+<p>One<a href="#note1">1</a></p><p id="note1">First note</p>
+<mbp:pagebreak/>
+<p>Two<a href="#note1">1</a> and <a href="#note1">again</a></p><p id="note1">Second note</p>
+</body></html>"##;
+
+        let split = split_mobi_html(html, None);
+        assert_eq!(split.chapters.len(), 2);
+        let ch0 = String::from_utf8_lossy(&split.chapters[0]);
+        let ch1 = String::from_utf8_lossy(&split.chapters[1]);
+
+        // The first chapter keeps its id; the second is renamed everywhere.
+        assert!(ch0.contains(r##"href="#note1""##), "{ch0}");
+        assert!(ch0.contains(r#"id="note1""#), "{ch0}");
+        assert!(ch1.contains(r#"id="note1-1""#), "{ch1}");
+        assert_eq!(ch1.matches(r##"href="#note1-1""##).count(), 2, "{ch1}");
+        assert!(!ch1.contains(r##""#note1""##), "{ch1}");
+    }
+
+    #[test]
+    fn test_dedupe_ids_rewrites_path_links_to_renamed_ids() {
+        // "n-1" is taken, so the renamed id gets a further suffix.
+        let mut chapters = vec![
+            br##"<p id="n">a</p><a href="chapter_1.xhtml#n">b</a>"##.to_vec(),
+            br#"<p id="n">c</p><p id="n-1">taken</p>"#.to_vec(),
+        ];
+        let paths = vec!["chapter_0.xhtml".to_string(), "chapter_1.xhtml".to_string()];
+
+        dedupe_ids(&mut chapters, &paths);
+
+        let ch0 = String::from_utf8_lossy(&chapters[0]);
+        let ch1 = String::from_utf8_lossy(&chapters[1]);
+        assert!(ch0.contains(r##"href="chapter_1.xhtml#n-1-2""##), "{ch0}");
+        assert!(ch1.contains(r#"<p id="n-1-2">c</p>"#), "{ch1}");
+        assert!(ch1.contains(r#"<p id="n-1">taken</p>"#), "{ch1}");
+    }
+
     // ====================================================================
     // OEB filename link neutralization tests
     // ====================================================================
@@ -176,6 +176,164 @@ impl Chapter {
             next: Some(NodeId::ROOT),
         }
     }
+
+    /// Opening and closing quotation marks for a [`Role::Quote`] node: the
+    /// marks of its content language (English curly quotes when unknown),
+    /// switching to the secondary pair inside another quotation.
+    pub fn quote_marks(&self, id: NodeId) -> (&'static str, &'static str) {
+        let lang = self
+            .node(id)
+            .and_then(|node| self.styles.get(node.style))
+            .and_then(|style| style.language.as_deref())
+            .unwrap_or("en");
+        let mut nested = false;
+        let mut ancestor = self.node(id).and_then(|node| node.parent);
+        while let Some(parent) = ancestor.and_then(|p| self.node(p)) {
+            if parent.role == Role::Quote {
+                nested = !nested;
+            }
+            ancestor = parent.parent;
+        }
+        quote_marks_for(lang, nested)
+    }
+
+    /// The chapter's text without markup, for search indexing and similar.
+    ///
+    /// Each block (paragraph, heading, list item, ...) becomes a line of its
+    /// own and a line break starts a new line; inline runs are joined and
+    /// their whitespace collapsed to single spaces. Code blocks and other
+    /// `white-space: pre` content keep their whitespace. Quotations get the
+    /// marks of their language and math its plain-text linearization;
+    /// images contribute nothing.
+    pub fn plain_text(&self) -> String {
+        let mut text = PlainText::default();
+        self.write_plain_text(NodeId::ROOT, false, 0, &mut text);
+        text.end_line();
+        text.out
+    }
+
+    fn write_plain_text(&self, id: NodeId, preserve: bool, depth: usize, text: &mut PlainText) {
+        use crate::dom::optimize::is_inline_role;
+        use crate::style::WhiteSpace;
+
+        let Some(node) = self.node(id) else {
+            return;
+        };
+        if depth > crate::util::MAX_TREE_DEPTH {
+            return;
+        }
+        let preserve = match node.role {
+            Role::CodeBlock => true,
+            _ if node.style == crate::style::StyleId::DEFAULT => preserve,
+            _ => self.styles.get(node.style).map_or(preserve, |style| {
+                matches!(
+                    style.white_space,
+                    WhiteSpace::Pre | WhiteSpace::PreWrap | WhiteSpace::PreLine
+                )
+            }),
+        };
+        match node.role {
+            Role::Text if preserve => text.push_preserved(self.text(node.text)),
+            Role::Text => text.push_collapsed(self.text(node.text)),
+            Role::Break => text.line_break(),
+            Role::Math => {
+                let display = self.math.get(&id).is_some_and(|math| math.display);
+                let linear = self.math.get(&id).map(|math| math.to_text());
+                if display {
+                    text.end_line();
+                }
+                text.push_collapsed(linear.as_deref().unwrap_or_default());
+                if display {
+                    text.end_line();
+                }
+            }
+            Role::Quote => {
+                let (open, close) = self.quote_marks(id);
+                text.push_collapsed(open);
+                for child in self.children(id) {
+                    self.write_plain_text(child, preserve, depth + 1, text);
+                }
+                text.push_collapsed(close);
+            }
+            role => {
+                let block = !is_inline_role(role);
+                if block {
+                    text.end_line();
+                }
+                for child in self.children(id) {
+                    self.write_plain_text(child, preserve, depth + 1, text);
+                }
+                if block {
+                    text.end_line();
+                }
+            }
+        }
+    }
+}
+
+/// Accumulator for [`Chapter::plain_text`]: finished lines in `out`, the
+/// current block's text in `line`.
+#[derive(Default)]
+struct PlainText {
+    out: String,
+    line: String,
+    /// Collapsed whitespace waiting for the next word on the line.
+    space: bool,
+}
+
+impl PlainText {
+    fn push_collapsed(&mut self, text: &str) {
+        for c in text.chars() {
+            if c.is_whitespace() {
+                self.space = !self.line.is_empty() && !self.line.ends_with('\n');
+            } else {
+                if std::mem::take(&mut self.space) {
+                    self.line.push(' ');
+                }
+                self.line.push(c);
+            }
+        }
+    }
+
+    fn push_preserved(&mut self, text: &str) {
+        if std::mem::take(&mut self.space) {
+            self.line.push(' ');
+        }
+        self.line.push_str(text);
+    }
+
+    fn line_break(&mut self) {
+        self.space = false;
+        self.line.push('\n');
+    }
+
+    /// Finish the current block's line; blank blocks add nothing.
+    fn end_line(&mut self) {
+        self.space = false;
+        let line = self.line.trim_end();
+        let line = line.trim_start_matches(['\n', '\r']);
+        if !line.trim().is_empty() {
+            self.out.push_str(line);
+            self.out.push('\n');
+        }
+        self.line.clear();
+    }
+}
+
+/// Quotation marks for a BCP 47 language tag, primary or nested pair.
+fn quote_marks_for(lang: &str, nested: bool) -> (&'static str, &'static str) {
+    let primary = lang.split(['-', '_']).next().unwrap_or_default();
+    let (outer, inner) = match primary.to_ascii_lowercase().as_str() {
+        "fr" | "es" | "it" | "pt" | "ca" | "el" => (("«", "»"), ("“", "”")),
+        "ru" | "uk" | "be" => (("«", "»"), ("„", "“")),
+        "de" | "cs" | "sk" | "lt" | "is" => (("„", "“"), ("‚", "‘")),
+        "pl" | "ro" | "hu" | "hr" | "bg" => (("„", "”"), ("«", "»")),
+        "nl" => (("“", "”"), ("‘", "’")),
+        "sv" | "fi" => (("”", "”"), ("’", "’")),
+        "ja" => (("「", "」"), ("『", "』")),
+        _ => (("“", "”"), ("‘", "’")),
+    };
+    if nested { inner } else { outer }
 }
 
 /// Whether `text` is already whitespace-normalized: ASCII-only with no
@@ -266,6 +424,24 @@ mod tests {
     use super::*;
     use crate::style::{ComputedStyle, FontWeight};
 
+    #[test]
+    fn quote_marks_follow_language_and_nesting() {
+        let mut chapter = Chapter::new();
+        let french = chapter.styles.intern(ComputedStyle {
+            language: Some("fr-CA".to_string()),
+            ..Default::default()
+        });
+        let outer = chapter.alloc_node(Node::new(Role::Quote));
+        chapter.append_child(NodeId::ROOT, outer);
+        let inner = chapter.alloc_node(Node::new(Role::Quote));
+        chapter.append_child(outer, inner);
+        assert_eq!(chapter.quote_marks(outer), ("“", "”"));
+        assert_eq!(chapter.quote_marks(inner), ("‘", "’"));
+
+        chapter.node_mut(outer).unwrap().style = french;
+        assert_eq!(chapter.quote_marks(outer), ("«", "»"));
+    }
+
     #[test]
     fn test_chapter_creation() {
         let chapter = Chapter::new();
@@ -13,6 +13,8 @@ use crate::model::NodeId;
 
 /// Unique identifier for a chapter/spine item within a book.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct ChapterId(pub u32);
 
 /// Uniquely identifies a node across the entire book.
@@ -24,6 +24,12 @@ pub enum Format {
     Kfx,
     /// Markdown (export only)
     Markdown,
+    /// Textual IR dump, for bug reports and regression tests
+    Ir,
+    /// FictionBook 2 (import only)
+    Fb2,
+    /// Comic book ZIP archive of page images (import only)
+    Cbz,
 }
 
 /// A resource (image, font, CSS, etc.) with its data and media type.
@@ -35,8 +41,38 @@ pub struct Resource {
     pub media_type: &'static str,
 }
 
+impl Resource {
+    /// The resource's MIME type.
+    pub fn media_type(&self) -> &'static str {
+        self.media_type
+    }
+
+    /// Relabel the resource, e.g. to correct a JPEG declared as PNG.
+    ///
+    /// Fails with [`Error::UnsupportedFormat`](crate::Error::UnsupportedFormat)
+    /// for a media type boko doesn't know, leaving the resource unchanged,
+    /// like [`Book::set_resource_media_type`](crate::Book::set_resource_media_type).
+    pub fn set_media_type(&mut self, media_type: &str) -> crate::Result<()> {
+        self.media_type = crate::util::require_known_media_type(media_type)?;
+        Ok(())
+    }
+
+    /// Whether the resource is a content document (XHTML or HTML).
+    pub fn is_content_document(&self) -> bool {
+        crate::util::is_content_document_type(self.media_type)
+    }
+
+    /// The MIME type an EPUB manifest declares for the resource: content
+    /// documents are `application/xhtml+xml` whatever they were labeled;
+    /// images, fonts and stylesheets keep their type.
+    pub fn normalized_mime(&self) -> &'static str {
+        crate::util::normalized_media_type(self.media_type)
+    }
+}
+
 /// A contributor with optional role and sort name (EPUB `dc:contributor`).
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Contributor {
     /// Display name of the contributor.
     pub name: String,
@@ -46,8 +82,21 @@ pub struct Contributor {
     pub role: Option<String>,
 }
 
+/// A publication identifier (`dc:identifier`) with its scheme, if declared.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Identifier {
+    /// Identifier scheme as declared by the source: `opf:scheme` in EPUB 2
+    /// (e.g. `"ISBN"`, `"UUID"`), the `identifier-type` refinement in EPUB 3,
+    /// or `"ISBN"`/`"ASIN"` for Kindle EXTH records.
+    pub scheme: Option<String>,
+    /// The identifier itself, e.g. `"9780141439518"` or `"urn:uuid:..."`.
+    pub value: String,
+}
+
 /// Collection/series information (EPUB 3 `belongs-to-collection`).
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CollectionInfo {
     /// Collection or series name.
     pub name: String,
@@ -57,6 +106,64 @@ pub struct CollectionInfo {
     pub position: Option<f64>,
 }
 
+/// EPUB 3 fixed-layout rendition settings (`rendition:*` package
+/// properties), plus the page size fixed-layout content documents declare.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Rendition {
+    /// `rendition:layout`: `"pre-paginated"` (fixed layout) or
+    /// `"reflowable"`.
+    pub layout: Option<String>,
+    /// `rendition:orientation`: `"auto"`, `"landscape"`, or `"portrait"`.
+    pub orientation: Option<String>,
+    /// `rendition:spread`: `"none"`, `"landscape"`, `"both"`, or `"auto"`.
+    pub spread: Option<String>,
+    /// Page size in CSS pixels, from the first content document's
+    /// `<meta name="viewport" content="width=..., height=...">`.
+    pub viewport: Option<(u32, u32)>,
+}
+
+impl Rendition {
+    /// Whether the book is fixed-layout (`rendition:layout` is
+    /// `pre-paginated`): every content document is a page of fixed size.
+    pub fn is_fixed_layout(&self) -> bool {
+        self.layout.as_deref() == Some("pre-paginated")
+    }
+}
+
+/// Accessibility metadata: the schema.org `schema:*` package properties
+/// (EPUB Accessibility 1.1) describing how the content can be perceived.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Accessibility {
+    /// `schema:accessibilityFeature` values (e.g. `"structuralNavigation"`,
+    /// `"alternativeText"`), in declaration order.
+    pub features: Vec<String>,
+    /// `schema:accessMode` values: `"textual"`, `"visual"`, `"auditory"`...
+    pub access_modes: Vec<String>,
+    /// `schema:accessModeSufficient` values, each a comma-separated set of
+    /// modes that together convey all content (e.g. `"textual,visual"`).
+    pub access_modes_sufficient: Vec<String>,
+    /// `schema:accessibilityHazard` values: `"none"`, `"flashing"`...
+    pub hazards: Vec<String>,
+    /// `schema:accessibilitySummary`: a prose description of the book's
+    /// accessibility.
+    pub summary: Option<String>,
+}
+
+impl Accessibility {
+    /// Whether the book declares no accessibility metadata.
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty()
+            && self.access_modes.is_empty()
+            && self.access_modes_sufficient.is_empty()
+            && self.hazards.is_empty()
+            && self.summary.is_none()
+    }
+}
+
 /// Book metadata (Dublin Core + extensions)
 ///
 /// Populated from the OPF `<metadata>` element for EPUB, or the
@@ -64,6 +171,8 @@ pub struct CollectionInfo {
 /// default to empty and `Option` fields to `None` when a source book
 /// omits them.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Metadata {
     /// Book title (`dc:title`).
     pub title: String,
@@ -71,8 +180,12 @@ pub struct Metadata {
     pub authors: Vec<String>,
     /// Language tag such as "en" or "pt-BR" (`dc:language`).
     pub language: String,
-    /// Unique identifier (`dc:identifier`), e.g. ISBN, UUID, or URI.
+    /// Unique identifier (`dc:identifier`), e.g. ISBN, UUID, or URI. For
+    /// EPUB this is the package's `unique-identifier`.
     pub identifier: String,
+    /// Every identifier the book declares, in declaration order (including
+    /// the unique `identifier`).
+    pub identifiers: Vec<Identifier>,
     /// Publisher name (`dc:publisher`).
     pub publisher: Option<String>,
     /// Description or blurb (`dc:description`); may contain HTML markup.
@@ -95,11 +208,29 @@ pub struct Metadata {
     pub author_sort: Option<String>,
     /// belongs-to-collection (series info)
     pub collection: Option<CollectionInfo>,
+    /// Calibre rating (`calibre:rating`) on Calibre's 0-10 scale, where
+    /// each star is worth 2.
+    pub rating: Option<f32>,
     /// Global reading direction from the OPF spine's
     /// `page-progression-direction` (`"ltr"`, `"rtl"`, or `"default"`).
     /// Right-to-left books (Arabic, Hebrew, vertical Japanese) lose their
     /// reading order without it.
     pub page_progression_direction: Option<String>,
+    /// Fixed-layout rendition settings. Comics and picture books are
+    /// `pre-paginated`; converting them as reflowable text breaks them.
+    pub rendition: Rendition,
+    /// Accessibility metadata (`schema:accessibilityFeature` and friends).
+    pub accessibility: Accessibility,
+    /// Metadata boko doesn't model, carried through EPUB export as
+    /// `(property, value)` pairs in document order: top-level
+    /// `<meta property="...">` values under their property name
+    /// (`"foo:bar"`) and unmodeled Dublin Core elements under their
+    /// qualified name (`"dc:coverage"`).
+    pub custom: Vec<(String, String)>,
+    /// Vocabulary prefixes declared by the source package's `prefix`
+    /// attribute, as `(prefix, IRI)` pairs, so the prefixes `custom`
+    /// properties use can be declared again on export.
+    pub custom_prefixes: Vec<(String, String)>,
 }
 
 /// A table of contents entry (hierarchical)
@@ -107,6 +238,7 @@ pub struct Metadata {
 /// Built from the EPUB 3 nav document or EPUB 2 NCX (or the equivalent
 /// Kindle TOC structures).
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TocEntry {
     /// Display label for the entry.
     pub title: String,
@@ -117,11 +249,13 @@ pub struct TocEntry {
     /// Play order for sorting (from NCX playOrder attribute)
     pub play_order: Option<usize>,
     /// Resolved target (set by `resolve_links()`)
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub target: Option<AnchorTarget>,
 }
 
 /// Type of landmark in a book's navigation.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LandmarkType {
     /// Cover page (image)
     Cover,
@@ -160,6 +294,7 @@ pub enum LandmarkType {
 /// Landmarks identify structural locations in a book (cover, start of content,
 /// endnotes, etc.) used for navigation and reader features.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Landmark {
     /// Type of landmark
     pub landmark_type: LandmarkType,
@@ -171,31 +306,58 @@ pub struct Landmark {
 
 impl Format {
     /// Detect format from file extension.
+    ///
+    /// A trailing `.gz` is looked through: `book.epub.gz` is EPUB. So is
+    /// `book.epub.zip`, the name some browsers and mail clients give an
+    /// EPUB (itself a ZIP archive) on download.
     pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
-        path.as_ref()
-            .extension()
-            .and_then(|e| e.to_str())
-            .and_then(|ext| match ext.to_lowercase().as_str() {
+        let path = path.as_ref();
+        let path = match path.extension() {
+            Some(ext) if ext.eq_ignore_ascii_case("gz") => Path::new(path.file_stem()?),
+            Some(ext) if ext.eq_ignore_ascii_case("zip") => {
+                let stem = Path::new(path.file_stem()?);
+                let is_epub = stem
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("epub"));
+                if !is_epub {
+                    return None;
+                }
+                stem
+            }
+            _ => path,
+        };
+        path.extension().and_then(|e| e.to_str()).and_then(|ext| {
+            match ext.to_lowercase().as_str() {
                 "epub" => Some(Format::Epub),
                 "azw3" => Some(Format::Azw3),
                 "mobi" | "azw" => Some(Format::Mobi),
                 "kfx" => Some(Format::Kfx),
                 "md" | "txt" => Some(Format::Markdown),
+                "ir" => Some(Format::Ir),
+                "fb2" => Some(Format::Fb2),
+                "cbz" => Some(Format::Cbz),
                 _ => None,
-            })
+            }
+        })
     }
 
     /// Whether this format can be used for input/import.
     pub fn can_import(&self) -> bool {
         matches!(
             self,
-            Format::Epub | Format::Azw3 | Format::Mobi | Format::Kfx
+            Format::Epub
+                | Format::Azw3
+                | Format::Mobi
+                | Format::Kfx
+                | Format::Ir
+                | Format::Fb2
+                | Format::Cbz
         )
     }
 
     /// Whether this format can be used for output/export.
     pub fn can_export(&self) -> bool {
-        !matches!(self, Format::Mobi)
+        !matches!(self, Format::Mobi | Format::Fb2 | Format::Cbz)
     }
 }
 
@@ -225,8 +387,16 @@ mod tests {
         assert_eq!(Format::from_path("book.azw"), Some(Format::Mobi));
         assert_eq!(Format::from_path("book.kfx"), Some(Format::Kfx));
         assert_eq!(Format::from_path("notes.md"), Some(Format::Markdown));
+        assert_eq!(Format::from_path("book.fb2"), Some(Format::Fb2));
+        assert_eq!(Format::from_path("comic.CBZ"), Some(Format::Cbz));
         assert_eq!(Format::from_path("book.AZW"), Some(Format::Mobi));
         assert_eq!(Format::from_path("book.unknown"), None);
         assert_eq!(Format::from_path("no_extension"), None);
+        assert_eq!(Format::from_path("book.epub.gz"), Some(Format::Epub));
+        assert_eq!(Format::from_path("book.epub.zip"), Some(Format::Epub));
+        assert_eq!(Format::from_path("book.kfx.zip"), None);
+        assert_eq!(Format::from_path("book.zip"), None);
+        assert_eq!(Format::from_path("book.AZW3.GZ"), Some(Format::Azw3));
+        assert_eq!(Format::from_path("book.gz"), None);
     }
 }
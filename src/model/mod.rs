@@ -22,7 +22,8 @@ mod semantic;
 
 // Re-export pure book data types
 pub use metadata::{
-    CollectionInfo, Contributor, Format, Landmark, LandmarkType, Metadata, Resource, TocEntry,
+    Accessibility, CollectionInfo, Contributor, Format, Identifier, Landmark, LandmarkType,
+    Metadata, Rendition, Resource, TocEntry,
 };
 
 // Re-export the Book runtime handle (moved to crate::book; kept here so
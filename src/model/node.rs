@@ -70,8 +70,14 @@ pub enum Role {
     /// Generic inline container (e.g., `<span>`).
     /// Distinct from Text which contains actual string data.
     Inline,
-    /// Block quotes.
+    /// Block quotes. The source URL is `cite` in SemanticMap.
     BlockQuote,
+    /// Inline quotation (`<q>`). Formats without a native `<q>` render it
+    /// with the quotation marks of the content language (see
+    /// [`Chapter::quote_marks`]). The source URL is `cite` in SemanticMap.
+    ///
+    /// [`Chapter::quote_marks`]: crate::model::Chapter::quote_marks
+    Quote,
     /// Root document node.
     Root,
     /// Semantic line break (`<br>`).
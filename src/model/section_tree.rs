@@ -20,7 +20,7 @@ use crate::util::strip_ebook_chars;
 /// single stream, then re-nested under headings. Useful for text analysis
 /// and structured (e.g. JSON) output.
 #[derive(Debug, Clone)]
-#[cfg_attr(feature = "cli", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct SectionTree {
     /// Book title, from the book's metadata (e.g. OPF `dc:title`).
     pub title: String,
@@ -29,7 +29,7 @@ pub struct SectionTree {
     /// Language tag (e.g. "en"), from the book's metadata (`dc:language`).
     pub language: String,
     /// Content appearing before the first heading in the book.
-    #[cfg_attr(feature = "cli", serde(skip_serializing_if = "Vec::is_empty"))]
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
     pub preamble: Vec<ContentBlock>,
     /// Top-level sections (each rooted at a heading), in reading order.
     pub sections: Vec<SectionNode>,
@@ -37,17 +37,17 @@ pub struct SectionTree {
 
 /// A section defined by a heading and its content.
 #[derive(Debug, Clone)]
-#[cfg_attr(feature = "cli", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct SectionNode {
     /// Heading level (1-6).
     pub level: u8,
     /// Heading text.
     pub title: String,
     /// Content blocks before any child section.
-    #[cfg_attr(feature = "cli", serde(skip_serializing_if = "Vec::is_empty"))]
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
     pub content: Vec<ContentBlock>,
     /// Subsections (headings at a deeper level).
-    #[cfg_attr(feature = "cli", serde(skip_serializing_if = "Vec::is_empty"))]
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
     pub children: Vec<SectionNode>,
 }
 
@@ -56,8 +56,8 @@ pub struct SectionNode {
 /// Text is extracted plain (inline formatting dropped, whitespace collapsed,
 /// footnote bodies skipped), except code which is kept verbatim.
 #[derive(Debug, Clone)]
-#[cfg_attr(feature = "cli", derive(serde::Serialize))]
-#[cfg_attr(feature = "cli", serde(tag = "type", rename_all = "snake_case"))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
 pub enum ContentBlock {
     /// A paragraph or caption (`Role::Paragraph`/`Role::Caption`).
     Paragraph {
@@ -67,7 +67,7 @@ pub enum ContentBlock {
     /// A code block (`Role::CodeBlock`), preserved verbatim.
     CodeBlock {
         /// Programming language, if declared (e.g. from a `language-*` class).
-        #[cfg_attr(feature = "cli", serde(skip_serializing_if = "Option::is_none"))]
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
         language: Option<String>,
         /// The code with original whitespace and newlines preserved.
         code: String,
@@ -88,7 +88,7 @@ pub enum ContentBlock {
     /// A table flattened to header and body cell text.
     Table {
         /// Header row cells (from `thead` or header cells), if any.
-        #[cfg_attr(feature = "cli", serde(skip_serializing_if = "Vec::is_empty"))]
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
         headers: Vec<String>,
         /// Body rows, each a list of cell texts.
         rows: Vec<Vec<String>>,
@@ -352,9 +352,16 @@ fn collect_text_recursive(chapter: &Chapter, node_id: NodeId, result: &mut Strin
         }
     }
 
+    let marks = (node.role == Role::Quote).then(|| chapter.quote_marks(node_id));
+    if let Some((open, _)) = marks {
+        result.push_str(open);
+    }
     for child_id in chapter.children(node_id) {
         collect_text_recursive(chapter, child_id, result, depth + 1);
     }
+    if let Some((_, close)) = marks {
+        result.push_str(close);
+    }
 }
 
 fn collect_text_verbatim(chapter: &Chapter, node_id: NodeId) -> String {
@@ -39,14 +39,24 @@ pub struct SemanticMap {
     aria_role: HashMap<NodeId, TextRange>,
     /// datetime attribute (for `<time>` elements).
     datetime: HashMap<NodeId, TextRange>,
+    /// cite attribute (source URL of `<blockquote>` / `<q>`).
+    cite: HashMap<NodeId, TextRange>,
     /// start attribute (for ordered lists, ol@start).
     list_start: HashMap<NodeId, u32>,
+    /// value attribute (for ordered list items, li@value).
+    list_value: HashMap<NodeId, u32>,
     /// rowspan attribute (for table cells).
     row_span: HashMap<NodeId, u32>,
     /// colspan attribute (for table cells).
     col_span: HashMap<NodeId, u32>,
     /// Whether a table cell is a header cell (th vs td).
     is_header_cell: HashMap<NodeId, bool>,
+    /// scope attribute (for header cells, th@scope).
+    scope: HashMap<NodeId, TextRange>,
+    /// headers attribute (ids of the header cells a cell belongs to).
+    headers: HashMap<NodeId, TextRange>,
+    /// Whether an image is decorative (`alt=""` or `role="presentation"`).
+    decorative: HashMap<NodeId, bool>,
     /// Programming language for code blocks.
     language: HashMap<NodeId, TextRange>,
 }
@@ -206,6 +216,21 @@ impl SemanticMap {
         self.datetime.get(&node).map(|r| self.get_str(*r))
     }
 
+    // --- cite ---
+
+    /// Set the cite URL for a node (from `<blockquote>` / `<q>`).
+    pub fn set_cite(&mut self, node: NodeId, cite: &str) {
+        if !cite.is_empty() {
+            let range = self.append(cite);
+            self.cite.insert(node, range);
+        }
+    }
+
+    /// Get the cite URL for a node.
+    pub fn cite(&self, node: NodeId) -> Option<&str> {
+        self.cite.get(&node).map(|r| self.get_str(*r))
+    }
+
     // --- list_start ---
 
     /// Set the start number for an ordered list (from `<ol start="N">`).
@@ -221,6 +246,18 @@ impl SemanticMap {
         self.list_start.get(&node).copied()
     }
 
+    // --- list_value ---
+
+    /// Set the ordinal of a list item (from `<li value="N">`).
+    pub fn set_list_value(&mut self, node: NodeId, value: u32) {
+        self.list_value.insert(node, value);
+    }
+
+    /// Get the ordinal of a list item, if it overrides the list's counter.
+    pub fn list_value(&self, node: NodeId) -> Option<u32> {
+        self.list_value.get(&node).copied()
+    }
+
     // --- row_span ---
 
     /// Set the rowspan for a table cell.
@@ -265,6 +302,53 @@ impl SemanticMap {
         self.is_header_cell.get(&node).copied().unwrap_or(false)
     }
 
+    // --- scope ---
+
+    /// Set the scope of a header cell (`row`, `col`, `rowgroup`, `colgroup`).
+    pub fn set_scope(&mut self, node: NodeId, scope: &str) {
+        if !scope.is_empty() {
+            let range = self.append(scope);
+            self.scope.insert(node, range);
+        }
+    }
+
+    /// Get the scope of a header cell.
+    pub fn scope(&self, node: NodeId) -> Option<&str> {
+        self.scope.get(&node).map(|r| self.get_str(*r))
+    }
+
+    // --- headers ---
+
+    /// Set the header cell ids a table cell is associated with.
+    pub fn set_headers(&mut self, node: NodeId, headers: &str) {
+        if !headers.is_empty() {
+            let range = self.append(headers);
+            self.headers.insert(node, range);
+        }
+    }
+
+    /// Get the header cell ids a table cell is associated with.
+    pub fn headers(&self, node: NodeId) -> Option<&str> {
+        self.headers.get(&node).map(|r| self.get_str(*r))
+    }
+
+    // --- decorative ---
+
+    /// Mark an image as decorative: it carries no information and should be
+    /// skipped by assistive technology.
+    pub fn set_decorative(&mut self, node: NodeId, decorative: bool) {
+        if decorative {
+            self.decorative.insert(node, true);
+        } else {
+            self.decorative.remove(&node);
+        }
+    }
+
+    /// Check if an image is decorative.
+    pub fn is_decorative(&self, node: NodeId) -> bool {
+        self.decorative.get(&node).copied().unwrap_or(false)
+    }
+
     // --- language ---
 
     /// Set the programming language for a code block.
@@ -293,10 +377,15 @@ impl SemanticMap {
             + self.epub_type.len()
             + self.aria_role.len()
             + self.datetime.len()
+            + self.cite.len()
             + self.list_start.len()
+            + self.list_value.len()
             + self.row_span.len()
             + self.col_span.len()
             + self.is_header_cell.len()
+            + self.scope.len()
+            + self.headers.len()
+            + self.decorative.len()
             + self.language.len()
     }
 
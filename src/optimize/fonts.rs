@@ -0,0 +1,756 @@
+//! Controlling embedded fonts.
+//!
+//! [`crate::Book::embed_fonts`] picks one of three treatments for the fonts a
+//! book ships. [`FontEmbedding::None`] wraps the backend in an overlay that
+//! hides every font asset and strips the `@font-face` rules loading them
+//! (from CSS assets, `<style>` elements in raw chapters, and the parsed
+//! stylesheets), so every exporter falls back to reader fonts without
+//! dangling references. [`FontEmbedding::Subset`] runs [`SubsetFonts`]
+//! through the optimizer's asset-edit overlay.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+
+use super::{AssetEdit, OptimizePass, PassReport};
+use crate::dom::Stylesheet;
+use crate::import::{ChapterId, Importer, SpineEntry};
+use crate::model::{AnchorTarget, Chapter, FontFace, GlobalNodeId, Landmark, Metadata, TocEntry};
+
+/// How [`crate::Book::embed_fonts`] treats the book's embedded fonts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FontEmbedding {
+    /// Drop every font resource and `@font-face` rule; readers use their
+    /// own fonts.
+    None,
+    /// Trim TrueType fonts down to the glyphs the book's text uses. Other
+    /// font formats (CFF-flavored OpenType, WOFF, WOFF2) are kept as-is.
+    Subset,
+    /// Keep fonts as they are.
+    #[default]
+    Full,
+}
+
+/// Whether `path` is a font, by its declared media type or its extension.
+pub(super) fn is_font_asset(backend: &dyn Importer, path: &str) -> bool {
+    backend.asset_media_type(path).is_some_and(|media_type| {
+        let media_type = media_type.to_ascii_lowercase();
+        media_type.starts_with("font/")
+            || media_type.contains("font-")
+            || media_type.contains("opentype")
+    }) || crate::util::detect_media_format(path, &[]).is_font()
+}
+
+fn is_css(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("css"))
+}
+
+/// Importer overlay for [`FontEmbedding::None`]: font assets are gone and
+/// nothing declares `@font-face` any more. Everything else is served by the
+/// inner backend.
+pub(super) struct FontlessImporter {
+    inner: Box<dyn Importer>,
+    /// Inner asset list minus the fonts, same order.
+    assets: Vec<String>,
+}
+
+impl FontlessImporter {
+    /// Wrap `inner`, reporting how many font assets were dropped and their
+    /// combined size.
+    pub(super) fn apply(inner: Box<dyn Importer>) -> (Self, PassReport) {
+        let mut report = PassReport {
+            pass: "fonts",
+            assets_changed: 0,
+            bytes_saved: 0,
+        };
+        let mut assets = Vec::new();
+        for path in inner.list_assets() {
+            if is_font_asset(inner.as_ref(), path) {
+                report.assets_changed += 1;
+                report.bytes_saved += inner.load_asset(path).map_or(0, |d| d.len() as u64);
+            } else {
+                assets.push(path.clone());
+            }
+        }
+        (Self { inner, assets }, report)
+    }
+
+    fn is_dropped(&self, path: &str) -> bool {
+        !self.assets.iter().any(|asset| asset == path)
+            && self.inner.list_assets().iter().any(|asset| asset == path)
+    }
+}
+
+impl Importer for FontlessImporter {
+    fn open(_path: &Path) -> crate::Result<Self>
+    where
+        Self: Sized,
+    {
+        Err(crate::Error::UnsupportedFormat {
+            detail: "FontlessImporter wraps an existing backend".to_string(),
+        })
+    }
+
+    fn metadata(&self) -> &Metadata {
+        self.inner.metadata()
+    }
+
+    fn toc(&self) -> &[TocEntry] {
+        self.inner.toc()
+    }
+
+    fn landmarks(&self) -> &[Landmark] {
+        self.inner.landmarks()
+    }
+
+    fn spine(&self) -> &[SpineEntry] {
+        self.inner.spine()
+    }
+
+    fn load_chapter(&self, id: ChapterId) -> crate::Result<Chapter> {
+        self.inner.load_chapter(id)
+    }
+
+    fn load_chapters(&self, ids: &[ChapterId]) -> Vec<crate::Result<Chapter>> {
+        self.inner.load_chapters(ids)
+    }
+
+    fn source_id(&self, id: ChapterId) -> Option<&str> {
+        self.inner.source_id(id)
+    }
+
+    fn load_raw(&self, id: ChapterId) -> crate::Result<Vec<u8>> {
+        let raw = self.inner.load_raw(id)?;
+        Ok(
+            match std::str::from_utf8(&raw)
+                .ok()
+                .and_then(strip_style_elements)
+            {
+                Some(stripped) => stripped.into_bytes(),
+                None => raw,
+            },
+        )
+    }
+
+    fn list_assets(&self) -> &[String] {
+        &self.assets
+    }
+
+    fn load_asset(&self, path: &str) -> crate::Result<Vec<u8>> {
+        if self.is_dropped(path) {
+            return Err(crate::Error::NotFound {
+                what: format!("asset {path} (fonts not embedded)"),
+            });
+        }
+        let data = self.inner.load_asset(path)?;
+        if !is_css(path) {
+            return Ok(data);
+        }
+        Ok(
+            match std::str::from_utf8(&data).ok().and_then(strip_font_faces) {
+                Some(stripped) => stripped.into_bytes(),
+                None => data,
+            },
+        )
+    }
+
+    fn contains_asset(&self, path: &str) -> bool {
+        crate::import::overlay_contains_asset(&self.assets, self.inner.as_ref(), path)
+    }
+
+    fn asset_media_type(&self, path: &str) -> Option<&str> {
+        self.inner.asset_media_type(path)
+    }
+
+    fn load_stylesheet(&self, path: &str) -> Option<Arc<Stylesheet>> {
+        let sheet = self.inner.load_stylesheet(path)?;
+        if sheet.font_faces.is_empty() {
+            return Some(sheet);
+        }
+        let mut sheet = Stylesheet::clone(&sheet);
+        sheet.font_faces.clear();
+        Some(Arc::new(sheet))
+    }
+
+    fn font_faces(&self) -> Vec<FontFace> {
+        Vec::new()
+    }
+
+    fn requires_normalized_export(&self) -> bool {
+        self.inner.requires_normalized_export()
+    }
+
+    fn index_anchor_ids(&self, ids: &[(GlobalNodeId, &str)]) {
+        self.inner.index_anchor_ids(ids)
+    }
+
+    fn resolve_toc(&self) -> Option<Vec<TocEntry>> {
+        self.inner.resolve_toc()
+    }
+
+    fn resolve_href(&self, from_chapter: ChapterId, href: &str) -> Option<AnchorTarget> {
+        self.inner.resolve_href(from_chapter, href)
+    }
+}
+
+/// Remove every `@font-face` rule from `css`, or `None` when it has none.
+/// Comments and strings are skipped so an `@font-face` inside them (or a
+/// brace inside a `src` string) doesn't confuse the scan.
+fn strip_font_faces(css: &str) -> Option<String> {
+    let bytes = css.as_bytes();
+    let mut out = String::with_capacity(css.len());
+    let mut copied = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'*') => i = skip_comment(bytes, i),
+            b'"' | b'\'' => i = skip_string(bytes, i),
+            b'@' if bytes[i..]
+                .get(..10)
+                .is_some_and(|at| at.eq_ignore_ascii_case(b"@font-face")) =>
+            {
+                let end = skip_block(bytes, i);
+                out.push_str(&css[copied..i]);
+                copied = end;
+                i = end;
+            }
+            _ => i += 1,
+        }
+    }
+    if copied == 0 {
+        return None;
+    }
+    out.push_str(&css[copied..]);
+    Some(out)
+}
+
+/// Index just past the comment opening at `start`.
+fn skip_comment(bytes: &[u8], start: usize) -> usize {
+    memchr::memmem::find(&bytes[start + 2..], b"*/").map_or(bytes.len(), |end| start + end + 4)
+}
+
+/// Index just past the string literal opening at `start`.
+fn skip_string(bytes: &[u8], start: usize) -> usize {
+    let quote = bytes[start];
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b if b == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Index just past the `{...}` block of the at-rule starting at `start`
+/// (or past its `;` if it has no block).
+fn skip_block(bytes: &[u8], start: usize) -> usize {
+    let mut depth = 0usize;
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'*') => i = skip_comment(bytes, i),
+            b'"' | b'\'' => i = skip_string(bytes, i),
+            b';' if depth == 0 => return i + 1,
+            b'{' => {
+                depth += 1;
+                i += 1;
+            }
+            b'}' => {
+                depth = depth.saturating_sub(1);
+                i += 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Strip `@font-face` rules from the `<style>` elements of an HTML
+/// document, or `None` when there were none.
+fn strip_style_elements(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let mut out = String::with_capacity(html.len());
+    let mut copied = 0;
+    let mut from = 0;
+    let mut changed = false;
+    while let Some(open) = lower[from..].find("<style").map(|at| from + at) {
+        let Some(body) = lower[open..].find('>').map(|at| open + at + 1) else {
+            break;
+        };
+        let close = lower[body..]
+            .find("</style")
+            .map_or(html.len(), |at| body + at);
+        if let Some(stripped) = strip_font_faces(&html[body..close]) {
+            out.push_str(&html[copied..body]);
+            out.push_str(&stripped);
+            copied = close;
+            changed = true;
+        }
+        from = close;
+    }
+    if !changed {
+        return None;
+    }
+    out.push_str(&html[copied..]);
+    Some(out)
+}
+
+/// Subset TrueType fonts to the characters the book uses.
+pub(super) struct SubsetFonts;
+
+impl OptimizePass for SubsetFonts {
+    fn name(&self) -> &'static str {
+        "fonts"
+    }
+
+    fn run(&self, backend: &dyn Importer) -> Vec<AssetEdit> {
+        let fonts: Vec<&String> = backend
+            .list_assets()
+            .iter()
+            .filter(|path| is_font_asset(backend, path))
+            .collect();
+        if fonts.is_empty() {
+            return Vec::new();
+        }
+        let used = used_chars(backend);
+        fonts
+            .into_iter()
+            .filter_map(|path| {
+                let data = backend.load_asset(path).ok()?;
+                Some(AssetEdit {
+                    path: path.clone(),
+                    new_path: None,
+                    data: subset_truetype(&data, &used)?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Every character the book can display: chapter text, the title and TOC
+/// labels, each in both cases (`text-transform` can change it), plus
+/// printable ASCII and the characters list markers commonly draw.
+fn used_chars(backend: &dyn Importer) -> HashSet<char> {
+    let mut text = backend.metadata().title.clone();
+    fn collect_toc(entries: &[TocEntry], out: &mut String) {
+        for entry in entries {
+            out.push_str(&entry.title);
+            collect_toc(&entry.children, out);
+        }
+    }
+    collect_toc(backend.toc(), &mut text);
+    let ids: Vec<ChapterId> = backend.spine().iter().map(|entry| entry.id).collect();
+    for chapter in backend.load_chapters(&ids).into_iter().flatten() {
+        text.push_str(chapter.text_buffer());
+    }
+
+    let mut used: HashSet<char> = (' '..='~').chain(['\u{a0}', '•', '◦', '▪']).collect();
+    for c in text.chars() {
+        used.insert(c);
+        used.extend(c.to_lowercase());
+        used.extend(c.to_uppercase());
+    }
+    used
+}
+
+/// Composite glyph component flags (OpenType `glyf` table).
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+/// Subset a TrueType font to the glyphs drawing `used`, or `None` for fonts
+/// this can't handle (CFF outlines, WOFF wrappers, collections, variable
+/// fonts, malformed tables).
+///
+/// Glyph IDs are kept stable: dropped glyphs become empty outlines rather
+/// than being renumbered, so `cmap`, `hmtx`, and the layout tables stay
+/// valid untouched. A glyph is dropped only when the character map reaches
+/// it exclusively through unused characters; glyphs no character maps to
+/// (ligatures, alternates) are kept, since layout features may substitute
+/// them in for used text.
+fn subset_truetype(data: &[u8], used: &HashSet<char>) -> Option<Vec<u8>> {
+    let face = ttf_parser::Face::parse(data, 0).ok()?;
+    let tables = read_table_directory(data)?;
+    let table = |tag: &[u8; 4]| tables.iter().find(|t| &t.tag == tag);
+    if table(b"gvar").is_some() {
+        return None;
+    }
+    let (head, loca, glyf) = (table(b"head")?, table(b"loca")?, table(b"glyf")?);
+    let glyph_count = face.number_of_glyphs() as usize;
+    let long_offsets = read_u16(data, head.offset + 50)? == 1;
+
+    let loca_data = data.get(loca.offset..loca.offset + loca.len)?;
+    let offsets: Vec<usize> = (0..=glyph_count)
+        .map(|i| {
+            if long_offsets {
+                read_u32(loca_data, i * 4).map(|o| o as usize)
+            } else {
+                read_u16(loca_data, i * 2).map(|o| o as usize * 2)
+            }
+        })
+        .collect::<Option<_>>()?;
+    let glyf_data = data.get(glyf.offset..glyf.offset + glyf.len)?;
+    let glyph = |gid: usize| -> Option<&[u8]> { glyf_data.get(offsets[gid]..offsets[gid + 1]) };
+
+    // Glyphs the character map reaches, and those it reaches via used text.
+    let mut mapped = vec![false; glyph_count];
+    let mut keep = vec![false; glyph_count];
+    for subtable in face.tables().cmap?.subtables {
+        if !subtable.is_unicode() {
+            continue;
+        }
+        subtable.codepoints(|cp| {
+            if let Some(gid) = subtable.glyph_index(cp)
+                && let Some(slot) = mapped.get_mut(gid.0 as usize)
+            {
+                *slot = true;
+                if char::from_u32(cp).is_some_and(|c| used.contains(&c)) {
+                    keep[gid.0 as usize] = true;
+                }
+            }
+        });
+    }
+    for gid in 0..glyph_count {
+        keep[gid] |= gid == 0 || !mapped[gid];
+    }
+
+    // Composite glyphs need their components.
+    let mut pending: Vec<usize> = (0..glyph_count).filter(|&gid| keep[gid]).collect();
+    while let Some(gid) = pending.pop() {
+        for component in composite_components(glyph(gid)?)? {
+            if component < glyph_count && !keep[component] {
+                keep[component] = true;
+                pending.push(component);
+            }
+        }
+    }
+    if keep.iter().all(|&k| k) {
+        return None;
+    }
+
+    let align = if long_offsets { 4 } else { 2 };
+    let mut new_glyf = Vec::with_capacity(glyf.len);
+    let mut new_loca = Vec::with_capacity(loca.len);
+    let push_offset = |loca: &mut Vec<u8>, offset: usize| {
+        if long_offsets {
+            loca.extend_from_slice(&(offset as u32).to_be_bytes());
+        } else {
+            loca.extend_from_slice(&((offset / 2) as u16).to_be_bytes());
+        }
+    };
+    for (gid, &kept) in keep.iter().enumerate() {
+        push_offset(&mut new_loca, new_glyf.len());
+        if kept {
+            new_glyf.extend_from_slice(glyph(gid)?);
+            new_glyf.resize(new_glyf.len().next_multiple_of(align), 0);
+        }
+    }
+    push_offset(&mut new_loca, new_glyf.len());
+
+    let replaced: Vec<(&TableRecord, &[u8])> = tables
+        .iter()
+        .map(|record| match &record.tag {
+            b"glyf" => Some((record, new_glyf.as_slice())),
+            b"loca" => Some((record, new_loca.as_slice())),
+            _ => Some((record, data.get(record.offset..record.offset + record.len)?)),
+        })
+        .collect::<Option<_>>()?;
+    Some(write_sfnt(&data[..4], &replaced))
+}
+
+/// One entry of an sfnt table directory.
+struct TableRecord {
+    tag: [u8; 4],
+    offset: usize,
+    len: usize,
+}
+
+/// The table directory of a bare TrueType font (not a collection, WOFF, or
+/// CFF-flavored OpenType).
+fn read_table_directory(data: &[u8]) -> Option<Vec<TableRecord>> {
+    let version = data.get(..4)?;
+    if version != [0, 1, 0, 0] && version != b"true" {
+        return None;
+    }
+    let count = read_u16(data, 4)? as usize;
+    (0..count)
+        .map(|i| {
+            let at = 12 + i * 16;
+            Some(TableRecord {
+                tag: data.get(at..at + 4)?.try_into().ok()?,
+                offset: read_u32(data, at + 8)? as usize,
+                len: read_u32(data, at + 12)? as usize,
+            })
+        })
+        .collect()
+}
+
+/// Glyph IDs a composite glyph references; empty for simple glyphs.
+fn composite_components(glyph: &[u8]) -> Option<Vec<usize>> {
+    if glyph.is_empty() || (read_u16(glyph, 0)? as i16) >= 0 {
+        return Some(Vec::new());
+    }
+    let mut components = Vec::new();
+    let mut at = 10;
+    loop {
+        let flags = read_u16(glyph, at)?;
+        components.push(read_u16(glyph, at + 2)? as usize);
+        at += 4;
+        at += if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+            4
+        } else {
+            2
+        };
+        if flags & WE_HAVE_A_SCALE != 0 {
+            at += 2;
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            at += 4;
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            at += 8;
+        }
+        if flags & MORE_COMPONENTS == 0 {
+            return Some(components);
+        }
+    }
+}
+
+/// Serialize a font from its tables, keeping the directory order and
+/// recomputing offsets, table checksums, and `head.checkSumAdjustment`.
+fn write_sfnt(version: &[u8], tables: &[(&TableRecord, &[u8])]) -> Vec<u8> {
+    let count = tables.len() as u16;
+    let search_range = 16 * (1u16 << count.max(1).ilog2());
+    let mut out = Vec::new();
+    out.extend_from_slice(version);
+    out.extend_from_slice(&count.to_be_bytes());
+    out.extend_from_slice(&search_range.to_be_bytes());
+    out.extend_from_slice(&(count.max(1).ilog2() as u16).to_be_bytes());
+    out.extend_from_slice(&(count * 16 - search_range).to_be_bytes());
+
+    let mut offset = out.len() + tables.len() * 16;
+    let mut head_offset = None;
+    for (record, body) in tables {
+        let mut body = body.to_vec();
+        if &record.tag == b"head" && body.len() >= 12 {
+            body[8..12].fill(0);
+            head_offset = Some(offset);
+        }
+        out.extend_from_slice(&record.tag);
+        out.extend_from_slice(&checksum(&body).to_be_bytes());
+        out.extend_from_slice(&(offset as u32).to_be_bytes());
+        out.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        offset += body.len().next_multiple_of(4);
+    }
+    for (record, body) in tables {
+        let start = out.len();
+        out.extend_from_slice(body);
+        if &record.tag == b"head" && body.len() >= 12 {
+            out[start + 8..start + 12].fill(0);
+        }
+        out.resize(out.len().next_multiple_of(4), 0);
+    }
+    if let Some(head) = head_offset {
+        let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&out));
+        out[head + 8..head + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+    out
+}
+
+/// OpenType table checksum: the wrapping sum of big-endian `u32` words,
+/// zero-padded to a multiple of four bytes.
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+fn read_u16(data: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn read_u32(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_font_face_rules_only() {
+        let css = "/* @font-face { } */\n@font-face {\n  font-family: \"A}\";\n  src: url(a.ttf);\n}\np { color: red; }\n@FONT-FACE{src:url(b.otf)}h1{margin:0}";
+        let stripped = strip_font_faces(css).unwrap();
+        assert_eq!(
+            stripped,
+            "/* @font-face { } */\n\np { color: red; }\nh1{margin:0}"
+        );
+        assert_eq!(strip_font_faces("p { color: red; }"), None);
+    }
+
+    #[test]
+    fn strips_font_faces_inside_style_elements() {
+        let html = "<html><head><STYLE>@font-face{src:url(a.ttf)} p{}</STYLE></head>\
+                    <body><p>@font-face {not css}</p></body></html>";
+        assert_eq!(
+            strip_style_elements(html).unwrap(),
+            "<html><head><STYLE> p{}</STYLE></head>\
+             <body><p>@font-face {not css}</p></body></html>"
+        );
+    }
+
+    /// A TrueType font with a contour glyph per entry of `chars` (glyph
+    /// `i + 1` draws `chars[i]`) and a trailing composite glyph no
+    /// character maps to, built from glyph 1.
+    fn build_font(chars: &[char]) -> Vec<u8> {
+        let glyph_count = chars.len() + 2;
+        let mut glyf = Vec::new();
+        let mut loca = Vec::new();
+        for gid in 0..glyph_count {
+            loca.extend_from_slice(&(glyf.len() as u32).to_be_bytes());
+            if gid == 0 {
+                continue;
+            }
+            if gid == glyph_count - 1 {
+                // Composite: one component (glyph 1), byte offsets.
+                glyf.extend_from_slice(&(-1i16).to_be_bytes());
+                glyf.extend_from_slice(&[0; 8]);
+                glyf.extend_from_slice(&0u16.to_be_bytes());
+                glyf.extend_from_slice(&1u16.to_be_bytes());
+                glyf.extend_from_slice(&[0, 0]);
+            } else {
+                // One-point simple glyph, padded with a recognizable body.
+                glyf.extend_from_slice(&1i16.to_be_bytes());
+                glyf.extend_from_slice(&[0; 8]);
+                glyf.extend_from_slice(&0u16.to_be_bytes()); // endPts[0]
+                glyf.extend_from_slice(&0u16.to_be_bytes()); // instructionLength
+                glyf.push(0x37); // on-curve, x/y short positive
+                glyf.extend_from_slice(&[1, 1]);
+                glyf.resize(glyf.len() + 40, 0);
+            }
+            glyf.resize(glyf.len().next_multiple_of(4), 0);
+        }
+        loca.extend_from_slice(&(glyf.len() as u32).to_be_bytes());
+
+        let mut head = vec![0u8; 54];
+        head[0..4].copy_from_slice(&[0, 1, 0, 0]);
+        head[12..16].copy_from_slice(&0x5F0F_3CF5u32.to_be_bytes());
+        head[18..20].copy_from_slice(&1000u16.to_be_bytes());
+        head[50..52].copy_from_slice(&1u16.to_be_bytes());
+
+        let mut hhea = vec![0u8; 36];
+        hhea[0..4].copy_from_slice(&[0, 1, 0, 0]);
+        hhea[34..36].copy_from_slice(&(glyph_count as u16).to_be_bytes());
+        let hmtx = vec![0u8; glyph_count * 4];
+
+        let mut maxp = vec![0u8; 6];
+        maxp[0..4].copy_from_slice(&0x0000_5000u32.to_be_bytes());
+        maxp[4..6].copy_from_slice(&(glyph_count as u16).to_be_bytes());
+
+        // cmap format 12 (platform 3, encoding 10): one group per char.
+        let mut cmap = Vec::new();
+        cmap.extend_from_slice(&[0, 0, 0, 1, 0, 3, 0, 10, 0, 0, 0, 12]);
+        let groups = chars.len() as u32;
+        cmap.extend_from_slice(&12u16.to_be_bytes());
+        cmap.extend_from_slice(&0u16.to_be_bytes());
+        cmap.extend_from_slice(&(16 + groups * 12).to_be_bytes());
+        cmap.extend_from_slice(&0u32.to_be_bytes());
+        cmap.extend_from_slice(&groups.to_be_bytes());
+        for (i, &c) in chars.iter().enumerate() {
+            cmap.extend_from_slice(&(c as u32).to_be_bytes());
+            cmap.extend_from_slice(&(c as u32).to_be_bytes());
+            cmap.extend_from_slice(&(i as u32 + 1).to_be_bytes());
+        }
+
+        let records = [
+            TableRecord {
+                tag: *b"cmap",
+                offset: 0,
+                len: 0,
+            },
+            TableRecord {
+                tag: *b"glyf",
+                offset: 0,
+                len: 0,
+            },
+            TableRecord {
+                tag: *b"head",
+                offset: 0,
+                len: 0,
+            },
+            TableRecord {
+                tag: *b"hhea",
+                offset: 0,
+                len: 0,
+            },
+            TableRecord {
+                tag: *b"hmtx",
+                offset: 0,
+                len: 0,
+            },
+            TableRecord {
+                tag: *b"loca",
+                offset: 0,
+                len: 0,
+            },
+            TableRecord {
+                tag: *b"maxp",
+                offset: 0,
+                len: 0,
+            },
+        ];
+        let bodies = [&cmap, &glyf, &head, &hhea, &hmtx, &loca, &maxp];
+        let tables: Vec<(&TableRecord, &[u8])> = records
+            .iter()
+            .zip(bodies)
+            .map(|(record, body)| (record, body.as_slice()))
+            .collect();
+        write_sfnt(&[0, 1, 0, 0], &tables)
+    }
+
+    fn outline_len(font: &[u8], c: char) -> usize {
+        let face = ttf_parser::Face::parse(font, 0).unwrap();
+        let gid = face.glyph_index(c).unwrap();
+        let tables = read_table_directory(font).unwrap();
+        let loca = tables.iter().find(|t| &t.tag == b"loca").unwrap();
+        let start = read_u32(font, loca.offset + gid.0 as usize * 4).unwrap();
+        let end = read_u32(font, loca.offset + gid.0 as usize * 4 + 4).unwrap();
+        (end - start) as usize
+    }
+
+    #[test]
+    fn subsetting_empties_glyphs_of_unused_characters() {
+        let font = build_font(&['ж', 'ф', 'щ']);
+        let used: HashSet<char> = ['ф'].into_iter().collect();
+        let subset = subset_truetype(&font, &used).unwrap();
+        assert!(subset.len() < font.len());
+
+        // Still a valid font with the same glyph IDs and character map.
+        let face = ttf_parser::Face::parse(&subset, 0).unwrap();
+        assert_eq!(face.number_of_glyphs(), 5);
+        assert!(outline_len(&subset, 'ф') > 0);
+        assert_eq!(outline_len(&subset, 'щ'), 0);
+        // 'ж' is unused but the unmapped composite glyph draws it.
+        assert!(outline_len(&subset, 'ж') > 0);
+
+        // The whole-font checksum is the magic constant.
+        assert_eq!(checksum(&subset), 0xB1B0_AFBA);
+    }
+
+    #[test]
+    fn subsetting_skips_fonts_it_cannot_shrink() {
+        let font = build_font(&['ж']);
+        let used: HashSet<char> = ['ж'].into_iter().collect();
+        assert_eq!(subset_truetype(&font, &used), None);
+        assert_eq!(subset_truetype(b"OTTO\0\0\0\0", &used), None);
+    }
+}
@@ -0,0 +1,677 @@
+//! Promote repeated inline `style` attributes into shared classes.
+//!
+//! Books produced by some converters carry every bit of formatting as
+//! inline `style="..."` attributes, repeating the same declaration list on
+//! hundreds of elements. [`crate::Book::merge_css_classes`] finds the
+//! declaration lists used more than once, moves each into one class rule in
+//! a generated stylesheet, and rewrites the chapters' raw markup to
+//! reference the class instead. The rewrite happens in an overlay importer,
+//! so every exporter — including EPUB raw passthrough — sees the smaller
+//! markup.
+//!
+//! Inline declarations outrank every selector-matched normal declaration;
+//! the generated rules mark their declarations `!important` so the class
+//! keeps winning over the book's own stylesheets. That only reproduces the
+//! original cascade while no author rule sets the same property
+//! `!important` (an inline declaration loses to it, a class rule may win or
+//! lose on specificity), so styles touching such a property stay inline.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+
+use memchr::memmem;
+
+use crate::dom::Stylesheet;
+use crate::import::{ChapterId, Importer, SpineEntry};
+use crate::model::{AnchorTarget, FontFace, GlobalNodeId, Landmark, Metadata, TocEntry};
+use crate::style::InlineStyle;
+
+/// Prefix for generated class names.
+const CLASS_PREFIX: &str = "boko-s";
+
+/// What [`crate::Book::merge_css_classes`] changed.
+#[derive(Debug, Clone, Default)]
+pub struct MergeCssReport {
+    /// Shared classes created (one per distinct repeated style).
+    pub classes_created: usize,
+    /// Inline `style` attributes replaced by a class reference.
+    pub attributes_replaced: usize,
+}
+
+/// One start tag's `style` and `class` attributes, as byte ranges into the
+/// chapter markup.
+struct StartTag {
+    /// The whole `style="..."` attribute, including its leading whitespace.
+    style_attr: std::ops::Range<usize>,
+    /// The `style` attribute's value (still entity-escaped).
+    style_value: std::ops::Range<usize>,
+    /// The `class` attribute's value, if present.
+    class_value: Option<std::ops::Range<usize>>,
+    /// Offset of the tag's closing `>` (or `/>`).
+    end: usize,
+}
+
+/// Every start tag with a non-empty `style` attribute, in document order.
+/// A forgiving scanner rather than a parser: comments, CDATA sections,
+/// declarations, and processing instructions are skipped, and anything that
+/// doesn't look like a well-formed start tag is left alone.
+fn styled_start_tags(html: &[u8]) -> Vec<StartTag> {
+    let mut tags = Vec::new();
+    let mut pos = 0;
+    while let Some(offset) = memchr::memchr(b'<', &html[pos..]) {
+        let start = pos + offset;
+        let rest = &html[start..];
+        let skip_to = |terminator: &[u8]| {
+            memmem::find(rest, terminator)
+                .map(|i| start + i + terminator.len())
+                .unwrap_or(html.len())
+        };
+        if rest.starts_with(b"<!--") {
+            pos = skip_to(b"-->");
+            continue;
+        }
+        if rest.starts_with(b"<![CDATA[") {
+            pos = skip_to(b"]]>");
+            continue;
+        }
+        if !rest.get(1).is_some_and(|b| b.is_ascii_alphabetic()) {
+            pos = start + 1;
+            continue;
+        }
+
+        let mut i = start + 1;
+        while i < html.len() && !html[i].is_ascii_whitespace() && !b"/>".contains(&html[i]) {
+            i += 1;
+        }
+
+        let mut style = None;
+        let mut class_value = None;
+        let end = loop {
+            let attr_start = i;
+            while i < html.len() && html[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            match html.get(i) {
+                None => break None,
+                Some(b'>') => break Some(i),
+                Some(b'/') if html.get(i + 1) == Some(&b'>') => break Some(i),
+                Some(b'/') => {
+                    i += 1;
+                    continue;
+                }
+                _ => {}
+            }
+            let name_start = i;
+            while i < html.len() && !html[i].is_ascii_whitespace() && !b"=/>".contains(&html[i]) {
+                i += 1;
+            }
+            let name = &html[name_start..i];
+            while i < html.len() && html[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            if html.get(i) != Some(&b'=') {
+                continue;
+            }
+            i += 1;
+            while i < html.len() && html[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            let value = match html.get(i) {
+                Some(&quote @ (b'"' | b'\'')) => {
+                    let Some(len) = memchr::memchr(quote, &html[i + 1..]) else {
+                        break None;
+                    };
+                    let value = i + 1..i + 1 + len;
+                    i = value.end + 1;
+                    value
+                }
+                _ => {
+                    let value_start = i;
+                    while i < html.len() && !html[i].is_ascii_whitespace() && html[i] != b'>' {
+                        i += 1;
+                    }
+                    value_start..i
+                }
+            };
+            if name.eq_ignore_ascii_case(b"style") {
+                style = Some((attr_start..i, value));
+            } else if name.eq_ignore_ascii_case(b"class") {
+                class_value = Some(value);
+            }
+        };
+
+        let Some(end) = end else {
+            break;
+        };
+        if let Some((style_attr, style_value)) = style
+            && !html[style_value.clone()]
+                .iter()
+                .all(u8::is_ascii_whitespace)
+        {
+            tags.push(StartTag {
+                style_attr,
+                style_value,
+                class_value,
+                end,
+            });
+        }
+        pos = end + 1;
+    }
+    tags
+}
+
+/// Canonical form of a `style` attribute value: entity-decoded, one
+/// `property: value` per declaration with lowercased property names and
+/// collapsed whitespace. Two attributes that differ only in spacing, case,
+/// or a trailing semicolon share a class. `None` when no declaration
+/// survives CSS parsing (nothing worth promoting).
+fn canonical_style(raw: &[u8]) -> Option<String> {
+    let raw = std::str::from_utf8(raw).ok()?;
+    let decoded = quick_xml::escape::unescape(raw).ok()?;
+    if InlineStyle::parse(&decoded).is_empty() {
+        return None;
+    }
+    let declarations: Vec<String> = split_declarations(&decoded)
+        .into_iter()
+        .filter_map(|decl| {
+            let (property, value) = decl.split_once(':')?;
+            let property = property.trim().to_ascii_lowercase();
+            let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+            (!property.is_empty() && !value.is_empty()).then(|| format!("{property}: {value}"))
+        })
+        .collect();
+    (!declarations.is_empty()).then(|| declarations.join("; "))
+}
+
+/// Split a declaration list on `;`, ignoring semicolons inside quotes or
+/// parentheses (`url(data:image/png;base64,...)`).
+fn split_declarations(css: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in css.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth = depth.saturating_sub(1),
+            (None, ';') if depth == 0 => {
+                parts.push(&css[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&css[start..]);
+    parts
+}
+
+/// Lowercased names of the properties `css` declares `!important`. Only
+/// innermost blocks are read, so declarations nested in `@media` count and
+/// at-rule preludes don't.
+fn collect_important_properties(css: &str, out: &mut HashSet<String>) {
+    let mut stripped = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(i) = rest.find("/*") {
+        stripped.push_str(&rest[..i]);
+        rest = rest[i + 2..]
+            .find("*/")
+            .map_or("", |j| &rest[i + 2 + j + 2..]);
+    }
+    stripped.push_str(rest);
+
+    let mut open = None;
+    for (i, c) in stripped.char_indices() {
+        match c {
+            '{' => open = Some(i + 1),
+            '}' => {
+                let Some(start) = open.take() else {
+                    continue;
+                };
+                for decl in split_declarations(&stripped[start..i]) {
+                    let Some((property, value)) = decl.split_once(':') else {
+                        continue;
+                    };
+                    let value: String = value.split_whitespace().collect();
+                    if value.to_ascii_lowercase().ends_with("!important") {
+                        out.insert(property.trim().to_ascii_lowercase());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The contents of every `<style>` element in a chapter.
+fn style_elements(html: &[u8]) -> Vec<String> {
+    let find = |haystack: &[u8], needle: &[u8]| {
+        haystack
+            .windows(needle.len())
+            .position(|w| w.eq_ignore_ascii_case(needle))
+    };
+    let mut blocks = Vec::new();
+    let mut pos = 0;
+    while let Some(offset) = find(&html[pos..], b"<style") {
+        let tag = pos + offset;
+        let Some(open) = memchr::memchr(b'>', &html[tag..]).map(|i| tag + i + 1) else {
+            break;
+        };
+        let close = find(&html[open..], b"</style").map_or(html.len(), |i| open + i);
+        blocks.push(String::from_utf8_lossy(&html[open..close]).into_owned());
+        pos = close;
+    }
+    blocks
+}
+
+/// Whether declarations of `a` and `b` can set the same property: equal
+/// names (ignoring vendor prefixes), a shorthand and one of its longhands,
+/// or `all`.
+fn properties_overlap(a: &str, b: &str) -> bool {
+    fn unprefixed(name: &str) -> &str {
+        name.strip_prefix('-')
+            .and_then(|n| n.split_once('-'))
+            .map_or(name, |(_, n)| n)
+    }
+    fn covers(short: &str, long: &str) -> bool {
+        long.strip_prefix(short)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('-'))
+    }
+    let (a, b) = (unprefixed(a), unprefixed(b));
+    a == "all"
+        || b == "all"
+        || covers(a, b)
+        || covers(b, a)
+        || matches!((a, b), ("font", "line-height") | ("line-height", "font"))
+}
+
+/// Case-insensitive position of `</head`.
+fn head_close(html: &[u8]) -> Option<usize> {
+    html.windows(6)
+        .position(|w| w.eq_ignore_ascii_case(b"</head"))
+}
+
+/// `to` (an archive path) relative to the directory of `from`.
+fn relative_href(from: &str, to: &str) -> String {
+    let from_dirs: Vec<&str> = from.split('/').collect();
+    let from_dirs = &from_dirs[..from_dirs.len() - 1];
+    let to_parts: Vec<&str> = to.split('/').collect();
+    let common = from_dirs
+        .iter()
+        .zip(&to_parts)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut href = "../".repeat(from_dirs.len() - common);
+    href.push_str(&to_parts[common..].join("/"));
+    href
+}
+
+/// Rewrite one chapter: replace promoted `style` attributes with class
+/// references and link the generated stylesheet from `<head>`.
+fn rewrite_chapter(
+    html: &[u8],
+    classes: &HashMap<String, String>,
+    css_href: &str,
+    replaced: &mut usize,
+) -> Option<Vec<u8>> {
+    let head = head_close(html)?;
+    let mut out = Vec::with_capacity(html.len());
+    let mut copied = 0;
+    let mut link_written = false;
+    let write_link = |out: &mut Vec<u8>| {
+        out.extend_from_slice(b"<link rel=\"stylesheet\" type=\"text/css\" href=\"");
+        out.extend_from_slice(css_href.as_bytes());
+        out.extend_from_slice(b"\"/>\n");
+    };
+
+    for tag in styled_start_tags(html) {
+        let Some(class) =
+            canonical_style(&html[tag.style_value.clone()]).and_then(|style| classes.get(&style))
+        else {
+            continue;
+        };
+        if !link_written && tag.style_attr.start > head {
+            out.extend_from_slice(&html[copied..head]);
+            copied = head;
+            write_link(&mut out);
+            link_written = true;
+        }
+        match &tag.class_value {
+            // Append to the existing class list, dropping the style attribute.
+            Some(class_value) if class_value.start < tag.style_attr.start => {
+                out.extend_from_slice(&html[copied..class_value.end]);
+                out.push(b' ');
+                out.extend_from_slice(class.as_bytes());
+                out.extend_from_slice(&html[class_value.end..tag.style_attr.start]);
+                copied = tag.style_attr.end;
+            }
+            Some(class_value) => {
+                out.extend_from_slice(&html[copied..tag.style_attr.start]);
+                out.extend_from_slice(&html[tag.style_attr.end..class_value.end]);
+                out.push(b' ');
+                out.extend_from_slice(class.as_bytes());
+                copied = class_value.end;
+            }
+            // Replace the style attribute with a class attribute in place.
+            None => {
+                out.extend_from_slice(&html[copied..tag.style_attr.start]);
+                out.extend_from_slice(b" class=\"");
+                out.extend_from_slice(class.as_bytes());
+                out.push(b'"');
+                copied = tag.style_attr.end;
+            }
+        }
+        debug_assert!(copied <= tag.end);
+        *replaced += 1;
+    }
+
+    if *replaced == 0 {
+        return None;
+    }
+    if !link_written {
+        out.extend_from_slice(&html[copied..head]);
+        copied = head;
+        write_link(&mut out);
+    }
+    out.extend_from_slice(&html[copied..]);
+    Some(out)
+}
+
+/// Importer wrapper serving chapters with repeated inline styles promoted
+/// to classes, plus the generated stylesheet as an extra asset.
+pub(crate) struct InlineStyleImporter {
+    inner: Box<dyn Importer>,
+    /// Inner asset list plus the generated stylesheet.
+    assets: Vec<String>,
+    css_path: String,
+    css: String,
+    /// Rewritten markup for chapters that had promoted styles.
+    chapters: HashMap<ChapterId, Vec<u8>>,
+}
+
+impl InlineStyleImporter {
+    /// Scan every chapter and build the overlay. Returns `None` (handing the
+    /// inner backend back) when no inline style repeats, or when the
+    /// backend's raw chapters aren't HTML.
+    pub(crate) fn apply(
+        inner: Box<dyn Importer>,
+    ) -> Result<(Self, MergeCssReport), Box<dyn Importer>> {
+        if inner.requires_normalized_export() {
+            return Err(inner);
+        }
+
+        // Properties some author rule declares `!important`, from the
+        // book's stylesheets and every chapter's `<style>` elements.
+        let mut important = HashSet::new();
+        for path in inner.list_assets() {
+            if path.to_ascii_lowercase().ends_with(".css")
+                && let Ok(css) = inner.load_asset(path)
+            {
+                collect_important_properties(&String::from_utf8_lossy(&css), &mut important);
+            }
+        }
+
+        // Count each canonical style across chapters that can link a
+        // stylesheet (a `<head>` to put the link in).
+        let mut raw_chapters = Vec::new();
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        let mut first_seen: Vec<String> = Vec::new();
+        for entry in inner.spine() {
+            let (Some(source), Ok(html)) = (inner.source_id(entry.id), inner.load_raw(entry.id))
+            else {
+                continue;
+            };
+            for css in style_elements(&html) {
+                collect_important_properties(&css, &mut important);
+            }
+            if head_close(&html).is_none() {
+                continue;
+            }
+            for tag in styled_start_tags(&html) {
+                if let Some(style) = canonical_style(&html[tag.style_value]) {
+                    let count = counts.entry(style.clone()).or_default();
+                    if *count == 0 {
+                        first_seen.push(style);
+                    }
+                    *count += 1;
+                }
+            }
+            raw_chapters.push((entry.id, source.to_string(), html));
+        }
+
+        // Number classes in first-use order so output is deterministic.
+        let competes_with_important = |style: &str| {
+            split_declarations(style).into_iter().any(|decl| {
+                let property = decl.split_once(':').map_or(decl, |(p, _)| p).trim();
+                important.iter().any(|p| properties_overlap(p, property))
+            })
+        };
+        let mut classes = HashMap::new();
+        let mut css = String::new();
+        for style in first_seen
+            .into_iter()
+            .filter(|s| counts[s] > 1 && !competes_with_important(s))
+        {
+            let class = format!("{CLASS_PREFIX}{}", classes.len() + 1);
+            css.push('.');
+            css.push_str(&class);
+            css.push_str(" {");
+            for decl in split_declarations(&style) {
+                let decl = decl.trim();
+                css.push(' ');
+                css.push_str(decl);
+                if !decl.to_ascii_lowercase().ends_with("!important") {
+                    css.push_str(" !important");
+                }
+                css.push(';');
+            }
+            css.push_str(" }\n");
+            classes.insert(style, class);
+        }
+        if classes.is_empty() {
+            return Err(inner);
+        }
+
+        // The stylesheet sits next to the first chapter, under a name no
+        // existing asset uses.
+        let dir = raw_chapters
+            .first()
+            .and_then(|(_, source, _)| source.rsplit_once('/'))
+            .map(|(dir, _)| format!("{dir}/"))
+            .unwrap_or_default();
+        let mut css_path = format!("{dir}boko-styles.css");
+        let mut n = 1;
+        while inner.list_assets().contains(&css_path) {
+            n += 1;
+            css_path = format!("{dir}boko-styles-{n}.css");
+        }
+
+        let mut report = MergeCssReport {
+            classes_created: classes.len(),
+            attributes_replaced: 0,
+        };
+        let mut chapters = HashMap::new();
+        for (id, source, html) in raw_chapters {
+            let href = relative_href(&source, &css_path);
+            if let Some(rewritten) =
+                rewrite_chapter(&html, &classes, &href, &mut report.attributes_replaced)
+            {
+                chapters.insert(id, rewritten);
+            }
+        }
+
+        let mut assets = inner.list_assets().to_vec();
+        assets.push(css_path.clone());
+        Ok((
+            Self {
+                inner,
+                assets,
+                css_path,
+                css,
+                chapters,
+            },
+            report,
+        ))
+    }
+}
+
+impl Importer for InlineStyleImporter {
+    fn open(_path: &Path) -> crate::Result<Self>
+    where
+        Self: Sized,
+    {
+        Err(crate::Error::UnsupportedFormat {
+            detail: "InlineStyleImporter wraps an existing backend".to_string(),
+        })
+    }
+
+    fn metadata(&self) -> &Metadata {
+        self.inner.metadata()
+    }
+
+    fn toc(&self) -> &[TocEntry] {
+        self.inner.toc()
+    }
+
+    fn landmarks(&self) -> &[Landmark] {
+        self.inner.landmarks()
+    }
+
+    fn spine(&self) -> &[SpineEntry] {
+        self.inner.spine()
+    }
+
+    // `load_chapter`/`load_chapters` keep the trait defaults: they compile
+    // from `load_raw` and `load_stylesheet` below, so the IR sees the
+    // rewritten markup and the generated rules.
+
+    fn source_id(&self, id: ChapterId) -> Option<&str> {
+        self.inner.source_id(id)
+    }
+
+    fn load_raw(&self, id: ChapterId) -> crate::Result<Vec<u8>> {
+        match self.chapters.get(&id) {
+            Some(html) => Ok(html.clone()),
+            None => self.inner.load_raw(id),
+        }
+    }
+
+    fn list_assets(&self) -> &[String] {
+        &self.assets
+    }
+
+    fn load_asset(&self, path: &str) -> crate::Result<Vec<u8>> {
+        if path == self.css_path {
+            return Ok(self.css.as_bytes().to_vec());
+        }
+        self.inner.load_asset(path)
+    }
+
+    fn contains_asset(&self, path: &str) -> bool {
+        crate::import::overlay_contains_asset(&self.assets, self.inner.as_ref(), path)
+    }
+
+    fn asset_media_type(&self, path: &str) -> Option<&str> {
+        if path == self.css_path {
+            return Some("text/css");
+        }
+        self.inner.asset_media_type(path)
+    }
+
+    fn load_stylesheet(&self, path: &str) -> Option<Arc<Stylesheet>> {
+        if path == self.css_path {
+            return Some(Arc::new(Stylesheet::parse(&self.css)));
+        }
+        self.inner.load_stylesheet(path)
+    }
+
+    fn font_faces(&self) -> Vec<FontFace> {
+        self.inner.font_faces()
+    }
+
+    fn requires_normalized_export(&self) -> bool {
+        self.inner.requires_normalized_export()
+    }
+
+    fn index_anchor_ids(&self, ids: &[(GlobalNodeId, &str)]) {
+        self.inner.index_anchor_ids(ids)
+    }
+
+    fn resolve_toc(&self) -> Option<Vec<TocEntry>> {
+        self.inner.resolve_toc()
+    }
+
+    fn resolve_href(&self, from_chapter: ChapterId, href: &str) -> Option<AnchorTarget> {
+        self.inner.resolve_href(from_chapter, href)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_style_ignores_spacing_case_and_trailing_semicolon() {
+        assert_eq!(
+            canonical_style(b"Color:red;  font-style : italic;"),
+            canonical_style(b"color: red; font-style: italic")
+        );
+        assert_eq!(canonical_style(b"bogus"), None);
+    }
+
+    #[test]
+    fn split_declarations_keeps_data_uris_whole() {
+        assert_eq!(
+            split_declarations("background: url(data:image/png;base64,AA); color: red"),
+            vec!["background: url(data:image/png;base64,AA)", " color: red"]
+        );
+    }
+
+    #[test]
+    fn rewrite_replaces_style_and_appends_to_existing_class() {
+        let html = b"<html><head><title>t</title></head><body>\
+            <p style=\"color: red\">a</p><p class=\"x\" style=\"color:red\">b</p>\
+            <p style='color: red' class=\"y\"/></body></html>";
+        let classes = HashMap::from([("color: red".to_string(), "boko-s1".to_string())]);
+        let mut replaced = 0;
+        let out = rewrite_chapter(html, &classes, "boko-styles.css", &mut replaced).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(replaced, 3);
+        assert!(!out.contains("style="), "{out}");
+        assert!(out.contains("<p class=\"boko-s1\">a</p>"), "{out}");
+        assert!(out.contains("<p class=\"x boko-s1\">b</p>"), "{out}");
+        assert!(out.contains("<p class=\"y boko-s1\"/>"), "{out}");
+        assert!(out.contains("href=\"boko-styles.css\"/>\n</head>"), "{out}");
+    }
+
+    #[test]
+    fn important_properties_come_from_innermost_blocks() {
+        let mut important = HashSet::new();
+        collect_important_properties(
+            "/* a { color: red !important } */ @media print { p { margin: 0 ! IMPORTANT; } }\
+             h1 { color: blue; font-size: 2em !important }",
+            &mut important,
+        );
+        assert_eq!(
+            important,
+            HashSet::from(["margin".to_string(), "font-size".to_string()])
+        );
+        assert!(properties_overlap("margin", "margin-left"));
+        assert!(properties_overlap("-webkit-hyphens", "hyphens"));
+        assert!(properties_overlap("font", "line-height"));
+        assert!(!properties_overlap("margin", "padding-left"));
+    }
+
+    #[test]
+    fn relative_href_walks_up_shared_directories() {
+        assert_eq!(relative_href("OEBPS/a.xhtml", "OEBPS/s.css"), "s.css");
+        assert_eq!(
+            relative_href("OEBPS/text/a.xhtml", "OEBPS/s.css"),
+            "../s.css"
+        );
+        assert_eq!(relative_href("a.xhtml", "css/s.css"), "css/s.css");
+    }
+}
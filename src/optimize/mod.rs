@@ -8,14 +8,30 @@
 //! references (chapter `src` attributes, the cover path) on the way out.
 //! Exporters need no knowledge of any of it, and passes compose by wrapping
 //! the previous pass's view.
-
-use std::collections::HashMap;
+//!
+//! [`Book::prune_unused_assets`](crate::Book::prune_unused_assets) uses the
+//! same overlay approach to hide assets nothing references, and
+//! [`Book::merge_css_classes`](crate::Book::merge_css_classes) to promote
+//! repeated inline styles into shared classes.
+//! [`Book::linearize_tables`](crate::Book::linearize_tables) overlays an IR
+//! rewrite instead, serving chapters whose key/value tables are flattened
+//! for narrow screens. [`Book::embed_fonts`](crate::Book::embed_fonts)
+//! either subsets fonts as a pass or hides them behind an overlay.
+
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::Arc;
 
 use crate::dom::Stylesheet;
 use crate::import::{ChapterId, Importer, SpineEntry};
-use crate::model::{AnchorTarget, Chapter, FontFace, Landmark, Metadata, TocEntry};
+use crate::model::{AnchorTarget, Chapter, FontFace, GlobalNodeId, Landmark, Metadata, TocEntry};
+
+mod fonts;
+mod inline_styles;
+mod tables;
+
+pub use fonts::FontEmbedding;
+pub use inline_styles::MergeCssReport;
 
 /// What one optimization pass changed.
 #[derive(Debug, Clone)]
@@ -267,6 +283,16 @@ impl Importer for OptimizedImporter {
         self.inner.load_asset(path)
     }
 
+    fn contains_asset(&self, path: &str) -> bool {
+        crate::import::overlay_contains_asset(&self.assets, self.inner.as_ref(), path)
+    }
+
+    fn asset_media_type(&self, path: &str) -> Option<&str> {
+        // A renamed asset's new path is unknown to the inner backend, so its
+        // (possibly converted) bytes get sniffed instead.
+        self.inner.asset_media_type(path)
+    }
+
     fn load_stylesheet(&self, path: &str) -> Option<Arc<Stylesheet>> {
         self.inner.load_stylesheet(path)
     }
@@ -282,8 +308,8 @@ impl Importer for OptimizedImporter {
         !self.renames.is_empty() || self.inner.requires_normalized_export()
     }
 
-    fn index_anchors(&self, chapters: &[(ChapterId, Arc<Chapter>)]) {
-        self.inner.index_anchors(chapters)
+    fn index_anchor_ids(&self, ids: &[(GlobalNodeId, &str)]) {
+        self.inner.index_anchor_ids(ids)
     }
 
     fn resolve_toc(&self) -> Option<Vec<TocEntry>> {
@@ -295,8 +321,194 @@ impl Importer for OptimizedImporter {
     }
 }
 
-/// Placeholder backend used only while swapping in the optimized wrapper.
-struct EmptyBackend(Metadata);
+/// Importer wrapper that hides assets nothing in the book references.
+/// Everything else is served unchanged by the inner backend.
+pub(crate) struct PrunedImporter {
+    inner: Box<dyn Importer>,
+    /// Inner asset list minus the pruned paths, same order.
+    assets: Vec<String>,
+}
+
+impl PrunedImporter {
+    /// Build the overlay, returning it with the number of assets pruned.
+    pub(crate) fn apply(inner: Box<dyn Importer>) -> (Self, usize) {
+        let all: Vec<String> = inner.list_assets().to_vec();
+        let keep = reachable_assets(inner.as_ref(), &all);
+        let assets: Vec<String> = all.into_iter().filter(|p| keep.contains(p)).collect();
+        let pruned = inner.list_assets().len() - assets.len();
+        (Self { inner, assets }, pruned)
+    }
+}
+
+/// Assets reachable from the book: chapter `src`/`href` references, the
+/// cover, TOC targets, `@font-face` sources, and anything named in CSS or
+/// raw chapter markup. Markup documents, stylesheets, and packaging files
+/// are always kept — the IR doesn't record every way they're linked.
+fn reachable_assets(backend: &dyn Importer, assets: &[String]) -> HashSet<String> {
+    let strip_fragment = |s: &str| s.split('#').next().unwrap_or(s).to_string();
+
+    let mut referenced: HashSet<String> = HashSet::new();
+    if let Some(cover) = &backend.metadata().cover_image {
+        referenced.insert(cover.clone());
+    }
+    fn collect_toc(entries: &[TocEntry], out: &mut HashSet<String>) {
+        for entry in entries {
+            out.insert(
+                entry
+                    .href
+                    .split('#')
+                    .next()
+                    .unwrap_or(&entry.href)
+                    .to_string(),
+            );
+            collect_toc(&entry.children, out);
+        }
+    }
+    collect_toc(backend.toc(), &mut referenced);
+
+    // Names mentioned anywhere in CSS, raw chapter markup, or font sources.
+    // Matched by basename: conservative, but relative-path resolution inside
+    // arbitrary markup isn't worth getting wrong here.
+    let mut mentioned = css_referenced_text(backend);
+    for face in backend.font_faces() {
+        mentioned.push_str(&face.src);
+        mentioned.push('\n');
+    }
+
+    for entry in backend.spine() {
+        if let Some(source) = backend.source_id(entry.id) {
+            referenced.insert(source.to_string());
+        }
+        if let Ok(raw) = backend.load_raw(entry.id) {
+            mentioned.push_str(&String::from_utf8_lossy(&raw));
+        }
+        let Ok(chapter) = backend.load_chapter(entry.id) else {
+            continue;
+        };
+        for node in chapter.iter_dfs() {
+            for target in [chapter.semantics.src(node), chapter.semantics.href(node)]
+                .into_iter()
+                .flatten()
+            {
+                referenced.insert(strip_fragment(target));
+            }
+        }
+    }
+
+    assets
+        .iter()
+        .filter(|path| {
+            let basename = path.rsplit('/').next().unwrap_or(path);
+            is_structural_asset(path) || referenced.contains(*path) || mentioned.contains(basename)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Markup, stylesheets, and container/packaging files, which pruning never
+/// touches.
+fn is_structural_asset(path: &str) -> bool {
+    if path == "mimetype" || path.starts_with("META-INF/") {
+        return true;
+    }
+    let ext = path.rsplit_once('.').map(|(_, e)| e).unwrap_or("");
+    ["css", "xhtml", "html", "htm", "xml", "opf", "ncx", "smil"]
+        .iter()
+        .any(|e| ext.eq_ignore_ascii_case(e))
+}
+
+impl Importer for PrunedImporter {
+    fn open(_path: &Path) -> crate::Result<Self>
+    where
+        Self: Sized,
+    {
+        Err(crate::Error::UnsupportedFormat {
+            detail: "PrunedImporter wraps an existing backend".to_string(),
+        })
+    }
+
+    fn metadata(&self) -> &Metadata {
+        self.inner.metadata()
+    }
+
+    fn toc(&self) -> &[TocEntry] {
+        self.inner.toc()
+    }
+
+    fn landmarks(&self) -> &[Landmark] {
+        self.inner.landmarks()
+    }
+
+    fn spine(&self) -> &[SpineEntry] {
+        self.inner.spine()
+    }
+
+    fn load_chapter(&self, id: ChapterId) -> crate::Result<Chapter> {
+        self.inner.load_chapter(id)
+    }
+
+    fn load_chapters(&self, ids: &[ChapterId]) -> Vec<crate::Result<Chapter>> {
+        self.inner.load_chapters(ids)
+    }
+
+    fn source_id(&self, id: ChapterId) -> Option<&str> {
+        self.inner.source_id(id)
+    }
+
+    fn load_raw(&self, id: ChapterId) -> crate::Result<Vec<u8>> {
+        self.inner.load_raw(id)
+    }
+
+    fn list_assets(&self) -> &[String] {
+        &self.assets
+    }
+
+    fn load_asset(&self, path: &str) -> crate::Result<Vec<u8>> {
+        if !self.assets.iter().any(|p| p == path)
+            && self.inner.list_assets().iter().any(|p| p == path)
+        {
+            return Err(crate::Error::NotFound {
+                what: format!("asset {path} (pruned)"),
+            });
+        }
+        self.inner.load_asset(path)
+    }
+
+    fn contains_asset(&self, path: &str) -> bool {
+        crate::import::overlay_contains_asset(&self.assets, self.inner.as_ref(), path)
+    }
+
+    fn asset_media_type(&self, path: &str) -> Option<&str> {
+        self.inner.asset_media_type(path)
+    }
+
+    fn load_stylesheet(&self, path: &str) -> Option<Arc<Stylesheet>> {
+        self.inner.load_stylesheet(path)
+    }
+
+    fn font_faces(&self) -> Vec<FontFace> {
+        self.inner.font_faces()
+    }
+
+    fn requires_normalized_export(&self) -> bool {
+        self.inner.requires_normalized_export()
+    }
+
+    fn index_anchor_ids(&self, ids: &[(GlobalNodeId, &str)]) {
+        self.inner.index_anchor_ids(ids)
+    }
+
+    fn resolve_toc(&self) -> Option<Vec<TocEntry>> {
+        self.inner.resolve_toc()
+    }
+
+    fn resolve_href(&self, from_chapter: ChapterId, href: &str) -> Option<AnchorTarget> {
+        self.inner.resolve_href(from_chapter, href)
+    }
+}
+
+/// Placeholder backend used only while swapping in a wrapper backend.
+pub(crate) struct EmptyBackend(pub(crate) Metadata);
 
 impl Importer for EmptyBackend {
     fn open(_path: &Path) -> crate::Result<Self>
@@ -365,6 +577,88 @@ impl crate::Book {
         self.replace_backend(backend);
         report
     }
+    /// Drop assets nothing in the book references, returning how many were
+    /// removed. Reachability covers chapter `src`/`href` attributes, the
+    /// cover image, TOC targets, `@font-face` sources, and any name that
+    /// appears in a stylesheet or raw chapter markup; stylesheets, markup
+    /// documents, and packaging files are never pruned.
+    pub fn prune_unused_assets(&mut self) -> usize {
+        let backend = self.replace_backend(Box::new(EmptyBackend(Metadata::default())));
+        let (pruned, count) = PrunedImporter::apply(backend);
+        if count == 0 {
+            self.replace_backend(pruned.inner);
+        } else {
+            self.replace_backend(Box::new(pruned));
+        }
+        count
+    }
+
+    /// Promote inline `style` attributes that repeat across the book into
+    /// shared classes in a generated stylesheet, rewriting the chapters'
+    /// markup to reference them. Shrinks books whose formatting is all
+    /// inline; declaration lists used only once stay inline. Books whose raw
+    /// chapters aren't HTML (KFX) are left unchanged.
+    pub fn merge_css_classes(&mut self) -> MergeCssReport {
+        let backend = self.replace_backend(Box::new(EmptyBackend(Metadata::default())));
+        match inline_styles::InlineStyleImporter::apply(backend) {
+            Ok((merged, report)) => {
+                self.replace_backend(Box::new(merged));
+                report
+            }
+            Err(backend) => {
+                self.replace_backend(backend);
+                MergeCssReport::default()
+            }
+        }
+    }
+
+    /// Rewrite simple two-column tables as `term: description` paragraphs
+    /// when chapters are loaded, for reflowable output on narrow screens.
+    /// Only tables where every row is one label cell and one value cell of
+    /// inline content qualify; tables with more columns, spanning cells, a
+    /// column-header row, or block content in a cell are left intact.
+    ///
+    /// The rewrite applies to the IR, so every export goes through the
+    /// normalized pipeline afterwards.
+    pub fn linearize_tables(&mut self) {
+        let backend = self.replace_backend(Box::new(EmptyBackend(Metadata::default())));
+        self.replace_backend(Box::new(tables::LinearizedTablesImporter {
+            inner: backend,
+        }));
+    }
+
+    /// Choose how embedded fonts reach the output, for every export format.
+    ///
+    /// [`FontEmbedding::None`] drops every font asset along with the
+    /// `@font-face` rules that load them, so readers fall back to their own
+    /// fonts. [`FontEmbedding::Subset`] empties the outlines of TrueType
+    /// glyphs only unused characters map to, keeping a font only when that
+    /// shrinks it. [`FontEmbedding::Full`] leaves fonts alone.
+    ///
+    /// The report counts the fonts dropped or subset and the bytes saved.
+    pub fn embed_fonts(&mut self, embedding: FontEmbedding) -> PassReport {
+        let backend = self.replace_backend(Box::new(EmptyBackend(Metadata::default())));
+        let (wrapped, report): (Box<dyn Importer>, PassReport) = match embedding {
+            FontEmbedding::None => {
+                let (fontless, report) = fonts::FontlessImporter::apply(backend);
+                (Box::new(fontless), report)
+            }
+            FontEmbedding::Subset => {
+                let (subset, report) = OptimizedImporter::apply(backend, &fonts::SubsetFonts);
+                (Box::new(subset), report)
+            }
+            FontEmbedding::Full => (
+                backend,
+                PassReport {
+                    pass: "fonts",
+                    assets_changed: 0,
+                    bytes_saved: 0,
+                },
+            ),
+        };
+        self.replace_backend(wrapped);
+        report
+    }
 }
 
 /// The optimization passes themselves.
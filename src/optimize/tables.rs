@@ -0,0 +1,294 @@
+//! Linearizing key/value tables for narrow screens.
+//!
+//! Two-column tables are usually label/value lists ("Born | 1869"), which
+//! render poorly on phones and e-ink. [`crate::Book::linearize_tables`]
+//! wraps the backend so every chapter's simple two-column tables come back
+//! as one `term: description` paragraph per row. Anything more involved —
+//! extra columns, spans, a column-header row, block content in a cell — is
+//! real tabular data and stays a table.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::dom::Stylesheet;
+use crate::dom::optimize::is_inline_role;
+use crate::import::{ChapterId, Importer, SpineEntry};
+use crate::model::{
+    AnchorTarget, Chapter, FontFace, GlobalNodeId, Landmark, Metadata, Node, NodeId, Role, TocEntry,
+};
+use crate::style::{ComputedStyle, StyleId};
+
+/// Importer wrapper serving chapters with their key/value tables
+/// linearized. Everything else is served unchanged by the inner backend.
+pub(crate) struct LinearizedTablesImporter {
+    pub(crate) inner: Box<dyn Importer>,
+}
+
+impl Importer for LinearizedTablesImporter {
+    fn open(_path: &Path) -> crate::Result<Self>
+    where
+        Self: Sized,
+    {
+        Err(crate::Error::UnsupportedFormat {
+            detail: "LinearizedTablesImporter wraps an existing backend".to_string(),
+        })
+    }
+
+    fn metadata(&self) -> &Metadata {
+        self.inner.metadata()
+    }
+
+    fn toc(&self) -> &[TocEntry] {
+        self.inner.toc()
+    }
+
+    fn landmarks(&self) -> &[Landmark] {
+        self.inner.landmarks()
+    }
+
+    fn spine(&self) -> &[SpineEntry] {
+        self.inner.spine()
+    }
+
+    fn load_chapter(&self, id: ChapterId) -> crate::Result<Chapter> {
+        let mut chapter = self.inner.load_chapter(id)?;
+        linearize_tables(&mut chapter);
+        Ok(chapter)
+    }
+
+    fn load_chapters(&self, ids: &[ChapterId]) -> Vec<crate::Result<Chapter>> {
+        self.inner
+            .load_chapters(ids)
+            .into_iter()
+            .map(|res| {
+                res.map(|mut chapter| {
+                    linearize_tables(&mut chapter);
+                    chapter
+                })
+            })
+            .collect()
+    }
+
+    fn source_id(&self, id: ChapterId) -> Option<&str> {
+        self.inner.source_id(id)
+    }
+
+    fn load_raw(&self, id: ChapterId) -> crate::Result<Vec<u8>> {
+        self.inner.load_raw(id)
+    }
+
+    fn list_assets(&self) -> &[String] {
+        self.inner.list_assets()
+    }
+
+    fn load_asset(&self, path: &str) -> crate::Result<Vec<u8>> {
+        self.inner.load_asset(path)
+    }
+
+    fn contains_asset(&self, path: &str) -> bool {
+        self.inner.contains_asset(path)
+    }
+
+    fn asset_media_type(&self, path: &str) -> Option<&str> {
+        self.inner.asset_media_type(path)
+    }
+
+    fn load_stylesheet(&self, path: &str) -> Option<Arc<Stylesheet>> {
+        self.inner.load_stylesheet(path)
+    }
+
+    fn font_faces(&self) -> Vec<FontFace> {
+        self.inner.font_faces()
+    }
+
+    fn requires_normalized_export(&self) -> bool {
+        // The rewrite happens in the IR; raw passthrough would ship the
+        // original tables.
+        true
+    }
+
+    fn index_anchor_ids(&self, ids: &[(GlobalNodeId, &str)]) {
+        self.inner.index_anchor_ids(ids)
+    }
+
+    fn resolve_toc(&self) -> Option<Vec<TocEntry>> {
+        self.inner.resolve_toc()
+    }
+
+    fn resolve_href(&self, from_chapter: ChapterId, href: &str) -> Option<AnchorTarget> {
+        self.inner.resolve_href(from_chapter, href)
+    }
+}
+
+/// Rewrite every key/value table in `chapter` as a container of
+/// `term: description` paragraphs, returning how many were rewritten.
+///
+/// Node ids are stable: each row becomes the paragraph and its cells become
+/// inline runs inside it, so ids and anchors on them still resolve.
+pub(crate) fn linearize_tables(chapter: &mut Chapter) -> usize {
+    let tables: Vec<NodeId> = chapter
+        .iter_dfs()
+        .filter(|&id| chapter.node(id).is_some_and(|n| n.role == Role::Table))
+        .collect();
+    let mut count = 0;
+    for table in tables {
+        if let Some((captions, rows)) = key_value_rows(chapter, table) {
+            linearize(chapter, table, &captions, &rows);
+            count += 1;
+        }
+    }
+    count
+}
+
+/// The captions and `[term, description]` cell pairs of a key/value table,
+/// or `None` when the table is anything more complex.
+fn key_value_rows(chapter: &Chapter, table: NodeId) -> Option<(Vec<NodeId>, Vec<[NodeId; 2]>)> {
+    let mut captions = Vec::new();
+    let mut row_ids = Vec::new();
+    for child in chapter.children(table) {
+        match chapter.node(child)?.role {
+            Role::Caption => captions.push(child),
+            Role::TableRow => row_ids.push(child),
+            Role::TableHead | Role::TableBody => row_ids.extend(chapter.children(child)),
+            _ => return None,
+        }
+    }
+
+    let mut rows = Vec::with_capacity(row_ids.len());
+    for row in row_ids {
+        if chapter.node(row)?.role != Role::TableRow {
+            return None;
+        }
+        let cells: Vec<NodeId> = chapter.children(row).collect();
+        let [term, description] = cells[..] else {
+            return None;
+        };
+        for cell in [term, description] {
+            let spans = [
+                chapter.semantics.row_span(cell),
+                chapter.semantics.col_span(cell),
+            ];
+            if chapter.node(cell)?.role != Role::TableCell
+                || spans.iter().any(|s| s.is_some_and(|s| s > 1))
+                || !inline_only(chapter, cell)
+            {
+                return None;
+            }
+        }
+        // A row of column headers means the columns are data, not labels.
+        if chapter.semantics.is_header_cell(term) && chapter.semantics.is_header_cell(description) {
+            return None;
+        }
+        rows.push([term, description]);
+    }
+    (!rows.is_empty()).then_some((captions, rows))
+}
+
+/// Whether everything under `node` is inline content.
+fn inline_only(chapter: &Chapter, node: NodeId) -> bool {
+    chapter.children(node).all(|child| {
+        chapter.node(child).is_some_and(|n| is_inline_role(n.role)) && inline_only(chapter, child)
+    })
+}
+
+fn linearize(chapter: &mut Chapter, table: NodeId, captions: &[NodeId], rows: &[[NodeId; 2]]) {
+    // Detach everything first; `append_child` rebuilds the sibling chains.
+    for &node in captions.iter().chain(rows.iter().flatten()) {
+        if let Some(n) = chapter.node_mut(node) {
+            n.next_sibling = None;
+        }
+    }
+    if let Some(n) = chapter.node_mut(table) {
+        n.role = Role::Container;
+        n.style = StyleId::DEFAULT;
+        n.first_child = None;
+        n.last_child = None;
+    }
+
+    for &caption in captions {
+        if let Some(n) = chapter.node_mut(caption) {
+            n.role = Role::Paragraph;
+        }
+        chapter.append_child(table, caption);
+    }
+
+    for &[term, description] in rows {
+        let row = chapter
+            .node(term)
+            .and_then(|n| n.parent)
+            .expect("cells hang off their row");
+        if let Some(n) = chapter.node_mut(row) {
+            n.role = Role::Paragraph;
+            n.style = StyleId::DEFAULT;
+            n.first_child = None;
+            n.last_child = None;
+            n.next_sibling = None;
+        }
+        for cell in [term, description] {
+            // Keep the cell's text styling (a `<th>` label stays bold) but
+            // none of its box: borders and padding made sense in a grid.
+            let style = chapter.node(cell).map(|n| n.style).unwrap_or_default();
+            let text_style = chapter
+                .styles
+                .get(style)
+                .map(|s| ComputedStyle {
+                    font_weight: s.font_weight,
+                    font_style: s.font_style,
+                    color: s.color,
+                    ..ComputedStyle::default()
+                })
+                .unwrap_or_default();
+            let text_style = chapter.styles.intern(text_style);
+            if let Some(n) = chapter.node_mut(cell) {
+                n.role = Role::Inline;
+                n.style = text_style;
+            }
+        }
+        chapter.append_child(row, term);
+        let separator = chapter.append_text(": ");
+        let separator = chapter.alloc_node(Node::text(separator));
+        chapter.append_child(row, separator);
+        chapter.append_child(row, description);
+        chapter.append_child(table, row);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compile(body: &str) -> Chapter {
+        let html = format!("<html><body>{body}</body></html>");
+        crate::compile_html(&html, &[])
+    }
+
+    fn roles(chapter: &Chapter) -> Vec<Role> {
+        chapter
+            .iter_dfs()
+            .filter_map(|id| chapter.node(id).map(|n| n.role))
+            .collect()
+    }
+
+    #[test]
+    fn only_simple_two_column_tables_linearize() {
+        for (body, expected) in [
+            ("<table><tr><td>a</td><td>b</td></tr></table>", 1),
+            ("<table><tr><th>Born</th><td>1869</td></tr></table>", 1),
+            ("<table><tr><td>a</td><td>b</td><td>c</td></tr></table>", 0),
+            (
+                "<table><tr><th>Key</th><th>Value</th></tr><tr><td>a</td><td>b</td></tr></table>",
+                0,
+            ),
+            (r#"<table><tr><td colspan="2">a</td></tr></table>"#, 0),
+            (
+                "<table><tr><td><p>a</p><p>b</p></td><td>c</td></tr></table>",
+                0,
+            ),
+        ] {
+            let mut chapter = compile(body);
+            assert_eq!(linearize_tables(&mut chapter), expected, "{body}");
+            let has_table = roles(&chapter).contains(&Role::Table);
+            assert_eq!(has_table, expected == 0, "{body}");
+        }
+    }
+}
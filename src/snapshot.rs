@@ -0,0 +1,270 @@
+//! Serde support for [`Book`] (the `serde` feature).
+//!
+//! A `Book` is a handle over a format backend, so it serializes as a
+//! snapshot: metadata, navigation, the spine with each chapter's source
+//! path and raw bytes, and every asset as a [`Resource`]. Deserializing
+//! rebuilds a `Book` over `SnapshotImporter`, an in-memory backend that
+//! serves the captured bytes, so chapters compile and export exactly as
+//! they would from the original file.
+//!
+//! Chapters are captured as raw source bytes. For books whose raw content
+//! isn't HTML (KFX), metadata, navigation, and resources round-trip, but the
+//! snapshot's chapters can't be recompiled to IR.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::RwLock;
+
+use base64::Engine;
+use serde::de::Error as _;
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::Book;
+use crate::import::{ChapterId, Importer, SpineEntry, resolve_path_based_href};
+use crate::model::{AnchorTarget, GlobalNodeId, Landmark, Metadata, Resource, TocEntry};
+use crate::util::{guess_media_type, intern_media_type};
+
+/// Binary data as a standard base64 string.
+mod base64_bytes {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&base64::engine::general_purpose::STANDARD.encode(data))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        base64::engine::general_purpose::STANDARD
+            .decode(encoded.as_bytes())
+            .map_err(D::Error::custom)
+    }
+}
+
+/// Wire form of a [`Resource`]: the media type as an owned string, the
+/// data base64-encoded.
+#[derive(Serialize, Deserialize)]
+struct ResourceRepr {
+    media_type: String,
+    #[serde(with = "base64_bytes")]
+    data: Vec<u8>,
+}
+
+impl Serialize for Resource {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Repr<'a> {
+            media_type: &'a str,
+            #[serde(with = "base64_bytes")]
+            data: &'a [u8],
+        }
+        Repr {
+            media_type: self.media_type,
+            data: &self.data,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Resource {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = ResourceRepr::deserialize(deserializer)?;
+        Ok(Resource {
+            data: repr.data,
+            media_type: intern_media_type(&repr.media_type),
+        })
+    }
+}
+
+/// One spine entry with the chapter's source path and raw bytes.
+#[derive(Serialize, Deserialize)]
+struct SnapshotChapter {
+    #[serde(flatten)]
+    entry: SpineEntry,
+    source: Option<String>,
+    #[serde(with = "base64_bytes")]
+    content: Vec<u8>,
+}
+
+/// Wire form of a [`Book`].
+#[derive(Serialize, Deserialize)]
+struct BookSnapshot {
+    metadata: Metadata,
+    #[serde(default)]
+    toc: Vec<TocEntry>,
+    #[serde(default)]
+    landmarks: Vec<Landmark>,
+    #[serde(default)]
+    spine: Vec<SnapshotChapter>,
+    /// Assets by archive path, in the backend's listing order.
+    #[serde(default)]
+    resources: Vec<(String, Resource)>,
+}
+
+impl Serialize for Book {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut spine = Vec::with_capacity(self.spine().len());
+        for entry in self.spine() {
+            spine.push(SnapshotChapter {
+                entry: entry.clone(),
+                source: self.source_id(entry.id).map(str::to_string),
+                content: self.load_raw(entry.id).map_err(S::Error::custom)?,
+            });
+        }
+
+        let mut resources = Vec::with_capacity(self.list_assets().len());
+        for path in self.list_assets() {
+            let data = self.load_asset(path).map_err(S::Error::custom)?;
+            let resource = Resource {
+                data,
+                media_type: self
+                    .media_type_override(path)
+                    .unwrap_or_else(|| guess_media_type(path)),
+            };
+            resources.push((path.clone(), resource));
+        }
+
+        BookSnapshot {
+            metadata: self.metadata().clone(),
+            toc: self.toc().to_vec(),
+            landmarks: self.landmarks().to_vec(),
+            spine,
+            resources,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Book {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let snapshot = BookSnapshot::deserialize(deserializer)?;
+        Ok(Book::from_backend(Box::new(SnapshotImporter::new(
+            snapshot,
+        ))))
+    }
+}
+
+/// In-memory backend over a deserialized [`BookSnapshot`].
+pub(crate) struct SnapshotImporter {
+    metadata: Metadata,
+    toc: Vec<TocEntry>,
+    landmarks: Vec<Landmark>,
+    spine: Vec<SpineEntry>,
+    sources: HashMap<ChapterId, String>,
+    path_to_chapter: HashMap<String, ChapterId>,
+    chapters: HashMap<ChapterId, Vec<u8>>,
+    assets: Vec<String>,
+    resources: BTreeMap<String, Resource>,
+    /// `path#id` → node, built by `index_anchors`.
+    anchor_map: RwLock<HashMap<String, GlobalNodeId>>,
+}
+
+impl SnapshotImporter {
+    fn new(snapshot: BookSnapshot) -> Self {
+        let mut spine = Vec::with_capacity(snapshot.spine.len());
+        let mut sources = HashMap::new();
+        let mut chapters = HashMap::new();
+        for chapter in snapshot.spine {
+            let id = chapter.entry.id;
+            if let Some(source) = chapter.source {
+                sources.insert(id, source);
+            }
+            chapters.insert(id, chapter.content);
+            spine.push(chapter.entry);
+        }
+        let path_to_chapter = sources.iter().map(|(id, p)| (p.clone(), *id)).collect();
+        let assets = snapshot.resources.iter().map(|(p, _)| p.clone()).collect();
+        Self {
+            metadata: snapshot.metadata,
+            toc: snapshot.toc,
+            landmarks: snapshot.landmarks,
+            spine,
+            sources,
+            path_to_chapter,
+            chapters,
+            assets,
+            resources: snapshot.resources.into_iter().collect(),
+            anchor_map: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl Importer for SnapshotImporter {
+    fn open(_path: &Path) -> crate::Result<Self>
+    where
+        Self: Sized,
+    {
+        Err(crate::Error::UnsupportedFormat {
+            detail: "SnapshotImporter is built from a deserialized book".to_string(),
+        })
+    }
+
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn toc(&self) -> &[TocEntry] {
+        &self.toc
+    }
+
+    fn landmarks(&self) -> &[Landmark] {
+        &self.landmarks
+    }
+
+    fn spine(&self) -> &[SpineEntry] {
+        &self.spine
+    }
+
+    fn source_id(&self, id: ChapterId) -> Option<&str> {
+        self.sources.get(&id).map(String::as_str)
+    }
+
+    fn load_raw(&self, id: ChapterId) -> crate::Result<Vec<u8>> {
+        self.chapters
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| crate::Error::NotFound {
+                what: format!("chapter {}", id.0),
+            })
+    }
+
+    fn list_assets(&self) -> &[String] {
+        &self.assets
+    }
+
+    fn load_asset(&self, path: &str) -> crate::Result<Vec<u8>> {
+        self.resources
+            .get(path)
+            .map(|r| r.data.clone())
+            .ok_or_else(|| crate::Error::NotFound {
+                what: format!("asset {path}"),
+            })
+    }
+
+    fn asset_media_type(&self, path: &str) -> Option<&str> {
+        self.resources.get(path).map(|r| r.media_type)
+    }
+
+    fn index_anchor_ids(&self, ids: &[(GlobalNodeId, &str)]) {
+        let mut anchor_map = HashMap::new();
+        for &(node, id) in ids {
+            let Some(chapter_path) = self.source_id(node.chapter) else {
+                continue;
+            };
+            anchor_map.insert(format!("{}#{}", chapter_path, id), node);
+        }
+        if let Ok(mut map) = self.anchor_map.write() {
+            *map = anchor_map;
+        }
+    }
+
+    fn resolve_href(&self, from_chapter: ChapterId, href: &str) -> Option<AnchorTarget> {
+        let from_path = self.source_id(from_chapter)?;
+        resolve_path_based_href(
+            from_path,
+            href,
+            |p| self.path_to_chapter.get(p).copied(),
+            |k| self.anchor_map.read().ok().and_then(|m| m.get(k).copied()),
+        )
+    }
+}
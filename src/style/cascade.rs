@@ -11,8 +11,9 @@ use selectors::bloom::BloomFilter;
 use selectors::context::{MatchingContext, QuirksMode, SelectorCaches};
 use selectors::parser::{AncestorHashes, Component, Selector};
 
-use super::declaration::Declaration;
+use super::declaration::{ColorProperty, CssWideKeyword, Declaration};
 use super::parse::{CssRule, Origin, Specificity, Stylesheet};
+use super::properties::{Color, Length, VerticalAlign};
 use super::style_pool::StylePool;
 use super::types::ComputedStyle;
 use crate::dom::element_ref::{BokoSelectors, ElementRef};
@@ -38,7 +39,8 @@ struct MatchedDecl {
 ///
 /// CSS inherited properties include:
 /// - color, font-*, line-height, text-align, text-indent
-/// - letter-spacing, word-spacing, hyphens, text-transform
+/// - letter-spacing, word-spacing, hyphens, text-transform, writing-mode,
+///   direction
 /// - list-style-*, visibility
 ///
 /// Non-inherited properties (width, height, margin, padding, display, etc.)
@@ -48,6 +50,7 @@ pub(crate) fn inherit_from_parent(parent: &ComputedStyle) -> ComputedStyle {
         // Font properties (inherited)
         font_size: parent.font_size,
         font_size_abs: parent.font_size_abs,
+        font_size_adjust: parent.font_size_adjust,
         font_weight: parent.font_weight,
         font_style: parent.font_style,
         font_variant: parent.font_variant,
@@ -57,10 +60,13 @@ pub(crate) fn inherit_from_parent(parent: &ComputedStyle) -> ComputedStyle {
         text_align: parent.text_align,
         text_indent: parent.text_indent,
         line_height: parent.line_height,
+        line_height_inherited: parent.line_height != Length::Auto,
         letter_spacing: parent.letter_spacing,
         word_spacing: parent.word_spacing,
         text_transform: parent.text_transform,
         hyphens: parent.hyphens,
+        writing_mode: parent.writing_mode,
+        direction: parent.direction,
         // Text decoration (inherited in some contexts)
         text_decoration_underline: parent.text_decoration_underline,
         text_decoration_line_through: parent.text_decoration_line_through,
@@ -70,6 +76,7 @@ pub(crate) fn inherit_from_parent(parent: &ComputedStyle) -> ComputedStyle {
         // List properties (inherited, but only apply to display:list-item)
         list_style_type: parent.list_style_type,
         list_style_position: parent.list_style_position,
+        list_style_image: parent.list_style_image.clone(),
         // Other inherited properties
         visibility: parent.visibility,
         language: parent.language.clone(),
@@ -78,6 +85,177 @@ pub(crate) fn inherit_from_parent(parent: &ComputedStyle) -> ComputedStyle {
     }
 }
 
+/// Copy the fields behind CSS property `name` (a longhand or shorthand)
+/// from `src` into `dst`. Returns `false` for properties boko doesn't model.
+fn copy_property(dst: &mut ComputedStyle, src: &ComputedStyle, name: &str) -> bool {
+    macro_rules! copy {
+        ($($field:ident),+) => {{
+            $(dst.$field = src.$field.clone();)+
+        }};
+    }
+    match name {
+        "color" => copy!(color),
+        "background-color" | "background" => copy!(background_color),
+        "font-family" => copy!(font_family),
+        "font-size" => copy!(font_size),
+        "font-size-adjust" => copy!(font_size_adjust),
+        "font-weight" => copy!(font_weight),
+        "font-style" => copy!(font_style),
+        "font-variant" | "font-variant-caps" => copy!(font_variant),
+        "font" => copy!(
+            font_family,
+            font_size,
+            font_weight,
+            font_style,
+            font_variant,
+            line_height
+        ),
+        "text-align" => copy!(text_align),
+        "text-indent" => copy!(text_indent),
+        "line-height" => copy!(line_height),
+        "letter-spacing" => copy!(letter_spacing),
+        "word-spacing" => copy!(word_spacing),
+        "text-transform" => copy!(text_transform),
+        "hyphens" | "-epub-hyphens" | "-webkit-hyphens" | "-moz-hyphens" => copy!(hyphens),
+        "writing-mode" | "-epub-writing-mode" | "-webkit-writing-mode" => copy!(writing_mode),
+        "direction" => copy!(direction),
+        "white-space" => copy!(white_space),
+        "vertical-align" => copy!(vertical_align, baseline_shift),
+        "text-decoration-line" => {
+            copy!(
+                text_decoration_underline,
+                text_decoration_line_through,
+                overline
+            )
+        }
+        "text-decoration-style" => copy!(underline_style),
+        "text-decoration-color" => copy!(underline_color),
+        "text-decoration" => copy!(
+            text_decoration_underline,
+            text_decoration_line_through,
+            overline,
+            underline_style,
+            underline_color
+        ),
+        "margin-top" => copy!(margin_top),
+        "margin-right" => copy!(margin_right),
+        "margin-bottom" => copy!(margin_bottom),
+        "margin-left" => copy!(margin_left),
+        "margin" => copy!(margin_top, margin_right, margin_bottom, margin_left),
+        "padding-top" => copy!(padding_top),
+        "padding-right" => copy!(padding_right),
+        "padding-bottom" => copy!(padding_bottom),
+        "padding-left" => copy!(padding_left),
+        "padding" => copy!(padding_top, padding_right, padding_bottom, padding_left),
+        "width" => copy!(width),
+        "height" => copy!(height),
+        "max-width" => copy!(max_width),
+        "max-height" => copy!(max_height),
+        "min-width" => copy!(min_width),
+        "min-height" => copy!(min_height),
+        "display" => copy!(display),
+        "float" => copy!(float),
+        "clear" => copy!(clear),
+        "visibility" => copy!(visibility),
+        "box-sizing" => copy!(box_sizing),
+        "overflow" => copy!(overflow),
+        "column-span" => copy!(column_span),
+        "orphans" => copy!(orphans),
+        "widows" => copy!(widows),
+        "word-break" => copy!(word_break),
+        "overflow-wrap" | "word-wrap" => copy!(overflow_wrap),
+        "break-before" | "page-break-before" => copy!(break_before),
+        "break-after" | "page-break-after" => copy!(break_after),
+        "break-inside" | "page-break-inside" => copy!(break_inside),
+        "border-top-style" => copy!(border_style_top),
+        "border-right-style" => copy!(border_style_right),
+        "border-bottom-style" => copy!(border_style_bottom),
+        "border-left-style" => copy!(border_style_left),
+        "border-style" => copy!(
+            border_style_top,
+            border_style_right,
+            border_style_bottom,
+            border_style_left
+        ),
+        "border-top-width" => copy!(border_width_top),
+        "border-right-width" => copy!(border_width_right),
+        "border-bottom-width" => copy!(border_width_bottom),
+        "border-left-width" => copy!(border_width_left),
+        "border-width" => copy!(
+            border_width_top,
+            border_width_right,
+            border_width_bottom,
+            border_width_left
+        ),
+        "border-top-color" => copy!(border_color_top),
+        "border-right-color" => copy!(border_color_right),
+        "border-bottom-color" => copy!(border_color_bottom),
+        "border-left-color" => copy!(border_color_left),
+        "border-color" => copy!(
+            border_color_top,
+            border_color_right,
+            border_color_bottom,
+            border_color_left
+        ),
+        "border-top" => copy!(border_style_top, border_width_top, border_color_top),
+        "border-right" => copy!(border_style_right, border_width_right, border_color_right),
+        "border-bottom" => copy!(
+            border_style_bottom,
+            border_width_bottom,
+            border_color_bottom
+        ),
+        "border-left" => copy!(border_style_left, border_width_left, border_color_left),
+        "border" => copy!(
+            border_style_top,
+            border_style_right,
+            border_style_bottom,
+            border_style_left,
+            border_width_top,
+            border_width_right,
+            border_width_bottom,
+            border_width_left,
+            border_color_top,
+            border_color_right,
+            border_color_bottom,
+            border_color_left
+        ),
+        "border-top-left-radius" => copy!(border_radius_top_left),
+        "border-top-right-radius" => copy!(border_radius_top_right),
+        "border-bottom-left-radius" => copy!(border_radius_bottom_left),
+        "border-bottom-right-radius" => copy!(border_radius_bottom_right),
+        "border-radius" => copy!(
+            border_radius_top_left,
+            border_radius_top_right,
+            border_radius_bottom_left,
+            border_radius_bottom_right
+        ),
+        "list-style-type" => copy!(list_style_type),
+        "list-style-position" => copy!(list_style_position),
+        "list-style-image" => copy!(list_style_image),
+        "list-style" => copy!(list_style_type, list_style_position, list_style_image),
+        "border-collapse" => copy!(border_collapse),
+        "border-spacing" => copy!(border_spacing),
+        "table-layout" => copy!(table_layout),
+        _ => return false,
+    }
+    true
+}
+
+/// The `currentColor`-capable properties a property name covers.
+fn shorthand_color_properties(name: &str) -> &'static [ColorProperty] {
+    use ColorProperty::*;
+    match name {
+        "background-color" | "background" => &[Background],
+        "text-decoration-color" | "text-decoration" => &[TextDecoration],
+        "border-top-color" | "border-top" => &[BorderTop],
+        "border-right-color" | "border-right" => &[BorderRight],
+        "border-bottom-color" | "border-bottom" => &[BorderBottom],
+        "border-left-color" | "border-left" => &[BorderLeft],
+        "border-color" | "border" => &[BorderTop, BorderRight, BorderBottom, BorderLeft],
+        _ => &[],
+    }
+}
+
 /// A reference to a rule as `(stylesheet index, rule index)`. Ordering by this
 /// tuple reproduces CSS source order, which the cascade uses as its final
 /// tiebreak — so candidate rules must be visited in this order.
@@ -394,6 +572,13 @@ pub fn compute_styles_indexed(
     } else {
         ComputedStyle::default()
     };
+    // CSS-wide keywords reset a property to one of three starting points:
+    // `unset` to this inherited-or-initial base, `inherit` to the parent's
+    // value, `initial` to the default. With no parent, `inherit` leaves the
+    // property unset so the reader's own inheritance applies.
+    let base = style.clone();
+    let initial = ComputedStyle::default();
+    let inherited = parent_style.unwrap_or(&initial);
 
     // Apply matched declarations in cascade order. `matched` is sorted with
     // all normal declarations before all `!important` ones, so the inline
@@ -406,9 +591,59 @@ pub fn compute_styles_indexed(
     // verbatim), so resolving the absolute size must multiply only when this
     // element actually declared one — an inherited value keeps the parent's
     // absolute size.
+    //
+    // `current_color` records which color properties were last set to
+    // `currentColor`; they take the element's final `color`, which may be
+    // declared after them, so they're resolved once everything has applied.
+    // `relative_line_height` likewise waits for the final font size.
     let mut font_size_declared = false;
+    let mut current_color = [false; 6];
+    let mut relative_line_height = None;
     let mut apply = |style: &mut ComputedStyle, decl: &Declaration| {
+        if let Declaration::CssWide(name, keyword) = decl {
+            let src = match keyword {
+                CssWideKeyword::Inherit => inherited,
+                CssWideKeyword::Initial => &initial,
+                CssWideKeyword::Unset => &base,
+            };
+            if !copy_property(style, src, name) {
+                return;
+            }
+            if matches!(name.as_str(), "font-size" | "font") {
+                // Inherited or unset, the size keeps the parent's absolute
+                // size; `initial` is the reader default of one root em.
+                font_size_declared = *keyword == CssWideKeyword::Initial;
+                if font_size_declared {
+                    style.font_size = Length::Rem(1.0);
+                }
+            }
+            if matches!(name.as_str(), "line-height" | "font") {
+                relative_line_height = None;
+                style.line_height_inherited =
+                    *keyword != CssWideKeyword::Initial && style.line_height != Length::Auto;
+            }
+            for p in shorthand_color_properties(name) {
+                current_color[*p as usize] = false;
+            }
+            return;
+        }
         font_size_declared |= matches!(decl, Declaration::FontSize(_));
+        match decl {
+            Declaration::LineHeight(_) => {
+                relative_line_height = None;
+                style.line_height_inherited = false;
+            }
+            Declaration::LineHeightRelative(factor) => {
+                relative_line_height = Some(*factor);
+                style.line_height_inherited = false;
+            }
+            _ => {}
+        }
+        if let Declaration::CurrentColor(p) = decl {
+            current_color[*p as usize] = true;
+        } else if let Some(p) = decl.color_property() {
+            current_color[p as usize] = false;
+        }
         apply_declaration(style, decl);
     };
     // Presentational hints sit between the user-agent and author origins:
@@ -457,12 +692,27 @@ pub fn compute_styles_indexed(
         }
     }
 
+    resolve_current_color(&mut style, current_color);
+
+    // `auto` vertical margins compute to zero on in-flow blocks (CSS 2.1
+    // §10.6.3); only horizontal `auto` means something (centering).
+    for margin in [&mut style.margin_top, &mut style.margin_bottom] {
+        if *margin == Length::Auto {
+            *margin = Length::Px(0.0);
+        }
+    }
+
     let parent_abs = parent_style.map(|p| p.font_size_abs.0).unwrap_or(1.0);
     style.font_size_abs = super::AbsFontSize(if font_size_declared {
         resolve_font_size_abs(style.font_size, parent_abs)
     } else {
         parent_abs
     });
+    // An `em`/`%` line-height is a length of this element's font: store it
+    // root-relative so descendants inherit the length, not the factor.
+    if let Some(factor) = relative_line_height {
+        style.line_height = Length::Rem(factor * style.font_size_abs.0);
+    }
 
     style
 }
@@ -511,16 +761,53 @@ fn rule_match_specificity(
         .max()
 }
 
+/// Resolve color properties set to `currentColor` to the computed `color`.
+///
+/// Border and decoration colors already treat `None` as "the text color", so
+/// an unset `color` leaves them `None`; a background needs a concrete color
+/// and falls back to black, the initial `color`.
+fn resolve_current_color(style: &mut ComputedStyle, current_color: [bool; 6]) {
+    let color = style.color;
+    let properties = [
+        ColorProperty::Background,
+        ColorProperty::TextDecoration,
+        ColorProperty::BorderTop,
+        ColorProperty::BorderRight,
+        ColorProperty::BorderBottom,
+        ColorProperty::BorderLeft,
+    ];
+    for (p, set) in properties.into_iter().zip(current_color) {
+        if !set {
+            continue;
+        }
+        match p {
+            ColorProperty::Background => {
+                style.background_color = Some(color.unwrap_or(Color::BLACK))
+            }
+            ColorProperty::TextDecoration => style.underline_color = color,
+            ColorProperty::BorderTop => style.border_color_top = color,
+            ColorProperty::BorderRight => style.border_color_right = color,
+            ColorProperty::BorderBottom => style.border_color_bottom = color,
+            ColorProperty::BorderLeft => style.border_color_left = color,
+        }
+    }
+}
+
 /// Apply a declaration to a computed style.
 fn apply_declaration(style: &mut ComputedStyle, decl: &Declaration) {
     match decl {
         // Colors
         Declaration::Color(c) => style.color = Some(*c),
         Declaration::BackgroundColor(c) => style.background_color = Some(*c),
+        // Resolved after the cascade by `resolve_current_color`.
+        Declaration::CurrentColor(_) => {}
+        // Resolved by the cascade against the parent and default styles.
+        Declaration::CssWide(..) => {}
 
         // Font properties
         Declaration::FontFamily(s) => style.font_family = Some(s.clone()),
         Declaration::FontSize(l) => style.font_size = *l,
+        Declaration::FontSizeAdjust(a) => style.font_size_adjust = *a,
         Declaration::FontWeight(w) => style.font_weight = *w,
         Declaration::FontStyle(s) => style.font_style = *s,
         Declaration::FontVariant(v) => style.font_variant = *v,
@@ -529,12 +816,24 @@ fn apply_declaration(style: &mut ComputedStyle, decl: &Declaration) {
         Declaration::TextAlign(a) => style.text_align = *a,
         Declaration::TextIndent(l) => style.text_indent = *l,
         Declaration::LineHeight(l) => style.line_height = *l,
+        // Provisional; converted to a root-relative length once the
+        // element's absolute font size is resolved.
+        Declaration::LineHeightRelative(factor) => style.line_height = Length::Em(*factor),
         Declaration::LetterSpacing(l) => style.letter_spacing = *l,
         Declaration::WordSpacing(l) => style.word_spacing = *l,
         Declaration::TextTransform(t) => style.text_transform = *t,
         Declaration::Hyphens(h) => style.hyphens = *h,
+        Declaration::WritingMode(w) => style.writing_mode = *w,
+        Declaration::Direction(d) => style.direction = *d,
         Declaration::WhiteSpace(ws) => style.white_space = *ws,
-        Declaration::VerticalAlign(v) => style.vertical_align = *v,
+        Declaration::VerticalAlign(v) => {
+            style.vertical_align = *v;
+            style.baseline_shift = Length::Auto;
+        }
+        Declaration::BaselineShift(l) => {
+            style.vertical_align = VerticalAlign::Baseline;
+            style.baseline_shift = *l;
+        }
 
         // Text decoration
         Declaration::TextDecoration(d) => {
@@ -583,6 +882,8 @@ fn apply_declaration(style: &mut ComputedStyle, decl: &Declaration) {
         Declaration::Clear(c) => style.clear = *c,
         Declaration::Visibility(v) => style.visibility = *v,
         Declaration::BoxSizing(bs) => style.box_sizing = *bs,
+        Declaration::Overflow(o) => style.overflow = *o,
+        Declaration::ColumnSpan(cs) => style.column_span = *cs,
 
         // Pagination control
         Declaration::Orphans(n) => style.orphans = *n,
@@ -648,17 +949,19 @@ fn apply_declaration(style: &mut ComputedStyle, decl: &Declaration) {
         // List properties
         Declaration::ListStyleType(lst) => style.list_style_type = *lst,
         Declaration::ListStylePosition(p) => style.list_style_position = *p,
+        Declaration::ListStyleImage(href) => style.list_style_image = href.clone(),
 
         // Table properties
         Declaration::BorderCollapse(bc) => style.border_collapse = *bc,
         Declaration::BorderSpacing(l) => style.border_spacing = *l,
+        Declaration::TableLayout(tl) => style.table_layout = *tl,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::style::properties::Color;
+    use crate::style::properties::{Color, TableLayout};
 
     /// Compute the resolved `color` of the first `<p>` for the given author CSS.
     fn p_color(css: &str) -> Option<Color> {
@@ -670,6 +973,65 @@ mod tests {
         compute_styles(elem, &[(sheet, Origin::Author)], None, &mut pool).color
     }
 
+    #[test]
+    fn normal_spacing_interns_as_unspaced() {
+        let dom = crate::dom::parse_dom("<p>x</p>");
+        let p = dom.find_by_tag("p").unwrap();
+        let mut pool = StylePool::default();
+        let mut style_id = |css: &str| {
+            let sheet = Stylesheet::parse(css);
+            let style = compute_styles(
+                ElementRef::new(&dom, p),
+                &[(sheet, Origin::Author)],
+                None,
+                &mut pool,
+            );
+            pool.intern(style)
+        };
+        let plain = style_id("p { color: red }");
+        assert_eq!(
+            style_id("p { color: red; letter-spacing: normal; word-spacing: normal }"),
+            plain
+        );
+        // Zero in any unit is one style, kept apart from `normal` so an
+        // explicit reset survives export.
+        let zero = style_id("p { color: red; letter-spacing: 0; word-spacing: 0em }");
+        assert_eq!(
+            style_id("p { color: red; letter-spacing: 0px; word-spacing: -0% }"),
+            zero
+        );
+        assert_ne!(zero, plain);
+        assert_ne!(style_id("p { color: red; letter-spacing: 0.1em }"), plain);
+    }
+
+    #[test]
+    fn vertical_align_length_is_a_baseline_shift() {
+        let dom = crate::dom::parse_dom("<sup>1</sup>");
+        let sup = dom.find_by_tag("sup").unwrap();
+        let style = |css: &str| {
+            let sheet = Stylesheet::parse(css);
+            compute_styles(
+                ElementRef::new(&dom, sup),
+                &[(sheet, Origin::Author)],
+                None,
+                &mut StylePool::default(),
+            )
+        };
+
+        let shifted = style("sup { vertical-align: -0.2em }");
+        assert_eq!(shifted.vertical_align, VerticalAlign::Baseline);
+        assert_eq!(shifted.baseline_shift, Length::Em(-0.2));
+        assert!(crate::style::ToCss::to_css_string(&shifted).contains("vertical-align: -0.2em"));
+
+        // A later keyword replaces the shift, and vice versa.
+        let keyword = style("sup { vertical-align: 20% } sup { vertical-align: super }");
+        assert_eq!(keyword.vertical_align, VerticalAlign::Super);
+        assert_eq!(keyword.baseline_shift, Length::Auto);
+        let length = style("sup { vertical-align: super } sup { vertical-align: 20% }");
+        assert_eq!(length.vertical_align, VerticalAlign::Baseline);
+        assert_eq!(length.baseline_shift, Length::Percent(20.0));
+    }
+
     #[test]
     fn important_declaration_beats_later_normal() {
         // `!important` must win even though the blue rule comes later and
@@ -690,6 +1052,225 @@ mod tests {
         );
     }
 
+    #[test]
+    fn gradient_background_sets_solid_background_color() {
+        let dom = crate::dom::parse_dom("<p>x</p>");
+        let p = dom.find_by_tag("p").unwrap();
+        let elem = ElementRef::new(&dom, p);
+        let sheet = Stylesheet::parse("p { background: linear-gradient(to right, #fff, #000) }");
+        let mut pool = StylePool::default();
+        let style = compute_styles(elem, &[(sheet, Origin::Author)], None, &mut pool);
+        assert_eq!(style.background_color, Some(Color::rgb(255, 255, 255)));
+    }
+
+    #[test]
+    fn table_layout_fixed_is_computed() {
+        let dom = crate::dom::parse_dom("<table><tr><td>x</td></tr></table>");
+        let table = dom.find_by_tag("table").unwrap();
+        let sheet = Stylesheet::parse("table { table-layout: fixed }");
+        let mut pool = StylePool::default();
+        let style = compute_styles(
+            ElementRef::new(&dom, table),
+            &[(sheet, Origin::Author)],
+            None,
+            &mut pool,
+        );
+        assert_eq!(style.table_layout, TableLayout::Fixed);
+    }
+
+    /// Resolved `color` of each element child of `<body>`, in document order.
+    fn body_child_colors(html: &str, css: &str) -> Vec<Option<Color>> {
+        let dom = crate::dom::parse_dom(html);
+        let body = dom.find_by_tag("body").unwrap();
+        let sheet = Stylesheet::parse(css);
+        let mut pool = StylePool::default();
+        dom.children(body)
+            .filter(|&child| dom.is_element(child))
+            .map(|child| {
+                let elem = ElementRef::new(&dom, child);
+                compute_styles(elem, &[(sheet.clone(), Origin::Author)], None, &mut pool).color
+            })
+            .collect()
+    }
+
+    #[test]
+    fn attribute_and_negation_selectors_apply() {
+        let red = Some(Color::rgb(255, 0, 0));
+        assert_eq!(
+            body_child_colors(
+                r#"<a href="http://example.com">x</a><a href="ch2.xhtml">y</a>"#,
+                r#"a[href^="http"] { color: red }"#,
+            ),
+            vec![red, None]
+        );
+        assert_eq!(
+            body_child_colors(
+                r#"<p>x</p><p class="note">y</p>"#,
+                "p:not(.note) { color: red }"
+            ),
+            vec![red, None]
+        );
+    }
+
+    #[test]
+    fn pseudo_elements_drop_only_their_own_selectors() {
+        // `::before` styles generated content, not the `<p>`, but it must not
+        // take the sibling `.lead` selector down with it.
+        let red = Some(Color::rgb(255, 0, 0));
+        assert_eq!(
+            body_child_colors(
+                r#"<p>x</p><p class="lead">y</p>"#,
+                "p::before, p:first-line, .lead { color: red }"
+            ),
+            vec![None, red]
+        );
+        // Dynamic pseudo-classes parse; only `:link` matches statically.
+        assert_eq!(
+            body_child_colors(
+                r#"<a href="a.xhtml">x</a><p>y</p>"#,
+                "a:link { color: red } p:hover, :is(p, li) { color: blue }"
+            ),
+            vec![red, Some(Color::rgb(0, 0, 255))]
+        );
+    }
+
+    #[test]
+    fn current_color_resolves_against_final_color() {
+        // `color` comes after the border in source order; `currentColor`
+        // still takes the element's final color, not the inherited one.
+        let dom = crate::dom::parse_dom("<p>x</p>");
+        let p = dom.find_by_tag("p").unwrap();
+        let elem = ElementRef::new(&dom, p);
+        let sheet = Stylesheet::parse(
+            "p { border: 1px solid currentColor; background-color: currentcolor } \
+             p { color: #c00 }",
+        );
+        let mut pool = StylePool::default();
+        let style = compute_styles(elem, &[(sheet, Origin::Author)], None, &mut pool);
+        let red = Some(Color::rgb(0xcc, 0, 0));
+        assert_eq!(style.border_color_top, red);
+        assert_eq!(style.border_color_left, red);
+        assert_eq!(style.background_color, red);
+
+        // A later concrete color replaces `currentColor`.
+        let sheet = Stylesheet::parse(
+            "p { color: red; border-top-color: currentColor; border-top-color: blue }",
+        );
+        let style = compute_styles(elem, &[(sheet, Origin::Author)], None, &mut pool);
+        assert_eq!(style.border_color_top, Some(Color::rgb(0, 0, 255)));
+    }
+
+    #[test]
+    fn line_height_factor_inherits_but_lengths_compute() {
+        let dom = crate::dom::parse_dom("<div><p>x</p></div>");
+        let div = ElementRef::new(&dom, dom.find_by_tag("div").unwrap());
+        let p = ElementRef::new(&dom, dom.find_by_tag("p").unwrap());
+        let mut pool = StylePool::default();
+        for (line_height, expected) in [
+            ("1.5", Length::Em(1.5)),
+            ("150%", Length::Rem(1.5)),
+            ("1.5em", Length::Rem(1.5)),
+            ("normal", Length::Auto),
+        ] {
+            let sheets = [(
+                Stylesheet::parse(&format!(
+                    "div {{ line-height: {line_height} }} p {{ font-size: 2em }}"
+                )),
+                Origin::Author,
+            )];
+            let parent = compute_styles(div, &sheets, None, &mut pool);
+            let child = compute_styles(p, &sheets, Some(&parent), &mut pool);
+            // A factor scales with the child's font; a length stays at
+            // 1.5 × the div's font however large the child's font is.
+            assert_eq!(child.line_height, expected, "line-height: {line_height}");
+        }
+    }
+
+    #[test]
+    fn css_wide_keywords_reset_properties() {
+        use crate::style::properties::TextAlign;
+
+        // `inherit` with no parent leaves the color to the reader.
+        assert_eq!(p_color("p { color: red } p { color: inherit }"), None);
+        assert_eq!(p_color("p { color: red } p { color: unset }"), None);
+        assert_eq!(
+            p_color("p { color: red !important } p { color: revert }"),
+            Some(Color::rgb(255, 0, 0))
+        );
+
+        let dom = crate::dom::parse_dom("<div><p>x</p></div>");
+        let div = ElementRef::new(&dom, dom.find_by_tag("div").unwrap());
+        let p = ElementRef::new(&dom, dom.find_by_tag("p").unwrap());
+        let mut pool = StylePool::default();
+        let sheets = [(
+            Stylesheet::parse(
+                "div { color: blue; text-align: center; margin: 1em }
+                 p { color: red; text-align: initial; margin-left: inherit;
+                     border: 1px solid currentColor; border-top-color: initial }",
+            ),
+            Origin::Author,
+        )];
+        let parent = compute_styles(div, &sheets, None, &mut pool);
+        let child = compute_styles(p, &sheets, Some(&parent), &mut pool);
+        assert_eq!(child.text_align, TextAlign::default());
+        assert_eq!(child.color, Some(Color::rgb(255, 0, 0)));
+        assert_eq!(child.margin_left, Length::Em(1.0));
+        assert_eq!(child.margin_right, Length::Px(0.0));
+        // `initial` also drops a pending `currentColor`.
+        assert_eq!(child.border_color_top, None);
+        assert_eq!(child.border_color_left, Some(Color::rgb(255, 0, 0)));
+
+        let sheets = [(
+            Stylesheet::parse("div { color: blue } p { color: green; color: inherit }"),
+            Origin::Author,
+        )];
+        let parent = compute_styles(div, &sheets, None, &mut pool);
+        let child = compute_styles(p, &sheets, Some(&parent), &mut pool);
+        assert_eq!(child.color, Some(Color::rgb(0, 0, 255)));
+    }
+
+    #[test]
+    fn auto_margins_center_horizontally_and_zero_vertically() {
+        let dom = crate::dom::parse_dom("<p>x</p>");
+        let p = ElementRef::new(&dom, dom.find_by_tag("p").unwrap());
+        let sheet = Stylesheet::parse("p { margin: auto }");
+        let mut pool = StylePool::default();
+        let style = compute_styles(p, &[(sheet, Origin::Author)], None, &mut pool);
+        assert_eq!(style.margin_left, Length::Auto);
+        assert_eq!(style.margin_right, Length::Auto);
+        assert_eq!(style.margin_top, Length::Px(0.0));
+        assert_eq!(style.margin_bottom, Length::Px(0.0));
+    }
+
+    #[test]
+    fn border_radius_shorthand_sets_each_corner() {
+        let dom = crate::dom::parse_dom("<p>x</p>");
+        let p = ElementRef::new(&dom, dom.find_by_tag("p").unwrap());
+        let mut pool = StylePool::default();
+        // (top-left, top-right, bottom-right, bottom-left), like margins.
+        for (value, [tl, tr, br, bl]) in [
+            ("3px", [3.0, 3.0, 3.0, 3.0]),
+            ("10px 20px", [10.0, 20.0, 10.0, 20.0]),
+            ("1px 2px 3px", [1.0, 2.0, 3.0, 2.0]),
+            ("1px 2px 3px 4px", [1.0, 2.0, 3.0, 4.0]),
+            // Elliptical corners keep their horizontal radii.
+            ("10px 20px / 5px", [10.0, 20.0, 10.0, 20.0]),
+        ] {
+            let sheet = Stylesheet::parse(&format!("p {{ border-radius: {value} }}"));
+            let style = compute_styles(p, &[(sheet, Origin::Author)], None, &mut pool);
+            assert_eq!(
+                [
+                    style.border_radius_top_left,
+                    style.border_radius_top_right,
+                    style.border_radius_bottom_right,
+                    style.border_radius_bottom_left,
+                ],
+                [tl, tr, br, bl].map(Length::Px),
+                "border-radius: {value}"
+            );
+        }
+    }
+
     #[test]
     fn box_shorthand_keeps_important() {
         // `parse_length` used to eat the `!` while probing for a 2nd..4th
@@ -701,6 +1282,7 @@ mod tests {
             "p { border-width: 1px !important }",
             "p { border-style: solid !important }",
             "p { border-color: #123 !important }",
+            "p { border-radius: 4px / 2px !important }",
         ] {
             let sheet = Stylesheet::parse(css);
             let rule = &sheet.rules[0];
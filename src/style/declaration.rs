@@ -9,21 +9,24 @@ use super::parse::border::{
     BorderSide, parse_border_shorthand, parse_border_side_shorthand,
     parse_border_style_shorthand_values, parse_color_shorthand_values,
 };
-use super::parse::box_model::parse_box_shorthand_values;
+use super::parse::box_model::{parse_border_radius_values, parse_box_shorthand_values};
 use super::parse::font::{
-    parse_font_family, parse_font_shorthand, parse_font_size, parse_font_weight, parse_line_height,
+    parse_font_family, parse_font_shorthand, parse_font_size, parse_font_size_adjust,
+    parse_font_weight, parse_line_height,
 };
 use super::parse::keywords::{
     parse_border_collapse, parse_border_style_value, parse_box_sizing, parse_break_inside,
-    parse_break_value, parse_clear, parse_decoration_style, parse_display, parse_float,
-    parse_font_style, parse_font_variant, parse_hyphens, parse_list_style_position,
-    parse_list_style_shorthand, parse_list_style_type, parse_overflow_wrap, parse_text_align,
-    parse_text_transform, parse_vertical_align, parse_visibility, parse_white_space,
-    parse_word_break,
+    parse_break_value, parse_clear, parse_column_span, parse_css_wide_keyword,
+    parse_decoration_style, parse_direction, parse_display, parse_float, parse_font_style,
+    parse_font_variant, parse_hyphens, parse_list_style_image, parse_list_style_position,
+    parse_list_style_shorthand, parse_list_style_type, parse_overflow, parse_overflow_wrap,
+    parse_table_layout, parse_text_align, parse_text_transform, parse_vertical_align,
+    parse_visibility, parse_white_space, parse_word_break, parse_writing_mode,
 };
 use super::parse::values::{
-    parse_background_shorthand, parse_color, parse_integer, parse_length, parse_spacing,
-    parse_text_decoration,
+    parse_background_shorthand, parse_baseline_shift, parse_color, parse_color_value,
+    parse_integer, parse_length, parse_spacing, parse_text_decoration,
+    parse_text_decoration_shorthand,
 };
 use super::properties::*;
 
@@ -37,7 +40,8 @@ pub enum Declaration {
     /// `color`: foreground text color.
     Color(Color),
     /// `background-color`: element background color (also produced by the
-    /// `background` shorthand, from which only the color component is kept).
+    /// `background` shorthand, from which only the color component — or a
+    /// gradient's first color stop — is kept).
     BackgroundColor(Color),
 
     // Font properties
@@ -46,6 +50,8 @@ pub enum Declaration {
     /// `font-size`: font size as a [`Length`] (keywords like `small` are
     /// resolved to em values during parsing).
     FontSize(Length),
+    /// `font-size-adjust`: x-height aspect value for fallback fonts.
+    FontSizeAdjust(FontSizeAdjust),
     /// `font-weight`: numeric weight 100-900 (`normal` = 400, `bold` = 700).
     FontWeight(FontWeight),
     /// `font-style`: normal, italic, or oblique.
@@ -60,6 +66,11 @@ pub enum Declaration {
     TextIndent(Length),
     /// `line-height`: line box height; unitless numbers are stored as em.
     LineHeight(Length),
+    /// `line-height` given in `em`, `ex` or `%`, as a multiple of the
+    /// element's own font size. Resolved to an absolute length once the
+    /// element's font size is known, so descendants inherit the length
+    /// rather than the factor.
+    LineHeightRelative(f32),
     /// `letter-spacing`: extra spacing between characters.
     LetterSpacing(Length),
     /// `word-spacing`: extra spacing between words.
@@ -68,11 +79,18 @@ pub enum Declaration {
     TextTransform(TextTransform),
     /// `hyphens`: automatic hyphenation mode.
     Hyphens(Hyphens),
+    /// `writing-mode`: horizontal or vertical block flow.
+    WritingMode(WritingMode),
+    /// `direction`: inline base direction (ltr/rtl).
+    Direction(Direction),
     /// `white-space`: whitespace collapsing and line-wrapping behavior.
     WhiteSpace(WhiteSpace),
     /// `vertical-align`: inline/table-cell vertical alignment (includes
     /// `super`/`sub` used for superscript/subscript detection).
     VerticalAlign(VerticalAlign),
+    /// `vertical-align` given as a length or percentage: a baseline shift
+    /// (fine-tuned subscripts, footnote markers).
+    BaselineShift(Length),
 
     // Text decoration
     /// `text-decoration` / `text-decoration-line`: which decoration lines
@@ -135,6 +153,10 @@ pub enum Declaration {
     Visibility(Visibility),
     /// `box-sizing`: whether width/height include padding and border.
     BoxSizing(BoxSizing),
+    /// `overflow`: whether content outside the box is clipped.
+    Overflow(Overflow),
+    /// `column-span`: whether the element spans all columns.
+    ColumnSpan(ColumnSpan),
 
     // Pagination control
     /// `orphans`: minimum lines left at the bottom of a page before a break.
@@ -196,7 +218,9 @@ pub enum Declaration {
     BorderLeftColor(Color),
 
     // Border radius
-    /// `border-radius` applied uniformly to all four corners.
+    /// `border-radius` applied uniformly to all four corners (the shorthand
+    /// itself is parsed into the four per-corner variants; this variant
+    /// exists for programmatic use).
     BorderRadius(Length),
     /// `border-top-left-radius`.
     BorderTopLeftRadius(Length),
@@ -212,6 +236,9 @@ pub enum Declaration {
     ListStyleType(ListStyleType),
     /// `list-style-position`: marker inside or outside the item's box.
     ListStylePosition(ListStylePosition),
+    /// `list-style-image`: marker image href as written in the stylesheet
+    /// (`None` for `none`).
+    ListStyleImage(Option<String>),
 
     // Table properties
     /// `border-collapse`: separate vs. collapsed table borders.
@@ -219,15 +246,73 @@ pub enum Declaration {
     /// `border-spacing`: gap between table cell borders (single value; used
     /// for both axes).
     BorderSpacing(Length),
+    /// `table-layout`: content-sized vs. fixed column widths.
+    TableLayout(TableLayout),
+
+    // Keywords resolved after the cascade
+    /// A color property set to `currentColor`. Resolved to the element's
+    /// computed `color` once its whole cascade has applied, so a `color`
+    /// declaration that sorts later still counts.
+    CurrentColor(ColorProperty),
+    /// A property (longhand or shorthand, lowercased) set to a CSS-wide
+    /// keyword. Resolved in the cascade, where the parent and default
+    /// styles are known.
+    CssWide(String, CssWideKeyword),
+}
+
+/// A CSS-wide keyword accepted by every property.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CssWideKeyword {
+    /// `inherit`: take the parent's computed value.
+    Inherit,
+    /// `initial`: reset to the property's initial value.
+    Initial,
+    /// `unset` (and `revert`, which boko treats the same): inherit for
+    /// inherited properties, initial for the rest.
+    Unset,
+}
+
+/// A color-valued property that accepts `currentColor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorProperty {
+    /// `background-color`.
+    Background,
+    /// `text-decoration-color`.
+    TextDecoration,
+    /// `border-top-color`.
+    BorderTop,
+    /// `border-right-color`.
+    BorderRight,
+    /// `border-bottom-color`.
+    BorderBottom,
+    /// `border-left-color`.
+    BorderLeft,
 }
 
 impl Declaration {
+    /// The color property this declaration sets to a concrete color, if any.
+    pub(crate) fn color_property(&self) -> Option<ColorProperty> {
+        match self {
+            Self::BackgroundColor(_) => Some(ColorProperty::Background),
+            Self::TextDecorationColor(_) => Some(ColorProperty::TextDecoration),
+            Self::BorderTopColor(_) => Some(ColorProperty::BorderTop),
+            Self::BorderRightColor(_) => Some(ColorProperty::BorderRight),
+            Self::BorderBottomColor(_) => Some(ColorProperty::BorderBottom),
+            Self::BorderLeftColor(_) => Some(ColorProperty::BorderLeft),
+            _ => None,
+        }
+    }
+
     /// Parse a CSS declaration from a property name and value parser.
     ///
     /// Returns a Vec of declarations. For most properties this is a single declaration,
     /// but shorthands like `margin`, `border`, etc. expand to multiple declarations.
     /// Returns an empty Vec if the property is unknown or the value fails to parse.
     pub fn parse(name: &str, input: &mut Parser<'_, '_>) -> Vec<Self> {
+        if let Ok(keyword) = input.try_parse(|i| parse_css_wide_keyword(i).ok_or(())) {
+            return vec![Self::CssWide(name.to_ascii_lowercase(), keyword)];
+        }
+
         // Try shorthand properties first (they expand to multiple declarations)
         if let Some(decls) = Self::parse_shorthand(name, input) {
             return decls;
@@ -264,6 +349,18 @@ impl Declaration {
                     Self::BorderLeftWidth(l),
                 ]
             }),
+            // Corners in margin order: top-left, top-right, bottom-right,
+            // bottom-left.
+            "border-radius" => parse_border_radius_values(input)
+                .map(|(tl, tr, br, bl)| {
+                    vec![
+                        Self::BorderTopLeftRadius(tl),
+                        Self::BorderTopRightRadius(tr),
+                        Self::BorderBottomRightRadius(br),
+                        Self::BorderBottomLeftRadius(bl),
+                    ]
+                })
+                .unwrap_or_default(),
             "border-style" => parse_border_style_shorthand_values(input)
                 .map(|(t, r, b, l)| {
                     vec![
@@ -277,14 +374,15 @@ impl Declaration {
             "border-color" => parse_color_shorthand_values(input)
                 .map(|(t, r, b, l)| {
                     vec![
-                        Self::BorderTopColor(t),
-                        Self::BorderRightColor(r),
-                        Self::BorderBottomColor(b),
-                        Self::BorderLeftColor(l),
+                        t.declaration(ColorProperty::BorderTop),
+                        r.declaration(ColorProperty::BorderRight),
+                        b.declaration(ColorProperty::BorderBottom),
+                        l.declaration(ColorProperty::BorderLeft),
                     ]
                 })
                 .unwrap_or_default(),
             "border" => parse_border_shorthand(input),
+            "text-decoration" => parse_text_decoration_shorthand(input),
             "border-top" => parse_border_side_shorthand(input, BorderSide::Top),
             "border-right" => parse_border_side_shorthand(input, BorderSide::Right),
             "border-bottom" => parse_border_side_shorthand(input, BorderSide::Bottom),
@@ -310,12 +408,15 @@ impl Declaration {
         match name {
             // Colors
             "color" => parse_color(input).map(Self::Color),
-            "background-color" => parse_color(input).map(Self::BackgroundColor),
+            "background-color" => {
+                parse_color_value(input).map(|c| c.declaration(ColorProperty::Background))
+            }
             "background" => parse_background_shorthand(input).map(Self::BackgroundColor),
 
             // Font properties
             "font-family" => parse_font_family(input).map(Self::FontFamily),
             "font-size" => parse_font_size(input).map(Self::FontSize),
+            "font-size-adjust" => parse_font_size_adjust(input).map(Self::FontSizeAdjust),
             "font-weight" => parse_font_weight(input).map(Self::FontWeight),
             "font-style" => parse_font_style(input).map(Self::FontStyle),
             "font-variant" | "font-variant-caps" => {
@@ -325,7 +426,7 @@ impl Declaration {
             // Text properties
             "text-align" => parse_text_align(input).map(Self::TextAlign),
             "text-indent" => parse_length(input).map(Self::TextIndent),
-            "line-height" => parse_line_height(input).map(Self::LineHeight),
+            "line-height" => parse_line_height(input),
             // `normal` is the spacing reset keyword (parse_length only knows
             // `auto`); both mean "no extra spacing" (`Length::Auto`).
             "letter-spacing" => parse_spacing(input).map(Self::LetterSpacing),
@@ -334,15 +435,23 @@ impl Declaration {
             // Vendor-prefixed hyphenation aliases are common in EPUB CSS.
             "hyphens" | "-epub-hyphens" | "-webkit-hyphens" | "-moz-hyphens"
             | "adobe-hyphenate" => parse_hyphens(input).map(Self::Hyphens),
+            "writing-mode" | "-epub-writing-mode" | "-webkit-writing-mode" => {
+                parse_writing_mode(input).map(Self::WritingMode)
+            }
+            "direction" => parse_direction(input).map(Self::Direction),
             "white-space" => parse_white_space(input).map(Self::WhiteSpace),
-            "vertical-align" => parse_vertical_align(input).map(Self::VerticalAlign),
+            "vertical-align" => input
+                .try_parse(|i| parse_vertical_align(i).ok_or(()))
+                .ok()
+                .map(Self::VerticalAlign)
+                .or_else(|| parse_baseline_shift(input).map(Self::BaselineShift)),
 
             // Text decoration
-            "text-decoration" | "text-decoration-line" => {
-                parse_text_decoration(input).map(Self::TextDecoration)
-            }
+            "text-decoration-line" => parse_text_decoration(input).map(Self::TextDecoration),
             "text-decoration-style" => parse_decoration_style(input).map(Self::TextDecorationStyle),
-            "text-decoration-color" => parse_color(input).map(Self::TextDecorationColor),
+            "text-decoration-color" => {
+                parse_color_value(input).map(|c| c.declaration(ColorProperty::TextDecoration))
+            }
 
             // Box model - margins (individual)
             "margin-top" => parse_length(input).map(Self::MarginTop),
@@ -370,6 +479,8 @@ impl Declaration {
             "clear" => parse_clear(input).map(Self::Clear),
             "visibility" => parse_visibility(input).map(Self::Visibility),
             "box-sizing" => parse_box_sizing(input).map(Self::BoxSizing),
+            "overflow" => parse_overflow(input).map(Self::Overflow),
+            "column-span" => parse_column_span(input).map(Self::ColumnSpan),
 
             // Pagination control
             "orphans" => parse_integer(input).map(Self::Orphans),
@@ -399,13 +510,20 @@ impl Declaration {
             "border-left-width" => parse_length(input).map(Self::BorderLeftWidth),
 
             // Border color (individual sides)
-            "border-top-color" => parse_color(input).map(Self::BorderTopColor),
-            "border-right-color" => parse_color(input).map(Self::BorderRightColor),
-            "border-bottom-color" => parse_color(input).map(Self::BorderBottomColor),
-            "border-left-color" => parse_color(input).map(Self::BorderLeftColor),
+            "border-top-color" => {
+                parse_color_value(input).map(|c| c.declaration(ColorProperty::BorderTop))
+            }
+            "border-right-color" => {
+                parse_color_value(input).map(|c| c.declaration(ColorProperty::BorderRight))
+            }
+            "border-bottom-color" => {
+                parse_color_value(input).map(|c| c.declaration(ColorProperty::BorderBottom))
+            }
+            "border-left-color" => {
+                parse_color_value(input).map(|c| c.declaration(ColorProperty::BorderLeft))
+            }
 
             // Border radius
-            "border-radius" => parse_length(input).map(Self::BorderRadius),
             "border-top-left-radius" => parse_length(input).map(Self::BorderTopLeftRadius),
             "border-top-right-radius" => parse_length(input).map(Self::BorderTopRightRadius),
             "border-bottom-left-radius" => parse_length(input).map(Self::BorderBottomLeftRadius),
@@ -414,10 +532,12 @@ impl Declaration {
             // List properties
             "list-style-type" => parse_list_style_type(input).map(Self::ListStyleType),
             "list-style-position" => parse_list_style_position(input).map(Self::ListStylePosition),
+            "list-style-image" => parse_list_style_image(input).map(Self::ListStyleImage),
 
             // Table properties
             "border-collapse" => parse_border_collapse(input).map(Self::BorderCollapse),
             "border-spacing" => parse_length(input).map(Self::BorderSpacing),
+            "table-layout" => parse_table_layout(input).map(Self::TableLayout),
 
             // Unknown properties
             _ => {
@@ -32,9 +32,10 @@ pub trait ToCss {
 
 // Re-export property types
 pub use properties::{
-    BorderCollapse, BorderStyle, BoxSizing, BreakValue, Clear, Color, DecorationStyle, Display,
-    Float, FontStyle, FontVariant, FontWeight, Hyphens, Length, ListStylePosition, ListStyleType,
-    OverflowWrap, TextAlign, TextTransform, VerticalAlign, Visibility, WhiteSpace, WordBreak,
+    BorderCollapse, BorderStyle, BoxSizing, BreakValue, Clear, Color, ColumnSpan, DecorationStyle,
+    Direction, Display, Float, FontSizeAdjust, FontStyle, FontVariant, FontWeight, Hyphens, Length,
+    ListStylePosition, ListStyleType, Overflow, OverflowWrap, TableLayout, TextAlign,
+    TextTransform, VerticalAlign, Visibility, WhiteSpace, WordBreak, WritingMode,
 };
 
 // Re-export core style types
@@ -43,14 +44,17 @@ pub use to_css::{changed_property_value, changed_property_value_from, for_each_c
 pub use types::{AbsFontSize, ComputedStyle, StyleId};
 
 // Re-export declaration type (kept minimal)
-pub use declaration::Declaration;
+pub use declaration::{ColorProperty, CssWideKeyword, Declaration};
 
 // Re-export stylesheet types from parse module
-pub use parse::{CssRule, InlineStyle, Origin, Specificity, Stylesheet, TextDecorationValue};
+pub use parse::{
+    CssRule, InlineStyle, Origin, PageRule, PageSize, Specificity, Stylesheet, TextDecorationValue,
+};
 
 // Re-export cascade function
 pub(crate) use cascade::inherit_from_parent;
 pub use cascade::{CascadeIndex, CascadeScratch, compute_styles, compute_styles_indexed};
+pub(crate) use to_css::quote_font_family;
 
 // Re-export macro for internal use
 #[allow(unused_imports)]
@@ -5,12 +5,13 @@
 
 use cssparser::Parser;
 
+use crate::style::ColorProperty;
 use crate::style::Declaration;
-use crate::style::properties::{BorderStyle, Color, Length};
+use crate::style::properties::{BorderStyle, Length};
 
 use super::box_model::expand_shorthand_4;
 use super::keywords::parse_border_style_value;
-use super::values::{parse_color, parse_length};
+use super::values::{ColorValue, parse_color_value, parse_length};
 
 /// Represents one of the four border sides.
 #[derive(Clone, Copy)]
@@ -22,12 +23,22 @@ pub(crate) enum BorderSide {
 }
 
 impl BorderSide {
+    /// This side's `border-*-color` property.
+    fn color_property(self) -> ColorProperty {
+        match self {
+            BorderSide::Top => ColorProperty::BorderTop,
+            BorderSide::Right => ColorProperty::BorderRight,
+            BorderSide::Bottom => ColorProperty::BorderBottom,
+            BorderSide::Left => ColorProperty::BorderLeft,
+        }
+    }
+
     /// Create declarations for this side's width, style, and color.
     pub(crate) fn make_declarations(
         self,
         width: Option<Length>,
         style: Option<BorderStyle>,
-        color: Option<Color>,
+        color: Option<ColorValue>,
     ) -> Vec<Declaration> {
         let mut decls = Vec::with_capacity(3);
 
@@ -50,12 +61,7 @@ impl BorderSide {
         }
 
         if let Some(c) = color {
-            decls.push(match self {
-                BorderSide::Top => Declaration::BorderTopColor(c),
-                BorderSide::Right => Declaration::BorderRightColor(c),
-                BorderSide::Bottom => Declaration::BorderBottomColor(c),
-                BorderSide::Left => Declaration::BorderLeftColor(c),
-            });
+            decls.push(c.declaration(self.color_property()));
         }
 
         decls
@@ -82,10 +88,10 @@ pub(crate) fn parse_border_width_value(input: &mut Parser<'_, '_>) -> Option<Len
 /// Parse border values (width, style, color) in any order, returning them as a tuple.
 pub(crate) fn parse_border_values(
     input: &mut Parser<'_, '_>,
-) -> (Option<Length>, Option<BorderStyle>, Option<Color>) {
+) -> (Option<Length>, Option<BorderStyle>, Option<ColorValue>) {
     let mut width: Option<Length> = None;
     let mut style: Option<BorderStyle> = None;
-    let mut color: Option<Color> = None;
+    let mut color: Option<ColorValue> = None;
 
     for _ in 0..3 {
         if style.is_none()
@@ -98,8 +104,8 @@ pub(crate) fn parse_border_values(
         }
 
         if color.is_none()
-            && let Ok(c) =
-                input.try_parse(|i| parse_color(i).ok_or_else(|| i.new_custom_error::<_, ()>(())))
+            && let Ok(c) = input
+                .try_parse(|i| parse_color_value(i).ok_or_else(|| i.new_custom_error::<_, ()>(())))
         {
             color = Some(c);
             continue;
@@ -158,10 +164,14 @@ pub(crate) fn parse_border_shorthand(input: &mut Parser<'_, '_>) -> Vec<Declarat
         decls.push(Declaration::BorderLeftStyle(s));
     }
     if let Some(c) = color {
-        decls.push(Declaration::BorderTopColor(c));
-        decls.push(Declaration::BorderRightColor(c));
-        decls.push(Declaration::BorderBottomColor(c));
-        decls.push(Declaration::BorderLeftColor(c));
+        for side in [
+            BorderSide::Top,
+            BorderSide::Right,
+            BorderSide::Bottom,
+            BorderSide::Left,
+        ] {
+            decls.push(c.declaration(side.color_property()));
+        }
     }
 
     decls
@@ -190,13 +200,13 @@ pub(crate) fn parse_border_style_shorthand_values(
 /// Returns (top, right, bottom, left) following CSS box model rules.
 pub(crate) fn parse_color_shorthand_values(
     input: &mut Parser<'_, '_>,
-) -> Option<(Color, Color, Color, Color)> {
+) -> Option<(ColorValue, ColorValue, ColorValue, ColorValue)> {
     let mut values = Vec::with_capacity(4);
 
     // Parse up to 4 color values. try_parse keeps a failed probe from
     // consuming the `!` of a trailing `!important` (see box_model.rs).
     while values.len() < 4 {
-        match input.try_parse(|i| parse_color(i).ok_or(())) {
+        match input.try_parse(|i| parse_color_value(i).ok_or(())) {
             Ok(color) => values.push(color),
             Err(()) => break,
         }
@@ -1,4 +1,4 @@
-//! Box model shorthand parsing (margin, padding).
+//! Box model shorthand parsing (margin, padding, border-radius).
 
 use cssparser::Parser;
 
@@ -32,6 +32,20 @@ pub(crate) fn parse_box_shorthand_values(
     expand_shorthand_4(values)
 }
 
+/// Parse the `border-radius` shorthand: 1-4 horizontal radii, expanded like
+/// margins to (top-left, top-right, bottom-right, bottom-left). Vertical
+/// radii after a `/` (elliptical corners) are accepted but dropped, as each
+/// corner holds a single length.
+pub(crate) fn parse_border_radius_values(
+    input: &mut Parser<'_, '_>,
+) -> Option<(Length, Length, Length, Length)> {
+    let radii = parse_box_shorthand_values(input)?;
+    if input.try_parse(|i| i.expect_delim('/')).is_ok() {
+        parse_box_shorthand_values(input)?;
+    }
+    Some(radii)
+}
+
 /// Expand 1-4 values to (top, right, bottom, left) following CSS shorthand rules.
 pub(crate) fn expand_shorthand_4<T: Copy>(values: Vec<T>) -> Option<(T, T, T, T)> {
     match values.len() {
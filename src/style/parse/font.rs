@@ -4,7 +4,7 @@ use cssparser::{ParseError, Parser, Token};
 
 use crate::model::FontFace;
 use crate::style::Declaration;
-use crate::style::properties::{FontStyle, FontWeight, Length};
+use crate::style::properties::{FontSizeAdjust, FontStyle, FontWeight, Length};
 
 use super::keywords::{parse_font_style, parse_font_variant};
 
@@ -47,28 +47,43 @@ pub(crate) fn parse_font_size(input: &mut Parser<'_, '_>) -> Option<Length> {
     }
 }
 
+/// Parse font-size-adjust: `none` or a non-negative aspect value. The CSS
+/// Fonts 5 metric keyword is accepted only as `ex-height`, its default.
+pub(crate) fn parse_font_size_adjust(input: &mut Parser<'_, '_>) -> Option<FontSizeAdjust> {
+    if input.try_parse(|i| i.expect_ident_matching("none")).is_ok() {
+        return Some(FontSizeAdjust::None);
+    }
+    let _ = input.try_parse(|i| i.expect_ident_matching("ex-height"));
+    match input.next().ok()? {
+        Token::Number { value, .. } if *value >= 0.0 => Some(FontSizeAdjust::Aspect(*value)),
+        _ => None,
+    }
+}
+
 /// Parse line-height value (handles unitless numbers and "normal" keyword).
-pub(crate) fn parse_line_height(input: &mut Parser<'_, '_>) -> Option<Length> {
+///
+/// A unitless number is a factor descendants inherit as-is
+/// ([`Declaration::LineHeight`] with `Em`); `em`, `ex` and `%` are lengths
+/// of the element's own font ([`Declaration::LineHeightRelative`]), which
+/// descendants inherit as the computed length.
+pub(crate) fn parse_line_height(input: &mut Parser<'_, '_>) -> Option<Declaration> {
     match input.next().ok()? {
-        Token::Dimension { value, unit, .. } => {
-            let length = match unit.as_ref() {
-                "px" => Length::Px(*value),
-                "em" => Length::Em(*value),
-                "rem" => Length::Rem(*value),
-                "%" => Length::Percent(*value),
-                // ex = x-height, approximately 0.5em
-                "ex" => Length::Em(*value * 0.5),
-                // pt = points, 1pt = 96/72 px
-                "pt" => Length::Px(*value * 96.0 / 72.0),
-                _ => return None,
-            };
-            Some(length)
-        }
-        Token::Percentage { unit_value, .. } => Some(Length::Percent(*unit_value * 100.0)),
+        Token::Dimension { value, unit, .. } => match unit.as_ref() {
+            "px" => Some(Declaration::LineHeight(Length::Px(*value))),
+            "em" => Some(Declaration::LineHeightRelative(*value)),
+            "rem" => Some(Declaration::LineHeight(Length::Rem(*value))),
+            "%" => Some(Declaration::LineHeightRelative(*value / 100.0)),
+            // ex = x-height, approximately 0.5em
+            "ex" => Some(Declaration::LineHeightRelative(*value * 0.5)),
+            // pt = points, 1pt = 96/72 px
+            "pt" => Some(Declaration::LineHeight(Length::Px(*value * 96.0 / 72.0))),
+            _ => None,
+        },
+        Token::Percentage { unit_value, .. } => Some(Declaration::LineHeightRelative(*unit_value)),
         // Unitless number becomes em multiplier
-        Token::Number { value, .. } => Some(Length::Em(*value)),
+        Token::Number { value, .. } => Some(Declaration::LineHeight(Length::Em(*value))),
         Token::Ident(ident) => match ident.as_ref() {
-            "normal" => Some(Length::Auto),
+            "normal" => Some(Declaration::LineHeight(Length::Auto)),
             _ => None,
         },
         _ => None,
@@ -87,13 +102,13 @@ pub(crate) fn parse_font_weight(input: &mut Parser<'_, '_>) -> Option<FontWeight
         return Some(weight);
     }
 
-    if let Ok(Token::Number {
-        int_value: Some(v), ..
-    }) = input.next()
-    {
-        let v = *v;
-        if (100..=900).contains(&v) && v % 100 == 0 {
-            return Some(FontWeight(v as u16));
+    // CSS Fonts 4 allows any number in 1..=1000; the writers map weights in
+    // steps of 100, so round to the nearest one.
+    if let Ok(Token::Number { value, .. }) = input.next() {
+        let v = *value;
+        if (1.0..=1000.0).contains(&v) {
+            let rounded = ((v / 100.0).round() * 100.0).clamp(100.0, 900.0);
+            return Some(FontWeight(rounded as u16));
         }
     }
 
@@ -171,7 +186,7 @@ pub(crate) fn parse_font_shorthand(input: &mut Parser<'_, '_>) -> Option<Vec<Dec
     if input.try_parse(|i| i.expect_delim('/')).is_ok()
         && let Some(lh) = parse_line_height(input)
     {
-        decls.push(Declaration::LineHeight(lh));
+        decls.push(lh);
     }
 
     // Required font-family (consumes the rest of the value).
@@ -308,6 +323,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn font_size_adjust_values() {
+        let adjust = |css: &str| {
+            let mut input = ParserInput::new(css);
+            parse_font_size_adjust(&mut Parser::new(&mut input))
+        };
+        assert_eq!(adjust("0.5"), Some(FontSizeAdjust::Aspect(0.5)));
+        assert_eq!(adjust("ex-height 0.46"), Some(FontSizeAdjust::Aspect(0.46)));
+        assert_eq!(adjust("none"), Some(FontSizeAdjust::None));
+        assert_eq!(adjust("-1"), None);
+        assert_eq!(adjust("cap-height 0.7"), None);
+    }
+
+    #[test]
+    fn font_weight_keywords_and_numbers() {
+        let weight = |css: &str| {
+            let mut input = ParserInput::new(css);
+            parse_font_weight(&mut Parser::new(&mut input)).map(|w| w.0)
+        };
+        assert_eq!(weight("lighter"), Some(300));
+        assert_eq!(weight("bolder"), Some(700));
+        assert_eq!(weight("600"), Some(600));
+        assert_eq!(weight("550"), Some(600));
+        assert_eq!(weight("1000"), Some(900));
+        assert_eq!(weight("0"), None);
+        assert_eq!(weight("heavy"), None);
+    }
+
     fn font(css: &str) -> Vec<Declaration> {
         let mut input = ParserInput::new(css);
         parse_font_shorthand(&mut Parser::new(&mut input)).unwrap_or_default()
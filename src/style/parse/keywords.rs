@@ -6,12 +6,13 @@
 use cssparser::Parser;
 
 use crate::style::properties::{
-    BorderCollapse, BorderStyle, BoxSizing, BreakValue, Clear, DecorationStyle, Display, Float,
-    FontStyle, FontVariant, Hyphens, ListStylePosition, ListStyleType, OverflowWrap, TextAlign,
-    TextTransform, VerticalAlign, Visibility, WhiteSpace, WordBreak,
+    BorderCollapse, BorderStyle, BoxSizing, BreakValue, Clear, ColumnSpan, DecorationStyle,
+    Direction, Display, Float, FontStyle, FontVariant, Hyphens, ListStylePosition, ListStyleType,
+    Overflow, OverflowWrap, TableLayout, TextAlign, TextTransform, VerticalAlign, Visibility,
+    WhiteSpace, WordBreak, WritingMode,
 };
 
-use crate::style::Declaration;
+use crate::style::{CssWideKeyword, Declaration};
 
 /// Macro for generating keyword parser functions.
 ///
@@ -29,35 +30,82 @@ macro_rules! keyword_parser {
 // Generate keyword parsers for all enum_property! types
 keyword_parser!(parse_font_style, FontStyle);
 keyword_parser!(parse_font_variant, FontVariant);
-keyword_parser!(parse_text_align, TextAlign);
 keyword_parser!(parse_text_transform, TextTransform);
 keyword_parser!(parse_hyphens, Hyphens);
+keyword_parser!(parse_direction, Direction);
 keyword_parser!(parse_white_space, WhiteSpace);
+
+/// Parse `writing-mode`, accepting the SVG 1.1 values (`lr-tb`, `tb-rl`, ...)
+/// that `-epub-writing-mode` still sees in older Japanese EPUBs.
+pub(crate) fn parse_writing_mode(input: &mut Parser<'_, '_>) -> Option<WritingMode> {
+    let token = input.expect_ident_cloned().ok()?;
+    match token.as_ref() {
+        "lr-tb" | "lr" | "rl-tb" | "rl" => Some(WritingMode::HorizontalTb),
+        "tb-rl" | "tb" => Some(WritingMode::VerticalRl),
+        "tb-lr" => Some(WritingMode::VerticalLr),
+        other => WritingMode::from_css(other),
+    }
+}
 keyword_parser!(parse_decoration_style, DecorationStyle);
 keyword_parser!(parse_display, Display);
 keyword_parser!(parse_float, Float);
 keyword_parser!(parse_clear, Clear);
 keyword_parser!(parse_visibility, Visibility);
 keyword_parser!(parse_box_sizing, BoxSizing);
+keyword_parser!(parse_overflow, Overflow);
+keyword_parser!(parse_column_span, ColumnSpan);
 keyword_parser!(parse_word_break, WordBreak);
 keyword_parser!(parse_overflow_wrap, OverflowWrap);
 keyword_parser!(parse_list_style_type, ListStyleType);
 keyword_parser!(parse_list_style_position, ListStylePosition);
 keyword_parser!(parse_border_collapse, BorderCollapse);
+keyword_parser!(parse_table_layout, TableLayout);
 keyword_parser!(parse_vertical_align, VerticalAlign);
 
+/// Parse a CSS-wide keyword standing alone as the whole value (optionally
+/// followed by `!important`). `revert` is treated as `unset`.
+pub(crate) fn parse_css_wide_keyword(input: &mut Parser<'_, '_>) -> Option<CssWideKeyword> {
+    let token = input.expect_ident_cloned().ok()?;
+    let keyword = match token.to_ascii_lowercase().as_str() {
+        "inherit" => CssWideKeyword::Inherit,
+        "initial" => CssWideKeyword::Initial,
+        "unset" | "revert" => CssWideKeyword::Unset,
+        _ => return None,
+    };
+    let rest = input.state();
+    let _ = input.try_parse(cssparser::parse_important);
+    let alone = input.is_exhausted();
+    input.reset(&rest);
+    alone.then_some(keyword)
+}
+
 /// Parse break-before/break-after values with CSS aliases.
 pub(crate) fn parse_break_value(input: &mut Parser<'_, '_>) -> Option<BreakValue> {
     let token = input.expect_ident_cloned().ok()?;
     match token.as_ref() {
         "auto" => Some(BreakValue::Auto),
-        "always" | "page" | "left" | "right" | "recto" | "verso" => Some(BreakValue::Always),
+        "always" | "page" | "left" | "right" => Some(BreakValue::Always),
+        "recto" => Some(BreakValue::Recto),
+        "verso" => Some(BreakValue::Verso),
         "avoid" | "avoid-page" => Some(BreakValue::Avoid),
         "column" | "avoid-column" => Some(BreakValue::Column),
         _ => None,
     }
 }
 
+/// Parse text-align, accepting the legacy vendor `center` keywords that
+/// center block children as well as inline content.
+pub(crate) fn parse_text_align(input: &mut Parser<'_, '_>) -> Option<TextAlign> {
+    let token = input.expect_ident_cloned().ok()?;
+    match token.as_ref() {
+        "-webkit-center" | "-moz-center" | "-khtml-center" => Some(TextAlign::Center),
+        // `justify-all` also justifies the last line; readers don't support
+        // that, so treat it as plain justify.
+        "justify-all" => Some(TextAlign::Justify),
+        other => TextAlign::from_css(other),
+    }
+}
+
 /// Parse break-inside values with CSS aliases.
 pub(crate) fn parse_break_inside(input: &mut Parser<'_, '_>) -> Option<BreakValue> {
     let token = input.expect_ident_cloned().ok()?;
@@ -85,11 +133,20 @@ pub(crate) fn parse_border_style_value(input: &mut Parser<'_, '_>) -> Option<Bor
     }
 }
 
+/// Parse a `list-style-image` value: `none` or a `url()`.
+pub(crate) fn parse_list_style_image(input: &mut Parser<'_, '_>) -> Option<Option<String>> {
+    if input.try_parse(|i| i.expect_ident_matching("none")).is_ok() {
+        return Some(None);
+    }
+    let url = input.expect_url().ok()?;
+    Some(Some(url.as_ref().to_string()))
+}
+
 /// Parse the list-style shorthand: list-style-type, list-style-position, list-style-image
-/// We only care about type and position (image is not supported).
 pub(crate) fn parse_list_style_shorthand(input: &mut Parser<'_, '_>) -> Vec<Declaration> {
     let mut list_style_type = None;
     let mut list_style_position = None;
+    let mut list_style_image = None;
 
     // Parse up to 3 values in any order
     for _ in 0..3 {
@@ -133,10 +190,15 @@ pub(crate) fn parse_list_style_shorthand(input: &mut Parser<'_, '_>) -> Vec<Decl
             input.reset(&state);
             break;
         } else {
-            // Not an identifier (could be url() for list-style-image), skip it
             input.reset(&state);
-            // Try to skip one value
-            if input.expect_url().is_ok() || input.expect_function().is_ok() {
+            if list_style_image.is_none()
+                && let Ok(url) = input.try_parse(|i| i.expect_url())
+            {
+                list_style_image = Some(url.as_ref().to_string());
+                continue;
+            }
+            // Skip other functions (gradients as marker images)
+            if input.expect_function().is_ok() {
                 continue;
             }
             break;
@@ -150,5 +212,89 @@ pub(crate) fn parse_list_style_shorthand(input: &mut Parser<'_, '_>) -> Vec<Decl
     if let Some(p) = list_style_position {
         decls.push(Declaration::ListStylePosition(p));
     }
+    // Like any shorthand, an omitted image resets to `none`.
+    decls.push(Declaration::ListStyleImage(list_style_image));
     decls
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cssparser::ParserInput;
+
+    fn list_style(css: &str) -> Vec<Declaration> {
+        let mut input = ParserInput::new(css);
+        parse_list_style_shorthand(&mut Parser::new(&mut input))
+    }
+
+    #[test]
+    fn list_style_shorthand_captures_image_and_type() {
+        let decls = format!("{:?}", list_style(r#"url("img/bullet.png") square inside"#));
+        assert!(
+            decls.contains(r#"ListStyleImage(Some("img/bullet.png"))"#),
+            "{decls}"
+        );
+        assert!(decls.contains("ListStyleType(Square)"), "{decls}");
+        assert!(decls.contains("ListStylePosition(Inside)"), "{decls}");
+
+        // Unquoted url, type first; an omitted image resets to none.
+        let decls = format!("{:?}", list_style("circle url(star.svg)"));
+        assert!(
+            decls.contains(r#"ListStyleImage(Some("star.svg"))"#),
+            "{decls}"
+        );
+        assert!(decls.contains("ListStyleType(Circle)"), "{decls}");
+        let decls = format!("{:?}", list_style("decimal"));
+        assert!(decls.contains("ListStyleImage(None)"), "{decls}");
+    }
+
+    #[test]
+    fn vendor_center_keywords_parse_as_center() {
+        for css in ["-webkit-center", "-moz-center", "center"] {
+            let mut input = ParserInput::new(css);
+            let align = parse_text_align(&mut Parser::new(&mut input));
+            assert_eq!(align, Some(TextAlign::Center), "{css}");
+        }
+    }
+
+    #[test]
+    fn layout_display_keywords_parse_distinctly() {
+        for (css, display) in [
+            ("inline-block", Display::InlineBlock),
+            ("flex", Display::Flex),
+            ("grid", Display::Grid),
+            ("table", Display::Table),
+        ] {
+            let mut input = ParserInput::new(css);
+            assert_eq!(
+                parse_display(&mut Parser::new(&mut input)),
+                Some(display),
+                "{css}"
+            );
+        }
+        assert!(!Display::InlineBlock.lays_out_as_block());
+        assert!(!Display::ListItem.lays_out_as_block());
+        assert!(Display::Flex.lays_out_as_block());
+    }
+
+    #[test]
+    fn justify_all_parses_as_justify() {
+        let mut input = ParserInput::new("justify-all");
+        let align = parse_text_align(&mut Parser::new(&mut input));
+        assert_eq!(align, Some(TextAlign::Justify));
+    }
+
+    #[test]
+    fn legacy_svg_writing_modes_map_to_css_values() {
+        for (css, mode) in [
+            ("tb-rl", WritingMode::VerticalRl),
+            ("vertical-rl", WritingMode::VerticalRl),
+            ("tb-lr", WritingMode::VerticalLr),
+            ("lr-tb", WritingMode::HorizontalTb),
+        ] {
+            let mut input = ParserInput::new(css);
+            let parsed = parse_writing_mode(&mut Parser::new(&mut input));
+            assert_eq!(parsed, Some(mode), "{css}");
+        }
+    }
+}
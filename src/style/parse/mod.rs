@@ -4,10 +4,12 @@ pub(crate) mod border;
 pub(crate) mod box_model;
 pub(crate) mod font;
 pub(crate) mod keywords;
+pub(crate) mod page;
 pub(crate) mod values;
 
 mod stylesheet;
 
 // Public types only
+pub use page::{PageRule, PageSize};
 pub use stylesheet::{CssRule, InlineStyle, Origin, Specificity, Stylesheet};
 pub use values::TextDecorationValue;
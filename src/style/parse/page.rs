@@ -0,0 +1,294 @@
+//! `@page` rule parsing: page size and margins.
+
+use std::fmt::Write;
+
+use cssparser::{ParseError, Parser, Token};
+
+use crate::style::ToCss;
+use crate::style::properties::Length;
+
+use super::box_model::expand_shorthand_4;
+use super::values::parse_length;
+
+/// Page geometry declared by `@page` rules.
+///
+/// Print-oriented and pre-paginated books use it to state the page box
+/// their content was laid out for. Only the unqualified `@page` rule is
+/// kept; `:first`, `:left`, `:right` and named pages are skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PageRule {
+    /// Page box size from the `size` property; `None` for `auto`, an
+    /// orientation alone, or no `size` at all.
+    pub size: Option<PageSize>,
+    /// Top page margin (`Length::Auto` when unset).
+    pub margin_top: Length,
+    /// Right page margin (`Length::Auto` when unset).
+    pub margin_right: Length,
+    /// Bottom page margin (`Length::Auto` when unset).
+    pub margin_bottom: Length,
+    /// Left page margin (`Length::Auto` when unset).
+    pub margin_left: Length,
+}
+
+/// A page box size in CSS pixels. Named sizes (`A5`, `letter`) are
+/// resolved to their dimensions, and orientation keywords applied.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageSize {
+    /// Page width in CSS pixels.
+    pub width: f32,
+    /// Page height in CSS pixels.
+    pub height: f32,
+}
+
+impl PageRule {
+    /// Overlay the properties `later` sets, as a later `@page` rule would.
+    pub(crate) fn merge(&mut self, later: &PageRule) {
+        if later.size.is_some() {
+            self.size = later.size;
+        }
+        for (mine, theirs) in [
+            (&mut self.margin_top, later.margin_top),
+            (&mut self.margin_right, later.margin_right),
+            (&mut self.margin_bottom, later.margin_bottom),
+            (&mut self.margin_left, later.margin_left),
+        ] {
+            if theirs != Length::Auto {
+                *mine = theirs;
+            }
+        }
+    }
+
+    fn margins(&self) -> [(&'static str, Length); 4] {
+        [
+            ("margin-top", self.margin_top),
+            ("margin-right", self.margin_right),
+            ("margin-bottom", self.margin_bottom),
+            ("margin-left", self.margin_left),
+        ]
+    }
+
+    /// Whether no property was set.
+    pub fn is_empty(&self) -> bool {
+        *self == PageRule::default()
+    }
+}
+
+impl ToCss for PageRule {
+    fn to_css(&self, buf: &mut String) {
+        buf.push_str("@page {");
+        if let Some(size) = self.size {
+            write!(buf, " size: {}px {}px;", size.width, size.height).unwrap();
+        }
+        for (name, margin) in self.margins() {
+            if margin != Length::Auto {
+                write!(buf, " {name}: ").unwrap();
+                margin.to_css(buf);
+                buf.push(';');
+            }
+        }
+        buf.push_str(" }");
+    }
+}
+
+/// Named page sizes from CSS Paged Media, as (width, height) in
+/// millimetres, portrait.
+const NAMED_SIZES_MM: &[(&str, f32, f32)] = &[
+    ("a5", 148.0, 210.0),
+    ("a4", 210.0, 297.0),
+    ("a3", 297.0, 420.0),
+    ("b5", 176.0, 250.0),
+    ("b4", 250.0, 353.0),
+    ("jis-b5", 182.0, 257.0),
+    ("jis-b4", 257.0, 364.0),
+    ("letter", 215.9, 279.4),
+    ("legal", 215.9, 355.6),
+    ("ledger", 279.4, 431.8),
+];
+
+const PX_PER_MM: f32 = 96.0 / 25.4;
+
+/// Parse the declarations of an `@page` block. Margin-box at-rules
+/// (`@top-center { ... }`) and unknown properties are skipped.
+pub(crate) fn parse_page_block(input: &mut Parser<'_, '_>) -> PageRule {
+    let mut rule = PageRule::default();
+    while !input.is_exhausted() {
+        let Ok(name) = input.expect_ident_cloned() else {
+            // A margin box's at-keyword and block, or a stray token.
+            continue;
+        };
+        let name = name.as_ref().to_ascii_lowercase();
+        if input.expect_colon().is_err() {
+            continue;
+        }
+        let _ = input.parse_until_after(
+            cssparser::Delimiter::Semicolon,
+            |value_input| -> Result<(), ParseError<'_, ()>> {
+                match name.as_str() {
+                    "size" => rule.size = parse_page_size(value_input),
+                    "margin" => {
+                        let mut values = Vec::with_capacity(4);
+                        while values.len() < 4 {
+                            match value_input.try_parse(|i| parse_page_length(i).ok_or(())) {
+                                Ok(len) => values.push(len),
+                                Err(()) => break,
+                            }
+                        }
+                        if let Some((top, right, bottom, left)) = expand_shorthand_4(values) {
+                            rule.margin_top = top;
+                            rule.margin_right = right;
+                            rule.margin_bottom = bottom;
+                            rule.margin_left = left;
+                        }
+                    }
+                    "margin-top" => set_margin(&mut rule.margin_top, value_input),
+                    "margin-right" => set_margin(&mut rule.margin_right, value_input),
+                    "margin-bottom" => set_margin(&mut rule.margin_bottom, value_input),
+                    "margin-left" => set_margin(&mut rule.margin_left, value_input),
+                    _ => {}
+                }
+                while value_input.next().is_ok() {}
+                Ok(())
+            },
+        );
+    }
+    rule
+}
+
+fn set_margin(side: &mut Length, input: &mut Parser<'_, '_>) {
+    if let Some(len) = parse_page_length(input) {
+        *side = len;
+    }
+}
+
+/// `size`: `auto`, one or two lengths, or a named size and/or an
+/// orientation in either order.
+fn parse_page_size(input: &mut Parser<'_, '_>) -> Option<PageSize> {
+    let mut dims = Vec::with_capacity(2);
+    let mut named = None;
+    let mut landscape = None;
+    while !input.is_exhausted() {
+        if let Ok(len) = input.try_parse(|i| parse_page_length(i).ok_or(())) {
+            match len {
+                Length::Px(px) if px > 0.0 && dims.len() < 2 => dims.push(px),
+                _ => return None,
+            }
+            continue;
+        }
+        let ident = input.expect_ident_cloned().ok()?;
+        let ident = ident.as_ref().to_ascii_lowercase();
+        match ident.as_str() {
+            "auto" => return None,
+            "portrait" => landscape = Some(false),
+            "landscape" => landscape = Some(true),
+            name => {
+                let &(_, width, height) =
+                    NAMED_SIZES_MM.iter().find(|(known, ..)| *known == name)?;
+                named = Some((width * PX_PER_MM, height * PX_PER_MM));
+            }
+        }
+    }
+    let (width, height) = match (dims.as_slice(), named) {
+        ([side], None) => (*side, *side),
+        ([width, height], None) => (*width, *height),
+        ([], Some(size)) => size,
+        _ => return None,
+    };
+    let (width, height) = match landscape {
+        Some(true) => (width.max(height), width.min(height)),
+        Some(false) => (width.min(height), width.max(height)),
+        None => (width, height),
+    };
+    Some(PageSize { width, height })
+}
+
+/// A length, also accepting the physical units (`in`, `cm`, `mm`, `Q`,
+/// `pc`) page geometry is usually given in, converted to pixels.
+fn parse_page_length(input: &mut Parser<'_, '_>) -> Option<Length> {
+    if let Ok(px) = input.try_parse(|i| match i.next() {
+        Ok(Token::Dimension { value, unit, .. }) => {
+            let per_unit = match unit.to_ascii_lowercase().as_str() {
+                "in" => 96.0,
+                "cm" => PX_PER_MM * 10.0,
+                "mm" => PX_PER_MM,
+                "q" => PX_PER_MM / 4.0,
+                "pc" => 16.0,
+                _ => return Err(()),
+            };
+            Ok(*value * per_unit)
+        }
+        _ => Err(()),
+    }) {
+        return Some(Length::Px(px));
+    }
+    parse_length(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::Stylesheet;
+
+    fn page_rule(css: &str) -> Option<PageRule> {
+        Stylesheet::parse(css).page_rule
+    }
+
+    fn assert_px(length: Length, expected: f32) {
+        match length {
+            Length::Px(px) => assert!((px - expected).abs() < 0.01, "{px} != {expected}"),
+            other => panic!("expected {expected}px, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn page_size_and_margin_are_captured() {
+        let rule = page_rule("@page { size: 5in 8in; margin: 1cm; } p { color: red }").unwrap();
+        assert_eq!(
+            rule.size,
+            Some(PageSize {
+                width: 480.0,
+                height: 768.0
+            })
+        );
+        for margin in [
+            rule.margin_top,
+            rule.margin_right,
+            rule.margin_bottom,
+            rule.margin_left,
+        ] {
+            assert_px(margin, 96.0 / 2.54);
+        }
+        assert_eq!(Stylesheet::parse("@page { size: 5in 8in }").rules.len(), 0);
+    }
+
+    #[test]
+    fn named_sizes_and_orientation_resolve() {
+        let size = |css| page_rule(css).and_then(|rule| rule.size).unwrap();
+        let a5 = size("@page { size: A5 }");
+        assert!((a5.width - 148.0 * PX_PER_MM).abs() < 0.01);
+        assert!((a5.height - 210.0 * PX_PER_MM).abs() < 0.01);
+        let landscape = size("@page { size: landscape a5 }");
+        assert_eq!((landscape.width, landscape.height), (a5.height, a5.width));
+        assert_eq!(size("@page { size: 6in }").height, 576.0);
+        assert_eq!(page_rule("@page { size: auto }").unwrap().size, None);
+    }
+
+    #[test]
+    fn later_page_rules_override_and_qualified_pages_are_skipped() {
+        let rule = page_rule(
+            "@page { size: 5in 8in; margin: 1in; }\
+             @page :first { margin-top: 3in; }\
+             @page { margin-left: 0.5in; @top-center { content: 'x' } margin-right: 2pc; }",
+        )
+        .unwrap();
+        assert_eq!(rule.size.unwrap().width, 480.0);
+        assert_px(rule.margin_top, 96.0);
+        assert_px(rule.margin_left, 48.0);
+        assert_px(rule.margin_right, 32.0);
+        assert_eq!(
+            rule.to_css_string(),
+            "@page { size: 480px 768px; margin-top: 96px; margin-right: 32px; \
+             margin-bottom: 96px; margin-left: 48px; }"
+        );
+        assert_eq!(page_rule("p { margin: 1in }"), None);
+    }
+}
@@ -11,6 +11,7 @@ use crate::model::FontFace;
 use crate::style::Declaration;
 
 use super::font::parse_font_face_block;
+use super::page::{PageRule, parse_page_block};
 
 /// A parsed CSS stylesheet.
 #[derive(Debug, Default, Clone)]
@@ -19,6 +20,9 @@ pub struct Stylesheet {
     pub rules: Vec<CssRule>,
     /// @font-face rules defining font family to file mappings.
     pub font_faces: Vec<FontFace>,
+    /// Page size and margins from the `@page` rules, merged in source
+    /// order; `None` when the stylesheet has none.
+    pub page_rule: Option<PageRule>,
 }
 
 /// A CSS rule with selectors and declarations.
@@ -95,10 +99,12 @@ impl Stylesheet {
         let mut parser = Parser::new(&mut input);
         let mut rules = Vec::new();
         let mut font_faces = Vec::new();
+        let mut page_rule = None;
 
         let mut rule_parser = TopLevelRuleParser {
             rules: &mut rules,
             font_faces: &mut font_faces,
+            page_rule: &mut page_rule,
         };
         let stylesheet_parser = StyleSheetParser::new(&mut parser, &mut rule_parser);
 
@@ -107,13 +113,33 @@ impl Stylesheet {
             let _ = result;
         }
 
-        Self { rules, font_faces }
+        Self {
+            rules,
+            font_faces,
+            page_rule,
+        }
     }
 
     /// Check if the stylesheet is empty.
     pub fn is_empty(&self) -> bool {
         self.rules.is_empty()
     }
+
+    /// Rewrite the `url()` references in rule declarations (marker images)
+    /// with `f`, e.g. to resolve them against the stylesheet's location.
+    pub(crate) fn map_urls(&mut self, f: impl Fn(&str) -> String) {
+        for rule in &mut self.rules {
+            let decls = rule
+                .declarations
+                .iter_mut()
+                .chain(rule.important_declarations.iter_mut());
+            for decl in decls {
+                if let Declaration::ListStyleImage(Some(href)) = decl {
+                    *href = f(href);
+                }
+            }
+        }
+    }
 }
 
 /// Declarations parsed from an HTML `style` attribute.
@@ -159,39 +185,54 @@ impl InlineStyle {
 struct TopLevelRuleParser<'a> {
     rules: &'a mut Vec<CssRule>,
     font_faces: &'a mut Vec<FontFace>,
+    page_rule: &'a mut Option<PageRule>,
 }
 
-/// Prelude for @font-face rules (empty, just a marker).
-struct FontFacePrelude;
+/// The at-rules the top-level parser keeps.
+enum AtRulePrelude {
+    FontFace,
+    Page,
+}
 
 impl<'i> AtRuleParser<'i> for TopLevelRuleParser<'_> {
-    type Prelude = FontFacePrelude;
+    type Prelude = AtRulePrelude;
     type AtRule = ();
     type Error = ();
 
     fn parse_prelude<'t>(
         &mut self,
         name: cssparser::CowRcStr<'i>,
-        _input: &mut Parser<'i, 't>,
+        input: &mut Parser<'i, 't>,
     ) -> Result<Self::Prelude, ParseError<'i, Self::Error>> {
         if name.eq_ignore_ascii_case("font-face") {
             // @font-face has no prelude, just a block
-            Ok(FontFacePrelude)
+            Ok(AtRulePrelude::FontFace)
+        } else if name.eq_ignore_ascii_case("page") && input.is_exhausted() {
+            // Only the unqualified @page: `:first`, `:left` and named pages
+            // style particular pages, not the book's page box.
+            Ok(AtRulePrelude::Page)
         } else {
             // Skip other at-rules
-            Err(_input.new_custom_error(()))
+            Err(input.new_custom_error(()))
         }
     }
 
     fn parse_block<'t>(
         &mut self,
-        _prelude: Self::Prelude,
+        prelude: Self::Prelude,
         _start: &cssparser::ParserState,
         input: &mut Parser<'i, 't>,
     ) -> Result<Self::AtRule, ParseError<'i, Self::Error>> {
-        // Parse @font-face declarations
-        if let Some(font_face) = parse_font_face_block(input) {
-            self.font_faces.push(font_face);
+        match prelude {
+            AtRulePrelude::FontFace => {
+                if let Some(font_face) = parse_font_face_block(input) {
+                    self.font_faces.push(font_face);
+                }
+            }
+            AtRulePrelude::Page => {
+                let rule = parse_page_block(input);
+                self.page_rule.get_or_insert_default().merge(&rule);
+            }
         }
         Ok(())
     }
@@ -252,7 +293,18 @@ fn parse_selector_list<'i>(
     )
     .map_err(|_| location.new_custom_error(()))?;
 
-    Ok(selectors.slice().to_vec())
+    // Pseudo-elements style generated content the DOM doesn't have; drop
+    // those selectors so their declarations never land on the host element.
+    let selectors: Vec<_> = selectors
+        .slice()
+        .iter()
+        .filter(|selector| !selector.has_pseudo_element())
+        .cloned()
+        .collect();
+    if selectors.is_empty() {
+        return Err(location.new_custom_error(()));
+    }
+    Ok(selectors)
 }
 
 struct DeclarationListParser<'a> {
@@ -4,7 +4,8 @@
 
 use cssparser::{ParseError, Parser, Token};
 
-use crate::style::properties::{Color, Length};
+use crate::style::properties::{Color, DecorationStyle, Length};
+use crate::style::{ColorProperty, Declaration};
 
 /// Text decoration value (can combine underline and line-through).
 #[derive(Debug, Clone, Copy, Default)]
@@ -17,6 +18,43 @@ pub struct TextDecorationValue {
     pub overline: bool,
 }
 
+/// A `<color>` value that may be the `currentColor` keyword.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ColorValue {
+    /// A concrete color.
+    Color(Color),
+    /// `currentColor`: the element's computed `color`.
+    Current,
+}
+
+impl ColorValue {
+    /// The declaration setting `property` to this value.
+    pub(crate) fn declaration(self, property: ColorProperty) -> Declaration {
+        match self {
+            ColorValue::Current => Declaration::CurrentColor(property),
+            ColorValue::Color(c) => match property {
+                ColorProperty::Background => Declaration::BackgroundColor(c),
+                ColorProperty::TextDecoration => Declaration::TextDecorationColor(c),
+                ColorProperty::BorderTop => Declaration::BorderTopColor(c),
+                ColorProperty::BorderRight => Declaration::BorderRightColor(c),
+                ColorProperty::BorderBottom => Declaration::BorderBottomColor(c),
+                ColorProperty::BorderLeft => Declaration::BorderLeftColor(c),
+            },
+        }
+    }
+}
+
+/// Parse a `<color>`, also accepting `currentColor`.
+pub(crate) fn parse_color_value(input: &mut Parser<'_, '_>) -> Option<ColorValue> {
+    if input
+        .try_parse(|i| i.expect_ident_matching("currentcolor"))
+        .is_ok()
+    {
+        return Some(ColorValue::Current);
+    }
+    parse_color(input).map(ColorValue::Color)
+}
+
 pub(crate) fn parse_color(input: &mut Parser<'_, '_>) -> Option<Color> {
     // Try named colors first
     if let Ok(token) = input.try_parse(|i| i.expect_ident_cloned()) {
@@ -55,8 +93,13 @@ pub(crate) fn parse_color(input: &mut Parser<'_, '_>) -> Option<Color> {
 /// The background shorthand can contain: color, image, position, repeat, size, attachment,
 /// origin, clip - in any order. We parse tokens in a loop and extract any color we find.
 /// See https://www.w3.org/TR/css-backgrounds-3/#background
+///
+/// No target format renders gradients, so a gradient image with no explicit
+/// color falls back to its first color stop — otherwise gradient-only
+/// backgrounds (common on cover and section pages) vanish entirely.
 pub(crate) fn parse_background_shorthand(input: &mut Parser<'_, '_>) -> Option<Color> {
     let mut color: Option<Color> = None;
+    let mut gradient_color: Option<Color> = None;
 
     // Try to parse each component in any order, like lightningcss does
     loop {
@@ -74,19 +117,22 @@ pub(crate) fn parse_background_shorthand(input: &mut Parser<'_, '_>) -> Option<C
             continue;
         }
 
-        // Skip over functions like linear-gradient() etc.
-        if input
-            .try_parse(|i: &mut Parser<'_, '_>| {
-                let _ = i.expect_function()?;
-                i.parse_nested_block(
-                    |nested: &mut Parser<'_, '_>| -> Result<(), ParseError<'_, ()>> {
+        // Skip over functions like linear-gradient() etc., keeping the first
+        // color stop of a gradient as the fallback color.
+        if let Ok(stop) = input.try_parse(|i: &mut Parser<'_, '_>| {
+            let is_gradient = i.expect_function()?.ends_with("gradient");
+            i.parse_nested_block(
+                |nested: &mut Parser<'_, '_>| -> Result<Option<Color>, ParseError<'_, ()>> {
+                    Ok(if is_gradient {
+                        first_color_stop(nested)
+                    } else {
                         while nested.next().is_ok() {}
-                        Ok(())
-                    },
-                )
-            })
-            .is_ok()
-        {
+                        None
+                    })
+                },
+            )
+        }) {
+            gradient_color = gradient_color.or(stop);
             continue;
         }
 
@@ -136,7 +182,27 @@ pub(crate) fn parse_background_shorthand(input: &mut Parser<'_, '_>) -> Option<C
         break;
     }
 
-    color
+    color.or(gradient_color)
+}
+
+/// Find the first color stop in a gradient's argument list, consuming the
+/// rest. Direction/shape arguments (`to right`, `45deg`, `circle at center`)
+/// and stop positions are skipped token by token until a color parses.
+fn first_color_stop(input: &mut Parser<'_, '_>) -> Option<Color> {
+    let mut stop = None;
+    while !input.is_exhausted() {
+        if stop.is_none()
+            && let Ok(c) =
+                input.try_parse(|i| parse_color(i).ok_or(i.new_custom_error::<_, ()>(())))
+        {
+            stop = Some(c);
+            continue;
+        }
+        if input.next().is_err() {
+            break;
+        }
+    }
+    stop
 }
 
 fn parse_hex_color(hex: &str) -> Option<Color> {
@@ -272,7 +338,19 @@ fn parse_color_component<'i, 't>(input: &mut Parser<'i, 't>) -> Result<u8, Parse
 }
 
 pub(crate) fn parse_length(input: &mut Parser<'_, '_>) -> Option<Length> {
-    match input.next().ok()? {
+    let token = input.next().ok()?.clone();
+    if let Token::Function(name) = &token
+        && name.eq_ignore_ascii_case("calc")
+    {
+        return input
+            .parse_nested_block(|i| parse_calc(i).ok_or_else(|| i.new_custom_error::<_, ()>(())))
+            .ok();
+    }
+    length_from_token(&token)
+}
+
+fn length_from_token(token: &Token<'_>) -> Option<Length> {
+    match token {
         Token::Dimension { value, unit, .. } => {
             let length = match unit.as_ref() {
                 "px" => Length::Px(*value),
@@ -297,8 +375,94 @@ pub(crate) fn parse_length(input: &mut Parser<'_, '_>) -> Option<Length> {
     }
 }
 
+/// Pixels per `em`/`rem` assumed when a `calc()` mixes font-relative and
+/// absolute lengths (the default 16px font size).
+const CALC_PX_PER_EM: f32 = 16.0;
+
+/// One `calc()` operand.
+#[derive(Clone, Copy)]
+enum CalcValue {
+    Length(Length),
+    Number(f32),
+}
+
+/// Evaluate the body of a `calc()`: a single operand, or two joined by
+/// `+`, `-`, `*` or `/`. Sums need lengths (`em`, `rem` and `px` convert
+/// at 16px per em; a percentage mixed with anything else keeps the first
+/// operand), products and quotients a length and a number. Nested
+/// parentheses and longer expressions are rejected.
+fn parse_calc(input: &mut Parser<'_, '_>) -> Option<Length> {
+    let lhs = parse_calc_operand(input)?;
+    if input.is_exhausted() {
+        return match lhs {
+            CalcValue::Length(length) => Some(length),
+            CalcValue::Number(_) => None,
+        };
+    }
+    let op = match input.next().ok()? {
+        Token::Delim(c @ ('+' | '-' | '*' | '/')) => *c,
+        _ => return None,
+    };
+    let rhs = parse_calc_operand(input)?;
+    if !input.is_exhausted() {
+        return None;
+    }
+    match (op, lhs, rhs) {
+        ('+', CalcValue::Length(a), CalcValue::Length(b)) => Some(calc_sum(a, b, 1.0)),
+        ('-', CalcValue::Length(a), CalcValue::Length(b)) => Some(calc_sum(a, b, -1.0)),
+        ('*', CalcValue::Length(l), CalcValue::Number(n))
+        | ('*', CalcValue::Number(n), CalcValue::Length(l)) => scale_length(l, n),
+        ('/', CalcValue::Length(l), CalcValue::Number(n)) if n != 0.0 => scale_length(l, 1.0 / n),
+        _ => None,
+    }
+}
+
+fn parse_calc_operand(input: &mut Parser<'_, '_>) -> Option<CalcValue> {
+    match input.next().ok()? {
+        Token::Number { value, .. } => Some(CalcValue::Number(*value)),
+        token => length_from_token(token)
+            .filter(|l| *l != Length::Auto)
+            .map(CalcValue::Length),
+    }
+}
+
+/// `a + sign * b`, converting `b` into `a`'s unit where that's possible
+/// and keeping `a` where it isn't.
+fn calc_sum(a: Length, b: Length, sign: f32) -> Length {
+    let px = |l: Length| match l {
+        Length::Px(v) => Some(v),
+        Length::Em(v) | Length::Rem(v) => Some(v * CALC_PX_PER_EM),
+        _ => None,
+    };
+    match (a, b) {
+        (Length::Px(x), Length::Px(y)) => Length::Px(x + sign * y),
+        (Length::Em(x), Length::Em(y)) => Length::Em(x + sign * y),
+        (Length::Rem(x), Length::Rem(y)) => Length::Rem(x + sign * y),
+        (Length::Percent(x), Length::Percent(y)) => Length::Percent(x + sign * y),
+        (first, other) => match (first, px(other)) {
+            (Length::Px(x), Some(y)) => Length::Px(x + sign * y),
+            (Length::Em(x), Some(y)) => Length::Em(x + sign * y / CALC_PX_PER_EM),
+            (Length::Rem(x), Some(y)) => Length::Rem(x + sign * y / CALC_PX_PER_EM),
+            _ => first,
+        },
+    }
+}
+
+fn scale_length(length: Length, factor: f32) -> Option<Length> {
+    Some(match length {
+        Length::Px(v) => Length::Px(v * factor),
+        Length::Em(v) => Length::Em(v * factor),
+        Length::Rem(v) => Length::Rem(v * factor),
+        Length::Percent(v) => Length::Percent(v * factor),
+        Length::Auto => return None,
+    })
+}
+
 /// Parse letter-/word-spacing: a length, or the `normal` reset keyword
 /// (mapped to `Length::Auto`, the unset value — both mean no extra spacing).
+/// Zero lengths in any unit canonicalize to `Length::Px(0.0)`, so `0`,
+/// `0em` and `-0px` intern as one style. They stay distinct from `normal`:
+/// a zero inside a spaced ancestor is a reset the CSS output must carry.
 pub(crate) fn parse_spacing(input: &mut Parser<'_, '_>) -> Option<Length> {
     if input
         .try_parse(|i| i.expect_ident_matching("normal"))
@@ -306,7 +470,23 @@ pub(crate) fn parse_spacing(input: &mut Parser<'_, '_>) -> Option<Length> {
     {
         return Some(Length::Auto);
     }
-    parse_length(input)
+    parse_length(input).map(|length| match length {
+        Length::Px(v) | Length::Em(v) | Length::Rem(v) | Length::Percent(v) if v == 0.0 => {
+            Length::Px(0.0)
+        }
+        length => length,
+    })
+}
+
+/// Parse the length/percentage form of `vertical-align`. A zero shift is
+/// the baseline and parses as `Length::Auto`.
+pub(crate) fn parse_baseline_shift(input: &mut Parser<'_, '_>) -> Option<Length> {
+    parse_length(input).map(|length| match length {
+        Length::Px(v) | Length::Em(v) | Length::Rem(v) | Length::Percent(v) if v == 0.0 => {
+            Length::Auto
+        }
+        length => length,
+    })
 }
 
 pub(crate) fn parse_integer(input: &mut Parser<'_, '_>) -> Option<u32> {
@@ -336,6 +516,53 @@ pub(crate) fn parse_text_decoration(input: &mut Parser<'_, '_>) -> Option<TextDe
     if found { Some(result) } else { None }
 }
 
+/// Parse the `text-decoration` shorthand: line keywords (any number, e.g.
+/// `underline overline`), a line style (`solid`, `dotted`, `dashed`,
+/// `double`), and a color, in any order. Only the components present
+/// produce declarations; unsupported keywords (`wavy`, `blink`) are skipped.
+pub(crate) fn parse_text_decoration_shorthand(input: &mut Parser<'_, '_>) -> Vec<Declaration> {
+    let mut lines = TextDecorationValue::default();
+    let mut found_lines = false;
+    let mut style = None;
+    let mut color = None;
+    while !input.is_exhausted() {
+        if color.is_none()
+            && let Ok(c) = input.try_parse(|i| parse_color_value(i).ok_or(()))
+        {
+            color = Some(c);
+            continue;
+        }
+        let Ok(ident) = input.try_parse(|i| i.expect_ident_cloned()) else {
+            break;
+        };
+        match ident.as_ref() {
+            "underline" => lines.underline = true,
+            "line-through" => lines.line_through = true,
+            "overline" => lines.overline = true,
+            "none" => {}
+            other => {
+                if let Some(s) = DecorationStyle::from_css(other) {
+                    style = Some(s);
+                }
+                continue;
+            }
+        }
+        found_lines = true;
+    }
+
+    let mut decls = Vec::new();
+    if found_lines {
+        decls.push(Declaration::TextDecoration(lines));
+    }
+    if let Some(s) = style {
+        decls.push(Declaration::TextDecorationStyle(s));
+    }
+    if let Some(c) = color {
+        decls.push(c.declaration(ColorProperty::TextDecoration));
+    }
+    decls
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -359,4 +586,130 @@ mod tests {
         // Non-color keywords are not treated as colors.
         assert_eq!(color("inherit"), None);
     }
+
+    fn length(css: &str) -> Option<Length> {
+        let mut input = ParserInput::new(css);
+        parse_length(&mut Parser::new(&mut input))
+    }
+
+    fn spacing(css: &str) -> Option<Length> {
+        let mut input = ParserInput::new(css);
+        parse_spacing(&mut Parser::new(&mut input))
+    }
+
+    #[test]
+    fn spacing_normal_and_zero_are_canonical() {
+        for css in ["normal", "NORMAL"] {
+            assert_eq!(spacing(css), Some(Length::Auto), "{css}");
+        }
+        for css in ["0", "0em", "-0px", "0%"] {
+            assert_eq!(spacing(css), Some(Length::Px(0.0)), "{css}");
+        }
+        assert_eq!(spacing("0.1em"), Some(Length::Em(0.1)));
+        assert_eq!(spacing("-1px"), Some(Length::Px(-1.0)));
+    }
+
+    #[test]
+    fn calc_combines_compatible_lengths() {
+        assert_eq!(length("calc(100% - 20%)"), Some(Length::Percent(80.0)));
+        assert_eq!(length("calc(1em + 4px)"), Some(Length::Em(1.25)));
+        assert_eq!(length("calc(10px + 1rem)"), Some(Length::Px(26.0)));
+        assert_eq!(length("CALC(2em * 3)"), Some(Length::Em(6.0)));
+        assert_eq!(length("calc(3 * 2em)"), Some(Length::Em(6.0)));
+        assert_eq!(length("calc(50% / 2)"), Some(Length::Percent(25.0)));
+        assert_eq!(length("calc(1.5em)"), Some(Length::Em(1.5)));
+    }
+
+    #[test]
+    fn calc_degrades_gracefully() {
+        // A percentage can't absorb a font-relative length: keep the first
+        // operand.
+        assert_eq!(length("calc(100% - 2em)"), Some(Length::Percent(100.0)));
+        assert_eq!(length("calc(2em + 10%)"), Some(Length::Em(2.0)));
+        // Not a length, or not supported.
+        for css in [
+            "calc(2 * 3)",
+            "calc(2em * 1em)",
+            "calc(1em / 0)",
+            "calc((1em + 2px) * 2)",
+            "calc(1em + 2px + 3px)",
+            "calc(1em -2px)",
+        ] {
+            assert_eq!(length(css), None, "{css}");
+        }
+    }
+
+    fn text_decoration(css: &str) -> Vec<Declaration> {
+        let mut input = ParserInput::new(css);
+        parse_text_decoration_shorthand(&mut Parser::new(&mut input))
+    }
+
+    #[test]
+    fn text_decoration_shorthand_reads_lines_style_and_color() {
+        let decls = text_decoration("underline dotted");
+        assert!(
+            matches!(
+                decls[..],
+                [
+                    Declaration::TextDecoration(TextDecorationValue {
+                        underline: true,
+                        line_through: false,
+                        overline: false,
+                    }),
+                    Declaration::TextDecorationStyle(DecorationStyle::Dotted),
+                ]
+            ),
+            "{decls:?}"
+        );
+
+        let decls = text_decoration("#f00 double underline overline");
+        assert!(
+            matches!(
+                decls[..],
+                [
+                    Declaration::TextDecoration(TextDecorationValue {
+                        underline: true,
+                        overline: true,
+                        ..
+                    }),
+                    Declaration::TextDecorationStyle(DecorationStyle::Double),
+                    Declaration::TextDecorationColor(c),
+                ] if c == Color::rgb(255, 0, 0)
+            ),
+            "{decls:?}"
+        );
+
+        // Unsupported styles are skipped without losing the line.
+        let decls = text_decoration("underline wavy");
+        assert!(
+            matches!(decls[..], [Declaration::TextDecoration(d)] if d.underline),
+            "{decls:?}"
+        );
+    }
+
+    fn background(css: &str) -> Option<Color> {
+        let mut input = ParserInput::new(css);
+        parse_background_shorthand(&mut Parser::new(&mut input))
+    }
+
+    #[test]
+    fn gradient_background_falls_back_to_first_stop() {
+        let white = Some(Color::rgb(255, 255, 255));
+        assert_eq!(background("linear-gradient(to right, #fff, #000)"), white);
+        assert_eq!(
+            background("radial-gradient(circle at center, white 0%, black 100%) no-repeat"),
+            white
+        );
+        assert_eq!(
+            background("-webkit-linear-gradient(45deg, rgb(255, 255, 255), red)"),
+            white
+        );
+        // An explicit color wins over the gradient fallback.
+        assert_eq!(
+            background("linear-gradient(#000, #fff) red"),
+            Some(Color::rgb(255, 0, 0))
+        );
+        // Non-gradient images still contribute nothing.
+        assert_eq!(background("url(a.png) no-repeat"), None);
+    }
 }
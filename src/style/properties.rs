@@ -138,6 +138,10 @@ enum_property! {
         Lowercase => "lowercase",
         /// Capitalize the first letter of each word.
         Capitalize => "capitalize",
+        /// Render ASCII as fullwidth forms (`A` → `Ａ`), for CJK text.
+        FullWidth => "full-width",
+        /// Render small kana as their full-size forms (`ぁ` → `あ`).
+        FullSizeKana => "full-size-kana",
     }
 }
 
@@ -155,6 +159,37 @@ enum_property! {
     }
 }
 
+enum_property! {
+    /// CSS `writing-mode` values (block flow direction).
+    pub enum WritingMode {
+        /// Horizontal lines stacked top to bottom (CSS initial value).
+        #[default]
+        HorizontalTb => "horizontal-tb",
+        /// Vertical lines stacked right to left, as in Japanese novels.
+        VerticalRl => "vertical-rl",
+        /// Vertical lines stacked left to right, as in Mongolian.
+        VerticalLr => "vertical-lr",
+    }
+}
+
+impl WritingMode {
+    /// Whether lines run vertically.
+    pub fn is_vertical(self) -> bool {
+        !matches!(self, Self::HorizontalTb)
+    }
+}
+
+enum_property! {
+    /// CSS `direction` values (inline base direction).
+    pub enum Direction {
+        /// Left-to-right text (CSS initial value).
+        #[default]
+        Ltr => "ltr",
+        /// Right-to-left text, as in Arabic and Hebrew.
+        Rtl => "rtl",
+    }
+}
+
 enum_property! {
     /// CSS `text-decoration-style` values (how the decoration line is drawn).
     ///
@@ -201,6 +236,11 @@ enum_property! {
         Avoid => "avoid",
         /// Force a column break.
         Column => "column",
+        /// Force one or two page breaks so the next page is a recto page
+        /// (the right-hand page in left-to-right books).
+        Recto => "recto",
+        /// Force one or two page breaks so the next page is a verso page.
+        Verso => "verso",
     }
 }
 
@@ -253,6 +293,47 @@ enum_property! {
     }
 }
 
+enum_property! {
+    /// CSS `table-layout` values.
+    pub enum TableLayout {
+        /// Column widths follow the cell contents.
+        #[default]
+        Auto => "auto",
+        /// Column widths come from the table width and the first row.
+        Fixed => "fixed",
+    }
+}
+
+enum_property! {
+    /// CSS `overflow` values: what happens to content that doesn't fit the
+    /// element's box.
+    pub enum Overflow {
+        /// Content spills out of the box (CSS initial value).
+        #[default]
+        Visible => "visible",
+        /// Content is clipped to the padding box.
+        Hidden => "hidden",
+        /// Like `hidden`, but forbids programmatic scrolling too.
+        Clip => "clip",
+        /// Content is clipped and scrollable.
+        Scroll => "scroll",
+        /// Scrollable only when content overflows.
+        Auto => "auto",
+    }
+}
+
+enum_property! {
+    /// CSS `column-span` values: whether an element in a multi-column
+    /// container spans all columns (typically a heading).
+    pub enum ColumnSpan {
+        /// Stays within its column (CSS initial value).
+        #[default]
+        None => "none",
+        /// Breaks out of the columns across the container's full width.
+        All => "all",
+    }
+}
+
 enum_property! {
     /// CSS box-sizing values.
     pub enum BoxSizing {
@@ -381,6 +462,18 @@ enum_property! {
     }
 }
 
+impl TextAlign {
+    /// Resolve `start`/`end` to a physical side for the given direction.
+    /// Other values are returned unchanged.
+    pub fn resolve(self, direction: Direction) -> Self {
+        match (self, direction) {
+            (Self::Start, Direction::Rtl) => Self::Right,
+            (Self::End, Direction::Rtl) => Self::Left,
+            _ => self,
+        }
+    }
+}
+
 enum_property! {
     /// CSS `display` values (the subset boko models).
     ///
@@ -402,6 +495,21 @@ enum_property! {
         TableCell => "table-cell",
         /// Table row box (`tr`).
         TableRow => "table-row",
+        /// Block-level flex container; its items are laid out as blocks.
+        Flex => "flex",
+        /// Block-level grid container; its items are laid out as blocks.
+        Grid => "grid",
+        /// Block-level table box (`table`), the context for rows and cells.
+        Table => "table",
+    }
+}
+
+impl Display {
+    /// Whether boko lays this display out as a plain block box: `block`,
+    /// and the `flex`/`grid`/`table` containers, whose children flow as
+    /// blocks. `list-item` is left out since its marker needs a list.
+    pub fn lays_out_as_block(self) -> bool {
+        matches!(self, Self::Block | Self::Flex | Self::Grid | Self::Table)
     }
 }
 
@@ -544,6 +652,40 @@ impl Hash for Length {
     }
 }
 
+/// CSS `font-size-adjust`: the aspect value (x-height divided by font
+/// size) that fallback fonts are scaled to match.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FontSizeAdjust {
+    /// No adjustment (CSS initial value).
+    #[default]
+    None,
+    /// Scale fallback fonts so their x-height is this fraction of the font size.
+    Aspect(f32),
+}
+
+impl Eq for FontSizeAdjust {}
+
+impl Hash for FontSizeAdjust {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            FontSizeAdjust::None => 0u8.hash(state),
+            FontSizeAdjust::Aspect(v) => {
+                1u8.hash(state);
+                v.to_bits().hash(state);
+            }
+        }
+    }
+}
+
+impl ToCss for FontSizeAdjust {
+    fn to_css(&self, buf: &mut String) {
+        match self {
+            FontSizeAdjust::None => buf.push_str("none"),
+            FontSizeAdjust::Aspect(v) => write!(buf, "{}", v).unwrap(),
+        }
+    }
+}
+
 impl ToCss for Length {
     fn to_css(&self, buf: &mut String) {
         match self {
@@ -10,6 +10,7 @@
 use std::fmt::Write;
 
 use super::ToCss;
+use super::properties::{BreakValue, Length};
 use super::types::ComputedStyle;
 
 /// Serializer for one property: writes the CSS value into `out` and returns
@@ -89,6 +90,27 @@ macro_rules! count_prop {
     };
 }
 
+/// Table entry: legacy `page-break-*` fallback for a recto/verso break.
+/// Reading systems that don't know the page-side values drop the whole
+/// `break-*` declaration, so a plain page break goes first; the `break-*`
+/// entry after it wins wherever recto/verso is supported.
+macro_rules! page_side_fallback {
+    ($name:expr, $field:ident) => {
+        CssProperty {
+            name: $name,
+            in_blob: true,
+            emit: |s, _d, out| {
+                if matches!(s.$field, BreakValue::Recto | BreakValue::Verso) {
+                    out.push_str("always");
+                    true
+                } else {
+                    false
+                }
+            },
+        }
+    };
+}
+
 /// Canonical property table, in `to_css` emission order.
 ///
 /// Blob entries (`in_blob: true`) are emitted by `ComputedStyle::to_css` in
@@ -111,6 +133,7 @@ const PROPERTIES: &[CssProperty] = &[
         },
     },
     prop!("font-size", font_size),
+    prop!("font-size-adjust", font_size_adjust),
     prop!("font-weight", font_weight),
     prop!("font-style", font_style),
     // Colors.
@@ -119,7 +142,27 @@ const PROPERTIES: &[CssProperty] = &[
     // Text properties.
     prop!("text-align", text_align),
     prop!("text-indent", text_indent),
-    prop!("line-height", line_height),
+    // Unitless factors are stored as em but must serialize unitless: an
+    // `em` line-height inherits as a fixed length, a number as a factor.
+    CssProperty {
+        name: "line-height",
+        in_blob: true,
+        emit: |s, d, out| match s.line_height {
+            _ if s.line_height == d.line_height => false,
+            Length::Em(factor) => {
+                write!(out, "{factor}").unwrap();
+                true
+            }
+            Length::Auto => {
+                out.push_str("normal");
+                true
+            }
+            other => {
+                other.to_css(out);
+                true
+            }
+        },
+    },
     // Combined underline/line-through value. The KFX exporter needs the two
     // flags separately and handles them itself (see extract_ir_field).
     CssProperty {
@@ -148,7 +191,24 @@ const PROPERTIES: &[CssProperty] = &[
     prop!("padding-bottom", padding_bottom),
     prop!("padding-left", padding_left),
     // Vertical alignment.
-    prop!("vertical-align", vertical_align),
+    // The length form (a baseline shift) takes the place of the keyword.
+    CssProperty {
+        name: "vertical-align",
+        in_blob: true,
+        emit: |s, d, out| {
+            if s.baseline_shift != Length::Auto {
+                if s.baseline_shift == d.baseline_shift {
+                    return false;
+                }
+                s.baseline_shift.to_css(out);
+            } else if s.vertical_align == d.vertical_align {
+                return false;
+            } else {
+                s.vertical_align.to_css(out);
+            }
+            true
+        },
+    },
     // List style. The blob emits it whenever non-default; the KFX exporter
     // additionally gates it on display: list-item (see extract_ir_field).
     prop!("list-style-type", list_style_type),
@@ -161,6 +221,9 @@ const PROPERTIES: &[CssProperty] = &[
     prop!("text-transform", text_transform),
     // Hyphenation.
     prop!("hyphens", hyphens),
+    // Writing mode.
+    prop!("writing-mode", writing_mode),
+    prop!("direction", direction),
     // White-space.
     prop!("white-space", white_space),
     // Underline style.
@@ -190,7 +253,9 @@ const PROPERTIES: &[CssProperty] = &[
     // Float.
     prop!("float", float),
     // Page breaks.
+    page_side_fallback!("page-break-before", break_before),
     prop!("break-before", break_before),
+    page_side_fallback!("page-break-after", break_after),
     prop!("break-after", break_after),
     prop!("break-inside", break_inside),
     // Border styles (4-sided).
@@ -215,8 +280,30 @@ const PROPERTIES: &[CssProperty] = &[
     prop!("border-bottom-right-radius", border_radius_bottom_right),
     // List style position (same display gating note as list-style-type).
     prop!("list-style-position", list_style_position),
+    CssProperty {
+        name: "list-style-image",
+        in_blob: true,
+        // Not in the KFX schema: KFX has no image markers and falls back to
+        // the list-style-type bullet.
+        emit: |s, _d, out| match &s.list_style_image {
+            Some(href) => {
+                out.push_str("url(\"");
+                for c in href.chars() {
+                    if matches!(c, '"' | '\\') {
+                        out.push('\\');
+                    }
+                    out.push(c);
+                }
+                out.push_str("\")");
+                true
+            }
+            None => false,
+        },
+    },
     // Visibility.
     prop!("visibility", visibility),
+    prop!("overflow", overflow),
+    prop!("column-span", column_span),
     // Note: language is stored but typically output via HTML lang attribute.
     //
     // Lookup-only entries below: never emitted in the CSS blob, used by the
@@ -229,6 +316,7 @@ const PROPERTIES: &[CssProperty] = &[
     prop!("word-break", word_break, in_blob: false),
     prop!("border-collapse", border_collapse, in_blob: false),
     prop!("border-spacing", border_spacing, in_blob: false),
+    prop!("table-layout", table_layout, in_blob: false),
     count_prop!("dropcap-lines", dropcap_lines),
     count_prop!("dropcap-chars", dropcap_chars),
 ];
@@ -330,9 +418,11 @@ const GENERIC_FAMILIES: &[&str] = &[
 /// Quote font-family names that need quoting in CSS.
 ///
 /// A comma-separated font stack like `din next lt pro,sans-serif` becomes
-/// `"din next lt pro",sans-serif` — generic families are left unquoted,
-/// custom names with spaces or leading digits are quoted.
-fn quote_font_family(buf: &mut String, family: &str) {
+/// `"din next lt pro",sans-serif` — generic families and names that are
+/// valid identifiers are left unquoted; names with spaces, a leading
+/// digit, or characters an identifier can't hold are quoted (escaping any
+/// `"` or `\` inside them).
+pub(crate) fn quote_font_family(buf: &mut String, family: &str) {
     for (i, part) in family.split(',').enumerate() {
         if i > 0 {
             buf.push(',');
@@ -341,21 +431,45 @@ fn quote_font_family(buf: &mut String, family: &str) {
         let is_generic = GENERIC_FAMILIES
             .iter()
             .any(|g| g.eq_ignore_ascii_case(trimmed));
-        let needs_quoting = !is_generic
-            && (trimmed.contains(' ')
-                || trimmed.starts_with(|c: char| c.is_ascii_digit())
-                || trimmed.contains('"')
-                || trimmed.is_empty());
-        if needs_quoting {
-            buf.push('"');
+        if is_generic || is_custom_ident(trimmed) {
             buf.push_str(trimmed);
-            buf.push('"');
         } else {
-            buf.push_str(trimmed);
+            buf.push('"');
+            for c in trimmed.chars() {
+                if matches!(c, '"' | '\\') {
+                    buf.push('\\');
+                }
+                buf.push(c);
+            }
+            buf.push('"');
         }
     }
 }
 
+/// Whether `name` can stand unquoted as a single CSS `<custom-ident>`:
+/// an identifier that isn't a CSS-wide keyword (those would be read as the
+/// keyword, not a family).
+fn is_custom_ident(name: &str) -> bool {
+    let name_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-' || !c.is_ascii();
+    let rest = name.strip_prefix('-').unwrap_or(name);
+    let starts_ok = rest
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || !c.is_ascii() || c == '-');
+    starts_ok
+        && name.chars().all(name_char)
+        && ![
+            "inherit",
+            "initial",
+            "unset",
+            "revert",
+            "revert-layer",
+            "default",
+        ]
+        .iter()
+        .any(|kw| kw.eq_ignore_ascii_case(name))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -411,6 +525,23 @@ mod tests {
     #[test]
     fn test_font_family_leading_digit() {
         assert_eq!(quoted("123font"), r#""123font""#);
+        assert_eq!(quoted("-1font"), r#""-1font""#);
+    }
+
+    #[test]
+    fn test_font_family_clean_stack_unquoted() {
+        assert_eq!(
+            quoted("Georgia, _Custom-Face, -vendor-font, serif"),
+            "Georgia,_Custom-Face,-vendor-font,serif"
+        );
+    }
+
+    #[test]
+    fn test_font_family_quotes_disallowed_characters_and_keywords() {
+        assert_eq!(quoted("Font!Co"), r#""Font!Co""#);
+        assert_eq!(quoted("Times (Web)"), r#""Times (Web)""#);
+        assert_eq!(quoted("inherit"), r#""inherit""#);
+        assert_eq!(quoted(r#"Say "Hi""#), r#""Say \"Hi\"""#);
     }
 
     #[test]
@@ -59,6 +59,8 @@ pub struct ComputedStyle {
     /// rendered size (KFX emits absolute `rem`) read this instead of
     /// interpreting the parent-relative `font_size`.
     pub font_size_abs: AbsFontSize,
+    /// `font-size-adjust` aspect value for fallback fonts.
+    pub font_size_adjust: FontSizeAdjust,
     /// `font-weight` as a numeric weight (default 0 means unset; 400 normal, 700 bold).
     pub font_weight: FontWeight,
     /// `font-style` (normal, italic, oblique).
@@ -76,7 +78,11 @@ pub struct ComputedStyle {
     /// `text-indent` for the first line; `Length::Auto` means unset.
     pub text_indent: Length,
     /// `line-height`; unitless values are stored as em, `Auto` means unset.
+    /// `em` and `%` values are computed lengths, stored as rem.
     pub line_height: Length,
+    /// Whether `line_height` was inherited rather than declared on this
+    /// element, so export-time refitting can leave authored leading alone.
+    pub line_height_inherited: bool,
     /// Export-time line-height scale (leading normalization); 1.0 outside
     /// KFX export. Kept on the style so emission can clamp the authored
     /// leading before scaling, the way reference output does.
@@ -109,6 +115,10 @@ pub struct ComputedStyle {
     /// `vertical-align` for inline and table-cell elements; `Super`/`Sub`
     /// drive superscript/subscript detection in exporters.
     pub vertical_align: VerticalAlign,
+    /// `vertical-align` given as a length or percentage: a baseline shift
+    /// (positive raises, `%` is of the line height). `Length::Auto` means
+    /// the `vertical_align` keyword applies.
+    pub baseline_shift: Length,
 
     /// `list-style-type` (marker kind for list items).
     pub list_style_type: ListStyleType,
@@ -128,6 +138,12 @@ pub struct ComputedStyle {
     /// `hyphens` automatic hyphenation mode (defaults to `Manual`).
     pub hyphens: Hyphens,
 
+    /// `writing-mode` block flow direction.
+    pub writing_mode: WritingMode,
+
+    /// `direction` inline base direction.
+    pub direction: Direction,
+
     /// `white-space` collapsing/wrapping behavior.
     pub white_space: WhiteSpace,
 
@@ -196,6 +212,9 @@ pub struct ComputedStyle {
 
     /// `list-style-position` (marker inside or outside the item box).
     pub list_style_position: ListStylePosition,
+    /// `list-style-image` marker href; `None` means no image. Stylesheets
+    /// loaded from a book have it resolved to an archive path.
+    pub list_style_image: Option<String>,
 
     // Language & rendering
     /// Content language (from `xml:lang`/`lang` attributes, not CSS); used by
@@ -205,6 +224,12 @@ pub struct ComputedStyle {
     pub visibility: Visibility,
     /// `box-sizing` (content-box or border-box).
     pub box_sizing: BoxSizing,
+    /// `overflow` (whether content outside the box is clipped).
+    pub overflow: Overflow,
+    /// `column-span` (whether the element spans all columns of a
+    /// multi-column container). KFX has no multi-column layout, so only
+    /// EPUB output carries it.
+    pub column_span: ColumnSpan,
 
     // Additional layout properties
     /// `max-height`; `Length::Auto` means unset.
@@ -231,6 +256,8 @@ pub struct ComputedStyle {
     pub border_collapse: BorderCollapse,
     /// `border-spacing` between table cells; `Length::Auto` means unset.
     pub border_spacing: Length,
+    /// `table-layout` for tables (auto or fixed).
+    pub table_layout: TableLayout,
 
     /// Dropcap span line height in text lines (0 = not a dropcap). Set by
     /// the dropcap-detection pass on the paragraph; drives the KFX
@@ -252,6 +279,7 @@ impl Default for ComputedStyle {
             font_family: Default::default(),
             font_size: Default::default(),
             font_size_abs: Default::default(),
+            font_size_adjust: Default::default(),
             font_weight: Default::default(),
             font_style: Default::default(),
             color: Default::default(),
@@ -259,6 +287,7 @@ impl Default for ComputedStyle {
             text_align: Default::default(),
             text_indent: Default::default(),
             line_height: Default::default(),
+            line_height_inherited: Default::default(),
             line_scale: Default::default(),
             text_decoration_underline: Default::default(),
             text_decoration_line_through: Default::default(),
@@ -268,12 +297,15 @@ impl Default for ComputedStyle {
             padding_left: Default::default(),
             padding_right: Default::default(),
             vertical_align: Default::default(),
+            baseline_shift: Default::default(),
             list_style_type: Default::default(),
             font_variant: Default::default(),
             letter_spacing: Default::default(),
             word_spacing: Default::default(),
             text_transform: Default::default(),
             hyphens: Default::default(),
+            writing_mode: Default::default(),
+            direction: Default::default(),
             white_space: Default::default(),
             underline_style: Default::default(),
             overline: Default::default(),
@@ -303,9 +335,12 @@ impl Default for ComputedStyle {
             border_radius_bottom_left: Default::default(),
             border_radius_bottom_right: Default::default(),
             list_style_position: Default::default(),
+            list_style_image: Default::default(),
             language: Default::default(),
             visibility: Default::default(),
             box_sizing: Default::default(),
+            overflow: Default::default(),
+            column_span: Default::default(),
             max_height: Default::default(),
             min_width: Default::default(),
             clear: Default::default(),
@@ -315,6 +350,7 @@ impl Default for ComputedStyle {
             overflow_wrap: Default::default(),
             border_collapse: Default::default(),
             border_spacing: Default::default(),
+            table_layout: Default::default(),
             dropcap_lines: 0,
             dropcap_chars: 0,
         }
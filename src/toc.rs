@@ -0,0 +1,279 @@
+//! Synthesizing a table of contents from chapter headings.
+//!
+//! Books converted from MOBI or plain text often carry an empty or flat
+//! TOC. [`Book::set_toc_from_headings`] rebuilds it from the headings in
+//! reading order, nesting entries by heading level. Headings without an
+//! `id` need one to be linked to, so the book's backend is wrapped in an
+//! overlay that serves the new TOC and writes the generated ids into each
+//! chapter's semantics as it loads.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::Book;
+use crate::dom::Stylesheet;
+use crate::import::{ChapterId, Importer, SpineEntry};
+use crate::markdown::{collect_heading_text, slugify};
+use crate::model::{
+    AnchorTarget, Chapter, FontFace, GlobalNodeId, Landmark, Metadata, NodeId, Role, TocEntry,
+};
+use crate::optimize::EmptyBackend;
+use crate::util::strip_ebook_chars;
+
+impl Book {
+    /// Replace the table of contents with one built from the chapters'
+    /// headings, `h1` through `h{max_level}`, in reading order. Entries nest
+    /// by level (an `h2` sits under the `h1` before it, even across
+    /// chapters) and link to `path#id`.
+    ///
+    /// Headings keep their existing ids; one without an id gets a unique
+    /// id slugged from its text, written into the chapter's semantics, so
+    /// exports afterwards go through the normalized pipeline. Headings with
+    /// no text are left out.
+    ///
+    /// Fails when a chapter can't be loaded; the book is unchanged.
+    pub fn set_toc_from_headings(&mut self, max_level: u8) -> crate::Result<()> {
+        let mut headings = Vec::new();
+        let mut generated = false;
+        for entry in self.spine().to_vec() {
+            let Some(path) = self.source_id(entry.id).map(str::to_string) else {
+                continue;
+            };
+            let mut chapter = self.load_chapter(entry.id)?;
+            for heading in anchor_headings(&mut chapter, max_level) {
+                generated |= heading.generated;
+                headings.push((
+                    heading.level,
+                    TocEntry {
+                        title: heading.title,
+                        href: format!("{path}#{}", heading.id),
+                        children: Vec::new(),
+                        play_order: None,
+                        target: None,
+                    },
+                ));
+            }
+        }
+
+        let backend = self.replace_backend(Box::new(EmptyBackend(Metadata::default())));
+        self.replace_backend(Box::new(HeadingTocImporter {
+            inner: backend,
+            toc: nest(headings),
+            max_level,
+            generated,
+        }));
+        self.clear_navigation_cache();
+        Ok(())
+    }
+}
+
+/// A heading that made it into the TOC.
+struct AnchoredHeading {
+    level: u8,
+    title: String,
+    id: String,
+    /// Whether `id` was generated rather than read from the source.
+    generated: bool,
+}
+
+/// Collect the headings down to `max_level` in document order, giving each
+/// one without an id a unique generated id. Deterministic, so the overlay
+/// assigns the same ids on every load.
+fn anchor_headings(chapter: &mut Chapter, max_level: u8) -> Vec<AnchoredHeading> {
+    let mut taken: HashSet<String> = chapter
+        .iter_dfs()
+        .filter_map(|id| chapter.semantics.id(id).map(str::to_string))
+        .collect();
+    let nodes: Vec<(NodeId, u8)> = chapter
+        .iter_dfs()
+        .filter_map(|id| match chapter.node(id)?.role {
+            Role::Heading(level) if level <= max_level => Some((id, level)),
+            _ => None,
+        })
+        .collect();
+
+    let mut headings = Vec::new();
+    for (node, level) in nodes {
+        let title = strip_ebook_chars(collect_heading_text(chapter, node).trim()).into_owned();
+        if title.is_empty() {
+            continue;
+        }
+        let (id, generated) = match chapter.semantics.id(node) {
+            Some(id) => (id.to_string(), false),
+            None => {
+                let id = unique_id(&title, &taken);
+                chapter.semantics.set_id(node, &id);
+                taken.insert(id.clone());
+                (id, true)
+            }
+        };
+        headings.push(AnchoredHeading {
+            level,
+            title,
+            id,
+            generated,
+        });
+    }
+    headings
+}
+
+/// An id slugged from `title` that no element in the chapter uses yet.
+/// Slugs that don't start with a letter (`"1. Origins"`) are prefixed, since
+/// XML ids can't begin with a digit.
+fn unique_id(title: &str, taken: &HashSet<String>) -> String {
+    let slug = slugify(title);
+    let base = if slug.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        slug
+    } else if slug.is_empty() {
+        "heading".to_string()
+    } else {
+        format!("heading-{slug}")
+    };
+    let mut id = base.clone();
+    let mut n = 2;
+    while taken.contains(&id) {
+        id = format!("{base}-{n}");
+        n += 1;
+    }
+    id
+}
+
+/// Nest `(level, entry)` pairs in reading order: each entry becomes a child
+/// of the nearest preceding entry with a lower level.
+fn nest(headings: Vec<(u8, TocEntry)>) -> Vec<TocEntry> {
+    fn close(stack: &mut Vec<(u8, TocEntry)>, roots: &mut Vec<TocEntry>) {
+        if let Some((_, entry)) = stack.pop() {
+            match stack.last_mut() {
+                Some((_, parent)) => parent.children.push(entry),
+                None => roots.push(entry),
+            }
+        }
+    }
+
+    let mut roots = Vec::new();
+    let mut stack: Vec<(u8, TocEntry)> = Vec::new();
+    for (level, entry) in headings {
+        while stack.last().is_some_and(|(open, _)| *open >= level) {
+            close(&mut stack, &mut roots);
+        }
+        stack.push((level, entry));
+    }
+    while !stack.is_empty() {
+        close(&mut stack, &mut roots);
+    }
+    roots
+}
+
+/// Importer overlay serving a TOC synthesized from headings, with the
+/// generated heading ids applied to each chapter. Everything else is served
+/// by the inner backend.
+struct HeadingTocImporter {
+    inner: Box<dyn Importer>,
+    toc: Vec<TocEntry>,
+    max_level: u8,
+    /// Whether any heading needed a generated id.
+    generated: bool,
+}
+
+impl HeadingTocImporter {
+    fn anchor_chapter(&self, mut chapter: Chapter) -> Chapter {
+        if self.generated {
+            anchor_headings(&mut chapter, self.max_level);
+        }
+        chapter
+    }
+}
+
+impl Importer for HeadingTocImporter {
+    fn open(_path: &Path) -> crate::Result<Self>
+    where
+        Self: Sized,
+    {
+        Err(crate::Error::UnsupportedFormat {
+            detail: "HeadingTocImporter wraps an existing backend".to_string(),
+        })
+    }
+
+    fn metadata(&self) -> &Metadata {
+        self.inner.metadata()
+    }
+
+    fn toc(&self) -> &[TocEntry] {
+        &self.toc
+    }
+
+    fn landmarks(&self) -> &[Landmark] {
+        self.inner.landmarks()
+    }
+
+    fn spine(&self) -> &[SpineEntry] {
+        self.inner.spine()
+    }
+
+    fn load_chapter(&self, id: ChapterId) -> crate::Result<Chapter> {
+        self.inner
+            .load_chapter(id)
+            .map(|ch| self.anchor_chapter(ch))
+    }
+
+    fn load_chapters(&self, ids: &[ChapterId]) -> Vec<crate::Result<Chapter>> {
+        self.inner
+            .load_chapters(ids)
+            .into_iter()
+            .map(|res| res.map(|ch| self.anchor_chapter(ch)))
+            .collect()
+    }
+
+    fn source_id(&self, id: ChapterId) -> Option<&str> {
+        self.inner.source_id(id)
+    }
+
+    fn load_raw(&self, id: ChapterId) -> crate::Result<Vec<u8>> {
+        self.inner.load_raw(id)
+    }
+
+    fn list_assets(&self) -> &[String] {
+        self.inner.list_assets()
+    }
+
+    fn load_asset(&self, path: &str) -> crate::Result<Vec<u8>> {
+        self.inner.load_asset(path)
+    }
+
+    fn contains_asset(&self, path: &str) -> bool {
+        self.inner.contains_asset(path)
+    }
+
+    fn asset_media_type(&self, path: &str) -> Option<&str> {
+        self.inner.asset_media_type(path)
+    }
+
+    fn load_stylesheet(&self, path: &str) -> Option<Arc<Stylesheet>> {
+        self.inner.load_stylesheet(path)
+    }
+
+    fn font_faces(&self) -> Vec<FontFace> {
+        self.inner.font_faces()
+    }
+
+    fn requires_normalized_export(&self) -> bool {
+        // Generated ids exist only in the IR; raw markup would leave the
+        // TOC's fragments dangling.
+        self.generated || self.inner.requires_normalized_export()
+    }
+
+    fn index_anchor_ids(&self, ids: &[(GlobalNodeId, &str)]) {
+        self.inner.index_anchor_ids(ids)
+    }
+
+    fn resolve_toc(&self) -> Option<Vec<TocEntry>> {
+        // The synthesized hrefs already name their fragments; the inner
+        // fixup would bring back the source TOC.
+        None
+    }
+
+    fn resolve_href(&self, from_chapter: ChapterId, href: &str) -> Option<AnchorTarget> {
+        self.inner.resolve_href(from_chapter, href)
+    }
+}
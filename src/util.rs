@@ -16,6 +16,167 @@ pub const MAX_TREE_DEPTH: usize = 512;
 /// ebook resource approaches this; a bomb hits the cap and errors cleanly.
 pub const MAX_DECOMPRESSED_ENTRY: usize = 512 * 1024 * 1024;
 
+/// Characters that have no business in exported text: C0 and C1 controls
+/// other than tab, line feed and carriage return, and the noncharacters
+/// U+FDD0..U+FDEF and U+xFFFE/U+xFFFF. Validators reject them and readers
+/// render them as boxes.
+pub(crate) fn is_unexpected_char(c: char) -> bool {
+    match c {
+        '\t' | '\n' | '\r' => false,
+        '\u{FDD0}'..='\u{FDEF}' => true,
+        c if c.is_control() => true,
+        c => (c as u32) & 0xFFFE == 0xFFFE,
+    }
+}
+
+/// Number of [unexpected characters](is_unexpected_char) in `text`.
+pub(crate) fn count_unexpected_chars(text: &str) -> usize {
+    text.chars().filter(|&c| is_unexpected_char(c)).count()
+}
+
+/// Raw markup without [unexpected characters](is_unexpected_char), with how
+/// many were removed. Only UTF-8 documents are cleaned; anything else is
+/// returned as is.
+pub(crate) fn clean_unexpected_bytes(data: Vec<u8>) -> (Vec<u8>, usize) {
+    let Ok(text) = std::str::from_utf8(&data) else {
+        return (data, 0);
+    };
+    match clean_unexpected_chars(text) {
+        Cow::Borrowed(_) => (data, 0),
+        Cow::Owned(cleaned) => {
+            let removed = text.chars().count() - cleaned.chars().count();
+            (cleaned.into_bytes(), removed)
+        }
+    }
+}
+
+/// `text` without [unexpected characters](is_unexpected_char); borrowed
+/// when there is nothing to remove.
+pub(crate) fn clean_unexpected_chars(text: &str) -> Cow<'_, str> {
+    if !text.chars().any(is_unexpected_char) {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(text.chars().filter(|&c| !is_unexpected_char(c)).collect())
+}
+
+/// Point the `href` and `src` attributes (`xlink:href` included) and CSS
+/// `url()` references of the passthrough document or stylesheet at archive
+/// path `base` at resources that moved. `moved` gets each reference resolved against `base` and returns the
+/// resource's new path, which must be in the same directory: only the file
+/// name in the reference changes, keeping its directory and fragment.
+pub(crate) fn rewrite_moved_references(
+    doc: Vec<u8>,
+    base: &str,
+    moved: impl Fn(&str) -> Option<String>,
+) -> Vec<u8> {
+    let Ok(text) = std::str::from_utf8(&doc) else {
+        return doc;
+    };
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(eq) = rest.find('=') {
+        let name_start = rest[..eq]
+            .rfind(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, ':' | '-' | '_')))
+            .map_or(0, |i| i + 1);
+        let name = &rest[name_start..eq];
+        // `data-href=` or an `=` in text isn't a reference.
+        let is_reference = matches!(name.rsplit(':').next(), Some("href" | "src"))
+            && rest[..name_start].ends_with(char::is_whitespace);
+        let (before, after) = rest.split_at(eq + 1);
+        out.push_str(before);
+        rest = after;
+        if !is_reference {
+            continue;
+        }
+        let Some(quote) = after.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            continue;
+        };
+        let Some(end) = after[1..].find(quote) else {
+            continue;
+        };
+        let Some(reference) = moved_reference(&after[1..1 + end], base, &moved) else {
+            continue;
+        };
+        out.push(quote);
+        out.push_str(&reference);
+        out.push(quote);
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    rewrite_moved_urls(&out, base, &moved).into_bytes()
+}
+
+/// Whether the passthrough document or stylesheet at archive path `base`
+/// points at archive path `target` through any reference
+/// [`rewrite_moved_references`] follows.
+pub(crate) fn refers_to(doc: &[u8], base: &str, target: &str) -> bool {
+    let found = std::cell::Cell::new(false);
+    rewrite_moved_references(doc.to_vec(), base, |path| {
+        found.set(found.get() || path == target);
+        None
+    });
+    found.get()
+}
+
+/// The CSS half of [`rewrite_moved_references`]: `url(...)` values, quoted
+/// or not, in stylesheets, `<style>` elements and `style` attributes.
+fn rewrite_moved_urls(text: &str, base: &str, moved: &impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find("url(") {
+        let (before, after) = rest.split_at(pos + "url(".len());
+        out.push_str(before);
+        rest = after;
+        let Some(close) = after.find(')') else {
+            continue;
+        };
+        let inner = &after[..close];
+        let trimmed = inner.trim();
+        let value = trimmed
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .or_else(|| {
+                trimmed
+                    .strip_prefix('\'')
+                    .and_then(|v| v.strip_suffix('\''))
+            })
+            .unwrap_or(trimmed);
+        let Some(reference) = moved_reference(value, base, moved) else {
+            continue;
+        };
+        // Keep the quotes and padding; only the value itself changes.
+        let start = inner.find(value).unwrap_or(0);
+        out.push_str(&inner[..start]);
+        out.push_str(&reference);
+        out.push_str(&inner[start + value.len()..]);
+        rest = &after[close..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// `reference` renamed to its resource's new file name, when it moved.
+fn moved_reference(
+    reference: &str,
+    base: &str,
+    moved: &impl Fn(&str) -> Option<String>,
+) -> Option<String> {
+    let (file, fragment) = match reference.split_once('#') {
+        Some((file, fragment)) => (file, Some(fragment)),
+        None => (reference, None),
+    };
+    if file.is_empty() || file.contains("://") || file.starts_with("data:") {
+        return None;
+    }
+    let new = moved(&crate::dom::resolve_path(base, file))?;
+    let name = new.rsplit('/').next().unwrap_or(&new);
+    let dir = file.rfind('/').map_or("", |i| &file[..=i]);
+    Some(match fragment {
+        Some(fragment) => format!("{dir}{name}#{fragment}"),
+        None => format!("{dir}{name}"),
+    })
+}
+
 /// Inflate raw DEFLATE `compressed` bytes with a hard cap on output size.
 ///
 /// The archive's claimed uncompressed size is untrusted, so it is only used
@@ -46,6 +207,24 @@ pub fn bounded_inflate(
     Ok(out)
 }
 
+/// Magic bytes opening a gzip stream.
+pub const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Decompress a (possibly multi-member) gzip stream, capped at `hard_cap`
+/// output bytes like [`bounded_inflate`].
+pub fn bounded_gunzip(compressed: &[u8], hard_cap: usize) -> io::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut reader = flate2::read::MultiGzDecoder::new(compressed).take(hard_cap as u64 + 1);
+    reader.read_to_end(&mut out)?;
+    if out.len() > hard_cap {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "decompressed file exceeds size limit",
+        ));
+    }
+    Ok(out)
+}
+
 /// Get a time-based seed value for pseudo-random number generation.
 ///
 /// On native platforms, uses `SystemTime::now()`.
@@ -179,20 +358,21 @@ pub fn percent_decode_href(href: &str) -> Cow<'_, str> {
 // Image Dimension Extraction
 // ============================================================================
 
-/// Extract image dimensions from raw image data.
+/// Intrinsic pixel dimensions of raw image data.
 ///
-/// Supports PNG, JPEG, and GIF formats by parsing header bytes.
-/// Returns `(width, height)` or `None` if format is unrecognized.
+/// Supports PNG, JPEG, GIF and WebP (lossy, lossless and extended) by
+/// parsing header bytes. Returns `(width, height)` or `None` if the format
+/// is unrecognized or the header is truncated.
 ///
 /// # Examples
 ///
 /// ```ignore
 /// let png_data = include_bytes!("../tests/fixtures/image.png");
-/// if let Some((w, h)) = extract_image_dimensions(png_data) {
+/// if let Some((w, h)) = image_dimensions(png_data) {
 ///     println!("Image is {}x{}", w, h);
 /// }
 /// ```
-pub fn extract_image_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+pub fn image_dimensions(data: &[u8]) -> Option<(u32, u32)> {
     if data.len() < 24 {
         return None;
     }
@@ -217,9 +397,49 @@ pub fn extract_image_dimensions(data: &[u8]) -> Option<(u32, u32)> {
         return Some((width, height));
     }
 
+    // WebP: RIFF container whose first chunk is VP8, VP8L or VP8X
+    if data.starts_with(b"RIFF") && &data[8..12] == b"WEBP" {
+        return extract_webp_dimensions(data);
+    }
+
     None
 }
 
+/// Extract dimensions from the first chunk of a WebP file.
+fn extract_webp_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    let le24 = |b: &[u8]| u32::from_le_bytes([b[0], b[1], b[2], 0]);
+    match &data[12..16] {
+        // Lossy: 3-byte frame tag, start code 9D 01 2A, then 14-bit
+        // width and height (the top two bits are scaling).
+        b"VP8 " => {
+            let frame = data.get(20..30)?;
+            if frame[3..6] != [0x9D, 0x01, 0x2A] {
+                return None;
+            }
+            let width = u16::from_le_bytes([frame[6], frame[7]]) & 0x3FFF;
+            let height = u16::from_le_bytes([frame[8], frame[9]]) & 0x3FFF;
+            Some((width as u32, height as u32))
+        }
+        // Lossless: signature 0x2F, then width-1 and height-1 as 14-bit
+        // fields packed little-endian.
+        b"VP8L" => {
+            let header = data.get(20..25)?;
+            if header[0] != 0x2F {
+                return None;
+            }
+            let bits = u32::from_le_bytes([header[1], header[2], header[3], header[4]]);
+            Some(((bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1))
+        }
+        // Extended: flags and reserved bytes, then the canvas width-1 and
+        // height-1 as 24-bit little-endian values.
+        b"VP8X" => {
+            let canvas = data.get(24..30)?;
+            Some((le24(&canvas[0..3]) + 1, le24(&canvas[3..6]) + 1))
+        }
+        _ => None,
+    }
+}
+
 /// Extract dimensions from JPEG data by parsing SOF markers.
 fn extract_jpeg_dimensions(data: &[u8]) -> Option<(u32, u32)> {
     let mut i = 2;
@@ -380,39 +600,20 @@ pub fn detect_media_format(path: &str, data: &[u8]) -> MediaFormat {
     }
 
     // Fallback to magic byte detection
-    if data.len() >= 4 {
-        // JPEG: FF D8 FF
-        if data[0] == 0xFF && data[1] == 0xD8 {
-            return MediaFormat::Jpeg;
-        }
-        // PNG: 89 50 4E 47 (.PNG)
-        if data[0] == 0x89 && data[1] == 0x50 && data[2] == 0x4E && data[3] == 0x47 {
-            return MediaFormat::Png;
-        }
-        // GIF: 47 49 46 (GIF)
-        if data[0] == 0x47 && data[1] == 0x49 && data[2] == 0x46 {
-            return MediaFormat::Gif;
-        }
-        // WOFF: "wOFF", WOFF2: "wOF2"
-        if data.starts_with(b"wOFF") {
-            return MediaFormat::Woff;
-        }
-        if data.starts_with(b"wOF2") {
-            return MediaFormat::Woff2;
-        }
-        // WebP: 52 49 46 46 ... 57 45 42 50 (RIFF...WEBP)
-        if data.len() >= 12
-            && data[0] == 0x52
-            && data[1] == 0x49
-            && data[2] == 0x46
-            && data[3] == 0x46
-            && data[8] == 0x57
-            && data[9] == 0x45
-            && data[10] == 0x42
-            && data[11] == 0x50
-        {
-            return MediaFormat::WebP;
-        }
+    match sniff_image_mime(data) {
+        Some("image/jpeg") => return MediaFormat::Jpeg,
+        Some("image/png") => return MediaFormat::Png,
+        Some("image/gif") => return MediaFormat::Gif,
+        Some("image/webp") => return MediaFormat::WebP,
+        Some("image/svg+xml") => return MediaFormat::Svg,
+        _ => {}
+    }
+    // WOFF: "wOFF", WOFF2: "wOF2"
+    if data.starts_with(b"wOFF") {
+        return MediaFormat::Woff;
+    }
+    if data.starts_with(b"wOF2") {
+        return MediaFormat::Woff2;
     }
 
     MediaFormat::Binary
@@ -448,6 +649,80 @@ pub(crate) fn guess_media_type(path: &str) -> &'static str {
     }
 }
 
+/// Map a media type string onto the matching `&'static str` that
+/// [`guess_media_type`] produces, or one of the other EPUB 3 core media
+/// types, so owned strings (e.g. deserialized ones) can populate
+/// [`Resource::media_type`](crate::model::Resource). Unknown types map to
+/// `application/octet-stream`.
+pub(crate) fn intern_media_type(media_type: &str) -> &'static str {
+    known_media_type(media_type).unwrap_or("application/octet-stream")
+}
+
+/// The `&'static str` form of a media type boko knows, if it is one:
+/// compared case-insensitively and without parameters, so
+/// `audio/ogg; codecs=opus` is `audio/ogg`.
+pub(crate) fn known_media_type(media_type: &str) -> Option<&'static str> {
+    const KNOWN: &[&str] = &[
+        "application/xhtml+xml",
+        "text/html",
+        "text/css",
+        "application/javascript",
+        "application/ecmascript",
+        "text/javascript",
+        "image/jpeg",
+        "image/png",
+        "image/gif",
+        "image/svg+xml",
+        "image/webp",
+        "image/avif",
+        "audio/mpeg",
+        "audio/mp4",
+        "audio/ogg",
+        "font/ttf",
+        "font/otf",
+        "font/woff",
+        "font/woff2",
+        "application/font-sfnt",
+        "application/vnd.ms-opentype",
+        "application/font-woff",
+        "application/x-dtbncx+xml",
+        "application/oebps-package+xml",
+        "application/smil+xml",
+        "application/pls+xml",
+    ];
+    let essence = media_type.split(';').next().unwrap_or_default().trim();
+    KNOWN
+        .iter()
+        .find(|known| known.eq_ignore_ascii_case(essence))
+        .copied()
+}
+
+/// [`known_media_type`], failing with
+/// [`Error::UnsupportedFormat`](crate::Error::UnsupportedFormat) for a type
+/// boko doesn't know. Shared by the media type setters.
+pub(crate) fn require_known_media_type(media_type: &str) -> crate::Result<&'static str> {
+    known_media_type(media_type).ok_or_else(|| crate::Error::UnsupportedFormat {
+        detail: format!("unknown media type {media_type:?}"),
+    })
+}
+
+/// Whether a media type names a content document (XHTML, or HTML as
+/// MOBI-derived and hand-built packages often declare it).
+pub(crate) fn is_content_document_type(media_type: &str) -> bool {
+    matches!(media_type, "application/xhtml+xml" | "text/html")
+}
+
+/// The media type to declare in an EPUB manifest: content documents are
+/// always `application/xhtml+xml` (epubcheck rejects `text/html`); every
+/// other type is kept.
+pub(crate) fn normalized_media_type(media_type: &'static str) -> &'static str {
+    if is_content_document_type(media_type) {
+        "application/xhtml+xml"
+    } else {
+        media_type
+    }
+}
+
 /// Strip invisible formatting characters used in ebooks.
 ///
 /// Removes:
@@ -470,6 +745,51 @@ pub fn strip_ebook_chars(s: &str) -> std::borrow::Cow<'_, str> {
     std::borrow::Cow::Owned(out)
 }
 
+/// Identify an image from its bytes alone: JPEG, PNG, GIF, WebP or SVG.
+///
+/// For content whose name or declared type can't be trusted. SVG is
+/// recognized by an `<svg` root element after any BOM, XML declaration,
+/// comments or doctype in the first KiB.
+pub fn sniff_image_mime(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if data.starts_with(b"\x89PNG") {
+        Some("image/png")
+    } else if data.starts_with(b"GIF8") {
+        Some("image/gif")
+    } else if data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else if has_svg_root(data) {
+        Some("image/svg+xml")
+    } else {
+        None
+    }
+}
+
+/// Whether the first element of an XML document is `<svg`.
+fn has_svg_root(data: &[u8]) -> bool {
+    let head = String::from_utf8_lossy(&data[..data.len().min(1024)]);
+    let mut rest = head.trim_start_matches('\u{FEFF}').trim_start();
+    loop {
+        let skip_to = if rest.starts_with("<?") {
+            "?>"
+        } else if rest.starts_with("<!--") {
+            "-->"
+        } else if rest.starts_with("<!") {
+            ">"
+        } else {
+            break;
+        };
+        let Some(end) = rest.find(skip_to) else {
+            return false;
+        };
+        rest = rest[end + skip_to.len()..].trim_start();
+    }
+    rest.strip_prefix("<svg")
+        .and_then(|after| after.chars().next())
+        .is_some_and(|c| c.is_ascii_whitespace() || c == '>' || c == '/')
+}
+
 /// Detect MIME type from file extension or magic bytes.
 ///
 /// Returns a static string like "image/jpeg", "image/png", etc.
@@ -505,6 +825,107 @@ pub fn truncate_to_date(s: &str) -> String {
     }
 }
 
+/// Normalize an ISO 8601 date or timestamp to the `CCYY-MM-DDThh:mm:ssZ`
+/// form EPUB 3 requires for `dcterms:modified`.
+///
+/// Missing month, day, and time parts default to their start, offsets are
+/// converted to UTC, and fractional seconds are dropped. Returns `None` for
+/// anything that isn't a valid date.
+///
+/// # Examples
+///
+/// ```ignore
+/// assert_eq!(to_utc_timestamp("2024-01-15").as_deref(), Some("2024-01-15T00:00:00Z"));
+/// assert_eq!(to_utc_timestamp("2019-03-02T10:00:00+01:00").as_deref(), Some("2019-03-02T09:00:00Z"));
+/// ```
+pub(crate) fn to_utc_timestamp(s: &str) -> Option<String> {
+    fn digits(s: &str, len: usize) -> Option<i64> {
+        if s.len() == len && s.bytes().all(|b| b.is_ascii_digit()) {
+            s.parse().ok()
+        } else {
+            None
+        }
+    }
+
+    let s = s.trim();
+    let (date, time) = match s.find(['T', 't', ' ']) {
+        Some(i) => (&s[..i], Some(&s[i + 1..])),
+        None => (s, None),
+    };
+    let mut parts = date.split('-');
+    let year = digits(parts.next()?, 4)?;
+    let month = parts.next().map_or(Some(1), |p| digits(p, 2))?;
+    let day = parts.next().map_or(Some(1), |p| digits(p, 2))?;
+    if parts.next().is_some()
+        || !(1..=12).contains(&month)
+        || civil_from_days(days_from_civil(year, month, day)) != (year, month, day)
+    {
+        return None;
+    }
+
+    let mut seconds = 0;
+    if let Some(time) = time {
+        let (clock, offset) = match time.find(['Z', 'z', '+', '-']) {
+            Some(i) => (&time[..i], &time[i..]),
+            None => (time, ""),
+        };
+        let mut fields = clock.split('.').next()?.split(':');
+        let hour = digits(fields.next()?, 2)?;
+        let minute = digits(fields.next()?, 2)?;
+        let second = fields.next().map_or(Some(0), |p| digits(p, 2))?;
+        if fields.next().is_some() || hour > 23 || minute > 59 || second > 60 {
+            return None;
+        }
+        // A leap second has no UTC timestamp of its own here.
+        seconds = hour * 3600 + minute * 60 + second.min(59);
+        if !matches!(offset, "" | "Z" | "z") {
+            let sign = if offset.starts_with('-') { -1 } else { 1 };
+            let offset = offset[1..].replace(':', "");
+            if offset.len() != 4 {
+                return None;
+            }
+            let hours = digits(&offset[..2], 2)?;
+            let minutes = digits(&offset[2..], 2)?;
+            seconds -= sign * (hours * 3600 + minutes * 60);
+        }
+    }
+
+    let total = days_from_civil(year, month, day) * 86400 + seconds;
+    let (y, m, d) = civil_from_days(total.div_euclid(86400));
+    let secs = total.rem_euclid(86400);
+    Some(format!(
+        "{y:04}-{m:02}-{d:02}T{:02}:{:02}:{:02}Z",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    ))
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Inverse of [`days_from_civil`].
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
 // ============================================================================
 // Encoding Detection
 // ============================================================================
@@ -634,6 +1055,120 @@ mod tests {
         assert_eq!(out, raw);
     }
 
+    #[test]
+    fn image_dimensions_read_from_headers() {
+        // PNG: signature, then the IHDR chunk's width and height.
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        png.extend_from_slice(&640u32.to_be_bytes());
+        png.extend_from_slice(&480u32.to_be_bytes());
+        png.extend_from_slice(&[8, 2, 0, 0, 0]);
+        assert_eq!(image_dimensions(&png), Some((640, 480)));
+
+        // JPEG: SOI, an APP0 segment to skip, then SOF0 (height first).
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10];
+        jpeg.extend_from_slice(b"JFIF\0\x01\x01\0\0\x01\0\x01\0\0");
+        jpeg.extend_from_slice(&[0xFF, 0xC0, 0x00, 0x11, 0x08, 0x01, 0xE0, 0x02, 0x80, 0x03]);
+        jpeg.extend_from_slice(&[0; 9]);
+        assert_eq!(image_dimensions(&jpeg), Some((640, 480)));
+
+        let mut gif = b"GIF89a".to_vec();
+        gif.extend_from_slice(&[0x80, 0x02, 0xE0, 0x01]);
+        gif.resize(24, 0);
+        assert_eq!(image_dimensions(&gif), Some((640, 480)));
+
+        let webp = |chunk: &[u8], payload: &[u8]| {
+            let mut data = b"RIFF\0\0\0\0WEBP".to_vec();
+            data.extend_from_slice(chunk);
+            data.extend_from_slice(&[0; 4]);
+            data.extend_from_slice(payload);
+            data
+        };
+        let lossy = webp(
+            b"VP8 ",
+            &[0x30, 0x01, 0x00, 0x9D, 0x01, 0x2A, 0x80, 0x02, 0xE0, 0x01],
+        );
+        assert_eq!(image_dimensions(&lossy), Some((640, 480)));
+        // 639 | 479 << 14, little-endian.
+        let bits = 639u32 | (479 << 14);
+        let mut lossless = vec![0x2F];
+        lossless.extend_from_slice(&bits.to_le_bytes());
+        assert_eq!(
+            image_dimensions(&webp(b"VP8L", &lossless)),
+            Some((640, 480))
+        );
+        let extended = webp(b"VP8X", &[0, 0, 0, 0, 0x7F, 0x02, 0x00, 0xDF, 0x01, 0x00]);
+        assert_eq!(image_dimensions(&extended), Some((640, 480)));
+
+        assert_eq!(image_dimensions(&png[..20]), None);
+        assert_eq!(image_dimensions(&lossy[..26]), None);
+    }
+
+    #[test]
+    fn rewrite_moved_references_renames_src_and_href_targets() {
+        let doc = br##"<img src="../images/old.png" alt="x"/><a href='../images/old.png#f'>a</a>
+<image xlink:href="../images/old.png"/><a data-href="../images/old.png">b</a>
+<a href="other.xhtml">c</a> src="../images/old.png" in text"##;
+        let out = rewrite_moved_references(doc.to_vec(), "OEBPS/text/ch1.xhtml", |path| {
+            (path == "OEBPS/images/old.png").then(|| "OEBPS/images/new.png".to_string())
+        });
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            r##"<img src="../images/new.png" alt="x"/><a href='../images/new.png#f'>a</a>
+<image xlink:href="../images/new.png"/><a data-href="../images/old.png">b</a>
+<a href="other.xhtml">c</a> src="../images/new.png" in text"##
+        );
+    }
+
+    #[test]
+    fn rewrite_moved_references_renames_css_urls() {
+        let css = br#"@font-face { src: url(../fonts/old.otf) }
+body { background: url( "../images/old.png" ) } p { background: url('../images/old.png#x') }
+h1 { background: url(../images/other.png) }"#;
+        let out = rewrite_moved_references(css.to_vec(), "OEBPS/css/main.css", |path| {
+            path.ends_with("/old.png")
+                .then(|| "OEBPS/images/new.png".to_string())
+                .or_else(|| {
+                    path.ends_with("/old.otf")
+                        .then(|| "OEBPS/fonts/new.otf".to_string())
+                })
+        });
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            r#"@font-face { src: url(../fonts/new.otf) }
+body { background: url( "../images/new.png" ) } p { background: url('../images/new.png#x') }
+h1 { background: url(../images/other.png) }"#
+        );
+    }
+
+    #[test]
+    fn sniff_image_mime_reads_magic_bytes() {
+        let cases: [(&[u8], Option<&str>); 8] = [
+            (&[0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10], Some("image/jpeg")),
+            (b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR", Some("image/png")),
+            (b"GIF89a\x01\0\x01\0", Some("image/gif")),
+            (b"RIFF\x24\0\0\0WEBPVP8 ", Some("image/webp")),
+            (
+                b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>",
+                Some("image/svg+xml"),
+            ),
+            (
+                b"\xEF\xBB\xBF<?xml version=\"1.0\"?>\n<!-- art -->\n<!DOCTYPE svg>\n<svg>",
+                Some("image/svg+xml"),
+            ),
+            (b"<svgx/>", None),
+            (b"<html><svg/></html>", None),
+        ];
+        for (data, mime) in cases {
+            assert_eq!(
+                sniff_image_mime(data),
+                mime,
+                "{:?}",
+                String::from_utf8_lossy(data)
+            );
+        }
+        assert_eq!(detect_media_format("e6", b"<svg/>"), MediaFormat::Svg);
+    }
+
     #[test]
     fn test_percent_decode_basic() {
         // No escapes: borrowed passthrough.
@@ -734,4 +1269,32 @@ mod tests {
         // With timezone offset
         assert_eq!(truncate_to_date("2022-05-26T16:26:51+00:00"), "2022-05-26");
     }
+
+    #[test]
+    fn test_to_utc_timestamp() {
+        assert_eq!(
+            to_utc_timestamp("2024-01-15").as_deref(),
+            Some("2024-01-15T00:00:00Z")
+        );
+        assert_eq!(
+            to_utc_timestamp("2024").as_deref(),
+            Some("2024-01-01T00:00:00Z")
+        );
+        assert_eq!(
+            to_utc_timestamp("2019-03-02T10:00:00+00:00").as_deref(),
+            Some("2019-03-02T10:00:00Z")
+        );
+        // Offsets carry across day and year boundaries.
+        assert_eq!(
+            to_utc_timestamp("2020-01-01T01:30:00.250+02:00").as_deref(),
+            Some("2019-12-31T23:30:00Z")
+        );
+        assert_eq!(
+            to_utc_timestamp("2024-02-28T22:00-0300").as_deref(),
+            Some("2024-02-29T01:00:00Z")
+        );
+        assert_eq!(to_utc_timestamp("2023-02-29"), None);
+        assert_eq!(to_utc_timestamp("15/01/2024"), None);
+        assert_eq!(to_utc_timestamp("2024-01-15T25:00:00Z"), None);
+    }
 }
@@ -0,0 +1,319 @@
+//! Book validation: structural problems worth knowing about before export.
+//!
+//! [`Book::validate`] loads every chapter, resolves links the same way
+//! export does, and reports what doesn't resolve as [`Diagnostic`]s:
+//! dangling `href`s, `src`s naming assets the book doesn't contain, TOC
+//! entries without a target, a cover path that isn't an asset, text that
+//! export will strip of control characters, and an empty spine.
+
+use std::collections::HashSet;
+
+use crate::Book;
+use crate::model::{ChapterId, TocEntry};
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Severity {
+    /// Worth a look, but the book is usable as-is.
+    Warning,
+    /// Readers will show something broken (a missing image, a dead link).
+    Error,
+}
+
+/// One problem found by [`Book::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Diagnostic {
+    /// How serious the problem is.
+    pub severity: Severity,
+    /// Stable machine-readable code, e.g. `"broken-link"` or
+    /// `"missing-asset"`.
+    pub code: &'static str,
+    /// Human-readable description naming the offending reference.
+    pub message: String,
+    /// Where the problem is: a chapter's source path, `"toc"`, or
+    /// `"metadata"`. `None` for book-wide problems.
+    pub location: Option<String>,
+}
+
+impl Diagnostic {
+    fn new(
+        severity: Severity,
+        code: &'static str,
+        message: String,
+        location: Option<String>,
+    ) -> Self {
+        Self {
+            severity,
+            code,
+            message,
+            location,
+        }
+    }
+
+    fn error(code: &'static str, message: String, location: Option<String>) -> Self {
+        Self::new(Severity::Error, code, message, location)
+    }
+}
+
+/// An internal link whose target doesn't exist, found by
+/// [`Book::validate_links`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct BrokenLink {
+    /// Chapter containing the link.
+    pub from_chapter: ChapterId,
+    /// The link's target, resolved against the chapter's path (e.g.
+    /// `text/ch1.xhtml#missing`).
+    pub href: String,
+}
+
+impl Book {
+    /// Find internal links that resolve to nothing.
+    ///
+    /// A narrower, faster check than [`validate`](Self::validate) for link
+    /// audits: every chapter's links are resolved as export would, and
+    /// those pointing at no chapter, anchor, or asset are returned in
+    /// document order. External URLs are skipped. A link into an existing
+    /// chapter whose fragment matches no id counts as broken.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a chapter can't be loaded.
+    pub fn validate_links(&self) -> crate::Result<Vec<BrokenLink>> {
+        let resolved = self.resolve_links()?;
+        let assets: HashSet<&str> = self.list_assets().iter().map(String::as_str).collect();
+        let chapters = self.chapter_paths();
+        // Chapter files are readable as assets too; a link into one with a
+        // bad fragment is still broken.
+        let is_asset_link = |href: &str| {
+            let file = href.split('#').next().unwrap_or(href);
+            !chapters.contains(file) && self.is_asset(&assets, href)
+        };
+        Ok(resolved
+            .broken_links()
+            .iter()
+            .filter(|(_, href)| !is_external(href) && !is_asset_link(href))
+            .map(|(source, href)| BrokenLink {
+                from_chapter: source.chapter,
+                href: href.clone(),
+            })
+            .collect())
+    }
+
+    /// Whether `path` (ignoring any fragment) names an asset. Some backends
+    /// serve assets under names they don't list (KFX resource names like
+    /// "e6"); the backend answers for those without loading them.
+    fn is_asset(&self, listed: &HashSet<&str>, path: &str) -> bool {
+        let file = path.split('#').next().unwrap_or(path);
+        listed.contains(file) || self.contains_asset(file)
+    }
+
+    /// Check the book for broken references and structural problems.
+    ///
+    /// Reports, in order:
+    /// - `empty-spine`: the book has no content documents
+    /// - `unreadable-chapter`: a chapter failed to load (link checks are
+    ///   skipped)
+    /// - `broken-link`: an `href` that resolves to no chapter or asset
+    /// - `missing-anchor` (warning): an `href` or TOC entry into an existing
+    ///   chapter whose fragment matches no id; readers land at the chapter
+    ///   start
+    /// - `missing-asset`: a `src` naming an asset the book doesn't contain
+    /// - `control-characters` (warning): text holding control characters
+    ///   or Unicode noncharacters, with their count; EPUB and KFX export
+    ///   drop them
+    /// - `broken-toc-target`: a TOC entry whose target doesn't exist
+    /// - `missing-cover`: the cover image path isn't an asset
+    ///
+    /// External URLs and `data:` URIs are never reported. Chapters are
+    /// loaded through the IR cache, so a following export reuses them.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use boko::{Book, Severity};
+    ///
+    /// let book = Book::open("input.epub")?;
+    /// let diagnostics = book.validate();
+    /// for d in &diagnostics {
+    ///     eprintln!("{:?} [{}] {}", d.severity, d.code, d.message);
+    /// }
+    /// let ok = diagnostics.iter().all(|d| d.severity < Severity::Error);
+    /// # Ok::<(), boko::Error>(())
+    /// ```
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        if self.spine().is_empty() {
+            diagnostics.push(Diagnostic::error(
+                "empty-spine",
+                "the spine has no content documents".to_string(),
+                None,
+            ));
+        }
+
+        let assets: HashSet<&str> = self.list_assets().iter().map(String::as_str).collect();
+        let is_asset = |path: &str| self.is_asset(&assets, path);
+
+        let chapters = self.chapter_paths();
+
+        let links_resolved = match self.validate_links() {
+            Ok(broken) => {
+                for BrokenLink { from_chapter, href } in broken {
+                    let location = Some(self.chapter_location(from_chapter));
+                    let diagnostic = match href.split_once('#') {
+                        Some((file, fragment)) if chapters.contains(file) => Diagnostic::new(
+                            Severity::Warning,
+                            "missing-anchor",
+                            format!("link target {file:?} has no id {fragment:?}"),
+                            location,
+                        ),
+                        _ => Diagnostic::error(
+                            "broken-link",
+                            format!("link target {href:?} does not exist"),
+                            location,
+                        ),
+                    };
+                    diagnostics.push(diagnostic);
+                }
+                true
+            }
+            Err(e) => {
+                diagnostics.push(Diagnostic::error(
+                    "unreadable-chapter",
+                    format!("could not load chapters: {e}"),
+                    None,
+                ));
+                false
+            }
+        };
+
+        for entry in self.spine() {
+            let Ok(chapter) = self.load_chapter_cached(entry.id) else {
+                continue;
+            };
+            for node_id in chapter.iter_dfs() {
+                let Some(src) = chapter.semantics.src(node_id) else {
+                    continue;
+                };
+                if is_external(src) || is_asset(src) {
+                    continue;
+                }
+                diagnostics.push(Diagnostic::error(
+                    "missing-asset",
+                    format!("referenced asset {src:?} is not in the book"),
+                    Some(self.chapter_location(entry.id)),
+                ));
+            }
+            let stray = crate::util::count_unexpected_chars(chapter.text_buffer());
+            if stray > 0 {
+                diagnostics.push(Diagnostic::new(
+                    Severity::Warning,
+                    "control-characters",
+                    format!(
+                        "{stray} control character(s) or noncharacter(s) in the text are removed on export"
+                    ),
+                    Some(self.chapter_location(entry.id)),
+                ));
+            }
+        }
+
+        // `toc()` carries targets only once links resolved.
+        if links_resolved {
+            fn flatten<'a>(entries: &'a [TocEntry], out: &mut Vec<&'a TocEntry>) {
+                for entry in entries {
+                    out.push(entry);
+                    flatten(&entry.children, out);
+                }
+            }
+            let mut entries = Vec::new();
+            flatten(self.toc(), &mut entries);
+            for entry in entries {
+                let href = entry.href.as_str();
+                if entry.target.is_some() || href.is_empty() || is_external(href) {
+                    continue;
+                }
+                let location = Some("toc".to_string());
+                let diagnostic = match href.split_once('#') {
+                    Some((file, fragment)) if chapters.contains(file) => Diagnostic::new(
+                        Severity::Warning,
+                        "missing-anchor",
+                        format!(
+                            "TOC entry {:?} targets {file:?}, which has no id {fragment:?}",
+                            entry.title
+                        ),
+                        location,
+                    ),
+                    _ => Diagnostic::error(
+                        "broken-toc-target",
+                        format!("TOC entry {:?} points to missing {href:?}", entry.title),
+                        location,
+                    ),
+                };
+                diagnostics.push(diagnostic);
+            }
+        }
+
+        if let Some(cover) = &self.metadata().cover_image
+            && !is_asset(cover)
+        {
+            diagnostics.push(Diagnostic::error(
+                "missing-cover",
+                format!("cover image {cover:?} is not in the book"),
+                Some("metadata".to_string()),
+            ));
+        }
+
+        diagnostics
+    }
+
+    /// Source paths of the spine chapters.
+    fn chapter_paths(&self) -> HashSet<&str> {
+        self.spine()
+            .iter()
+            .filter_map(|entry| self.source_id(entry.id))
+            .collect()
+    }
+
+    /// A chapter's source path, or its id when the backend has none.
+    fn chapter_location(&self, id: ChapterId) -> String {
+        self.source_id(id)
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("chapter {}", id.0))
+    }
+}
+
+/// Whether `reference` points outside the book: a URL with a scheme
+/// (`https:`, `mailto:`, `data:`, ...) rather than an archive path.
+fn is_external(reference: &str) -> bool {
+    let Some((scheme, _)) = reference.split_once(':') else {
+        return false;
+    };
+    scheme.len() > 1
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn external_references_have_a_scheme() {
+        for external in [
+            "https://example.com/a.png",
+            "mailto:someone@example.com",
+            "data:image/png;base64,AAAA",
+        ] {
+            assert!(is_external(external), "{external}");
+        }
+        for internal in ["OEBPS/images/a.png", "text/ch1.xhtml#p:1", "C:/x.png"] {
+            assert!(!is_external(internal), "{internal}");
+        }
+    }
+}
@@ -22,6 +22,9 @@ fn parse_format(name: &str) -> Result<Format, JsValue> {
         "mobi" | "azw" => Ok(Format::Mobi),
         "kfx" => Ok(Format::Kfx),
         "markdown" | "md" => Ok(Format::Markdown),
+        "ir" => Ok(Format::Ir),
+        "fb2" => Ok(Format::Fb2),
+        "cbz" => Ok(Format::Cbz),
         _ => Err(JsValue::from_str(&format!("unknown format: {name}"))),
     }
 }
@@ -109,7 +112,7 @@ pub fn book_info(data: &[u8], from: &str) -> Result<JsValue, JsValue> {
         json_string(&meta.title),
         authors.join(","),
         json_string(&meta.language),
-        book.spine().len(),
+        book.chapter_count(),
         count_toc(book.toc()),
     );
 
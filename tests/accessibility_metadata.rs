@@ -0,0 +1,124 @@
+//! Accessibility metadata (`schema:accessibilityFeature` and friends) is
+//! parsed into `Metadata::accessibility` and written back out on EPUB export.
+
+mod common;
+
+use std::io::{Cursor, Read};
+use std::process::Command;
+
+use boko::export::{EpubConfig, EpubExporter, EpubVersion, Exporter};
+use boko::model::Accessibility;
+use boko::{Book, Format};
+
+fn sample_epub() -> Vec<u8> {
+    use common::{Doc, EpubBuilder, Nav};
+
+    EpubBuilder::new("Accessible")
+        .metadata_xml(r#"<meta property="schema:accessMode">textual</meta>"#)
+        .metadata_xml(r#"<meta property="schema:accessMode">visual</meta>"#)
+        .metadata_xml(r#"<meta property="schema:accessModeSufficient">textual</meta>"#)
+        .metadata_xml(
+            r#"<meta property="schema:accessibilityFeature">structuralNavigation</meta>"#,
+        )
+        .metadata_xml(r#"<meta property="schema:accessibilityFeature">alternativeText</meta>"#)
+        .metadata_xml(r#"<meta property="schema:accessibilityHazard">none</meta>"#)
+        .metadata_xml(
+            r#"<meta property="schema:accessibilitySummary">Images have text &amp; descriptions.</meta>"#,
+        )
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>x</p>"))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build()
+}
+
+fn expected() -> Accessibility {
+    Accessibility {
+        features: vec!["structuralNavigation".into(), "alternativeText".into()],
+        access_modes: vec!["textual".into(), "visual".into()],
+        access_modes_sufficient: vec!["textual".into()],
+        hazards: vec!["none".into()],
+        summary: Some("Images have text & descriptions.".into()),
+    }
+}
+
+fn opf(epub: &[u8]) -> String {
+    let mut archive = zip::ZipArchive::new(Cursor::new(epub)).expect("valid zip");
+    let mut text = String::new();
+    archive
+        .by_name("OEBPS/content.opf")
+        .unwrap()
+        .read_to_string(&mut text)
+        .unwrap();
+    text
+}
+
+#[test]
+fn accessibility_metadata_round_trips_through_epub() {
+    let book = Book::from_bytes(&sample_epub(), Format::Epub).expect("import");
+    assert_eq!(book.metadata().accessibility, expected());
+    // Modeled properties aren't duplicated as custom metadata.
+    assert!(
+        book.metadata()
+            .custom
+            .iter()
+            .all(|(property, _)| !property.starts_with("schema:")),
+        "{:?}",
+        book.metadata().custom
+    );
+
+    for version in [EpubVersion::Epub3, EpubVersion::Epub2] {
+        for normalize in [false, true] {
+            let mut out = Cursor::new(Vec::new());
+            EpubExporter::new()
+                .with_config(EpubConfig {
+                    version,
+                    normalize,
+                    ..Default::default()
+                })
+                .export(&book, &mut out)
+                .expect("export epub");
+            let epub = out.into_inner();
+            let opf = opf(&epub);
+            if version == EpubVersion::Epub2 {
+                assert!(
+                    opf.contains(r#"<meta name="schema:accessMode" content="visual"/>"#),
+                    "{opf}"
+                );
+            } else {
+                assert!(
+                    opf.contains(r#"<meta property="schema:accessMode">visual</meta>"#),
+                    "{opf}"
+                );
+            }
+            let reread = Book::from_bytes(&epub, Format::Epub).expect("reimport");
+            assert_eq!(reread.metadata().accessibility, expected(), "{opf}");
+        }
+    }
+}
+
+#[test]
+fn info_json_lists_accessibility_metadata() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("book.epub");
+    std::fs::write(&path, sample_epub()).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_boko"))
+        .arg("info")
+        .arg("--json")
+        .arg(&path)
+        .output()
+        .expect("failed to run boko info");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let a11y = &json["metadata"]["accessibility"];
+    assert_eq!(
+        a11y["features"],
+        serde_json::json!(["structuralNavigation", "alternativeText"]),
+        "{json}"
+    );
+    assert_eq!(
+        a11y["access_modes"],
+        serde_json::json!(["textual", "visual"])
+    );
+    assert_eq!(a11y["hazards"], serde_json::json!(["none"]));
+    assert_eq!(a11y["summary"], "Images have text & descriptions.");
+}
@@ -0,0 +1,52 @@
+//! Elements with an `id` keep an IR node carrying it, so links to them
+//! resolve even when the element itself has no content.
+
+mod common;
+
+use boko::Book;
+use boko::model::AnchorTarget;
+use common::{Doc, EpubBuilder, Nav};
+
+/// A book whose first chapter links to `text/b.xhtml#x`, with `body` as the
+/// second chapter.
+fn linking_book(body: &str) -> Book {
+    EpubBuilder::new("Anchors")
+        .doc(Doc::new(
+            "text/a.xhtml",
+            "A",
+            r#"<p><a href="b.xhtml#x">See here</a>.</p>"#,
+        ))
+        .doc(Doc::new("text/b.xhtml", "B", body))
+        .nav(vec![Nav::new("A", "text/a.xhtml")])
+        .book()
+}
+
+fn assert_link_resolves_to_id(book: &Book) {
+    let resolved = book.resolve_links().unwrap();
+    assert!(
+        resolved.broken_links().is_empty(),
+        "{:?}",
+        resolved.broken_links()
+    );
+    let target = resolved
+        .iter()
+        .find_map(|(_, target)| match target {
+            AnchorTarget::Internal(target) => Some(*target),
+            _ => None,
+        })
+        .expect("link resolves to a node");
+    let chapter = book.load_chapter(target.chapter).unwrap();
+    assert_eq!(chapter.semantics.id(target.node), Some("x"));
+}
+
+#[test]
+fn empty_span_anchor_survives_and_resolves() {
+    let book = linking_book(r#"<p>Before<span id="x"/> after.</p>"#);
+    assert_link_resolves_to_id(&book);
+}
+
+#[test]
+fn hidden_element_id_survives_and_resolves() {
+    let book = linking_book(r#"<p>Body.</p><aside style="display: none" id="x">Note.</aside>"#);
+    assert_link_resolves_to_id(&book);
+}
@@ -0,0 +1,317 @@
+//! `Book::list_assets_with_types`.
+
+mod common;
+
+use boko::export::Exporter;
+use boko::{Book, Format};
+use std::path::{Path, PathBuf};
+
+fn type_of<'a>(assets: &'a [(PathBuf, String)], path: &str) -> Option<&'a str> {
+    assets
+        .iter()
+        .find(|(p, _)| p == Path::new(path))
+        .map(|(_, t)| t.as_str())
+}
+
+#[test]
+fn epub_assets_report_manifest_types() {
+    let book = Book::open("tests/fixtures/epictetus.epub").expect("open epub");
+    let assets = book.list_assets_with_types();
+    assert_eq!(assets.len(), book.list_assets().len());
+    assert_eq!(
+        type_of(&assets, "epub/images/cover.jpg"),
+        Some("image/jpeg")
+    );
+    assert_eq!(type_of(&assets, "epub/images/logo.png"), Some("image/png"));
+    assert_eq!(type_of(&assets, "epub/css/core.css"), Some("text/css"));
+
+    // The declared type is reported as-is, without sniffing the bytes.
+    use common::{Doc, EpubBuilder, Nav};
+    let book = EpubBuilder::new("Mislabeled")
+        .image("images/photo.jpg", common::tiny_png())
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>x</p>"))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+    let assets = book.list_assets_with_types();
+    assert_eq!(
+        type_of(&assets, "OEBPS/images/photo.jpg"),
+        Some("image/jpeg")
+    );
+}
+
+#[test]
+fn manifestless_assets_are_sniffed() {
+    // KFX resources have bare ids for names; their bytes identify them.
+    let book = Book::open("tests/fixtures/epictetus.kfx").expect("open kfx");
+    let assets = book.list_assets_with_types();
+    assert!(!assets.is_empty());
+    assert!(assets.iter().all(|(_, t)| t == "image/jpeg"), "{assets:?}");
+
+    let book = Book::open_format("tests/fixtures/epictetus.azw3", Format::Azw3).expect("azw3");
+    let assets = book.list_assets_with_types();
+    assert!(
+        assets
+            .iter()
+            .any(|(p, t)| p.extension().is_some_and(|e| e == "jpg") && t == "image/jpeg")
+    );
+}
+
+#[test]
+fn kfx_media_types_read_only_entity_heads() {
+    use boko::import::KfxImporter;
+    use boko::{ByteSource, FileSource, Importer};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Remembers the longest read it served.
+    struct Recording {
+        inner: FileSource,
+        longest: AtomicUsize,
+    }
+
+    impl ByteSource for Recording {
+        fn len(&self) -> u64 {
+            self.inner.len()
+        }
+
+        fn read_at_into(&self, offset: u64, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.longest.fetch_max(buf.len(), Ordering::Relaxed);
+            self.inner.read_at_into(offset, buf)
+        }
+    }
+
+    let file = std::fs::File::open("tests/fixtures/epictetus.kfx").unwrap();
+    let source = Arc::new(Recording {
+        inner: FileSource::new(file).unwrap(),
+        longest: AtomicUsize::new(0),
+    });
+    let importer = KfxImporter::from_source(source.clone()).expect("open kfx");
+    source.longest.store(0, Ordering::Relaxed);
+    assert!(importer.list_assets().iter().any(|p| p.starts_with('#')));
+    for path in importer.list_assets().iter().filter(|p| p.starts_with('#')) {
+        assert_eq!(
+            importer.asset_media_type(path),
+            Some("image/jpeg"),
+            "{path}"
+        );
+    }
+    assert!(source.longest.load(Ordering::Relaxed) <= 1024);
+}
+
+#[test]
+fn corrected_media_type_reaches_the_epub_manifest() {
+    use common::{Doc, EpubBuilder, Nav};
+    use std::io::Read;
+
+    // PNG bytes behind a .jpg name: the manifest would say image/jpeg.
+    let mut book = EpubBuilder::new("Mislabeled")
+        .image("images/photo.jpg", common::tiny_png())
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<p><img src=\"../images/photo.jpg\" alt=\"Photo\"/></p>",
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+    book.set_resource_media_type("OEBPS/images/photo.jpg", "image/png")
+        .expect("relabel");
+    assert_eq!(
+        type_of(&book.list_assets_with_types(), "OEBPS/images/photo.jpg"),
+        Some("image/png")
+    );
+
+    let epub = common::export_to_bytes(&mut book, Format::Epub);
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(epub)).expect("zip");
+    let mut opf = String::new();
+    archive
+        .by_name("OEBPS/content.opf")
+        .expect("opf")
+        .read_to_string(&mut opf)
+        .unwrap();
+    let item = opf
+        .lines()
+        .find(|line| line.contains("images/photo.jpg"))
+        .expect("manifest item");
+    assert!(item.contains("media-type=\"image/png\""), "{item}");
+
+    assert!(matches!(
+        book.set_resource_media_type("OEBPS/images/missing.png", "image/png"),
+        Err(boko::Error::NotFound { .. })
+    ));
+    assert!(matches!(
+        book.set_resource_media_type("OEBPS/images/photo.jpg", "image/x-unknown"),
+        Err(boko::Error::UnsupportedFormat { .. })
+    ));
+}
+
+#[test]
+fn resource_media_type_accessors() {
+    let mut resource = boko::model::Resource {
+        data: common::tiny_png(),
+        media_type: "image/jpeg",
+    };
+    resource.set_media_type("IMAGE/PNG").expect("known type");
+    assert_eq!(resource.media_type(), "image/png");
+    resource
+        .set_media_type("audio/mpeg")
+        .expect("EPUB core type");
+    assert_eq!(resource.media_type(), "audio/mpeg");
+    // Unknown types fail the same way on a resource and on a book.
+    assert!(matches!(
+        resource.set_media_type("application/x-custom"),
+        Err(boko::Error::UnsupportedFormat { .. })
+    ));
+    assert_eq!(resource.media_type(), "audio/mpeg");
+}
+
+#[test]
+fn added_images_are_typed_by_their_bytes() {
+    use common::{Doc, EpubBuilder, Nav};
+    use std::io::Read;
+
+    let mut book = EpubBuilder::new("Added")
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>x</p>"))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+    let svg = b"<?xml version=\"1.0\"?>\n<svg xmlns=\"http://www.w3.org/2000/svg\"/>".to_vec();
+    book.add_image("OEBPS/images/photo.jpg", common::tiny_png())
+        .expect("png");
+    book.add_image("OEBPS/images/art", svg.clone())
+        .expect("svg");
+
+    let assets = book.list_assets_with_types();
+    assert_eq!(
+        type_of(&assets, "OEBPS/images/photo.jpg"),
+        Some("image/png")
+    );
+    assert_eq!(type_of(&assets, "OEBPS/images/art"), Some("image/svg+xml"));
+    assert_eq!(book.load_asset("OEBPS/images/art").unwrap(), svg);
+
+    assert!(matches!(
+        book.add_image("OEBPS/images/notes", b"plain text".to_vec()),
+        Err(boko::Error::UnsupportedFormat { .. })
+    ));
+    assert!(matches!(
+        book.add_image("OEBPS/images/photo.jpg", common::tiny_png()),
+        Err(boko::Error::InUse { .. })
+    ));
+
+    let epub = common::export_to_bytes(&mut book, Format::Epub);
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(epub)).expect("zip");
+    let mut opf = String::new();
+    archive
+        .by_name("OEBPS/content.opf")
+        .expect("opf")
+        .read_to_string(&mut opf)
+        .unwrap();
+    let item = opf
+        .lines()
+        .find(|line| line.contains("images/photo.jpg"))
+        .expect("manifest item");
+    assert!(item.contains("media-type=\"image/png\""), "{item}");
+}
+
+#[test]
+fn html_content_documents_are_declared_as_xhtml() {
+    use common::{Doc, EpubBuilder, Nav};
+    use std::io::Read;
+
+    let mut book = EpubBuilder::new("Html")
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>x</p>"))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+    // A spine document and the (non-spine) nav document, both relabeled the
+    // way MOBI-derived sources declare their HTML.
+    for path in ["OEBPS/text/ch1.xhtml", "OEBPS/nav.xhtml"] {
+        book.set_resource_media_type(path, "text/html")
+            .expect("relabel");
+        let resource = book.resource(path).unwrap();
+        assert_eq!(resource.media_type(), "text/html");
+        assert!(resource.is_content_document());
+        assert_eq!(resource.normalized_mime(), "application/xhtml+xml");
+    }
+    let css = boko::model::Resource {
+        data: b"p {}".to_vec(),
+        media_type: "text/css",
+    };
+    assert!(!css.is_content_document());
+    assert_eq!(css.normalized_mime(), "text/css");
+
+    for normalize in [false, true] {
+        let mut out = std::io::Cursor::new(Vec::new());
+        boko::export::EpubExporter::new()
+            .with_config(boko::export::EpubConfig {
+                normalize,
+                ..Default::default()
+            })
+            .export(&book, &mut out)
+            .unwrap();
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(out.into_inner())).unwrap();
+        let mut opf = String::new();
+        archive
+            .by_name("OEBPS/content.opf")
+            .expect("opf")
+            .read_to_string(&mut opf)
+            .unwrap();
+        assert!(!opf.contains("text/html"), "{opf}");
+        if !normalize {
+            for href in ["text/ch1.xhtml", "nav.xhtml"] {
+                let item = opf
+                    .lines()
+                    .find(|line| line.contains(&format!("/{href}\"")))
+                    .expect("manifest item");
+                assert!(
+                    item.contains("media-type=\"application/xhtml+xml\""),
+                    "{item}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn declared_types_survive_asset_passes_and_reach_the_manifest() {
+    use common::{Doc, EpubBuilder, Nav};
+    use std::io::Read;
+
+    // Neither the extension nor the bytes identify the clip; only the
+    // manifest does.
+    let para = "<p style=\"color: red\">x</p>".repeat(3);
+    let mut book = EpubBuilder::new("Audio")
+        .image("audio/intro.mp3", b"not really mpeg".to_vec())
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            &format!("<p><audio src=\"../audio/intro.mp3\"/></p>{para}"),
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+    book.optimize();
+    book.prune_unused_assets();
+    assert_eq!(book.merge_css_classes().classes_created, 1);
+
+    let assets = book.list_assets_with_types();
+    assert_eq!(
+        type_of(&assets, "OEBPS/audio/intro.mp3"),
+        Some("audio/mpeg")
+    );
+    let (css, _) = assets
+        .iter()
+        .find(|(p, _)| p.extension().is_some_and(|e| e == "css"))
+        .expect("merged stylesheet");
+    assert_eq!(type_of(&assets, css.to_str().unwrap()), Some("text/css"));
+
+    let epub = common::export_to_bytes(&mut book, Format::Epub);
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(epub)).expect("zip");
+    let mut opf = String::new();
+    archive
+        .by_name("OEBPS/content.opf")
+        .expect("opf")
+        .read_to_string(&mut opf)
+        .unwrap();
+    let item = opf
+        .lines()
+        .find(|line| line.contains("audio/intro.mp3"))
+        .expect("manifest item");
+    assert!(item.contains("media-type=\"audio/mpeg\""), "{item}");
+}
@@ -10,6 +10,8 @@
 //! Each assertion below corresponds to a specific bug class previously fixed
 //! in the writer; if one regresses it should fail here, not on a Kindle.
 
+mod common;
+
 use std::io::Cursor;
 
 use boko::model::{Format, TocEntry};
@@ -235,3 +237,69 @@ fn azw3_roundtrip_preserves_stylesheet_styles() {
          (KF8 flow CSS regression)"
     );
 }
+
+/// Append `record` to a PDB file as its last record.
+fn append_pdb_record(pdb: &[u8], record: &[u8]) -> Vec<u8> {
+    let count = u16::from_be_bytes([pdb[76], pdb[77]]) as usize;
+    let list_end = 78 + count * 8;
+    let mut out = pdb[..76].to_vec();
+    out.extend_from_slice(&(count as u16 + 1).to_be_bytes());
+    // Every record moves down by the new 8-byte list entry.
+    for entry in pdb[78..list_end].chunks(8) {
+        let offset = u32::from_be_bytes(entry[..4].try_into().unwrap()) + 8;
+        out.extend_from_slice(&offset.to_be_bytes());
+        out.extend_from_slice(&entry[4..]);
+    }
+    out.extend_from_slice(&(pdb.len() as u32 + 8).to_be_bytes());
+    out.extend_from_slice(&(count as u32 * 2).to_be_bytes());
+    out.extend_from_slice(&pdb[list_end..]);
+    out.extend_from_slice(record);
+    out
+}
+
+/// Repeated EXTH author records all survive, and the series that kindlegen
+/// carries in the RESC record's embedded OPF metadata fills
+/// `Metadata::collection`.
+#[test]
+fn azw3_reads_multiple_authors_and_resc_series() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let mut book = EpubBuilder::new("Guards! Guards!")
+        .metadata_xml("<dc:creator>Second Author</dc:creator>")
+        .image("images/map.png", common::tiny_png())
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<p>The watch.</p><img src=\"../images/map.png\" alt=\"Map\"/>",
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+    let azw3 = common::export_to_bytes(&mut book, Format::Azw3);
+
+    let reopened = Book::from_bytes(&azw3, Format::Azw3).expect("reopen azw3");
+    assert_eq!(
+        reopened.metadata().authors,
+        ["Test Author", "Second Author"]
+    );
+    assert!(reopened.metadata().collection.is_none());
+    let plain_assets = reopened.list_assets().to_vec();
+
+    let mut resc = b"RESC".to_vec();
+    for word in [1u32, 0, 0] {
+        resc.extend_from_slice(&word.to_be_bytes());
+    }
+    resc.extend_from_slice(
+        br#"<?xml version="1.0" encoding="UTF-8"?><package xmlns="http://www.idpf.org/2007/opf" xmlns:dc="http://purl.org/dc/elements/1.1/"><metadata><dc:creator>Test Author</dc:creator><dc:creator>Second Author</dc:creator><meta name="calibre:series" content="Discworld"/><meta name="calibre:series_index" content="8"/></metadata><spine toc="ncx"><itemref idref="x" skelid="0"/></spine></package>"#,
+    );
+    resc.extend_from_slice(&[0; 4]);
+    let with_resc = append_pdb_record(&azw3, &resc);
+
+    let reopened = Book::from_bytes(&with_resc, Format::Azw3).expect("reopen azw3 with RESC");
+    let metadata = reopened.metadata();
+    assert_eq!(metadata.authors, ["Test Author", "Second Author"]);
+    let series = metadata.collection.as_ref().expect("series from RESC");
+    assert_eq!(series.name, "Discworld");
+    assert_eq!(series.position, Some(8.0));
+    // The RESC record is metadata, not an asset.
+    assert_eq!(reopened.list_assets(), plain_assets);
+}
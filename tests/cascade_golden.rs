@@ -104,12 +104,43 @@ fn descendant_book() -> EpubBuilder {
 // Updated again when anonymous mixed-content wrappers started interning an
 // inherit-only style (CSS anonymous-box semantics) instead of the default
 // StyleId — the pool gains entries, so class numbering shifts.
-const FP_EPICTETUS: &str = "2f62e95c8fba1a676cf91f2921768e0ddcd4ebfc";
-const FP_CLASS: &str = "0011593d1051d42ce417aa0bd9d63012fdaf42b7";
+// Updated again when line-height started distinguishing unitless factors
+// from font-relative lengths: factors serialize unitless (`1.6`, not
+// `1.6em`), and `em`/`%` line-heights compute to rem so descendants inherit
+// the length.
+// Updated again when vertical `auto` margins started computing to zero
+// (only horizontal `auto` centers), so `margin: auto` no longer emits
+// `margin-top: auto`.
+// Updated again when elements with a language started carrying `lang`
+// alongside `xml:lang`.
+// Updated again when styles rendering identical CSS (differing only in
+// fields CSS doesn't carry, like language) started sharing one class and
+// one rule — the emitted declarations are unchanged.
+// Updated again when normalization started sizing the image-only cover
+// page to fill the screen (only the cover chapter's classes change).
+// Updated again when images inside `text-align: center` containers started
+// getting auto side margins.
+// Updated again when `alt=""` images started being exported as decorative
+// (`role="presentation"` on the title page image).
+// Updated again when landmark targets started carrying their `epub:type`
+// (the `<body>` of each landmarked chapter).
+// Updated again when the UA stylesheet started declaring phrasing elements
+// (`em`, `b`, `code`, ...) `display: inline`; their classes now carry it.
+// Updated again when cover-page sizing started leaving author-sized images
+// alone (the title page image keeps its `width: 100%`).
+// Updated again when centering-container auto margins became limited to
+// block images and images alone in their container (the inline colophon
+// and imprint logos, which share their header with a heading, lose them).
+const FP_EPICTETUS: &str = "3544aa72248e358383ddb1af2cb822900ee15934";
+const FP_CLASS: &str = "adde01db717a2b21ad03cc07acd88fa72de4628e";
 // Updated when the UA stylesheet's blockquote/figure/dd margins moved from
 // the browser-literal 40px to 2.5em (same length at the default font size,
 // but it scales with the font instead of freezing at a device-pixel size).
-const FP_DESCENDANT: &str = "1794dcb313c9799f0c5c9fff01c8ffedfd886c68";
+// Updated again when unitless line-heights started serializing unitless.
+// Updated again when landmark targets started carrying their `epub:type`.
+// Updated again when `em` and the other phrasing elements became
+// `display: inline` in the UA stylesheet.
+const FP_DESCENDANT: &str = "2a80289168ea5de1e65fb5f65520dd2963b2a902";
 
 #[test]
 fn cascade_output_is_stable_epictetus() {
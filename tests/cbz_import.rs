@@ -0,0 +1,97 @@
+//! CBZ import: a ZIP of page images becomes a fixed-layout book with one
+//! image-only page per image.
+
+mod common;
+
+use std::io::{Cursor, Write};
+
+use boko::model::LandmarkType;
+use boko::{Book, Format, Role};
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+/// A CBZ whose pages are stored out of order, with the junk real archives
+/// carry alongside them.
+fn cbz() -> Vec<u8> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+    for name in [
+        "Comic/page10.png",
+        "Comic/page2.png",
+        "Comic/page1.png",
+        "__MACOSX/Comic/._page1.png",
+        "Comic/.thumb.png",
+    ] {
+        zip.start_file(name, options).unwrap();
+        zip.write_all(&common::tiny_png()).unwrap();
+    }
+    zip.start_file("ComicInfo.xml", options).unwrap();
+    zip.write_all(b"<ComicInfo/>").unwrap();
+    zip.finish().unwrap().into_inner()
+}
+
+#[test]
+fn cbz_pages_become_image_chapters() {
+    let book = Book::from_bytes(&cbz(), Format::Cbz).unwrap();
+
+    assert_eq!(book.spine().len(), 3);
+    let pages: Vec<String> = book
+        .spine()
+        .iter()
+        .map(|entry| {
+            let chapter = book.load_chapter(entry.id).unwrap();
+            let images: Vec<_> = chapter
+                .iter_dfs()
+                .filter(|&id| chapter.node(id).unwrap().role == Role::Image)
+                .collect();
+            assert_eq!(images.len(), 1);
+            assert!(chapter.plain_text().is_empty());
+            chapter.semantics.src(images[0]).unwrap().to_string()
+        })
+        .collect();
+    assert_eq!(
+        pages,
+        ["Comic/page1.png", "Comic/page2.png", "Comic/page10.png"]
+    );
+
+    let metadata = book.metadata();
+    assert_eq!(metadata.cover_image.as_deref(), Some("Comic/page1.png"));
+    assert!(metadata.rendition.is_fixed_layout());
+    assert_eq!(metadata.rendition.viewport, Some((1, 1)));
+    assert_eq!(book.list_assets().len(), 3);
+    assert_eq!(book.landmarks()[0].landmark_type, LandmarkType::Cover);
+}
+
+#[test]
+fn cbz_title_comes_from_the_file_name() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("Space_Raiders 01.cbz");
+    std::fs::write(&path, cbz()).unwrap();
+
+    let book = Book::open(&path).unwrap();
+    assert_eq!(book.metadata().title, "Space Raiders 01");
+    assert_eq!(book.spine().len(), 3);
+}
+
+#[test]
+fn cbz_converts_to_fixed_layout_epub() {
+    let mut book = Book::from_bytes(&cbz(), Format::Cbz).unwrap();
+    let bytes = common::export_to_bytes(&mut book, Format::Epub);
+    let epub = Book::from_bytes(&bytes, Format::Epub).unwrap();
+    // The first page already is the cover; no separate cover page.
+    assert_eq!(epub.spine().len(), 3);
+    assert!(epub.metadata().rendition.is_fixed_layout());
+    assert_eq!(epub.metadata().rendition.viewport, Some((1, 1)));
+    assert!(epub.metadata().cover_image.is_some());
+}
+
+#[test]
+fn archive_without_images_is_malformed() {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    zip.start_file("readme.txt", SimpleFileOptions::default())
+        .unwrap();
+    zip.write_all(b"no pages").unwrap();
+    let bytes = zip.finish().unwrap().into_inner();
+    let err = Book::from_bytes(&bytes, Format::Cbz).err().unwrap();
+    assert!(matches!(err, boko::Error::Malformed { .. }), "{err}");
+}
@@ -0,0 +1,151 @@
+//! `Book::chapter_html` serializes one chapter to standalone XHTML.
+
+mod common;
+
+use boko::model::Role;
+use common::{Doc, EpubBuilder, Nav};
+
+#[test]
+fn bold_text_keeps_its_paragraph_and_inline_style() {
+    let book = EpubBuilder::new("Chapter HTML")
+        .css(".note { color: #c00000; }")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<h1>Opening</h1><p class=\"note\">Some <b>bold</b> words.</p>",
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+
+    let id = book.spine()[0].id;
+    let html = book.chapter_html(id).unwrap();
+
+    assert!(html.starts_with("<?xml"), "{html}");
+    assert!(html.contains("<title>Opening</title>"), "{html}");
+    assert!(html.contains("<style>"), "{html}");
+    assert!(html.contains("color: #c00000"), "{html}");
+    assert!(!html.contains("<link"), "{html}");
+
+    // Bold is a style in the IR, so it comes back as a classed span whose
+    // rule in the inline stylesheet sets the weight.
+    let p = html.find("<p").expect("paragraph");
+    let span = html[p..].find("<span class=\"").expect("span in paragraph") + p;
+    assert!(html[p..span].contains("Some"), "{html}");
+    assert!(html[span..].contains(">bold</span> words.</p>"), "{html}");
+    let class = html[span + "<span class=\"".len()..]
+        .split('"')
+        .next()
+        .unwrap();
+    let rule = html
+        .lines()
+        .find(|line| line.starts_with(&format!(".{class} ")))
+        .expect("rule for the span's class");
+    assert!(rule.contains("font-weight: bold"), "{html}");
+}
+
+#[test]
+fn figure_keeps_figcaption() {
+    let book = EpubBuilder::new("Figure HTML")
+        .image("images/cat.png", common::tiny_png())
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<figure><img src=\"../images/cat.png\" alt=\"A cat\"/>\
+             <figcaption>A small cat.</figcaption></figure>",
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+
+    let html = book.chapter_html(book.spine()[0].id).unwrap();
+    let figure = html.find("<figure").expect("figure element");
+    let img = html[figure..].find("<img").expect("image in figure") + figure;
+    let caption = html[img..]
+        .find("<figcaption")
+        .expect("caption after image")
+        + img;
+    assert!(html[img..caption].contains("alt=\"A cat\""), "{html}");
+    let caption_end = html[caption..]
+        .find("</figcaption>")
+        .expect("closed caption")
+        + caption;
+    assert!(
+        html[caption..caption_end].contains("A small cat."),
+        "{html}"
+    );
+    assert!(html[caption_end..].contains("</figure>"), "{html}");
+}
+
+#[test]
+fn definition_list_survives_epub_roundtrip() {
+    let mut book = EpubBuilder::new("Glossary")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<dl><dt>Apple</dt><dd>A red fruit.</dd><dd>Also a company.</dd>\
+             <dt>Pear</dt><dd>A green fruit.</dd></dl>",
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+
+    let book = common::roundtrip(&mut book, boko::Format::Epub);
+    let html = book.chapter_html(book.spine()[0].id).unwrap();
+    let tags: Vec<&str> = html
+        .split('<')
+        .filter_map(|tag| {
+            let name = tag.split([' ', '>']).next()?;
+            matches!(name, "dl" | "dt" | "dd").then_some(name)
+        })
+        .collect();
+    assert_eq!(tags, ["dl", "dt", "dd", "dd", "dt", "dd"], "{html}");
+    for text in [
+        "Apple",
+        "A red fruit.",
+        "Also a company.",
+        "Pear",
+        "A green fruit.",
+    ] {
+        assert!(html.contains(text), "{html}");
+    }
+}
+
+/// `(alt, decorative)` for every image in the book's first chapter.
+fn image_alternatives(book: &boko::Book) -> Vec<(Option<String>, bool)> {
+    let chapter = book.load_chapter(book.spine()[0].id).unwrap();
+    chapter
+        .iter_dfs()
+        .filter(|&id| chapter.node(id).map(|n| n.role) == Some(Role::Image))
+        .map(|id| {
+            (
+                chapter.semantics.alt(id).map(str::to_string),
+                chapter.semantics.is_decorative(id),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn empty_alt_is_decorative_and_aria_label_fills_in_alt() {
+    let mut book = EpubBuilder::new("Image Alternatives")
+        .image("images/rule.png", common::tiny_png())
+        .image("images/map.png", common::tiny_png())
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<p><img src=\"../images/rule.png\" alt=\"\"/></p>\
+             <p><img src=\"../images/map.png\" aria-label=\"Map of the coast\"/></p>",
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+
+    let expected = [(None, true), (Some("Map of the coast".to_string()), false)];
+    assert_eq!(image_alternatives(&book), expected);
+
+    let html = book.chapter_html(book.spine()[0].id).unwrap();
+    assert!(html.contains("alt=\"\" role=\"presentation\""), "{html}");
+    assert!(html.contains("alt=\"Map of the coast\""), "{html}");
+
+    for format in [boko::Format::Epub, boko::Format::Kfx] {
+        let out = common::roundtrip(&mut book, format);
+        assert_eq!(image_alternatives(&out), expected, "{format:?}");
+    }
+}
@@ -0,0 +1,152 @@
+//! `EpubConfig::chapter_naming`: predictable chapter file names on export.
+
+mod common;
+
+use std::io::{Cursor, Read};
+
+use boko::export::{ChapterNaming, EpubConfig, EpubExporter, Exporter};
+use boko::{Book, Format};
+
+fn sample_book() -> Book {
+    use common::{Doc, EpubBuilder, Nav};
+
+    EpubBuilder::new("Naming")
+        .doc(Doc::new(
+            "text/intro.xhtml",
+            "Opening Words",
+            "<h1>Opening Words</h1><p>See <a href=\"part2.xhtml#later\">later</a>.</p>",
+        ))
+        .doc(Doc::new(
+            "text/part2.xhtml",
+            "The Journey",
+            "<h1>The Journey</h1><p id=\"later\">Here.</p>",
+        ))
+        .nav(vec![
+            Nav::new("Opening Words", "text/intro.xhtml"),
+            Nav::new("The Journey", "text/part2.xhtml"),
+        ])
+        .book()
+}
+
+fn export(book: &mut Book, config: EpubConfig) -> Vec<u8> {
+    let mut out = Cursor::new(Vec::new());
+    EpubExporter::new()
+        .with_config(config)
+        .export(book, &mut out)
+        .unwrap();
+    out.into_inner()
+}
+
+fn entries(epub: &[u8]) -> Vec<(String, String)> {
+    let mut zip = zip::ZipArchive::new(Cursor::new(epub)).unwrap();
+    (0..zip.len())
+        .map(|i| {
+            let mut entry = zip.by_index(i).unwrap();
+            let mut text = String::new();
+            let _ = entry.read_to_string(&mut text);
+            (entry.name().to_string(), text)
+        })
+        .collect()
+}
+
+fn entry<'a>(entries: &'a [(String, String)], suffix: &str) -> Option<&'a str> {
+    entries
+        .iter()
+        .find(|(name, _)| name.ends_with(suffix))
+        .map(|(_, text)| text.as_str())
+}
+
+#[test]
+fn sequential_names_number_chapters_and_rewrite_links() {
+    let mut book = sample_book();
+    let epub = export(
+        &mut book,
+        EpubConfig {
+            chapter_naming: Some(ChapterNaming::Sequential("ch".into())),
+            ..Default::default()
+        },
+    );
+    let entries = entries(&epub);
+    let names: Vec<&str> = entries.iter().map(|(name, _)| name.as_str()).collect();
+    assert!(
+        names.iter().any(|n| n.ends_with("text/ch-001.xhtml")),
+        "{names:?}"
+    );
+    assert!(
+        names.iter().any(|n| n.ends_with("text/ch-002.xhtml")),
+        "{names:?}"
+    );
+    assert!(
+        !names
+            .iter()
+            .any(|n| n.ends_with("intro.xhtml") || n.ends_with("part2.xhtml")),
+        "{names:?}"
+    );
+
+    let first = entry(&entries, "ch-001.xhtml").unwrap();
+    assert!(first.contains(r#"href="ch-002.xhtml#later""#), "{first}");
+    let nav = entry(&entries, "OEBPS/nav.xhtml").unwrap();
+    assert!(
+        nav.contains("ch-001.xhtml") && nav.contains("ch-002.xhtml"),
+        "{nav}"
+    );
+    let opf = entry(&entries, "content.opf").unwrap();
+    assert!(!opf.contains("part2.xhtml"), "{opf}");
+
+    // The renamed package reimports with a working link and TOC.
+    let reread = Book::from_bytes(&epub, Format::Epub).unwrap();
+    assert_eq!(reread.chapter_count(), 2);
+    assert!(
+        reread.toc()[1].href.ends_with("ch-002.xhtml"),
+        "{:?}",
+        reread.toc()
+    );
+    assert!(reread.validate().is_empty(), "{:?}", reread.validate());
+}
+
+#[test]
+fn normalized_export_names_chapters_from_titles() {
+    let mut book = sample_book();
+    let entries = entries(&export(
+        &mut book,
+        EpubConfig {
+            normalize: true,
+            chapter_naming: Some(ChapterNaming::FromTitle),
+            ..Default::default()
+        },
+    ));
+    let first = entry(&entries, "OEBPS/opening-words.xhtml").expect("titled first chapter");
+    assert!(first.contains("the-journey.xhtml#later"), "{first}");
+    assert!(entry(&entries, "OEBPS/the-journey.xhtml").is_some());
+    assert!(entry(&entries, "chapter_0.xhtml").is_none());
+}
+
+#[test]
+fn sequential_names_skip_existing_assets_and_unsafe_prefix_characters() {
+    use common::{Doc, EpubBuilder};
+
+    // An out-of-spine document already sits at the first generated name.
+    let mut book = EpubBuilder::new("Naming")
+        .doc(Doc::new("text/one.xhtml", "One", "<p>One</p>"))
+        .doc(Doc::new("text/two.xhtml", "Two", "<p>Two</p>"))
+        .image("text/ch-001.xhtml", b"<html/>".to_vec())
+        .book();
+    let entries = entries(&export(
+        &mut book,
+        EpubConfig {
+            chapter_naming: Some(ChapterNaming::Sequential("../ch".into())),
+            ..Default::default()
+        },
+    ));
+    let names: Vec<&str> = entries.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(entry(&entries, "text/ch-001.xhtml"), Some("<html/>"));
+    assert!(
+        names.iter().any(|n| n.ends_with("text/ch-001-2.xhtml")),
+        "{names:?}"
+    );
+    assert!(
+        names.iter().any(|n| n.ends_with("text/ch-002.xhtml")),
+        "{names:?}"
+    );
+    assert!(!names.iter().any(|n| n.contains("..")), "{names:?}");
+}
@@ -10,6 +10,12 @@ use std::io::{Cursor, Write};
 
 use boko::Book;
 use boko::export::Exporter;
+use boko::kfx::container::{
+    extract_doc_symbols, parse_container_header, parse_container_info, parse_index_table,
+    skip_enty_header,
+};
+use boko::kfx::ion::{IonParser, IonValue};
+use boko::kfx::symbols::{KFX_SYMBOL_TABLE, KfxSymbol};
 use boko::model::{Format, TocEntry};
 use zip::write::SimpleFileOptions;
 use zip::{CompressionMethod, ZipWriter};
@@ -48,6 +54,9 @@ pub fn export_to_bytes(book: &mut Book, format: Format) -> Vec<u8> {
         Format::Markdown => boko::export::MarkdownExporter::new()
             .export(book, &mut buf)
             .expect("markdown export"),
+        Format::Ir => boko::export::IrExporter::new()
+            .export(book, &mut buf)
+            .expect("ir export"),
         other => panic!("unsupported export format {other:?}"),
     }
     buf.into_inner()
@@ -60,6 +69,74 @@ pub fn roundtrip(book: &mut Book, format: Format) -> Book {
         .unwrap_or_else(|e| panic!("re-import {format:?} after export: {e}"))
 }
 
+// ---------------------------------------------------------------------------
+// KFX inspection
+// ---------------------------------------------------------------------------
+
+/// Import an EPUB and export it as KFX.
+pub fn epub_to_kfx(epub: &[u8]) -> Vec<u8> {
+    let mut book = Book::from_bytes(epub, Format::Epub).expect("import epub");
+    export_to_bytes(&mut book, Format::Kfx)
+}
+
+/// Parse every entity of the given fragment type into Ion values.
+pub fn kfx_entities(kfx: &[u8], fragment: KfxSymbol) -> Vec<IonValue> {
+    let header = parse_container_header(&kfx[..18]).expect("container header");
+    let info = parse_container_info(
+        &kfx[header.container_info_offset
+            ..header.container_info_offset + header.container_info_length],
+    )
+    .expect("container info");
+    let (index_offset, index_length) = info.index.expect("index table");
+    let entities = parse_index_table(
+        &kfx[index_offset..index_offset + index_length],
+        header.header_len,
+    );
+
+    entities
+        .iter()
+        .filter(|loc| loc.type_id == fragment as u32)
+        .filter_map(|loc| {
+            let entity = &kfx[loc.offset..loc.offset + loc.length];
+            IonParser::new(skip_enty_header(entity)).parse().ok()
+        })
+        .collect()
+}
+
+/// Document symbols (local symbol table) of the container.
+pub fn kfx_doc_symbols(kfx: &[u8]) -> Vec<String> {
+    let header = parse_container_header(&kfx[..18]).expect("container header");
+    let info = parse_container_info(
+        &kfx[header.container_info_offset
+            ..header.container_info_offset + header.container_info_length],
+    )
+    .expect("container info");
+    match info.doc_symbols {
+        Some((off, len)) if len > 0 => extract_doc_symbols(&kfx[off..off + len]),
+        _ => Vec::new(),
+    }
+}
+
+/// Name of symbol `id`: a shared KFX symbol or one of `doc_symbols`.
+pub fn kfx_symbol_name(doc_symbols: &[String], id: u64) -> String {
+    let base = KFX_SYMBOL_TABLE.len() as u64;
+    if id < base {
+        KFX_SYMBOL_TABLE[id as usize].to_string()
+    } else {
+        doc_symbols
+            .get((id - base) as usize)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// The value of field `sym` in an Ion struct's fields.
+pub fn ion_field(fields: &[(u64, IonValue)], sym: KfxSymbol) -> Option<&IonValue> {
+    fields
+        .iter()
+        .find_map(|(k, v)| (*k == sym as u64).then_some(v))
+}
+
 // ---------------------------------------------------------------------------
 // TOC helpers
 // ---------------------------------------------------------------------------
@@ -215,6 +292,7 @@ pub struct Doc {
     pub body: String,
     pub lang: Option<String>,
     pub dir: Option<String>,
+    pub head: String,
 }
 
 impl Doc {
@@ -225,6 +303,7 @@ impl Doc {
             body: body.into(),
             lang: None,
             dir: None,
+            head: String::new(),
         }
     }
     /// Set the document language and text direction (e.g. `"ar"`, `"rtl"`).
@@ -233,6 +312,11 @@ impl Doc {
         self.dir = Some(dir.into());
         self
     }
+    /// Append raw XHTML to `<head>` (e.g. a viewport `<meta>`).
+    pub fn head(mut self, xml: &str) -> Self {
+        self.head.push_str(xml);
+        self
+    }
 }
 
 /// Builds a valid EPUB 3 (with NCX) as an in-memory zip. Layout mirrors what the
@@ -248,6 +332,8 @@ pub struct EpubBuilder {
     images: Vec<(String, Vec<u8>)>,
     cover: Option<String>,
     direction: Option<String>,
+    package_prefix: Option<String>,
+    extra_metadata: String,
 }
 
 impl EpubBuilder {
@@ -263,6 +349,8 @@ impl EpubBuilder {
             images: Vec::new(),
             cover: None,
             direction: None,
+            package_prefix: None,
+            extra_metadata: String::new(),
         }
     }
 
@@ -282,6 +370,20 @@ impl EpubBuilder {
         self
     }
 
+    /// Set the package `prefix` attribute (e.g. `"foo: http://example.com/foo#"`).
+    pub fn package_prefix(mut self, prefix: &str) -> Self {
+        self.package_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Append raw XML (one or more elements) to the OPF `<metadata>`.
+    pub fn metadata_xml(mut self, xml: &str) -> Self {
+        self.extra_metadata.push_str("    ");
+        self.extra_metadata.push_str(xml);
+        self.extra_metadata.push('\n');
+        self
+    }
+
     pub fn doc(mut self, doc: Doc) -> Self {
         self.docs.push(doc);
         self
@@ -395,19 +497,25 @@ impl EpubBuilder {
 
         format!(
             r#"<?xml version="1.0" encoding="utf-8"?>
-<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="bookid">
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="bookid"{prefix}>
   <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
     <dc:identifier id="bookid">{identifier}</dc:identifier>
     <dc:title>{title}</dc:title>
     <dc:language>{language}</dc:language>
     <dc:creator>{author}</dc:creator>
-  </metadata>
+{extra}  </metadata>
   <manifest>
 {manifest}  </manifest>
   <spine toc="ncx"{spine_dir}>
 {spine}  </spine>
 </package>
 "#,
+            prefix = self
+                .package_prefix
+                .as_deref()
+                .map(|p| format!(" prefix=\"{}\"", xml_escape(p)))
+                .unwrap_or_default(),
+            extra = self.extra_metadata,
             spine_dir = self
                 .direction
                 .as_deref()
@@ -483,13 +591,14 @@ impl EpubBuilder {
             r#"<?xml version="1.0" encoding="utf-8"?>
 <!DOCTYPE html>
 <html xmlns="http://www.w3.org/1999/xhtml"{lang}{dir}>
-<head><title>{title}</title>{css_link}</head>
+<head><title>{title}</title>{css_link}{head}</head>
 <body>
 {body}
 </body>
 </html>
 "#,
             title = xml_escape(&doc.title),
+            head = doc.head,
             body = doc.body,
         )
     }
@@ -535,6 +644,10 @@ fn media_type_for(file: &str) -> &'static str {
         "image/gif"
     } else if lower.ends_with(".svg") {
         "image/svg+xml"
+    } else if lower.ends_with(".ttf") {
+        "font/ttf"
+    } else if lower.ends_with(".mp3") {
+        "audio/mpeg"
     } else {
         "application/octet-stream"
     }
@@ -0,0 +1,134 @@
+//! Unmodeled OPF metadata (`dc:coverage`, custom `<meta property>`,
+//! `calibre:*` metas) is kept on `Metadata::custom` and written back out on
+//! EPUB export.
+
+mod common;
+
+use std::io::{Cursor, Read};
+
+use boko::export::{EpubConfig, EpubExporter, EpubVersion, Exporter};
+use boko::{Book, Format};
+
+fn sample_book() -> Book {
+    use common::{Doc, EpubBuilder, Nav};
+
+    EpubBuilder::new("Custom Meta")
+        .package_prefix("foo: http://example.com/foo#")
+        .metadata_xml("<dc:coverage>Edo-period Japan</dc:coverage>")
+        .metadata_xml(r#"<meta property="foo:bar">shelf &amp; row 3</meta>"#)
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>x</p>"))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book()
+}
+
+fn export(book: &Book, config: EpubConfig) -> Vec<u8> {
+    let mut out = Cursor::new(Vec::new());
+    EpubExporter::new()
+        .with_config(config)
+        .export(book, &mut out)
+        .expect("export epub");
+    out.into_inner()
+}
+
+fn opf(epub: &[u8]) -> String {
+    let mut archive = zip::ZipArchive::new(Cursor::new(epub)).expect("valid zip");
+    let mut text = String::new();
+    archive
+        .by_name("OEBPS/content.opf")
+        .unwrap()
+        .read_to_string(&mut text)
+        .unwrap();
+    text
+}
+
+fn expected() -> Vec<(String, String)> {
+    vec![
+        ("dc:coverage".into(), "Edo-period Japan".into()),
+        ("foo:bar".into(), "shelf & row 3".into()),
+    ]
+}
+
+#[test]
+fn custom_meta_round_trips_through_epub() {
+    let book = sample_book();
+    assert_eq!(book.metadata().custom, expected());
+
+    for normalize in [false, true] {
+        let epub = export(
+            &book,
+            EpubConfig {
+                normalize,
+                ..Default::default()
+            },
+        );
+        let opf = opf(&epub);
+        assert!(
+            opf.contains(r#"prefix="foo: http://example.com/foo#""#),
+            "{opf}"
+        );
+        let reread = Book::from_bytes(&epub, Format::Epub).expect("reimport");
+        assert_eq!(reread.metadata().custom, expected(), "{opf}");
+    }
+}
+
+#[test]
+fn epub2_writes_custom_properties_as_name_content_metas() {
+    let epub = export(
+        &sample_book(),
+        EpubConfig {
+            version: EpubVersion::Epub2,
+            ..Default::default()
+        },
+    );
+    let opf = opf(&epub);
+    assert!(
+        opf.contains("<dc:coverage>Edo-period Japan</dc:coverage>"),
+        "{opf}"
+    );
+    assert!(
+        opf.contains(r#"<meta name="foo:bar" content="shelf &amp; row 3"/>"#),
+        "{opf}"
+    );
+    assert!(!opf.contains("prefix="), "{opf}");
+}
+
+#[test]
+fn calibre_rating_and_metas_round_trip_through_epub() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let book = EpubBuilder::new("Calibre")
+        .metadata_xml(r#"<meta name="calibre:rating" content="6"/>"#)
+        .metadata_xml(r#"<meta name="calibre:timestamp" content="2019-03-02T10:00:00+02:00"/>"#)
+        .metadata_xml(r#"<meta name="calibre:user_categories" content="{}"/>"#)
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>x</p>"))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+    let custom = vec![("calibre:user_categories".to_string(), "{}".to_string())];
+    assert_eq!(book.metadata().rating, Some(6.0));
+    assert_eq!(book.metadata().custom, custom);
+
+    for version in [EpubVersion::Epub3, EpubVersion::Epub2] {
+        let epub = export(
+            &book,
+            EpubConfig {
+                version,
+                ..Default::default()
+            },
+        );
+        let opf = opf(&epub);
+        assert!(
+            opf.contains(r#"<meta name="calibre:rating" content="6"/>"#),
+            "{opf}"
+        );
+        if version == EpubVersion::Epub3 {
+            // Calibre's offset timestamp becomes a valid dcterms:modified.
+            assert!(
+                opf.contains(r#"<meta property="dcterms:modified">2019-03-02T08:00:00Z</meta>"#),
+                "{opf}"
+            );
+        }
+        let reread = Book::from_bytes(&epub, Format::Epub).expect("reimport");
+        assert_eq!(reread.metadata().rating, Some(6.0), "{opf}");
+        assert_eq!(reread.metadata().custom, custom, "{opf}");
+    }
+}
@@ -0,0 +1,69 @@
+//! `Book::diff_text` reports text changes between two books as a unified diff.
+
+mod common;
+
+use boko::{Book, Format};
+use common::{Doc, EpubBuilder, Nav};
+
+fn book(second_sentence: &str) -> Book {
+    let body = format!(
+        "<h1>One</h1><p>The first paragraph.</p><p>{second_sentence}</p><p>The <em>last</em> one.</p>"
+    );
+    EpubBuilder::new("Diffed")
+        .doc(Doc::new("text/ch1.xhtml", "One", &body))
+        .doc(Doc::new("text/ch2.xhtml", "Two", "<p>Untouched.</p>"))
+        .nav(vec![
+            Nav::new("One", "text/ch1.xhtml"),
+            Nav::new("Two", "text/ch2.xhtml"),
+        ])
+        .book()
+}
+
+#[test]
+fn diff_shows_the_edited_sentence() {
+    let original = book("It was a dark and stormy night.");
+    let edited = book("It was a bright and sunny day.");
+
+    let diff = original.diff_text(&edited);
+    assert!(diff.contains("--- a/"), "{diff}");
+    assert!(diff.contains("ch1.xhtml"), "{diff}");
+    assert!(!diff.contains("ch2.xhtml"), "{diff}");
+    assert!(
+        diff.contains("\n-It was a dark and stormy night.\n"),
+        "{diff}"
+    );
+    assert!(
+        diff.contains("\n+It was a bright and sunny day.\n"),
+        "{diff}"
+    );
+    assert!(diff.contains("\n The first paragraph.\n"), "{diff}");
+    assert!(diff.contains("\n The last one.\n"), "{diff}");
+}
+
+#[test]
+fn identical_text_survives_conversion() {
+    let mut original = book("It was a dark and stormy night.");
+    let converted = common::roundtrip(&mut original, Format::Epub);
+    assert_eq!(original.diff_text(&converted), "");
+}
+
+#[test]
+fn chapters_pair_by_source_path_across_spine_shifts() {
+    let original = book("It was a dark and stormy night.");
+    let body = "<h1>One</h1><p>The first paragraph.</p>\
+                <p>It was a dark and stormy night.</p><p>The <em>last</em> one.</p>";
+    let extended = EpubBuilder::new("Diffed")
+        .doc(Doc::new("text/preface.xhtml", "Preface", "<p>Added.</p>"))
+        .doc(Doc::new("text/ch1.xhtml", "One", body))
+        .doc(Doc::new("text/ch2.xhtml", "Two", "<p>Untouched.</p>"))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+
+    // Only the inserted chapter differs; the shifted ones still line up.
+    let diff = original.diff_text(&extended);
+    assert!(diff.starts_with("--- /dev/null\n+++ b/"), "{diff}");
+    assert!(diff.contains("preface.xhtml"), "{diff}");
+    assert!(diff.contains("\n+Added.\n"), "{diff}");
+    assert!(!diff.contains("ch1.xhtml"), "{diff}");
+    assert_eq!(diff.matches("--- ").count(), 1, "{diff}");
+}
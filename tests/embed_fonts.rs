@@ -0,0 +1,107 @@
+//! `convert --embed-fonts` / `Book::embed_fonts`: dropping or subsetting
+//! embedded fonts for every output format.
+
+mod common;
+
+use std::io::{Cursor, Read};
+use std::process::Command;
+
+use boko::optimize::FontEmbedding;
+use boko::{Book, Format};
+use common::{Doc, EpubBuilder, Nav};
+
+/// Not a parseable font, just TrueType magic and filler.
+fn fake_font() -> Vec<u8> {
+    let mut font = vec![0x00, 0x01, 0x00, 0x00];
+    font.extend((0..2000u32).map(|i| (i % 251) as u8));
+    font
+}
+
+fn font_epub() -> Vec<u8> {
+    EpubBuilder::new("Font Book")
+        .css(
+            "@font-face { font-family: \"Body\"; src: url(../fonts/body.ttf); }\n\
+             p { font-family: \"Body\", serif; color: #333; }",
+        )
+        .image("fonts/body.ttf", fake_font())
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<h1>One</h1><p>Typeset in a custom face.</p>",
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build()
+}
+
+/// Every entry of a ZIP archive, by name.
+fn zip_entries(bytes: &[u8]) -> Vec<(String, Vec<u8>)> {
+    let mut zip = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+    (0..zip.len())
+        .map(|i| {
+            let mut entry = zip.by_index(i).unwrap();
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data).unwrap();
+            (entry.name().to_string(), data)
+        })
+        .collect()
+}
+
+#[test]
+fn embed_fonts_none_drops_fonts_and_font_face_rules() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("in.epub");
+    let output = dir.path().join("out.epub");
+    std::fs::write(&input, font_epub()).unwrap();
+
+    let status = Command::new(env!("CARGO_BIN_EXE_boko"))
+        .args(["convert", "--quiet", "--embed-fonts=none"])
+        .arg(&input)
+        .arg(&output)
+        .status()
+        .expect("failed to run boko convert");
+    assert!(status.success());
+
+    let entries = zip_entries(&std::fs::read(&output).unwrap());
+    assert!(
+        !entries.iter().any(|(name, _)| name.ends_with(".ttf")),
+        "font file still present"
+    );
+    for (name, data) in &entries {
+        let text = String::from_utf8_lossy(data);
+        assert!(!text.contains("@font-face"), "{name}: {text}");
+        assert!(!text.contains("body.ttf"), "{name}: {text}");
+    }
+    // The rest of the stylesheet survives.
+    let css = entries
+        .iter()
+        .find(|(name, _)| name.ends_with(".css"))
+        .expect("stylesheet");
+    assert!(String::from_utf8_lossy(&css.1).contains("color: #333"));
+}
+
+#[test]
+fn embed_fonts_none_applies_to_kfx_export() {
+    let mut book = Book::from_bytes(&font_epub(), Format::Epub).unwrap();
+    assert_eq!(book.font_faces().len(), 1);
+
+    let report = book.embed_fonts(FontEmbedding::None);
+    assert_eq!(report.assets_changed, 1);
+    assert!(book.font_faces().is_empty());
+    assert!(!book.list_assets().iter().any(|a| a.ends_with(".ttf")));
+
+    let kfx = common::roundtrip(&mut book, Format::Kfx);
+    assert!(kfx.font_faces().is_empty());
+    assert!(!kfx.list_assets().iter().any(|a| a.starts_with("fonts/")));
+}
+
+#[test]
+fn embed_fonts_subset_keeps_fonts_it_cannot_parse() {
+    let mut book = Book::from_bytes(&font_epub(), Format::Epub).unwrap();
+    let report = book.embed_fonts(FontEmbedding::Subset);
+    assert_eq!(report.assets_changed, 0);
+    assert_eq!(
+        book.load_asset("OEBPS/fonts/body.ttf").unwrap(),
+        fake_font()
+    );
+    assert_eq!(book.font_faces().len(), 1);
+}
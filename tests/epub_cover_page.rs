@@ -0,0 +1,147 @@
+//! EPUB export adds a cover page when the source has a cover image but no
+//! page showing it, in normalized and passthrough mode alike.
+
+mod common;
+
+use std::io::{Cursor, Read};
+
+use boko::export::{EpubConfig, EpubExporter, Exporter};
+use boko::model::LandmarkType;
+use boko::{Book, Format};
+use common::{Doc, EpubBuilder, Nav};
+
+fn normalized_epub(book: &Book, low_memory: bool) -> Vec<u8> {
+    let mut out = Cursor::new(Vec::new());
+    EpubExporter::new()
+        .with_config(EpubConfig {
+            normalize: true,
+            low_memory,
+            ..Default::default()
+        })
+        .export(book, &mut out)
+        .unwrap();
+    out.into_inner()
+}
+
+fn entry(epub: &[u8], name: &str) -> Option<String> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(epub)).unwrap();
+    let mut file = archive.by_name(name).ok()?;
+    let mut text = String::new();
+    file.read_to_string(&mut text).unwrap();
+    Some(text)
+}
+
+#[test]
+fn cover_image_without_a_page_gets_one() {
+    let book = EpubBuilder::new("Covered")
+        .cover_png()
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<p>Once upon a time.</p>",
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+
+    for low_memory in [false, true] {
+        let epub = normalized_epub(&book, low_memory);
+        let page = entry(&epub, "OEBPS/cover.xhtml").expect("cover page written");
+        assert!(page.contains(r#"<body epub:type="cover">"#), "{page}");
+        let src = page
+            .split(r#"<img src=""#)
+            .nth(1)
+            .and_then(|rest| rest.split('"').next())
+            .expect("cover page shows an image");
+        assert!(src.ends_with("images/cover.png"), "{page}");
+        let mut archive = zip::ZipArchive::new(Cursor::new(&epub)).unwrap();
+        assert!(archive.by_name(&format!("OEBPS/{src}")).is_ok(), "{src}");
+
+        let out = Book::from_bytes(&epub, Format::Epub).unwrap();
+        assert_eq!(out.source_id(out.spine()[0].id), Some("OEBPS/cover.xhtml"));
+        assert_eq!(out.spine().len(), 2);
+        assert!(
+            out.landmarks().iter().any(|lm| {
+                lm.landmark_type == LandmarkType::Cover && lm.href.ends_with("cover.xhtml")
+            }),
+            "{:?}",
+            out.landmarks()
+        );
+        assert!(out.metadata().cover_image.is_some());
+    }
+}
+
+#[test]
+fn existing_cover_page_is_kept_as_is() {
+    let book = EpubBuilder::new("Covered")
+        .cover_png()
+        .doc(Doc::new(
+            "text/titlepage.xhtml",
+            "Cover",
+            r#"<div><img src="../images/cover.png" alt="Cover"/></div>"#,
+        ))
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<p>Once upon a time.</p>",
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+
+    let epub = normalized_epub(&book, false);
+    assert!(entry(&epub, "OEBPS/cover.xhtml").is_none());
+    let out = Book::from_bytes(&epub, Format::Epub).unwrap();
+    assert_eq!(out.spine().len(), 2);
+}
+
+#[test]
+fn passthrough_mobi_conversion_gets_a_cover_page() {
+    let mut book = common::open_fixture("epictetus.mobi");
+    let cover = book
+        .metadata()
+        .cover_image
+        .clone()
+        .expect("mobi has a cover");
+    let epub = common::export_to_bytes(&mut book, Format::Epub);
+
+    let page = entry(&epub, "OEBPS/cover.xhtml").expect("cover page written");
+    assert!(page.contains(r#"<body epub:type="cover">"#), "{page}");
+    let src = page
+        .split(r#"<img src=""#)
+        .nth(1)
+        .and_then(|rest| rest.split('"').next())
+        .expect("cover page shows an image");
+    assert!(src.ends_with(&cover), "{src} vs {cover}");
+    let mut archive = zip::ZipArchive::new(Cursor::new(&epub)).unwrap();
+    assert!(archive.by_name(&format!("OEBPS/{src}")).is_ok(), "{src}");
+
+    let out = Book::from_bytes(&epub, Format::Epub).unwrap();
+    assert_eq!(out.source_id(out.spine()[0].id), Some("OEBPS/cover.xhtml"));
+    assert_eq!(out.spine().len(), book.spine().len() + 1);
+    assert!(
+        out.landmarks()
+            .iter()
+            .any(|lm| lm.landmark_type == LandmarkType::Cover && lm.href.ends_with("cover.xhtml")),
+        "{:?}",
+        out.landmarks()
+    );
+}
+
+#[test]
+fn passthrough_epub_with_a_cover_page_is_left_alone() {
+    let mut book = EpubBuilder::new("Covered")
+        .cover_png()
+        .doc(Doc::new(
+            "text/titlepage.xhtml",
+            "Cover",
+            r#"<div><img src="../images/cover.png" alt="Cover"/></div>"#,
+        ))
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>x</p>"))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+    let epub = common::export_to_bytes(&mut book, Format::Epub);
+    assert!(entry(&epub, "OEBPS/cover.xhtml").is_none());
+    assert_eq!(
+        Book::from_bytes(&epub, Format::Epub).unwrap().spine().len(),
+        2
+    );
+}
@@ -0,0 +1,78 @@
+//! `EpubImporter::from_bytes` reads an EPUB held in memory in place.
+
+mod common;
+
+use std::sync::Arc;
+
+use boko::Importer;
+use boko::import::EpubImporter;
+
+fn small_epub() -> Vec<u8> {
+    use common::{Doc, EpubBuilder, Nav};
+
+    EpubBuilder::new("In Memory")
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>Held in RAM.</p>"))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build()
+}
+
+fn assert_readable(importer: &EpubImporter) {
+    assert_eq!(importer.metadata().title, "In Memory");
+    let id = importer.spine()[0].id;
+    let raw = String::from_utf8(importer.load_raw(id).unwrap()).unwrap();
+    assert!(raw.contains("Held in RAM."), "{raw}");
+    let chapter = importer.load_chapter(id).unwrap();
+    assert!(chapter.text_buffer().contains("Held in RAM."));
+}
+
+#[test]
+fn epub_opens_from_owned_shared_and_static_bytes() {
+    let epub = small_epub();
+
+    let shared: Arc<[u8]> = epub.as_slice().into();
+    assert_readable(&EpubImporter::from_bytes(shared.clone()).expect("Arc<[u8]>"));
+    assert_readable(&EpubImporter::from_bytes(epub.clone()).expect("Vec<u8>"));
+    let leaked: &'static [u8] = epub.leak();
+    assert_readable(&EpubImporter::from_bytes(leaked).expect("&'static [u8]"));
+}
+
+#[test]
+fn truncated_bytes_are_an_error() {
+    let epub = small_epub();
+    assert!(EpubImporter::from_bytes(epub[..epub.len() / 2].to_vec()).is_err());
+}
+
+/// Shift every offset the central directory records by `by` bytes, as
+/// self-extractor tools (`zip -A`) do after prepending their stub.
+fn adjust_offsets(zip: &mut [u8], by: u32) {
+    let u16_at = |d: &[u8], i: usize| u16::from_le_bytes([d[i], d[i + 1]]) as usize;
+    let add = |d: &mut [u8], i: usize| {
+        let value = u32::from_le_bytes(d[i..i + 4].try_into().unwrap()) + by;
+        d[i..i + 4].copy_from_slice(&value.to_le_bytes());
+    };
+    let eocd = zip.windows(4).rposition(|w| w == b"PK\x05\x06").unwrap();
+    let mut entry = u32::from_le_bytes(zip[eocd + 16..eocd + 20].try_into().unwrap()) as usize;
+    add(zip, eocd + 16);
+    for _ in 0..u16_at(zip, eocd + 10) {
+        assert_eq!(&zip[entry..entry + 4], b"PK\x01\x02");
+        add(zip, entry + 42);
+        entry += 46 + u16_at(zip, entry + 28) + u16_at(zip, entry + 30) + u16_at(zip, entry + 32);
+    }
+}
+
+#[test]
+fn epub_behind_a_leading_stub_opens() {
+    let stub = b"MZ\x90\x00 self-extracting archive stub ".repeat(64);
+    let mut adjusted = small_epub();
+    adjust_offsets(&mut adjusted, stub.len() as u32);
+
+    let dir = tempfile::tempdir().unwrap();
+    for (name, zip) in [("prefixed.epub", small_epub()), ("sfx.epub", adjusted)] {
+        let path = dir.path().join(name);
+        std::fs::write(&path, [stub.as_slice(), &zip].concat()).unwrap();
+        let book = boko::Book::open(&path).expect(name);
+        assert_eq!(book.metadata().title, "In Memory", "{name}");
+        let chapter = book.load_chapter(book.spine()[0].id).unwrap();
+        assert!(chapter.text_buffer().contains("Held in RAM."), "{name}");
+    }
+}
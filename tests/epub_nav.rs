@@ -0,0 +1,169 @@
+//! The EPUB 3 nav document written on export: TOC structure and landmarks.
+
+mod common;
+
+use std::io::{Cursor, Read};
+
+use boko::Format;
+use boko::export::{EpubConfig, EpubExporter, Exporter};
+use boko::model::LandmarkType;
+
+fn sample_book() -> boko::Book {
+    use common::{Doc, EpubBuilder, Nav};
+
+    EpubBuilder::new("Nav Book")
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>first</p>"))
+        .doc(Doc::new(
+            "text/ch2.xhtml",
+            "Two",
+            r#"<h2 id="a">A</h2><p>a</p><h2 id="b">B</h2><p>b</p>"#,
+        ))
+        .nav(vec![
+            Nav::new("One", "text/ch1.xhtml"),
+            Nav::new("Two", "text/ch2.xhtml").with_children(vec![
+                Nav::new("Two A", "text/ch2.xhtml#a"),
+                Nav::new("Two B", "text/ch2.xhtml#b"),
+            ]),
+        ])
+        .book()
+}
+
+fn read_entry(epub: &[u8], name: &str) -> String {
+    let mut archive = zip::ZipArchive::new(Cursor::new(epub)).expect("valid zip");
+    let mut text = String::new();
+    archive
+        .by_name(name)
+        .unwrap_or_else(|_| panic!("{name} present"))
+        .read_to_string(&mut text)
+        .unwrap();
+    text
+}
+
+/// The archive path of the manifest item carrying `properties="nav"`.
+fn nav_item_path(opf: &str) -> String {
+    let items: Vec<&str> = opf
+        .split("<item ")
+        .skip(1)
+        .filter(|item| item.contains(r#"properties="nav""#))
+        .collect();
+    assert_eq!(items.len(), 1, "exactly one nav item: {opf}");
+    let href = items[0].split(r#"href=""#).nth(1).unwrap();
+    format!("OEBPS/{}", &href[..href.find('"').unwrap()])
+}
+
+#[test]
+fn nav_document_carries_nested_toc_and_landmarks() {
+    let book = sample_book();
+    for normalize in [false, true] {
+        let mut out = Cursor::new(Vec::new());
+        EpubExporter::new()
+            .with_config(EpubConfig {
+                normalize,
+                ..Default::default()
+            })
+            .export(&book, &mut out)
+            .expect("export epub");
+        let epub = out.into_inner();
+
+        let opf = read_entry(&epub, "OEBPS/content.opf");
+        let nav = read_entry(&epub, &nav_item_path(&opf));
+        assert!(nav.contains(r#"<nav epub:type="toc""#), "{nav}");
+        // One top-level list plus the nested one under "Two".
+        let toc = &nav[..nav.find(r#"epub:type="landmarks""#).expect("landmarks nav")];
+        assert_eq!(toc.matches("<ol>").count(), 2, "{nav}");
+        assert_eq!(toc.matches("<li>").count(), 4, "{nav}");
+        assert!(nav.contains(r#"<a epub:type="bodymatter" href=""#), "{nav}");
+
+        // The nav reads back with the same TOC and landmark.
+        let reread = boko::Book::from_bytes(&epub, Format::Epub).expect("reimport");
+        let titles: Vec<_> = reread.toc().iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, ["One", "Two"], "normalize: {normalize}");
+        assert_eq!(reread.toc()[1].children.len(), 2);
+        let landmark = reread
+            .landmarks()
+            .iter()
+            .find(|lm| lm.landmark_type == LandmarkType::BodyMatter)
+            .expect("bodymatter landmark");
+        let file = landmark.href.split('#').next().unwrap();
+        assert!(
+            reread
+                .spine()
+                .iter()
+                .any(|e| reread.source_id(e.id) == Some(file)),
+            "landmark {} targets a spine document (normalize: {normalize})",
+            landmark.href
+        );
+    }
+}
+
+/// `epub` with the entry `name` passed through `edit`.
+fn rewrite_entry(epub: &[u8], name: &str, edit: impl Fn(String) -> String) -> Vec<u8> {
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(epub)).unwrap();
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let stored = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).unwrap();
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).unwrap();
+        if entry.name() == name {
+            data = edit(String::from_utf8(data).unwrap()).into_bytes();
+        }
+        zip.start_file(entry.name(), stored).unwrap();
+        zip.write_all(&data).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
+
+#[test]
+fn nav_gains_a_bodymatter_landmark_past_the_front_matter() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let source = EpubBuilder::new("No Start")
+        .doc(Doc::new("text/cover.xhtml", "Cover", "<p>Cover</p>"))
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>first</p>"))
+        .nav(vec![
+            Nav::new("Cover", "text/cover.xhtml"),
+            Nav::new("One", "text/ch1.xhtml"),
+        ])
+        .build();
+    // The source's only landmark is its cover.
+    let source = rewrite_entry(&source, "OEBPS/nav.xhtml", |nav| {
+        nav.replace("epub:type=\"bodymatter\"", "epub:type=\"cover\"")
+    });
+    let book = boko::Book::from_bytes(&source, Format::Epub).unwrap();
+    let types: Vec<_> = book.landmarks().iter().map(|lm| lm.landmark_type).collect();
+    assert_eq!(types, [LandmarkType::Cover]);
+
+    for normalize in [false, true] {
+        let mut out = Cursor::new(Vec::new());
+        EpubExporter::new()
+            .with_config(EpubConfig {
+                normalize,
+                ..Default::default()
+            })
+            .export(&book, &mut out)
+            .expect("export epub");
+        let epub = out.into_inner();
+        let opf = read_entry(&epub, "OEBPS/content.opf");
+        let nav = read_entry(&epub, &nav_item_path(&opf));
+        assert!(nav.contains(r#"<a epub:type="bodymatter" href=""#), "{nav}");
+
+        let reread = boko::Book::from_bytes(&epub, Format::Epub).unwrap();
+        let start = reread
+            .landmarks()
+            .iter()
+            .find(|lm| lm.landmark_type == LandmarkType::BodyMatter)
+            .expect("bodymatter landmark");
+        let file = start.href.split('#').next().unwrap();
+        let chapter = reread
+            .spine()
+            .iter()
+            .find(|e| reread.source_id(e.id) == Some(file))
+            .expect("bodymatter targets a spine document");
+        let html = String::from_utf8(reread.load_raw(chapter.id).unwrap()).unwrap();
+        assert!(html.contains("first"), "normalize {normalize}: {html}");
+    }
+}
@@ -0,0 +1,128 @@
+//! Password-protected EPUBs: entries under traditional ZIP encryption.
+
+mod common;
+
+use std::io::{Cursor, Read, Write};
+
+use boko::{Book, Format};
+use common::{Doc, EpubBuilder, Nav};
+use zip::write::SimpleFileOptions;
+
+const PASSWORD: &str = "review-copy";
+
+fn plain_epub() -> Vec<u8> {
+    EpubBuilder::new("Advance Copy")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<p>Embargoed until publication.</p>",
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build()
+}
+
+/// Re-zip `epub` with every entry but `mimetype` encrypted by `encrypt`.
+fn encrypt_entries(
+    epub: &[u8],
+    encrypt: impl Fn(SimpleFileOptions) -> SimpleFileOptions,
+) -> Vec<u8> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(epub)).unwrap();
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).unwrap();
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).unwrap();
+        let options = SimpleFileOptions::default().compression_method(entry.compression());
+        let options = if entry.name() == "mimetype" {
+            options
+        } else {
+            encrypt(options)
+        };
+        zip.start_file(entry.name(), options).unwrap();
+        zip.write_all(&data).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
+
+fn write_epub(dir: &tempfile::TempDir, data: &[u8]) -> std::path::PathBuf {
+    let path = dir.path().join("advance.epub");
+    std::fs::write(&path, data).unwrap();
+    path
+}
+
+fn assert_readable(book: &mut Book) {
+    assert_eq!(book.metadata().title, "Advance Copy");
+    assert_eq!(book.toc()[0].title, "One");
+    let text = String::from_utf8(common::export_to_bytes(book, Format::Markdown)).unwrap();
+    assert!(text.contains("Embargoed until publication."), "{text}");
+}
+
+#[test]
+fn zipcrypto_epub_opens_with_its_password() {
+    use zip::unstable::write::FileOptionsExt;
+
+    let epub = encrypt_entries(&plain_epub(), |options| {
+        options
+            .with_deprecated_encryption(PASSWORD.as_bytes())
+            .unwrap()
+    });
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_epub(&dir, &epub);
+
+    let mut book = Book::open_with_password(&path, PASSWORD).expect("decrypts");
+    assert_readable(&mut book);
+
+    assert!(matches!(
+        Book::open(&path),
+        Err(boko::Error::PasswordRequired(Format::Epub))
+    ));
+    assert!(matches!(
+        Book::open_with_password(&path, "not-it"),
+        Err(boko::Error::WrongPassword(Format::Epub))
+    ));
+}
+
+#[cfg(feature = "zip-aes")]
+#[test]
+fn aes_epub_opens_with_its_password() {
+    let epub = encrypt_entries(&plain_epub(), |options| {
+        options.with_aes_encryption(zip::AesMode::Aes256, PASSWORD)
+    });
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_epub(&dir, &epub);
+
+    let mut book = Book::open_with_password(&path, PASSWORD).expect("decrypts");
+    assert_readable(&mut book);
+    assert!(matches!(
+        Book::open_with_password(&path, "not-it"),
+        Err(boko::Error::WrongPassword(Format::Epub))
+    ));
+}
+
+#[test]
+fn unencrypted_epub_ignores_the_password() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_epub(&dir, &plain_epub());
+    let mut book = Book::open_with_password(&path, PASSWORD).unwrap();
+    assert_readable(&mut book);
+}
+
+#[test]
+fn gzipped_encrypted_epub_opens_with_its_password() {
+    use flate2::{Compression, write::GzEncoder};
+    use zip::unstable::write::FileOptionsExt;
+
+    let epub = encrypt_entries(&plain_epub(), |options| {
+        options
+            .with_deprecated_encryption(PASSWORD.as_bytes())
+            .unwrap()
+    });
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&epub).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("advance.epub.gz");
+    std::fs::write(&path, encoder.finish().unwrap()).unwrap();
+
+    let mut book = Book::open_with_password(&path, PASSWORD).expect("decrypts");
+    assert_readable(&mut book);
+}
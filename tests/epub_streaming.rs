@@ -0,0 +1,116 @@
+//! EPUB export to non-seekable writers (`EpubExporter::export_stream`).
+
+mod common;
+
+use std::io::{Cursor, Read, Write};
+
+use boko::export::{EpubConfig, EpubExporter, Exporter};
+
+/// A `Write` that can't seek and records how much arrived per call.
+#[derive(Default)]
+struct CountingWriter {
+    bytes: Vec<u8>,
+    writes: usize,
+    largest_write: usize,
+}
+
+impl Write for CountingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writes += 1;
+        self.largest_write = self.largest_write.max(buf.len());
+        self.bytes.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A book with several chapters and an incompressible 256 KiB asset.
+fn illustrated_book() -> boko::Book {
+    use common::{Doc, EpubBuilder, Nav};
+
+    // xorshift noise: deflate can't shrink it, so it dominates the output.
+    let mut state = 0x2545_f491_u32;
+    let noise: Vec<u8> = (0..256 * 1024)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as u8
+        })
+        .collect();
+
+    let mut builder = EpubBuilder::new("Streaming Book").image("images/plate.bin", noise);
+    let mut nav = Vec::new();
+    for i in 0..5 {
+        let file = format!("text/ch{i}.xhtml");
+        builder = builder.doc(Doc::new(&file, "Chapter", &format!("<p>chapter {i}</p>")));
+        nav.push(Nav::new("Chapter", &file));
+    }
+    let epub = builder.nav(nav).build();
+    boko::Book::from_bytes(&epub, boko::Format::Epub).expect("import epub")
+}
+
+fn entry_names(epub: Vec<u8>) -> Vec<String> {
+    let mut zip = zip::ZipArchive::new(Cursor::new(epub)).expect("valid zip");
+    (0..zip.len())
+        .map(|i| zip.by_index(i).unwrap().name().to_string())
+        .collect()
+}
+
+#[test]
+fn stream_export_matches_seekable_export_entries() {
+    let book = illustrated_book();
+
+    let mut seekable = Cursor::new(Vec::new());
+    EpubExporter::new().export(&book, &mut seekable).unwrap();
+    let streamed = EpubExporter::new()
+        .export_stream(&book, Cursor::new(Vec::new()))
+        .unwrap()
+        .into_inner();
+
+    let names = entry_names(streamed.clone());
+    assert_eq!(names, entry_names(seekable.into_inner()));
+    assert_eq!(names.first().map(String::as_str), Some("mimetype"));
+
+    let mut zip = zip::ZipArchive::new(Cursor::new(streamed)).unwrap();
+    let mut mimetype = String::new();
+    zip.by_name("mimetype")
+        .unwrap()
+        .read_to_string(&mut mimetype)
+        .unwrap();
+    assert_eq!(mimetype, "application/epub+zip");
+}
+
+#[test]
+fn stream_export_writes_incrementally_to_plain_writer() {
+    let book = illustrated_book();
+
+    for normalize in [false, true] {
+        let exporter = EpubExporter::new().with_config(EpubConfig {
+            normalize,
+            ..Default::default()
+        });
+        let writer = exporter
+            .export_stream(&book, CountingWriter::default())
+            .unwrap();
+
+        // Many small writes, not one archive-sized buffer handed over at
+        // the end.
+        assert!(writer.writes > 10, "only {} writes", writer.writes);
+        assert!(
+            writer.largest_write < writer.bytes.len() / 2,
+            "largest write {} of {} bytes",
+            writer.largest_write,
+            writer.bytes.len()
+        );
+        let names = entry_names(writer.bytes);
+        assert!(names.iter().any(|n| n.ends_with(".opf")));
+        if !normalize {
+            // Passthrough ships every asset, referenced or not.
+            assert!(names.iter().any(|n| n.ends_with("plate.bin")));
+        }
+    }
+}
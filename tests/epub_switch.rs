@@ -0,0 +1,89 @@
+//! `<epub:switch>` MathML content with an image fallback.
+
+mod common;
+
+use std::io::{Cursor, Read};
+
+use boko::Format;
+use boko::export::{EpubConfig, EpubExporter, Exporter};
+use boko::model::Role;
+
+const SWITCH: &str = "<p>Mass-energy:</p>\
+    <div><epub:switch xmlns:epub=\"http://www.idpf.org/2007/ops\">\
+    <epub:case required-namespace=\"http://www.w3.org/1998/Math/MathML\">\
+    <math xmlns=\"http://www.w3.org/1998/Math/MathML\" alttext=\"E equals m c squared\">\
+    <mi>E</mi><mo>=</mo><mi>m</mi><msup><mi>c</mi><mn>2</mn></msup></math>\
+    </epub:case>\
+    <epub:default><img src=\"../images/emc2.png\" alt=\"E=mc^2\"/></epub:default>\
+    </epub:switch></div>\
+    <p>after</p>";
+
+fn switch_book() -> boko::Book {
+    use common::{Doc, EpubBuilder, Nav};
+
+    EpubBuilder::new("Switch Book")
+        .image("images/emc2.png", common::tiny_png())
+        .doc(Doc::new("text/ch1.xhtml", "One", SWITCH))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book()
+}
+
+#[test]
+fn epub_export_preserves_switch_with_both_branches() {
+    let book = switch_book();
+    let mut out = Cursor::new(Vec::new());
+    EpubExporter::new()
+        .with_config(EpubConfig {
+            normalize: true,
+            ..Default::default()
+        })
+        .export(&book, &mut out)
+        .expect("export epub");
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(out.into_inner())).unwrap();
+    let mut chapter = String::new();
+    archive
+        .by_name("OEBPS/chapter_0.xhtml")
+        .unwrap()
+        .read_to_string(&mut chapter)
+        .unwrap();
+
+    let switch = chapter.find("<epub:switch>").expect("switch survives");
+    let case = chapter.find("<epub:case").unwrap();
+    let math = chapter.find("<math").unwrap();
+    let default = chapter.find("<epub:default>").unwrap();
+    let img = chapter.find("<img").unwrap();
+    assert!(switch < case && case < math && math < default && default < img);
+    // Exactly one rendering of each branch: the equation isn't duplicated
+    // as loose content beside the switch.
+    assert_eq!(chapter.matches("<math").count(), 1, "{chapter}");
+    assert_eq!(chapter.matches("<img").count(), 1, "{chapter}");
+    assert!(archive.by_name("OEBPS/OEBPS/images/emc2.png").is_ok());
+}
+
+#[test]
+fn kfx_export_chooses_the_image_fallback() {
+    let mut book = switch_book();
+    let kfx = common::export_to_bytes(&mut book, Format::Kfx);
+    let text = String::from_utf8_lossy(&kfx);
+
+    // Neither the KVG annotations nor the text linearization: the
+    // publisher's image stands in for the equation.
+    assert!(!text.contains("<math"), "MathML must not reach the KFX");
+    assert!(
+        !text.contains("E=mc²"),
+        "equation text must not reach the KFX"
+    );
+    assert!(text.contains("after"));
+
+    // The image reads back as the equation's stand-in.
+    let reread = boko::Book::from_bytes(&kfx, Format::Kfx).expect("reimport kfx");
+    let id = reread.spine()[0].id;
+    let chapter = reread.load_chapter(id).unwrap();
+    assert!(
+        chapter
+            .iter_dfs()
+            .any(|n| chapter.node(n).is_some_and(|n| n.role == Role::Image)),
+        "fallback image missing from the KFX"
+    );
+}
@@ -0,0 +1,97 @@
+//! EPUB package version targeting (`EpubConfig::version`).
+
+mod common;
+
+use std::io::{Cursor, Read};
+
+use boko::Format;
+use boko::export::{EpubConfig, EpubExporter, EpubVersion, Exporter};
+
+fn sample_book() -> boko::Book {
+    use common::{Doc, EpubBuilder, Nav};
+
+    EpubBuilder::new("Version Book")
+        .cover_png()
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>first</p>"))
+        .doc(Doc::new("text/ch2.xhtml", "Two", "<p>second</p>"))
+        .nav(vec![
+            Nav::new("One", "text/ch1.xhtml"),
+            Nav::new("Two", "text/ch2.xhtml"),
+        ])
+        .book()
+}
+
+fn export(book: &boko::Book, config: EpubConfig) -> Vec<u8> {
+    let mut out = Cursor::new(Vec::new());
+    EpubExporter::new()
+        .with_config(config)
+        .export(book, &mut out)
+        .expect("export epub");
+    out.into_inner()
+}
+
+fn entry_names(epub: &[u8]) -> Vec<String> {
+    let archive = zip::ZipArchive::new(Cursor::new(epub)).expect("valid zip");
+    archive.file_names().map(str::to_string).collect()
+}
+
+fn read_entry(epub: &[u8], name: &str) -> String {
+    let mut archive = zip::ZipArchive::new(Cursor::new(epub)).expect("valid zip");
+    let mut text = String::new();
+    archive
+        .by_name(name)
+        .expect("entry present")
+        .read_to_string(&mut text)
+        .unwrap();
+    text
+}
+
+#[test]
+fn epub2_writes_ncx_only_version_2_package() {
+    let book = sample_book();
+    for normalize in [false, true] {
+        let epub = export(
+            &book,
+            EpubConfig {
+                normalize,
+                version: EpubVersion::Epub2,
+                ..Default::default()
+            },
+        );
+
+        let opf = read_entry(&epub, "OEBPS/content.opf");
+        assert!(opf.contains(r#"version="2.0""#), "{opf}");
+        assert!(opf.contains(r#"<spine toc="ncx">"#), "{opf}");
+        // No EPUB 3-only constructs.
+        for epub3_only in ["properties=", "refines=", "dcterms:modified"] {
+            assert!(!opf.contains(epub3_only), "{epub3_only} in {opf}");
+        }
+        // The cover survives through the EPUB 2 meta. (Normalized output
+        // only carries assets the content references.)
+        if !normalize {
+            assert!(opf.contains(r#"<meta name="cover""#), "{opf}");
+        }
+
+        let names = entry_names(&epub);
+        assert!(names.iter().any(|n| n == "OEBPS/toc.ncx"), "{names:?}");
+        assert!(
+            !names.iter().any(|n| n.ends_with("nav.xhtml")),
+            "EPUB 2 must not ship a nav document: {names:?}"
+        );
+        let ncx = read_entry(&epub, "OEBPS/toc.ncx");
+        assert_eq!(ncx.matches("<navPoint").count(), 2, "{ncx}");
+
+        // The package reads back with its NCX navigation.
+        let reread = boko::Book::from_bytes(&epub, Format::Epub).expect("reimport");
+        assert_eq!(reread.toc().len(), 2);
+    }
+}
+
+#[test]
+fn epub3_is_the_default_and_ships_nav() {
+    let epub = export(&sample_book(), EpubConfig::default());
+    let opf = read_entry(&epub, "OEBPS/content.opf");
+    assert!(opf.contains(r#"version="3.0""#), "{opf}");
+    assert!(opf.contains(r#"properties="nav""#), "{opf}");
+    assert!(entry_names(&epub).iter().any(|n| n == "OEBPS/nav.xhtml"));
+}
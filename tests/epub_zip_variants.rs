@@ -0,0 +1,244 @@
+//! EPUB archives in less common shapes: ZIP64 with bit-3 data descriptors,
+//! read through the random-access entry reader, `.epub.zip` file names,
+//! entries compressed with methods other than Store and Deflate, and
+//! individually gzipped stylesheets.
+
+mod common;
+
+use std::io::{Cursor, Read, Write};
+
+use boko::model::Role;
+use boko::style::FontWeight;
+use boko::{Book, Format};
+use common::{Doc, EpubBuilder, Nav};
+
+/// One archive entry, as copied from a regular archive: the raw (possibly
+/// deflated) payload plus what the headers must say about it.
+struct Entry {
+    name: String,
+    method: u16,
+    crc: u32,
+    size: u64,
+    raw: Vec<u8>,
+}
+
+fn entries_of(zip: &[u8]) -> Vec<Entry> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(zip)).unwrap();
+    (0..archive.len())
+        .map(|i| {
+            let mut file = archive.by_index_raw(i).unwrap();
+            let mut raw = Vec::new();
+            file.read_to_end(&mut raw).unwrap();
+            Entry {
+                name: file.name().to_string(),
+                method: match file.compression() {
+                    zip::CompressionMethod::Stored => 0,
+                    _ => 8,
+                },
+                crc: file.crc32(),
+                size: file.size(),
+                raw,
+            }
+        })
+        .collect()
+}
+
+/// Rewrite `zip` in the shape large-file and streaming writers produce:
+/// every size and offset moved to ZIP64 extra fields (the 32-bit fields
+/// saturated), sizes left out of the local headers in favour of trailing
+/// ZIP64 data descriptors (general-purpose bit 3), and a ZIP64 end of
+/// central directory record with its locator.
+fn zip64_with_data_descriptors(zip: &[u8]) -> Vec<u8> {
+    write_zip64(&entries_of(zip))
+}
+
+/// Write `entries` as an archive in the ZIP64 data-descriptor shape (see
+/// [`zip64_with_data_descriptors`]).
+fn write_zip64(entries: &[Entry]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut offsets = Vec::new();
+    for entry in entries {
+        offsets.push(out.len() as u64);
+        out.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        out.extend_from_slice(&45u16.to_le_bytes()); // version needed
+        out.extend_from_slice(&0x0008u16.to_le_bytes()); // data descriptor
+        out.extend_from_slice(&entry.method.to_le_bytes());
+        out.extend_from_slice(&[0, 0, 0x21, 0]); // 1980-01-01 00:00
+        out.extend_from_slice(&0u32.to_le_bytes()); // crc: in descriptor
+        out.extend_from_slice(&u32::MAX.to_le_bytes());
+        out.extend_from_slice(&u32::MAX.to_le_bytes());
+        out.extend_from_slice(&(entry.name.len() as u16).to_le_bytes());
+        out.extend_from_slice(&20u16.to_le_bytes());
+        out.extend_from_slice(entry.name.as_bytes());
+        // ZIP64 extra with zero sizes: the real ones follow the data.
+        out.extend_from_slice(&1u16.to_le_bytes());
+        out.extend_from_slice(&16u16.to_le_bytes());
+        out.extend_from_slice(&[0; 16]);
+        out.extend_from_slice(&entry.raw);
+        out.extend_from_slice(&0x0807_4b50u32.to_le_bytes());
+        out.extend_from_slice(&entry.crc.to_le_bytes());
+        out.extend_from_slice(&(entry.raw.len() as u64).to_le_bytes());
+        out.extend_from_slice(&entry.size.to_le_bytes());
+    }
+
+    let central_start = out.len() as u64;
+    for (entry, offset) in entries.iter().zip(offsets) {
+        out.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        out.extend_from_slice(&45u16.to_le_bytes()); // version made by
+        out.extend_from_slice(&45u16.to_le_bytes()); // version needed
+        out.extend_from_slice(&0x0008u16.to_le_bytes());
+        out.extend_from_slice(&entry.method.to_le_bytes());
+        out.extend_from_slice(&[0, 0, 0x21, 0]);
+        out.extend_from_slice(&entry.crc.to_le_bytes());
+        out.extend_from_slice(&u32::MAX.to_le_bytes());
+        out.extend_from_slice(&u32::MAX.to_le_bytes());
+        out.extend_from_slice(&(entry.name.len() as u16).to_le_bytes());
+        out.extend_from_slice(&28u16.to_le_bytes()); // extra length
+        out.extend_from_slice(&[0; 6]); // comment, disk, internal attributes
+        out.extend_from_slice(&0u32.to_le_bytes()); // external attributes
+        out.extend_from_slice(&u32::MAX.to_le_bytes()); // local header offset
+        out.extend_from_slice(entry.name.as_bytes());
+        out.extend_from_slice(&1u16.to_le_bytes());
+        out.extend_from_slice(&24u16.to_le_bytes());
+        out.extend_from_slice(&entry.size.to_le_bytes());
+        out.extend_from_slice(&(entry.raw.len() as u64).to_le_bytes());
+        out.extend_from_slice(&offset.to_le_bytes());
+    }
+    let central_size = out.len() as u64 - central_start;
+
+    let eocd64_offset = out.len() as u64;
+    out.extend_from_slice(&0x0606_4b50u32.to_le_bytes());
+    out.extend_from_slice(&44u64.to_le_bytes());
+    out.extend_from_slice(&45u16.to_le_bytes());
+    out.extend_from_slice(&45u16.to_le_bytes());
+    out.extend_from_slice(&[0; 8]); // this disk, central directory disk
+    out.extend_from_slice(&(entries.len() as u64).to_le_bytes());
+    out.extend_from_slice(&(entries.len() as u64).to_le_bytes());
+    out.extend_from_slice(&central_size.to_le_bytes());
+    out.extend_from_slice(&central_start.to_le_bytes());
+
+    out.extend_from_slice(&0x0706_4b50u32.to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+    out.extend_from_slice(&eocd64_offset.to_le_bytes());
+    out.extend_from_slice(&1u32.to_le_bytes());
+
+    out.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+    out.extend_from_slice(&[0; 4]); // disk numbers
+    out.extend_from_slice(&u16::MAX.to_le_bytes());
+    out.extend_from_slice(&u16::MAX.to_le_bytes());
+    out.extend_from_slice(&u32::MAX.to_le_bytes());
+    out.extend_from_slice(&u32::MAX.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out
+}
+
+fn sample_epub() -> Vec<u8> {
+    EpubBuilder::new("Sixty-Four")
+        .image("images/fig.png", common::tiny_png())
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            r#"<p>Stored far away.</p><img src="../images/fig.png" alt="fig"/>"#,
+        ))
+        .doc(Doc::new("text/ch2.xhtml", "Two", "<p>Second chapter.</p>"))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build()
+}
+
+#[test]
+fn zip64_archive_with_data_descriptors_opens() {
+    let epub = zip64_with_data_descriptors(&sample_epub());
+    let book = Book::from_bytes(&epub, Format::Epub).unwrap();
+
+    assert_eq!(book.metadata().title, "Sixty-Four");
+    assert_eq!(book.spine().len(), 2);
+    let chapter = book.load_chapter(book.spine()[0].id).unwrap();
+    assert!(chapter.text_buffer().contains("Stored far away."));
+    let chapter = book.load_chapter(book.spine()[1].id).unwrap();
+    assert!(chapter.text_buffer().contains("Second chapter."));
+    assert_eq!(
+        book.load_asset("OEBPS/images/fig.png").unwrap(),
+        common::tiny_png()
+    );
+}
+
+#[test]
+fn epub_zip_file_name_opens_as_epub() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("Sixty-Four.epub.zip");
+    std::fs::write(&path, sample_epub()).unwrap();
+
+    let book = Book::open(&path).unwrap();
+    assert_eq!(book.metadata().title, "Sixty-Four");
+}
+
+/// `sample_epub` with its image entry rewritten by `rewrite`.
+fn with_image_entry(rewrite: impl Fn(&mut Entry)) -> Vec<u8> {
+    let mut entries = entries_of(&sample_epub());
+    let image = entries
+        .iter_mut()
+        .find(|entry| entry.name == "OEBPS/images/fig.png")
+        .unwrap();
+    rewrite(image);
+    write_zip64(&entries)
+}
+
+#[test]
+fn deflate64_entry_is_readable() {
+    let png = common::tiny_png();
+    let epub = with_image_entry(|entry| {
+        // A single stored block is valid Deflate64 as well as Deflate.
+        let mut raw = vec![0x01];
+        raw.extend_from_slice(&(png.len() as u16).to_le_bytes());
+        raw.extend_from_slice(&(!(png.len() as u16)).to_le_bytes());
+        raw.extend_from_slice(&png);
+        entry.method = 9;
+        entry.raw = raw;
+    });
+    let book = Book::from_bytes(&epub, Format::Epub).unwrap();
+    assert_eq!(book.load_asset("OEBPS/images/fig.png").unwrap(), png);
+}
+
+#[test]
+fn unsupported_compression_method_is_named() {
+    let epub = with_image_entry(|entry| entry.method = 12);
+    let book = Book::from_bytes(&epub, Format::Epub).unwrap();
+    let err = book.load_asset("OEBPS/images/fig.png").unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("bzip2"), "{message}");
+    assert!(message.contains("OEBPS/images/fig.png"), "{message}");
+}
+
+#[test]
+fn gzipped_stylesheet_is_applied() {
+    let epub = EpubBuilder::new("Squeezed")
+        .css("p { font-weight: bold }")
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>Heavy</p>"))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build();
+    let mut entries = entries_of(&epub);
+    let css = entries
+        .iter_mut()
+        .find(|entry| entry.name == "OEBPS/css/style.css")
+        .unwrap();
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(b"p { font-weight: bold }").unwrap();
+    css.raw = encoder.finish().unwrap();
+    css.method = 0;
+    css.size = css.raw.len() as u64;
+    let mut crc = flate2::Crc::new();
+    crc.update(&css.raw);
+    css.crc = crc.sum();
+
+    let book = Book::from_bytes(&write_zip64(&entries), Format::Epub).unwrap();
+    let chapter = book.load_chapter(book.spine()[0].id).unwrap();
+    let para = chapter
+        .iter_dfs()
+        .find(|&id| chapter.node(id).unwrap().role == Role::Paragraph)
+        .unwrap();
+    let style = chapter
+        .styles
+        .get(chapter.node(para).unwrap().style)
+        .unwrap();
+    assert_eq!(style.font_weight, FontWeight::BOLD);
+}
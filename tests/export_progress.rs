@@ -0,0 +1,145 @@
+//! `export_with_progress`: chapter and asset events during export.
+
+mod common;
+
+use std::io::Cursor;
+
+use boko::{Book, Format, ProgressEvent};
+use common::{Doc, EpubBuilder, Nav};
+
+/// Three chapters, the second showing an image.
+fn three_chapter_book() -> Book {
+    EpubBuilder::new("Progress")
+        .image("images/fig.png", common::tiny_png())
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>First.</p>"))
+        .doc(Doc::new(
+            "text/ch2.xhtml",
+            "Two",
+            r#"<p>Second.</p><img src="../images/fig.png" alt="fig"/>"#,
+        ))
+        .doc(Doc::new("text/ch3.xhtml", "Three", "<p>Third.</p>"))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book()
+}
+
+fn events(book: &Book, format: Format) -> Vec<ProgressEvent> {
+    let mut events = Vec::new();
+    book.export_with_progress(format, &mut Cursor::new(Vec::new()), &mut |event| {
+        events.push(event)
+    })
+    .unwrap();
+    events
+}
+
+#[test]
+fn chapter_events_fire_once_per_spine_chapter_in_order() {
+    let book = three_chapter_book();
+    let expected: Vec<ProgressEvent> = (0..3)
+        .flat_map(|index| {
+            [
+                ProgressEvent::ChapterStarted { index, total: 3 },
+                ProgressEvent::ChapterFinished { index, total: 3 },
+            ]
+        })
+        .collect();
+    for format in [
+        Format::Epub,
+        Format::Azw3,
+        Format::Kfx,
+        Format::Markdown,
+        Format::Ir,
+    ] {
+        let chapters: Vec<_> = events(&book, format)
+            .into_iter()
+            .filter(|e| {
+                matches!(
+                    e,
+                    ProgressEvent::ChapterStarted { .. } | ProgressEvent::ChapterFinished { .. }
+                )
+            })
+            .collect();
+        assert_eq!(chapters, expected, "{format:?}");
+    }
+}
+
+#[test]
+fn whole_book_passes_report_prepared_chapters_first() {
+    use boko::export::{Azw3Config, Azw3Exporter, EpubConfig, EpubExporter, Exporter};
+
+    fn record(export: impl FnOnce(&mut dyn FnMut(ProgressEvent))) -> Vec<ProgressEvent> {
+        let mut log = Vec::new();
+        export(&mut |event| log.push(event));
+        log
+    }
+
+    let book = three_chapter_book();
+    let prepared: Vec<ProgressEvent> = (0..3)
+        .map(|index| ProgressEvent::ChapterPrepared { index, total: 3 })
+        .collect();
+    let mut runs = vec![("kfx", events(&book, Format::Kfx))];
+    runs.push((
+        "normalized azw3",
+        record(|progress| {
+            Azw3Exporter::new()
+                .with_config(Azw3Config { normalize: true })
+                .export_with_progress(&book, &mut Cursor::new(Vec::new()), progress)
+                .unwrap()
+        }),
+    ));
+    for low_memory in [false, true] {
+        runs.push((
+            "normalized epub",
+            record(|progress| {
+                EpubExporter::new()
+                    .with_config(EpubConfig {
+                        normalize: true,
+                        low_memory,
+                        ..Default::default()
+                    })
+                    .export_with_progress(&book, &mut Cursor::new(Vec::new()), progress)
+                    .unwrap()
+            }),
+        ));
+    }
+    for (name, log) in runs {
+        let first_started = log
+            .iter()
+            .position(|e| matches!(e, ProgressEvent::ChapterStarted { .. }))
+            .expect("chapters written");
+        assert_eq!(log[..first_started], prepared[..], "{name}");
+        assert!(
+            !log[first_started..]
+                .iter()
+                .any(|e| matches!(e, ProgressEvent::ChapterPrepared { .. })),
+            "{name}"
+        );
+    }
+
+    // Passthrough writes chapters as it reads them.
+    for format in [Format::Epub, Format::Azw3] {
+        assert!(
+            !events(&book, format)
+                .iter()
+                .any(|e| matches!(e, ProgressEvent::ChapterPrepared { .. })),
+            "{format:?}"
+        );
+    }
+}
+
+#[test]
+fn written_assets_are_reported() {
+    let book = three_chapter_book();
+    for format in [Format::Epub, Format::Azw3, Format::Kfx] {
+        let assets: Vec<_> = events(&book, format)
+            .into_iter()
+            .filter_map(|e| match e {
+                ProgressEvent::AssetWritten { path } => Some(path),
+                _ => None,
+            })
+            .collect();
+        assert!(
+            assets.iter().any(|path| path.ends_with("images/fig.png")),
+            "{format:?}: {assets:?}"
+        );
+    }
+}
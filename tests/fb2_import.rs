@@ -0,0 +1,168 @@
+//! FictionBook 2 import: metadata from `<description>`, one chapter per
+//! top-level section, titles as the TOC, and base64 binaries as images.
+
+use boko::model::LandmarkType;
+use boko::style::{FontStyle, FontWeight};
+use boko::{Book, Format, Role};
+
+const PNG_1X1: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mP8z8BQDwAEhQGAhKmMIQAAAABJRU5ErkJggg==";
+
+fn sample() -> String {
+    format!(
+        r##"<?xml version="1.0" encoding="UTF-8"?>
+<FictionBook xmlns="http://www.gribuser.ru/xml/fictionbook/2.0" xmlns:l="http://www.w3.org/1999/xlink">
+  <description>
+    <title-info>
+      <genre>sf_space</genre>
+      <author><first-name>Ivan</first-name><last-name>Petrov</last-name></author>
+      <book-title>Beyond the Stars</book-title>
+      <annotation><p>A voyage.</p></annotation>
+      <date value="2001-05-01">2001</date>
+      <coverpage><image l:href="#cover.png"/></coverpage>
+      <lang>ru</lang>
+      <sequence name="Voyages" number="2"/>
+    </title-info>
+    <document-info><id>fb2-doc-1</id></document-info>
+    <publish-info><publisher>Mir</publisher><isbn>978-5-00-000000-0</isbn></publish-info>
+  </description>
+  <body>
+    <section id="one">
+      <title><p>Chapter One</p><p>Departure</p></title>
+      <epigraph><p>Per aspera.</p><text-author>Seneca</text-author></epigraph>
+      <p>The ship was <emphasis>ready</emphasis> and <strong>fueled</strong>.<a l:href="#n1" type="note">1</a></p>
+      <section>
+        <title><p>Launch</p></title>
+        <p>Countdown.</p>
+      </section>
+    </section>
+    <section>
+      <title><p>Chapter Two</p></title>
+      <image l:href="#cover.png"/>
+      <p>Back to <a l:href="#one">the start</a>.</p>
+    </section>
+  </body>
+  <body name="notes">
+    <section id="n1"><title><p>1</p></title><p>A footnote.</p></section>
+  </body>
+  <binary id="cover.png" content-type="image/png">{PNG_1X1}</binary>
+</FictionBook>
+"##
+    )
+}
+
+#[test]
+fn fb2_imports_metadata_chapters_and_images() {
+    let book = Book::from_bytes(sample().as_bytes(), Format::Fb2).unwrap();
+
+    let metadata = book.metadata();
+    assert_eq!(metadata.title, "Beyond the Stars");
+    assert_eq!(metadata.authors, ["Ivan Petrov"]);
+    assert_eq!(metadata.author_sort.as_deref(), Some("Petrov, Ivan"));
+    assert_eq!(metadata.language, "ru");
+    assert_eq!(metadata.subjects, ["sf_space"]);
+    assert_eq!(metadata.date.as_deref(), Some("2001-05-01"));
+    assert_eq!(metadata.publisher.as_deref(), Some("Mir"));
+    assert_eq!(metadata.identifier, "fb2-doc-1");
+    assert!(
+        metadata
+            .identifiers
+            .iter()
+            .any(|id| id.scheme.as_deref() == Some("ISBN"))
+    );
+    assert_eq!(metadata.description.as_deref(), Some("<p>A voyage.</p>"));
+    let collection = metadata.collection.as_ref().unwrap();
+    assert_eq!(
+        (collection.name.as_str(), collection.position),
+        ("Voyages", Some(2.0))
+    );
+    let cover = metadata.cover_image.as_deref().unwrap();
+    assert_eq!(cover, "images/cover.png");
+    assert_eq!(book.list_assets(), [cover]);
+    assert!(book.load_asset(cover).unwrap().starts_with(b"\x89PNG"));
+
+    // Two chapters from the main body, then the notes.
+    assert_eq!(book.spine().len(), 3);
+    let titles: Vec<_> = book.toc().iter().map(|e| e.title.as_str()).collect();
+    assert_eq!(titles, ["Chapter One Departure", "Chapter Two", "Notes"]);
+    assert_eq!(book.toc()[0].children[0].title, "Launch");
+
+    let one = book.load_chapter(book.spine()[0].id).unwrap();
+    let roles: Vec<_> = one
+        .iter_dfs()
+        .map(|id| one.node(id).unwrap().role)
+        .collect();
+    assert!(roles.contains(&Role::Heading(1)));
+    assert!(roles.contains(&Role::Heading(2)));
+    let styles: Vec<_> = one
+        .iter_dfs()
+        .filter(|&id| one.node(id).unwrap().role == Role::Inline)
+        .map(|id| one.styles.get(one.node(id).unwrap().style).unwrap())
+        .collect();
+    assert!(styles.iter().any(|s| s.font_style == FontStyle::Italic));
+    assert!(styles.iter().any(|s| s.font_weight == FontWeight::BOLD));
+    let text = one.plain_text();
+    assert!(text.starts_with("Chapter One\nDeparture\n"), "{text}");
+    assert!(text.contains("The ship was ready and fueled."), "{text}");
+
+    let href = |chapter: &boko::Chapter| {
+        chapter
+            .iter_dfs()
+            .find_map(|id| chapter.semantics.href(id).map(str::to_string))
+    };
+    // Links into other chapters name the chapter holding the target id.
+    assert_eq!(href(&one).as_deref(), Some("notes.xhtml#n1"));
+
+    let two = book.load_chapter(book.spine()[1].id).unwrap();
+    assert_eq!(href(&two).as_deref(), Some("chapter_0.xhtml#one"));
+    assert!(
+        two.iter_dfs()
+            .any(|id| two.semantics.src(id) == Some("images/cover.png"))
+    );
+    assert!(
+        book.landmarks()
+            .iter()
+            .all(|lm| lm.landmark_type != LandmarkType::Cover)
+    );
+}
+
+#[test]
+fn fb2_converts_to_epub() {
+    let book = Book::from_bytes(sample().as_bytes(), Format::Fb2).unwrap();
+    let mut out = std::io::Cursor::new(Vec::new());
+    book.export(Format::Epub, &mut out).unwrap();
+    let epub = Book::from_bytes(&out.into_inner(), Format::Epub).unwrap();
+    assert_eq!(epub.metadata().title, "Beyond the Stars");
+    assert!(epub.metadata().cover_image.is_some());
+    // FB2 has no cover page; export synthesizes one ahead of the chapters.
+    assert_eq!(epub.spine().len(), 4);
+    assert!(
+        epub.source_id(epub.spine()[0].id)
+            .is_some_and(|path| path.ends_with("cover.xhtml"))
+    );
+}
+
+#[test]
+fn fb2_in_windows_1251_is_decoded() {
+    let xml = "<?xml version=\"1.0\" encoding=\"windows-1251\"?>\
+        <FictionBook><description><title-info><book-title>\u{0}</book-title>\
+        </title-info></description><body><section><p>x</p></section></body></FictionBook>";
+    // "Книга" in windows-1251.
+    let title: &[u8] = &[0xCA, 0xED, 0xE8, 0xE3, 0xE0];
+    let mut bytes = Vec::new();
+    let (before, after) = xml.split_once('\u{0}').unwrap();
+    bytes.extend_from_slice(before.as_bytes());
+    bytes.extend_from_slice(title);
+    bytes.extend_from_slice(after.as_bytes());
+
+    let book = Book::from_bytes(&bytes, Format::Fb2).unwrap();
+    assert_eq!(book.metadata().title, "Книга");
+    assert_eq!(book.spine().len(), 1);
+}
+
+#[test]
+fn non_fb2_xml_is_malformed() {
+    let err = Book::from_bytes(b"<html><body/></html>", Format::Fb2)
+        .err()
+        .unwrap();
+    assert!(matches!(err, boko::Error::Malformed { .. }), "{err}");
+}
@@ -0,0 +1,141 @@
+//! Fixed-layout (`rendition:layout` pre-paginated) EPUBs keep their
+//! rendition settings and page size through conversion.
+
+mod common;
+
+use boko::Format;
+use boko::export::{EpubConfig, EpubExporter, Exporter};
+use common::{Doc, EpubBuilder, Nav};
+
+fn comic() -> EpubBuilder {
+    let viewport = "<meta name=\"viewport\" content=\"width=1200, height=1800\"/>";
+    EpubBuilder::new("Comic")
+        .metadata_xml("<meta property=\"rendition:layout\">pre-paginated</meta>")
+        .metadata_xml("<meta property=\"rendition:orientation\">portrait</meta>")
+        .metadata_xml("<meta property=\"rendition:spread\">none</meta>")
+        .image("images/p1.png", common::tiny_png())
+        .doc(
+            Doc::new(
+                "text/p1.xhtml",
+                "Page 1",
+                "<img src=\"../images/p1.png\" alt=\"\"/>",
+            )
+            .head(viewport),
+        )
+        .doc(Doc::new("text/p2.xhtml", "Page 2", "<p>The end.</p>").head(viewport))
+        .nav(vec![Nav::new("Page 1", "text/p1.xhtml")])
+}
+
+#[test]
+fn pre_paginated_epub_reads_rendition_and_viewport() {
+    let book = comic().book();
+    let rendition = &book.metadata().rendition;
+    assert!(rendition.is_fixed_layout());
+    assert_eq!(rendition.orientation.as_deref(), Some("portrait"));
+    assert_eq!(rendition.spread.as_deref(), Some("none"));
+    assert_eq!(rendition.viewport, Some((1200, 1800)));
+    assert!(
+        !book
+            .metadata()
+            .custom
+            .iter()
+            .any(|(property, _)| property.starts_with("rendition:")),
+        "rendition properties are modeled, not custom"
+    );
+}
+
+#[test]
+fn pre_paginated_epub_keeps_rendition_layout_after_roundtrip() {
+    for normalize in [false, true] {
+        let book = comic().book();
+        let mut out = Vec::new();
+        EpubExporter::new()
+            .with_config(EpubConfig {
+                normalize,
+                ..Default::default()
+            })
+            .export(&book, &mut std::io::Cursor::new(&mut out))
+            .unwrap();
+
+        let reread = boko::Book::from_bytes(&out, Format::Epub).unwrap();
+        let rendition = &reread.metadata().rendition;
+        assert!(rendition.is_fixed_layout(), "normalize={normalize}");
+        assert_eq!(rendition.orientation.as_deref(), Some("portrait"));
+        assert_eq!(rendition.spread.as_deref(), Some("none"));
+        assert_eq!(
+            rendition.viewport,
+            Some((1200, 1800)),
+            "normalize={normalize}"
+        );
+
+        let html = String::from_utf8(reread.load_raw(reread.spine()[0].id).unwrap()).unwrap();
+        assert!(
+            html.contains("name=\"viewport\" content=\"width=1200, height=1800\""),
+            "normalize={normalize}: {html}"
+        );
+    }
+}
+
+#[test]
+fn epub2_export_declares_fixed_layout_the_kindle_way() {
+    let book = comic().book();
+    let mut out = Vec::new();
+    EpubExporter::new()
+        .with_config(EpubConfig {
+            version: boko::export::EpubVersion::Epub2,
+            ..Default::default()
+        })
+        .export(&book, &mut std::io::Cursor::new(&mut out))
+        .unwrap();
+
+    let reread = boko::Book::from_bytes(&out, Format::Epub).unwrap();
+    let rendition = &reread.metadata().rendition;
+    assert!(rendition.is_fixed_layout());
+    assert_eq!(rendition.viewport, Some((1200, 1800)));
+    assert_eq!(rendition.orientation.as_deref(), Some("portrait"));
+}
+
+#[test]
+fn kindle_orientation_lock_none_reads_as_auto() {
+    for (lock, orientation) in [("none", Some("auto")), ("sideways", None)] {
+        let book = EpubBuilder::new("Unlocked")
+            .metadata_xml(&format!(
+                "<meta name=\"orientation-lock\" content=\"{lock}\"/>"
+            ))
+            .doc(Doc::new("text/p1.xhtml", "Page 1", "<p>Folio.</p>"))
+            .nav(vec![Nav::new("Page 1", "text/p1.xhtml")])
+            .book();
+        assert_eq!(
+            book.metadata().rendition.orientation.as_deref(),
+            orientation,
+            "{lock}"
+        );
+    }
+}
+
+#[test]
+fn page_rule_sizes_pages_without_a_viewport() {
+    let book = EpubBuilder::new("Print Replica")
+        .metadata_xml("<meta property=\"rendition:layout\">pre-paginated</meta>")
+        .css("@page { size: 5in 8in; margin: 1cm; }")
+        .doc(Doc::new("text/p1.xhtml", "Page 1", "<p>Folio.</p>"))
+        .nav(vec![Nav::new("Page 1", "text/p1.xhtml")])
+        .book();
+    let page = book.page_rule().expect("@page rule");
+    let size = page.size.expect("page size");
+    assert_eq!((size.width, size.height), (480.0, 768.0));
+
+    let mut out = Vec::new();
+    EpubExporter::new()
+        .with_config(EpubConfig {
+            normalize: true,
+            ..Default::default()
+        })
+        .export(&book, &mut std::io::Cursor::new(&mut out))
+        .unwrap();
+    let reread = boko::Book::from_bytes(&out, Format::Epub).unwrap();
+    assert_eq!(reread.metadata().rendition.viewport, Some((480, 768)));
+    let page = reread.page_rule().expect("@page re-emitted");
+    assert_eq!(page.size, Some(size));
+    assert!(page.margin_top != boko::style::Length::Auto);
+}
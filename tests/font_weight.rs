@@ -0,0 +1,74 @@
+//! Intermediate font weights (600) survive export as themselves rather
+//! than snapping to `normal`/`bold`.
+
+mod common;
+
+use std::io::{Cursor, Read};
+
+use boko::export::{EpubConfig, EpubExporter, Exporter};
+use boko::{Book, Format, Role};
+
+fn sample_book() -> Book {
+    use common::{Doc, EpubBuilder, Nav};
+
+    EpubBuilder::new("Weights")
+        .css(".semi { font-weight: 600; } .heavy { font-weight: bold; }")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            r#"<p><span class="semi">semibold</span> <span class="heavy">bold</span></p>"#,
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book()
+}
+
+/// Font weight of the inline run whose text is `text`.
+fn weight_of(book: &Book, text: &str) -> u16 {
+    let chapter = book.load_chapter(book.spine()[0].id).unwrap();
+    chapter
+        .iter_dfs()
+        .find_map(|id| {
+            let node = chapter.node(id)?;
+            let child = chapter.node(node.first_child?)?;
+            (node.role == Role::Inline
+                && child.role == Role::Text
+                && chapter.text(child.text) == text)
+                .then(|| chapter.styles.get(node.style).unwrap().font_weight.0)
+        })
+        .unwrap_or_else(|| panic!("no inline run {text:?}"))
+}
+
+#[test]
+fn semibold_is_distinct_from_bold_in_epub_css() {
+    let book = sample_book();
+    assert_eq!(weight_of(&book, "semibold"), 600);
+    assert_eq!(weight_of(&book, "bold"), 700);
+
+    let mut out = Cursor::new(Vec::new());
+    EpubExporter::new()
+        .with_config(EpubConfig {
+            normalize: true,
+            ..Default::default()
+        })
+        .export(&book, &mut out)
+        .unwrap();
+    let mut archive = zip::ZipArchive::new(Cursor::new(out.into_inner())).unwrap();
+    let mut css = String::new();
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).unwrap();
+        if file.name().ends_with(".css") {
+            file.read_to_string(&mut css).unwrap();
+        }
+    }
+    assert!(css.contains("font-weight: 600"), "{css}");
+    assert!(css.contains("font-weight: bold"), "{css}");
+}
+
+#[test]
+fn semibold_survives_kfx_round_trip() {
+    let mut book = sample_book();
+    let kfx = common::export_to_bytes(&mut book, Format::Kfx);
+    let reread = Book::from_bytes(&kfx, Format::Kfx).unwrap();
+    assert_eq!(weight_of(&reread, "semibold"), 600);
+    assert_eq!(weight_of(&reread, "bold"), 700);
+}
@@ -0,0 +1,100 @@
+//! `Book::from_directory`: loose XHTML folders and unpacked EPUBs.
+
+mod common;
+
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+use boko::{Book, Format};
+
+fn xhtml(title: &str, body: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<html xmlns="http://www.w3.org/1999/xhtml">
+<head><title>{title}</title></head>
+<body>{body}</body>
+</html>"#
+    )
+}
+
+fn write(dir: &Path, path: &str, data: impl AsRef<[u8]>) {
+    let path = dir.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, data).unwrap();
+}
+
+#[test]
+fn loose_files_become_spine_and_assets() {
+    let root = tempfile::tempdir().unwrap();
+    let dir = root.path().join("My Draft");
+    write(&dir, "ch10.xhtml", xhtml("Ten", "<p>Tenth chapter.</p>"));
+    write(
+        &dir,
+        "ch2.xhtml",
+        xhtml(
+            "Two",
+            "<p>Second chapter.</p><img src=\"images/map.png\" alt=\"Map\"/>",
+        ),
+    );
+    write(&dir, "images/map.png", common::tiny_png());
+    write(&dir, ".DS_Store", b"junk");
+
+    let mut book = Book::from_directory(&dir).expect("loose directory");
+    assert_eq!(book.metadata().title, "My Draft");
+    assert_eq!(book.chapter_count(), 2);
+    let sources: Vec<_> = book
+        .spine()
+        .iter()
+        .map(|entry| book.source_id(entry.id).unwrap())
+        .collect();
+    assert_eq!(sources, ["ch2.xhtml", "ch10.xhtml"]);
+
+    assert!(!book.list_assets().iter().any(|a| a.contains("DS_Store")));
+    let map = book.resource("images/map.png").expect("image asset");
+    assert_eq!(map.media_type(), "image/png");
+    assert_eq!(map.data, common::tiny_png());
+    assert!(book.validate().is_empty(), "{:?}", book.validate());
+
+    let text = String::from_utf8(common::export_to_bytes(&mut book, Format::Markdown)).unwrap();
+    let second = text.find("Second chapter.").expect("ch2 text");
+    let tenth = text.find("Tenth chapter.").expect("ch10 text");
+    assert!(second < tenth, "{text}");
+}
+
+#[test]
+fn unpacked_epub_keeps_its_package() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let epub = EpubBuilder::new("Unpacked")
+        .cover_png()
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>First.</p>"))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build();
+    let dir = tempfile::tempdir().unwrap();
+    let mut zip = zip::ZipArchive::new(Cursor::new(epub)).unwrap();
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).unwrap();
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).unwrap();
+        write(dir.path(), entry.name(), data);
+    }
+
+    let book = Book::from_directory(dir.path()).expect("unpacked epub");
+    assert_eq!(book.metadata().title, "Unpacked");
+    assert_eq!(
+        book.metadata().cover_image.as_deref(),
+        Some("OEBPS/images/cover.png")
+    );
+    assert_eq!(book.toc()[0].title, "One");
+    assert_eq!(book.chapter_count(), 1);
+}
+
+#[test]
+fn directory_without_documents_is_an_error() {
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "images/map.png", common::tiny_png());
+    assert!(matches!(
+        Book::from_directory(dir.path()),
+        Err(boko::Error::NotFound { .. })
+    ));
+}
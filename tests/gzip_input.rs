@@ -0,0 +1,50 @@
+//! Reading gzip-compressed ebooks (`book.epub.gz`).
+
+mod common;
+
+use std::io::Write;
+
+use boko::{Book, Format};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+
+fn gzipped_epub() -> Vec<u8> {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let epub = EpubBuilder::new("Compressed Book")
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>inside the gzip</p>"))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build();
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&epub).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[test]
+fn open_decompresses_gzipped_epub() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("book.epub.gz");
+    std::fs::write(&path, gzipped_epub()).unwrap();
+
+    let book = Book::open(&path).expect("open .epub.gz");
+    assert_eq!(book.metadata().title, "Compressed Book");
+    let id = book.spine()[0].id;
+    let raw = book.load_raw(id).unwrap();
+    assert!(String::from_utf8_lossy(&raw).contains("inside the gzip"));
+    assert!(book.load_chapter(id).is_ok());
+}
+
+#[test]
+fn gzip_is_detected_by_magic_bytes() {
+    let gz = gzipped_epub();
+
+    // The extension doesn't matter once the format is known.
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("download.bin");
+    std::fs::write(&path, &gz).unwrap();
+    let book = Book::open_format(&path, Format::Epub).expect("open gzipped bytes");
+    assert_eq!(book.metadata().title, "Compressed Book");
+
+    let book = Book::from_bytes(&gz, Format::Epub).expect("gzipped from_bytes");
+    assert_eq!(book.metadata().title, "Compressed Book");
+}
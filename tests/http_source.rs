@@ -0,0 +1,133 @@
+//! `HttpRangeSource` against a local HTTP server, with and without `Range`
+//! support.
+#![cfg(feature = "http")]
+
+mod common;
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use boko::import::{EpubImporter, Importer};
+use boko::{Book, ByteSource, HttpRangeSource};
+use common::{Doc, EpubBuilder, Nav};
+
+/// Incompressible filler so the image dominates the archive size.
+fn noise(len: usize) -> Vec<u8> {
+    let mut state = 0x2545_f491_u32;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as u8
+        })
+        .collect()
+}
+
+fn big_epub() -> Vec<u8> {
+    EpubBuilder::new("Remote")
+        .image("images/plate.png", noise(2 << 20))
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>Hello from afar.</p>"))
+        .doc(Doc::new(
+            "text/ch2.xhtml",
+            "Two",
+            r#"<p><img src="../images/plate.png" alt="plate"/></p>"#,
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build()
+}
+
+/// Serve `data` at `http://127.0.0.1:<port>/book.epub`, honouring
+/// `Range: bytes=<first>-<last>` when `ranges` is set. Returns the base URL
+/// and a counter of body bytes sent.
+fn serve(data: Vec<u8>, ranges: bool) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/book.epub", listener.local_addr().unwrap());
+    let sent = Arc::new(AtomicUsize::new(0));
+    let counter = sent.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut range = None;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 || line.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':')
+                    && name.eq_ignore_ascii_case("range")
+                    && let Some((first, last)) =
+                        value.trim().trim_start_matches("bytes=").split_once('-')
+                {
+                    range = Some((
+                        first.parse::<usize>().unwrap(),
+                        last.parse::<usize>().unwrap(),
+                    ));
+                }
+            }
+            let (head, body) = match range.filter(|_| ranges) {
+                Some((first, last)) => {
+                    let last = last.min(data.len() - 1);
+                    (
+                        format!(
+                            "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes {first}-{last}/{}\r\n",
+                            data.len()
+                        ),
+                        &data[first..=last],
+                    )
+                }
+                None => ("HTTP/1.1 200 OK\r\n".to_string(), &data[..]),
+            };
+            counter.fetch_add(body.len(), Ordering::Relaxed);
+            let _ = write!(
+                stream,
+                "{head}Content-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            let _ = stream.write_all(body);
+        }
+    });
+    (url, sent)
+}
+
+#[test]
+fn range_requests_read_only_the_needed_entries() {
+    let epub = big_epub();
+    let total = epub.len();
+    let (url, sent) = serve(epub, true);
+
+    let source = HttpRangeSource::open(url.as_str()).unwrap();
+    assert!(source.supports_ranges());
+    assert_eq!(source.len(), total as u64);
+    let importer = EpubImporter::from_source(Arc::new(source)).unwrap();
+    assert_eq!(importer.metadata().title, "Remote");
+
+    let book = Book::open_http(&url).unwrap();
+    let chapter = book.load_chapter(book.spine()[0].id).unwrap();
+    assert!(chapter.text_buffer().contains("Hello from afar."));
+
+    let sent = sent.load(Ordering::Relaxed);
+    assert!(
+        sent < total / 2,
+        "sent {sent} of {total} bytes; the image should not be read"
+    );
+}
+
+#[test]
+fn servers_without_range_support_fall_back_to_a_full_download() {
+    let epub = big_epub();
+    let total = epub.len();
+    let (url, _) = serve(epub, false);
+
+    let source = HttpRangeSource::open(url.as_str()).unwrap();
+    assert!(!source.supports_ranges());
+    assert_eq!(source.len(), total as u64);
+
+    let book = Book::open_http(&url).unwrap();
+    assert_eq!(book.metadata().title, "Remote");
+    let chapter = book.load_chapter(book.spine()[0].id).unwrap();
+    assert!(chapter.text_buffer().contains("Hello from afar."));
+}
@@ -0,0 +1,113 @@
+//! Every `dc:identifier` is kept on `Metadata::identifiers` with its scheme
+//! and written back out on EPUB export.
+
+mod common;
+
+use std::io::{Cursor, Read};
+use std::process::Command;
+
+use boko::export::{EpubConfig, EpubExporter, EpubVersion, Exporter};
+use boko::model::Identifier;
+use boko::{Book, Format};
+
+fn sample_epub() -> Vec<u8> {
+    use common::{Doc, EpubBuilder, Nav};
+
+    EpubBuilder::new("Identifiers")
+        .metadata_xml(r#"<dc:identifier id="isbn">9780141439518</dc:identifier>"#)
+        .metadata_xml(r##"<meta refines="#isbn" property="identifier-type">ISBN</meta>"##)
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>x</p>"))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build()
+}
+
+fn export(book: &Book, config: EpubConfig) -> Vec<u8> {
+    let mut out = Cursor::new(Vec::new());
+    EpubExporter::new()
+        .with_config(config)
+        .export(book, &mut out)
+        .expect("export epub");
+    out.into_inner()
+}
+
+fn opf(epub: &[u8]) -> String {
+    let mut archive = zip::ZipArchive::new(Cursor::new(epub)).expect("valid zip");
+    let mut text = String::new();
+    archive
+        .by_name("OEBPS/content.opf")
+        .unwrap()
+        .read_to_string(&mut text)
+        .unwrap();
+    text
+}
+
+fn isbn() -> Identifier {
+    Identifier {
+        scheme: Some("ISBN".into()),
+        value: "9780141439518".into(),
+    }
+}
+
+fn assert_identifiers(book: &Book, unique: &str, context: &str) {
+    let metadata = book.metadata();
+    assert_eq!(metadata.identifier, unique, "{context}");
+    assert!(unique.starts_with("urn:uuid:"), "{context}");
+    assert_eq!(metadata.identifiers.len(), 2, "{context}");
+    assert!(
+        metadata.identifiers.iter().any(|i| i.value == unique),
+        "{context}"
+    );
+    assert!(metadata.identifiers.contains(&isbn()), "{context}");
+}
+
+#[test]
+fn isbn_and_uuid_round_trip_through_epub() {
+    let book = Book::from_bytes(&sample_epub(), Format::Epub).expect("import");
+    let unique = book.metadata().identifier.clone();
+    assert_identifiers(&book, &unique, "source");
+
+    for version in [EpubVersion::Epub3, EpubVersion::Epub2] {
+        for normalize in [false, true] {
+            let epub = export(
+                &book,
+                EpubConfig {
+                    version,
+                    normalize,
+                    ..Default::default()
+                },
+            );
+            let opf = opf(&epub);
+            if version == EpubVersion::Epub2 {
+                assert!(opf.contains(r#"opf:scheme="ISBN""#), "{opf}");
+            } else {
+                assert!(opf.contains(r#"property="identifier-type">ISBN<"#), "{opf}");
+            }
+            let reread = Book::from_bytes(&epub, Format::Epub).expect("reimport");
+            assert_identifiers(&reread, &unique, &opf);
+        }
+    }
+}
+
+#[test]
+fn info_json_lists_identifiers() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("book.epub");
+    std::fs::write(&path, sample_epub()).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_boko"))
+        .arg("info")
+        .arg("--json")
+        .arg(&path)
+        .output()
+        .expect("failed to run boko info");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let identifiers = json["metadata"]["identifiers"].as_array().unwrap();
+    assert_eq!(identifiers.len(), 2, "{json}");
+    assert!(
+        identifiers
+            .iter()
+            .any(|i| i["scheme"] == "ISBN" && i["value"] == "9780141439518"),
+        "{json}"
+    );
+}
@@ -0,0 +1,126 @@
+//! Stripping control characters from exported text: in KFX unless
+//! `KfxConfig::clean_content` is off, and always in EPUB XHTML, whether
+//! normalized or passed through.
+
+mod common;
+
+use std::io::{Cursor, Read};
+
+use boko::export::{EpubConfig, EpubExporter, Exporter, KfxConfig, KfxExporter, normalize_book};
+use boko::{Book, Format, ProgressEvent};
+use common::{Doc, EpubBuilder, Nav};
+
+/// KFX text of `book` exported with `clean_content`, read back.
+fn kfx_text(book: &Book, clean_content: bool) -> String {
+    let mut out = Cursor::new(Vec::new());
+    KfxExporter::new()
+        .with_config(KfxConfig {
+            clean_content,
+            ..Default::default()
+        })
+        .export(book, &mut out)
+        .unwrap();
+    let kfx = Book::from_bytes(&out.into_inner(), Format::Kfx).unwrap();
+    let mut text = String::new();
+    for entry in kfx.spine() {
+        text.push_str(kfx.load_chapter(entry.id).unwrap().text_buffer());
+    }
+    text
+}
+
+#[test]
+fn control_characters_survive_only_with_cleaning_disabled() {
+    let book = EpubBuilder::new("Controls")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<p>bell\u{7}here and <b>bold\u{1b}text</b> after</p>",
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+    let source = book.load_chapter(book.spine()[0].id).unwrap();
+    assert!(source.text_buffer().contains('\u{7}'), "source keeps it");
+
+    let cleaned = kfx_text(&book, true);
+    assert!(
+        cleaned.contains("bellhere and boldtext after"),
+        "{cleaned:?}"
+    );
+
+    let raw = kfx_text(&book, false);
+    assert!(raw.contains("bell\u{7}here"), "{raw:?}");
+    assert!(raw.contains("bold\u{1b}text"), "{raw:?}");
+}
+
+#[test]
+fn bell_and_noncharacter_are_dropped_but_tabs_survive() {
+    let book = EpubBuilder::new("Controls")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<pre>ring\u{7}ing\u{FFFE}\tcolumn</pre>",
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+
+    let kfx = kfx_text(&book, true);
+    assert!(kfx.contains("ringing\tcolumn"), "{kfx:?}");
+
+    let normalized = normalize_book(&book).unwrap();
+    let xhtml = &normalized.chapters[0].document;
+    assert!(xhtml.contains("ringing\tcolumn"), "{xhtml}");
+    assert!(!xhtml.contains('\u{7}') && !xhtml.contains('\u{FFFE}'));
+}
+
+#[test]
+fn passthrough_epub_drops_control_characters_and_reports_them() {
+    let book = EpubBuilder::new("Controls")
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>clean</p>"))
+        .doc(Doc::new(
+            "text/ch2.xhtml",
+            "Two",
+            "<pre>ring\u{7}ing\u{FFFE}\tcolumn</pre>",
+        ))
+        .nav(vec![
+            Nav::new("One", "text/ch1.xhtml"),
+            Nav::new("Two", "text/ch2.xhtml"),
+        ])
+        .book();
+
+    for normalize in [false, true] {
+        let mut removed = Vec::new();
+        let mut out = Cursor::new(Vec::new());
+        EpubExporter::new()
+            .with_config(EpubConfig {
+                normalize,
+                ..Default::default()
+            })
+            .export_with_progress(&book, &mut out, &mut |event| {
+                if let ProgressEvent::CharactersRemoved { index, count } = event {
+                    removed.push((index, count));
+                }
+            })
+            .unwrap();
+        assert_eq!(removed, [(1, 2)], "normalize: {normalize}");
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(out.into_inner())).unwrap();
+        let mut xhtml = String::new();
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i).unwrap();
+            if file.name().ends_with(".xhtml") {
+                file.read_to_string(&mut xhtml).unwrap();
+            }
+        }
+        assert!(xhtml.contains("ringing\tcolumn"), "{xhtml}");
+        assert!(!xhtml.contains('\u{7}') && !xhtml.contains('\u{FFFE}'));
+    }
+
+    let mut removed = Vec::new();
+    book.export_with_progress(Format::Kfx, &mut Cursor::new(Vec::new()), &mut |event| {
+        if let ProgressEvent::CharactersRemoved { index, count } = event {
+            removed.push((index, count));
+        }
+    })
+    .unwrap();
+    assert_eq!(removed, [(1, 2)]);
+}
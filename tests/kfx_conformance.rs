@@ -16,68 +16,9 @@
 mod common;
 
 use boko::Format;
-use boko::kfx::container::{
-    extract_doc_symbols, parse_container_header, parse_container_info, parse_index_table,
-    skip_enty_header,
-};
-use boko::kfx::ion::{IonParser, IonValue};
-use boko::kfx::symbols::{KFX_SYMBOL_TABLE, KfxSymbol};
-
-/// Parse every entity of the given fragment type into Ion values.
-fn parse_entities(kfx: &[u8], type_id: u32) -> Vec<IonValue> {
-    let header = parse_container_header(&kfx[..18]).expect("container header");
-    let info = parse_container_info(
-        &kfx[header.container_info_offset
-            ..header.container_info_offset + header.container_info_length],
-    )
-    .expect("container info");
-    let (index_offset, index_length) = info.index.expect("index table");
-    let entities = parse_index_table(
-        &kfx[index_offset..index_offset + index_length],
-        header.header_len,
-    );
-
-    entities
-        .iter()
-        .filter(|loc| loc.type_id == type_id)
-        .filter_map(|loc| {
-            let entity = &kfx[loc.offset..loc.offset + loc.length];
-            IonParser::new(skip_enty_header(entity)).parse().ok()
-        })
-        .collect()
-}
-
-/// Document symbols (local symbol table) of the container.
-fn doc_symbols(kfx: &[u8]) -> Vec<String> {
-    let header = parse_container_header(&kfx[..18]).expect("container header");
-    let info = parse_container_info(
-        &kfx[header.container_info_offset
-            ..header.container_info_offset + header.container_info_length],
-    )
-    .expect("container info");
-    match info.doc_symbols {
-        Some((off, len)) if len > 0 => extract_doc_symbols(&kfx[off..off + len]),
-        _ => Vec::new(),
-    }
-}
-
-fn resolve_symbol(doc_symbols: &[String], id: u64) -> String {
-    let base = KFX_SYMBOL_TABLE.len() as u64;
-    if id < base {
-        KFX_SYMBOL_TABLE[id as usize].to_string()
-    } else {
-        doc_symbols
-            .get((id - base) as usize)
-            .cloned()
-            .unwrap_or_default()
-    }
-}
-
-fn get_field(fields: &[(u64, IonValue)], sym: KfxSymbol) -> Option<&IonValue> {
-    fields
-        .iter()
-        .find_map(|(k, v)| (*k == sym as u64).then_some(v))
-}
+use boko::kfx::ion::IonValue;
+use boko::kfx::symbols::KfxSymbol;
+use common::{epub_to_kfx, ion_field, kfx_doc_symbols, kfx_entities, kfx_symbol_name};
 
 /// A one-page PNG "cover" chapter plus a text chapter, with CSS that would
 /// previously leak image styling and percentage font sizes into the KFX.
@@ -109,31 +50,30 @@ fn build_test_book() -> Vec<u8> {
 #[test]
 fn scale_fit_images_reference_empty_style() {
     let epub = build_test_book();
-    let mut book = boko::Book::from_bytes(&epub, Format::Epub).expect("import epub");
-    let kfx = common::export_to_bytes(&mut book, Format::Kfx);
-    let symbols = doc_symbols(&kfx);
+    let kfx = epub_to_kfx(&epub);
+    let symbols = kfx_doc_symbols(&kfx);
 
     // Collect story names of scale-fit sections and the style of each
     // storyline image.
     let mut scale_fit_stories = std::collections::BTreeSet::new();
-    for section in parse_entities(&kfx, KfxSymbol::Section as u32) {
+    for section in kfx_entities(&kfx, KfxSymbol::Section) {
         let IonValue::Struct(fields) = &section else {
             continue;
         };
-        let Some(IonValue::List(templates)) = get_field(fields, KfxSymbol::PageTemplates) else {
+        let Some(IonValue::List(templates)) = ion_field(fields, KfxSymbol::PageTemplates) else {
             continue;
         };
         for template in templates {
             let IonValue::Struct(tf) = template else {
                 continue;
             };
-            let is_scale_fit = get_field(tf, KfxSymbol::Layout)
+            let is_scale_fit = ion_field(tf, KfxSymbol::Layout)
                 .and_then(|v| v.as_symbol())
                 .is_some_and(|s| s == KfxSymbol::ScaleFit as u64);
             if is_scale_fit
-                && let Some(story) = get_field(tf, KfxSymbol::StoryName).and_then(|v| v.as_symbol())
+                && let Some(story) = ion_field(tf, KfxSymbol::StoryName).and_then(|v| v.as_symbol())
             {
-                scale_fit_stories.insert(resolve_symbol(&symbols, story));
+                scale_fit_stories.insert(kfx_symbol_name(&symbols, story));
             }
         }
     }
@@ -144,29 +84,29 @@ fn scale_fit_images_reference_empty_style() {
 
     // Style names referenced by scale-fit storyline images.
     let mut image_styles = std::collections::BTreeSet::new();
-    for storyline in parse_entities(&kfx, KfxSymbol::Storyline as u32) {
+    for storyline in kfx_entities(&kfx, KfxSymbol::Storyline) {
         let IonValue::Struct(fields) = &storyline else {
             continue;
         };
-        let story = get_field(fields, KfxSymbol::StoryName)
+        let story = ion_field(fields, KfxSymbol::StoryName)
             .and_then(|v| v.as_symbol())
-            .map(|s| resolve_symbol(&symbols, s))
+            .map(|s| kfx_symbol_name(&symbols, s))
             .unwrap_or_default();
         if !scale_fit_stories.contains(&story) {
             continue;
         }
-        let Some(IonValue::List(content)) = get_field(fields, KfxSymbol::ContentList) else {
+        let Some(IonValue::List(content)) = ion_field(fields, KfxSymbol::ContentList) else {
             continue;
         };
         for node in content {
             let IonValue::Struct(nf) = node else { continue };
-            let is_image = get_field(nf, KfxSymbol::Type)
+            let is_image = ion_field(nf, KfxSymbol::Type)
                 .and_then(|v| v.as_symbol())
                 .is_some_and(|s| s == KfxSymbol::Image as u64);
             if is_image
-                && let Some(style) = get_field(nf, KfxSymbol::Style).and_then(|v| v.as_symbol())
+                && let Some(style) = ion_field(nf, KfxSymbol::Style).and_then(|v| v.as_symbol())
             {
-                image_styles.insert(resolve_symbol(&symbols, style));
+                image_styles.insert(kfx_symbol_name(&symbols, style));
             }
         }
     }
@@ -176,13 +116,13 @@ fn scale_fit_images_reference_empty_style() {
     );
 
     // Each referenced style must have no properties besides its name.
-    for style in parse_entities(&kfx, KfxSymbol::Style as u32) {
+    for style in kfx_entities(&kfx, KfxSymbol::Style) {
         let IonValue::Struct(fields) = &style else {
             continue;
         };
-        let name = get_field(fields, KfxSymbol::StyleName)
+        let name = ion_field(fields, KfxSymbol::StyleName)
             .and_then(|v| v.as_symbol())
-            .map(|s| resolve_symbol(&symbols, s))
+            .map(|s| kfx_symbol_name(&symbols, s))
             .unwrap_or_default();
         if image_styles.contains(&name) {
             assert_eq!(
@@ -200,19 +140,18 @@ fn scale_fit_images_reference_empty_style() {
 #[test]
 fn font_size_is_never_percent() {
     let epub = build_test_book();
-    let mut book = boko::Book::from_bytes(&epub, Format::Epub).expect("import epub");
-    let kfx = common::export_to_bytes(&mut book, Format::Kfx);
+    let kfx = epub_to_kfx(&epub);
 
     let mut saw_font_size = false;
-    for style in parse_entities(&kfx, KfxSymbol::Style as u32) {
+    for style in kfx_entities(&kfx, KfxSymbol::Style) {
         let IonValue::Struct(fields) = &style else {
             continue;
         };
-        let Some(IonValue::Struct(dim)) = get_field(fields, KfxSymbol::FontSize) else {
+        let Some(IonValue::Struct(dim)) = ion_field(fields, KfxSymbol::FontSize) else {
             continue;
         };
         saw_font_size = true;
-        let unit = get_field(dim, KfxSymbol::Unit).and_then(|v| v.as_symbol());
+        let unit = ion_field(dim, KfxSymbol::Unit).and_then(|v| v.as_symbol());
         assert_ne!(
             unit,
             Some(KfxSymbol::Percent as u64),
@@ -245,8 +184,7 @@ fn mathml_survives_into_kfx_as_text() {
         .nav(vec![Nav::new("One", "text/ch1.xhtml")])
         .build();
 
-    let mut book = boko::Book::from_bytes(&epub, Format::Epub).expect("import epub");
-    let kfx = common::export_to_bytes(&mut book, Format::Kfx);
+    let kfx = epub_to_kfx(&epub);
 
     let text = String::from_utf8_lossy(&kfx);
     if boko::math::kvg::MathFont::load_system().is_some() {
@@ -294,8 +232,7 @@ fn math_inside_span_is_not_dropped() {
         .nav(vec![Nav::new("One", "text/ch1.xhtml")])
         .build();
 
-    let mut book = boko::Book::from_bytes(&epub, Format::Epub).expect("import epub");
-    let kfx = common::export_to_bytes(&mut book, Format::Kfx);
+    let kfx = epub_to_kfx(&epub);
     let text = String::from_utf8_lossy(&kfx);
     // Span-nested math always stays inline readable text (a container can't
     // nest mid-style-event), regardless of font availability.
@@ -325,19 +262,18 @@ fn css_dropcap_becomes_native_kfx_dropcap() {
         .nav(vec![Nav::new("One", "text/ch1.xhtml")])
         .build();
 
-    let mut book = boko::Book::from_bytes(&epub, Format::Epub).expect("import epub");
-    let kfx = common::export_to_bytes(&mut book, Format::Kfx);
+    let kfx = epub_to_kfx(&epub);
 
     let mut lines = None;
     let mut chars = None;
-    for style in parse_entities(&kfx, KfxSymbol::Style as u32) {
+    for style in kfx_entities(&kfx, KfxSymbol::Style) {
         let IonValue::Struct(fields) = &style else {
             continue;
         };
-        if let Some(v) = get_field(fields, KfxSymbol::DropcapLines).and_then(|v| v.as_int()) {
+        if let Some(v) = ion_field(fields, KfxSymbol::DropcapLines).and_then(|v| v.as_int()) {
             lines = Some(v);
         }
-        if let Some(v) = get_field(fields, KfxSymbol::DropcapChars).and_then(|v| v.as_int()) {
+        if let Some(v) = ion_field(fields, KfxSymbol::DropcapChars).and_then(|v| v.as_int()) {
             chars = Some(v);
         }
     }
@@ -353,12 +289,12 @@ fn css_dropcap_becomes_native_kfx_dropcap() {
     );
 
     // No float property survives on any style — the dropcap replaces it.
-    for style in parse_entities(&kfx, KfxSymbol::Style as u32) {
+    for style in kfx_entities(&kfx, KfxSymbol::Style) {
         let IonValue::Struct(fields) = &style else {
             continue;
         };
         assert!(
-            get_field(fields, KfxSymbol::Float).is_none(),
+            ion_field(fields, KfxSymbol::Float).is_none(),
             "the dropcap span's float must be dropped"
         );
     }
@@ -383,9 +319,8 @@ fn box_align_rides_block_styles_not_style_events() {
         .nav(vec![Nav::new("One", "text/ch1.xhtml")])
         .build();
 
-    let mut book = boko::Book::from_bytes(&epub, Format::Epub).expect("import epub");
-    let kfx = common::export_to_bytes(&mut book, Format::Kfx);
-    let symbols = doc_symbols(&kfx);
+    let kfx = epub_to_kfx(&epub);
+    let symbols = kfx_doc_symbols(&kfx);
 
     // Styles referenced from style_events vs. from element style fields.
     let mut event_styles = std::collections::BTreeSet::new();
@@ -426,20 +361,20 @@ fn box_align_rides_block_styles_not_style_events() {
             _ => {}
         }
     }
-    for storyline in parse_entities(&kfx, KfxSymbol::Storyline as u32) {
+    for storyline in kfx_entities(&kfx, KfxSymbol::Storyline) {
         walk(&storyline, false, &mut event_styles, &mut block_styles);
     }
 
     // Which style names carry box_align?
     let mut box_align_styles = std::collections::BTreeSet::new();
-    for style in parse_entities(&kfx, KfxSymbol::Style as u32) {
+    for style in kfx_entities(&kfx, KfxSymbol::Style) {
         let IonValue::Struct(fields) = &style else {
             continue;
         };
-        if get_field(fields, KfxSymbol::BoxAlign).is_some()
-            && let Some(name) = get_field(fields, KfxSymbol::StyleName).and_then(|v| v.as_symbol())
+        if ion_field(fields, KfxSymbol::BoxAlign).is_some()
+            && let Some(name) = ion_field(fields, KfxSymbol::StyleName).and_then(|v| v.as_symbol())
         {
-            box_align_styles.insert(resolve_symbol(&symbols, name));
+            box_align_styles.insert(kfx_symbol_name(&symbols, name));
         }
     }
     assert!(
@@ -448,7 +383,7 @@ fn box_align_rides_block_styles_not_style_events() {
     );
 
     for style_sym in &event_styles {
-        let name = resolve_symbol(&symbols, *style_sym);
+        let name = kfx_symbol_name(&symbols, *style_sym);
         assert!(
             !box_align_styles.contains(&name),
             "style_event references box_align style {name}"
@@ -456,6 +391,126 @@ fn box_align_rides_block_styles_not_style_events() {
     }
 }
 
+/// `margin: 0 auto` centers a figure: the figure's block style carries
+/// `box_align: center` alongside its width, and the zero vertical margins
+/// don't turn into spacing.
+#[test]
+fn auto_margins_center_figures() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let epub = EpubBuilder::new("Figure Book")
+        .css("figure { margin: 0 auto; width: 50%; }")
+        .image("images/a.png", common::tiny_png())
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<figure><img src=\"../images/a.png\" alt=\"a\"/>\
+             <figcaption>caption</figcaption></figure>",
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build();
+
+    let kfx = epub_to_kfx(&epub);
+
+    let figure_style = kfx_entities(&kfx, KfxSymbol::Style)
+        .into_iter()
+        .find_map(|style| match style {
+            IonValue::Struct(fields) if ion_field(&fields, KfxSymbol::Width).is_some() => {
+                Some(fields)
+            }
+            _ => None,
+        })
+        .expect("figure style with a width");
+    assert_eq!(
+        ion_field(&figure_style, KfxSymbol::BoxAlign).and_then(|v| v.as_symbol()),
+        Some(KfxSymbol::Center as u64),
+        "{figure_style:?}"
+    );
+    assert!(
+        ion_field(&figure_style, KfxSymbol::MarginTop).is_none(),
+        "{figure_style:?}"
+    );
+}
+
+/// A paragraph's line-height reaches its inline runs by inheritance: the
+/// paragraph style carries it, and a bold style_event inside it must not
+/// override it (with its own value or a reset), so the leading is the same
+/// on both sides of the run boundary.
+#[test]
+fn paragraph_line_height_spans_inline_runs() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let filler = "Plain body text at the default leading. ".repeat(40);
+    let epub = EpubBuilder::new("Run Leading Book")
+        .css(".l { line-height: 1.8; }")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            &format!("<p>{filler}</p><p class=\"l\">before <b>bold run</b> after</p>"),
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build();
+
+    let kfx = epub_to_kfx(&epub);
+    let symbols = kfx_doc_symbols(&kfx);
+
+    let mut styles = std::collections::HashMap::new();
+    for style in kfx_entities(&kfx, KfxSymbol::Style) {
+        let IonValue::Struct(fields) = style else {
+            continue;
+        };
+        if let Some(name) = ion_field(&fields, KfxSymbol::StyleName).and_then(|v| v.as_symbol()) {
+            styles.insert(kfx_symbol_name(&symbols, name), fields);
+        }
+    }
+    let style_of = |v: &IonValue| -> Option<&Vec<(u64, IonValue)>> {
+        let IonValue::Struct(fields) = v else {
+            return None;
+        };
+        let sym = ion_field(fields, KfxSymbol::Style)?.as_symbol()?;
+        styles.get(&kfx_symbol_name(&symbols, sym))
+    };
+
+    let mut checked = false;
+    for storyline in kfx_entities(&kfx, KfxSymbol::Storyline) {
+        let IonValue::Struct(fields) = &storyline else {
+            continue;
+        };
+        let Some(IonValue::List(blocks)) = ion_field(fields, KfxSymbol::ContentList) else {
+            continue;
+        };
+        for block in blocks {
+            let IonValue::Struct(block_fields) = block else {
+                continue;
+            };
+            let Some(IonValue::List(events)) = ion_field(block_fields, KfxSymbol::StyleEvents)
+            else {
+                continue;
+            };
+            let para = style_of(block).expect("paragraph style");
+            let leading = ion_field(para, KfxSymbol::LineHeight);
+            assert!(
+                leading.is_some(),
+                "paragraph should carry line_height: {para:?}"
+            );
+            for event in events {
+                let run = style_of(event).expect("run style");
+                assert!(
+                    ion_field(run, KfxSymbol::FontWeight).is_some(),
+                    "bold run: {run:?}"
+                );
+                let run_leading = ion_field(run, KfxSymbol::LineHeight);
+                assert!(
+                    run_leading.is_none() || format!("{run_leading:?}") == format!("{leading:?}"),
+                    "run line_height {run_leading:?} differs from paragraph {leading:?}"
+                );
+                checked = true;
+            }
+        }
+    }
+    assert!(checked, "no paragraph with a styled run found");
+}
+
 /// Adjoining vertical margins must be collapsed statically, like Kindle
 /// Previewer output: the Kindle renderer does not collapse margins, so an
 /// uncollapsed `margin: 1em 0` paragraph sequence renders double gaps.
@@ -480,17 +535,16 @@ fn adjoining_margins_collapse_statically() {
         .nav(vec![Nav::new("One", "text/ch1.xhtml")])
         .build();
 
-    let mut book = boko::Book::from_bytes(&epub, Format::Epub).expect("import epub");
-    let kfx = common::export_to_bytes(&mut book, Format::Kfx);
+    let kfx = epub_to_kfx(&epub);
 
     // Collect every (margin-top, margin-bottom) pair from emitted styles,
     // in lh units.
     let dim = |fields: &[(u64, IonValue)], sym: KfxSymbol| -> Option<f64> {
-        let IonValue::Struct(d) = get_field(fields, sym)? else {
+        let IonValue::Struct(d) = ion_field(fields, sym)? else {
             return None;
         };
-        let value = get_field(d, KfxSymbol::Value)?;
-        let unit = get_field(d, KfxSymbol::Unit)?.as_symbol()?;
+        let value = ion_field(d, KfxSymbol::Value)?;
+        let unit = ion_field(d, KfxSymbol::Unit)?.as_symbol()?;
         assert_eq!(
             unit,
             KfxSymbol::Lh as u64,
@@ -507,7 +561,7 @@ fn adjoining_margins_collapse_statically() {
 
     let mut margin_bottoms = 0;
     let mut saw_collapsed_3em = false;
-    for style in parse_entities(&kfx, KfxSymbol::Style as u32) {
+    for style in kfx_entities(&kfx, KfxSymbol::Style) {
         let IonValue::Struct(fields) = &style else {
             continue;
         };
@@ -557,15 +611,14 @@ fn unset_margins_never_produce_box_align() {
         .nav(vec![Nav::new("One", "text/ch1.xhtml")])
         .build();
 
-    let mut book = boko::Book::from_bytes(&epub, Format::Epub).expect("import epub");
-    let kfx = common::export_to_bytes(&mut book, Format::Kfx);
+    let kfx = epub_to_kfx(&epub);
 
-    for style in parse_entities(&kfx, KfxSymbol::Style as u32) {
+    for style in kfx_entities(&kfx, KfxSymbol::Style) {
         let IonValue::Struct(fields) = &style else {
             continue;
         };
         assert!(
-            get_field(fields, KfxSymbol::BoxAlign).is_none(),
+            ion_field(fields, KfxSymbol::BoxAlign).is_none(),
             "no block in this book sets margin:auto, yet a style carries box_align"
         );
     }
@@ -587,22 +640,98 @@ fn empty_chapter_storyline_has_list_content_list() {
         ])
         .build();
 
-    let mut book = boko::Book::from_bytes(&epub, Format::Epub).expect("import epub");
-    let kfx = common::export_to_bytes(&mut book, Format::Kfx);
+    let kfx = epub_to_kfx(&epub);
 
-    let storylines = parse_entities(&kfx, KfxSymbol::Storyline as u32);
+    let storylines = kfx_entities(&kfx, KfxSymbol::Storyline);
     assert!(storylines.len() >= 2, "expected a storyline per chapter");
     for storyline in &storylines {
         let IonValue::Struct(fields) = storyline else {
             panic!("storyline is not a struct");
         };
-        match get_field(fields, KfxSymbol::ContentList) {
+        match ion_field(fields, KfxSymbol::ContentList) {
             Some(IonValue::List(_)) => {}
             other => panic!("content_list must be a list, got: {other:?}"),
         }
     }
 }
 
+/// With `single_section`, a chapter whose text would normally span several
+/// content fragments is emitted as exactly one.
+#[test]
+fn single_section_keeps_chapter_in_one_content_fragment() {
+    use boko::export::{Exporter, KfxConfig, KfxExporter};
+    use common::{Doc, EpubBuilder, Nav};
+
+    let body: String = (0..200)
+        .map(|i| format!("<p>Paragraph {i} of a long chapter that spans several chunks.</p>"))
+        .collect();
+    let epub = EpubBuilder::new("Long Chapter Book")
+        .doc(Doc::new("text/ch1.xhtml", "One", &body))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build();
+    let book = boko::Book::from_bytes(&epub, Format::Epub).expect("import epub");
+
+    let export = |config: KfxConfig| {
+        let mut buf = std::io::Cursor::new(Vec::new());
+        KfxExporter::new()
+            .with_config(config)
+            .export(&book, &mut buf)
+            .expect("kfx export");
+        buf.into_inner()
+    };
+
+    let chunked = export(KfxConfig::default());
+    assert!(
+        kfx_entities(&chunked, KfxSymbol::Content).len() > 1,
+        "test chapter should exceed one content chunk by default"
+    );
+
+    let single = export(KfxConfig {
+        single_section: true,
+        ..Default::default()
+    });
+    assert_eq!(kfx_entities(&single, KfxSymbol::Content).len(), 1);
+    assert_eq!(kfx_entities(&single, KfxSymbol::Section).len(), 1);
+}
+
+/// `language_override` replaces the book's `dc:language` in the emitted
+/// metadata, which is what the Kindle reads back as the content language.
+#[test]
+fn language_override_replaces_metadata_language() {
+    use boko::export::{Exporter, KfxConfig, KfxExporter};
+    use common::{Doc, EpubBuilder, Nav};
+
+    let epub = EpubBuilder::new("Mislabelled")
+        .language("fr")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<p>Colour and flavour.</p>",
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build();
+    let book = boko::Book::from_bytes(&epub, Format::Epub).expect("import epub");
+
+    let language = |config: KfxConfig| {
+        let mut buf = std::io::Cursor::new(Vec::new());
+        KfxExporter::new()
+            .with_config(config)
+            .export(&book, &mut buf)
+            .expect("kfx export");
+        let kfx = boko::Book::from_bytes(&buf.into_inner(), Format::Kfx).expect("import kfx");
+        kfx.metadata().language.clone()
+    };
+
+    assert_eq!(language(KfxConfig::default()), "fr");
+    assert_eq!(
+        language(KfxConfig {
+            language_override: Some("en-GB".to_string()),
+            ..Default::default()
+        }),
+        "en-GB"
+    );
+}
+
 /// A bordered image must still be emitted as an image element. The border
 /// container-wrapper assumes text content (its inner element is
 /// `type: text`), so wrapping an image swallowed it entirely — a childless
@@ -622,8 +751,7 @@ fn bordered_image_keeps_image_element() {
         .nav(vec![Nav::new("One", "text/ch1.xhtml")])
         .build();
 
-    let mut book = boko::Book::from_bytes(&epub, Format::Epub).expect("import epub");
-    let kfx = common::export_to_bytes(&mut book, Format::Kfx);
+    let kfx = epub_to_kfx(&epub);
 
     // Some node with type: image and a resource_name must exist, and no
     // container may carry a resource_name.
@@ -632,8 +760,8 @@ fn bordered_image_keeps_image_element() {
     fn walk(v: &IonValue, images: &mut u32, bad_containers: &mut u32) {
         match v {
             IonValue::Struct(fields) => {
-                let node_type = get_field(fields, KfxSymbol::Type).and_then(|v| v.as_symbol());
-                let has_resource = get_field(fields, KfxSymbol::ResourceName).is_some();
+                let node_type = ion_field(fields, KfxSymbol::Type).and_then(|v| v.as_symbol());
+                let has_resource = ion_field(fields, KfxSymbol::ResourceName).is_some();
                 if node_type == Some(KfxSymbol::Image as u64) && has_resource {
                     *images += 1;
                 }
@@ -649,7 +777,7 @@ fn bordered_image_keeps_image_element() {
             _ => {}
         }
     }
-    for storyline in parse_entities(&kfx, KfxSymbol::Storyline as u32) {
+    for storyline in kfx_entities(&kfx, KfxSymbol::Storyline) {
         walk(&storyline, &mut image_nodes, &mut containers_with_resource);
     }
     assert!(
@@ -679,15 +807,14 @@ fn visibility_encodes_as_boolean() {
         .nav(vec![Nav::new("One", "text/ch1.xhtml")])
         .build();
 
-    let mut book = boko::Book::from_bytes(&epub, Format::Epub).expect("import epub");
-    let kfx = common::export_to_bytes(&mut book, Format::Kfx);
+    let kfx = epub_to_kfx(&epub);
 
     let mut saw_visibility = false;
-    for style in parse_entities(&kfx, KfxSymbol::Style as u32) {
+    for style in kfx_entities(&kfx, KfxSymbol::Style) {
         let IonValue::Struct(fields) = &style else {
             continue;
         };
-        if let Some(value) = get_field(fields, KfxSymbol::Visibility) {
+        if let Some(value) = ion_field(fields, KfxSymbol::Visibility) {
             saw_visibility = true;
             assert!(
                 matches!(value, IonValue::Bool(false)),
@@ -701,6 +828,232 @@ fn visibility_encodes_as_boolean() {
     );
 }
 
+/// `overflow: hidden` on a fixed-size box (a cropped image frame) must
+/// reach KFX as the boolean `overflow` clip property; without a fixed size
+/// nothing can overflow and the property is left out.
+#[test]
+fn overflow_hidden_with_fixed_height_clips() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let epub = EpubBuilder::new("Overflow Book")
+        .css(
+            ".crop { overflow: hidden; height: 10em; } \
+             .loose { overflow: hidden; }",
+        )
+        .image("images/shot.png", common::tiny_png())
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<div class=\"crop\"><img src=\"../images/shot.png\" alt=\"shot\"/></div>\
+             <div class=\"loose\"><p>text</p></div>",
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build();
+
+    let kfx = epub_to_kfx(&epub);
+
+    let mut clipped = Vec::new();
+    for style in kfx_entities(&kfx, KfxSymbol::Style) {
+        let IonValue::Struct(fields) = &style else {
+            continue;
+        };
+        if let Some(value) = ion_field(fields, KfxSymbol::Overflow) {
+            assert!(
+                matches!(value, IonValue::Bool(true)),
+                "overflow: hidden must encode as Ion bool true, got: {value:?}"
+            );
+            clipped.push(ion_field(fields, KfxSymbol::Height).is_some());
+        }
+    }
+    assert_eq!(
+        clipped,
+        [true],
+        "only the fixed-height container clips, alongside its height"
+    );
+}
+
+/// Hanging indents (bibliographies): a negative `text-indent` paired with
+/// left padding must reach the KFX style as a negative em value, not be
+/// clamped to zero or dropped.
+#[test]
+fn negative_text_indent_survives_as_hanging_indent() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let epub = EpubBuilder::new("Bibliography Book")
+        .css(".bib { text-indent: -2em; padding-left: 2em; }")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "Works Cited",
+            "<p class=\"bib\">Author, A. A long citation that wraps onto a hanging line.</p>",
+        ))
+        .nav(vec![Nav::new("Works Cited", "text/ch1.xhtml")])
+        .build();
+
+    let kfx = epub_to_kfx(&epub);
+
+    let dim_value = |dim: &[(u64, IonValue)]| -> Option<(f64, Option<u64>)> {
+        let value = match ion_field(dim, KfxSymbol::Value)? {
+            IonValue::Decimal(s) => s.parse().ok()?,
+            IonValue::Int(i) => *i as f64,
+            IonValue::Float(f) => *f,
+            _ => return None,
+        };
+        Some((
+            value,
+            ion_field(dim, KfxSymbol::Unit).and_then(|v| v.as_symbol()),
+        ))
+    };
+
+    let mut saw_hanging = false;
+    for style in kfx_entities(&kfx, KfxSymbol::Style) {
+        let IonValue::Struct(fields) = &style else {
+            continue;
+        };
+        let Some(IonValue::Struct(indent)) = ion_field(fields, KfxSymbol::TextIndent) else {
+            continue;
+        };
+        let (value, unit) = dim_value(indent).expect("text_indent dimension");
+        assert_eq!(unit, Some(KfxSymbol::Em as u64), "{indent:?}");
+        assert!(
+            (value + 2.0).abs() < 1e-6,
+            "text_indent should be -2em: {indent:?}"
+        );
+        let padding = match ion_field(fields, KfxSymbol::PaddingLeft) {
+            Some(IonValue::Struct(p)) => dim_value(p),
+            _ => None,
+        };
+        assert!(
+            padding.is_some_and(|(v, _)| v > 0.0),
+            "hanging indent should keep its left padding: {fields:?}"
+        );
+        saw_hanging = true;
+    }
+    assert!(saw_hanging, "no style carried the negative text-indent");
+}
+
+/// `currentColor` in a border resolves to the element's text color, so a
+/// red paragraph with a `currentColor` border emits red border colors.
+#[test]
+fn current_color_border_takes_text_color() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let epub = EpubBuilder::new("Current Color Book")
+        .css(".b { border: 1px solid currentColor; color: #ff0000; }")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<p class=\"b\">boxed in red</p>",
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build();
+
+    let kfx = epub_to_kfx(&epub);
+
+    const RED: i64 = 0xFFFF0000;
+    let mut saw_border = false;
+    for style in kfx_entities(&kfx, KfxSymbol::Style) {
+        let IonValue::Struct(fields) = &style else {
+            continue;
+        };
+        // Uniform sides collapse into the shorthand `border_color`.
+        let Some(top) = ion_field(fields, KfxSymbol::BorderColor)
+            .or_else(|| ion_field(fields, KfxSymbol::BorderColorTop))
+        else {
+            continue;
+        };
+        saw_border = true;
+        assert!(
+            matches!(top, IonValue::Int(c) if *c == RED),
+            "border color should be the text color, got: {top:?}"
+        );
+        assert!(
+            matches!(ion_field(fields, KfxSymbol::TextColor), Some(IonValue::Int(c)) if *c == RED),
+            "{fields:?}"
+        );
+    }
+    assert!(saw_border, "no style carried the currentColor border");
+}
+
+/// Unitless, percent, and em line-heights all reach the KFX style as lh
+/// (1lh = the 1.2em base line box), and `normal` under a spaced ancestor
+/// resets to 1lh instead of being dropped. They differ in inheritance: a
+/// unitless factor scales with a descendant's font, while `150%`/`1.5em`
+/// are lengths of the declaring element's font. A 2× descendant would get
+/// 0.75em of its own font; display-size text falls back to normal leading
+/// instead of being squeezed under the base line box.
+#[test]
+fn line_height_forms_reach_kfx_distinctly() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let filler = "Plain body text at the default leading. ".repeat(40);
+    let epub = EpubBuilder::new("Leading Book")
+        .css(
+            ".u { line-height: 1.5; } .p { line-height: 150%; } \
+             .e { line-height: 1.5em; } .n { line-height: normal; } \
+             .big { font-size: 2em; }",
+        )
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            &format!(
+                "<p>{filler}</p>\
+                 <div class=\"u\"><p>unitless</p><p class=\"big\">unitless big</p></div>\
+                 <div class=\"p\"><p>percent</p><p class=\"big\">percent big</p></div>\
+                 <div class=\"e\"><p>em</p><p class=\"big\">em big</p></div>\
+                 <div class=\"u\"><p class=\"n\">normal</p></div>"
+            ),
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build();
+
+    let kfx = epub_to_kfx(&epub);
+
+    let lh = |fields: &[(u64, IonValue)]| -> Option<f64> {
+        let Some(IonValue::Struct(dim)) = ion_field(fields, KfxSymbol::LineHeight) else {
+            return None;
+        };
+        assert_eq!(
+            ion_field(dim, KfxSymbol::Unit).and_then(|v| v.as_symbol()),
+            Some(KfxSymbol::Lh as u64),
+            "{dim:?}"
+        );
+        match ion_field(dim, KfxSymbol::Value)? {
+            IonValue::Decimal(s) => s.parse().ok(),
+            IonValue::Int(i) => Some(*i as f64),
+            IonValue::Float(f) => Some(*f),
+            _ => None,
+        }
+    };
+
+    let mut values = Vec::new();
+    let mut big_without_lh = false;
+    for style in kfx_entities(&kfx, KfxSymbol::Style) {
+        let IonValue::Struct(fields) = &style else {
+            continue;
+        };
+        match lh(fields) {
+            Some(v) => values.push(v),
+            None => big_without_lh |= ion_field(fields, KfxSymbol::FontSize).is_some(),
+        }
+    }
+    let saw = |want: f64| values.iter().any(|v| (v - want).abs() < 1e-3);
+    // 1.5 / 1.2 for the unitless, percent, and em containers.
+    assert!(saw(1.25), "1.5 leading should emit 1.25lh: {values:?}");
+    // `normal` under the unitless container resets to the base line box.
+    assert!(saw(1.0), "line-height: normal should emit 1lh: {values:?}");
+    // 1.5em of the container is 0.75em of a 2× font: normal, not the
+    // 0.99lh floor.
+    assert!(
+        !saw(0.99),
+        "inherited lengths should not squeeze 2× text: {values:?}"
+    );
+    // The unitless factor carries over to the big paragraph unchanged.
+    assert!(
+        big_without_lh,
+        "unitless leading should inherit as a factor"
+    );
+}
+
 /// Consecutive empty anchor targets must not produce anchor positions with
 /// offsets into dropped marker text. An empty target element emits no
 /// content; a second anchor in the same run used to sit at offset 1 past
@@ -720,8 +1073,7 @@ fn anchors_into_empty_targets_have_zero_offset() {
         .nav(vec![Nav::new("One", "text/ch1.xhtml")])
         .build();
 
-    let mut book = boko::Book::from_bytes(&epub, Format::Epub).expect("import epub");
-    let kfx = common::export_to_bytes(&mut book, Format::Kfx);
+    let kfx = epub_to_kfx(&epub);
 
     // Content-bearing eids (those with a content ref or style events have
     // locatable text; anchors may carry offsets only into those).
@@ -729,9 +1081,9 @@ fn anchors_into_empty_targets_have_zero_offset() {
     fn collect_text_eids(v: &IonValue, out: &mut std::collections::BTreeSet<i64>) {
         match v {
             IonValue::Struct(fields) => {
-                let id = get_field(fields, KfxSymbol::Id).and_then(|v| v.as_int());
+                let id = ion_field(fields, KfxSymbol::Id).and_then(|v| v.as_int());
                 if let Some(id) = id
-                    && get_field(fields, KfxSymbol::Content).is_some()
+                    && ion_field(fields, KfxSymbol::Content).is_some()
                 {
                     out.insert(id);
                 }
@@ -744,22 +1096,22 @@ fn anchors_into_empty_targets_have_zero_offset() {
             _ => {}
         }
     }
-    for storyline in parse_entities(&kfx, KfxSymbol::Storyline as u32) {
+    for storyline in kfx_entities(&kfx, KfxSymbol::Storyline) {
         collect_text_eids(&storyline, &mut text_eids);
     }
 
     let mut checked = 0;
-    for anchor in parse_entities(&kfx, KfxSymbol::Anchor as u32) {
+    for anchor in kfx_entities(&kfx, KfxSymbol::Anchor) {
         let IonValue::Struct(fields) = &anchor else {
             continue;
         };
-        let Some(IonValue::Struct(pos)) = get_field(fields, KfxSymbol::Position) else {
+        let Some(IonValue::Struct(pos)) = ion_field(fields, KfxSymbol::Position) else {
             continue;
         };
-        let id = get_field(pos, KfxSymbol::Id)
+        let id = ion_field(pos, KfxSymbol::Id)
             .and_then(|v| v.as_int())
             .unwrap_or(-1);
-        let offset = get_field(pos, KfxSymbol::Offset)
+        let offset = ion_field(pos, KfxSymbol::Offset)
             .and_then(|v| v.as_int())
             .unwrap_or(0);
         checked += 1;
@@ -794,32 +1146,32 @@ fn identifierless_book_gets_content_id() {
             "fixture must lack an identifier"
         );
         let kfx = common::export_to_bytes(&mut book, Format::Kfx);
-        let symbols = doc_symbols(&kfx);
-        for meta in parse_entities(&kfx, KfxSymbol::BookMetadata as u32) {
+        let symbols = kfx_doc_symbols(&kfx);
+        for meta in kfx_entities(&kfx, KfxSymbol::BookMetadata) {
             let IonValue::Struct(fields) = &meta else {
                 continue;
             };
-            let Some(IonValue::List(cats)) = get_field(fields, KfxSymbol::CategorisedMetadata)
+            let Some(IonValue::List(cats)) = ion_field(fields, KfxSymbol::CategorisedMetadata)
             else {
                 continue;
             };
             for cat in cats {
                 let IonValue::Struct(cf) = cat else { continue };
-                let Some(IonValue::List(entries)) = get_field(cf, KfxSymbol::Metadata) else {
+                let Some(IonValue::List(entries)) = ion_field(cf, KfxSymbol::Metadata) else {
                     continue;
                 };
                 for entry in entries {
                     let IonValue::Struct(ef) = entry else {
                         continue;
                     };
-                    let key = get_field(ef, KfxSymbol::Key);
+                    let key = ion_field(ef, KfxSymbol::Key);
                     let is_content_id = match key {
                         Some(IonValue::String(s)) => s == "content_id",
-                        Some(IonValue::Symbol(s)) => resolve_symbol(&symbols, *s) == "content_id",
+                        Some(IonValue::Symbol(s)) => kfx_symbol_name(&symbols, *s) == "content_id",
                         _ => false,
                     };
                     if is_content_id
-                        && let Some(IonValue::String(v)) = get_field(ef, KfxSymbol::Value)
+                        && let Some(IonValue::String(v)) = ion_field(ef, KfxSymbol::Value)
                     {
                         return v.clone();
                     }
@@ -878,3 +1230,228 @@ fn kfxcheck_reports_no_errors() {
         "kfxcheck reported errors:\n{stdout}"
     );
 }
+
+/// KFX has no image list markers: an image-only bullet (`list-style: none
+/// url(...)`) falls back to a disc instead of losing its marker.
+#[test]
+fn list_style_image_falls_back_to_disc() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let epub = EpubBuilder::new("Marker Image Book")
+        .css("li { list-style: none url(../images/star.png); }")
+        .image("images/star.png", common::tiny_png())
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<ul><li>starred</li><li>also starred</li></ul>",
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build();
+
+    let kfx = epub_to_kfx(&epub);
+
+    let list_styles: Vec<String> = kfx_entities(&kfx, KfxSymbol::Style)
+        .iter()
+        .filter_map(|style| match style {
+            IonValue::Struct(fields) => ion_field(fields, KfxSymbol::ListStyle),
+            _ => None,
+        })
+        .map(|value| format!("{value:?}"))
+        .collect();
+    let disc = format!("{:?}", IonValue::Symbol(KfxSymbol::Disc as u64));
+    assert!(
+        list_styles.contains(&disc),
+        "expected a disc marker, got {list_styles:?}"
+    );
+}
+
+/// Vertical Japanese books turn pages right to left. A `vertical-rl` body
+/// implies that even when the OPF has no `page-progression-direction`.
+#[test]
+fn vertical_rl_body_turns_pages_right_to_left() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let book = |css: &str| {
+        EpubBuilder::new("Tategaki")
+            .css(css)
+            .doc(Doc::new("text/ch1.xhtml", "One", "<p>吾輩は猫である。</p>"))
+            .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+    };
+
+    assert_eq!(
+        direction_and_writing_mode(book(VERTICAL_RL)),
+        (
+            Some(KfxSymbol::Rtl as u64),
+            Some(KfxSymbol::VerticalRl as u64)
+        )
+    );
+    assert_eq!(
+        direction_and_writing_mode(book("body { margin: 0; }")),
+        (
+            Some(KfxSymbol::Ltr as u64),
+            Some(KfxSymbol::HorizontalTb as u64)
+        )
+    );
+    // An explicit OPF direction wins over the inferred one.
+    assert_eq!(
+        direction_and_writing_mode(book(VERTICAL_RL).direction("ltr")),
+        (
+            Some(KfxSymbol::Ltr as u64),
+            Some(KfxSymbol::VerticalRl as u64)
+        )
+    );
+}
+
+/// One vertical chapter in a horizontal book (a poem set tategaki) doesn't
+/// turn the whole book's pages right to left.
+#[test]
+fn a_minority_of_vertical_chapters_keeps_the_book_horizontal() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let vertical = format!("<style>{VERTICAL_RL}</style>");
+    let epub = EpubBuilder::new("Anthology")
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>Prose.</p>"))
+        .doc(Doc::new("text/ch2.xhtml", "Two", "<p>古池や</p>").head(&vertical))
+        .doc(Doc::new("text/ch3.xhtml", "Three", "<p>More prose.</p>"))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")]);
+    assert_eq!(
+        direction_and_writing_mode(epub),
+        (
+            Some(KfxSymbol::Ltr as u64),
+            Some(KfxSymbol::HorizontalTb as u64)
+        )
+    );
+}
+
+const VERTICAL_RL: &str = "body { -epub-writing-mode: vertical-rl; writing-mode: vertical-rl; }";
+
+/// The `direction` and `writing_mode` symbols of the KFX document_data
+/// exported from `epub`.
+fn direction_and_writing_mode(epub: common::EpubBuilder) -> (Option<u64>, Option<u64>) {
+    let kfx = epub_to_kfx(&epub.build());
+    let Some(IonValue::Struct(fields)) = kfx_entities(&kfx, KfxSymbol::DocumentData)
+        .into_iter()
+        .next()
+    else {
+        panic!("missing document_data");
+    };
+    let symbol = |sym| ion_field(&fields, sym).and_then(|v| v.as_symbol());
+    (symbol(KfxSymbol::Direction), symbol(KfxSymbol::WritingMode))
+}
+
+/// `text-align: start`/`end` follow the element's direction: in an RTL
+/// document `start` is the right edge, not KFX's LTR-only `left`.
+#[test]
+fn text_align_start_resolves_by_direction() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let alignments = |doc: Doc| {
+        let epub = EpubBuilder::new("Direction")
+            .css("p { text-align: start; } p.end { text-align: end; }")
+            .doc(doc)
+            .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+            .build();
+        let kfx = epub_to_kfx(&epub);
+        kfx_entities(&kfx, KfxSymbol::Style)
+            .iter()
+            .filter_map(|style| match style {
+                IonValue::Struct(fields) => {
+                    ion_field(fields, KfxSymbol::TextAlignment).and_then(|v| v.as_symbol())
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+    let body = "<p>مرحبا</p><p class=\"end\">بالعالم</p>";
+
+    let rtl = alignments(Doc::new("text/ch1.xhtml", "One", body).lang_dir("ar", "rtl"));
+    assert!(rtl.contains(&(KfxSymbol::Right as u64)), "{rtl:?}");
+    assert!(rtl.contains(&(KfxSymbol::Left as u64)), "{rtl:?}");
+
+    let ltr = alignments(Doc::new("text/ch1.xhtml", "One", body));
+    assert!(ltr.contains(&(KfxSymbol::Right as u64)), "{ltr:?}");
+    assert!(!ltr.contains(&(KfxSymbol::Left as u64)), "{ltr:?}");
+}
+
+/// `table-layout: fixed` stays in the IR but isn't emitted: KFX has no
+/// table layout property to carry it.
+#[test]
+fn table_layout_fixed_is_not_emitted_to_kfx() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let epub = EpubBuilder::new("Tables")
+        .css("table { table-layout: fixed; width: 100%; }")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<table><tr><td>Narrow</td><td>A much longer second cell</td></tr></table>",
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build();
+    let kfx = epub_to_kfx(&epub);
+    let layouts = kfx_entities(&kfx, KfxSymbol::Style)
+        .iter()
+        .filter(|style| match style {
+            IonValue::Struct(fields) => ion_field(fields, KfxSymbol::Layout).is_some(),
+            _ => false,
+        })
+        .count();
+    assert_eq!(layouts, 0);
+}
+
+/// `display: flex`/`grid`/`table` on non-table elements export as plain
+/// block containers, each child keeping its own content entry;
+/// `inline-block` stays inline in its paragraph's text.
+#[test]
+fn layout_display_containers_export_as_blocks() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let epub = EpubBuilder::new("Layouts")
+        .css(
+            ".flex { display: flex; } .grid { display: grid; } \
+             .table { display: table; } .cell { display: table-cell; } \
+             .box { display: inline-block; border: 1px solid black; }",
+        )
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<div class=\"flex\"><p>Flex one</p><p>Flex two</p></div>\
+             <div class=\"grid\"><p>Grid one</p><p>Grid two</p></div>\
+             <div class=\"table\"><div class=\"cell\">Cell one</div>\
+             <div class=\"cell\">Cell two</div></div>\
+             <p>Before <span class=\"box\">boxed</span> after.</p>",
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build();
+    let kfx = epub_to_kfx(&epub);
+
+    let texts: Vec<String> = kfx_entities(&kfx, KfxSymbol::Content)
+        .iter()
+        .filter_map(|content| match content {
+            IonValue::Struct(fields) => match ion_field(fields, KfxSymbol::ContentList)? {
+                IonValue::List(items) => Some(items.clone()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|item| match item {
+            IonValue::String(text) => Some(text),
+            _ => None,
+        })
+        .collect();
+    for expected in [
+        "Flex one",
+        "Flex two",
+        "Grid one",
+        "Grid two",
+        "Cell one",
+        "Cell two",
+        "Before boxed after.",
+    ] {
+        assert!(
+            texts.iter().any(|text| text == expected),
+            "{expected:?} should be its own content entry: {texts:?}"
+        );
+    }
+}
@@ -0,0 +1,90 @@
+//! Heading line spacing in KFX output relative to body paragraphs, for each
+//! way a book can set its body leading.
+
+mod common;
+
+use boko::style::Length;
+use boko::{Book, Chapter, Format, Role};
+use common::{Doc, EpubBuilder, Nav};
+
+/// Line box height in root ems of the first node with `role`, as the KFX
+/// importer reads it back (font size in rem, line-height in em of the
+/// node's own font or `normal`).
+fn line_spacing(chapter: &Chapter, role: Role) -> f32 {
+    let id = chapter
+        .iter_dfs()
+        .find(|&id| chapter.node(id).unwrap().role == role)
+        .unwrap();
+    let style = chapter.styles.get(chapter.node(id).unwrap().style).unwrap();
+    let font = match style.font_size {
+        Length::Rem(x) => x,
+        Length::Auto => 1.0,
+        other => panic!("unexpected font size {other:?}"),
+    };
+    let line = match style.line_height {
+        Length::Auto => 1.2,
+        Length::Em(x) => x,
+        other => panic!("unexpected line height {other:?}"),
+    };
+    font * line
+}
+
+#[test]
+fn headings_keep_normal_leading_under_body_line_heights() {
+    let body = "Body text long enough to set the book's dominant metrics. ".repeat(20);
+    for css in [
+        "",
+        "body { line-height: 1.5 }",
+        "body { line-height: 1.5em }",
+        "body { line-height: 150% }",
+        "body { line-height: 24px }",
+    ] {
+        let mut book = EpubBuilder::new("Leading")
+            .css(css)
+            .doc(Doc::new(
+                "text/ch1.xhtml",
+                "One",
+                &format!("<h1>A heading</h1><p>{body}</p>"),
+            ))
+            .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+            .book();
+        let kfx = Book::from_bytes(
+            &common::export_to_bytes(&mut book, Format::Kfx),
+            Format::Kfx,
+        )
+        .unwrap();
+        let chapter = kfx.load_chapter(kfx.spine()[0].id).unwrap();
+
+        // The UA `h1 { font-size: 2em }` leaves the heading's lines twice
+        // the paragraph's: neither squeezed by the body's length nor
+        // stretched by it.
+        let ratio =
+            line_spacing(&chapter, Role::Heading(1)) / line_spacing(&chapter, Role::Paragraph);
+        assert!((ratio - 2.0).abs() < 0.05, "{css:?}: ratio {ratio}");
+    }
+}
+
+#[test]
+fn explicit_heading_line_height_is_kept() {
+    let body = "Body text long enough to set the book's dominant metrics. ".repeat(20);
+    let mut book = EpubBuilder::new("Leading")
+        .css("body { line-height: 1.5em } h1 { line-height: 1rem }")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            &format!("<h1>A heading</h1><p>{body}</p>"),
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+    let kfx = Book::from_bytes(
+        &common::export_to_bytes(&mut book, Format::Kfx),
+        Format::Kfx,
+    )
+    .unwrap();
+    let chapter = kfx.load_chapter(kfx.spine()[0].id).unwrap();
+
+    // The author's tight leading survives (down to the line-box floor)
+    // rather than being refitted to twice the paragraph's.
+    let ratio = line_spacing(&chapter, Role::Heading(1)) / line_spacing(&chapter, Role::Paragraph);
+    assert!(ratio < 1.8, "ratio {ratio}");
+}
@@ -0,0 +1,53 @@
+//! KFX export gives images without CSS dimensions their intrinsic width.
+
+mod common;
+
+use boko::model::Role;
+use boko::style::Length;
+use boko::{Book, Format};
+use common::{Doc, EpubBuilder, Nav};
+
+/// A PNG header declaring a 300x200 image; only the header is read.
+fn png_300x200() -> Vec<u8> {
+    let mut png = common::tiny_png();
+    png[16..20].copy_from_slice(&300u32.to_be_bytes());
+    png[20..24].copy_from_slice(&200u32.to_be_bytes());
+    png
+}
+
+/// Width and max-width of each image in the first chapter, in order.
+fn image_widths(book: &Book) -> Vec<(Length, Length)> {
+    let chapter = book.load_chapter(book.spine()[0].id).unwrap();
+    chapter
+        .iter_dfs()
+        .filter(|&id| chapter.node(id).unwrap().role == Role::Image)
+        .map(|id| {
+            let style = chapter.styles.get(chapter.node(id).unwrap().style).unwrap();
+            (style.width, style.max_width)
+        })
+        .collect()
+}
+
+#[test]
+fn unsized_images_get_their_intrinsic_width() {
+    let mut book = EpubBuilder::new("Sizes")
+        .image("images/plain.png", png_300x200())
+        .image("images/styled.png", png_300x200())
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            r#"<p>Text before the figures.</p>
+            <p><img src="../images/plain.png" alt="plain"/></p>
+            <p><img src="../images/styled.png" alt="styled" style="width: 50%"/></p>"#,
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+
+    let kfx = common::export_to_bytes(&mut book, Format::Kfx);
+    let kfx = Book::from_bytes(&kfx, Format::Kfx).unwrap();
+    let widths = image_widths(&kfx);
+    assert_eq!(widths.len(), 2, "{widths:?}");
+    assert_eq!(widths[0], (Length::Px(300.0), Length::Percent(100.0)));
+    assert_eq!(widths[1].0, Length::Percent(50.0));
+    assert_ne!(widths[1].1, Length::Percent(100.0));
+}
@@ -0,0 +1,171 @@
+//! Chapter-at-a-time KFX → EPUB conversion (`EpubConfig::low_memory`).
+//!
+//! A counting global allocator records the peak heap use of each export, so
+//! this binary holds a single test: parallel tests would pollute the count.
+
+mod common;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::Cursor;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use boko::export::{EpubConfig, EpubExporter, Exporter};
+use boko::{Book, Format, Role};
+use common::{Doc, EpubBuilder, Nav};
+
+struct PeakAlloc;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let now = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(now, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: PeakAlloc = PeakAlloc;
+
+const CHAPTERS: usize = 40;
+
+/// Heap bytes allocated at the peak of `f`, above what was live before it.
+fn peak_during(f: impl FnOnce()) -> usize {
+    let base = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(base, Ordering::Relaxed);
+    f();
+    PEAK.load(Ordering::Relaxed) - base
+}
+
+/// A 256×256 noise PNG (~190 KiB): deflate can't shrink it, so each
+/// chapter's image outweighs its text.
+fn noise_png(seed: u32) -> Vec<u8> {
+    let mut state = seed | 1;
+    let img = image::RgbImage::from_fn(256, 256, |_, _| {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        let [r, g, b, _] = state.to_le_bytes();
+        image::Rgb([r, g, b])
+    });
+    let mut buf = Cursor::new(Vec::new());
+    img.write_to(&mut buf, image::ImageFormat::Png).unwrap();
+    buf.into_inner()
+}
+
+fn export_epub(book: &Book, low_memory: bool, path: &std::path::Path) {
+    let mut file = std::fs::File::create(path).unwrap();
+    EpubExporter::new()
+        .with_config(EpubConfig {
+            low_memory,
+            ..Default::default()
+        })
+        .export(book, &mut file)
+        .unwrap();
+}
+
+#[test]
+fn low_memory_kfx_to_epub_is_bounded_and_complete() {
+    let mut builder = EpubBuilder::new("Streaming Atlas");
+    let mut nav = Vec::new();
+    let mut image_bytes = 0;
+    for i in 0..CHAPTERS {
+        let file = format!("text/ch{i:02}.xhtml");
+        let image = format!("images/plate{i:02}.png");
+        let png = noise_png(i as u32 * 7919);
+        image_bytes += png.len();
+        builder = builder.image(&image, png);
+        let prose = format!(
+            "<p>{}</p>",
+            format!("Plate {i} shows the coast at dawn. ").repeat(12)
+        )
+        .repeat(80);
+        let body = format!(
+            "<h1 id=\"c{i}\">Plate {i}</h1>{prose}\
+             <p><img src=\"../{image}\" alt=\"Plate {i}\"/></p>\
+             <p>See <a href=\"ch{next:02}.xhtml#c{next}\">the next plate</a>.</p>",
+            next = (i + 1) % CHAPTERS
+        );
+        builder = builder.doc(Doc::new(&file, &format!("Plate {i}"), &body));
+        nav.push(Nav::new(&format!("Plate {i}"), &file));
+    }
+    let mut source = builder.nav(nav).book();
+    let kfx = common::export_to_bytes(&mut source, Format::Kfx);
+    drop(source);
+
+    let dir = tempfile::tempdir().unwrap();
+    let kfx_path = dir.path().join("atlas.kfx");
+    std::fs::write(&kfx_path, kfx).unwrap();
+
+    let in_memory_path = dir.path().join("in-memory.epub");
+    let streamed_path = dir.path().join("streamed.epub");
+    let in_memory_peak = peak_during(|| {
+        let book = Book::open(&kfx_path).unwrap();
+        export_epub(&book, false, &in_memory_path);
+    });
+    let streamed_peak = peak_during(|| {
+        let book = Book::open(&kfx_path).unwrap();
+        export_epub(&book, true, &streamed_path);
+    });
+
+    // One chapter plus one image, not the whole book: the in-memory export
+    // holds every chapter's IR and markup at once.
+    assert!(
+        streamed_peak < image_bytes / 3,
+        "streamed peak {streamed_peak} vs {image_bytes} bytes of images"
+    );
+    assert!(
+        streamed_peak * 3 < in_memory_peak,
+        "streamed peak {streamed_peak} vs in-memory peak {in_memory_peak}"
+    );
+
+    let streamed = std::fs::read(&streamed_path).unwrap();
+    let mut book = Book::from_bytes(&streamed, Format::Epub).unwrap();
+    assert!(book.validate().is_empty(), "{:?}", book.validate());
+    assert_eq!(book.chapter_count(), CHAPTERS);
+    assert_eq!(book.toc().len(), CHAPTERS);
+
+    let spine: Vec<_> = book.spine().iter().map(|entry| entry.id).collect();
+    for (i, id) in spine.into_iter().enumerate() {
+        let chapter = book.load_chapter(id).unwrap();
+        let images = chapter
+            .iter_dfs()
+            .filter(|&node| chapter.node(node).is_some_and(|n| n.role == Role::Image))
+            .count();
+        assert_eq!(images, 1, "chapter {i} keeps its plate");
+    }
+    let images = book
+        .list_assets()
+        .iter()
+        .filter(|path| {
+            book.resource(path)
+                .is_ok_and(|r| r.media_type().starts_with("image/"))
+        })
+        .count();
+    assert_eq!(images, CHAPTERS);
+
+    let text = String::from_utf8(common::export_to_bytes(&mut book, Format::Markdown)).unwrap();
+    for i in 0..CHAPTERS {
+        assert!(text.contains(&format!("Plate {i} shows the coast")), "{i}");
+    }
+
+    // Same content as the in-memory export, entry for entry.
+    let in_memory = std::fs::read(&in_memory_path).unwrap();
+    let names = |epub: Vec<u8>| {
+        let zip = zip::ZipArchive::new(Cursor::new(epub)).unwrap();
+        let mut names: Vec<String> = zip.file_names().map(str::to_string).collect();
+        names.sort();
+        names
+    };
+    assert_eq!(names(streamed), names(in_memory));
+}
@@ -1,6 +1,7 @@
 //! KFX must preserve table cell spans and ordered-list start values.
 //!
-//! These live in the IR `SemanticMap` (`col_span`, `row_span`, `list_start`)
+//! These live in the IR `SemanticMap` (`col_span`, `row_span`, `list_start`,
+//! `list_value`)
 //! and are emitted as Ion integers on the storyline element. Before the
 //! carriers existed, KFX export dropped them: spanned cells collapsed to 1x1
 //! and `<ol start=N>` lost its numbering.
@@ -22,7 +23,7 @@ fn kfx_preserves_table_spans_and_ol_start() {
              <tr><td colspan=\"2\">wide</td><td rowspan=\"3\">tall</td></tr>\
              <tr><td>a</td><td>b</td></tr>\
              </tbody></table>\
-             <ol start=\"5\"><li>five</li><li>six</li></ol>",
+             <ol start=\"5\"><li>five</li><li value=\"9\">nine</li></ol>",
         ))
         .nav(vec![Nav::new("Grid", "text/ch1.xhtml")])
         .build();
@@ -78,6 +79,20 @@ fn kfx_preserves_table_spans_and_ol_start() {
         }
     }
     assert!(saw_start, "ol start=5 did not survive the KFX round trip");
+
+    // A list item's own ordinal survives, and is not mistaken for a start.
+    let ch = out.load_chapter(out.spine()[0].id).expect("load");
+    let values: Vec<_> = ch
+        .iter_dfs()
+        .filter(|&nid| ch.node(nid).map(|n| n.role) == Some(Role::ListItem))
+        .map(|nid| (ch.semantics.list_value(nid), ch.semantics.list_start(nid)))
+        .collect();
+    assert_eq!(values, [(None, None), (Some(9), None)]);
+
+    // And XHTML export writes both attributes back out.
+    let html = out.chapter_html(out.spine()[0].id).expect("chapter html");
+    assert!(html.contains(" start=\"5\""), "{html}");
+    assert!(html.contains(" value=\"9\""), "{html}");
 }
 
 // ============================================================================
@@ -0,0 +1,108 @@
+//! Per-element `lang`/`xml:lang` attributes set the node style's language,
+//! inherit down the tree, and survive EPUB and KFX conversion.
+
+mod common;
+
+use std::io::{Cursor, Read};
+
+use boko::export::{EpubConfig, EpubExporter, Exporter};
+use boko::model::{Chapter, Role};
+use boko::{Book, Format};
+
+fn sample_book() -> Book {
+    use common::{Doc, EpubBuilder, Nav};
+
+    EpubBuilder::new("Languages")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            r#"<p>Caesar said <span lang="la">alea iacta est</span>.</p>
+<p xml:lang="fr">bonjour <em>monde</em></p>"#,
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book()
+}
+
+/// The language of the element directly holding the text `text`.
+fn language_of(chapter: &Chapter, text: &str) -> Option<String> {
+    let node = chapter
+        .iter_dfs()
+        .find(|&id| {
+            chapter
+                .node(id)
+                .is_some_and(|n| n.role == Role::Text && chapter.text(n.text) == text)
+        })
+        .unwrap_or_else(|| panic!("no text node {text:?}"));
+    let parent = chapter.node(node).unwrap().parent.unwrap();
+    let style = chapter.node(parent).unwrap().style;
+    chapter.styles.get(style).unwrap().language.clone()
+}
+
+fn assert_languages(book: &Book, context: &str) {
+    let id = book.spine()[0].id;
+    let chapter = book.load_chapter(id).unwrap();
+    assert_eq!(
+        language_of(&chapter, "alea iacta est").as_deref(),
+        Some("la"),
+        "{context}"
+    );
+    assert_eq!(
+        language_of(&chapter, "monde").as_deref(),
+        Some("fr"),
+        "{context}"
+    );
+    assert_ne!(
+        language_of(&chapter, "Caesar said ").as_deref(),
+        Some("la"),
+        "{context}"
+    );
+}
+
+fn chapter_xhtml(epub: &[u8]) -> String {
+    let mut archive = zip::ZipArchive::new(Cursor::new(epub)).expect("valid zip");
+    let name = archive
+        .file_names()
+        .find(|n| n.ends_with(".xhtml") && !n.contains("nav"))
+        .unwrap()
+        .to_string();
+    let mut text = String::new();
+    archive
+        .by_name(&name)
+        .unwrap()
+        .read_to_string(&mut text)
+        .unwrap();
+    text
+}
+
+#[test]
+fn lang_attributes_set_inherited_node_language() {
+    assert_languages(&sample_book(), "source");
+}
+
+#[test]
+fn lang_survives_normalized_epub_export() {
+    let mut out = Cursor::new(Vec::new());
+    EpubExporter::new()
+        .with_config(EpubConfig {
+            normalize: true,
+            ..Default::default()
+        })
+        .export(&sample_book(), &mut out)
+        .expect("export epub");
+    let epub = out.into_inner();
+    let xhtml = chapter_xhtml(&epub);
+    assert!(xhtml.contains(r#" lang="la" xml:lang="la""#), "{xhtml}");
+    let reread = Book::from_bytes(&epub, Format::Epub).expect("reimport");
+    assert_languages(&reread, &xhtml);
+}
+
+#[test]
+fn lang_survives_kfx_round_trip() {
+    let kfx = common::roundtrip(&mut sample_book(), Format::Kfx);
+    assert_languages(&kfx, "kfx");
+
+    let mut kfx = kfx;
+    let epub = common::export_to_bytes(&mut kfx, Format::Epub);
+    let xhtml = chapter_xhtml(&epub);
+    assert!(xhtml.contains(r#" lang="la" xml:lang="la""#), "{xhtml}");
+}
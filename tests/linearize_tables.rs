@@ -0,0 +1,51 @@
+//! `Book::linearize_tables`: key/value tables become `term: description`
+//! paragraphs; real tabular data stays a table.
+
+mod common;
+
+use boko::{Book, Format};
+
+const BODY: &str = r#"
+<table id="facts">
+  <tr><th>Born</th><td>1869</td></tr>
+  <tr><th>Died</th><td>1948</td></tr>
+</table>
+<table>
+  <tr><th>Year</th><th>Event</th><th>Place</th></tr>
+  <tr><td>1893</td><td>Arrival</td><td>Durban</td></tr>
+</table>
+"#;
+
+fn sample_book() -> Book {
+    use common::{Doc, EpubBuilder, Nav};
+
+    EpubBuilder::new("Tables")
+        .doc(Doc::new("text/ch1.xhtml", "One", BODY))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book()
+}
+
+#[test]
+fn key_value_tables_linearize_when_enabled() {
+    let mut book = sample_book();
+    let before = String::from_utf8(common::export_to_bytes(&mut book, Format::Markdown)).unwrap();
+    assert!(!before.contains("Born: 1869"), "{before}");
+
+    book.linearize_tables();
+    let text = String::from_utf8(common::export_to_bytes(&mut book, Format::Markdown)).unwrap();
+    assert!(text.contains("**Born**: 1869"), "{text}");
+    assert!(text.contains("**Died**: 1948"), "{text}");
+    // The three-column table is left alone.
+    assert!(text.contains("| Year"), "{text}");
+
+    // EPUB output goes through the IR too, keeping the table's id.
+    let epub = common::export_to_bytes(&mut book, Format::Epub);
+    let reread = Book::from_bytes(&epub, Format::Epub).unwrap();
+    let id = reread.spine()[0].id;
+    let html = String::from_utf8(reread.load_raw(id).unwrap()).unwrap();
+    assert!(html.contains(r#"id="facts""#), "{html}");
+    assert_eq!(html.matches("<table").count(), 1, "{html}");
+    let chapter = reread.load_chapter(id).unwrap();
+    let plain = chapter.text_buffer().to_string();
+    assert!(plain.contains("Died: 1948"), "{plain}");
+}
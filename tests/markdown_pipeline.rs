@@ -146,6 +146,19 @@ fn ordered_lists_with_start_are_not_fused_and_numbering_survives() {
     );
 }
 
+#[test]
+fn nested_ordered_lists_keep_their_own_numbering() {
+    let md = markdown_for_body(
+        "<ol start=\"5\"><li>Five<ol start=\"3\"><li>Three</li><li>Four</li></ol></li>\
+         <li>Six</li><li value=\"10\">Ten</li><li>Eleven</li></ol>",
+    );
+
+    assert!(md.contains("5. Five"), "{md}");
+    assert!(md.contains("   3. Three\n   4. Four"), "{md}");
+    // The nested list makes the outer one loose.
+    assert!(md.contains("6. Six\n\n10. Ten\n\n11. Eleven"), "{md}");
+}
+
 // ---------------------------------------------------------------------------
 // Escaping through the pipeline: line-start markers in plain prose.
 // ---------------------------------------------------------------------------
@@ -162,3 +175,151 @@ fn prose_resembling_list_markers_is_escaped() {
     assert!(md.contains("1\\. numbered prose"), "number escaped: {md}");
     assert!(md.contains("a - b stays"), "mid-line dash untouched: {md}");
 }
+
+// ---------------------------------------------------------------------------
+// Wrapping: `MarkdownConfig::line_width` and `convert --wrap`.
+// ---------------------------------------------------------------------------
+
+const LONG_PARAGRAPH: &str = "It was the best of times, it was the worst of times, it was the age of \
+     wisdom, it was the age of foolishness, it was the epoch of belief.";
+
+#[test]
+fn long_paragraph_wraps_at_line_width() {
+    use boko::export::{Exporter, MarkdownConfig, MarkdownExporter};
+
+    let book = EpubBuilder::new("Wrap")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "Chapter 1",
+            &format!("<h1>A heading that is longer than forty columns</h1><p>{LONG_PARAGRAPH}</p><blockquote><p>{LONG_PARAGRAPH}</p></blockquote>"),
+        ))
+        .nav(vec![Nav::new("Chapter 1", "text/ch1.xhtml")])
+        .book();
+    let mut out = std::io::Cursor::new(Vec::new());
+    MarkdownExporter::with_config(MarkdownConfig { line_width: 40 })
+        .export(&book, &mut out)
+        .unwrap();
+    let md = String::from_utf8(out.into_inner()).unwrap();
+
+    assert!(
+        md.contains("# A heading that is longer than forty columns\n"),
+        "headings are not wrapped: {md}"
+    );
+    let paragraph: Vec<&str> = md
+        .lines()
+        .filter(|l| !l.starts_with('#') && !l.is_empty())
+        .collect();
+    assert!(paragraph.len() > 4, "{md}");
+    assert!(paragraph.iter().all(|l| l.chars().count() <= 40), "{md}");
+    let quoted: Vec<&str> = paragraph
+        .iter()
+        .filter(|l| l.starts_with("> "))
+        .copied()
+        .collect();
+    assert!(quoted.len() > 2, "every quoted line keeps its marker: {md}");
+    let words: Vec<&str> = LONG_PARAGRAPH.split_whitespace().collect();
+    let plain: Vec<&str> = paragraph
+        .iter()
+        .filter(|l| !l.starts_with('>'))
+        .flat_map(|l| l.split_whitespace())
+        .collect();
+    assert_eq!(plain, words, "no word is split or lost: {md}");
+}
+
+#[test]
+fn convert_wrap_flag_wraps_markdown_output() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("book.epub");
+    let epub = EpubBuilder::new("Wrap")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "Chapter 1",
+            &format!("<p>{LONG_PARAGRAPH}</p>"),
+        ))
+        .nav(vec![Nav::new("Chapter 1", "text/ch1.xhtml")])
+        .build();
+    std::fs::write(&input, epub).unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_boko"))
+        .args(["convert", "--wrap", "40"])
+        .arg(&input)
+        .arg("-")
+        .output()
+        .expect("failed to run boko convert");
+    assert!(output.status.success());
+    let md = String::from_utf8(output.stdout).unwrap();
+    assert!(md.lines().count() > 3, "{md}");
+    assert!(md.lines().all(|l| l.chars().count() <= 40), "{md}");
+}
+
+// ---------------------------------------------------------------------------
+// Phrasing elements are inline in the UA stylesheet; a styled `em` or `code`
+// without it fell back to block display and broke the line before itself.
+// ---------------------------------------------------------------------------
+
+#[test]
+fn styled_phrasing_elements_stay_on_the_line() {
+    let md = markdown_for_body("<p>Some <em>emphasis</em> and <code>code</code> here.</p>");
+    assert!(md.contains("Some *emphasis* and `code` here."), "{md}");
+}
+
+// ---------------------------------------------------------------------------
+// CJK text transforms are applied to the text, since Markdown has no CSS.
+// ---------------------------------------------------------------------------
+
+#[test]
+fn full_width_text_transform_maps_ascii() {
+    let md = markdown_for_body(
+        r#"<p><span style="text-transform: full-width">AB</span> and <span style="text-transform: full-size-kana">ちょっと</span></p>"#,
+    );
+    assert!(md.contains("ＡＢ and ちよつと"), "{md}");
+}
+
+// ---------------------------------------------------------------------------
+// Figures: the caption follows its image on the next line of the same
+// paragraph, so the two stay grouped.
+// ---------------------------------------------------------------------------
+
+#[test]
+fn figure_groups_image_and_caption() {
+    let md = markdown_for_body(
+        "<figure><img src=\"../images/cat.png\" alt=\"A cat\"/>\
+         <figcaption>A <em>small</em> cat.</figcaption></figure><p>After.</p>",
+    );
+    assert!(
+        md.contains("![A cat](OEBPS/images/cat.png)\n_A *small* cat._\n\nAfter."),
+        "{md}"
+    );
+}
+
+#[test]
+fn figure_without_image_or_with_several_children() {
+    let md = markdown_for_body("<figure><figcaption>Only a caption</figcaption></figure>");
+    assert!(md.contains("_Only a caption_"), "{md}");
+
+    let md = markdown_for_body(
+        "<figure><img src=\"../images/a.png\" alt=\"one\"/><p>Between.</p>\
+         <p><img src=\"../images/b.png\" alt=\"two\"/></p>\
+         <figcaption>Both</figcaption></figure>",
+    );
+    assert!(
+        md.contains("![one](OEBPS/images/a.png)\n\nBetween."),
+        "{md}"
+    );
+    assert!(md.contains("![two](OEBPS/images/b.png)\n_Both_"), "{md}");
+}
+
+#[test]
+fn definition_list_keeps_terms_and_descriptions_apart() {
+    let md = markdown_for_body(
+        "<dl><dt>Apple</dt><dd>A red fruit.</dd><dd>Also a company.</dd>\
+         <dt>Pear</dt><dt>Poire</dt><dd>A green fruit.</dd></dl><p>After.</p>",
+    );
+    assert!(
+        md.contains(
+            "**Apple**\n: A red fruit.\n: Also a company.\n\n\
+             **Pear**\n**Poire**\n: A green fruit.\n\nAfter."
+        ),
+        "{md}"
+    );
+}
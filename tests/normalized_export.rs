@@ -1,12 +1,14 @@
 //! Integration tests for normalized export pipeline.
 
+mod common;
+
 use std::io::Cursor;
 use std::sync::Arc;
 
-use boko::Book;
 use boko::export::{EpubConfig, EpubExporter, Exporter, GlobalStylePool, normalize_book};
 use boko::model::Chapter;
 use boko::style::{ComputedStyle, FontStyle, FontWeight, StyleId};
+use boko::{Book, Format};
 
 // ============================================================================
 // Unit Tests for GlobalStylePool
@@ -164,6 +166,501 @@ fn test_normalize_book_emits_css_for_used_classes() {
     }
 }
 
+/// Hanging indents (negative `text-indent` over matching left padding)
+/// must survive normalization with the sign intact, not clamp to zero.
+#[test]
+fn test_normalized_css_keeps_negative_text_indent() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let epub = EpubBuilder::new("Bibliography")
+        .css(".bib { text-indent: -2em; padding-left: 2em; }")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "Works Cited",
+            "<p class=\"bib\">Author, A. A long citation that wraps onto a hanging line.</p>",
+        ))
+        .nav(vec![Nav::new("Works Cited", "text/ch1.xhtml")])
+        .build();
+    let book = Book::from_bytes(&epub, Format::Epub).expect("import epub");
+
+    let content = normalize_book(&book).expect("normalize_book failed");
+    assert!(
+        content.css.contains("text-indent: -2em"),
+        "negative text-indent lost in normalized CSS:\n{}",
+        content.css
+    );
+    assert!(
+        content.css.contains("padding-left: 2em"),
+        "hanging-indent padding lost in normalized CSS:\n{}",
+        content.css
+    );
+}
+
+/// A zero `letter-spacing` inside a spaced paragraph is a reset: the span's
+/// class must carry it, or the span inherits the paragraph's spacing.
+#[test]
+fn test_normalized_css_keeps_zero_letter_spacing_reset() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let epub = EpubBuilder::new("Spacing")
+        .css("p { letter-spacing: .2em; }")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "Spacing",
+            "<p>Spaced <span style=\"letter-spacing:0\">tight</span> spaced.</p>",
+        ))
+        .nav(vec![Nav::new("Spacing", "text/ch1.xhtml")])
+        .build();
+    let book = Book::from_bytes(&epub, Format::Epub).expect("import epub");
+
+    let content = normalize_book(&book).expect("normalize_book failed");
+    let document = &content.chapters[0].document;
+    let span_class = document
+        .split("<span class=\"")
+        .nth(1)
+        .and_then(|rest| rest.split('"').next())
+        .expect("span keeps a class");
+    let rule = content
+        .css
+        .lines()
+        .find(|line| line.starts_with(&format!(".{span_class} ")))
+        .expect("span class has a rule");
+    assert!(rule.contains("letter-spacing: 0"), "{}", content.css);
+    assert!(
+        content.css.contains("letter-spacing: 0.2em"),
+        "{}",
+        content.css
+    );
+}
+
+/// `column-span: all` on a heading inside a multi-column section is parsed
+/// into the style and re-emitted in the normalized CSS.
+#[test]
+fn test_normalized_css_keeps_column_span() {
+    use boko::style::ColumnSpan;
+    use common::{Doc, EpubBuilder, Nav};
+
+    let epub = EpubBuilder::new("Columns")
+        .css(".cols h2 { column-span: all; }")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "Columns",
+            "<div class=\"cols\"><h2>Across</h2><p>Body text.</p></div>",
+        ))
+        .nav(vec![Nav::new("Columns", "text/ch1.xhtml")])
+        .build();
+    let book = Book::from_bytes(&epub, Format::Epub).expect("import epub");
+
+    let chapter = book.load_chapter(book.spine()[0].id).unwrap();
+    let spans: Vec<ColumnSpan> = chapter
+        .iter_dfs()
+        .filter_map(|id| chapter.styles.get(chapter.node(id)?.style))
+        .map(|style| style.column_span)
+        .filter(|&span| span != ColumnSpan::None)
+        .collect();
+    assert_eq!(spans, [ColumnSpan::All]);
+
+    let content = normalize_book(&book).expect("normalize_book failed");
+    assert!(
+        content.css.contains("column-span: all"),
+        "column-span lost in normalized CSS:\n{}",
+        content.css
+    );
+}
+
+/// `font-size-adjust` survives a normalized EPUB round trip.
+#[test]
+fn test_normalized_css_keeps_font_size_adjust() {
+    use boko::style::FontSizeAdjust;
+    use common::{Doc, EpubBuilder, Nav};
+
+    let epub = EpubBuilder::new("Adjust")
+        .css("p { font-family: Verdana, sans-serif; font-size-adjust: 0.5; }")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "Adjust",
+            "<p>Fallback text.</p>",
+        ))
+        .nav(vec![Nav::new("Adjust", "text/ch1.xhtml")])
+        .build();
+    let adjustments = |book: &Book| {
+        let chapter = book.load_chapter(book.spine()[0].id).unwrap();
+        chapter
+            .iter_dfs()
+            .filter_map(|id| chapter.styles.get(chapter.node(id)?.style))
+            .map(|style| style.font_size_adjust)
+            .filter(|&adjust| adjust != FontSizeAdjust::None)
+            .collect::<Vec<_>>()
+    };
+    let book = Book::from_bytes(&epub, Format::Epub).expect("import epub");
+    assert_eq!(adjustments(&book), [FontSizeAdjust::Aspect(0.5)]);
+
+    let mut output = Cursor::new(Vec::new());
+    EpubExporter::new()
+        .with_config(EpubConfig {
+            normalize: true,
+            ..Default::default()
+        })
+        .export(&book, &mut output)
+        .expect("normalized export");
+    let book = Book::from_bytes(&output.into_inner(), Format::Epub).expect("reimport");
+    assert_eq!(adjustments(&book), [FontSizeAdjust::Aspect(0.5)]);
+}
+
+/// `break-before: recto` keeps its page side and is written with a plain
+/// page-break fallback for reading systems that don't know recto/verso.
+#[test]
+fn test_normalized_css_keeps_recto_break() {
+    use boko::style::BreakValue;
+    use common::{Doc, EpubBuilder, Nav};
+
+    let epub = EpubBuilder::new("Recto")
+        .css("h1 { break-before: recto; } h2 { page-break-after: verso; }")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "Recto",
+            "<h1>Part One</h1><h2>Prelude</h2><p>Body text.</p>",
+        ))
+        .nav(vec![Nav::new("Recto", "text/ch1.xhtml")])
+        .build();
+    let breaks = |book: &Book| {
+        let chapter = book.load_chapter(book.spine()[0].id).unwrap();
+        chapter
+            .iter_dfs()
+            .filter_map(|id| chapter.styles.get(chapter.node(id)?.style))
+            .map(|style| (style.break_before, style.break_after))
+            .filter(|&breaks| breaks != (BreakValue::Auto, BreakValue::Auto))
+            .collect::<Vec<_>>()
+    };
+    let book = Book::from_bytes(&epub, Format::Epub).expect("import epub");
+    let expected = [
+        (BreakValue::Recto, BreakValue::Auto),
+        (BreakValue::Auto, BreakValue::Verso),
+    ];
+    assert_eq!(breaks(&book), expected);
+
+    let content = normalize_book(&book).expect("normalize_book failed");
+    assert!(
+        content
+            .css
+            .contains("page-break-before: always; break-before: recto"),
+        "recto break lost in normalized CSS:\n{}",
+        content.css
+    );
+    assert!(
+        content
+            .css
+            .contains("page-break-after: always; break-after: verso")
+    );
+
+    let mut output = Cursor::new(Vec::new());
+    EpubExporter::new()
+        .with_config(EpubConfig {
+            normalize: true,
+            ..Default::default()
+        })
+        .export(&book, &mut output)
+        .expect("normalized export");
+    let book = Book::from_bytes(&output.into_inner(), Format::Epub).expect("reimport");
+    assert_eq!(breaks(&book), expected);
+}
+
+/// Identically styled paragraphs share one class and one CSS rule, across
+/// chapters and even when their styles differ only in language.
+#[test]
+fn test_normalized_identical_styles_share_one_class() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let epub = EpubBuilder::new("Shared")
+        .css(".aside { color: #336699; font-style: italic; }")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<p class=\"aside\">First aside.</p>",
+        ))
+        .doc(Doc::new(
+            "text/ch2.xhtml",
+            "Two",
+            "<p class=\"aside\">Second aside.</p><p class=\"aside\" lang=\"fr\">Troisième.</p>",
+        ))
+        .nav(vec![
+            Nav::new("One", "text/ch1.xhtml"),
+            Nav::new("Two", "text/ch2.xhtml"),
+        ])
+        .build();
+    let book = Book::from_bytes(&epub, Format::Epub).expect("import epub");
+
+    let content = normalize_book(&book).expect("normalize_book failed");
+    let classes: Vec<String> = content
+        .chapters
+        .iter()
+        .flat_map(|chapter| extract_style_classes(&chapter.document))
+        .collect();
+    assert_eq!(classes.len(), 3, "{classes:?}");
+    assert!(classes.iter().all(|c| *c == classes[0]), "{classes:?}");
+    assert_eq!(
+        content.css.matches("color: #336699").count(),
+        1,
+        "{}",
+        content.css
+    );
+}
+
+/// Legacy `<ol type="i">` numbering survives into the normalized CSS.
+#[test]
+fn test_normalized_css_keeps_ol_type_numbering() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let epub = EpubBuilder::new("Roman")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "Roman",
+            "<ol type=\"i\"><li>First</li><li>Second</li></ol>",
+        ))
+        .nav(vec![Nav::new("Roman", "text/ch1.xhtml")])
+        .build();
+    let book = Book::from_bytes(&epub, Format::Epub).expect("import epub");
+
+    let content = normalize_book(&book).expect("normalize_book failed");
+    assert!(
+        content.css.contains("list-style-type: lower-roman"),
+        "ol type lost in normalized CSS:\n{}",
+        content.css
+    );
+}
+
+/// An image-only cover page gets full-page sizing in the normalized CSS;
+/// images elsewhere keep their own sizing.
+#[test]
+fn test_normalized_cover_page_image_fills_page() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let epub = EpubBuilder::new("Covered")
+        .cover_png()
+        .image("images/plate.png", common::tiny_png())
+        .doc(Doc::new(
+            "text/cover.xhtml",
+            "Cover",
+            "<div><img src=\"../images/cover.png\" alt=\"Cover\"/></div>",
+        ))
+        .doc(Doc::new(
+            "text/plate.xhtml",
+            "Plate",
+            "<div><img src=\"../images/plate.png\" alt=\"Plate\"/></div>",
+        ))
+        .nav(vec![
+            Nav::new("Cover", "text/cover.xhtml"),
+            Nav::new("Plate", "text/plate.xhtml"),
+        ])
+        .build();
+    let book = Book::from_bytes(&epub, Format::Epub).expect("import epub");
+
+    let content = normalize_book(&book).expect("normalize_book failed");
+    // The CSS rule for the class on the first `tag` element of a chapter.
+    let rule = |chapter: usize, tag: &str| {
+        let document = &content.chapters[chapter].document;
+        let at = document.find(tag).expect("element");
+        let class = extract_style_classes(&document[at..])
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        content
+            .css
+            .lines()
+            .find(|line| !class.is_empty() && line.starts_with(&format!(".{class} ")))
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    let cover = rule(0, "<img");
+    for declaration in ["height: 100%", "max-width: 100%"] {
+        assert!(
+            cover.contains(declaration),
+            "{declaration} missing: {cover}"
+        );
+    }
+    assert!(
+        rule(0, "<div").contains("text-align: center"),
+        "{}",
+        content.css
+    );
+    assert!(!rule(1, "<img").contains("height: 100%"), "{}", content.css);
+}
+
+/// A cover page the author already sized keeps its own image size and
+/// alignment.
+#[test]
+fn test_normalized_cover_page_keeps_author_sizing() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let epub = EpubBuilder::new("Covered")
+        .cover_png()
+        .css(".frame { text-align: right } .frame img { width: 50% }")
+        .doc(Doc::new(
+            "text/cover.xhtml",
+            "Cover",
+            "<div class=\"frame\"><img src=\"../images/cover.png\" alt=\"Cover\"/></div>",
+        ))
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>Text.</p>"))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build();
+    let book = Book::from_bytes(&epub, Format::Epub).expect("import epub");
+
+    let content = normalize_book(&book).expect("normalize_book failed");
+    assert!(content.css.contains("width: 50%"), "{}", content.css);
+    assert!(content.css.contains("text-align: right"), "{}", content.css);
+    assert!(!content.css.contains("height: 100%"), "{}", content.css);
+    assert!(
+        !content.css.contains("text-align: center"),
+        "{}",
+        content.css
+    );
+}
+
+/// Landmarks become `epub:type` on what they point at: the `<body>` of a
+/// whole-file target, or the element a fragment names.
+#[test]
+fn test_normalized_landmarks_type_their_targets() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let epub = EpubBuilder::new("Landmarks")
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>First.</p>"))
+        .doc(Doc::new("text/ch2.xhtml", "Two", "<p>Second.</p>"))
+        .nav(vec![
+            Nav::new("One", "text/ch1.xhtml"),
+            Nav::new("Two", "text/ch2.xhtml"),
+        ])
+        .build();
+    let book = Book::from_bytes(&epub, Format::Epub).expect("import epub");
+    let content = normalize_book(&book).expect("normalize_book failed");
+
+    let first = &content.chapters[0].document;
+    assert!(first.contains("<body epub:type=\"bodymatter\">"), "{first}");
+    let second = &content.chapters[1].document;
+    assert!(!second.contains("bodymatter"), "{second}");
+}
+
+/// Block images, or images alone in their container, inside a centering
+/// container (plain or the legacy `-webkit-center`) get auto side margins,
+/// so they stay centered where only block centering applies. Inline images
+/// sharing a line with text don't.
+#[test]
+fn test_normalized_epub_centers_images_in_centered_containers() {
+    use common::{Doc, EpubBuilder, Nav};
+    use std::io::Read;
+
+    let epub = EpubBuilder::new("Centered")
+        .css(
+            "img { display: block; } img.inline { display: inline; } \
+             .plate { text-align: -webkit-center; }",
+        )
+        .image("images/a.png", common::tiny_png())
+        .image("images/b.png", common::tiny_png())
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "Plates",
+            "<p>Two plates.</p>\
+             <div style=\"text-align: center\"><img src=\"../images/a.png\" alt=\"A\"/></div>\
+             <div class=\"plate\"><img src=\"../images/b.png\" alt=\"B\"/></div>\
+             <div><img src=\"../images/a.png\" alt=\"C\"/></div>\
+             <p style=\"text-align: center\">An <img class=\"inline\" src=\"../images/b.png\" alt=\"D\"/> icon.</p>\
+             <div style=\"text-align: center\"><img class=\"inline\" src=\"../images/b.png\" alt=\"E\"/></div>",
+        ))
+        .nav(vec![Nav::new("Plates", "text/ch1.xhtml")])
+        .build();
+    let book = Book::from_bytes(&epub, Format::Epub).expect("import epub");
+
+    let mut output = Cursor::new(Vec::new());
+    EpubExporter::new()
+        .with_config(EpubConfig {
+            normalize: true,
+            ..Default::default()
+        })
+        .export(&book, &mut output)
+        .expect("normalized export");
+    let mut archive = zip::ZipArchive::new(Cursor::new(output.into_inner())).expect("zip");
+    let mut read = |suffix: &str| {
+        let name = archive
+            .file_names()
+            .find(|name| name.ends_with(suffix))
+            .expect("entry")
+            .to_string();
+        let mut text = String::new();
+        archive
+            .by_name(&name)
+            .unwrap()
+            .read_to_string(&mut text)
+            .unwrap();
+        text
+    };
+    let document = read("chapter_0.xhtml");
+    let css = read("style.css");
+
+    let image_rule = |alt: &str| {
+        let at = document.find(&format!("alt=\"{alt}\"")).expect("image");
+        let tag = &document[document[..at].rfind("<img").unwrap()..];
+        let class = extract_style_classes(&tag[..tag.find('>').unwrap()])
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        css.lines()
+            .find(|line| !class.is_empty() && line.starts_with(&format!(".{class} ")))
+            .unwrap_or_default()
+            .to_string()
+    };
+    for alt in ["A", "B", "E"] {
+        let rule = image_rule(alt);
+        assert!(
+            rule.contains("margin-left: auto") && rule.contains("margin-right: auto"),
+            "{alt}: {rule}\n{css}"
+        );
+    }
+    for alt in ["C", "D"] {
+        assert!(
+            !image_rule(alt).contains("margin-left: auto"),
+            "{alt}: {css}"
+        );
+    }
+}
+
+/// `list-style-image` urls resolve against the stylesheet, are re-emitted
+/// in the normalized CSS, and pull the marker image into the package.
+#[test]
+fn test_normalized_css_keeps_list_style_image() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let epub = EpubBuilder::new("Stars")
+        .css("ul.stars { list-style: square url(../images/star.png) inside; }")
+        .image("images/star.png", common::tiny_png())
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "Stars",
+            "<ul class=\"stars\"><li>one</li><li>two</li></ul>",
+        ))
+        .nav(vec![Nav::new("Stars", "text/ch1.xhtml")])
+        .build();
+    let book = Book::from_bytes(&epub, Format::Epub).expect("import epub");
+
+    let content = normalize_book(&book).expect("normalize_book failed");
+    assert!(
+        content
+            .css
+            .contains(r#"list-style-image: url("OEBPS/images/star.png")"#),
+        "marker image lost in normalized CSS:\n{}",
+        content.css
+    );
+    assert!(
+        content.css.contains("list-style-type: square"),
+        "{}",
+        content.css
+    );
+    assert!(
+        content.assets.contains("OEBPS/images/star.png"),
+        "{:?}",
+        content.assets
+    );
+}
+
 #[test]
 fn test_normalized_export_contains_css_and_numbered_chapters() {
     let book = Book::open("tests/fixtures/epictetus.epub").expect("Failed to open test book");
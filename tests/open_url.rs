@@ -0,0 +1,99 @@
+//! `Book::open_url` reads a remote book through a `RangeFetcher`, fetching
+//! only the byte ranges it needs.
+
+mod common;
+
+use std::io;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use boko::{Book, RangeFetcher};
+
+/// Serves an in-memory file like an HTTP server honouring `Range`
+/// requests, counting the bytes it hands out.
+struct MockServer {
+    data: Vec<u8>,
+    fetched: Arc<AtomicUsize>,
+}
+
+impl RangeFetcher for MockServer {
+    fn content_length(&self, url: &str) -> io::Result<u64> {
+        assert_eq!(url, "https://books.example.com/big.epub?token=abc");
+        Ok(self.data.len() as u64)
+    }
+
+    fn read_at(&self, _url: &str, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+        let start = offset as usize;
+        let range = self
+            .data
+            .get(start..start + len)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "range not satisfiable"))?;
+        self.fetched.fetch_add(len, Ordering::Relaxed);
+        Ok(range.to_vec())
+    }
+}
+
+/// Incompressible filler so the image dominates the archive size.
+fn noise(len: usize) -> Vec<u8> {
+    let mut state = 0x2545_f491_u32;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as u8
+        })
+        .collect()
+}
+
+#[test]
+fn open_url_fetches_only_needed_entries() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let epub = EpubBuilder::new("Remote")
+        .image("images/plate.png", noise(2 << 20))
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>Hello from afar.</p>"))
+        .doc(Doc::new(
+            "text/ch2.xhtml",
+            "Two",
+            r#"<p><img src="../images/plate.png" alt="plate"/></p>"#,
+        ))
+        .nav(vec![
+            Nav::new("One", "text/ch1.xhtml"),
+            Nav::new("Two", "text/ch2.xhtml"),
+        ])
+        .build();
+    let total = epub.len();
+    let fetched = Arc::new(AtomicUsize::new(0));
+    let server = MockServer {
+        data: epub,
+        fetched: fetched.clone(),
+    };
+
+    let book = Book::open_url("https://books.example.com/big.epub?token=abc", server)
+        .expect("open remote epub");
+    assert_eq!(book.metadata().title, "Remote");
+    let chapter = book.load_chapter(book.spine()[0].id).unwrap();
+    assert!(chapter.text_buffer().contains("Hello from afar."));
+
+    let fetched = fetched.load(Ordering::Relaxed);
+    assert!(
+        fetched < total / 4,
+        "fetched {fetched} of {total} bytes; the image should not be read"
+    );
+}
+
+#[test]
+fn open_url_rejects_unknown_extensions() {
+    let server = MockServer {
+        data: Vec::new(),
+        fetched: Arc::default(),
+    };
+    let Err(err) = Book::open_url("https://books.example.com/book", server) else {
+        panic!("opened a URL without a known extension");
+    };
+    assert!(
+        matches!(err, boko::Error::UnsupportedFormat { .. }),
+        "{err}"
+    );
+}
@@ -0,0 +1,51 @@
+//! `dc:date` events: publication and modification dates land in their own
+//! fields and survive EPUB export.
+
+mod common;
+
+use std::io::Cursor;
+
+use boko::export::{EpubConfig, EpubExporter, EpubVersion, Exporter};
+use boko::{Book, Format};
+use common::{Doc, EpubBuilder, Nav};
+
+#[test]
+fn publication_and_modification_dates_round_trip() {
+    // The modification date comes last, where it used to replace the
+    // publication date.
+    let book = EpubBuilder::new("Dated")
+        .metadata_xml(
+            r#"<dc:date xmlns:opf="http://www.idpf.org/2007/opf" opf:event="publication">2020-05-17</dc:date>"#,
+        )
+        .metadata_xml(
+            r#"<dc:date xmlns:opf="http://www.idpf.org/2007/opf" opf:event="modification">2024-01-15</dc:date>"#,
+        )
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>x</p>"))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+    assert_eq!(book.metadata().date.as_deref(), Some("2020-05-17"));
+    assert_eq!(book.metadata().modified_date.as_deref(), Some("2024-01-15"));
+
+    for version in [EpubVersion::Epub2, EpubVersion::Epub3] {
+        for normalize in [false, true] {
+            let mut out = Cursor::new(Vec::new());
+            EpubExporter::new()
+                .with_config(EpubConfig {
+                    version,
+                    normalize,
+                    ..Default::default()
+                })
+                .export(&book, &mut out)
+                .unwrap();
+            let back = Book::from_bytes(&out.into_inner(), Format::Epub).unwrap();
+            let meta = back.metadata();
+            assert_eq!(meta.date.as_deref(), Some("2020-05-17"), "{version:?}");
+            // EPUB 3's dcterms:modified only takes a full UTC timestamp.
+            let modified = match version {
+                EpubVersion::Epub2 => "2024-01-15",
+                EpubVersion::Epub3 => "2024-01-15T00:00:00Z",
+            };
+            assert_eq!(meta.modified_date.as_deref(), Some(modified), "{version:?}");
+        }
+    }
+}
@@ -5,6 +5,9 @@
 //! (PNG→JPEG) must rewrite chapter `src` references and the cover path, and
 //! must force normalized export so raw-passthrough EPUB output can't ship
 //! dangling references.
+//!
+//! Also covers the other asset-level passes: `Book::prune_unused_assets` and
+//! `Book::merge_css_classes`.
 
 mod common;
 
@@ -257,3 +260,157 @@ fn optimize_keeps_small_flat_and_css_referenced_images() {
         .unwrap();
     assert_eq!(book.load_asset(wallpaper).expect("load"), big_png);
 }
+
+#[test]
+fn prune_unused_assets_removes_only_orphans() {
+    use common::{Doc, EpubBuilder, Nav, tiny_png};
+
+    let epub = EpubBuilder::new("Prune Book")
+        .css("p { margin: 0 }")
+        .image("images/used.png", tiny_png())
+        .image("images/orphan.png", tiny_png())
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<p>text</p><img src=\"../images/used.png\" alt=\"used\"/>",
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build();
+
+    let mut book = boko::Book::from_bytes(&epub, Format::Epub).expect("import epub");
+    let before = book.list_assets().to_vec();
+    assert!(before.iter().any(|p| p.ends_with("orphan.png")));
+
+    assert_eq!(book.prune_unused_assets(), 1);
+    let after = book.list_assets().to_vec();
+    assert!(after.iter().any(|p| p.ends_with("used.png")));
+    assert!(!after.iter().any(|p| p.ends_with("orphan.png")));
+    assert!(after.iter().any(|p| p.ends_with(".css")));
+    assert_eq!(after.len(), before.len() - 1);
+
+    // Nothing left to prune the second time round.
+    assert_eq!(book.prune_unused_assets(), 0);
+
+    let out = common::export_to_bytes(&mut book, Format::Epub);
+    let mut zip = zip::ZipArchive::new(Cursor::new(out)).expect("open exported epub");
+    let names: Vec<String> = (0..zip.len())
+        .map(|i| zip.by_index(i).unwrap().name().to_string())
+        .collect();
+    assert!(names.iter().any(|n| n.ends_with("used.png")));
+    assert!(!names.iter().any(|n| n.ends_with("orphan.png")));
+}
+
+#[test]
+fn merge_css_classes_collapses_repeated_inline_styles() {
+    use boko::Role;
+    use boko::style::FontStyle;
+    use common::{Doc, EpubBuilder, Nav};
+
+    let body: String = (0..20)
+        .map(|i| format!("<p style=\"font-style: italic; margin-left: 2em\">line {i}</p>"))
+        .collect::<String>()
+        + "<p style=\"color: red\">unique</p>";
+    let epub = EpubBuilder::new("Inline Book")
+        .doc(Doc::new("text/ch1.xhtml", "One", &body))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build();
+
+    let mut book = boko::Book::from_bytes(&epub, Format::Epub).expect("import epub");
+    let id = book.spine()[0].id;
+    let before = book.load_raw(id).unwrap().len();
+
+    let report = book.merge_css_classes();
+    assert_eq!(report.classes_created, 1);
+    assert_eq!(report.attributes_replaced, 20);
+
+    let raw = String::from_utf8(book.load_raw(id).unwrap()).unwrap();
+    assert!(raw.len() < before, "markup should shrink");
+    assert_eq!(raw.matches("class=\"boko-s1\"").count(), 20);
+    // Styles used once stay inline.
+    assert_eq!(raw.matches("style=").count(), 1);
+    let css_path = book
+        .list_assets()
+        .iter()
+        .find(|p| p.ends_with("boko-styles.css"))
+        .expect("generated stylesheet listed")
+        .clone();
+    let css = String::from_utf8(book.load_asset(&css_path).unwrap()).unwrap();
+    assert_eq!(css.matches(".boko-s").count(), 1, "{css}");
+
+    // The cascade result is unchanged: every paragraph is still italic.
+    let chapter = book.load_chapter(id).unwrap();
+    let italic = chapter
+        .iter_dfs()
+        .filter_map(|n| chapter.node(n))
+        .filter(|node| node.role == Role::Paragraph)
+        .filter(|node| {
+            chapter
+                .styles
+                .get(node.style)
+                .is_some_and(|s| s.font_style == FontStyle::Italic)
+        })
+        .count();
+    assert_eq!(italic, 20);
+
+    // Raw-passthrough EPUB export ships the rewritten markup and the sheet.
+    let out = common::export_to_bytes(&mut book, Format::Epub);
+    let mut zip = zip::ZipArchive::new(Cursor::new(out)).expect("open exported epub");
+    assert!(zip.by_name(&format!("OEBPS/{css_path}")).is_ok());
+}
+
+#[test]
+fn merge_css_classes_leaves_styles_competing_with_author_important_inline() {
+    use boko::Role;
+    use boko::style::{Color, FontStyle};
+    use common::{Doc, EpubBuilder, Nav};
+
+    // The author rule beats the inline `color: red`; a promoted
+    // `.boko-s1 { color: red !important }` would beat it instead.
+    let body: String = (0..10)
+        .map(|i| format!("<p class=\"note\" style=\"color: red\">note {i}</p>"))
+        .chain((0..10).map(|i| format!("<p style=\"font-style: italic\">line {i}</p>")))
+        .collect();
+    let epub = EpubBuilder::new("Important Book")
+        .css("p.note { color: blue !important; }")
+        .doc(Doc::new("text/ch1.xhtml", "One", &body))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build();
+
+    let mut book = boko::Book::from_bytes(&epub, Format::Epub).expect("import epub");
+    let id = book.spine()[0].id;
+    let source = book.source_id(id).unwrap().to_string();
+    let report = book.merge_css_classes();
+    assert_eq!(report.classes_created, 1);
+    assert_eq!(report.attributes_replaced, 10);
+
+    let chapter = book.load_chapter(id).unwrap();
+    let paragraphs: Vec<_> = chapter
+        .iter_dfs()
+        .filter_map(|n| chapter.node(n))
+        .filter(|node| node.role == Role::Paragraph)
+        .filter_map(|node| chapter.styles.get(node.style))
+        .collect();
+    assert_eq!(paragraphs.len(), 20);
+    assert!(
+        paragraphs[..10]
+            .iter()
+            .all(|s| s.color == Some(Color::rgb(0, 0, 255)))
+    );
+    assert!(
+        paragraphs[10..]
+            .iter()
+            .all(|s| s.font_style == FontStyle::Italic)
+    );
+
+    // Raw passthrough ships the competing style attributes untouched.
+    let out = common::export_to_bytes(&mut book, Format::Epub);
+    let mut zip = zip::ZipArchive::new(Cursor::new(out)).expect("open exported epub");
+    let mut xhtml = String::new();
+    std::io::Read::read_to_string(
+        &mut zip.by_name(&format!("OEBPS/{source}")).expect("chapter"),
+        &mut xhtml,
+    )
+    .unwrap();
+    assert_eq!(xhtml.matches("style=\"color: red\"").count(), 10, "{xhtml}");
+    assert_eq!(xhtml.matches("boko-s1").count(), 10, "{xhtml}");
+}
@@ -0,0 +1,42 @@
+//! `Chapter::plain_text` and `Book::plain_text`: readable text without
+//! markup.
+
+mod common;
+
+use common::{Doc, EpubBuilder, Nav};
+
+#[test]
+fn nested_inlines_read_as_clean_paragraphs() {
+    let book = EpubBuilder::new("Plain")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<h1>Chapter <em>One</em></h1>\n\
+             <p>It was a <b>dark <i>and <span>stormy</span></i></b>\n    night; \
+             the <a href=\"#x\">rain</a> fell in <q>torrents</q>.</p>\n\
+             <div><p>Nested block.</p>Trailing text.</div>\
+             <p>First line<br/>second line</p>\
+             <ul><li>one</li><li>two</li></ul>\
+             <pre>fn main() {\n    run();\n}</pre>",
+        ))
+        .doc(Doc::new("text/ch2.xhtml", "Two", "<p>The end.</p>"))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+
+    let chapter = book.load_chapter(book.spine()[0].id).unwrap();
+    assert_eq!(
+        chapter.plain_text(),
+        "Chapter One\n\
+         It was a dark and stormy night; the rain fell in “torrents”.\n\
+         Nested block.\n\
+         Trailing text.\n\
+         First line\nsecond line\n\
+         one\n\
+         two\n\
+         fn main() {\n    run();\n}\n"
+    );
+
+    let text = book.plain_text();
+    assert!(text.starts_with("Chapter One\n"), "{text}");
+    assert!(text.ends_with("}\n\nThe end.\n"), "{text}");
+}
@@ -0,0 +1,86 @@
+//! Quotation semantics: `<q>` marks and `cite` on `<blockquote>` / `<q>`.
+
+mod common;
+
+use boko::export::{EpubConfig, EpubExporter, Exporter};
+use boko::{Book, Format, Role};
+
+const CITE: &str = "https://example.com/enchiridion";
+
+fn sample_book() -> Book {
+    use common::{Doc, EpubBuilder, Nav};
+
+    EpubBuilder::new("Quotations")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            &format!(
+                "<p>He said <q cite=\"{CITE}\">hello</q>.</p>\
+                 <blockquote cite=\"{CITE}\"><p>Some things are up to us.</p></blockquote>\
+                 <p lang=\"fr\">Elle a dit <q>bonjour</q>.</p>"
+            ),
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book()
+}
+
+/// Cite URLs of every node with `role`, across the book.
+fn cites(book: &Book, role: Role) -> Vec<String> {
+    let mut out = Vec::new();
+    for entry in book.spine() {
+        let chapter = book.load_chapter(entry.id).unwrap();
+        for id in chapter.iter_dfs() {
+            if chapter.node(id).unwrap().role == role
+                && let Some(cite) = chapter.semantics.cite(id)
+            {
+                out.push(cite.to_string());
+            }
+        }
+    }
+    out
+}
+
+#[test]
+fn q_and_blockquote_keep_their_cite() {
+    let book = sample_book();
+    assert_eq!(cites(&book, Role::Quote), [CITE]);
+    assert_eq!(cites(&book, Role::BlockQuote), [CITE]);
+
+    let mut out = std::io::Cursor::new(Vec::new());
+    EpubExporter::new()
+        .with_config(EpubConfig {
+            normalize: true,
+            ..Default::default()
+        })
+        .export(&book, &mut out)
+        .unwrap();
+    let reread = Book::from_bytes(&out.into_inner(), Format::Epub).unwrap();
+    assert_eq!(cites(&reread, Role::Quote), [CITE]);
+    assert_eq!(cites(&reread, Role::BlockQuote), [CITE]);
+}
+
+#[test]
+fn kfx_renders_q_with_language_quote_marks() {
+    let mut book = sample_book();
+    let kfx = common::roundtrip(&mut book, Format::Kfx);
+    let mut text = String::new();
+    for entry in kfx.spine() {
+        let chapter = kfx.load_chapter(entry.id).unwrap();
+        text.push_str(chapter.text_buffer());
+    }
+    assert!(text.contains("He said “hello”."), "{text}");
+    assert!(text.contains("Elle a dit «bonjour»."), "{text}");
+}
+
+#[test]
+fn text_export_renders_q_with_quote_marks() {
+    let mut book = sample_book();
+    let md = String::from_utf8(common::export_to_bytes(&mut book, Format::Markdown)).unwrap();
+    assert!(md.contains("He said “hello”."), "{md}");
+    assert!(md.contains("Elle a dit «bonjour»."), "{md}");
+
+    // Plain-text extraction spells the marks out too.
+    let tree = boko::extract_section_tree(&mut book).unwrap();
+    let text = format!("{tree:?}");
+    assert!(text.contains("He said “hello”."), "{text}");
+}
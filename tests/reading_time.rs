@@ -0,0 +1,55 @@
+//! `Book::word_count`, `Book::reading_time_minutes` and the chapter/asset
+//! counts `info --json` reports.
+
+mod common;
+
+use std::process::Command;
+
+use boko::Book;
+
+fn book_with_words(words: usize) -> Book {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let half = words / 2;
+    let paragraph = |n: usize| format!("<p>{}</p>", "word ".repeat(n));
+    EpubBuilder::new("Reading Time")
+        .doc(Doc::new("text/ch1.xhtml", "One", &paragraph(half)))
+        // Inline markup inside a word doesn't split it.
+        .doc(Doc::new(
+            "text/ch2.xhtml",
+            "Two",
+            &format!("{}<p><b>wo</b>rd</p>", paragraph(words - half - 1)),
+        ))
+        .nav(vec![
+            Nav::new("One", "text/ch1.xhtml"),
+            Nav::new("Two", "text/ch2.xhtml"),
+        ])
+        .book()
+}
+
+#[test]
+fn reading_time_rounds_up_at_250_wpm() {
+    let book = book_with_words(1200);
+    assert_eq!(book.word_count(), 1200);
+    // 1200 / 250 = 4.8 minutes.
+    assert_eq!(book.reading_time_minutes(250), 5);
+    assert_eq!(book.reading_time_minutes(300), 4);
+
+    assert_eq!(book_with_words(1000).reading_time_minutes(250), 4);
+}
+
+#[test]
+fn chapter_and_asset_counts() {
+    let book = Book::open("tests/fixtures/epictetus.epub").expect("open fixture");
+    assert_eq!(book.chapter_count(), 7);
+    assert_eq!(book.asset_count(), 19);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_boko"))
+        .args(["info", "--json", "tests/fixtures/epictetus.epub"])
+        .output()
+        .expect("failed to run boko info");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["chapter_count"], 7, "{json}");
+    assert_eq!(json["asset_count"], 19, "{json}");
+}
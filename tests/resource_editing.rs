@@ -0,0 +1,252 @@
+//! `Book::resources`, `Book::resource`, `Book::remove_resource` and
+//! `Book::set_cover` / `convert --cover`.
+
+mod common;
+
+use std::process::Command;
+
+use boko::{Book, Format};
+use common::{Doc, EpubBuilder, Nav};
+
+fn two_chapter_book() -> Book {
+    EpubBuilder::new("Editing")
+        .css("p { margin: 0; }")
+        .image("images/plate.png", common::tiny_png())
+        .image("images/unused.png", common::tiny_png())
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>First.</p>"))
+        .doc(Doc::new(
+            "text/ch2.xhtml",
+            "Two",
+            "<p>Second.</p><img src=\"../images/plate.png\" alt=\"Plate\"/>",
+        ))
+        .nav(vec![
+            Nav::new("One", "text/ch1.xhtml"),
+            Nav::new("Two", "text/ch2.xhtml"),
+        ])
+        .book()
+}
+
+#[test]
+fn resources_iterate_every_asset_with_its_type() {
+    let book = two_chapter_book();
+    let resources: Vec<_> = book.resources().collect();
+    assert_eq!(resources.len(), book.list_assets().len());
+
+    let (_, plate) = resources
+        .iter()
+        .find(|(path, _)| *path == "OEBPS/images/plate.png")
+        .expect("plate listed");
+    let plate = plate.as_ref().expect("plate readable");
+    assert_eq!(plate.media_type(), "image/png");
+    assert_eq!(plate.data, common::tiny_png());
+    assert!(resources.iter().all(|(_, resource)| resource.is_ok()));
+}
+
+#[test]
+fn resource_reads_one_asset() {
+    let book = two_chapter_book();
+    let plate = book.resource("OEBPS/images/plate.png").expect("plate");
+    assert_eq!(plate.data, common::tiny_png());
+    assert!(matches!(
+        book.resource("OEBPS/images/missing.png"),
+        Err(boko::Error::NotFound { .. })
+    ));
+}
+
+#[test]
+fn remove_resource_refuses_referenced_assets() {
+    let mut book = two_chapter_book();
+    let err = book.remove_resource("OEBPS/images/plate.png").unwrap_err();
+    assert!(matches!(err, boko::Error::InUse { .. }), "{err}");
+    assert!(err.to_string().contains("ch2.xhtml"), "{err}");
+    assert!(book.resource("OEBPS/images/plate.png").is_ok());
+
+    let removed = book
+        .remove_resource("OEBPS/images/unused.png")
+        .expect("unreferenced image");
+    assert_eq!(removed.data, common::tiny_png());
+    assert!(
+        !book
+            .list_assets()
+            .iter()
+            .any(|a| a == "OEBPS/images/unused.png")
+    );
+    assert!(book.load_asset("OEBPS/images/unused.png").is_err());
+}
+
+#[test]
+fn remove_resource_refuses_stylesheets_and_css_urls_in_use() {
+    let mut book = EpubBuilder::new("Styled")
+        .css("body { background: url(../images/paper.png) }")
+        .image("images/paper.png", common::tiny_png())
+        .image("images/stamp.png", common::tiny_png())
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<p style=\"background: url('../images/stamp.png')\">First.</p>",
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+
+    for (path, referrer) in [
+        ("OEBPS/css/style.css", "ch1.xhtml"),
+        ("OEBPS/images/paper.png", "style.css"),
+        ("OEBPS/images/stamp.png", "ch1.xhtml"),
+    ] {
+        let err = book.remove_resource(path).unwrap_err();
+        assert!(matches!(err, boko::Error::InUse { .. }), "{path}: {err}");
+        assert!(err.to_string().contains(referrer), "{path}: {err}");
+    }
+}
+
+#[test]
+fn removing_a_chapter_document_drops_it_from_the_spine() {
+    let mut book = two_chapter_book();
+    let second = book.spine()[1].id;
+    book.remove_resource("OEBPS/text/ch2.xhtml")
+        .expect("chapter document");
+
+    assert_eq!(book.spine().len(), 1);
+    assert!(book.load_chapter(second).is_err());
+
+    // The image only the removed chapter used is now free to go.
+    book.remove_resource("OEBPS/images/plate.png")
+        .expect("plate no longer referenced");
+
+    let mut book = common::roundtrip(&mut book, Format::Epub);
+    assert_eq!(book.spine().len(), 1);
+    assert!(
+        !book
+            .list_assets()
+            .iter()
+            .any(|a| a.ends_with("plate.png") || a.ends_with("ch2.xhtml")),
+        "{:?}",
+        book.list_assets()
+    );
+    let text = String::from_utf8(common::export_to_bytes(&mut book, Format::Markdown)).unwrap();
+    assert!(text.contains("First."), "{text}");
+    assert!(!text.contains("Second."), "{text}");
+}
+
+/// A valid PNG distinguishable from `tiny_png` (decoders ignore bytes after
+/// `IEND`).
+fn new_png() -> Vec<u8> {
+    let mut png = common::tiny_png();
+    png.extend_from_slice(b"new cover");
+    png
+}
+
+fn covered_book() -> Vec<u8> {
+    EpubBuilder::new("Covered")
+        .cover_png()
+        .doc(Doc::new(
+            "text/cover.xhtml",
+            "Cover",
+            "<img src=\"../images/cover.png\" alt=\"Cover\"/>",
+        ))
+        .doc(Doc::new("text/ch1.xhtml", "One", "<p>First.</p>"))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build()
+}
+
+#[test]
+fn set_cover_replaces_the_cover_and_its_page() {
+    let mut book = Book::from_bytes(&covered_book(), Format::Epub).unwrap();
+    book.set_cover("new.png", new_png()).expect("png cover");
+    assert_eq!(
+        book.metadata().cover_image.as_deref(),
+        Some("OEBPS/images/new.png")
+    );
+    assert_eq!(
+        book.resource("OEBPS/images/new.png").unwrap().data,
+        new_png()
+    );
+
+    let cover_page = book.load_chapter(book.spine()[0].id).unwrap();
+    assert!(
+        cover_page
+            .iter_dfs()
+            .any(|id| cover_page.semantics.src(id) == Some("OEBPS/images/new.png"))
+    );
+
+    for format in [Format::Epub, Format::Kfx, Format::Azw3] {
+        let out = common::roundtrip(&mut book, format);
+        let cover = out.metadata().cover_image.clone().expect("cover survives");
+        assert_eq!(out.load_asset(&cover).unwrap(), new_png(), "{format:?}");
+    }
+}
+
+#[test]
+fn set_cover_keeps_epub_export_passthrough() {
+    use std::io::Read;
+
+    let mut book = Book::from_bytes(&covered_book(), Format::Epub).unwrap();
+    book.set_cover("new.png", new_png()).expect("png cover");
+    assert!(!book.requires_normalized_export());
+
+    let epub = common::export_to_bytes(&mut book, Format::Epub);
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(epub)).unwrap();
+    let entry = |archive: &zip::ZipArchive<_>, suffix: &str| {
+        archive
+            .file_names()
+            .find(|name| name.ends_with(suffix))
+            .unwrap_or_else(|| panic!("no {suffix}"))
+            .to_string()
+    };
+    // Passthrough keeps the source file names, with the raw cover page
+    // pointed at the new image.
+    let page_name = entry(&archive, "text/cover.xhtml");
+    let image_name = entry(&archive, "images/new.png");
+    let mut page = String::new();
+    archive
+        .by_name(&page_name)
+        .unwrap()
+        .read_to_string(&mut page)
+        .unwrap();
+    assert!(page.contains("src=\"../images/new.png\""), "{page}");
+    assert!(!page.contains("cover.png"), "{page}");
+    let mut image = Vec::new();
+    archive
+        .by_name(&image_name)
+        .unwrap()
+        .read_to_end(&mut image)
+        .unwrap();
+    assert_eq!(image, new_png());
+}
+
+#[test]
+fn set_cover_rejects_non_images() {
+    let mut book = Book::from_bytes(&covered_book(), Format::Epub).unwrap();
+    let err = book.set_cover("notes.txt", b"hello".to_vec()).unwrap_err();
+    assert!(
+        matches!(err, boko::Error::UnsupportedFormat { .. }),
+        "{err}"
+    );
+    assert_eq!(
+        book.metadata().cover_image.as_deref(),
+        Some("OEBPS/images/cover.png")
+    );
+}
+
+#[test]
+fn convert_cover_flag_overrides_the_cover() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("in.epub");
+    let cover = dir.path().join("new.png");
+    let output = dir.path().join("out.epub");
+    std::fs::write(&input, covered_book()).unwrap();
+    std::fs::write(&cover, new_png()).unwrap();
+
+    let status = Command::new(env!("CARGO_BIN_EXE_boko"))
+        .args(["convert", "--quiet", "--cover"])
+        .arg(&cover)
+        .arg(&input)
+        .arg(&output)
+        .status()
+        .expect("failed to run boko convert");
+    assert!(status.success());
+
+    let book = Book::open(&output).unwrap();
+    let cover = book.metadata().cover_image.clone().expect("cover");
+    assert_eq!(book.load_asset(&cover).unwrap(), new_png());
+}
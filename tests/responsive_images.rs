@@ -0,0 +1,37 @@
+//! `<picture>` and `srcset`: the chosen source must be one the book can ship.
+
+mod common;
+
+use boko::Role;
+use common::{Doc, EpubBuilder, Nav};
+
+#[test]
+fn picture_falls_back_past_avif_and_missing_candidates() {
+    let book = EpubBuilder::new("Pictures")
+        .image("images/photo.jpg", common::tiny_png())
+        .image("images/photo.avif", common::tiny_png())
+        .image("images/photo@2x.jpg", common::tiny_png())
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<p><picture>\
+               <source type=\"image/avif\" srcset=\"../images/photo.avif 2000w\"/>\
+               <source srcset=\"../images/gone.jpg 1600w\"/>\
+               <img src=\"../images/photo.jpg\" alt=\"\"/>\
+             </picture></p>\
+             <p><img src=\"../images/photo.jpg\" srcset=\"../images/photo@2x.jpg 2x\" alt=\"\"/></p>",
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+
+    let chapter = book.load_chapter(book.spine()[0].id).unwrap();
+    let sources: Vec<&str> = chapter
+        .iter_dfs()
+        .filter(|&id| chapter.node(id).is_some_and(|n| n.role == Role::Image))
+        .filter_map(|id| chapter.semantics.src(id))
+        .collect();
+    assert_eq!(
+        sources,
+        ["OEBPS/images/photo.jpg", "OEBPS/images/photo@2x.jpg"]
+    );
+}
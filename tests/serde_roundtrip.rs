@@ -0,0 +1,104 @@
+//! JSON round-trip of `Book` through its serde snapshot (`serde` feature).
+
+#![cfg(feature = "serde")]
+
+mod common;
+
+use boko::{Book, Format, Resource};
+
+#[test]
+fn book_json_roundtrip_preserves_structure_and_resources() {
+    use common::{Doc, EpubBuilder, Nav, tiny_png};
+
+    let epub = EpubBuilder::new("Serde Book")
+        .css("p { font-style: italic }")
+        .image("images/pic.png", tiny_png())
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<p>first</p><img src=\"../images/pic.png\" alt=\"pic\"/>",
+        ))
+        .doc(Doc::new("text/ch2.xhtml", "Two", "<p>second</p>"))
+        .nav(vec![
+            Nav::new("One", "text/ch1.xhtml"),
+            Nav::new("Two", "text/ch2.xhtml"),
+        ])
+        .build();
+    let book = Book::from_bytes(&epub, Format::Epub).expect("import epub");
+
+    let json = serde_json::to_string(&book).expect("serialize book");
+    let restored: Book = serde_json::from_str(&json).expect("deserialize book");
+
+    assert_eq!(restored.metadata().title, book.metadata().title);
+    assert_eq!(restored.metadata().authors, book.metadata().authors);
+    assert_eq!(restored.metadata().language, book.metadata().language);
+    assert_eq!(restored.toc(), book.toc());
+    assert_eq!(restored.landmarks(), book.landmarks());
+
+    let ids: Vec<_> = book.spine().iter().map(|e| e.id).collect();
+    let restored_ids: Vec<_> = restored.spine().iter().map(|e| e.id).collect();
+    assert_eq!(restored_ids, ids);
+    for id in ids {
+        assert_eq!(restored.source_id(id), book.source_id(id));
+        assert_eq!(restored.load_raw(id).unwrap(), book.load_raw(id).unwrap());
+        // Chapters recompile from the snapshot alone.
+        let text: String = {
+            let ch = restored.load_chapter(id).unwrap();
+            ch.iter_dfs()
+                .filter_map(|n| ch.node(n))
+                .map(|node| ch.text(node.text).to_string())
+                .collect()
+        };
+        assert!(!text.is_empty());
+    }
+
+    assert_eq!(restored.list_assets(), book.list_assets());
+    for path in book.list_assets() {
+        assert_eq!(
+            restored.load_asset(path).unwrap(),
+            book.load_asset(path).unwrap()
+        );
+    }
+
+    // The restored book exports without its source file.
+    let mut restored = restored;
+    let out = common::export_to_bytes(&mut restored, Format::Epub);
+    assert!(!out.is_empty());
+}
+
+#[test]
+fn resource_data_is_base64_encoded() {
+    let resource = Resource {
+        data: vec![0, 1, 2, 255],
+        media_type: "image/png",
+    };
+    let json = serde_json::to_value(&resource).unwrap();
+    assert_eq!(json["data"], "AAEC/w==");
+    assert_eq!(json["media_type"], "image/png");
+
+    let back: Resource = serde_json::from_value(json).unwrap();
+    assert_eq!(back.data, resource.data);
+    assert_eq!(back.media_type, "image/png");
+}
+
+#[test]
+fn audio_resource_keeps_its_media_type() {
+    for (declared, interned) in [
+        ("audio/mpeg", "audio/mpeg"),
+        ("audio/ogg; codecs=opus", "audio/ogg"),
+        ("application/smil+xml", "application/smil+xml"),
+    ] {
+        let resource = Resource {
+            data: vec![0xFF, 0xFB, 0x90, 0x00],
+            media_type: "application/octet-stream",
+        };
+        let mut json = serde_json::to_value(&resource).unwrap();
+        json["media_type"] = declared.into();
+
+        let back: Resource = serde_json::from_str(&json.to_string()).unwrap();
+        assert_eq!(back.media_type, interned, "{declared}");
+        assert_eq!(back.data, resource.data);
+        let again = serde_json::to_value(&back).unwrap();
+        assert_eq!(again["media_type"], interned);
+    }
+}
@@ -0,0 +1,82 @@
+//! Inline `<svg>` cover pages: `<svg><image xlink:href="cover.jpg"/></svg>`.
+
+mod common;
+
+use std::io::Cursor;
+
+use boko::export::{EpubConfig, EpubExporter, Exporter};
+use boko::{Book, Format, Role};
+use common::{Doc, EpubBuilder, Nav};
+
+const SVG_COVER: &str = "<div><svg xmlns=\"http://www.w3.org/2000/svg\" \
+     xmlns:xlink=\"http://www.w3.org/1999/xlink\" version=\"1.1\" \
+     viewBox=\"0 0 1400 2100\" preserveAspectRatio=\"xMidYMid meet\">\
+     <title>The Voyage Out</title>\
+     <image width=\"1400\" height=\"2100\" xlink:href=\"../images/cover.png\"/>\
+     </svg></div>";
+
+/// An EPUB whose package declares no cover image: only the cover page
+/// points at it.
+fn svg_cover_book() -> Book {
+    EpubBuilder::new("The Voyage Out")
+        .image("images/cover.png", common::tiny_png())
+        .doc(Doc::new("text/cover.xhtml", "Cover", SVG_COVER))
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<p>As the streets...</p>",
+        ))
+        .nav(vec![
+            Nav::new("Cover", "text/cover.xhtml"),
+            Nav::new("One", "text/ch1.xhtml"),
+        ])
+        .book()
+}
+
+#[test]
+fn svg_cover_page_yields_the_cover_image() {
+    let book = svg_cover_book();
+    assert_eq!(
+        book.metadata().cover_image.as_deref(),
+        Some("OEBPS/images/cover.png")
+    );
+    let cover = book
+        .resource("OEBPS/images/cover.png")
+        .expect("cover asset");
+    assert_eq!(cover.data, common::tiny_png());
+
+    // The page itself becomes a plain image of the raster.
+    let chapter = book.load_chapter(book.spine()[0].id).unwrap();
+    let images: Vec<_> = chapter
+        .iter_dfs()
+        .filter(|&id| chapter.node(id).is_some_and(|n| n.role == Role::Image))
+        .collect();
+    assert_eq!(images.len(), 1);
+    assert_eq!(
+        chapter.semantics.src(images[0]),
+        Some("OEBPS/images/cover.png")
+    );
+    assert_eq!(chapter.semantics.alt(images[0]), Some("The Voyage Out"));
+}
+
+#[test]
+fn svg_cover_survives_epub_export() {
+    let book = svg_cover_book();
+    for normalize in [false, true] {
+        let mut out = Cursor::new(Vec::new());
+        EpubExporter::new()
+            .with_config(EpubConfig {
+                normalize,
+                ..Default::default()
+            })
+            .export(&book, &mut out)
+            .unwrap();
+        let reread = Book::from_bytes(&out.into_inner(), Format::Epub).unwrap();
+        let cover = reread
+            .metadata()
+            .cover_image
+            .clone()
+            .unwrap_or_else(|| panic!("cover declared (normalize: {normalize})"));
+        assert_eq!(reread.resource(&cover).unwrap().data, common::tiny_png());
+    }
+}
@@ -0,0 +1,55 @@
+//! Table captions and header-cell associations survive normalized EPUB
+//! export.
+
+mod common;
+
+use std::io::{Cursor, Read};
+
+use boko::export::{EpubConfig, EpubExporter, Exporter};
+use common::{Doc, EpubBuilder, Nav};
+
+#[test]
+fn caption_and_scope_survive_epub_export() {
+    let book = EpubBuilder::new("Tables")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            r#"<table>
+                 <caption>Monthly rainfall</caption>
+                 <tr><th scope="col" id="m">Month</th><th scope="col" id="r">Rain</th></tr>
+                 <tr><td headers="m">May</td><td headers="r">42</td></tr>
+               </table>"#,
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+
+    let mut out = Cursor::new(Vec::new());
+    EpubExporter::new()
+        .with_config(EpubConfig {
+            normalize: true,
+            ..Default::default()
+        })
+        .export(&book, &mut out)
+        .unwrap();
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(out.into_inner())).unwrap();
+    let name = (0..archive.len())
+        .map(|i| archive.by_index(i).unwrap().name().to_string())
+        .find(|name| name.ends_with(".xhtml") && !name.ends_with("nav.xhtml"))
+        .unwrap();
+    let mut xhtml = String::new();
+    archive
+        .by_name(&name)
+        .unwrap()
+        .read_to_string(&mut xhtml)
+        .unwrap();
+
+    let table = &xhtml[xhtml.find("<table").expect("table kept")..];
+    let caption = &table[table.find("<caption").expect("caption kept")..];
+    let caption = &caption[..caption.find("</caption>").unwrap()];
+    assert!(caption.contains("Monthly rainfall"), "{xhtml}");
+    assert!(table.find("<caption") < table.find("<tr"), "{xhtml}");
+    assert!(!xhtml.contains("<figcaption"), "{xhtml}");
+    assert!(table.contains(r#"scope="col""#), "{xhtml}");
+    assert!(table.contains(r#"headers="m""#), "{xhtml}");
+}
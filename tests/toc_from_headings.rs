@@ -0,0 +1,125 @@
+//! `Book::set_toc_from_headings`: a TOC synthesized from chapter headings.
+
+mod common;
+
+use std::io::Cursor;
+
+use boko::export::{EpubConfig, EpubExporter, Exporter};
+use boko::{Book, Format};
+use common::{Doc, EpubBuilder, Nav};
+
+/// Two chapters of `h1`/`h2` headings behind a flat, one-entry TOC. Some
+/// headings carry ids, some don't; the `h3` is below the two-level cut.
+fn flat_toc_book() -> Book {
+    EpubBuilder::new("Headings")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            r#"<h1 id="part-one">Part One</h1><p>a</p>
+               <h2>The Voyage</h2><p>b</p>
+               <h3>Aside</h3><p>c</p>
+               <h2 id="landfall">Landfall</h2><p>d</p>"#,
+        ))
+        .doc(Doc::new(
+            "text/ch2.xhtml",
+            "Two",
+            r#"<h1>Part Two</h1><p>e</p>
+               <h2 id="the-return">Return</h2><p>f</p>
+               <h2>The Return</h2><p>g</p>"#,
+        ))
+        .nav(vec![Nav::new("Start", "text/ch1.xhtml")])
+        .book()
+}
+
+/// `(title, href)` per entry, children indented by depth.
+fn outline(entries: &[boko::TocEntry], depth: usize, out: &mut Vec<(usize, String, String)>) {
+    for entry in entries {
+        out.push((depth, entry.title.clone(), entry.href.clone()));
+        outline(&entry.children, depth + 1, out);
+    }
+}
+
+#[test]
+fn two_level_toc_from_headings_across_chapters() {
+    let mut book = flat_toc_book();
+    book.set_toc_from_headings(2).unwrap();
+
+    let mut entries = Vec::new();
+    outline(book.toc(), 0, &mut entries);
+    let expected = [
+        (0, "Part One", "OEBPS/text/ch1.xhtml#part-one"),
+        (1, "The Voyage", "OEBPS/text/ch1.xhtml#the-voyage"),
+        (1, "Landfall", "OEBPS/text/ch1.xhtml#landfall"),
+        (0, "Part Two", "OEBPS/text/ch2.xhtml#part-two"),
+        (1, "Return", "OEBPS/text/ch2.xhtml#the-return"),
+        // The slug is taken by an existing id.
+        (1, "The Return", "OEBPS/text/ch2.xhtml#the-return-2"),
+    ];
+    let actual: Vec<_> = entries
+        .iter()
+        .map(|(depth, title, href)| (*depth, title.as_str(), href.as_str()))
+        .collect();
+    assert_eq!(actual, expected);
+
+    // Every entry resolves to its heading, generated ids included.
+    book.resolve_links().unwrap();
+    let mut unresolved = Vec::new();
+    fn check(entries: &[boko::TocEntry], unresolved: &mut Vec<String>) {
+        for entry in entries {
+            if entry.target.is_none() {
+                unresolved.push(entry.href.clone());
+            }
+            check(&entry.children, unresolved);
+        }
+    }
+    check(book.toc(), &mut unresolved);
+    assert!(unresolved.is_empty(), "unresolved: {unresolved:?}");
+}
+
+#[test]
+fn generated_heading_ids_reach_the_export() {
+    let mut book = flat_toc_book();
+    book.set_toc_from_headings(2).unwrap();
+    assert!(book.requires_normalized_export());
+
+    let mut out = Cursor::new(Vec::new());
+    EpubExporter::new()
+        .with_config(EpubConfig {
+            normalize: true,
+            ..Default::default()
+        })
+        .export(&book, &mut out)
+        .unwrap();
+    let reread = Book::from_bytes(&out.into_inner(), Format::Epub).unwrap();
+
+    let mut entries = Vec::new();
+    outline(reread.toc(), 0, &mut entries);
+    let titles: Vec<_> = entries.iter().map(|(d, t, _)| (*d, t.as_str())).collect();
+    assert_eq!(
+        titles,
+        [
+            (0, "Part One"),
+            (1, "The Voyage"),
+            (1, "Landfall"),
+            (0, "Part Two"),
+            (1, "Return"),
+            (1, "The Return"),
+        ]
+    );
+    let fragment = |i: usize| entries[i].2.split('#').nth(1).map(str::to_string);
+    assert!(fragment(1).is_some(), "generated id exported: {entries:?}");
+    let html = reread.chapter_html(reread.spine()[0].id).unwrap();
+    assert!(
+        html.contains(&format!("id=\"{}\"", fragment(1).unwrap())),
+        "{html}"
+    );
+}
+
+#[test]
+fn max_level_one_keeps_only_top_headings() {
+    let mut book = flat_toc_book();
+    book.set_toc_from_headings(1).unwrap();
+    let titles: Vec<_> = book.toc().iter().map(|e| e.title.as_str()).collect();
+    assert_eq!(titles, ["Part One", "Part Two"]);
+    assert!(book.toc().iter().all(|e| e.children.is_empty()));
+}
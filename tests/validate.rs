@@ -0,0 +1,139 @@
+//! `Book::validate`, `Book::validate_links` and `boko validate`: broken
+//! references are reported as diagnostics; a well-formed book reports nothing.
+
+mod common;
+
+use std::process::Command;
+
+use boko::{Book, Format, Severity};
+
+fn clean_epub() -> Vec<u8> {
+    use common::{Doc, EpubBuilder, Nav};
+
+    EpubBuilder::new("Clean")
+        .image("images/a.png", b"\x89PNG".to_vec())
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            r#"<p><img src="../images/a.png" alt=""/><a href="ch2.xhtml#b">next</a></p>"#,
+        ))
+        .doc(Doc::new("text/ch2.xhtml", "Two", r#"<p id="b">b</p>"#))
+        .nav(vec![
+            Nav::new("One", "text/ch1.xhtml"),
+            Nav::new("Two", "text/ch2.xhtml#b"),
+        ])
+        .build()
+}
+
+fn dangling_image_epub() -> Vec<u8> {
+    use common::{Doc, EpubBuilder, Nav};
+
+    EpubBuilder::new("Dangling")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            r#"<p><img src="../images/missing.png" alt=""/></p>"#,
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .build()
+}
+
+#[test]
+fn clean_book_has_no_diagnostics() {
+    let book = Book::from_bytes(&clean_epub(), Format::Epub).unwrap();
+    assert_eq!(book.validate(), []);
+}
+
+#[test]
+fn kfx_images_referenced_by_resource_name_are_found() {
+    // KFX chapters name images by resource ("e6"), which the backend serves
+    // but doesn't list.
+    let book = Book::open("tests/fixtures/epictetus.kfx").unwrap();
+    assert!(!book.list_assets().iter().any(|asset| asset == "e6"));
+    assert_eq!(book.validate(), []);
+}
+
+#[test]
+fn dangling_image_is_a_missing_asset_error() {
+    let book = Book::from_bytes(&dangling_image_epub(), Format::Epub).unwrap();
+    let diagnostics = book.validate();
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    let d = &diagnostics[0];
+    assert_eq!(d.severity, Severity::Error);
+    assert_eq!(d.code, "missing-asset");
+    assert!(d.message.contains("images/missing.png"), "{}", d.message);
+    assert_eq!(d.location.as_deref(), Some("OEBPS/text/ch1.xhtml"));
+}
+
+#[test]
+fn control_characters_are_counted_per_chapter() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let book = EpubBuilder::new("Controls")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            "<p>a\u{7}b\u{FFFE}c\td</p>",
+        ))
+        .doc(Doc::new("text/ch2.xhtml", "Two", "<p>clean</p>"))
+        .nav(vec![
+            Nav::new("One", "text/ch1.xhtml"),
+            Nav::new("Two", "text/ch2.xhtml"),
+        ])
+        .book();
+    let diagnostics = book.validate();
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    let d = &diagnostics[0];
+    assert_eq!(d.severity, Severity::Warning);
+    assert_eq!(d.code, "control-characters");
+    assert!(d.message.starts_with("2 "), "{}", d.message);
+    assert_eq!(d.location.as_deref(), Some("OEBPS/text/ch1.xhtml"));
+}
+
+#[test]
+fn validate_links_reports_dangling_anchor() {
+    use common::{Doc, EpubBuilder, Nav};
+
+    let book = EpubBuilder::new("Links")
+        .doc(Doc::new(
+            "text/ch1.xhtml",
+            "One",
+            r##"<p id="top"><a href="#top">up</a> <a href="#missing">gone</a>
+                <a href="https://example.com/">web</a></p>"##,
+        ))
+        .nav(vec![Nav::new("One", "text/ch1.xhtml")])
+        .book();
+
+    let broken = book.validate_links().unwrap();
+    assert_eq!(broken.len(), 1, "{broken:?}");
+    assert_eq!(broken[0].from_chapter, book.spine()[0].id);
+    assert!(broken[0].href.ends_with("#missing"), "{}", broken[0].href);
+
+    // `validate` reports the same link as a missing anchor.
+    let diagnostics = book.validate();
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    assert_eq!(diagnostics[0].code, "missing-anchor");
+
+    let clean = Book::from_bytes(&clean_epub(), Format::Epub).unwrap();
+    assert_eq!(clean.validate_links().unwrap(), []);
+}
+
+#[test]
+fn validate_command_exits_non_zero_on_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    for (name, epub, ok) in [
+        ("clean.epub", clean_epub(), true),
+        ("dangling.epub", dangling_image_epub(), false),
+    ] {
+        let path = dir.path().join(name);
+        std::fs::write(&path, epub).unwrap();
+        let output = Command::new(env!("CARGO_BIN_EXE_boko"))
+            .arg("validate")
+            .arg(&path)
+            .output()
+            .expect("failed to run boko validate");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(output.status.success(), ok, "{name}: {stdout}");
+        assert_eq!(stdout.contains("error[missing-asset]"), !ok, "{stdout}");
+    }
+}
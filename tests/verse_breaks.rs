@@ -0,0 +1,78 @@
+//! Verse line breaks: `<br>` runs and trailing breaks survive export.
+
+mod common;
+
+use std::io::Cursor;
+
+use boko::export::{EpubConfig, EpubExporter, Exporter};
+use boko::{Book, Chapter, Format, Role};
+use common::{Doc, EpubBuilder, Nav};
+
+/// Two stanzas in one paragraph, separated by a double break, with a
+/// trailing break after the last line.
+const POEM: &str = "<p class=\"verse\">Whose woods these are I think I know.<br/>\
+     His house is in the village though;<br/><br/>\
+     He will not see me stopping here<br/>\
+     To watch his woods fill up with snow.<br/></p><p>After.</p>";
+
+fn poem_book() -> Book {
+    EpubBuilder::new("Stopping by Woods")
+        .doc(Doc::new("text/poem.xhtml", "Poem", POEM))
+        .nav(vec![Nav::new("Poem", "text/poem.xhtml")])
+        .book()
+}
+
+/// Line breaks in the first paragraph, whether they are `Break` nodes
+/// (HTML sources) or newlines in the text (KFX).
+fn verse_breaks(chapter: &Chapter) -> usize {
+    let verse = chapter
+        .iter_dfs()
+        .find(|&id| chapter.node(id).unwrap().role == Role::Paragraph)
+        .unwrap();
+    fn count(chapter: &Chapter, id: boko::NodeId) -> usize {
+        let node = chapter.node(id).unwrap();
+        let own = match node.role {
+            Role::Break => 1,
+            Role::Text => chapter.text(node.text).matches('\n').count(),
+            _ => 0,
+        };
+        own + chapter
+            .children(id)
+            .map(|child| count(chapter, child))
+            .sum::<usize>()
+    }
+    count(chapter, verse)
+}
+
+fn first_chapter(book: &Book) -> std::sync::Arc<Chapter> {
+    book.load_chapter_cached(book.spine()[0].id).unwrap()
+}
+
+#[test]
+fn two_stanza_poem_keeps_its_breaks() {
+    let mut book = poem_book();
+    assert_eq!(verse_breaks(&first_chapter(&book)), 5);
+
+    let kfx = Book::from_bytes(
+        &common::export_to_bytes(&mut book, Format::Kfx),
+        Format::Kfx,
+    )
+    .unwrap();
+    assert_eq!(verse_breaks(&first_chapter(&kfx)), 5, "KFX");
+
+    let mut out = Cursor::new(Vec::new());
+    EpubExporter::new()
+        .with_config(EpubConfig {
+            normalize: true,
+            ..Default::default()
+        })
+        .export(&book, &mut out)
+        .unwrap();
+    let epub = Book::from_bytes(&out.into_inner(), Format::Epub).unwrap();
+    assert_eq!(verse_breaks(&first_chapter(&epub)), 5, "EPUB");
+    let raw = String::from_utf8(epub.load_raw(epub.spine()[0].id).unwrap()).unwrap();
+    assert!(
+        raw.contains("though;<br/><br/>He will") && raw.contains("snow.<br/></p>"),
+        "{raw}"
+    );
+}